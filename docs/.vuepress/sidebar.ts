@@ -20,6 +20,8 @@ export default sidebar({
         "components_overview",
         "connection_pool",
         "connection_pool_builder",
+        "tenant_pool",
+        "replica_pool",
         "connection",
         "transaction",
         "cursor",
@@ -65,6 +67,8 @@ export default sidebar({
             "predefined_row_factories",
           ]
         },
+        "trio",
+        "tools",
       ],
     },
     {
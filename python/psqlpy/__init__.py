@@ -1,41 +1,127 @@
 from psqlpy._internal import (
+    ChannelListener,
+    ColumnCase,
+    ColumnCodec,
+    ColumnMask,
     Connection,
+    ConnectionAnnotation,
+    ConnectionLeakWarning,
     ConnectionPool,
     ConnectionPoolBuilder,
     ConnRecyclingMethod,
+    CopyCommandFormat,
+    CopyOutChunks,
     Cursor,
+    HookMetrics,
     IsolationLevel,
+    IsolationUpgradeWarning,
     KeepaliveConfig,
     Listener,
     ListenerNotificationMsg,
     LoadBalanceHosts,
+    LockInfo,
+    MergeMatchedAction,
+    MergeNotMatchedAction,
+    NoticeSeverity,
+    NoticeSummary,
+    PartialStatement,
+    Pipeline,
+    PooledSession,
+    PreparedTransaction,
+    QueryRegistry,
     QueryResult,
     ReadVariant,
+    RecoveryPolicy,
+    RedactionPolicy,
+    RegisteredQuery,
+    ReplicaPool,
+    ResultSchemaFormat,
+    RowIterator,
+    Savepoint,
+    ScheduledTask,
+    SessionDiagnostics,
+    SharedConnectionHandle,
     SingleQueryResult,
+    SpilledQueryResult,
+    StatementCacheInfo,
+    StatementStat,
+    SslCertificate,
     SslMode,
     SynchronousCommit,
+    TableWatcher,
     TargetSessionAttrs,
+    TenantPool,
     Transaction,
+    WarmStatement,
     connect,
+    debug_loop_integration,
+    disable_logging,
+    enable_logging,
+    open_connection,
+    query_fingerprint,
+    register_composite,
 )
 
 __all__ = [
+    "ChannelListener",
+    "ColumnCase",
+    "ColumnCodec",
+    "ColumnMask",
     "ConnRecyclingMethod",
     "Connection",
+    "ConnectionAnnotation",
+    "ConnectionLeakWarning",
     "ConnectionPool",
     "ConnectionPoolBuilder",
+    "CopyCommandFormat",
+    "CopyOutChunks",
     "Cursor",
+    "HookMetrics",
     "IsolationLevel",
+    "IsolationUpgradeWarning",
     "KeepaliveConfig",
     "Listener",
     "ListenerNotificationMsg",
     "LoadBalanceHosts",
+    "LockInfo",
+    "MergeMatchedAction",
+    "MergeNotMatchedAction",
+    "NoticeSeverity",
+    "NoticeSummary",
+    "PartialStatement",
+    "Pipeline",
+    "PooledSession",
+    "PreparedTransaction",
+    "QueryRegistry",
     "QueryResult",
     "ReadVariant",
+    "RecoveryPolicy",
+    "RedactionPolicy",
+    "RegisteredQuery",
+    "ReplicaPool",
+    "ResultSchemaFormat",
+    "RowIterator",
+    "Savepoint",
+    "ScheduledTask",
+    "SessionDiagnostics",
+    "SharedConnectionHandle",
     "SingleQueryResult",
+    "SpilledQueryResult",
+    "StatementCacheInfo",
+    "StatementStat",
+    "SslCertificate",
     "SslMode",
     "SynchronousCommit",
+    "TableWatcher",
     "TargetSessionAttrs",
+    "TenantPool",
     "Transaction",
+    "WarmStatement",
     "connect",
+    "debug_loop_integration",
+    "disable_logging",
+    "enable_logging",
+    "open_connection",
+    "query_fingerprint",
+    "register_composite",
 ]
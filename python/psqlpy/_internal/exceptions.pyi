@@ -13,6 +13,28 @@ class ConnectionPoolConfigurationError(BaseConnectionPoolError):
 class ConnectionPoolExecuteError(BaseConnectionPoolError):
     """Error in connection pool execution."""
 
+class SchemaValidationError(BaseConnectionPoolError):
+    """Database schema doesn't match the expectations.
+
+    `mismatches` has all found mismatches.
+    """
+
+    mismatches: list[str]
+
+class ConnectionPoolCheckoutError(BaseConnectionPoolError):
+    """Cannot get connection from the pool.
+
+    Attributes have the state of the pool at the moment of the error,
+    `longest_held_ms` is the age of the oldest taken connection
+    or None if all connections are returned.
+    """
+
+    max_size: int
+    size: int
+    in_use: int
+    waiting: int
+    longest_held_ms: float | None
+
 class BaseConnectionError(RustPSQLDriverPyBaseError):
     """Base error for Connection errors."""
 
@@ -22,6 +44,75 @@ class ConnectionExecuteError(BaseConnectionError):
 class ConnectionClosedError(BaseConnectionError):
     """Error if underlying connection is already closed."""
 
+class QueryTimeoutError(ConnectionExecuteError):
+    """Error if query doesn't finish in time, query is cancelled."""
+
+class DatabaseError(ConnectionExecuteError):
+    """Error returned by the server.
+
+    Carries SQLSTATE code and fields of the server error,
+    fields not sent by the server are None.
+    """
+
+    sqlstate: str
+    detail: str | None
+    hint: str | None
+    schema: str | None
+    table: str | None
+    column: str | None
+    constraint: str | None
+
+class DataError(DatabaseError):
+    """Error of SQLSTATE class 22, data exception."""
+
+class IntegrityConstraintViolationError(DatabaseError):
+    """Error of SQLSTATE class 23, integrity constraint violation."""
+
+class UniqueViolationError(IntegrityConstraintViolationError):
+    """Error with SQLSTATE 23505."""
+
+class ForeignKeyViolationError(IntegrityConstraintViolationError):
+    """Error with SQLSTATE 23503."""
+
+class NotNullViolationError(IntegrityConstraintViolationError):
+    """Error with SQLSTATE 23502."""
+
+class CheckViolationError(IntegrityConstraintViolationError):
+    """Error with SQLSTATE 23514."""
+
+class ExclusionViolationError(IntegrityConstraintViolationError):
+    """Error with SQLSTATE 23P01."""
+
+class TransactionRollbackSqlError(DatabaseError):
+    """Error of SQLSTATE class 40, transaction rollback."""
+
+class SerializationError(TransactionRollbackSqlError):
+    """Error with SQLSTATE 40001."""
+
+class DeadlockDetectedError(TransactionRollbackSqlError):
+    """Error with SQLSTATE 40P01."""
+
+class SyntaxOrAccessError(DatabaseError):
+    """Error of SQLSTATE class 42, syntax error or access rule violation."""
+
+class SqlSyntaxError(SyntaxOrAccessError):
+    """Error with SQLSTATE 42601."""
+
+class UndefinedTableError(SyntaxOrAccessError):
+    """Error with SQLSTATE 42P01."""
+
+class UndefinedColumnError(SyntaxOrAccessError):
+    """Error with SQLSTATE 42703."""
+
+class InsufficientPrivilegeError(SyntaxOrAccessError):
+    """Error with SQLSTATE 42501."""
+
+class OperatorInterventionError(DatabaseError):
+    """Error of SQLSTATE class 57, operator intervention."""
+
+class QueryCanceledError(OperatorInterventionError):
+    """Error with SQLSTATE 57014."""
+
 class BaseTransactionError(RustPSQLDriverPyBaseError):
     """Base error for all transaction errors."""
 
@@ -40,9 +131,21 @@ class TransactionSavepointError(BaseTransactionError):
 class TransactionExecuteError(BaseTransactionError):
     """Error in transaction execution."""
 
+class LockNotAvailableError(TransactionExecuteError):
+    """Error if rows are locked by another transaction and `NOWAIT` is used."""
+
 class TransactionClosedError(BaseTransactionError):
     """Error if underlying connection is already closed."""
 
+class TransactionNotStartedError(TransactionBeginError):
+    """Error if transaction is used before `begin()`."""
+
+class TransactionAlreadyCommittedError(TransactionBeginError):
+    """Error if transaction is used after commit."""
+
+class TransactionAlreadyRolledBackError(TransactionBeginError):
+    """Error if transaction is used after rollback."""
+
 class BaseCursorError(RustPSQLDriverPyBaseError):
     """Base error for Cursor errors."""
 
@@ -91,3 +194,9 @@ class ListenerClosedError(BaseListenerError):
 
 class ListenerCallbackError(BaseListenerError):
     """Error if callback passed to listener isn't a coroutine."""
+
+class QueryResultClosedError(RustPSQLDriverPyBaseError):
+    """Error if rows of the closed QueryResult are accessed."""
+
+class BlockingCallError(RustPSQLDriverPyBaseError):
+    """Error if blocking method is called from the running event loop."""
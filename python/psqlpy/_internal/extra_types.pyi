@@ -46,6 +46,19 @@ class Money:
         - `inner_value`: int object.
         """
 
+class PyDecimal:
+    """Represent `NUMERIC` in PostgreSQL without loss of precision."""
+
+    def __init__(self: Self, inner_value: Decimal | int | float | str) -> None:
+        """Create new instance of class.
+
+        Float is converted by its shortest representation,
+        e.g. `0.1` stays `0.1`.
+
+        ### Parameters:
+        - `inner_value`: Decimal, int, float or str object.
+        """
+
 class Float32:
     """Represents `FLOAT4` in `PostgreSQL` and `f32` in Rust."""
 
@@ -69,25 +82,25 @@ class Float64:
 class VarChar:
     """Represent VarChar in PostgreSQL and String in Rust."""
 
-    def __init__(self: Self, inner_value: str) -> None:
+    def __init__(self: Self, text_value: str) -> None:
         """Create new instance of class.
 
         You need to pass uuid as a str.
 
         ### Parameters:
-        - `inner_value`: str object.
+        - `text_value`: str object.
         """
 
 class Text:
     """Represent TEXT in PostgreSQL and String ins Rust."""
 
-    def __init__(self: Self, inner_value: str) -> None:
+    def __init__(self: Self, text_value: str) -> None:
         """Create new instance of class.
 
         You need to pass uuid as a str.
 
         ### Parameters:
-        - `inner_value`: str object.
+        - `text_value`: str object.
         """
 
 class JSONB:
@@ -141,7 +154,37 @@ class MacAddr8:
         """
 
 class CustomType:
-    def __init__(self, value: bytes) -> None: ...
+    def __init__(self, type_bytes: bytes) -> None: ...
+
+class AsText:
+    """Parameter which is sent in text format instead of binary.
+
+    Use it for types whose binary encoding isn't accepted
+    by the server-side extension.
+    """
+
+    def __init__(self: Self, value: typing.Any) -> None:
+        """Create new instance of AsText.
+
+        ### Parameters:
+        - `value`: any value, it's sent as its `str()` representation.
+        """
+
+class PyCast:
+    """Parameter with explicit cast, it's rendered as `$N::type_name`.
+
+    Value is sent in text format, so PostgreSQL parses it
+    with the input function of the type.
+    """
+
+    def __init__(self: Self, value: typing.Any, type_name: str) -> None:
+        """Create new instance of PyCast.
+
+        ### Parameters:
+        - `value`: any value, it's sent as its `str()` representation,
+            `None` is sent as `NULL`.
+        - `type_name`: name of the type to cast the parameter to.
+        """
 
 Coordinates: TypeAlias = list[int | float] | set[int | float] | tuple[int | float, int | float]
 PairsOfCoordinates: TypeAlias = (
@@ -608,10 +651,79 @@ class PgVector:
 
     def __init__(
         self: Self,
-        inner: typing.Sequence[float | int],
+        vector: typing.Sequence[float | int],
     ) -> None:
         """Create new instance of PgVector.
 
         ### Parameters:
-        - `inner`: inner value, sequence of float or int values.
+        - `vector`: sequence of float or int values.
+        """
+
+class PyVector:
+    """Represent pgvector VECTOR in PostgreSQL.
+
+    Lists, tuples and `numpy.ndarray` are encoded as VECTOR
+    without it, VECTOR is returned as list of float.
+    """
+
+    def __init__(
+        self: Self,
+        vector: typing.Sequence[float | int] | typing.Any,
+    ) -> None:
+        """Create new instance of PyVector.
+
+        ### Parameters:
+        - `vector`: sequence of float or int values or `numpy.ndarray`.
+        """
+    def to_list(self: Self) -> list[float]:
+        """Return values of the vector."""
+    def to_numpy(self: Self) -> typing.Any:
+        """Return `numpy.ndarray` with `float32` dtype.
+
+        numpy must be installed.
         """
+    def __len__(self: Self) -> int: ...
+
+class Range:
+    """Represent range types in PostgreSQL.
+
+    Bound which is None is infinite.
+    """
+
+    def __init__(
+        self: Self,
+        lower: typing.Any = None,
+        upper: typing.Any = None,
+        bounds: str = "[)",
+        empty: bool = False,
+    ) -> None:
+        """Create new instance of Range.
+
+        ### Parameters:
+        - `lower`: lower bound of the range, None means infinite bound.
+        - `upper`: upper bound of the range, None means infinite bound.
+        - `bounds`: one of `[)`, `(]`, `[]` and `()`,
+            square bracket means that the bound is included.
+        - `empty`: create empty range, bounds are ignored.
+        """
+    @property
+    def lower(self: Self) -> typing.Any:
+        """Lower bound of the range."""
+    @property
+    def upper(self: Self) -> typing.Any:
+        """Upper bound of the range."""
+    @property
+    def lower_inc(self: Self) -> bool:
+        """Is lower bound included."""
+    @property
+    def upper_inc(self: Self) -> bool:
+        """Is upper bound included."""
+    @property
+    def lower_inf(self: Self) -> bool:
+        """Is lower bound infinite."""
+    @property
+    def upper_inf(self: Self) -> bool:
+        """Is upper bound infinite."""
+    @property
+    def isempty(self: Self) -> bool:
+        """Is range empty."""
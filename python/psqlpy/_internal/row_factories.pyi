@@ -2,6 +2,8 @@ from typing import Any, Generic, TypeVar
 
 from typing_extensions import Self
 
+from psqlpy._internal import Connection, Transaction
+
 _CustomClass = TypeVar(
     "_CustomClass",
 )
@@ -65,3 +67,74 @@ class class_row(Generic[_CustomClass]):  # noqa: N801
         ### Returns:
         Constructed specified class.
         """
+
+class tracked_row:  # noqa: N801
+    """Row converter to `TrackedRow` of the table.
+
+    ### Example:
+    ```python
+    from psqlpy.row_factories import tracked_row
+
+
+    async def main:
+        res = await connection.execute(
+            "SELECT * FROM users WHERE id = $1",
+            [user_id],
+        )
+        user = res.row_factory(tracked_row("users"))[0]
+        user["name"] = "Alex"
+        await user.save(connection)
+    ```
+    """
+
+    def __init__(
+        self: Self,
+        table_name: str,
+        primary_key: list[str] = ["id"],  # noqa: B006
+        schema_name: str | None = None,
+    ) -> None:
+        """Construct new `tracked_row`.
+
+        ### Parameters:
+        - `table_name`: table the rows are selected from.
+        - `primary_key`: columns which identify the row, rows must have them.
+        - `schema_name`: schema of the table.
+        """
+    def __call__(self, row: dict[str, Any]) -> TrackedRow:
+        """Convert row into `TrackedRow`.
+
+        ### Parameters:
+        - `row`: row in dictionary.
+
+        ### Returns:
+        `TrackedRow` of the table.
+        """
+
+class TrackedRow:
+    """Row which remembers its table, primary key and changed columns.
+
+    Values are read and changed by column name,
+    primary key columns cannot be changed.
+    """
+
+    dirty_fields: list[str]
+    """Columns changed since the row was fetched or saved, in order of changes."""
+
+    def __getitem__(self: Self, column: str) -> Any: ...
+    def __setitem__(self: Self, column: str, value: Any) -> None: ...
+    def __contains__(self: Self, column: str) -> bool: ...
+    def __len__(self: Self) -> int: ...
+    def as_dict(self: Self) -> dict[str, Any]:
+        """Return copy of the row values."""
+    async def save(self: Self, connection: Connection | Transaction) -> int:
+        """Update changed columns of the row in the table.
+
+        Row is found by the primary key, nothing is executed
+        if there are no changes.
+
+        ### Parameters:
+        - `connection`: connection or transaction to execute `UPDATE`.
+
+        ### Returns:
+        Number of updated rows, 0 means the row doesn't exist anymore.
+        """
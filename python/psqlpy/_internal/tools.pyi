@@ -0,0 +1,53 @@
+from collections.abc import Sequence
+from typing import Any
+
+from typing_extensions import Self
+
+from psqlpy import QueryResult
+
+class ResultDiff:
+    """Difference between two results matched by the key columns.
+
+    Falsy if results are equal.
+    """
+
+    added: list[dict[str, Any]]
+    """Rows of the second result which aren't in the first one."""
+    removed: list[dict[str, Any]]
+    """Rows of the first result which aren't in the second one."""
+    changed: list[tuple[dict[str, Any], dict[str, Any]]]
+    """Pairs of the rows with the same keys and different values."""
+
+    def __bool__(self: Self) -> bool: ...
+
+def diff_results(
+    result_a: QueryResult | Sequence[dict[str, Any]],
+    result_b: QueryResult | Sequence[dict[str, Any]],
+    keys: Sequence[str],
+) -> ResultDiff:
+    """Compare two results by the key columns.
+
+    Rows are matched with hash join on the key values in Rust,
+    every result is traversed once.
+
+    ### Parameters:
+    - `result_a`: the first result, `QueryResult` or sequence of dicts.
+    - `result_b`: the second result, `QueryResult` or sequence of dicts.
+    - `keys`: names of the columns which identify the row.
+
+    ### Returns:
+    Added, removed and changed rows.
+
+    ### Example:
+    ```python
+    from psqlpy.tools import diff_results
+
+    async with old_pool.acquire() as old_conn, new_pool.acquire() as new_conn:
+        diff = diff_results(
+            await old_conn.fetch("SELECT * FROM users"),
+            await new_conn.fetch("SELECT * FROM users"),
+            keys=["id"],
+        )
+    assert not diff, diff
+    ```
+    """
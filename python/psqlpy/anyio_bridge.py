@@ -0,0 +1,193 @@
+"""Use psqlpy from Trio and other non-asyncio AnyIO backends.
+
+psqlpy awaitables are asyncio awaitables, the queries themselves
+run on the tokio runtime of the driver.
+`bridge` wraps psqlpy object, so its awaitables run on the asyncio
+event loop in the background thread and Trio task waits for the result
+without blocking.
+In asyncio code wrapped objects call psqlpy directly.
+"""
+
+from __future__ import annotations
+
+import asyncio
+import functools
+import inspect
+import threading
+import typing
+
+import sniffio
+
+from psqlpy._internal import (
+    ChannelListener,
+    Connection,
+    ConnectionPool,
+    CopyOutChunks,
+    Cursor,
+    Listener,
+    ListenerNotificationMsg,
+    ReplicaPool,
+    TableWatcher,
+    TenantPool,
+    Transaction,
+)
+
+__all__ = [
+    "bridge",
+]
+
+_T = typing.TypeVar("_T")
+
+_BRIDGED_TYPES: typing.Final = (
+    ChannelListener,
+    Connection,
+    ConnectionPool,
+    CopyOutChunks,
+    Cursor,
+    Listener,
+    ListenerNotificationMsg,
+    ReplicaPool,
+    TableWatcher,
+    TenantPool,
+    Transaction,
+)
+
+_bridge_loop: asyncio.AbstractEventLoop | None = None
+_bridge_loop_lock: typing.Final = threading.Lock()
+
+
+def _get_bridge_loop() -> asyncio.AbstractEventLoop:
+    """Return asyncio event loop of the bridge, start it if needed."""
+    global _bridge_loop  # noqa: PLW0603
+    with _bridge_loop_lock:
+        if _bridge_loop is None:
+            loop = asyncio.new_event_loop()
+            threading.Thread(
+                target=loop.run_forever,
+                name="psqlpy-bridge-loop",
+                daemon=True,
+            ).start()
+            _bridge_loop = loop
+        return _bridge_loop
+
+
+def _needs_bridge() -> bool:
+    """Check that current async library is not asyncio."""
+    try:
+        return sniffio.current_async_library() != "asyncio"
+    except sniffio.AsyncLibraryNotFoundError:
+        return False
+
+
+def _call_in_loop(func: typing.Callable[..., _T], *args: typing.Any, **kwargs: typing.Any) -> _T:
+    """Call function in the bridge loop and wait for the result.
+
+    Some psqlpy methods create asyncio futures right away,
+    so they must be called while asyncio loop is running.
+    """
+
+    async def call() -> _T:
+        return func(*args, **kwargs)
+
+    return asyncio.run_coroutine_threadsafe(call(), _get_bridge_loop()).result()
+
+
+async def _await_in_loop(awaitable: typing.Awaitable[_T]) -> _T:
+    """Await in the bridge loop, current Trio task isn't blocked."""
+    if sniffio.current_async_library() != "trio":
+        raise RuntimeError("psqlpy bridge supports only asyncio and trio")
+    import trio  # noqa: PLC0415
+
+    async def run() -> _T:
+        return await awaitable
+
+    future = asyncio.run_coroutine_threadsafe(run(), _get_bridge_loop())
+    token = trio.lowlevel.current_trio_token()
+    done = trio.Event()
+
+    def wake_up(_: object) -> None:
+        try:
+            token.run_sync_soon(done.set)
+        except trio.RunFinishedError:
+            pass
+
+    future.add_done_callback(wake_up)
+    try:
+        await done.wait()
+    except BaseException:
+        future.cancel()
+        raise
+    return future.result()
+
+
+async def _bridge_await(awaitable: typing.Awaitable[typing.Any]) -> typing.Any:
+    if _needs_bridge():
+        result = await _await_in_loop(awaitable)
+    else:
+        result = await awaitable
+    return _bridge_result(result)
+
+
+def _bridge_result(result: typing.Any) -> typing.Any:
+    # Some psqlpy objects are awaitable themselves, they are wrapped as is.
+    if isinstance(result, _BRIDGED_TYPES):
+        return _Bridged(result)
+    if inspect.isawaitable(result):
+        return _bridge_await(result)
+    return result
+
+
+def _bridged_call(func: typing.Callable[..., typing.Any], *args: typing.Any, **kwargs: typing.Any) -> typing.Any:
+    if _needs_bridge():
+        result = _call_in_loop(func, *args, **kwargs)
+    else:
+        result = func(*args, **kwargs)
+    return _bridge_result(result)
+
+
+class _Bridged:
+    """Proxy of psqlpy object which bridges its awaitables."""
+
+    __slots__ = ("_psqlpy_object",)
+
+    def __init__(self, psqlpy_object: typing.Any) -> None:
+        object.__setattr__(self, "_psqlpy_object", psqlpy_object)
+
+    def __getattr__(self, name: str) -> typing.Any:
+        attribute = getattr(self._psqlpy_object, name)
+        if callable(attribute) and not isinstance(attribute, _BRIDGED_TYPES):
+            return functools.wraps(attribute)(functools.partial(_bridged_call, attribute))
+        return _bridge_result(attribute)
+
+    def __setattr__(self, name: str, value: typing.Any) -> None:
+        setattr(self._psqlpy_object, name, value)
+
+    def __repr__(self) -> str:
+        return f"bridge({self._psqlpy_object!r})"
+
+    def __aiter__(self) -> _Bridged:
+        return self
+
+    async def __anext__(self) -> typing.Any:
+        return await _bridged_call(self._psqlpy_object.__anext__)
+
+    async def __aenter__(self) -> typing.Any:
+        return await _bridged_call(self._psqlpy_object.__aenter__)
+
+    async def __aexit__(self, *exc_info: typing.Any) -> typing.Any:
+        return await _bridged_call(self._psqlpy_object.__aexit__, *exc_info)
+
+
+def bridge(psqlpy_object: _T) -> _T:
+    """Make psqlpy object usable from Trio.
+
+    Returned object has the same methods, psqlpy objects returned
+    by them (connections, transactions, cursors, listeners)
+    are bridged too.
+
+    ### Parameters:
+    - `psqlpy_object`: connection pool or any other psqlpy object.
+    """
+    if not isinstance(psqlpy_object, _BRIDGED_TYPES):
+        raise TypeError(f"Cannot bridge {type(psqlpy_object).__name__}")
+    return typing.cast(_T, _Bridged(psqlpy_object))
@@ -4,28 +4,55 @@ from ._internal.exceptions import (
     BaseCursorError,
     BaseListenerError,
     BaseTransactionError,
+    BlockingCallError,
+    CheckViolationError,
     ConnectionClosedError,
     ConnectionExecuteError,
     ConnectionPoolBuildError,
+    ConnectionPoolCheckoutError,
     ConnectionPoolConfigurationError,
     ConnectionPoolExecuteError,
     CursorClosedError,
     CursorCloseError,
     CursorFetchError,
     CursorStartError,
+    DatabaseError,
+    DataError,
+    DeadlockDetectedError,
+    ExclusionViolationError,
+    ForeignKeyViolationError,
+    InsufficientPrivilegeError,
+    IntegrityConstraintViolationError,
     ListenerCallbackError,
     ListenerClosedError,
     ListenerStartError,
+    LockNotAvailableError,
     MacAddrConversionError,
+    NotNullViolationError,
+    OperatorInterventionError,
     PyToRustValueMappingError,
+    QueryCanceledError,
+    QueryResultClosedError,
+    QueryTimeoutError,
     RustPSQLDriverPyBaseError,
     RustToPyValueMappingError,
+    SchemaValidationError,
+    SerializationError,
+    SqlSyntaxError,
+    SyntaxOrAccessError,
+    TransactionAlreadyCommittedError,
+    TransactionAlreadyRolledBackError,
     TransactionBeginError,
     TransactionClosedError,
     TransactionCommitError,
     TransactionExecuteError,
+    TransactionNotStartedError,
     TransactionRollbackError,
+    TransactionRollbackSqlError,
     TransactionSavepointError,
+    UndefinedColumnError,
+    UndefinedTableError,
+    UniqueViolationError,
     UUIDValueConvertError,
 )
 
@@ -35,27 +62,54 @@ __all__ = [
     "BaseCursorError",
     "BaseListenerError",
     "BaseTransactionError",
+    "BlockingCallError",
+    "CheckViolationError",
     "ConnectionClosedError",
     "ConnectionExecuteError",
     "ConnectionPoolBuildError",
+    "ConnectionPoolCheckoutError",
     "ConnectionPoolConfigurationError",
     "ConnectionPoolExecuteError",
     "CursorCloseError",
     "CursorClosedError",
     "CursorFetchError",
     "CursorStartError",
+    "DataError",
+    "DatabaseError",
+    "DeadlockDetectedError",
+    "ExclusionViolationError",
+    "ForeignKeyViolationError",
+    "InsufficientPrivilegeError",
+    "IntegrityConstraintViolationError",
     "ListenerCallbackError",
     "ListenerClosedError",
     "ListenerStartError",
+    "LockNotAvailableError",
     "MacAddrConversionError",
+    "NotNullViolationError",
+    "OperatorInterventionError",
     "PyToRustValueMappingError",
+    "QueryCanceledError",
+    "QueryResultClosedError",
+    "QueryTimeoutError",
     "RustPSQLDriverPyBaseError",
     "RustToPyValueMappingError",
+    "SchemaValidationError",
+    "SerializationError",
+    "SqlSyntaxError",
+    "SyntaxOrAccessError",
+    "TransactionAlreadyCommittedError",
+    "TransactionAlreadyRolledBackError",
     "TransactionBeginError",
     "TransactionClosedError",
     "TransactionCommitError",
     "TransactionExecuteError",
+    "TransactionNotStartedError",
     "TransactionRollbackError",
+    "TransactionRollbackSqlError",
     "TransactionSavepointError",
     "UUIDValueConvertError",
+    "UndefinedColumnError",
+    "UndefinedTableError",
+    "UniqueViolationError",
 ]
@@ -1,6 +1,7 @@
 from ._internal.extra_types import (
     JSON,
     JSONB,
+    AsText,
     BigInt,
     BoolArray,
     Box,
@@ -39,6 +40,10 @@ from ._internal.extra_types import (
     PgVector,
     Point,
     PointArray,
+    PyCast,
+    PyDecimal,
+    PyVector,
+    Range,
     SmallInt,
     Text,
     TextArray,
@@ -51,6 +56,7 @@ from ._internal.extra_types import (
 __all__ = [
     "JSON",
     "JSONB",
+    "AsText",
     "BigInt",
     "BoolArray",
     "BoolArray",
@@ -92,6 +98,10 @@ __all__ = [
     "PgVector",
     "Point",
     "PointArray",
+    "PyCast",
+    "PyDecimal",
+    "PyVector",
+    "Range",
     "SmallInt",
     "Text",
     "TextArray",
@@ -1,6 +1,8 @@
-from ._internal.row_factories import class_row, tuple_row
+from ._internal.row_factories import TrackedRow, class_row, tracked_row, tuple_row
 
 __all__ = [
+    "TrackedRow",
     "class_row",
+    "tracked_row",
     "tuple_row",
 ]
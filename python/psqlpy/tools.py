@@ -0,0 +1,6 @@
+from ._internal.tools import ResultDiff, diff_results
+
+__all__ = [
+    "ResultDiff",
+    "diff_results",
+]
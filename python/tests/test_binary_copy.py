@@ -1,10 +1,11 @@
 import os
 import typing
-from io import BytesIO
+from io import BytesIO, StringIO
 
 import pytest
 from pgpq import ArrowToPostgresBinaryEncoder
-from psqlpy import ConnectionPool
+from psqlpy import ConnectionPool, CopyCommandFormat
+from psqlpy.exceptions import PyToRustValueMappingError
 from pyarrow import parquet
 
 pytestmark = pytest.mark.anyio
@@ -115,3 +116,221 @@ CREATE TABLE IF NOT EXISTS cars (
         f"SELECT COUNT(*) AS rows_count FROM {table_name}",
     )
     assert real_table_rows.result()[0]["rows_count"] == expected_inserted_row
+
+
+async def test_binary_copy_from_table(
+    psql_pool: ConnectionPool,
+    table_name: str,
+    number_database_records: int,
+) -> None:
+    """Test that exported binary data can be loaded back."""
+    copy_table_name: typing.Final = f"{table_name}_copy"
+    connection = await psql_pool.connection()
+    await connection.execute(f"DROP TABLE IF EXISTS {copy_table_name}")
+    await connection.execute(
+        f"CREATE TABLE {copy_table_name} (LIKE {table_name})",
+    )
+
+    data = await connection.binary_copy_from_table(
+        table_name=table_name.lower(),
+        columns=["id", "name"],
+    )
+    assert isinstance(data, bytes)
+
+    inserted_rows = await connection.binary_copy_to_table(
+        source=data,
+        table_name=copy_table_name.lower(),
+        columns=["id", "name"],
+    )
+    assert inserted_rows == number_database_records
+
+    not_copied_rows = await connection.execute(
+        f"SELECT * FROM {table_name} EXCEPT SELECT * FROM {copy_table_name}",
+    )
+    assert not not_copied_rows.result()
+    await connection.execute(f"DROP TABLE {copy_table_name}")
+
+
+async def test_binary_copy_from_table_chunks(
+    psql_pool: ConnectionPool,
+    table_name: str,
+) -> None:
+    """Test that chunks of the binary copy make the whole data."""
+    connection = await psql_pool.connection()
+
+    data = await connection.binary_copy_from_table(table_name=table_name.lower())
+    chunks = [
+        chunk
+        async for chunk in await connection.binary_copy_from_table_chunks(
+            table_name=table_name.lower(),
+        )
+    ]
+
+    assert all(isinstance(chunk, bytes) for chunk in chunks)
+    assert b"".join(chunks) == data
+
+
+async def test_copy_records_to_table(
+    psql_pool: ConnectionPool,
+) -> None:
+    """Test that records are encoded for the column types."""
+    table_name: typing.Final = "copy_records"
+    connection = await psql_pool.connection()
+    await connection.execute(f"DROP TABLE IF EXISTS {table_name}")
+    await connection.execute(
+        f"CREATE TABLE {table_name} (id INT8, small INT2, price NUMERIC, name TEXT, meta JSONB)",
+    )
+    records = [
+        (1, 2, 10.5, "first", {"tag": "a"}),
+        (2, None, 3, "second", None),
+    ]
+
+    inserted_rows = await connection.copy_records_to_table(table_name, records)
+    assert inserted_rows == len(records)
+
+    async with connection.transaction() as transaction:
+        inserted_rows = await transaction.copy_records_to_table(
+            table_name,
+            [("third", 3)],
+            columns=["name", "id"],
+            types=["text", "int8"],
+        )
+    assert inserted_rows == 1
+
+    result = await connection.fetch(f"SELECT id, small, price::text, name, meta FROM {table_name} ORDER BY id")
+    assert result.result() == [
+        {"id": 1, "small": 2, "price": "10.5", "name": "first", "meta": {"tag": "a"}},
+        {"id": 2, "small": None, "price": "3", "name": "second", "meta": None},
+        {"id": 3, "small": None, "price": None, "name": "third", "meta": None},
+    ]
+
+    await connection.execute(f"DROP TABLE {table_name}")
+
+
+async def test_csv_copy_to_and_from_table(
+    psql_pool: ConnectionPool,
+) -> None:
+    """Test that CSV and TEXT data is loaded and exported with the format options."""
+    table_name: typing.Final = "copy_csv"
+    connection = await psql_pool.connection()
+    await connection.execute(f"DROP TABLE IF EXISTS {table_name}")
+    await connection.execute(f"CREATE TABLE {table_name} (id INT, name TEXT)")
+
+    inserted_rows = await connection.copy_to_table(
+        "id,name\n1,first\n2,\n",
+        table_name,
+        header=True,
+    )
+    assert inserted_rows == 2  # noqa: PLR2004
+    inserted_rows = await connection.copy_to_table(
+        StringIO("3|third\n4|NULL\n"),
+        table_name,
+        columns=["id", "name"],
+        format=CopyCommandFormat.TEXT,
+        delimiter="|",
+        null="NULL",
+    )
+    assert inserted_rows == 2  # noqa: PLR2004
+
+    data = await connection.copy_from_table(table_name, header=True)
+    assert data == b"id,name\n1,first\n2,\n3,third\n4,\n"
+    chunks = [
+        chunk
+        async for chunk in await connection.copy_from_table_chunks(
+            table_name,
+            columns=["name"],
+            format=CopyCommandFormat.TEXT,
+        )
+    ]
+    assert b"".join(chunks) == b"first\n\\N\nthird\n\\N\n"
+
+    with pytest.raises(expected_exception=PyToRustValueMappingError):
+        await connection.copy_from_table(table_name, format=CopyCommandFormat.BINARY, header=True)
+
+    await connection.execute(f"DROP TABLE {table_name}")
+
+
+async def test_copy_in_transaction_rollback(
+    psql_pool: ConnectionPool,
+) -> None:
+    """Test that several copies in a transaction are rolled back together."""
+    table_name: typing.Final = "copy_transaction"
+    connection = await psql_pool.connection()
+    await connection.execute(f"DROP TABLE IF EXISTS {table_name}")
+    await connection.execute(f"CREATE TABLE {table_name} (id INT, name TEXT)")
+
+    transaction = connection.transaction()
+    await transaction.begin()
+    await transaction.execute(f"INSERT INTO {table_name} VALUES (1, 'first')")
+    inserted_rows = await transaction.copy_to_table("2,second\n", table_name)
+    assert inserted_rows == 1
+    data = await transaction.binary_copy_from_table(table_name)
+    inserted_rows = await transaction.binary_copy_to_table(data, table_name)
+    assert inserted_rows == 2  # noqa: PLR2004
+
+    assert await transaction.copy_from_table(table_name) == (
+        b"1,first\n2,second\n1,first\n2,second\n"
+    )
+    chunks = [
+        chunk
+        async for chunk in await transaction.copy_from_table_chunks(
+            table_name,
+            columns=["id"],
+            format=CopyCommandFormat.TEXT,
+        )
+    ]
+    assert b"".join(chunks) == b"1\n2\n1\n2\n"
+    await transaction.rollback()
+
+    assert await connection.fetch_val(f"SELECT COUNT(*) FROM {table_name}") == 0
+    await connection.execute(f"DROP TABLE {table_name}")
+
+
+async def test_binary_copy_to_table_validate(
+    psql_pool: ConnectionPool,
+    table_name: str,
+    number_database_records: int,
+) -> None:
+    """Test that invalid binary data is rejected before sending."""
+    copy_table_name: typing.Final = f"{table_name}_validate"
+    connection = await psql_pool.connection()
+    await connection.execute(f"DROP TABLE IF EXISTS {copy_table_name}")
+    await connection.execute(
+        f"CREATE TABLE {copy_table_name} (LIKE {table_name})",
+    )
+    data = await connection.binary_copy_from_table(table_name=table_name.lower())
+
+    inserted_rows = await connection.binary_copy_to_table(
+        source=data,
+        table_name=copy_table_name.lower(),
+        validate=True,
+    )
+    assert inserted_rows == number_database_records
+
+    invalid_sources = [
+        (b"id,name\n", "signature"),
+        (data[:-2], "trailer is missing"),
+        (data[:-5], "needs"),
+        (data + b"\x00", "after the trailer"),
+    ]
+    for invalid_source, message in invalid_sources:
+        with pytest.raises(PyToRustValueMappingError, match=message):
+            await connection.binary_copy_to_table(
+                source=invalid_source,
+                table_name=copy_table_name.lower(),
+                validate=True,
+            )
+
+    with pytest.raises(PyToRustValueMappingError, match="1 columns are expected"):
+        await connection.binary_copy_to_table(
+            source=data,
+            table_name=copy_table_name.lower(),
+            columns=["id"],
+            validate=True,
+        )
+
+    assert (
+        await connection.fetch_val(f"SELECT COUNT(*) FROM {copy_table_name}")
+        == number_database_records
+    )
+    await connection.execute(f"DROP TABLE {copy_table_name}")
@@ -4,6 +4,7 @@ import math
 from typing import TYPE_CHECKING
 
 import pytest
+from psqlpy.exceptions import CursorClosedError, CursorStartError
 
 if TYPE_CHECKING:
     from psqlpy import ConnectionPool, Cursor, QueryResult, Transaction
@@ -206,3 +207,76 @@ async def test_cursor_send_underlying_connection_to_pool_manually(
             assert not psql_pool.status().available
         assert not psql_pool.status().available
     assert psql_pool.status().available == 1
+
+
+async def test_cursor_dbapi_fetch(
+    number_database_records: int,
+    test_cursor: Cursor,
+) -> None:
+    """Test DBAPI fetchone, fetchmany and fetchall."""
+    row = await test_cursor.fetchone()
+    assert isinstance(row, dict)
+
+    test_cursor.arraysize = 2
+    assert test_cursor.fetch_number == 2  # noqa: PLR2004
+    rows = await test_cursor.fetchmany()
+    assert len(rows) == 2  # noqa: PLR2004
+
+    rows = await test_cursor.fetchmany(size=1)
+    assert len(rows) == 1
+
+    rows = await test_cursor.fetchall()
+    assert len(rows) == number_database_records - 4
+
+    assert await test_cursor.fetchone() is None
+    assert await test_cursor.fetchmany() == []
+    assert await test_cursor.fetchall() == []
+
+
+async def test_connection_cursor_names(
+    psql_pool: ConnectionPool,
+    table_name: str,
+) -> None:
+    """Test that connection cursors get unique names and names can't be reused."""
+    async with psql_pool.acquire() as connection:
+        await connection.execute("BEGIN")
+        first_cursor = connection.cursor(querystring=f"SELECT * FROM {table_name}")
+        second_cursor = connection.cursor(querystring=f"SELECT * FROM {table_name}")
+        assert first_cursor.cursor_name != second_cursor.cursor_name
+
+        await first_cursor.start()
+        await second_cursor.start()
+        assert len((await first_cursor.fetch(1)).result()) == 1
+        assert len((await second_cursor.fetch(1)).result()) == 1
+        assert len(connection.cursors()) == 2  # noqa: PLR2004
+
+        with pytest.raises(expected_exception=CursorStartError):
+            connection.cursor(
+                querystring=f"SELECT * FROM {table_name}",
+                cursor_name=first_cursor.cursor_name,
+            )
+        await connection.execute("COMMIT")
+
+
+async def test_connection_cursor_closed_on_release(
+    psql_pool: ConnectionPool,
+    table_name: str,
+) -> None:
+    """Test that open cursors are closed when connection goes back to the pool."""
+    async with psql_pool.acquire() as connection:
+        cursor = connection.cursor(
+            querystring=f"SELECT * FROM {table_name}",
+            hold=True,
+        )
+        await cursor.start()
+        assert connection.cursors() == [cursor]
+
+    async with psql_pool.acquire() as connection:
+        open_cursors = await connection.fetch_val(
+            "SELECT COUNT(*) FROM pg_cursors WHERE name = $1",
+            [cursor.cursor_name],
+        )
+
+    assert open_cursors == 0
+    with pytest.raises(expected_exception=CursorClosedError):
+        await cursor.fetch(1)
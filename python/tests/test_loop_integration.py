@@ -0,0 +1,26 @@
+import asyncio
+
+import pytest
+from psqlpy import debug_loop_integration
+
+pytestmark = pytest.mark.anyio
+
+
+async def test_debug_loop_integration() -> None:
+    """Test that report describes the running event loop."""
+    report = await debug_loop_integration()
+
+    running_loop = asyncio.get_running_loop()
+    assert report["loop_class"].endswith(type(running_loop).__qualname__)
+    assert report["loop_flavor"] in {"uvloop", "winloop", "proactor", "selector", "other"}
+    assert report["loop_debug"] is running_loop.get_debug()
+    assert report["driver_loop_matches"] is True
+    assert report["runtime_workers"] >= 1
+    assert 0 <= report["wakeup_latency_min_ms"] <= report["wakeup_latency_avg_ms"]
+    assert report["wakeup_latency_avg_ms"] <= report["wakeup_latency_max_ms"]
+
+
+def test_debug_loop_integration_without_loop() -> None:
+    """Test that report can't be made without running event loop."""
+    with pytest.raises(expected_exception=RuntimeError):
+        debug_loop_integration()
@@ -0,0 +1,48 @@
+import asyncio
+
+import pytest
+from psqlpy import ReplicaPool
+from psqlpy.exceptions import ConnectionPoolConfigurationError
+
+pytestmark = pytest.mark.anyio
+
+
+@pytest.fixture
+def dsn(
+    postgres_host: str,
+    postgres_user: str,
+    postgres_password: str,
+    postgres_port: int,
+    postgres_dbname: str,
+) -> str:
+    return (
+        f"postgres://{postgres_user}:{postgres_password}@"
+        f"{postgres_host}:{postgres_port}/{postgres_dbname}"
+    )
+
+
+async def test_replica_pool_falls_back_to_primary(dsn: str) -> None:
+    """Test that server which isn't in recovery is excluded from replicas."""
+    replica_pool = ReplicaPool(
+        primary_dsn=dsn,
+        replica_dsns=[dsn],
+        lag_check_interval_ms=50,
+    )
+    await asyncio.sleep(0.2)
+
+    assert replica_pool.replica_lags() == [None]
+
+    connection = await replica_pool.connection(read_only=True, lag_tolerance=0)
+    assert await connection.fetch_val("SELECT pg_is_in_recovery()") is False
+    connection.back_to_pool()
+
+    result = await replica_pool.execute("SELECT 1 AS one", read_only=True)
+    assert result.result() == [{"one": 1}]
+
+    replica_pool.close()
+
+
+async def test_replica_pool_requires_replicas(dsn: str) -> None:
+    """Test that replica pool cannot be created without replicas."""
+    with pytest.raises(expected_exception=ConnectionPoolConfigurationError):
+        ReplicaPool(primary_dsn=dsn, replica_dsns=[])
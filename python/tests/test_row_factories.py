@@ -1,9 +1,11 @@
+import sys
 from dataclasses import dataclass
 from typing import Any, Callable, Dict, Type
 
 import pytest
-from psqlpy import ConnectionPool
-from psqlpy.row_factories import class_row, tuple_row
+from psqlpy import ColumnCase, ConnectionPool
+from psqlpy.exceptions import PyToRustValueMappingError, QueryResultClosedError
+from psqlpy.row_factories import TrackedRow, class_row, tracked_row, tuple_row
 
 pytestmark = pytest.mark.anyio
 
@@ -42,6 +44,38 @@ async def test_class_row(
     assert isinstance(class_res[0], ValidationTestModel)
 
 
+async def test_tracked_row(
+    psql_pool: ConnectionPool,
+    table_name: str,
+) -> None:
+    connection = await psql_pool.connection()
+    conn_result = await connection.execute(
+        querystring=f"SELECT * FROM {table_name} WHERE id = 1",
+    )
+    row = conn_result.row_factory(row_factory=tracked_row(table_name, ["id"]))[0]
+    assert isinstance(row, TrackedRow)
+    assert row.dirty_fields == []
+    assert await row.save(connection) == 0
+
+    row["name"] = "tracked"
+    assert row["name"] == "tracked"
+    assert row.dirty_fields == ["name"]
+    with pytest.raises(expected_exception=PyToRustValueMappingError):
+        row["id"] = 2
+    with pytest.raises(expected_exception=KeyError):
+        row["unknown"] = 1
+
+    async with connection.transaction() as transaction:
+        assert await row.save(transaction) == 1
+    assert row.dirty_fields == []
+
+    name = await connection.fetch_val(
+        querystring=f"SELECT name FROM {table_name} WHERE id = 1",
+    )
+    assert name == "tracked"
+    assert row.as_dict() == {"id": 1, "name": "tracked"}
+
+
 async def test_custom_row_factory(
     psql_pool: ConnectionPool,
     table_name: str,
@@ -68,3 +102,86 @@ async def test_custom_row_factory(
 
     assert len(class_res) == number_database_records
     assert isinstance(class_res[0], ValidationTestModel)
+
+
+async def test_result_column_case(
+    psql_pool: ConnectionPool,
+    table_name: str,
+) -> None:
+    connection = await psql_pool.connection()
+    conn_result = await connection.execute(
+        querystring=f'SELECT id AS user_id, name AS "UserName" FROM {table_name}',
+    )
+
+    assert set(conn_result.result(column_case=ColumnCase.Camel)[0]) == {
+        "userId",
+        "username",
+    }
+    assert set(conn_result.result(column_case=ColumnCase.Snake)[0]) == {
+        "user_id",
+        "user_name",
+    }
+    assert set(conn_result.result(column_case=ColumnCase.Upper)[0]) == {
+        "USER_ID",
+        "USERNAME",
+    }
+
+
+async def test_result_column_mapping(
+    psql_pool: ConnectionPool,
+    table_name: str,
+) -> None:
+    connection = await psql_pool.connection()
+    single_result = await connection.fetch_row(
+        querystring=f"SELECT id AS user_id, name FROM {table_name} LIMIT 1",
+    )
+
+    result = single_result.result(
+        column_case=ColumnCase.Camel,
+        column_mapping={"name": "fullName"},
+    )
+    assert set(result) == {"userId", "fullName"}
+
+
+async def test_result_columnar(
+    psql_pool: ConnectionPool,
+    table_name: str,
+) -> None:
+    connection = await psql_pool.connection()
+    conn_result = await connection.execute(
+        querystring=f"SELECT id AS user_id, name FROM {table_name} ORDER BY id",
+    )
+
+    columnar_result = conn_result.columnar(column_mapping={"name": "fullName"})
+    rows = conn_result.result()
+    assert list(columnar_result) == ["user_id", "fullName"]
+    assert columnar_result["user_id"] == [row["user_id"] for row in rows]
+    assert columnar_result["fullName"] == [row["name"] for row in rows]
+
+    empty_result = await connection.execute(
+        querystring=f"SELECT id FROM {table_name} WHERE id < 0",
+    )
+    assert empty_result.columnar() == {}
+
+
+async def test_result_close(
+    psql_pool: ConnectionPool,
+    table_name: str,
+) -> None:
+    connection = await psql_pool.connection()
+    conn_result = await connection.execute(
+        querystring=f"SELECT * FROM {table_name}",
+    )
+    empty_result = await connection.execute(
+        querystring=f"SELECT * FROM {table_name} WHERE id < 0",
+    )
+    assert sys.getsizeof(conn_result) > sys.getsizeof(empty_result)
+
+    with conn_result:
+        assert not conn_result.closed
+        assert conn_result.result()
+
+    assert conn_result.closed
+    assert sys.getsizeof(conn_result) == sys.getsizeof(empty_result)
+    with pytest.raises(QueryResultClosedError):
+        conn_result.result()
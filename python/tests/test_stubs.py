@@ -0,0 +1,131 @@
+from __future__ import annotations
+
+import ast
+import importlib.util
+import inspect
+import sys
+import types
+import typing
+from pathlib import Path
+
+import pytest
+from psqlpy import _internal
+from psqlpy._internal import extra_types, row_factories, tools
+
+STUBS_DIR = Path(_internal.__file__).parent / "_internal"
+SELF_NAMES = {"self", "self_", "slf", "cls"}
+STUB_GENERATOR = Path(__file__).parent.parent.parent / "scripts" / "generate_stubs.py"
+
+
+def stub_signatures(stub_name: str) -> dict[str, list[tuple[str, bool]]]:
+    """Return parameters of the functions and methods from the stub.
+
+    Keys are `function` or `Class.method`,
+    parameters are pairs of name and keyword-only flag.
+    """
+    stub = ast.parse((STUBS_DIR / stub_name).read_text())
+    signatures: dict[str, list[tuple[str, bool]]] = {}
+
+    def parameters(function: ast.FunctionDef | ast.AsyncFunctionDef) -> list[tuple[str, bool]]:
+        arguments = function.args
+        return [
+            (argument.arg, False)
+            for argument in arguments.posonlyargs + arguments.args
+            if argument.arg not in SELF_NAMES
+        ] + [(argument.arg, True) for argument in arguments.kwonlyargs]
+
+    for node in stub.body:
+        if isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef)):
+            signatures[node.name] = parameters(node)
+        if isinstance(node, ast.ClassDef):
+            for class_node in node.body:
+                if isinstance(class_node, (ast.FunctionDef, ast.AsyncFunctionDef)):
+                    signatures[f"{node.name}.{class_node.name}"] = parameters(class_node)
+    return signatures
+
+
+def runtime_signature(runtime_object: typing.Any) -> list[tuple[str, bool]] | None:
+    """Return parameters of the object from the extension module.
+
+    None is returned if extension doesn't provide signature.
+    """
+    try:
+        signature = inspect.signature(runtime_object)
+    except (TypeError, ValueError):
+        return None
+    return [
+        (parameter.name, parameter.kind == inspect.Parameter.KEYWORD_ONLY)
+        for parameter in signature.parameters.values()
+        if parameter.name not in SELF_NAMES
+        and parameter.kind not in (inspect.Parameter.VAR_POSITIONAL, inspect.Parameter.VAR_KEYWORD)
+    ]
+
+
+@pytest.mark.parametrize(
+    ("stub_name", "module"),
+    [
+        ("__init__.pyi", _internal),
+        ("extra_types.pyi", extra_types),
+        ("row_factories.pyi", row_factories),
+        ("tools.pyi", tools),
+    ],
+)
+def test_stub_signatures(stub_name: str, module: types.ModuleType) -> None:
+    """Test that stubs have the same parameters as the extension."""
+    mismatches = []
+    for name, stub_parameters in stub_signatures(stub_name).items():
+        owner_name, _, method_name = name.rpartition(".")
+        owner = getattr(module, owner_name, None) if owner_name else module
+        # Stubs also describe helpers which exist only for type checkers.
+        if owner is None or not hasattr(owner, method_name or name):
+            continue
+        if method_name.startswith("__") and method_name != "__init__":
+            continue
+
+        runtime_object = owner if method_name == "__init__" else getattr(owner, method_name or name)
+        runtime_parameters = runtime_signature(runtime_object)
+        if runtime_parameters is not None and runtime_parameters != stub_parameters:
+            mismatches.append(f"{name}: stub {stub_parameters}, extension {runtime_parameters}")
+
+    assert not mismatches, "\n".join(mismatches)
+
+
+@pytest.mark.parametrize(
+    ("stub_name", "module"),
+    [
+        ("__init__.pyi", _internal),
+        ("extra_types.pyi", extra_types),
+        ("tools.pyi", tools),
+    ],
+)
+def test_stub_classes(stub_name: str, module: types.ModuleType) -> None:
+    """Test that every class of the extension is described in the stub."""
+    stub = ast.parse((STUBS_DIR / stub_name).read_text())
+    stub_classes = {node.name for node in stub.body if isinstance(node, ast.ClassDef)}
+    runtime_classes = {
+        name
+        for name, runtime_object in vars(module).items()
+        if isinstance(runtime_object, type) and not issubclass(runtime_object, BaseException)
+    }
+
+    assert runtime_classes <= stub_classes, runtime_classes - stub_classes
+
+
+def test_stubs_are_generated() -> None:
+    """Test that stubs are up to date with `scripts/generate_stubs.py`."""
+    if not STUB_GENERATOR.exists():
+        pytest.skip("Stub generator is available only in the source tree")
+    spec = importlib.util.spec_from_file_location("generate_stubs", STUB_GENERATOR)
+    assert spec is not None
+    assert spec.loader is not None
+    generate_stubs = importlib.util.module_from_spec(spec)
+    sys.modules[spec.name] = generate_stubs
+    spec.loader.exec_module(generate_stubs)
+
+    outdated = []
+    for stub_name, module in generate_stubs.stub_modules():
+        source, generated = generate_stubs.generate_stub(stub_name, module)
+        if source != generated:
+            outdated.append(stub_name)
+
+    assert not outdated, f"Run `python scripts/generate_stubs.py`, outdated stubs: {outdated}"
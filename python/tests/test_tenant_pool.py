@@ -0,0 +1,100 @@
+import pytest
+from psqlpy import ConnectionPool, TenantPool
+from psqlpy.exceptions import (
+    ConnectionPoolCheckoutError,
+    ConnectionPoolConfigurationError,
+)
+
+pytestmark = pytest.mark.anyio
+
+
+@pytest.fixture
+def tenant_pool(
+    postgres_host: str,
+    postgres_user: str,
+    postgres_password: str,
+    postgres_port: int,
+    postgres_dbname: str,
+) -> TenantPool:
+    return TenantPool(
+        username=postgres_user,
+        password=postgres_password,
+        host=postgres_host,
+        port=postgres_port,
+        db_name=postgres_dbname,
+        max_total_connections=2,
+    )
+
+
+async def test_tenant_pool_connection(
+    tenant_pool: TenantPool,
+    postgres_user: str,
+    postgres_dbname: str,
+) -> None:
+    """Test that tenant pool creates pool per database on demand."""
+    connection = await tenant_pool.connection()
+    assert await connection.fetch_val("SELECT current_database()") == postgres_dbname
+    connection.back_to_pool()
+
+    connection = await tenant_pool.connection(db_name="postgres")
+    assert await connection.fetch_val("SELECT current_database()") == "postgres"
+    connection.back_to_pool()
+
+    assert sorted(tenant_pool.tenants()) == sorted(
+        [(postgres_dbname, postgres_user), ("postgres", postgres_user)],
+    )
+    assert isinstance(tenant_pool.pool(db_name="postgres"), ConnectionPool)
+
+    tenant_pool.remove_tenant(db_name="postgres")
+    assert tenant_pool.tenants() == [(postgres_dbname, postgres_user)]
+
+
+async def test_tenant_pool_total_connections_limit(
+    tenant_pool: TenantPool,
+) -> None:
+    """Test that idle connections of other tenants are closed on the limit."""
+    for db_name in (None, "postgres", "template1"):
+        connection = await tenant_pool.connection(db_name=db_name)
+        await connection.execute("SELECT 1")
+        connection.back_to_pool()
+
+        assert tenant_pool.total_connections() <= 2  # noqa: PLR2004
+
+    tenant_pool.close()
+    assert tenant_pool.tenants() == []
+
+
+async def test_tenant_pool_acquire_timeout(
+    postgres_host: str,
+    postgres_user: str,
+    postgres_password: str,
+    postgres_port: int,
+    postgres_dbname: str,
+) -> None:
+    """Test that waiting for the limit of total connections is bounded."""
+    tenant_pool = TenantPool(
+        username=postgres_user,
+        password=postgres_password,
+        host=postgres_host,
+        port=postgres_port,
+        db_name=postgres_dbname,
+        max_total_connections=1,
+        acquire_timeout=0.5,
+    )
+    connection = await tenant_pool.connection()
+
+    with pytest.raises(expected_exception=ConnectionPoolCheckoutError):
+        await tenant_pool.connection(db_name="postgres")
+
+    connection.back_to_pool()
+    tenant_pool.close()
+
+
+async def test_tenant_pool_configuration_error() -> None:
+    """Test that tenant pool validates pool sizes."""
+    with pytest.raises(expected_exception=ConnectionPoolConfigurationError):
+        TenantPool(max_tenant_pool_size=1)
+    with pytest.raises(expected_exception=ConnectionPoolConfigurationError):
+        TenantPool(max_total_connections=0)
+    with pytest.raises(expected_exception=ConnectionPoolConfigurationError):
+        TenantPool(acquire_timeout=-1)
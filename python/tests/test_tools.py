@@ -0,0 +1,44 @@
+import pytest
+from psqlpy import ConnectionPool
+from psqlpy.exceptions import PyToRustValueMappingError
+from psqlpy.tools import diff_results
+
+pytestmark = pytest.mark.anyio
+
+
+async def test_diff_results(
+    psql_pool: ConnectionPool,
+    table_name: str,
+    number_database_records: int,
+) -> None:
+    """Test that rows are matched by keys."""
+    connection = await psql_pool.connection()
+    result = await connection.fetch(f"SELECT * FROM {table_name} ORDER BY id")
+    rows = result.result()
+
+    assert not diff_results(result, rows, keys=["id"])
+
+    shadow_rows = [*rows[1:], {"id": -1, "name": "added"}]
+    diff = diff_results(result, shadow_rows, keys=["id"])
+
+    assert diff
+    assert diff.added == [{"id": -1, "name": "added"}]
+    assert diff.removed == [rows[0]]
+    assert diff.changed == []
+    assert len(rows) == number_database_records
+
+    changed_rows = [{**rows[1], "name": "changed"}, *rows[2:]]
+    diff = diff_results(rows[1:], changed_rows, keys=["id"])
+    assert diff.changed == [(rows[1], changed_rows[0])]
+    assert not diff.added
+    assert not diff.removed
+
+
+def test_diff_results_duplicate_keys() -> None:
+    """Test that keys must be unique and present in every row."""
+    with pytest.raises(PyToRustValueMappingError):
+        diff_results([{"id": 1}, {"id": 1}], [], keys=["id"])
+    with pytest.raises(PyToRustValueMappingError):
+        diff_results([{"name": "a"}], [], keys=["id"])
+    with pytest.raises(PyToRustValueMappingError):
+        diff_results([], [], keys=[])
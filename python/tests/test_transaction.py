@@ -7,13 +7,21 @@ from psqlpy import (
     ConnectionPool,
     Cursor,
     IsolationLevel,
+    PartialStatement,
     ReadVariant,
     SynchronousCommit,
 )
 from psqlpy.exceptions import (
+    CursorFetchError,
+    CursorStartError,
+    LockNotAvailableError,
+    PyToRustValueMappingError,
     RustPSQLDriverPyBaseError,
+    TransactionAlreadyCommittedError,
+    TransactionAlreadyRolledBackError,
     TransactionBeginError,
     TransactionExecuteError,
+    TransactionNotStartedError,
     TransactionSavepointError,
 )
 
@@ -115,6 +123,117 @@ async def test_transaction_commit(
     assert len(result.result())
 
 
+async def test_transaction_commit_and_rollback_chain(
+    psql_pool: ConnectionPool,
+    table_name: str,
+) -> None:
+    """Test that chained transaction keeps characteristics and stays ready."""
+    connection = await psql_pool.connection()
+    transaction = connection.transaction(
+        isolation_level=IsolationLevel.Serializable,
+        synchronous_commit=SynchronousCommit.Off,
+    )
+    await transaction.begin()
+    await transaction.create_savepoint("before_insert")
+    await transaction.execute(
+        f"INSERT INTO {table_name} VALUES ($1, $2)",
+        parameters=[100, "chained"],
+    )
+    await transaction.commit(chain=True)
+
+    assert await transaction.fetch_val("SHOW transaction_isolation") == "serializable"
+    assert await transaction.fetch_val("SHOW synchronous_commit") == "off"
+    with pytest.raises(expected_exception=TransactionSavepointError):
+        await transaction.rollback_savepoint("before_insert")
+
+    await transaction.execute(f"DELETE FROM {table_name} WHERE name = $1", ["chained"])
+    await transaction.rollback(chain=True)
+    assert await transaction.fetch_val("SHOW transaction_isolation") == "serializable"
+    await transaction.rollback()
+
+    other_connection = await psql_pool.connection()
+    assert await other_connection.fetch_val(
+        f"SELECT COUNT(*) FROM {table_name} WHERE name = $1",
+        ["chained"],
+    ) == 1
+
+
+async def test_transaction_export_snapshot(
+    psql_pool: ConnectionPool,
+    table_name: str,
+    number_database_records: int,
+) -> None:
+    """Test that transactions with imported snapshot see the same data."""
+    connection = await psql_pool.connection()
+    async with connection.transaction(
+        isolation_level=IsolationLevel.RepeatableRead,
+    ) as transaction:
+        snapshot = await transaction.export_snapshot()
+
+        other_connection = await psql_pool.connection()
+        await other_connection.execute(
+            f"INSERT INTO {table_name} VALUES ($1, $2)",
+            parameters=[100, "after_snapshot"],
+        )
+
+        snapshot_connection = await psql_pool.connection()
+        async with snapshot_connection.transaction(
+            isolation_level=IsolationLevel.RepeatableRead,
+            snapshot=snapshot,
+        ) as snapshot_transaction:
+            assert (
+                await count_rows_in_test_table(table_name, snapshot_transaction)
+                == number_database_records
+            )
+        assert await count_rows_in_test_table(table_name, transaction) == number_database_records
+
+    invalid_transaction = connection.transaction(
+        isolation_level=IsolationLevel.RepeatableRead,
+        snapshot="'; DROP TABLE users; --",
+    )
+    with pytest.raises(expected_exception=TransactionBeginError):
+        await invalid_transaction.begin()
+
+
+async def test_transaction_state_errors(
+    psql_pool: ConnectionPool,
+) -> None:
+    """Test that transaction raises precise errors when used in wrong state."""
+    connection = await psql_pool.connection()
+    transaction = connection.transaction()
+
+    with pytest.raises(expected_exception=TransactionNotStartedError):
+        await transaction.execute("SELECT 1")
+
+    with pytest.raises(expected_exception=TransactionNotStartedError):
+        await transaction.commit()
+
+    await transaction.begin()
+    with pytest.raises(expected_exception=TransactionBeginError):
+        await transaction.begin()
+
+    await transaction.commit()
+
+    with pytest.raises(expected_exception=TransactionAlreadyCommittedError):
+        await transaction.commit()
+
+    with pytest.raises(expected_exception=TransactionAlreadyCommittedError):
+        await transaction.execute("SELECT 1")
+
+    with pytest.raises(expected_exception=TransactionAlreadyCommittedError):
+        await transaction.begin()
+
+    transaction = connection.transaction()
+    await transaction.begin()
+    await transaction.rollback()
+
+    with pytest.raises(expected_exception=TransactionAlreadyRolledBackError):
+        await transaction.rollback()
+
+    with pytest.raises(expected_exception=TransactionAlreadyRolledBackError):
+        await transaction.fetch_val("SELECT 1")
+
+
 async def test_transaction_savepoint(
     psql_pool: ConnectionPool,
     table_name: str,
@@ -209,6 +328,78 @@ async def test_transaction_release_savepoint(
     await transaction.create_savepoint(sp_name_1)
 
 
+async def test_transaction_savepoint_context_manager(
+    psql_pool: ConnectionPool,
+    table_name: str,
+    number_database_records: int,
+) -> None:
+    """Test that nested savepoint blocks roll back only their own work."""
+    connection = await psql_pool.connection()
+    async with connection.transaction() as transaction:
+        await transaction.execute(f"DELETE FROM {table_name} WHERE id = 1")
+        with pytest.raises(expected_exception=ValueError):
+            async with transaction.savepoint("sp1") as savepoint:
+                assert savepoint.name == "sp1"
+                await transaction.execute(f"DELETE FROM {table_name} WHERE id = 2")
+                async with transaction.transaction() as nested:
+                    assert transaction.savepoints() == ["sp1", nested.name]
+                    await transaction.execute(f"DELETE FROM {table_name} WHERE id = 3")
+                raise ValueError
+
+        assert transaction.savepoints() == []
+        assert await transaction.fetch_val(f"SELECT COUNT(*) FROM {table_name}") == number_database_records - 1
+
+        async with transaction.transaction():
+            await transaction.execute(f"DELETE FROM {table_name} WHERE id = 2")
+
+    connection = await psql_pool.connection()
+    assert await connection.fetch_val(f"SELECT COUNT(*) FROM {table_name}") == number_database_records - 2
+
+
+async def test_transaction_savepoints_stack(
+    psql_pool: ConnectionPool,
+) -> None:
+    """Test that rollback to savepoint invalidates later savepoints."""
+    connection = await psql_pool.connection()
+    transaction = connection.transaction()
+    await transaction.begin()
+
+    await transaction.create_savepoint("sp1")
+    generated_name = await transaction.create_savepoint()
+    await transaction.create_savepoint("sp3")
+    assert transaction.savepoints() == ["sp1", generated_name, "sp3"]
+
+    await transaction.rollback_savepoint(generated_name)
+    assert transaction.savepoints() == ["sp1", generated_name]
+
+    with pytest.raises(expected_exception=TransactionSavepointError):
+        await transaction.release_savepoint("sp3")
+
+    await transaction.release_savepoint("sp1")
+    assert transaction.savepoints() == []
+
+    await transaction.rollback()
+
+
+@pytest.mark.parametrize(
+    "savepoint_name",
+    ["", "1sp", "sp-1", 'sp"; DROP TABLE users; --', "a" * 64],
+)
+async def test_transaction_savepoint_invalid_name(
+    psql_pool: ConnectionPool,
+    savepoint_name: str,
+) -> None:
+    """Test that invalid savepoint names are rejected."""
+    connection = await psql_pool.connection()
+    transaction = connection.transaction()
+    await transaction.begin()
+
+    with pytest.raises(expected_exception=TransactionSavepointError):
+        await transaction.create_savepoint(savepoint_name)
+
+    await transaction.rollback()
+
+
 async def test_transaction_cursor(
     psql_pool: ConnectionPool,
     table_name: str,
@@ -221,6 +412,121 @@ async def test_transaction_cursor(
         assert isinstance(cursor, Cursor)
 
 
+async def test_transaction_statement_partial_execution(
+    psql_pool: ConnectionPool,
+    table_name: str,
+    number_database_records: int,
+) -> None:
+    """Test that statement returns rows by portions of the cursor."""
+    connection = await psql_pool.connection()
+    async with connection.transaction() as transaction:
+        statement = transaction.statement(
+            f"SELECT * FROM {table_name} WHERE id > $1 ORDER BY id",
+            [0],
+        )
+        assert isinstance(statement, PartialStatement)
+
+        result, suspended = await statement.execute_partial(max_rows=1)
+        assert len(result.result()) == 1
+        assert suspended
+        assert statement.suspended
+
+        fetched_rows = 1
+        while suspended:
+            result, suspended = await statement.resume(max_rows=1)
+            fetched_rows += len(result.result())
+        assert fetched_rows == number_database_records
+        assert not statement.suspended
+
+        with pytest.raises(expected_exception=CursorFetchError):
+            await statement.resume(max_rows=1)
+
+        await statement.close()
+
+
+async def test_transaction_statement_exact_portion(
+    psql_pool: ConnectionPool,
+    table_name: str,
+    number_database_records: int,
+) -> None:
+    """Test that statement isn't suspended when the last portion is full."""
+    connection = await psql_pool.connection()
+    async with connection.transaction() as transaction:
+        statement = transaction.statement(f"SELECT * FROM {table_name}")
+        assert statement.cursor_name is not None
+
+        result, suspended = await statement.execute_partial(
+            max_rows=number_database_records - 1,
+        )
+        assert len(result.result()) == number_database_records - 1
+        assert suspended
+
+        result, suspended = await statement.resume(max_rows=1)
+        assert len(result.result()) == 1
+        assert not suspended
+        assert not statement.suspended
+
+        await statement.close()
+
+
+async def test_transaction_statement_returning(
+    psql_pool: ConnectionPool,
+    table_name: str,
+) -> None:
+    """Test that statement which cannot be a cursor returns rows by portions."""
+    connection = await psql_pool.connection()
+    async with connection.transaction() as transaction:
+        statement = transaction.statement(
+            f"INSERT INTO {table_name} (name) VALUES ($1), ($2), ($3) RETURNING name",
+            ["first", "second", "third"],
+        )
+        assert statement.cursor_name is None
+
+        result, suspended = await statement.execute_partial(max_rows=2)
+        assert [row["name"] for row in result.result()] == ["first", "second"]
+        assert suspended
+
+        result, suspended = await statement.resume(max_rows=2)
+        assert [row["name"] for row in result.result()] == ["third"]
+        assert not suspended
+
+        await statement.close()
+
+
+async def test_transaction_cursors(
+    psql_pool: ConnectionPool,
+    table_name: str,
+) -> None:
+    """Test that transaction tracks open cursors."""
+    connection = await psql_pool.connection()
+    async with connection.transaction() as transaction:
+        first_cursor = transaction.cursor(
+            f"SELECT * FROM {table_name}",
+            cursor_name="first_cursor",
+            scroll=True,
+            hold=False,
+        )
+        second_cursor = transaction.cursor(
+            f"SELECT * FROM {table_name}",
+            cursor_name="second_cursor",
+        )
+        assert transaction.cursors() == []
+
+        await first_cursor.start()
+        await second_cursor.start()
+        assert transaction.cursors() == [first_cursor, second_cursor]
+
+        await first_cursor.close()
+        assert transaction.cursors() == [second_cursor]
+        await second_cursor.close()
+
+        with pytest.raises(expected_exception=CursorStartError):
+            transaction.cursor(
+                f"SELECT * FROM {table_name}",
+                cursor_name="bad; DROP TABLE users",
+            )
+
+
 async def test_transaction_fetch(
     psql_pool: ConnectionPool,
     table_name: str,
@@ -383,3 +689,40 @@ async def test_synchronous_commit(
         )
 
         assert len(res.result()) == number_database_records
+
+
+async def test_transaction_claim_rows(
+    psql_pool: ConnectionPool,
+    table_name: str,
+) -> None:
+    """Test that claimed rows are skipped by other workers and NOWAIT raises."""
+    first_connection = await psql_pool.connection()
+    second_connection = await psql_pool.connection()
+    third_connection = await psql_pool.connection()
+
+    async with first_connection.transaction() as first_worker:
+        first_claimed = await first_worker.claim_rows(
+            table_name.lower(),
+            where="id > $1",
+            limit=3,
+            returning=["id"],
+            order_by="id",
+            parameters=[0],
+        )
+        assert first_claimed.result() == [{"id": 1}, {"id": 2}, {"id": 3}]
+
+        async with second_connection.transaction() as second_worker:
+            second_claimed = await second_worker.claim_rows(
+                table_name.lower(),
+                limit=3,
+                returning=["id"],
+                order_by="id",
+            )
+            assert second_claimed.result() == [{"id": 4}, {"id": 5}, {"id": 6}]
+
+        with pytest.raises(expected_exception=LockNotAvailableError):
+            async with third_connection.transaction() as third_worker:
+                await third_worker.claim_rows(table_name.lower(), order_by="id", nowait=True)
+
+        with pytest.raises(expected_exception=PyToRustValueMappingError):
+            await first_worker.claim_rows(table_name.lower(), lock="exclusive")
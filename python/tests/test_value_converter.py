@@ -1,17 +1,19 @@
+import dataclasses
 import datetime
 import sys
 import uuid
 from decimal import Decimal
-from enum import Enum
+from enum import Enum, IntEnum
 from ipaddress import IPv4Address
-from typing import Any, Dict, List, Tuple, Union
+from typing import Any, Dict, List, Optional, Tuple, Union
 
 import pytest
-from psqlpy import ConnectionPool
+from psqlpy import ConnectionPool, register_composite
 from psqlpy.exceptions import PyToRustValueMappingError
 from psqlpy.extra_types import (
     JSON,
     JSONB,
+    AsText,
     BigInt,
     BoolArray,
     Box,
@@ -46,6 +48,10 @@ from psqlpy.extra_types import (
     PathArray,
     Point,
     PointArray,
+    PyCast,
+    PyDecimal,
+    PyVector,
+    Range,
     SmallInt,
     Text,
     TextArray,
@@ -82,6 +88,13 @@ now_datetime_with_tz_in_asia_jakarta = datetime.datetime(
     142574,
     tzinfo=datetime.timezone.utc,
 )
+now_time_with_tz = datetime.time(
+    17,
+    3,
+    46,
+    142574,
+    tzinfo=datetime.timezone(datetime.timedelta(hours=7)),
+)
 if sys.version_info >= (3, 9):
     import zoneinfo
 
@@ -144,6 +157,14 @@ async def test_as_class(
         ("MONEY", BigInt(99999999999999999), 99999999999999999),
         ("MONEY", Money(99999999999999999), 99999999999999999),
         ("NUMERIC(5, 2)", Decimal("120.12"), Decimal("120.12")),
+        (
+            "NUMERIC",
+            Decimal("-1234567890.123456789012345678901234500"),
+            Decimal("-1234567890.123456789012345678901234500"),
+        ),
+        ("NUMERIC", Decimal("1E+30"), Decimal("1000000000000000000000000000000")),
+        ("NUMERIC", Decimal("-Infinity"), Decimal("-Infinity")),
+        ("NUMERIC(10, 3)", PyDecimal(0.1), Decimal("0.100")),
         ("FLOAT8", 32.12329864501953, 32.12329864501953),
         ("FLOAT4", Float32(32.12329864501953), 32.12329864501953),
         ("FLOAT8", Float64(32.12329864501953), 32.12329864501953),
@@ -152,6 +173,7 @@ async def test_as_class(
         ("TIMESTAMP", now_datetime, now_datetime),
         ("TIMESTAMPTZ", now_datetime_with_tz, now_datetime_with_tz),
         ("TIMESTAMPTZ", now_datetime_with_tz_in_asia_jakarta, now_datetime_with_tz_in_asia_jakarta),
+        ("TIMETZ", now_time_with_tz, now_time_with_tz),
         ("UUID", uuid_, str(uuid_)),
         ("INET", IPv4Address("192.0.0.1"), IPv4Address("192.0.0.1")),
         (
@@ -307,6 +329,11 @@ async def test_as_class(
             [[now_datetime.time()], [now_datetime.time()]],
             [[now_datetime.time()], [now_datetime.time()]],
         ),
+        (
+            "TIMETZ ARRAY",
+            [now_time_with_tz, now_time_with_tz],
+            [now_time_with_tz, now_time_with_tz],
+        ),
         ("TIMESTAMP ARRAY", [now_datetime, now_datetime], [now_datetime, now_datetime]),
         ("TIMESTAMP ARRAY", [[now_datetime], [now_datetime]], [[now_datetime], [now_datetime]]),
         (
@@ -964,6 +991,53 @@ async def test_enum_type(psql_pool: ConnectionPool) -> None:
     assert qs_result.result()[0]["test_mood2"] == TestStrEnum.OK
 
 
+async def test_registered_enum_type(psql_pool: ConnectionPool) -> None:
+    """Test that registered ENUM type is decoded into Enum members."""
+
+    class Mood(Enum):
+        SAD = "sad"
+        OK = "ok"
+        HAPPY = "happy"
+
+    class Level(IntEnum):
+        low = 1
+        high = 2
+
+    connection = await psql_pool.connection()
+    await connection.execute("DROP TABLE IF EXISTS for_test")
+    await connection.execute("DROP TYPE IF EXISTS mood")
+    await connection.execute("DROP TYPE IF EXISTS level")
+    await connection.execute("CREATE TYPE mood AS ENUM ('sad', 'ok', 'happy')")
+    await connection.execute("CREATE TYPE level AS ENUM ('low', 'high')")
+    await connection.execute(
+        "CREATE TABLE for_test (test_mood mood, moods mood[], test_level level)",
+    )
+
+    connection.register_enum("mood", Mood)
+    connection.register_enum("level", Level)
+    try:
+        await connection.execute(
+            querystring="INSERT INTO for_test VALUES ($1, $2, $3)",
+            parameters=[Mood.HAPPY, [Mood.OK, None, Mood.SAD], Level.high],
+        )
+        result = await connection.fetch_row("SELECT * FROM for_test")
+        assert result.result() == {
+            "test_mood": Mood.HAPPY,
+            "moods": [Mood.OK, None, Mood.SAD],
+            "test_level": Level.high,
+        }
+        assert isinstance(result.result()["test_mood"], Mood)
+
+        with pytest.raises(expected_exception=PyToRustValueMappingError):
+            connection.register_enum("mood", dict)
+    finally:
+        connection.register_enum("mood", None)
+        connection.register_enum("level", None)
+
+    result = await connection.fetch_val("SELECT test_mood FROM for_test")
+    assert result == "happy"
+
+
 async def test_custom_type_as_parameter(
     psql_pool: ConnectionPool,
 ) -> None:
@@ -1096,6 +1170,361 @@ async def test_empty_array(
         assert not json_result[0]["e_array"]
 
 
+@pytest.mark.parametrize(
+    ("postgres_type", "py_value", "expected_deserialized"),
+    [
+        ("INT2[]", [1, None, 3], [1, None, 3]),
+        ("INT8[]", [1, None, 10000000000], [1, None, 10000000000]),
+        ("TEXT[]", ["Some", None, "String"], ["Some", None, "String"]),
+        ("TEXT[]", [None, None], [None, None]),
+        ("TEXT[]", [], []),
+        ("NUMERIC[]", [1, None, 1.5], [Decimal("1"), None, Decimal("1.5")]),
+        (
+            "UUID[]",
+            ["00000000-0000-0000-0000-000000000001", None],
+            [uuid.UUID("00000000-0000-0000-0000-000000000001"), None],
+        ),
+        (
+            "TIMESTAMP[]",
+            [None, datetime.datetime(2024, 1, 1, 12, 0)],
+            [None, datetime.datetime(2024, 1, 1, 12, 0)],
+        ),
+        ("INT4[][]", [[1, 2], [None, 4]], [[1, 2], [None, 4]]),
+        (
+            "INT4[][][]",
+            [[[1, 2], [3, 4], [5, 6]], [[7, 8], [9, 10], [11, 12]]],
+            [[[1, 2], [3, 4], [5, 6]], [[7, 8], [9, 10], [11, 12]]],
+        ),
+    ],
+)
+async def test_array_elements_with_nulls_and_dimensions(
+    psql_pool: ConnectionPool,
+    postgres_type: str,
+    py_value: Any,
+    expected_deserialized: Any,
+) -> None:
+    """Test arrays with NULL elements, any dimensions and element types."""
+    async with psql_pool.acquire() as conn:
+        await conn.execute("DROP TABLE IF EXISTS for_test")
+        await conn.execute(f"CREATE TABLE for_test (test_field {postgres_type})")
+        await conn.execute(
+            querystring="INSERT INTO for_test VALUES ($1)",
+            parameters=[py_value],
+        )
+
+        raw_result = await conn.execute(
+            querystring="SELECT test_field FROM for_test",
+        )
+
+        assert raw_result.result()[0]["test_field"] == expected_deserialized
+
+
+async def test_composite_and_enum_arrays(
+    psql_pool: ConnectionPool,
+) -> None:
+    """Test arrays of composite and enum types."""
+    async with psql_pool.acquire() as conn:
+        await conn.execute("DROP TABLE IF EXISTS for_test")
+        await conn.execute("DROP TYPE IF EXISTS inventory_item")
+        await conn.execute("DROP TYPE IF EXISTS mood")
+        await conn.execute("CREATE TYPE mood AS ENUM ('sad', 'ok', 'happy')")
+        await conn.execute(
+            "CREATE TYPE inventory_item AS (name TEXT, price NUMERIC, tags TEXT[])",
+        )
+        await conn.execute(
+            "CREATE TABLE for_test (items inventory_item[], moods mood[])",
+        )
+
+        items = [
+            {"name": "fuzzy dice", "price": 42, "tags": ["car", None]},
+            None,
+            {"name": "dice", "price": None, "tags": []},
+        ]
+        await conn.execute(
+            querystring="INSERT INTO for_test VALUES ($1, $2)",
+            parameters=[items, ["happy", None, "sad"]],
+        )
+
+        result = await conn.execute("SELECT * FROM for_test")
+
+        assert result.result()[0]["items"] == [
+            {"name": "fuzzy dice", "price": Decimal("42"), "tags": ["car", None]},
+            None,
+            {"name": "dice", "price": None, "tags": []},
+        ]
+        assert result.result()[0]["moods"] == ["happy", None, "sad"]
+
+
+async def test_composite_parameters_and_registered_class(
+    psql_pool: ConnectionPool,
+) -> None:
+    """Test composite values from dicts, tuples and registered classes."""
+
+    @dataclasses.dataclass
+    class InventoryItem:
+        name: str
+        price: Optional[Decimal]
+        tags: List[str]
+
+    async with psql_pool.acquire() as conn:
+        await conn.execute("DROP TABLE IF EXISTS for_test")
+        await conn.execute("DROP TYPE IF EXISTS inventory_order")
+        await conn.execute("DROP TYPE IF EXISTS inventory_item")
+        await conn.execute(
+            "CREATE TYPE inventory_item AS (name TEXT, price NUMERIC, tags TEXT[])",
+        )
+        await conn.execute(
+            "CREATE TYPE inventory_order AS (item inventory_item, amount INT4)",
+        )
+        await conn.execute(
+            "CREATE TABLE for_test (id INT4, item inventory_item, order_ inventory_order)",
+        )
+
+        await conn.execute(
+            querystring="INSERT INTO for_test VALUES (1, $1, $2), (2, $3, $4)",
+            parameters=[
+                ("dice", 42, ["car", "game"]),
+                {"item": None, "amount": 2},
+                {"name": "ball"},
+                {"item": {"name": "cube", "tags": []}, "amount": 3},
+            ],
+        )
+        result = await conn.execute("SELECT item, order_ FROM for_test ORDER BY id")
+        assert result.result() == [
+            {
+                "item": {"name": "dice", "price": Decimal("42"), "tags": ["car", "game"]},
+                "order_": {"item": None, "amount": 2},
+            },
+            {
+                "item": {"name": "ball", "price": None, "tags": None},
+                "order_": {
+                    "item": {"name": "cube", "price": None, "tags": []},
+                    "amount": 3,
+                },
+            },
+        ]
+
+        register_composite("inventory_item", InventoryItem)
+        try:
+            await conn.execute(
+                querystring="INSERT INTO for_test VALUES (3, $1)",
+                parameters=[InventoryItem(name="top", price=Decimal("1.5"), tags=[])],
+            )
+            item = await conn.fetch_val("SELECT item FROM for_test WHERE id = 3")
+            assert item == InventoryItem(name="top", price=Decimal("1.5"), tags=[])
+
+            order = await conn.fetch_val("SELECT order_ FROM for_test WHERE id = 2")
+            assert order["item"] == InventoryItem(name="cube", price=None, tags=[])
+        finally:
+            register_composite("inventory_item", None)
+
+
+async def test_as_text_parameter(
+    psql_pool: ConnectionPool,
+) -> None:
+    async with psql_pool.acquire() as conn:
+        await conn.execute("DROP TABLE IF EXISTS test_as_text")
+        await conn.execute(
+            "CREATE TABLE test_as_text (point_col POINT, int_col INT4, date_col DATE)",
+        )
+
+        await conn.execute(
+            "INSERT INTO test_as_text VALUES ($1, $2, $3)",
+            [AsText("(1.5, 2.5)"), AsText(10), AsText(datetime.date(2024, 1, 2))],
+        )
+
+        res = await conn.fetch_row(
+            "SELECT point_col::TEXT AS point_col, int_col, date_col FROM test_as_text",
+        )
+        assert res.result() == {
+            "point_col": "(1.5,2.5)",
+            "int_col": 10,
+            "date_col": datetime.date(2024, 1, 2),
+        }
+
+
+async def test_py_cast_parameter(
+    psql_pool: ConnectionPool,
+) -> None:
+    async with psql_pool.acquire() as conn:
+        res = await conn.fetch_row(
+            "SELECT pg_typeof($1)::TEXT AS cast_type, $1 AS cast_value, '$2' AS literal, $2 AS null_value",
+            [PyCast("12.50", "numeric(10, 2)"), PyCast(None, "int4")],
+        )
+        assert res.result() == {
+            "cast_type": "numeric",
+            "cast_value": Decimal("12.50"),
+            "literal": "$2",
+            "null_value": None,
+        }
+
+    with pytest.raises(expected_exception=PyToRustValueMappingError):
+        PyCast(1, "int4; DROP TABLE users")
+
+
+async def test_py_cast_parameter_execute_many(
+    psql_pool: ConnectionPool,
+) -> None:
+    async with psql_pool.acquire() as conn:
+        await conn.execute("DROP TABLE IF EXISTS test_py_cast_many")
+        await conn.execute(
+            "CREATE TABLE test_py_cast_many (id INT, cast_type TEXT, amount TEXT)",
+        )
+
+        await conn.execute_many(
+            "INSERT INTO test_py_cast_many VALUES ($1, pg_typeof($2)::TEXT, $2::TEXT)",
+            [
+                [1, PyCast("12.50", "numeric(10, 2)")],
+                [2, PyCast("3", "numeric(10, 2)")],
+            ],
+        )
+        await conn.execute_values(
+            "INSERT INTO test_py_cast_many (id, amount) VALUES %s",
+            [
+                [3, PyCast("1.5", "numeric(10, 2)")],
+                [4, PyCast("2", "numeric(10, 2)")],
+            ],
+        )
+
+        res = await conn.fetch(
+            "SELECT id, cast_type, amount FROM test_py_cast_many ORDER BY id",
+        )
+        assert res.result() == [
+            {"id": 1, "cast_type": "numeric", "amount": "12.50"},
+            {"id": 2, "cast_type": "numeric", "amount": "3.00"},
+            {"id": 3, "cast_type": None, "amount": "1.50"},
+            {"id": 4, "cast_type": None, "amount": "2.00"},
+        ]
+
+        with pytest.raises(expected_exception=PyToRustValueMappingError):
+            await conn.execute_many(
+                "INSERT INTO test_py_cast_many (id, amount) VALUES ($1, $2)",
+                [
+                    [5, PyCast("1", "numeric(10, 2)")],
+                    [6, "1"],
+                ],
+            )
+
+
+async def test_range_types(
+    psql_pool: ConnectionPool,
+) -> None:
+    async with psql_pool.acquire() as conn:
+        await conn.execute("DROP TABLE IF EXISTS test_range")
+        await conn.execute(
+            "CREATE TABLE test_range ("
+            "int_col INT4RANGE, num_col NUMRANGE, date_col DATERANGE, "
+            "tstz_col TSTZRANGE, arr_col INT8RANGE[])",
+        )
+
+        moment = datetime.datetime(2024, 1, 2, 3, 4, 5, tzinfo=datetime.timezone.utc)
+        await conn.execute(
+            "INSERT INTO test_range VALUES ($1, $2, $3, $4, $5)",
+            [
+                Range(1, 10, "[]"),
+                Range(Decimal("1.5"), None, "(]"),
+                (datetime.date(2024, 1, 1), datetime.date(2024, 2, 1)),
+                Range(None, moment),
+                [Range(1, 5), Range(empty=True)],
+            ],
+        )
+
+        res = await conn.fetch_row("SELECT * FROM test_range")
+        result = res.result()
+        assert result == {
+            "int_col": Range(1, 11, "[)"),
+            "num_col": Range(Decimal("1.5"), None, "()"),
+            "date_col": Range(datetime.date(2024, 1, 1), datetime.date(2024, 2, 1)),
+            "tstz_col": Range(None, moment, "()"),
+            "arr_col": [Range(1, 5), Range(empty=True)],
+        }
+        assert result["num_col"].upper_inf
+        assert not result["num_col"].lower_inc
+        assert not result["arr_col"][1]
+        assert result["arr_col"][1].isempty
+
+    with pytest.raises(expected_exception=PyToRustValueMappingError):
+        Range(1, 2, "[[")
+
+
+async def test_multirange_types(
+    psql_pool: ConnectionPool,
+) -> None:
+    async with psql_pool.acquire() as conn:
+        await conn.execute("DROP TABLE IF EXISTS test_multirange")
+        await conn.execute(
+            "CREATE TABLE test_multirange ("
+            "int_col INT4MULTIRANGE, tstz_col TSTZMULTIRANGE, empty_col NUMMULTIRANGE)",
+        )
+
+        first_moment = datetime.datetime(2024, 1, 1, tzinfo=datetime.timezone.utc)
+        second_moment = datetime.datetime(2024, 1, 2, tzinfo=datetime.timezone.utc)
+        await conn.execute(
+            "INSERT INTO test_multirange VALUES ($1, $2, $3)",
+            [
+                [Range(1, 3), (2, 5), Range(10, 20, "[]")],
+                [Range(first_moment, second_moment), Range(second_moment, None, "()")],
+                [],
+            ],
+        )
+
+        res = await conn.fetch_row("SELECT * FROM test_multirange")
+        assert res.result() == {
+            "int_col": [Range(1, 5), Range(10, 21)],
+            "tstz_col": [
+                Range(first_moment, second_moment),
+                Range(second_moment, None, "()"),
+            ],
+            "empty_col": [],
+        }
+
+
+async def test_vector_type(
+    psql_pool: ConnectionPool,
+) -> None:
+    async with psql_pool.acquire() as conn:
+        try:
+            await conn.execute("CREATE EXTENSION IF NOT EXISTS vector")
+        except Exception:  # noqa: BLE001
+            pytest.skip("pgvector extension is not available")
+
+        await conn.execute("DROP TABLE IF EXISTS test_vector")
+        await conn.execute("CREATE TABLE test_vector (id INT, embedding VECTOR(3))")
+
+        numpy = pytest.importorskip("numpy")
+        await conn.execute_many(
+            "INSERT INTO test_vector VALUES ($1, $2)",
+            [
+                [1, [1.0, 2.0, 3.0]],
+                [2, (4, 5, 6)],
+                [3, PyVector([7, 8, 9])],
+                [4, numpy.array([0.5, 1.5, 2.5], dtype=numpy.float32)],
+            ],
+        )
+
+        res = await conn.fetch("SELECT embedding FROM test_vector ORDER BY id")
+        assert [row["embedding"] for row in res.result()] == [
+            [1.0, 2.0, 3.0],
+            [4.0, 5.0, 6.0],
+            [7.0, 8.0, 9.0],
+            [0.5, 1.5, 2.5],
+        ]
+
+        nearest = await conn.fetch_val(
+            "SELECT id FROM test_vector ORDER BY embedding <-> $1 LIMIT 1",
+            [numpy.array([7.0, 8.0, 8.0])],
+        )
+        assert nearest == 3
+
+    vector = PyVector(numpy.array([1.0, 2.0]))
+    assert vector.to_list() == [1.0, 2.0]
+    assert len(vector) == 2
+    assert vector.to_numpy().dtype == numpy.float32
+
+    with pytest.raises(expected_exception=PyToRustValueMappingError):
+        PyVector(["not a number"])
+
+
 @pytest.mark.parametrize(
     ("postgres_type", "py_value", "expected_deserialized"),
     [
@@ -0,0 +1,345 @@
+"""Generate signatures of the type stubs from the built extension.
+
+Stubs are kept in `python/psqlpy/_internal/*.pyi` because they carry
+types and documentation, but parameters of every function and method
+are taken from the extension, so they always match the Rust signatures.
+
+- Parameter lists which differ from the extension are rewritten,
+  annotations and defaults of the existing parameters are kept.
+- Functions, methods and classes which are missing in the stub are added
+  with `typing.Any` annotations, so they should be typed by hand later.
+
+Run it after `maturin develop`:
+
+    python scripts/generate_stubs.py          # rewrite stubs
+    python scripts/generate_stubs.py --check  # fail if stubs are outdated
+"""
+
+from __future__ import annotations
+
+import argparse
+import ast
+import inspect
+import sys
+import types
+import typing
+from dataclasses import dataclass
+from pathlib import Path
+
+STUBS_DIR = Path(__file__).parent.parent / "python" / "psqlpy" / "_internal"
+SELF_NAMES = {"self", "self_", "slf", "cls"}
+ANY_ANNOTATION = "typing.Any"
+INDENT = "    "
+
+
+@dataclass(frozen=True)
+class RuntimeParameter:
+    """Parameter of the function from the extension."""
+
+    name: str
+    is_keyword_only: bool
+    default: str | None
+
+
+def stub_modules() -> list[tuple[str, types.ModuleType]]:
+    """Return stub file names with the extension modules they describe."""
+    from psqlpy import _internal  # noqa: PLC0415
+    from psqlpy._internal import extra_types, row_factories, tools  # noqa: PLC0415
+
+    return [
+        ("__init__.pyi", _internal),
+        ("extra_types.pyi", extra_types),
+        ("row_factories.pyi", row_factories),
+        ("tools.pyi", tools),
+    ]
+
+
+def runtime_parameters(runtime_object: typing.Any) -> list[RuntimeParameter] | None:
+    """Return parameters of the object from the extension.
+
+    None is returned if extension doesn't provide signature.
+    Extension shows every default except `None` as `...`.
+    """
+    try:
+        signature = inspect.signature(runtime_object)
+    except (TypeError, ValueError):
+        return None
+
+    parameters = []
+    for parameter in signature.parameters.values():
+        if parameter.name in SELF_NAMES or parameter.kind in (
+            inspect.Parameter.VAR_POSITIONAL,
+            inspect.Parameter.VAR_KEYWORD,
+        ):
+            continue
+        default = None
+        if parameter.default is not inspect.Parameter.empty:
+            default = "None" if parameter.default is None else "..."
+        parameters.append(
+            RuntimeParameter(
+                name=parameter.name,
+                is_keyword_only=parameter.kind == inspect.Parameter.KEYWORD_ONLY,
+                default=default,
+            ),
+        )
+    return parameters
+
+
+def runtime_functions(owner: typing.Any) -> dict[str, typing.Any]:
+    """Return public functions and methods of the module or class from the extension."""
+    functions = {}
+    if isinstance(owner, type):
+        functions["__init__"] = owner
+    for name in vars(owner):
+        if name.startswith("_"):
+            continue
+        runtime_object = getattr(owner, name)
+        if callable(runtime_object) and not isinstance(runtime_object, type):
+            functions[name] = runtime_object
+    return functions
+
+
+def is_static_method(owner: typing.Any, name: str) -> bool:
+    """Check is method of the class static or not."""
+    return isinstance(inspect.getattr_static(owner, name, None), staticmethod)
+
+
+def runtime_classes(module: types.ModuleType) -> dict[str, type]:
+    """Return classes of the extension module, except exceptions."""
+    return {
+        name: runtime_object
+        for name, runtime_object in vars(module).items()
+        if isinstance(runtime_object, type) and not issubclass(runtime_object, BaseException)
+    }
+
+
+class StubGenerator:
+    """Rewrite one stub file according to the extension module."""
+
+    def __init__(self, source: str, module: types.ModuleType) -> None:
+        self.source = source
+        self.lines = source.splitlines(keepends=True)
+        self.module = module
+        self.tree = ast.parse(source)
+        # Replacements as (start offset, end offset, text).
+        self.edits: list[tuple[int, int, str]] = []
+        # New functions and classes for the end of the stub.
+        self.appended: list[str] = []
+
+    def offset(self, lineno: int, col_offset: int) -> int:
+        """Convert position of the node into offset in the source."""
+        return sum(len(line.encode()) for line in self.lines[: lineno - 1]) + col_offset
+
+    def segment(self, node: ast.AST | None) -> str | None:
+        """Return source of the node."""
+        if node is None:
+            return None
+        return ast.get_source_segment(self.source, node)
+
+    def stub_parameters(
+        self,
+        function: ast.FunctionDef | ast.AsyncFunctionDef,
+    ) -> dict[str, tuple[str | None, str | None]]:
+        """Return annotations and defaults of the stub parameters by name."""
+        arguments = function.args
+        positional = arguments.posonlyargs + arguments.args
+        positional_defaults: list[ast.expr | None] = [None] * (
+            len(positional) - len(arguments.defaults)
+        ) + list(arguments.defaults)
+        pairs = list(zip(positional, positional_defaults)) + list(
+            zip(arguments.kwonlyargs, arguments.kw_defaults),
+        )
+        return {
+            argument.arg: (self.segment(argument.annotation), self.segment(default))
+            for argument, default in pairs
+        }
+
+    @staticmethod
+    def stub_shape(
+        function: ast.FunctionDef | ast.AsyncFunctionDef,
+    ) -> list[RuntimeParameter]:
+        """Return parameters of the stub in the form comparable with the extension."""
+        arguments = function.args
+        positional = arguments.posonlyargs + arguments.args
+        first_default = len(positional) - len(arguments.defaults)
+        shape = [
+            RuntimeParameter(argument.arg, is_keyword_only=False, default=str(index >= first_default))
+            for index, argument in enumerate(positional)
+            if argument.arg not in SELF_NAMES
+        ]
+        shape += [
+            RuntimeParameter(argument.arg, is_keyword_only=True, default=str(default is not None))
+            for argument, default in zip(arguments.kwonlyargs, arguments.kw_defaults)
+        ]
+        return shape
+
+    def render_parameters(
+        self,
+        function: ast.FunctionDef | ast.AsyncFunctionDef | None,
+        parameters: list[RuntimeParameter],
+        indent: str,
+        *,
+        is_method: bool,
+    ) -> str:
+        """Render parameter list of the function, one parameter per line.
+
+        `self` of the stub function is kept, new method gets `self` if `is_method` is set.
+        """
+        stub_parameters = self.stub_parameters(function) if function else {}
+        rendered = []
+        if function is not None:
+            arguments = function.args
+            rendered += [
+                self.segment(argument) or argument.arg
+                for argument in arguments.posonlyargs + arguments.args
+                if argument.arg in SELF_NAMES
+            ]
+        elif is_method:
+            rendered.append("self")
+
+        is_keyword_only = False
+        for parameter in parameters:
+            if parameter.is_keyword_only and not is_keyword_only:
+                rendered.append("*")
+                is_keyword_only = True
+            annotation, default = stub_parameters.get(parameter.name, (None, None))
+            text = f"{parameter.name}: {annotation or ANY_ANNOTATION}"
+            if parameter.default is not None:
+                text += f" = {default or parameter.default}"
+            rendered.append(text)
+
+        if not rendered:
+            return "()"
+        inner_indent = indent + INDENT
+        return "(\n" + "".join(f"{inner_indent}{text},\n" for text in rendered) + f"{indent})"
+
+    def update_function(
+        self,
+        function: ast.FunctionDef | ast.AsyncFunctionDef,
+        runtime_object: typing.Any,
+    ) -> None:
+        """Rewrite parameters of the stub function if they differ from the extension."""
+        parameters = runtime_parameters(runtime_object)
+        if parameters is None:
+            return
+        expected_shape = [
+            RuntimeParameter(parameter.name, parameter.is_keyword_only, str(parameter.default is not None))
+            for parameter in parameters
+        ]
+        if self.stub_shape(function) == expected_shape:
+            return
+
+        # Parameters are between the parentheses after the function name.
+        start = self.source.encode().index(b"(", self.offset(function.lineno, function.col_offset))
+        end_node = function.returns if function.returns is not None else function.body[0]
+        end = self.source.encode().rindex(b")", start, self.offset(end_node.lineno, end_node.col_offset)) + 1
+        indent = " " * function.col_offset
+        self.edits.append(
+            (start, end, self.render_parameters(function, parameters, indent, is_method=False)),
+        )
+
+    def render_function(self, name: str, runtime_object: typing.Any, indent: str, *, owner: typing.Any) -> str:
+        """Render stub of the function which is missing in the stub file.
+
+        Empty string is returned for the constructor without signature.
+        """
+        parameters = runtime_parameters(runtime_object)
+        if parameters is None and name == "__init__":
+            return ""
+        is_method = isinstance(owner, type) and not is_static_method(owner, name)
+        rendered_parameters = self.render_parameters(None, parameters or [], indent, is_method=is_method)
+        returns = "None" if name == "__init__" else ANY_ANNOTATION
+        decorator = f"{indent}@staticmethod\n" if isinstance(owner, type) and not is_method else ""
+        return f"{decorator}{indent}def {name}{rendered_parameters} -> {returns}: ...\n"
+
+    def insert(self, lineno: int, text: str) -> None:
+        """Insert text after the line."""
+        offset = self.offset(lineno + 1, 0)
+        self.edits.append((offset, offset, text))
+
+    def generate(self) -> str:
+        """Return stub with signatures from the extension."""
+        stub_classes = {node.name: node for node in self.tree.body if isinstance(node, ast.ClassDef)}
+        stub_functions = {
+            node.name: node for node in self.tree.body if isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef))
+        }
+
+        for name, runtime_object in runtime_functions(self.module).items():
+            if name in stub_functions:
+                self.update_function(stub_functions[name], runtime_object)
+            else:
+                self.appended.append(self.render_function(name, runtime_object, "", owner=self.module))
+
+        for class_name, runtime_class in runtime_classes(self.module).items():
+            class_node = stub_classes.get(class_name)
+            if class_node is None:
+                methods = "".join(
+                    self.render_function(name, runtime_object, INDENT, owner=runtime_class)
+                    for name, runtime_object in runtime_functions(runtime_class).items()
+                )
+                self.appended.append(f"class {class_name}:\n{methods or INDENT + '...' + chr(10)}")
+                continue
+
+            stub_methods = {
+                node.name: node
+                for node in class_node.body
+                if isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef))
+            }
+            for name, runtime_object in runtime_functions(runtime_class).items():
+                if name in stub_methods:
+                    self.update_function(stub_methods[name], runtime_object)
+                elif name != "__init__":
+                    method_indent = " " * (class_node.col_offset + len(INDENT))
+                    self.insert(
+                        class_node.end_lineno or class_node.lineno,
+                        self.render_function(name, runtime_object, method_indent, owner=runtime_class),
+                    )
+
+        # Edits are applied from the end, so offsets of the others stay valid,
+        # insertions at the same offset keep their order.
+        source = self.source.encode()
+        ordered_edits = sorted(enumerate(self.edits), key=lambda edit: (edit[1][0], edit[0]), reverse=True)
+        for _, (start, end, text) in ordered_edits:
+            source = source[:start] + text.encode() + source[end:]
+        generated = source.decode()
+        if self.appended:
+            generated = generated.rstrip("\n") + "\n\n" + "\n".join(self.appended)
+        if ANY_ANNOTATION in generated and not any(
+            isinstance(node, ast.Import) and any(alias.name == "typing" and alias.asname is None for alias in node.names)
+            for node in self.tree.body
+        ):
+            generated = "import typing\n" + generated
+        return generated
+
+
+def generate_stub(stub_name: str, module: types.ModuleType) -> tuple[str, str]:
+    """Return current and generated source of the stub."""
+    source = (STUBS_DIR / stub_name).read_text()
+    return source, StubGenerator(source, module).generate()
+
+
+def main() -> int:
+    """Rewrite stubs or check that they are up to date."""
+    parser = argparse.ArgumentParser(description=__doc__, formatter_class=argparse.RawDescriptionHelpFormatter)
+    parser.add_argument("--check", action="store_true", help="don't write stubs, fail if they are outdated")
+    args = parser.parse_args()
+
+    outdated = []
+    for stub_name, module in stub_modules():
+        source, generated = generate_stub(stub_name, module)
+        if source == generated:
+            continue
+        outdated.append(stub_name)
+        if not args.check:
+            (STUBS_DIR / stub_name).write_text(generated)
+
+    if outdated and args.check:
+        sys.stderr.write(f"Stubs are outdated: {', '.join(outdated)}, run `python scripts/generate_stubs.py`\n")
+        return 1
+    for stub_name in outdated:
+        sys.stdout.write(f"Stub {stub_name} is regenerated\n")
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -622,3 +622,210 @@ impl<'a> FromSql<'a> for Circle {
         true
     }
 }
+
+const NUMERIC_POS: u16 = 0x0000;
+const NUMERIC_NEG: u16 = 0x4000;
+const NUMERIC_NAN: u16 = 0xC000;
+const NUMERIC_PINF: u16 = 0xD000;
+const NUMERIC_NINF: u16 = 0xF000;
+const NUMERIC_DSCALE_MAX: i64 = 0x3FFF;
+
+/// `PostgreSQL` NUMERIC with arbitrary precision.
+///
+/// Finite value is `digits * 10 ^ exponent`, `digits` are decimal digits
+/// without leading zeros, `scale` is the number of digits after the point
+/// which is kept on the server, e.g. `1.50` has scale 2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PgNumeric {
+    NaN,
+    Infinity {
+        negative: bool,
+    },
+    Finite {
+        negative: bool,
+        digits: Vec<u8>,
+        exponent: i64,
+        scale: u16,
+    },
+}
+
+impl PgNumeric {
+    /// Build numeric from the parts of `decimal.Decimal.as_tuple()`.
+    ///
+    /// # Errors
+    /// May return Err if value doesn't fit into `PostgreSQL` NUMERIC.
+    pub fn from_parts(
+        negative: bool,
+        digits: &[u8],
+        exponent: i64,
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        let digits: Vec<u8> = digits
+            .iter()
+            .copied()
+            .skip_while(|digit| *digit == 0)
+            .collect();
+        let scale = (-exponent).max(0);
+        if scale > NUMERIC_DSCALE_MAX {
+            return Err(
+                format!("NUMERIC scale {scale} is greater than {NUMERIC_DSCALE_MAX}").into(),
+            );
+        }
+        Ok(PgNumeric::Finite {
+            negative: negative && !digits.is_empty(),
+            digits,
+            exponent,
+            scale: u16::try_from(scale)?,
+        })
+    }
+}
+
+impl std::fmt::Display for PgNumeric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (negative, digits, exponent) = match self {
+            PgNumeric::NaN => return write!(f, "NaN"),
+            PgNumeric::Infinity { negative: false } => return write!(f, "Infinity"),
+            PgNumeric::Infinity { negative: true } => return write!(f, "-Infinity"),
+            PgNumeric::Finite {
+                negative,
+                digits,
+                exponent,
+                ..
+            } => (negative, digits, exponent),
+        };
+        if *negative {
+            write!(f, "-")?;
+        }
+        let digits: String = if digits.is_empty() {
+            "0".into()
+        } else {
+            digits
+                .iter()
+                .map(|digit| char::from(b'0' + digit))
+                .collect()
+        };
+        // Scientific notation is parsed by `decimal.Decimal` exactly.
+        write!(f, "{digits}E{exponent}")
+    }
+}
+
+impl ToSql for PgNumeric {
+    fn to_sql(
+        &self,
+        _ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        let no_digits = vec![];
+        let (sign, digits, exponent, scale) = match self {
+            PgNumeric::NaN => (NUMERIC_NAN, &no_digits, 0, 0),
+            PgNumeric::Infinity { negative: false } => (NUMERIC_PINF, &no_digits, 0, 0),
+            PgNumeric::Infinity { negative: true } => (NUMERIC_NINF, &no_digits, 0, 0),
+            PgNumeric::Finite {
+                negative,
+                digits,
+                exponent,
+                scale,
+            } => (
+                if *negative { NUMERIC_NEG } else { NUMERIC_POS },
+                digits,
+                *exponent,
+                *scale,
+            ),
+        };
+
+        // Digits are grouped by 4 starting from the decimal point,
+        // group `g` holds multiplier of `10000 ^ g`.
+        let mut groups: Vec<i16> = vec![];
+        let mut weight = 0i16;
+        if !digits.is_empty() {
+            let last_power = exponent;
+            let first_power = exponent + i64::try_from(digits.len())? - 1;
+            weight = i16::try_from(first_power.div_euclid(4))
+                .map_err(|_| "NUMERIC value is out of range")?;
+            let last_group = last_power.div_euclid(4);
+            groups = vec![0; usize::try_from(i64::from(weight) - last_group + 1)?];
+            for (position, digit) in digits.iter().rev().enumerate() {
+                let power = last_power + i64::try_from(position)?;
+                let group = usize::try_from(i64::from(weight) - power.div_euclid(4))?;
+                groups[group] += i16::from(*digit) * 10i16.pow(u32::try_from(power.rem_euclid(4))?);
+            }
+            while groups.last() == Some(&0) {
+                groups.pop();
+            }
+        }
+
+        out.put_i16(i16::try_from(groups.len())?);
+        out.put_i16(weight);
+        out.put_u16(sign);
+        out.put_u16(scale);
+        for group in groups {
+            out.put_i16(group);
+        }
+        Ok(IsNull::No)
+    }
+
+    to_sql_checked!();
+
+    fn accepts(_ty: &Type) -> bool {
+        true
+    }
+}
+
+impl<'a> FromSql<'a> for PgNumeric {
+    fn from_sql(
+        _ty: &Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        let mut buf = raw;
+        let groups_count = buf.read_i16::<BigEndian>()?;
+        let weight = i64::from(buf.read_i16::<BigEndian>()?);
+        let sign = buf.read_u16::<BigEndian>()?;
+        let scale = buf.read_u16::<BigEndian>()?;
+
+        let negative = match sign {
+            NUMERIC_POS => false,
+            NUMERIC_NEG => true,
+            NUMERIC_NAN => return Ok(PgNumeric::NaN),
+            NUMERIC_PINF => return Ok(PgNumeric::Infinity { negative: false }),
+            NUMERIC_NINF => return Ok(PgNumeric::Infinity { negative: true }),
+            _ => return Err("Cannot convert PostgreSQL NUMERIC, unknown sign".into()),
+        };
+
+        let mut digits = Vec::with_capacity(usize::try_from(groups_count)? * 4);
+        for _ in 0..groups_count {
+            let group = buf.read_i16::<BigEndian>()?;
+            if !(0..10000).contains(&group) {
+                return Err("Cannot convert PostgreSQL NUMERIC, invalid digit".into());
+            }
+            for power in [1000, 100, 10, 1] {
+                digits.push(u8::try_from(group / power % 10)?);
+            }
+        }
+        if !buf.is_empty() {
+            return Err("Cannot convert PostgreSQL NUMERIC into rust PgNumeric".into());
+        }
+
+        // Value is `digits * 10 ^ exponent`, digits after the scale are zeros,
+        // so the exponent is moved to keep exactly `scale` digits.
+        let mut exponent = (weight - i64::from(groups_count) + 1) * 4;
+        let scale_exponent = -i64::from(scale);
+        while exponent < scale_exponent && digits.last() == Some(&0) {
+            digits.pop();
+            exponent += 1;
+        }
+        if digits.is_empty() {
+            exponent = scale_exponent;
+        } else if exponent > scale_exponent {
+            digits.resize(
+                digits.len() + usize::try_from(exponent - scale_exponent)?,
+                0,
+            );
+            exponent = scale_exponent;
+        }
+
+        PgNumeric::from_parts(negative, &digits, exponent)
+    }
+
+    fn accepts(_ty: &Type) -> bool {
+        true
+    }
+}
@@ -0,0 +1,119 @@
+use std::{collections::HashMap, sync::Arc};
+
+use pyo3::{pyclass, pymethods, Py, PyAny, Python};
+
+use crate::exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult};
+
+use super::inner_connection::PsqlpyConnection;
+
+pub const DEFAULT_ANNOTATION_SETTING: &str = "application_name";
+
+/// Return value of the setting for the block.
+///
+/// Tag is appended to `application_name`, so the application
+/// is still recognizable in `pg_stat_activity`,
+/// other settings are replaced with the tag.
+fn annotated_value(setting: &str, previous_value: Option<&str>, tag: &str) -> String {
+    match previous_value {
+        Some(previous_value)
+            if setting == DEFAULT_ANNOTATION_SETTING && !previous_value.is_empty() =>
+        {
+            format!("{previous_value} [{tag}]")
+        }
+        _ => tag.to_string(),
+    }
+}
+
+/// Async context manager which tags the connection
+/// for the duration of the block.
+///
+/// Previous value of the setting is restored on exit,
+/// even if the block raises.
+#[pyclass]
+pub struct ConnectionAnnotation {
+    db_client: Option<Arc<PsqlpyConnection>>,
+    setting: String,
+    tag: String,
+    previous_value: Option<Option<String>>,
+}
+
+impl ConnectionAnnotation {
+    #[must_use]
+    pub fn new(db_client: Arc<PsqlpyConnection>, setting: String, tag: String) -> Self {
+        ConnectionAnnotation {
+            db_client: Some(db_client),
+            setting,
+            tag,
+            previous_value: None,
+        }
+    }
+}
+
+#[pymethods]
+impl ConnectionAnnotation {
+    #[getter]
+    fn setting(&self) -> String {
+        self.setting.clone()
+    }
+
+    #[getter]
+    fn tag(&self) -> String {
+        self.tag.clone()
+    }
+
+    async fn __aenter__<'a>(self_: Py<Self>) -> RustPSQLDriverPyResult<Py<Self>> {
+        let (db_client, setting, tag) = Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (
+                self_.db_client.clone(),
+                self_.setting.clone(),
+                self_.tag.clone(),
+            )
+        });
+        let Some(db_client) = db_client else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+
+        let previous_value = db_client
+            .capture_settings(vec![setting.clone()])
+            .await?
+            .remove(&setting)
+            .flatten();
+        let value = annotated_value(&setting, previous_value.as_deref(), &tag);
+        db_client
+            .restore_settings(HashMap::from([(setting, Some(value))]))
+            .await?;
+
+        Python::with_gil(|gil| {
+            self_.borrow_mut(gil).previous_value = Some(previous_value);
+        });
+        Ok(self_)
+    }
+
+    async fn __aexit__<'a>(
+        self_: Py<Self>,
+        _exception_type: Py<PyAny>,
+        _exception: Py<PyAny>,
+        _traceback: Py<PyAny>,
+    ) -> RustPSQLDriverPyResult<()> {
+        let (db_client, setting, previous_value) = Python::with_gil(|gil| {
+            let mut self_ = self_.borrow_mut(gil);
+            (
+                self_.db_client.take(),
+                self_.setting.clone(),
+                self_.previous_value.take(),
+            )
+        });
+        let (Some(db_client), Some(previous_value)) = (db_client, previous_value) else {
+            return Ok(());
+        };
+
+        // Setting which didn't exist before is left empty.
+        db_client
+            .restore_settings(HashMap::from([(
+                setting,
+                Some(previous_value.unwrap_or_default()),
+            )]))
+            .await
+    }
+}
@@ -0,0 +1,181 @@
+use crate::exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult};
+
+use super::inner_connection::PsqlpyConnection;
+
+/// Signature in the beginning of the binary `COPY` data.
+const BINARY_COPY_SIGNATURE: &[u8] = b"PGCOPY\n\xff\r\n\0";
+
+/// Flag of the header that every row has OID.
+const OIDS_FLAG: u32 = 1 << 16;
+
+/// Bits 17-31 of the header flags are critical,
+/// PostgreSQL rejects data with unknown critical bits.
+const CRITICAL_FLAGS_MASK: u32 = 0xFFFE_0000;
+
+/// Number of the columns filled by `COPY` without the column list.
+///
+/// Generated columns cannot be copied, so they aren't counted.
+/// `to_regclass` returns NULL if table doesn't exist.
+const TABLE_COPY_COLUMNS_QUERY: &str = "SELECT to_regclass($1) IS NOT NULL, \
+     (SELECT count(*) FROM pg_attribute \
+     WHERE attrelid = to_regclass($1) AND attnum > 0 \
+     AND NOT attisdropped AND attgenerated = '')";
+
+fn copy_data_error(message: &str) -> RustPSQLDriverError {
+    RustPSQLDriverError::PyToRustValueConversionError(format!(
+        "Invalid binary COPY data: {message}"
+    ))
+}
+
+/// Reader of the binary `COPY` data which knows current position.
+struct CopyDataReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl CopyDataReader<'_> {
+    fn take(&mut self, len: usize, what: &str) -> RustPSQLDriverPyResult<&[u8]> {
+        let left = self.data.len() - self.position;
+        if left < len {
+            return Err(copy_data_error(&format!(
+                "{what} at byte {} needs {len} bytes, only {left} left",
+                self.position
+            )));
+        }
+        let bytes = &self.data[self.position..self.position + len];
+        self.position += len;
+        Ok(bytes)
+    }
+
+    fn read_i16(&mut self, what: &str) -> RustPSQLDriverPyResult<i16> {
+        let bytes = self.take(2, what)?;
+        Ok(i16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_i32(&mut self, what: &str) -> RustPSQLDriverPyResult<i32> {
+        let bytes = self.take(4, what)?;
+        Ok(i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+/// Check header, trailer and rows of the binary `COPY` data.
+///
+/// Every row must have `columns_number` fields.
+/// Returns number of the rows.
+///
+/// # Errors
+/// May return Err Result if:
+/// 1) Signature or header is invalid
+/// 2) Row has wrong number of fields
+/// 3) Field is truncated or has invalid length
+/// 4) Trailer is missing or there is data after it
+#[allow(clippy::cast_sign_loss)]
+pub fn validate_binary_copy_data(
+    data: &[u8],
+    columns_number: usize,
+) -> RustPSQLDriverPyResult<u64> {
+    if !data.starts_with(BINARY_COPY_SIGNATURE) {
+        return Err(copy_data_error(
+            "data doesn't start with PGCOPY signature, use FORMAT binary to produce it",
+        ));
+    }
+    let mut reader = CopyDataReader {
+        data,
+        position: BINARY_COPY_SIGNATURE.len(),
+    };
+
+    let flags = reader.read_i32("header flags")? as u32;
+    if flags & OIDS_FLAG != 0 {
+        return Err(copy_data_error("data with OIDs isn't supported"));
+    }
+    if flags & CRITICAL_FLAGS_MASK != 0 {
+        return Err(copy_data_error(&format!(
+            "header flags {flags:#010x} have unknown critical bits"
+        )));
+    }
+    let extension_len = reader.read_i32("header extension length")?;
+    if extension_len < 0 {
+        return Err(copy_data_error(&format!(
+            "header extension length {extension_len} is negative"
+        )));
+    }
+    reader.take(extension_len as usize, "header extension")?;
+
+    let mut rows_number: u64 = 0;
+    loop {
+        let row_position = reader.position;
+        if row_position == data.len() {
+            return Err(copy_data_error(&format!(
+                "trailer is missing, data ends after {rows_number} rows"
+            )));
+        }
+        let fields_number = reader.read_i16("row fields number")?;
+        if fields_number == -1 {
+            break;
+        }
+        rows_number += 1;
+        if fields_number < 0 || fields_number as usize != columns_number {
+            return Err(copy_data_error(&format!(
+                "row {rows_number} at byte {row_position} has {fields_number} fields, \
+                 {columns_number} columns are expected"
+            )));
+        }
+        for field_number in 1..=fields_number {
+            let field_len = reader.read_i32("field length")?;
+            if field_len == -1 {
+                continue;
+            }
+            if field_len < 0 {
+                return Err(copy_data_error(&format!(
+                    "field {field_number} of row {rows_number} has invalid length {field_len}"
+                )));
+            }
+            reader.take(
+                field_len as usize,
+                &format!("field {field_number} of row {rows_number}"),
+            )?;
+        }
+    }
+
+    let extra_bytes = data.len() - reader.position;
+    if extra_bytes > 0 {
+        return Err(copy_data_error(&format!(
+            "{extra_bytes} bytes after the trailer at byte {}",
+            reader.position - 2
+        )));
+    }
+    Ok(rows_number)
+}
+
+/// Check binary `COPY` data against the columns of the table.
+///
+/// `table` must be already quoted, all columns except generated
+/// are expected if `columns` are None.
+///
+/// # Errors
+/// May return Err Result if:
+/// 1) Table doesn't exist
+/// 2) Cannot query the catalog
+/// 3) Data is invalid
+pub async fn validate_binary_copy(
+    db_client: &PsqlpyConnection,
+    table: &str,
+    columns: Option<&[String]>,
+    data: &[u8],
+) -> RustPSQLDriverPyResult<u64> {
+    let columns_number = match columns {
+        Some(columns) => columns.len(),
+        None => {
+            let row = db_client
+                .query_one(TABLE_COPY_COLUMNS_QUERY, &[&table])
+                .await?;
+            let table_exists: bool = row.try_get(0)?;
+            if !table_exists {
+                return Err(copy_data_error(&format!("table {table} doesn't exist")));
+            }
+            let columns_number: i64 = row.try_get(1)?;
+            usize::try_from(columns_number).unwrap_or_default()
+        }
+    };
+    validate_binary_copy_data(data, columns_number)
+}
@@ -0,0 +1,389 @@
+use pyo3::{
+    buffer::{Element, PyBuffer},
+    types::{PyAnyMethods, PyDict, PyDictMethods},
+    Bound, Py, PyAny, Python,
+};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use tokio_postgres::{types::Type, Row};
+
+use crate::exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult};
+
+/// Numeric value of the column decoded from the row.
+#[derive(Clone, Copy)]
+enum NumericValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+}
+
+/// Decode numeric value of the column without creating Python objects.
+///
+/// # Errors
+/// May return Err Result if column has non-numeric type.
+fn read_numeric(row: &Row, column_idx: usize) -> RustPSQLDriverPyResult<Option<NumericValue>> {
+    let column = &row.columns()[column_idx];
+    let value = match *column.type_() {
+        Type::BOOL => row
+            .try_get::<_, Option<bool>>(column_idx)?
+            .map(NumericValue::Bool),
+        Type::INT2 => row
+            .try_get::<_, Option<i16>>(column_idx)?
+            .map(|value| NumericValue::Int(value.into())),
+        Type::INT4 => row
+            .try_get::<_, Option<i32>>(column_idx)?
+            .map(|value| NumericValue::Int(value.into())),
+        Type::INT8 => row
+            .try_get::<_, Option<i64>>(column_idx)?
+            .map(NumericValue::Int),
+        Type::FLOAT4 => row
+            .try_get::<_, Option<f32>>(column_idx)?
+            .map(|value| NumericValue::Float(value.into())),
+        Type::FLOAT8 => row
+            .try_get::<_, Option<f64>>(column_idx)?
+            .map(NumericValue::Float),
+        Type::NUMERIC => row
+            .try_get::<_, Option<Decimal>>(column_idx)?
+            .map(|value| NumericValue::Float(value.to_f64().unwrap_or(f64::NAN))),
+        _ => {
+            return Err(RustPSQLDriverError::RustToPyValueConversionError(format!(
+                "Column {} has non-numeric type {}, it cannot be fetched into buffer",
+                column.name(),
+                column.type_(),
+            )))
+        }
+    };
+    Ok(value)
+}
+
+/// Element of the buffer which can be filled with numeric values.
+trait BufferElement: Element + Copy {
+    /// Convert numeric value into buffer element.
+    ///
+    /// # Errors
+    /// May return Err Result if value doesn't fit into the element.
+    fn from_numeric(value: NumericValue) -> RustPSQLDriverPyResult<Self>;
+
+    /// Value for the `NULL`, None if `NULL` isn't supported.
+    fn null() -> Option<Self> {
+        None
+    }
+}
+
+fn value_not_fit_error(type_name: &str) -> RustPSQLDriverError {
+    RustPSQLDriverError::RustToPyValueConversionError(format!(
+        "Value cannot be written into {type_name} buffer"
+    ))
+}
+
+macro_rules! impl_float_element {
+    ($type:ty) => {
+        impl BufferElement for $type {
+            #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+            fn from_numeric(value: NumericValue) -> RustPSQLDriverPyResult<Self> {
+                Ok(match value {
+                    NumericValue::Bool(value) => <$type>::from(u8::from(value)),
+                    NumericValue::Int(value) => value as $type,
+                    NumericValue::Float(value) => value as $type,
+                })
+            }
+
+            fn null() -> Option<Self> {
+                Some(<$type>::NAN)
+            }
+        }
+    };
+}
+
+macro_rules! impl_int_element {
+    ($type:ty) => {
+        impl BufferElement for $type {
+            fn from_numeric(value: NumericValue) -> RustPSQLDriverPyResult<Self> {
+                match value {
+                    NumericValue::Bool(value) => Ok(<$type>::from(u8::from(value))),
+                    NumericValue::Int(value) => {
+                        <$type>::try_from(value).map_err(|_| value_not_fit_error(stringify!($type)))
+                    }
+                    NumericValue::Float(_) => Err(value_not_fit_error(stringify!($type))),
+                }
+            }
+        }
+    };
+}
+
+impl_float_element!(f64);
+impl_float_element!(f32);
+impl_int_element!(i64);
+impl_int_element!(i32);
+impl_int_element!(i16);
+
+impl BufferElement for bool {
+    fn from_numeric(value: NumericValue) -> RustPSQLDriverPyResult<Self> {
+        match value {
+            NumericValue::Bool(value) => Ok(value),
+            _ => Err(value_not_fit_error("bool")),
+        }
+    }
+}
+
+/// Write values of the column into the buffer, row by row.
+///
+/// # Errors
+/// May return Err Result if:
+/// 1) Buffer isn't writable or C-contiguous
+/// 2) Buffer is smaller than number of rows
+/// 3) Value cannot be written into the buffer
+fn fill_buffer<T: BufferElement>(
+    py: Python<'_>,
+    buffer: &PyBuffer<T>,
+    rows: &[Row],
+    column_idx: usize,
+) -> RustPSQLDriverPyResult<()> {
+    let Some(cells) = buffer.as_mut_slice(py) else {
+        return Err(RustPSQLDriverError::RustToPyValueConversionError(
+            "Buffer must be writable and C-contiguous".into(),
+        ));
+    };
+    if cells.len() < rows.len() {
+        return Err(RustPSQLDriverError::RustToPyValueConversionError(format!(
+            "Buffer has {} items, but query returned {} rows",
+            cells.len(),
+            rows.len(),
+        )));
+    }
+
+    for (cell, row) in cells.iter().zip(rows) {
+        let value = match read_numeric(row, column_idx)? {
+            Some(value) => T::from_numeric(value)?,
+            None => T::null().ok_or_else(|| {
+                RustPSQLDriverError::RustToPyValueConversionError(format!(
+                    "Column {} has NULL value, it can be fetched only into float buffer",
+                    row.columns()[column_idx].name(),
+                ))
+            })?,
+        };
+        cell.set(value);
+    }
+    Ok(())
+}
+
+/// Write values of the column into buffer with any supported element type.
+///
+/// # Errors
+/// May return Err Result if buffer has unsupported format or cannot be filled.
+fn fill_column(
+    py: Python<'_>,
+    target: &Bound<'_, PyAny>,
+    rows: &[Row],
+    column_idx: usize,
+) -> RustPSQLDriverPyResult<()> {
+    if let Ok(buffer) = PyBuffer::<f64>::get(target) {
+        return fill_buffer(py, &buffer, rows, column_idx);
+    }
+    if let Ok(buffer) = PyBuffer::<f32>::get(target) {
+        return fill_buffer(py, &buffer, rows, column_idx);
+    }
+    if let Ok(buffer) = PyBuffer::<i64>::get(target) {
+        return fill_buffer(py, &buffer, rows, column_idx);
+    }
+    if let Ok(buffer) = PyBuffer::<i32>::get(target) {
+        return fill_buffer(py, &buffer, rows, column_idx);
+    }
+    if let Ok(buffer) = PyBuffer::<i16>::get(target) {
+        return fill_buffer(py, &buffer, rows, column_idx);
+    }
+    if let Ok(buffer) = PyBuffer::<bool>::get(target) {
+        return fill_buffer(py, &buffer, rows, column_idx);
+    }
+
+    Err(RustPSQLDriverError::PyToRustValueConversionError(
+        "Output must support buffer protocol with float64, float32, int64, int32, int16 or bool items"
+            .into(),
+    ))
+}
+
+/// Write numeric columns of the rows into caller-provided buffers.
+///
+/// `out` is either a dict with column names as keys
+/// or a sequence of buffers in the column order,
+/// `None` instead of the buffer skips the column in both cases.
+/// Returns number of written rows.
+///
+/// # Errors
+/// May return Err Result if:
+/// 1) `out` is neither dict nor sequence
+/// 2) Column from `out` doesn't exist in the result
+/// 3) Any buffer cannot be filled
+pub fn fill_column_buffers(
+    py: Python<'_>,
+    rows: &[Row],
+    out: &Bound<'_, PyAny>,
+) -> RustPSQLDriverPyResult<usize> {
+    let Some(first_row) = rows.first() else {
+        return Ok(0);
+    };
+    let columns = first_row.columns();
+
+    if let Ok(out) = out.downcast::<PyDict>() {
+        for (column_name, target) in out.iter() {
+            if target.is_none() {
+                continue;
+            }
+            let column_name = column_name.extract::<String>()?;
+            let Some(column_idx) = columns
+                .iter()
+                .position(|column| column.name() == column_name)
+            else {
+                return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                    "Column {column_name} doesn't exist in the result"
+                )));
+            };
+            fill_column(py, &target, rows, column_idx)?;
+        }
+        return Ok(rows.len());
+    }
+
+    for (column_idx, target) in out.try_iter()?.enumerate() {
+        let target = target?;
+        if target.is_none() {
+            continue;
+        }
+        if column_idx >= columns.len() {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "Query returned {} columns, but more buffers are passed",
+                columns.len(),
+            )));
+        }
+        fill_column(py, &target, rows, column_idx)?;
+    }
+    Ok(rows.len())
+}
+
+/// Default numpy dtype for the numeric column.
+fn numeric_dtype(column_type: &Type) -> Option<&'static str> {
+    match *column_type {
+        Type::BOOL => Some("bool"),
+        Type::INT2 => Some("int16"),
+        Type::INT4 => Some("int32"),
+        Type::INT8 => Some("int64"),
+        Type::FLOAT4 => Some("float32"),
+        Type::FLOAT8 | Type::NUMERIC => Some("float64"),
+        _ => None,
+    }
+}
+
+fn is_text_type(column_type: &Type) -> bool {
+    matches!(
+        *column_type,
+        Type::TEXT | Type::VARCHAR | Type::BPCHAR | Type::NAME
+    )
+}
+
+/// Write text column into numpy array of fixed-width unicode strings.
+///
+/// Array is viewed as `uint32` code points and filled directly,
+/// so there is no Python string per value.
+///
+/// # Errors
+/// May return Err Result if column has `NULL` value or array cannot be created.
+fn text_column_array<'py>(
+    py: Python<'py>,
+    numpy: &Bound<'py, PyAny>,
+    rows: &[Row],
+) -> RustPSQLDriverPyResult<Bound<'py, PyAny>> {
+    let mut values = Vec::with_capacity(rows.len());
+    for row in rows {
+        let Some(value) = row.try_get::<_, Option<&str>>(0)? else {
+            return Err(RustPSQLDriverError::RustToPyValueConversionError(format!(
+                "Column {} has NULL value, it cannot be fetched into text array",
+                row.columns()[0].name(),
+            )));
+        };
+        values.push(value);
+    }
+    let width = values
+        .iter()
+        .map(|value| value.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    let array = numpy.call_method1("empty", (rows.len(), format!("<U{width}")))?;
+    let code_points = array.call_method1("view", ("<u4",))?;
+    let buffer = PyBuffer::<u32>::get(&code_points)?;
+    let Some(cells) = buffer.as_mut_slice(py) else {
+        return Err(RustPSQLDriverError::RustToPyValueConversionError(
+            "Cannot write into text array".into(),
+        ));
+    };
+
+    for (row_cells, value) in cells.chunks(width).zip(values) {
+        let mut chars = value.chars();
+        for cell in row_cells {
+            cell.set(chars.next().map_or(0, u32::from));
+        }
+    }
+    Ok(array)
+}
+
+/// Return the only column of the rows as numpy array.
+///
+/// Numeric columns are decoded directly into the array,
+/// `dtype` defaults to the closest type of the column.
+/// Text columns are returned as array of fixed-width unicode strings,
+/// it's converted to `dtype` if it's passed.
+///
+/// # Errors
+/// May return Err Result if:
+/// 1) numpy cannot be imported
+/// 2) Rows have more than one column
+/// 3) Column has unsupported type or cannot be written into the array
+#[allow(clippy::needless_pass_by_value)]
+pub fn fetch_column_array(
+    py: Python<'_>,
+    rows: &[Row],
+    dtype: Option<Py<PyAny>>,
+) -> RustPSQLDriverPyResult<Py<PyAny>> {
+    let numpy = py.import("numpy").map_err(|err| {
+        RustPSQLDriverError::RustToPyValueConversionError(format!(
+            "numpy is required to fetch column as array, error - {err}"
+        ))
+    })?;
+
+    let Some(first_row) = rows.first() else {
+        let array = match &dtype {
+            Some(dtype) => numpy.call_method1("empty", (0, dtype))?,
+            None => numpy.call_method1("empty", (0, "float64"))?,
+        };
+        return Ok(array.unbind());
+    };
+    let columns = first_row.columns();
+    if columns.len() != 1 {
+        return Err(RustPSQLDriverError::RustToPyValueConversionError(format!(
+            "Query must return exactly one column, but it returned {} columns",
+            columns.len(),
+        )));
+    }
+    let column_type = columns[0].type_();
+
+    if is_text_type(column_type) {
+        let array = text_column_array(py, numpy.as_any(), rows)?;
+        return match dtype {
+            Some(dtype) => Ok(array.call_method1("astype", (dtype,))?.unbind()),
+            None => Ok(array.unbind()),
+        };
+    }
+
+    let array = match (dtype, numeric_dtype(column_type)) {
+        (Some(dtype), _) => numpy.call_method1("empty", (rows.len(), dtype))?,
+        (None, Some(dtype)) => numpy.call_method1("empty", (rows.len(), dtype))?,
+        (None, None) => {
+            return Err(RustPSQLDriverError::RustToPyValueConversionError(format!(
+                "Column {} has type {}, it cannot be fetched into array",
+                columns[0].name(),
+                column_type,
+            )))
+        }
+    };
+    fill_column(py, &array, rows, 0)?;
+    Ok(array.unbind())
+}
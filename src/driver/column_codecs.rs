@@ -0,0 +1,533 @@
+use std::{collections::HashMap, sync::Arc};
+
+use openssl::{
+    rand::rand_bytes,
+    symm::{decrypt_aead, encrypt_aead, Cipher},
+};
+use pyo3::{
+    pyclass, pymethods,
+    types::{PyAnyMethods, PyBytes, PyDict, PyDictMethods, PyList, PyString},
+    Bound, IntoPy, Py, PyAny, Python,
+};
+use tokio_postgres::Row;
+
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    format_helpers::{fold_identifier, guess_parameter_columns, query_words, ParameterUse},
+};
+
+/// Version of the envelope written by the AES-GCM codec.
+const ENVELOPE_VERSION: u8 = 1;
+const AES_KEY_LEN: usize = 32;
+const AES_NONCE_LEN: usize = 12;
+const AES_TAG_LEN: usize = 16;
+
+/// Type of the encrypted value, it's restored on decryption.
+const BYTES_VALUE: u8 = 0;
+const STR_VALUE: u8 = 1;
+
+enum CodecKind {
+    /// Python callables which encrypt and decrypt the value.
+    Callables {
+        encrypt: Py<PyAny>,
+        decrypt: Py<PyAny>,
+    },
+    /// AES-256-GCM with the keys from the Python key provider.
+    AesGcm { key_provider: Py<PyAny> },
+}
+
+/// Encrypts values of the column on the way to the database
+/// and decrypts them in the results.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct ColumnCodec {
+    kind: Arc<CodecKind>,
+}
+
+impl ColumnCodec {
+    /// Check that the same value is always encrypted the same way,
+    /// so encrypted parameters can be compared with the stored values.
+    ///
+    /// Callables are expected to be deterministic,
+    /// AES-GCM uses random nonce for every value.
+    fn is_deterministic(&self) -> bool {
+        matches!(self.kind.as_ref(), CodecKind::Callables { .. })
+    }
+
+    /// Call key provider and check the key.
+    fn provide_key(
+        py: Python<'_>,
+        key_provider: &Py<PyAny>,
+        key_id: Option<&str>,
+    ) -> RustPSQLDriverPyResult<(String, Vec<u8>)> {
+        let (provided_id, key) = key_provider
+            .call1(py, (key_id,))?
+            .extract::<(String, Vec<u8>)>(py)
+            .map_err(|err| {
+                RustPSQLDriverError::PyToRustValueConversionError(format!(
+                    "Key provider must return tuple of key id and bytes, error - {err}"
+                ))
+            })?;
+        if key.len() != AES_KEY_LEN {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "AES-GCM key must be {AES_KEY_LEN} bytes, key {provided_id} has {} bytes",
+                key.len()
+            )));
+        }
+        Ok((provided_id, key))
+    }
+
+    /// Encrypt value into envelope.
+    ///
+    /// Envelope is `version | key id length | key id | value type | nonce | ciphertext | tag`,
+    /// everything before the nonce is authenticated too.
+    fn aes_gcm_encrypt(
+        py: Python<'_>,
+        key_provider: &Py<PyAny>,
+        value: &Bound<'_, PyAny>,
+    ) -> RustPSQLDriverPyResult<Vec<u8>> {
+        let (value_type, plaintext) = if value.is_instance_of::<PyString>() {
+            (STR_VALUE, value.extract::<String>()?.into_bytes())
+        } else if value.is_instance_of::<PyBytes>() {
+            (BYTES_VALUE, value.extract::<Vec<u8>>()?)
+        } else {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(
+                "AES-GCM codec encrypts only str and bytes".into(),
+            ));
+        };
+        let (key_id, key) = Self::provide_key(py, key_provider, None)?;
+        let key_id_len = u8::try_from(key_id.len()).map_err(|_| {
+            RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "Key id must be at most {} bytes",
+                u8::MAX
+            ))
+        })?;
+
+        let mut envelope =
+            Vec::with_capacity(3 + key_id.len() + AES_NONCE_LEN + plaintext.len() + AES_TAG_LEN);
+        envelope.push(ENVELOPE_VERSION);
+        envelope.push(key_id_len);
+        envelope.extend_from_slice(key_id.as_bytes());
+        envelope.push(value_type);
+
+        let map_err = |err| {
+            RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "Cannot encrypt value, error - {err}"
+            ))
+        };
+        let mut nonce = [0_u8; AES_NONCE_LEN];
+        rand_bytes(&mut nonce).map_err(map_err)?;
+        let mut tag = [0_u8; AES_TAG_LEN];
+        let ciphertext = encrypt_aead(
+            Cipher::aes_256_gcm(),
+            &key,
+            Some(&nonce),
+            &envelope,
+            &plaintext,
+            &mut tag,
+        )
+        .map_err(map_err)?;
+
+        envelope.extend_from_slice(&nonce);
+        envelope.extend_from_slice(&ciphertext);
+        envelope.extend_from_slice(&tag);
+        Ok(envelope)
+    }
+
+    fn aes_gcm_decrypt(
+        py: Python<'_>,
+        key_provider: &Py<PyAny>,
+        envelope: &[u8],
+    ) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        let invalid_envelope = || {
+            RustPSQLDriverError::RustToPyValueConversionError(
+                "Value isn't encrypted by AES-GCM codec".into(),
+            )
+        };
+        let [version, key_id_len, rest @ ..] = envelope else {
+            return Err(invalid_envelope());
+        };
+        let key_id_len = usize::from(*key_id_len);
+        if *version != ENVELOPE_VERSION || rest.len() < key_id_len + 1 + AES_NONCE_LEN + AES_TAG_LEN
+        {
+            return Err(invalid_envelope());
+        }
+        let header_len = 3 + key_id_len;
+        let key_id = std::str::from_utf8(&rest[..key_id_len]).map_err(|_| invalid_envelope())?;
+        let value_type = rest[key_id_len];
+        let (nonce, sealed) = envelope[header_len..].split_at(AES_NONCE_LEN);
+        let (ciphertext, tag) = sealed.split_at(sealed.len() - AES_TAG_LEN);
+
+        let (_, key) = Self::provide_key(py, key_provider, Some(key_id))?;
+        let plaintext = decrypt_aead(
+            Cipher::aes_256_gcm(),
+            &key,
+            Some(nonce),
+            &envelope[..header_len],
+            ciphertext,
+            tag,
+        )
+        .map_err(|err| {
+            RustPSQLDriverError::RustToPyValueConversionError(format!(
+                "Cannot decrypt value with key {key_id}, error - {err}"
+            ))
+        })?;
+
+        if value_type == STR_VALUE {
+            let plaintext = String::from_utf8(plaintext).map_err(|_| invalid_envelope())?;
+            return Ok(plaintext.into_py(py));
+        }
+        Ok(PyBytes::new(py, &plaintext).into_any().unbind())
+    }
+}
+
+#[pymethods]
+impl ColumnCodec {
+    /// Create codec from Python callables.
+    ///
+    /// # Errors
+    /// May return Err Result if `encrypt` or `decrypt` is not callable.
+    #[new]
+    fn new(py: Python<'_>, encrypt: Py<PyAny>, decrypt: Py<PyAny>) -> RustPSQLDriverPyResult<Self> {
+        if !encrypt.bind(py).is_callable() || !decrypt.bind(py).is_callable() {
+            return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                "encrypt and decrypt must be callables".into(),
+            ));
+        }
+        Ok(ColumnCodec {
+            kind: Arc::new(CodecKind::Callables { encrypt, decrypt }),
+        })
+    }
+
+    /// Create AES-256-GCM codec.
+    ///
+    /// `key_provider` is called with None to get id and key
+    /// for encryption and with the key id stored in the value
+    /// to get the key for decryption, so keys can be rotated.
+    ///
+    /// # Errors
+    /// May return Err Result if `key_provider` is not callable.
+    #[staticmethod]
+    fn aes_gcm(py: Python<'_>, key_provider: Py<PyAny>) -> RustPSQLDriverPyResult<Self> {
+        if !key_provider.bind(py).is_callable() {
+            return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                "key_provider must be a callable".into(),
+            ));
+        }
+        Ok(ColumnCodec {
+            kind: Arc::new(CodecKind::AesGcm { key_provider }),
+        })
+    }
+
+    /// Encrypt value, None stays None.
+    ///
+    /// # Errors
+    /// May return Err Result if value cannot be encrypted.
+    pub fn encrypt(&self, py: Python<'_>, value: Py<PyAny>) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        if value.is_none(py) {
+            return Ok(value);
+        }
+        match self.kind.as_ref() {
+            CodecKind::Callables { encrypt, .. } => Ok(encrypt.call1(py, (value,))?),
+            CodecKind::AesGcm { key_provider } => {
+                let envelope = Self::aes_gcm_encrypt(py, key_provider, value.bind(py))?;
+                Ok(PyBytes::new(py, &envelope).into_any().unbind())
+            }
+        }
+    }
+
+    /// Decrypt value, None stays None.
+    ///
+    /// # Errors
+    /// May return Err Result if value cannot be decrypted.
+    pub fn decrypt(&self, py: Python<'_>, value: Py<PyAny>) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        if value.is_none(py) {
+            return Ok(value);
+        }
+        match self.kind.as_ref() {
+            CodecKind::Callables { decrypt, .. } => Ok(decrypt.call1(py, (value,))?),
+            CodecKind::AesGcm { key_provider } => {
+                let envelope = value.extract::<Vec<u8>>(py).map_err(|_| {
+                    RustPSQLDriverError::RustToPyValueConversionError(
+                        "AES-GCM codec decrypts only bytes".into(),
+                    )
+                })?;
+                Self::aes_gcm_decrypt(py, key_provider, &envelope)
+            }
+        }
+    }
+}
+
+/// Codecs of the columns by the column names.
+///
+/// Names are folded like `PostgreSQL` identifiers:
+/// `SSN` is the `ssn` column, `"SSN"` keeps its case.
+#[derive(Clone, Default)]
+pub struct ColumnCodecs {
+    codecs: Arc<HashMap<String, ColumnCodec>>,
+}
+
+impl ColumnCodecs {
+    #[must_use]
+    pub fn new(codecs: HashMap<String, ColumnCodec>) -> Self {
+        ColumnCodecs {
+            codecs: Arc::new(
+                codecs
+                    .into_iter()
+                    .map(|(column_name, codec)| (fold_identifier(&column_name), codec))
+                    .collect(),
+            ),
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.codecs.is_empty()
+    }
+
+    /// Encrypt value of the parameter if its column has codec.
+    ///
+    /// # Errors
+    /// May return Err Result if value cannot be encrypted.
+    pub fn encrypt_value<'py>(
+        &self,
+        column_name: &str,
+        value: Bound<'py, PyAny>,
+    ) -> RustPSQLDriverPyResult<Bound<'py, PyAny>> {
+        let Some(codec) = self.codecs.get(column_name) else {
+            return Ok(value);
+        };
+        let py = value.py();
+        Ok(codec.encrypt(py, value.unbind())?.into_bound(py))
+    }
+
+    /// Encrypt values of the sequence by the column names at the same positions.
+    ///
+    /// Object which is not a sequence is returned as is,
+    /// the parameters conversion rejects it later.
+    fn encrypt_sequence(
+        &self,
+        py: Python<'_>,
+        values: Py<PyAny>,
+        column_names: &[Option<String>],
+    ) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        let Ok(items) = values.extract::<Vec<Py<PyAny>>>(py) else {
+            return Ok(values);
+        };
+        let mut encrypted = Vec::with_capacity(items.len());
+        for (idx, value) in items.into_iter().enumerate() {
+            let value = value.into_bound(py);
+            encrypted.push(match column_names.get(idx).and_then(Option::as_deref) {
+                Some(column_name) => self.encrypt_value(column_name, value)?,
+                None => value,
+            });
+        }
+        Ok(PyList::new(py, encrypted)?.into_any().unbind())
+    }
+
+    /// Encrypt values of the row, `columns` are in order of the values.
+    ///
+    /// # Errors
+    /// May return Err Result if value cannot be encrypted.
+    pub fn encrypt_row(
+        &self,
+        columns: &[String],
+        row: Py<PyAny>,
+    ) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        if self.codecs.is_empty() {
+            return Ok(row);
+        }
+        let column_names: Vec<Option<String>> = columns.iter().cloned().map(Some).collect();
+        Python::with_gil(|gil| self.encrypt_sequence(gil, row, &column_names))
+    }
+
+    /// Return columns with codecs used by the querystring, sorted by name.
+    ///
+    /// # Errors
+    /// May return Err Result if querystring uses the name of the column with codec
+    /// in other case, quoted names are compared exactly, so it's another column
+    /// or a mistake and the value would be sent unencrypted.
+    fn used_columns(&self, querystring: &str) -> RustPSQLDriverPyResult<Vec<&str>> {
+        let mut used_columns = vec![];
+        for word in query_words(querystring) {
+            if let Some((column_name, _)) = self.codecs.get_key_value(&word) {
+                used_columns.push(column_name.as_str());
+                continue;
+            }
+            if let Some(column_name) = self
+                .codecs
+                .keys()
+                .find(|column_name| column_name.to_lowercase() == word.to_lowercase())
+            {
+                return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                    "Querystring uses `{word}`, but codec is set for `{column_name}`, \
+                     quote the column name in the querystring or in column_codecs \
+                     the same way"
+                )));
+            }
+        }
+        used_columns.sort_unstable();
+        used_columns.dedup();
+        Ok(used_columns)
+    }
+
+    /// Check that parameters aren't compared with the columns of non-deterministic codecs.
+    ///
+    /// Such comparison never matches the stored value, so it's an error.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Querystring uses name of the column with codec in other case
+    /// 2) Parameter is compared with the column of non-deterministic codec
+    pub fn check_comparisons(
+        &self,
+        querystring: &str,
+        parameters_count: usize,
+    ) -> RustPSQLDriverPyResult<()> {
+        if self.codecs.is_empty() || self.used_columns(querystring)?.is_empty() {
+            return Ok(());
+        }
+        for (column_name, parameter_use) in guess_parameter_columns(querystring, parameters_count)?
+            .into_iter()
+            .flatten()
+        {
+            self.check_comparison(&column_name, parameter_use)?;
+        }
+        Ok(())
+    }
+
+    /// Fail if the parameter is compared with the column of non-deterministic codec.
+    fn check_comparison(
+        &self,
+        column_name: &str,
+        parameter_use: ParameterUse,
+    ) -> RustPSQLDriverPyResult<()> {
+        match self.codecs.get(column_name) {
+            Some(codec)
+                if parameter_use == ParameterUse::Comparison && !codec.is_deterministic() =>
+            {
+                Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                    "Parameter is compared with `{column_name}` column encrypted by AES-GCM codec, \
+                     encrypted value never equals the stored one"
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check that rows aren't matched by the columns of non-deterministic codecs.
+    ///
+    /// # Errors
+    /// May return Err Result if key column has non-deterministic codec.
+    pub fn check_key_columns(&self, key_columns: &[String]) -> RustPSQLDriverPyResult<()> {
+        for key_column in key_columns {
+            self.check_comparison(key_column, ParameterUse::Comparison)?;
+        }
+        Ok(())
+    }
+
+    /// Encrypt `$N` parameters of the querystring which are used with columns with codecs.
+    ///
+    /// Columns of the parameters are guessed like names of `execute_named` parameters.
+    /// Values stored by `INSERT` column list and `SET column = $1` are encrypted,
+    /// values compared with the columns are encrypted only by deterministic codecs,
+    /// comparison with the column of AES-GCM codec is an error.
+    /// If querystring uses column with codec, every parameter must be matched
+    /// with a column, so the value is never sent unencrypted by mistake.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Querystring uses name of the column with codec in other case
+    /// 2) Querystring uses column with codec and some parameters cannot be matched with columns
+    /// 3) Parameter is compared with the column of non-deterministic codec
+    /// 4) Value cannot be encrypted
+    pub fn encrypt_parameters(
+        &self,
+        querystring: &str,
+        parameters: Py<PyAny>,
+    ) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        if self.codecs.is_empty() {
+            return Ok(parameters);
+        }
+        let used_columns = self.used_columns(querystring)?;
+        if used_columns.is_empty() {
+            return Ok(parameters);
+        }
+
+        Python::with_gil(|gil| {
+            let Ok(parameters_count) = parameters.bind(gil).len() else {
+                return Ok(parameters);
+            };
+            let columns = guess_parameter_columns(querystring, parameters_count)?;
+            let unmatched: Vec<String> = columns
+                .iter()
+                .enumerate()
+                .filter(|(_, column)| column.is_none())
+                .map(|(idx, _)| format!("${}", idx + 1))
+                .collect();
+            if !unmatched.is_empty() {
+                return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                    "Querystring uses columns with codecs {}, but parameters {} cannot be \
+                     matched with columns, use execute_named with parameter_names",
+                    used_columns.join(", "),
+                    unmatched.join(", ")
+                )));
+            }
+
+            let mut column_names = Vec::with_capacity(columns.len());
+            for (column_name, parameter_use) in columns.into_iter().flatten() {
+                self.check_comparison(&column_name, parameter_use)?;
+                column_names.push((parameter_use != ParameterUse::Clause).then_some(column_name));
+            }
+            self.encrypt_sequence(gil, parameters, &column_names)
+        })
+    }
+
+    /// Decrypt value of the column if it has codec.
+    ///
+    /// # Errors
+    /// May return Err Result if value cannot be decrypted.
+    pub fn decrypt_value(
+        &self,
+        py: Python<'_>,
+        column_name: &str,
+        value: Py<PyAny>,
+    ) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        match self.codecs.get(column_name) {
+            Some(codec) => codec.decrypt(py, value),
+            None => Ok(value),
+        }
+    }
+
+    /// Decrypt values of the row dict in place.
+    ///
+    /// Codecs are found by the database column names,
+    /// dict keys can be renamed with `column_names`.
+    ///
+    /// # Errors
+    /// May return Err Result if value cannot be decrypted.
+    pub fn decrypt_row(
+        &self,
+        row: &Row,
+        python_dict: &Bound<'_, PyDict>,
+        column_names: Option<&[Py<PyAny>]>,
+    ) -> RustPSQLDriverPyResult<()> {
+        if self.codecs.is_empty() {
+            return Ok(());
+        }
+        let py = python_dict.py();
+        for (column_idx, column) in row.columns().iter().enumerate() {
+            let Some(codec) = self.codecs.get(column.name()) else {
+                continue;
+            };
+            let key = match column_names.and_then(|column_names| column_names.get(column_idx)) {
+                Some(column_name) => column_name.clone_ref(py).into_bound(py),
+                None => PyString::new(py, column.name()).into_any(),
+            };
+            if let Some(value) = python_dict.get_item(&key)? {
+                python_dict.set_item(&key, codec.decrypt(py, value.unbind())?)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -3,6 +3,11 @@ use std::time::Duration;
 use deadpool_postgres::RecyclingMethod;
 use pyo3::{pyclass, pymethods};
 
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    format_helpers::quote_literal,
+};
+
 #[pyclass(eq, eq_int)]
 #[derive(Clone, Copy, PartialEq)]
 pub enum ConnRecyclingMethod {
@@ -50,6 +55,9 @@ pub enum TargetSessionAttrs {
     ReadWrite,
     /// The session allow only reads.
     ReadOnly,
+    /// Read-only session is preferred,
+    /// any session is used if no host allows only reads.
+    PreferStandby,
 }
 
 impl TargetSessionAttrs {
@@ -59,6 +67,8 @@ impl TargetSessionAttrs {
             TargetSessionAttrs::Any => tokio_postgres::config::TargetSessionAttrs::Any,
             TargetSessionAttrs::ReadWrite => tokio_postgres::config::TargetSessionAttrs::ReadWrite,
             TargetSessionAttrs::ReadOnly => tokio_postgres::config::TargetSessionAttrs::ReadOnly,
+            // Read-only session is tried separately before the config is used.
+            TargetSessionAttrs::PreferStandby => tokio_postgres::config::TargetSessionAttrs::Any,
         }
     }
 }
@@ -138,4 +148,68 @@ impl CopyCommandFormat {
             CopyCommandFormat::BINARY => "binary".into(),
         }
     }
+
+    /// Build options list of the `COPY` command.
+    ///
+    /// # Errors
+    /// May return Err Result if `delimiter`, `null` or `header`
+    /// is passed with the binary format.
+    pub fn copy_options(
+        &self,
+        delimiter: Option<&str>,
+        null: Option<&str>,
+        header: Option<bool>,
+    ) -> RustPSQLDriverPyResult<String> {
+        if *self == CopyCommandFormat::BINARY
+            && (delimiter.is_some() || null.is_some() || header.is_some())
+        {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(
+                "delimiter, null and header cannot be used with binary format".into(),
+            ));
+        }
+
+        let mut options = vec![format!("FORMAT {}", self.to_internal())];
+        if let Some(delimiter) = delimiter {
+            options.push(format!("DELIMITER {}", quote_literal(delimiter)));
+        }
+        if let Some(null) = null {
+            options.push(format!("NULL {}", quote_literal(null)));
+        }
+        if let Some(header) = header {
+            options.push(format!("HEADER {header}"));
+        }
+        Ok(options.join(", "))
+    }
+}
+
+/// Action of `MERGE` for the rows which exist in the table.
+#[pyclass(eq, eq_int)]
+#[derive(Clone, Copy, PartialEq)]
+pub enum MergeMatchedAction {
+    /// Update the row with the values from the source row.
+    Update,
+    /// Delete the row.
+    Delete,
+    /// Leave the row as is.
+    DoNothing,
+}
+
+/// Action of `MERGE` for the source rows which don't exist in the table.
+#[pyclass(eq, eq_int)]
+#[derive(Clone, Copy, PartialEq)]
+pub enum MergeNotMatchedAction {
+    /// Insert the source row.
+    Insert,
+    /// Skip the source row.
+    DoNothing,
+}
+
+/// Format of the schema of the result columns.
+#[pyclass(eq, eq_int)]
+#[derive(Clone, Copy, PartialEq)]
+pub enum ResultSchemaFormat {
+    /// JSON Schema of the row object as dict.
+    JsonSchema,
+    /// `pyarrow.Schema` of the result.
+    Arrow,
 }
@@ -1,22 +1,64 @@
 use bytes::BytesMut;
-use deadpool_postgres::Pool;
-use futures_util::pin_mut;
-use pyo3::{buffer::PyBuffer, pyclass, pymethods, Py, PyAny, PyErr, Python};
-use std::{collections::HashSet, net::IpAddr, sync::Arc};
+use deadpool_postgres::{Object, Pool};
+use futures_util::{
+    future::{self, Either},
+    pin_mut, SinkExt, StreamExt,
+};
+use pyo3::{
+    buffer::PyBuffer,
+    pyclass, pyfunction, pymethods,
+    types::{PyAnyMethods, PyBytes, PyDict, PyDictMethods, PyList, PyTuple},
+    Bound, Py, PyAny, PyErr, Python,
+};
+use std::{collections::HashMap, net::IpAddr, sync::Arc, time::Duration};
 use tokio_postgres::{binary_copy::BinaryCopyInWriter, config::Host, Config};
 
 use crate::{
     exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
-    format_helpers::quote_ident,
+    format_helpers::{annotate_query, guess_parameter_names, quote_ident},
     query_result::{PSQLDriverPyQueryResult, PSQLDriverSinglePyQueryResult},
     runtime::tokio_runtime,
+    type_registry::register_enum,
 };
 
 use super::{
-    cursor::Cursor,
+    annotation::{ConnectionAnnotation, DEFAULT_ANNOTATION_SETTING},
+    binary_copy_validation::validate_binary_copy,
+    column_buffers::{fetch_column_array, fill_column_buffers},
+    column_codecs::ColumnCodecs,
+    common_options::{
+        CopyCommandFormat, LoadBalanceHosts, MergeMatchedAction, MergeNotMatchedAction,
+        ResultSchemaFormat, SslMode, TargetSessionAttrs,
+    },
+    connection_affinity::ConnectionAffinity,
+    connection_pool::checkout_connection,
+    copy_out::CopyOutChunks,
+    cursor::{prepare_cursor_name, Cursor},
+    diagnostics::{fetch_session_diagnostics, SessionDiagnostics},
+    dry_run::{dry_run_rollback, reject_dry_run, run_dry},
+    dsn::{parse_dsn, DsnOptions},
     inner_connection::PsqlpyConnection,
+    isolation_advisor::IsolationAdvisor,
+    listener::{channel_listener::ChannelListener, table_watcher::TableWatcher},
+    maintenance::{build_maintenance_command, extract_maintenance_options, run_maintenance},
+    materialized_view::{refresh_materialized_view, watch_refresh_progress},
+    pipeline::Pipeline,
+    pool_stats::PoolStats,
+    query_cancel::{cancel_query, run_cancellable, CancelOnDrop},
+    query_queue::QueryQueue,
+    redaction::RedactionPolicy,
+    result_masking::ColumnMasks,
+    result_schema::build_result_schema,
+    row_iterator::{RowIterator, DEFAULT_FETCH_CHUNK_SIZE},
+    shared_connection::{SharedConnectionHandle, SharedQueue},
+    spilled_result::{SpilledQueryResult, DEFAULT_SPILL_THRESHOLD},
+    sqlcommenter::SqlCommenter,
+    ssl_info::{fetch_peer_certificate, fetch_ssl_status, SslCertificate},
+    statement_cache::{PooledStatementCaches, StatementCacheInfo},
+    statement_stats::StatementStats,
     transaction::Transaction,
     transaction_options::{IsolationLevel, ReadVariant, SynchronousCommit},
+    utils::{apply_server_settings, build_connection_config, connect_single, TlsOptions},
 };
 
 #[pyclass(subclass)]
@@ -25,6 +67,24 @@ pub struct Connection {
     db_client: Option<Arc<PsqlpyConnection>>,
     db_pool: Option<Pool>,
     pg_config: Arc<Config>,
+    tls_options: TlsOptions,
+    ssl_mode: Option<SslMode>,
+    affinity: Option<(String, ConnectionAffinity)>,
+    statement_stats: Option<StatementStats>,
+    statement_caches: PooledStatementCaches,
+    pool_stats: PoolStats,
+    sqlcommenter: Option<SqlCommenter>,
+    validate_on_checkout: bool,
+    check_latency: Option<Duration>,
+    isolation_advisor: Option<IsolationAdvisor>,
+    column_codecs: ColumnCodecs,
+    redaction_policy: Option<RedactionPolicy>,
+    column_masks: ColumnMasks,
+    recycle_on_error: bool,
+    dry_run: bool,
+    query_queue: QueryQueue,
+    shared_queue: SharedQueue,
+    cursors: Vec<Py<Cursor>>,
 }
 
 impl Connection {
@@ -33,12 +93,172 @@ impl Connection {
         db_client: Option<Arc<PsqlpyConnection>>,
         db_pool: Option<Pool>,
         pg_config: Arc<Config>,
+        tls_options: TlsOptions,
+        ssl_mode: Option<SslMode>,
     ) -> Self {
         Connection {
             db_client,
             db_pool,
             pg_config,
+            tls_options,
+            ssl_mode,
+            affinity: None,
+            statement_stats: None,
+            statement_caches: PooledStatementCaches::default(),
+            pool_stats: PoolStats::default(),
+            sqlcommenter: None,
+            validate_on_checkout: false,
+            check_latency: None,
+            isolation_advisor: None,
+            column_codecs: ColumnCodecs::default(),
+            redaction_policy: None,
+            column_masks: ColumnMasks::default(),
+            recycle_on_error: false,
+            dry_run: false,
+            query_queue: QueryQueue::default(),
+            shared_queue: SharedQueue::default(),
+            cursors: vec![],
+        }
+    }
+
+    /// Bind connection to the affinity key.
+    ///
+    /// On return to the pool connection is parked under the key
+    /// instead of being released.
+    #[must_use]
+    pub fn with_affinity(mut self, affinity_key: String, affinity: ConnectionAffinity) -> Self {
+        self.affinity = Some((affinity_key, affinity));
+        self
+    }
+
+    /// Set statistics for the connections acquired from the pool.
+    #[must_use]
+    pub fn with_statement_stats(mut self, statement_stats: StatementStats) -> Self {
+        self.statement_stats = Some(statement_stats);
+        self
+    }
+
+    /// Set statement caches of the pool connections.
+    #[must_use]
+    pub fn with_statement_caches(mut self, statement_caches: PooledStatementCaches) -> Self {
+        self.statement_caches = statement_caches;
+        self
+    }
+
+    /// Set acquire counters of the pool.
+    #[must_use]
+    pub fn with_pool_stats(mut self, pool_stats: PoolStats) -> Self {
+        self.pool_stats = pool_stats;
+        self
+    }
+
+    /// Close connection if `async with` block exits with an exception.
+    #[must_use]
+    pub fn with_recycle_on_error(mut self, recycle_on_error: bool) -> Self {
+        self.recycle_on_error = recycle_on_error;
+        self
+    }
+
+    /// Set limit of the statements executed by the pool connections at once.
+    #[must_use]
+    pub fn with_query_queue(mut self, query_queue: QueryQueue) -> Self {
+        self.query_queue = query_queue;
+        self
+    }
+
+    /// Set commenter for the outgoing queries.
+    #[must_use]
+    pub fn with_sqlcommenter(mut self, sqlcommenter: Option<SqlCommenter>) -> Self {
+        self.sqlcommenter = sqlcommenter;
+        self
+    }
+
+    /// Check connection acquired from the pool before use.
+    #[must_use]
+    pub fn with_validate_on_checkout(mut self, validate_on_checkout: bool) -> Self {
+        self.validate_on_checkout = validate_on_checkout;
+        self
+    }
+
+    /// Set advisor which reports statements needing stronger isolation level.
+    #[must_use]
+    pub fn with_isolation_advisor(mut self, isolation_advisor: Option<IsolationAdvisor>) -> Self {
+        self.isolation_advisor = isolation_advisor;
+        self
+    }
+
+    /// Set codecs of the encrypted columns.
+    #[must_use]
+    pub fn with_column_codecs(mut self, column_codecs: ColumnCodecs) -> Self {
+        self.column_codecs = column_codecs;
+        self
+    }
+
+    /// Set policy which hides sensitive parameters in the errors.
+    #[must_use]
+    pub fn with_redaction_policy(mut self, redaction_policy: Option<RedactionPolicy>) -> Self {
+        self.redaction_policy = redaction_policy;
+        self
+    }
+
+    /// Set masks of the sensitive columns in the results.
+    #[must_use]
+    pub fn with_column_masks(mut self, column_masks: ColumnMasks) -> Self {
+        self.column_masks = column_masks;
+        self
+    }
+
+    /// Set round trip time of the check made on checkout.
+    #[must_use]
+    pub fn with_check_latency(mut self, check_latency: Option<Duration>) -> Self {
+        self.check_latency = check_latency;
+        self
+    }
+
+    /// Drop database client.
+    ///
+    /// If connection is bound to the affinity key and nobody else uses it,
+    /// it's parked under the key, otherwise it goes back to the pool.
+    fn release_db_client(&mut self) {
+        let Some(db_client) = self.db_client.take() else {
+            return;
+        };
+        let Some((affinity_key, affinity)) = &self.affinity else {
+            return;
+        };
+        if let Ok(PsqlpyConnection::PoolConn(object, ..)) = Arc::try_unwrap(db_client) {
+            affinity.park(affinity_key.clone(), object);
+        }
+    }
+
+    /// Close connection instead of returning it to the pool.
+    ///
+    /// Connection shared with a transaction or a cursor
+    /// is returned to the pool as usual.
+    fn discard_db_client(&mut self) {
+        let Some(db_client) = self.db_client.take() else {
+            return;
+        };
+        if let Ok(PsqlpyConnection::PoolConn(object, ..)) = Arc::try_unwrap(db_client) {
+            let _ = Object::take(object);
+        }
+    }
+
+    /// Take names of the open cursors created by the connection.
+    ///
+    /// Cursors are marked closed, the caller must close them in the database.
+    /// Cursors that are busy right now are skipped.
+    fn take_open_cursor_names(&mut self, py: Python<'_>) -> Vec<String> {
+        let mut cursor_names = vec![];
+        for cursor in std::mem::take(&mut self.cursors) {
+            if let Ok(mut cursor) = cursor.try_borrow_mut(py) {
+                if cursor.is_open() {
+                    cursor_names.push(cursor.cursor_name());
+                    cursor.mark_closed();
+                }
+            }
         }
+        cursor_names
     }
 
     #[must_use]
@@ -46,6 +266,11 @@ impl Connection {
         self.db_client.clone()
     }
 
+    #[must_use]
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
     #[must_use]
     pub fn db_pool(&self) -> Option<Pool> {
         self.db_pool.clone()
@@ -54,7 +279,13 @@ impl Connection {
 
 impl Default for Connection {
     fn default() -> Self {
-        Connection::new(None, None, Arc::new(Config::default()))
+        Connection::new(
+            None,
+            None,
+            Arc::new(Config::default()),
+            TlsOptions::default(),
+            None,
+        )
     }
 }
 
@@ -137,10 +368,53 @@ impl Connection {
         return self.pg_config.get_options();
     }
 
+    /// Return round trip time of the check made on checkout in milliseconds.
+    ///
+    /// None if connection wasn't checked.
+    #[getter]
+    fn last_check_latency(&self) -> Option<f64> {
+        self.check_latency
+            .map(|check_latency| check_latency.as_secs_f64() * 1000.0)
+    }
+
+    /// Return `True` if mutating statements are rolled back.
+    #[getter]
+    fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Roll back every mutating statement of the connection
+    /// and of its transactions.
+    ///
+    /// `RETURNING` rows and `rows_affected` of the results show
+    /// what the statement would do.
+    #[setter]
+    fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
     async fn __aenter__<'a>(self_: Py<Self>) -> RustPSQLDriverPyResult<Py<Self>> {
-        let (db_client, db_pool) = pyo3::Python::with_gil(|gil| {
+        let (
+            db_client,
+            db_pool,
+            statement_caches,
+            statement_stats,
+            validate_on_checkout,
+            pool_stats,
+            column_codecs,
+            stack,
+        ) = pyo3::Python::with_gil(|gil| {
             let self_ = self_.borrow(gil);
-            (self_.db_client.clone(), self_.db_pool.clone())
+            (
+                self_.db_client.clone(),
+                self_.db_pool.clone(),
+                self_.statement_caches.clone(),
+                self_.statement_stats.clone(),
+                self_.validate_on_checkout,
+                self_.pool_stats.clone(),
+                self_.column_codecs.clone(),
+                self_.pool_stats.capture_stack(gil),
+            )
         });
 
         if db_client.is_some() {
@@ -148,14 +422,20 @@ impl Connection {
         }
 
         if let Some(db_pool) = db_pool {
-            let db_connection = tokio_runtime()
-                .spawn(async move {
-                    Ok::<deadpool_postgres::Object, RustPSQLDriverError>(db_pool.get().await?)
-                })
-                .await??;
+            let (db_connection, checkout_guard, check_latency) =
+                checkout_connection(db_pool, validate_on_checkout, pool_stats).await?;
+            checkout_guard.set_stack(stack);
+            let statement_cache = statement_caches.checkout(&db_connection);
             pyo3::Python::with_gil(|gil| {
                 let mut self_ = self_.borrow_mut(gil);
-                self_.db_client = Some(Arc::new(PsqlpyConnection::PoolConn(db_connection)));
+                self_.db_client = Some(Arc::new(PsqlpyConnection::PoolConn(
+                    db_connection,
+                    statement_stats,
+                    Some(checkout_guard),
+                    column_codecs,
+                    statement_cache,
+                )));
+                self_.check_latency = check_latency;
             });
             return Ok(self_);
         }
@@ -163,7 +443,6 @@ impl Connection {
         Err(RustPSQLDriverError::ConnectionClosedError)
     }
 
-    #[allow(clippy::unused_async)]
     async fn __aexit__<'a>(
         self_: Py<Self>,
         _exception_type: Py<PyAny>,
@@ -177,10 +456,22 @@ impl Connection {
             )
         });
 
+        let (db_client, cursor_names) = pyo3::Python::with_gil(|gil| {
+            let mut self_ = self_.borrow_mut(gil);
+            (self_.db_client.clone(), self_.take_open_cursor_names(gil))
+        });
+        if let Some(db_client) = db_client {
+            close_cursors(&db_client, &cursor_names).await;
+        }
+
         pyo3::Python::with_gil(|gil| {
             let mut self_ = self_.borrow_mut(gil);
 
-            std::mem::take(&mut self_.db_client);
+            if !is_exception_none && self_.recycle_on_error {
+                self_.discard_db_client();
+            } else {
+                self_.release_db_client();
+            }
             std::mem::take(&mut self_.db_pool);
 
             if is_exception_none {
@@ -199,22 +490,207 @@ impl Connection {
     /// 1) Cannot convert incoming parameters
     /// 2) Cannot prepare statement
     /// 3) Cannot execute query
-    #[pyo3(signature = (querystring, parameters=None, prepared=None))]
+    /// 4) Query doesn't finish in `timeout` seconds
+    #[pyo3(signature = (
+        querystring,
+        parameters=None,
+        prepared=None,
+        hints=None,
+        comment=None,
+        timeout=None,
+        row_factory=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
     pub async fn execute(
         self_: pyo3::Py<Self>,
         querystring: String,
         parameters: Option<pyo3::Py<PyAny>>,
         prepared: Option<bool>,
+        hints: Option<String>,
+        comment: Option<String>,
+        timeout: Option<f64>,
+        row_factory: Option<pyo3::Py<PyAny>>,
     ) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
-        let db_client = pyo3::Python::with_gil(|gil| self_.borrow(gil).db_client.clone());
+        let (
+            db_client,
+            sqlcommenter,
+            tls_options,
+            ssl_mode,
+            column_codecs,
+            column_masks,
+            redaction_policy,
+            dry_run,
+            query_queue,
+        ) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (
+                self_.db_client.clone(),
+                self_.sqlcommenter.clone(),
+                self_.tls_options.clone(),
+                self_.ssl_mode,
+                self_.column_codecs.clone(),
+                self_.column_masks.clone(),
+                self_.redaction_policy.clone(),
+                self_.dry_run,
+                self_.query_queue.clone(),
+            )
+        });
+        let (querystring, prepared) =
+            SqlCommenter::apply(sqlcommenter.as_ref(), querystring, prepared)?;
+        let (querystring, prepared) = annotate_query(querystring, hints, comment, prepared)?;
+        let redact = RedactionPolicy::error_redactor(
+            redaction_policy.as_ref(),
+            &querystring,
+            parameters.as_ref(),
+        );
+        let rollback = dry_run_rollback(dry_run, &querystring)?;
 
         if let Some(db_client) = db_client {
-            return db_client.execute(querystring, parameters, prepared).await;
+            let cancel_guard = CancelOnDrop::new(db_client.clone(), tls_options, ssl_mode);
+            return query_queue
+                .run(run_dry(
+                    &db_client,
+                    rollback,
+                    false,
+                    run_cancellable(
+                        db_client.execute(querystring, parameters, prepared),
+                        cancel_guard,
+                        timeout,
+                    ),
+                ))
+                .await
+                .map_err(redact)
+                .map(|result| {
+                    result
+                        .with_row_factory(row_factory)
+                        .with_column_codecs(column_codecs)
+                        .with_column_masks(column_masks)
+                });
         }
 
         Err(RustPSQLDriverError::ConnectionClosedError)
     }
 
+    /// Execute statement with named parameters.
+    ///
+    /// Statement is described to get number of its `$N` parameters,
+    /// then values of the dict are placed by positions.
+    /// Name of every position is taken from `parameter_names`
+    /// or guessed by the columns the parameters are used with.
+    ///
+    /// # Errors
+    ///
+    /// May return Err Result if
+    /// 1) Cannot prepare statement
+    /// 2) Names of the parameters cannot be guessed or don't match the statement
+    /// 3) Parameters have unknown names or some names are missing
+    /// 4) Cannot execute query
+    #[pyo3(signature = (querystring, parameters, parameter_names=None, prepared=None))]
+    pub async fn execute_named(
+        self_: pyo3::Py<Self>,
+        querystring: String,
+        parameters: Py<PyDict>,
+        parameter_names: Option<Vec<String>>,
+        prepared: Option<bool>,
+    ) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
+        let (db_client, sqlcommenter, column_codecs, column_masks, dry_run) =
+            pyo3::Python::with_gil(|gil| {
+                let self_ = self_.borrow(gil);
+                (
+                    self_.db_client.clone(),
+                    self_.sqlcommenter.clone(),
+                    self_.column_codecs.clone(),
+                    self_.column_masks.clone(),
+                    self_.dry_run,
+                )
+            });
+        let (querystring, prepared) =
+            SqlCommenter::apply(sqlcommenter.as_ref(), querystring, prepared)?;
+        let rollback = dry_run_rollback(dry_run, &querystring)?;
+
+        let Some(db_client) = db_client else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+
+        let statement = db_client.prepare_cached(&querystring).await?;
+        let parameters_count = statement.params().len();
+        let parameter_names = match parameter_names {
+            Some(parameter_names) => parameter_names,
+            None => guess_parameter_names(&querystring, parameters_count)?,
+        };
+        if parameter_names.len() != parameters_count {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "Statement has {parameters_count} parameters, but {} names are passed",
+                parameter_names.len()
+            )));
+        }
+        column_codecs.check_comparisons(&querystring, parameters_count)?;
+
+        let positional_parameters = pyo3::Python::with_gil(|gil| {
+            let parameters = parameters.bind(gil);
+            for key in parameters.keys() {
+                let key: String = key.extract()?;
+                if !parameter_names.contains(&key) {
+                    return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                        "Statement doesn't have parameter `{key}`"
+                    )));
+                }
+            }
+
+            let mut values = Vec::with_capacity(parameters_count);
+            for name in &parameter_names {
+                let Some(value) = parameters.get_item(name)? else {
+                    return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                        "Parameter `{name}` is missing"
+                    )));
+                };
+                values.push(column_codecs.encrypt_value(name, value)?);
+            }
+            Ok(PyList::new(gil, values)?.into_any().unbind())
+        })?;
+
+        // Values are already encrypted by the parameter names.
+        run_dry(
+            &db_client,
+            rollback,
+            false,
+            db_client.execute_without_codecs(querystring, Some(positional_parameters), prepared),
+        )
+        .await
+        .map(|result| {
+            result
+                .with_column_codecs(column_codecs)
+                .with_column_masks(column_masks)
+        })
+    }
+
+    /// Cancel the query running on the connection.
+    ///
+    /// Cancel request is sent by the separate connection,
+    /// so it can be called from another task while the query is running.
+    /// Nothing happens if there is no running query.
+    ///
+    /// # Errors
+    ///
+    /// May return Err Result if:
+    /// 1) Connection is closed
+    /// 2) Cannot send cancel request
+    pub async fn cancel(self_: pyo3::Py<Self>) -> RustPSQLDriverPyResult<()> {
+        let (db_client, tls_options, ssl_mode) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (
+                self_.db_client.clone(),
+                self_.tls_options.clone(),
+                self_.ssl_mode,
+            )
+        });
+        let Some(db_client) = db_client else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+
+        cancel_query(db_client.cancel_token(), tls_options, ssl_mode).await
+    }
+
     /// Executes a sequence of SQL statements using the simple query protocol.
     ///
     /// Statements should be separated by semicolons.
@@ -231,10 +707,26 @@ impl Connection {
         self_: pyo3::Py<Self>,
         querystring: String,
     ) -> RustPSQLDriverPyResult<()> {
-        let db_client = pyo3::Python::with_gil(|gil| self_.borrow(gil).db_client.clone());
+        let (db_client, dry_run, query_queue) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (
+                self_.db_client.clone(),
+                self_.dry_run,
+                self_.query_queue.clone(),
+            )
+        });
+
+        let rollback = dry_run_rollback(dry_run, &querystring)?;
 
         if let Some(db_client) = db_client {
-            return db_client.batch_execute(&querystring).await;
+            return query_queue
+                .run(run_dry(
+                    &db_client,
+                    rollback,
+                    false,
+                    db_client.batch_execute(&querystring),
+                ))
+                .await;
         }
 
         Err(RustPSQLDriverError::ConnectionClosedError)
@@ -250,114 +742,1050 @@ impl Connection {
     /// May return Err Result if:
     /// 1) Cannot convert python parameters
     /// 2) Cannot execute querystring.
-    #[pyo3(signature = (querystring, parameters=None, prepared=None))]
+    #[pyo3(signature = (querystring, parameters=None, prepared=None, hints=None, comment=None))]
     pub async fn execute_many<'a>(
         self_: pyo3::Py<Self>,
         querystring: String,
         parameters: Option<Vec<Py<PyAny>>>,
         prepared: Option<bool>,
+        hints: Option<String>,
+        comment: Option<String>,
     ) -> RustPSQLDriverPyResult<()> {
-        let db_client = pyo3::Python::with_gil(|gil| self_.borrow(gil).db_client.clone());
+        let (db_client, sqlcommenter, dry_run, query_queue) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (
+                self_.db_client.clone(),
+                self_.sqlcommenter.clone(),
+                self_.dry_run,
+                self_.query_queue.clone(),
+            )
+        });
+        let (querystring, prepared) =
+            SqlCommenter::apply(sqlcommenter.as_ref(), querystring, prepared)?;
+        let (querystring, prepared) = annotate_query(querystring, hints, comment, prepared)?;
+        let rollback = dry_run_rollback(dry_run, &querystring)?;
 
         if let Some(db_client) = db_client {
-            return db_client
-                .execute_many(querystring, parameters, prepared)
+            return query_queue
+                .run(run_dry(
+                    &db_client,
+                    rollback,
+                    false,
+                    db_client.execute_many(querystring, parameters, prepared),
+                ))
                 .await;
         }
 
         Err(RustPSQLDriverError::ConnectionClosedError)
     }
 
-    /// Fetch result from the database.
+    /// Insert list of rows with `VALUES %s` statements.
+    ///
+    /// `%s` marker is expanded into the list of numbered parameters,
+    /// so up to `page_size` rows are sent in one statement.
     ///
     /// # Errors
     ///
-    /// May return Err Result if
-    /// 1) Cannot convert incoming parameters
-    /// 2) Cannot prepare statement
-    /// 3) Cannot execute query
-    #[pyo3(signature = (querystring, parameters=None, prepared=None))]
-    pub async fn fetch(
+    /// May return Err Result if:
+    /// 1) Querystring doesn't contain exactly one `%s` marker
+    /// 2) Cannot convert python parameters
+    /// 3) Cannot execute querystring.
+    #[pyo3(signature = (querystring, values, page_size=100, prepared=None))]
+    pub async fn execute_values(
         self_: pyo3::Py<Self>,
         querystring: String,
-        parameters: Option<pyo3::Py<PyAny>>,
+        values: Vec<Py<PyAny>>,
+        page_size: usize,
         prepared: Option<bool>,
-    ) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
-        let db_client = pyo3::Python::with_gil(|gil| self_.borrow(gil).db_client.clone());
+    ) -> RustPSQLDriverPyResult<()> {
+        let (db_client, sqlcommenter, dry_run, query_queue) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (
+                self_.db_client.clone(),
+                self_.sqlcommenter.clone(),
+                self_.dry_run,
+                self_.query_queue.clone(),
+            )
+        });
+        let (querystring, prepared) =
+            SqlCommenter::apply(sqlcommenter.as_ref(), querystring, prepared)?;
+        let rollback = dry_run_rollback(dry_run, &querystring)?;
 
         if let Some(db_client) = db_client {
-            return db_client.execute(querystring, parameters, prepared).await;
+            return query_queue
+                .run(run_dry(
+                    &db_client,
+                    rollback,
+                    false,
+                    db_client.execute_values(querystring, values, page_size, prepared),
+                ))
+                .await;
         }
 
         Err(RustPSQLDriverError::ConnectionClosedError)
     }
 
-    /// Fetch exaclty single row from query.
+    /// Update rows of the table matched by the key columns.
     ///
-    /// Method doesn't acquire lock on any structure fields.
-    /// It prepares and caches querystring in the inner Object object.
+    /// Every row is a dict of column values, all rows must have the same columns.
+    /// Rows are sent in `UPDATE ... FROM (VALUES ...)` statements
+    /// of up to `page_size` rows, values are cast to the types of the columns.
+    /// Table, schema and column names are quoted, so they are case-sensitive.
     ///
-    /// Then execute the query.
+    /// Returns number of updated rows.
     ///
     /// # Errors
+    ///
     /// May return Err Result if:
-    /// 1) Transaction is not started
-    /// 2) Transaction is done already
-    /// 3) Can not create/retrieve prepared statement
-    /// 4) Can not execute statement
-    /// 5) Query returns more than one row
-    #[pyo3(signature = (querystring, parameters=None, prepared=None))]
-    pub async fn fetch_row(
+    /// 1) Rows have different columns or don't have key columns
+    /// 2) Cannot convert python parameters
+    /// 3) Cannot execute statement
+    #[pyo3(signature = (table_name, key_columns, rows, schema_name=None, page_size=100))]
+    pub async fn update_many(
+        self_: pyo3::Py<Self>,
+        table_name: String,
+        key_columns: Vec<String>,
+        rows: Vec<Py<PyDict>>,
+        schema_name: Option<String>,
+        page_size: usize,
+    ) -> RustPSQLDriverPyResult<usize> {
+        let (db_client, dry_run) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (self_.db_client.clone(), self_.dry_run)
+        });
+        let Some(db_client) = db_client else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+        if key_columns.is_empty() {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(
+                "At least one key column must be passed".into(),
+            ));
+        }
+
+        let (columns, row_values) = rows_to_values(&rows, &key_columns)?;
+        if row_values.is_empty() {
+            return Ok(0);
+        }
+        if columns.len() == key_columns.len() {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(
+                "Rows must have at least one column besides the key columns".into(),
+            ));
+        }
+
+        let table = copy_target(&table_name, None, schema_name);
+        run_dry(
+            &db_client,
+            dry_run,
+            false,
+            db_client.update_many(&table, &key_columns, &columns, row_values, page_size),
+        )
+        .await
+    }
+
+    /// Merge rows into the table with `MERGE` statement.
+    ///
+    /// Every row is a dict of column values, all rows must have the same columns.
+    /// Rows matched by `on` columns are updated, deleted or left as is,
+    /// other rows are inserted or skipped.
+    /// Table, schema and column names are quoted, so they are case-sensitive.
+    /// `MERGE` requires PostgreSQL 15+.
+    ///
+    /// Returns number of inserted, updated and deleted rows.
+    ///
+    /// # Errors
+    ///
+    /// May return Err Result if:
+    /// 1) Rows have different columns or don't have `on` columns
+    /// 2) Cannot convert python parameters
+    /// 3) Cannot execute statement
+    #[pyo3(signature = (
+        table_name,
+        source_rows,
+        on,
+        when_matched=MergeMatchedAction::Update,
+        when_not_matched=MergeNotMatchedAction::Insert,
+        schema_name=None,
+        page_size=100,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn merge(
+        self_: pyo3::Py<Self>,
+        table_name: String,
+        source_rows: Vec<Py<PyDict>>,
+        on: Vec<String>,
+        when_matched: MergeMatchedAction,
+        when_not_matched: MergeNotMatchedAction,
+        schema_name: Option<String>,
+        page_size: usize,
+    ) -> RustPSQLDriverPyResult<usize> {
+        let (db_client, dry_run) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (self_.db_client.clone(), self_.dry_run)
+        });
+        let Some(db_client) = db_client else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+        if on.is_empty() {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(
+                "At least one `on` column must be passed".into(),
+            ));
+        }
+
+        let (columns, row_values) = rows_to_values(&source_rows, &on)?;
+        if row_values.is_empty() {
+            return Ok(0);
+        }
+
+        let table = copy_target(&table_name, None, schema_name);
+        run_dry(
+            &db_client,
+            dry_run,
+            false,
+            db_client.merge_many(
+                &table,
+                &on,
+                &columns,
+                row_values,
+                when_matched,
+                when_not_matched,
+                page_size,
+            ),
+        )
+        .await
+    }
+
+    /// Fetch result from the database.
+    ///
+    /// # Errors
+    ///
+    /// May return Err Result if
+    /// 1) Cannot convert incoming parameters
+    /// 2) Cannot prepare statement
+    /// 3) Cannot execute query
+    /// 4) Query doesn't finish in `timeout` seconds
+    #[pyo3(signature = (
+        querystring,
+        parameters=None,
+        prepared=None,
+        hints=None,
+        comment=None,
+        timeout=None,
+        row_factory=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn fetch(
         self_: pyo3::Py<Self>,
         querystring: String,
         parameters: Option<pyo3::Py<PyAny>>,
         prepared: Option<bool>,
-    ) -> RustPSQLDriverPyResult<PSQLDriverSinglePyQueryResult> {
-        let db_client = pyo3::Python::with_gil(|gil| self_.borrow(gil).db_client.clone());
+        hints: Option<String>,
+        comment: Option<String>,
+        timeout: Option<f64>,
+        row_factory: Option<pyo3::Py<PyAny>>,
+    ) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
+        let (
+            db_client,
+            sqlcommenter,
+            tls_options,
+            ssl_mode,
+            column_codecs,
+            column_masks,
+            redaction_policy,
+            dry_run,
+            query_queue,
+        ) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (
+                self_.db_client.clone(),
+                self_.sqlcommenter.clone(),
+                self_.tls_options.clone(),
+                self_.ssl_mode,
+                self_.column_codecs.clone(),
+                self_.column_masks.clone(),
+                self_.redaction_policy.clone(),
+                self_.dry_run,
+                self_.query_queue.clone(),
+            )
+        });
+        let (querystring, prepared) =
+            SqlCommenter::apply(sqlcommenter.as_ref(), querystring, prepared)?;
+        let (querystring, prepared) = annotate_query(querystring, hints, comment, prepared)?;
+        let redact = RedactionPolicy::error_redactor(
+            redaction_policy.as_ref(),
+            &querystring,
+            parameters.as_ref(),
+        );
+        let rollback = dry_run_rollback(dry_run, &querystring)?;
 
         if let Some(db_client) = db_client {
-            return db_client.fetch_row(querystring, parameters, prepared).await;
+            let cancel_guard = CancelOnDrop::new(db_client.clone(), tls_options, ssl_mode);
+            return query_queue
+                .run(run_dry(
+                    &db_client,
+                    rollback,
+                    false,
+                    run_cancellable(
+                        db_client.execute(querystring, parameters, prepared),
+                        cancel_guard,
+                        timeout,
+                    ),
+                ))
+                .await
+                .map_err(redact)
+                .map(|result| {
+                    result
+                        .with_row_factory(row_factory)
+                        .with_column_codecs(column_codecs)
+                        .with_column_masks(column_masks)
+                });
         }
 
         Err(RustPSQLDriverError::ConnectionClosedError)
     }
 
-    /// Execute querystring with parameters and return first value in the first row.
+    /// Fetch result which keeps large row data on disk.
     ///
-    /// It converts incoming parameters to rust readable,
-    /// executes query with them and returns first row of response.
+    /// Raw row data is kept in memory until it exceeds `memory_threshold`
+    /// bytes, then it's written to the temporary file.
+    /// Rows are converted to Python on iteration over the result.
     ///
     /// # Errors
-    ///
     /// May return Err Result if:
     /// 1) Cannot convert python parameters
-    /// 2) Cannot execute querystring.
-    /// 3) Query returns more than one row
-    #[pyo3(signature = (querystring, parameters=None, prepared=None))]
-    pub async fn fetch_val<'a>(
+    /// 2) Cannot execute querystring
+    /// 3) Cannot write to the temporary file
+    #[pyo3(signature = (
+        querystring,
+        parameters=None,
+        prepared=None,
+        memory_threshold=DEFAULT_SPILL_THRESHOLD,
+        custom_decoders=None,
+    ))]
+    pub async fn fetch_spilled(
         self_: pyo3::Py<Self>,
         querystring: String,
         parameters: Option<pyo3::Py<PyAny>>,
         prepared: Option<bool>,
-    ) -> RustPSQLDriverPyResult<Py<PyAny>> {
-        let db_client = pyo3::Python::with_gil(|gil| self_.borrow(gil).db_client.clone());
+        memory_threshold: usize,
+        custom_decoders: Option<Py<PyDict>>,
+    ) -> RustPSQLDriverPyResult<SpilledQueryResult> {
+        let (db_client, sqlcommenter, dry_run) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (
+                self_.db_client.clone(),
+                self_.sqlcommenter.clone(),
+                self_.dry_run,
+            )
+        });
+        let (querystring, prepared) =
+            SqlCommenter::apply(sqlcommenter.as_ref(), querystring, prepared)?;
+        let rollback = dry_run_rollback(dry_run, &querystring)?;
 
         if let Some(db_client) = db_client {
-            return db_client.fetch_val(querystring, parameters, prepared).await;
+            return run_dry(
+                &db_client,
+                rollback,
+                false,
+                db_client.execute_spilled(
+                    querystring,
+                    parameters,
+                    prepared,
+                    memory_threshold,
+                    custom_decoders,
+                ),
+            )
+            .await;
         }
 
         Err(RustPSQLDriverError::ConnectionClosedError)
     }
 
-    /// Create new transaction object.
+    /// Fetch result row by row with async iterator.
+    ///
+    /// Rows are read from the connection by `chunk_size` rows,
+    /// so the whole result is never held in memory.
     ///
     /// # Errors
-    /// May return Err Result if db_client is None.
+    /// May return Err Result if:
+    /// 1) Connection is closed
+    /// 2) Chunk size is zero
+    /// 3) Cannot convert python parameters
+    /// 4) Cannot execute querystring
     #[pyo3(signature = (
-        isolation_level=None,
-        read_variant=None,
+        querystring,
+        parameters=None,
+        chunk_size=DEFAULT_FETCH_CHUNK_SIZE,
+        prepared=None,
+        custom_decoders=None,
+    ))]
+    pub async fn fetch_iter(
+        self_: pyo3::Py<Self>,
+        querystring: String,
+        parameters: Option<pyo3::Py<PyAny>>,
+        chunk_size: usize,
+        prepared: Option<bool>,
+        custom_decoders: Option<Py<PyDict>>,
+    ) -> RustPSQLDriverPyResult<RowIterator> {
+        if chunk_size == 0 {
+            return Err(RustPSQLDriverError::ConnectionExecuteError(
+                "Chunk size must be greater than zero".into(),
+            ));
+        }
+        let (db_client, sqlcommenter, dry_run) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (
+                self_.db_client.clone(),
+                self_.sqlcommenter.clone(),
+                self_.dry_run,
+            )
+        });
+        let (querystring, prepared) =
+            SqlCommenter::apply(sqlcommenter.as_ref(), querystring, prepared)?;
+        // Rows are streamed after the statement, it cannot be rolled back in between.
+        if dry_run_rollback(dry_run, &querystring)? {
+            reject_dry_run(true, "fetch_iter with a mutating query")?;
+        }
+
+        let Some(db_client) = db_client else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+        let (_, row_stream) = db_client
+            .execute_stream(querystring, parameters, prepared)
+            .await?;
+        Ok(RowIterator::new(
+            row_stream,
+            db_client,
+            chunk_size,
+            custom_decoders,
+        ))
+    }
+
+    /// Fetch exaclty single row from query.
+    ///
+    /// Method doesn't acquire lock on any structure fields.
+    /// It prepares and caches querystring in the inner Object object.
+    ///
+    /// Then execute the query.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Transaction is not started
+    /// 2) Transaction is done already
+    /// 3) Can not create/retrieve prepared statement
+    /// 4) Can not execute statement
+    /// 5) Query returns more than one row
+    /// 6) Query doesn't finish in `timeout` seconds
+    #[pyo3(signature = (
+        querystring,
+        parameters=None,
+        prepared=None,
+        hints=None,
+        comment=None,
+        timeout=None,
+    ))]
+    pub async fn fetch_row(
+        self_: pyo3::Py<Self>,
+        querystring: String,
+        parameters: Option<pyo3::Py<PyAny>>,
+        prepared: Option<bool>,
+        hints: Option<String>,
+        comment: Option<String>,
+        timeout: Option<f64>,
+    ) -> RustPSQLDriverPyResult<PSQLDriverSinglePyQueryResult> {
+        let (
+            db_client,
+            sqlcommenter,
+            tls_options,
+            ssl_mode,
+            column_codecs,
+            column_masks,
+            redaction_policy,
+            dry_run,
+            query_queue,
+        ) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (
+                self_.db_client.clone(),
+                self_.sqlcommenter.clone(),
+                self_.tls_options.clone(),
+                self_.ssl_mode,
+                self_.column_codecs.clone(),
+                self_.column_masks.clone(),
+                self_.redaction_policy.clone(),
+                self_.dry_run,
+                self_.query_queue.clone(),
+            )
+        });
+        let (querystring, prepared) =
+            SqlCommenter::apply(sqlcommenter.as_ref(), querystring, prepared)?;
+        let (querystring, prepared) = annotate_query(querystring, hints, comment, prepared)?;
+        let redact = RedactionPolicy::error_redactor(
+            redaction_policy.as_ref(),
+            &querystring,
+            parameters.as_ref(),
+        );
+        let rollback = dry_run_rollback(dry_run, &querystring)?;
+
+        if let Some(db_client) = db_client {
+            let cancel_guard = CancelOnDrop::new(db_client.clone(), tls_options, ssl_mode);
+            return query_queue
+                .run(run_dry(
+                    &db_client,
+                    rollback,
+                    false,
+                    run_cancellable(
+                        db_client.fetch_row(querystring, parameters, prepared),
+                        cancel_guard,
+                        timeout,
+                    ),
+                ))
+                .await
+                .map_err(redact)
+                .map(|result| {
+                    result
+                        .with_column_codecs(column_codecs)
+                        .with_column_masks(column_masks)
+                });
+        }
+
+        Err(RustPSQLDriverError::ConnectionClosedError)
+    }
+
+    /// Execute querystring with parameters and return first value in the first row.
+    ///
+    /// It converts incoming parameters to rust readable,
+    /// executes query with them and returns first row of response.
+    ///
+    /// # Errors
+    ///
+    /// May return Err Result if:
+    /// 1) Cannot convert python parameters
+    /// 2) Cannot execute querystring.
+    /// 3) Query returns more than one row
+    /// 4) Query doesn't finish in `timeout` seconds
+    #[pyo3(signature = (
+        querystring,
+        parameters=None,
+        prepared=None,
+        hints=None,
+        comment=None,
+        timeout=None,
+    ))]
+    pub async fn fetch_val<'a>(
+        self_: pyo3::Py<Self>,
+        querystring: String,
+        parameters: Option<pyo3::Py<PyAny>>,
+        prepared: Option<bool>,
+        hints: Option<String>,
+        comment: Option<String>,
+        timeout: Option<f64>,
+    ) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        let (
+            db_client,
+            sqlcommenter,
+            tls_options,
+            ssl_mode,
+            redaction_policy,
+            dry_run,
+            query_queue,
+        ) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (
+                self_.db_client.clone(),
+                self_.sqlcommenter.clone(),
+                self_.tls_options.clone(),
+                self_.ssl_mode,
+                self_.redaction_policy.clone(),
+                self_.dry_run,
+                self_.query_queue.clone(),
+            )
+        });
+        let (querystring, prepared) =
+            SqlCommenter::apply(sqlcommenter.as_ref(), querystring, prepared)?;
+        let (querystring, prepared) = annotate_query(querystring, hints, comment, prepared)?;
+        let redact = RedactionPolicy::error_redactor(
+            redaction_policy.as_ref(),
+            &querystring,
+            parameters.as_ref(),
+        );
+        let rollback = dry_run_rollback(dry_run, &querystring)?;
+
+        if let Some(db_client) = db_client {
+            let cancel_guard = CancelOnDrop::new(db_client.clone(), tls_options, ssl_mode);
+            return query_queue
+                .run(run_dry(
+                    &db_client,
+                    rollback,
+                    false,
+                    run_cancellable(
+                        db_client.fetch_val(querystring, parameters, prepared),
+                        cancel_guard,
+                        timeout,
+                    ),
+                ))
+                .await
+                .map_err(redact);
+        }
+
+        Err(RustPSQLDriverError::ConnectionClosedError)
+    }
+
+    /// Execute querystring and write numeric columns into passed buffers.
+    ///
+    /// All rows of the result are received first,
+    /// then values are decoded directly into the buffers
+    /// without creating Python objects for every row.
+    /// `out` is either a dict with column names as keys
+    /// or a sequence of buffers in the column order.
+    /// Returns number of written rows.
+    ///
+    /// # Errors
+    ///
+    /// May return Err Result if:
+    /// 1) Cannot convert python parameters
+    /// 2) Cannot execute querystring
+    /// 3) Column is not numeric or buffer is too small
+    #[pyo3(signature = (querystring, parameters=None, *, out, prepared=None))]
+    pub async fn fetch_into(
+        self_: pyo3::Py<Self>,
+        querystring: String,
+        parameters: Option<pyo3::Py<PyAny>>,
+        out: pyo3::Py<PyAny>,
+        prepared: Option<bool>,
+    ) -> RustPSQLDriverPyResult<usize> {
+        let (db_client, dry_run) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (self_.db_client.clone(), self_.dry_run)
+        });
+        let rollback = dry_run_rollback(dry_run, &querystring)?;
+
+        if let Some(db_client) = db_client {
+            let result = run_dry(
+                &db_client,
+                rollback,
+                false,
+                db_client.execute(querystring, parameters, prepared),
+            )
+            .await?;
+            return Python::with_gil(|gil| fill_column_buffers(gil, result.rows(), out.bind(gil)));
+        }
+
+        Err(RustPSQLDriverError::ConnectionClosedError)
+    }
+
+    /// Execute querystring and return its only column as numpy array.
+    ///
+    /// Numeric values are decoded directly into the array
+    /// without creating Python objects for every row.
+    /// Text values are returned as array of fixed-width unicode strings.
+    ///
+    /// # Errors
+    ///
+    /// May return Err Result if:
+    /// 1) Cannot convert python parameters
+    /// 2) Cannot execute querystring
+    /// 3) Query returned not one column or column has unsupported type
+    #[pyo3(signature = (querystring, parameters=None, dtype=None, prepared=None))]
+    pub async fn fetch_column_np(
+        self_: pyo3::Py<Self>,
+        querystring: String,
+        parameters: Option<pyo3::Py<PyAny>>,
+        dtype: Option<pyo3::Py<PyAny>>,
+        prepared: Option<bool>,
+    ) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        let (db_client, dry_run) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (self_.db_client.clone(), self_.dry_run)
+        });
+        let rollback = dry_run_rollback(dry_run, &querystring)?;
+
+        if let Some(db_client) = db_client {
+            let result = run_dry(
+                &db_client,
+                rollback,
+                false,
+                db_client.execute(querystring, parameters, prepared),
+            )
+            .await?;
+            return Python::with_gil(|gil| fetch_column_array(gil, result.rows(), dtype));
+        }
+
+        Err(RustPSQLDriverError::ConnectionClosedError)
+    }
+
+    /// Return schema of the columns the querystring returns.
+    ///
+    /// Statement is only described, it isn't executed.
+    /// JSON Schema describes the row as object,
+    /// Arrow schema is `pyarrow.Schema` of the result.
+    ///
+    /// # Errors
+    ///
+    /// May return Err Result if:
+    /// 1) Cannot prepare statement
+    /// 2) Column names aren't unique for JSON Schema
+    /// 3) pyarrow cannot be imported for Arrow schema
+    #[pyo3(signature = (querystring, format=ResultSchemaFormat::JsonSchema, title=None))]
+    pub async fn result_schema(
+        self_: pyo3::Py<Self>,
+        querystring: String,
+        format: ResultSchemaFormat,
+        title: Option<String>,
+    ) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        let db_client = pyo3::Python::with_gil(|gil| self_.borrow(gil).db_client.clone());
+
+        if let Some(db_client) = db_client {
+            let statement = db_client.prepare_cached(&querystring).await?;
+            return Python::with_gil(|gil| {
+                build_result_schema(gil, statement.columns(), format, title)
+            });
+        }
+
+        Err(RustPSQLDriverError::ConnectionClosedError)
+    }
+
+    /// Return planner's estimate of the number of rows returned by the querystring.
+    ///
+    /// It executes `EXPLAIN (FORMAT JSON)` for the querystring
+    /// and extracts row estimate of the top plan node.
+    ///
+    /// # Errors
+    ///
+    /// May return Err Result if:
+    /// 1) Cannot convert python parameters
+    /// 2) Cannot execute `EXPLAIN`
+    /// 3) Plan has no row estimate
+    #[pyo3(signature = (querystring, parameters=None))]
+    pub async fn estimate_count(
+        self_: pyo3::Py<Self>,
+        querystring: String,
+        parameters: Option<pyo3::Py<PyAny>>,
+    ) -> RustPSQLDriverPyResult<i64> {
+        let db_client = pyo3::Python::with_gil(|gil| self_.borrow(gil).db_client.clone());
+
+        if let Some(db_client) = db_client {
+            return db_client.estimate_count(querystring, parameters).await;
+        }
+
+        Err(RustPSQLDriverError::ConnectionClosedError)
+    }
+
+    /// Return `PostgreSQL` query identifier of the querystring.
+    ///
+    /// It executes `EXPLAIN (VERBOSE)` for the querystring and extracts
+    /// query identifier, the same as `queryid` in `pg_stat_statements`.
+    /// Returns None if `compute_query_id` is disabled.
+    ///
+    /// # Errors
+    ///
+    /// May return Err Result if:
+    /// 1) Cannot convert python parameters
+    /// 2) Cannot execute `EXPLAIN`
+    #[pyo3(signature = (querystring, parameters=None))]
+    pub async fn query_id(
+        self_: pyo3::Py<Self>,
+        querystring: String,
+        parameters: Option<pyo3::Py<PyAny>>,
+    ) -> RustPSQLDriverPyResult<Option<i64>> {
+        let db_client = pyo3::Python::with_gil(|gil| self_.borrow(gil).db_client.clone());
+
+        if let Some(db_client) = db_client {
+            return db_client.query_id(querystring, parameters).await;
+        }
+
+        Err(RustPSQLDriverError::ConnectionClosedError)
+    }
+
+    /// Read current values of the settings.
+    ///
+    /// All settings are read in one round trip,
+    /// value is None if setting doesn't exist.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Connection is closed
+    /// 2) Cannot execute query
+    pub async fn capture_settings(
+        self_: pyo3::Py<Self>,
+        names: Vec<String>,
+    ) -> RustPSQLDriverPyResult<HashMap<String, Option<String>>> {
+        let db_client = pyo3::Python::with_gil(|gil| self_.borrow(gil).db_client.clone());
+
+        if let Some(db_client) = db_client {
+            return db_client.capture_settings(names).await;
+        }
+
+        Err(RustPSQLDriverError::ConnectionClosedError)
+    }
+
+    /// Apply settings captured with `capture_settings`.
+    ///
+    /// All settings are applied in one round trip,
+    /// settings which didn't exist at capture time are skipped.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Connection is closed
+    /// 2) Cannot execute query
+    pub async fn restore_settings(
+        self_: pyo3::Py<Self>,
+        snapshot: HashMap<String, Option<String>>,
+    ) -> RustPSQLDriverPyResult<()> {
+        let db_client = pyo3::Python::with_gil(|gil| self_.borrow(gil).db_client.clone());
+
+        if let Some(db_client) = db_client {
+            return db_client.restore_settings(snapshot).await;
+        }
+
+        Err(RustPSQLDriverError::ConnectionClosedError)
+    }
+
+    /// Return async context manager which tags the connection
+    /// with the request ID for the duration of the block.
+    ///
+    /// By default tag is appended to `application_name`, so it's shown
+    /// in `pg_stat_activity` and server logs, any other setting
+    /// (e.g. custom `app.request_id`) is set to the tag itself.
+    /// Previous value is restored on exit.
+    ///
+    /// # Errors
+    /// May return Err Result if connection is closed.
+    #[pyo3(signature = (tag, setting=None))]
+    pub fn annotate(
+        &self,
+        tag: String,
+        setting: Option<String>,
+    ) -> RustPSQLDriverPyResult<ConnectionAnnotation> {
+        let Some(db_client) = &self.db_client else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+
+        Ok(ConnectionAnnotation::new(
+            db_client.clone(),
+            setting.unwrap_or_else(|| DEFAULT_ANNOTATION_SETTING.into()),
+            tag,
+        ))
+    }
+
+    /// Register Python `Enum` for the `PostgreSQL` enum type.
+    ///
+    /// Labels of the type are decoded into members of `cls`
+    /// (arrays too), members are encoded back into labels.
+    /// Registration is shared by all connections.
+    ///
+    /// # Errors
+    /// May return Err Result if `cls` isn't a subclass of `enum.Enum`.
+    #[pyo3(signature = (type_name, cls))]
+    pub fn register_enum(
+        &self,
+        py: Python<'_>,
+        type_name: &str,
+        cls: Option<Py<PyAny>>,
+    ) -> RustPSQLDriverPyResult<()> {
+        register_enum(py, type_name, cls)
+    }
+
+    /// Execute DDL statements with short `lock_timeout` and retries.
+    ///
+    /// Every statement is executed separately, statement which
+    /// cannot acquire lock within `lock_timeout_ms` is retried
+    /// up to `retries` times with exponential backoff.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Connection is closed
+    /// 2) Statement failed or couldn't acquire lock after all retries
+    #[pyo3(signature = (statements, lock_timeout_ms=1000, retries=3))]
+    pub async fn run_ddl(
+        self_: pyo3::Py<Self>,
+        statements: Vec<String>,
+        lock_timeout_ms: u64,
+        retries: u32,
+    ) -> RustPSQLDriverPyResult<()> {
+        let (db_client, dry_run) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (self_.db_client.clone(), self_.dry_run)
+        });
+        reject_dry_run(dry_run, "run_ddl")?;
+
+        if let Some(db_client) = db_client {
+            return db_client
+                .run_ddl(statements, Duration::from_millis(lock_timeout_ms), retries)
+                .await;
+        }
+
+        Err(RustPSQLDriverError::ConnectionClosedError)
+    }
+
+    /// Run `VACUUM` and/or `ANALYZE` on the table.
+    ///
+    /// Table name is quoted, `options` are added to the command
+    /// in parentheses, like `{"parallel": 4, "skip_locked": True}`.
+    /// Command is sent outside of the transaction block.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Connection is closed
+    /// 2) Neither `vacuum` nor `analyze` is set
+    /// 3) Option isn't supported by the command
+    /// 4) `VACUUM` is run inside of the transaction block
+    /// 5) Cannot execute the command
+    #[pyo3(signature = (
+        table_name,
+        vacuum=true,
+        analyze=true,
+        *,
+        schema_name=None,
+        options=None,
+    ))]
+    pub async fn maintenance(
+        self_: pyo3::Py<Self>,
+        table_name: String,
+        vacuum: bool,
+        analyze: bool,
+        schema_name: Option<String>,
+        options: Option<Py<PyDict>>,
+    ) -> RustPSQLDriverPyResult<()> {
+        let (db_client, dry_run) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (self_.db_client.clone(), self_.dry_run)
+        });
+        let Some(db_client) = db_client else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+        reject_dry_run(dry_run, "maintenance")?;
+
+        let options = match options {
+            Some(options) => {
+                pyo3::Python::with_gil(|gil| extract_maintenance_options(options.bind(gil)))?
+            }
+            None => vec![],
+        };
+        let querystring = build_maintenance_command(
+            &copy_target(&table_name, None, schema_name),
+            vacuum,
+            analyze,
+            &options,
+        )?;
+        run_maintenance(&db_client, &querystring, vacuum).await
+    }
+
+    /// Refresh materialized view.
+    ///
+    /// View is checked to exist first, its name is quoted.
+    /// If `progress_callback` is passed, it's called every `poll_interval`
+    /// seconds with activity of the refreshing backend,
+    /// activity is read by the separate connection.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Connection is closed
+    /// 2) View doesn't exist
+    /// 3) View is locked and `wait` is false
+    /// 4) Cannot refresh the view
+    /// 5) Progress callback raises an exception
+    #[pyo3(signature = (
+        name,
+        concurrently=true,
+        wait=true,
+        *,
+        schema_name=None,
+        with_data=true,
+        progress_callback=None,
+        poll_interval=1.0,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn refresh_materialized_view(
+        self_: pyo3::Py<Self>,
+        name: String,
+        concurrently: bool,
+        wait: bool,
+        schema_name: Option<String>,
+        with_data: bool,
+        progress_callback: Option<Py<PyAny>>,
+        poll_interval: f64,
+    ) -> RustPSQLDriverPyResult<()> {
+        let (db_client, pg_config, tls_options, ssl_mode, dry_run) =
+            pyo3::Python::with_gil(|gil| {
+                let self_ = self_.borrow(gil);
+                (
+                    self_.db_client.clone(),
+                    self_.pg_config.clone(),
+                    self_.tls_options.clone(),
+                    self_.ssl_mode,
+                    self_.dry_run,
+                )
+            });
+        let Some(db_client) = db_client else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+        reject_dry_run(dry_run, "refresh_materialized_view")?;
+
+        let Some(progress_callback) = progress_callback else {
+            return refresh_materialized_view(
+                &db_client,
+                &name,
+                schema_name.as_deref(),
+                concurrently,
+                with_data,
+                wait,
+            )
+            .await;
+        };
+        let poll_interval = Duration::try_from_secs_f64(poll_interval).map_err(|_| {
+            RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "Poll interval must be non-negative number of seconds, got {poll_interval}"
+            ))
+        })?;
+        let backend_pid: i32 = db_client
+            .query_one("SELECT pg_backend_pid()", &[])
+            .await?
+            .try_get(0)?;
+
+        tokio_runtime()
+            .spawn(async move {
+                let refresh = refresh_materialized_view(
+                    &db_client,
+                    &name,
+                    schema_name.as_deref(),
+                    concurrently,
+                    with_data,
+                    wait,
+                );
+                let progress = watch_refresh_progress(
+                    pg_config,
+                    tls_options,
+                    ssl_mode,
+                    backend_pid,
+                    poll_interval,
+                    progress_callback,
+                );
+                pin_mut!(refresh, progress);
+                match future::select(refresh, progress).await {
+                    Either::Left((refresh_result, _)) => refresh_result,
+                    Either::Right((progress_result, refresh)) => {
+                        // Refresh isn't interrupted by the failed callback.
+                        refresh.await?;
+                        progress_result
+                    }
+                }
+            })
+            .await?
+    }
+
+    /// Create new transaction object.
+    ///
+    /// If `snapshot` is passed, transaction starts with the snapshot
+    /// exported by another transaction.
+    ///
+    /// # Errors
+    /// May return Err Result if db_client is None.
+    #[pyo3(signature = (
+        isolation_level=None,
+        read_variant=None,
         deferrable=None,
         synchronous_commit=None,
+        snapshot=None,
     ))]
     pub fn transaction(
         &self,
@@ -365,73 +1793,371 @@ impl Connection {
         read_variant: Option<ReadVariant>,
         deferrable: Option<bool>,
         synchronous_commit: Option<SynchronousCommit>,
+        snapshot: Option<String>,
     ) -> RustPSQLDriverPyResult<Transaction> {
         if let Some(db_client) = &self.db_client {
             return Ok(Transaction::new(
                 db_client.clone(),
                 self.pg_config.clone(),
-                false,
-                false,
                 isolation_level,
                 synchronous_commit,
                 read_variant,
                 deferrable,
-                HashSet::new(),
-            ));
+                vec![],
+            )
+            .with_sqlcommenter(self.sqlcommenter.clone())
+            .with_isolation_advisor(self.isolation_advisor.clone())
+            .with_column_codecs(self.column_codecs.clone())
+            .with_redaction_policy(self.redaction_policy.clone())
+            .with_column_masks(self.column_masks.clone())
+            .with_snapshot(snapshot)
+            .with_dry_run(self.dry_run)
+            .with_query_queue(self.query_queue.clone()));
+        }
+
+        Err(RustPSQLDriverError::ConnectionClosedError)
+    }
+
+    /// Create new pipeline of queries.
+    ///
+    /// Queries queued in the pipeline are sent together
+    /// on `flush()` or on exit from `async with`.
+    ///
+    /// # Errors
+    /// May return Err Result if db_client is None.
+    pub fn pipeline(&self) -> RustPSQLDriverPyResult<Pipeline> {
+        reject_dry_run(self.dry_run, "pipeline")?;
+        if let Some(db_client) = &self.db_client {
+            return Ok(Pipeline::new(db_client.clone(), self.sqlcommenter.clone()));
         }
 
         Err(RustPSQLDriverError::ConnectionClosedError)
     }
 
+    /// Create handle of the connection for several asyncio tasks.
+    ///
+    /// Operations of the handle are executed one by one
+    /// in the order they are called.
+    /// Every handle of the connection uses the same queue.
+    ///
+    /// # Errors
+    /// May return Err Result if connection is closed.
+    pub fn share(
+        self_: Py<Self>,
+        py: Python<'_>,
+    ) -> RustPSQLDriverPyResult<SharedConnectionHandle> {
+        let shared_queue = {
+            let connection = self_.borrow(py);
+            if connection.db_client.is_none() {
+                return Err(RustPSQLDriverError::ConnectionClosedError);
+            }
+            connection.shared_queue.clone()
+        };
+        Ok(SharedConnectionHandle::new(self_, shared_queue))
+    }
+
     /// Create new cursor object.
     ///
+    /// Cursor gets unique name if `cursor_name` isn't passed.
+    /// Created cursor is remembered by the connection,
+    /// open cursors are closed when connection goes back to the pool.
+    ///
     /// # Errors
-    /// May return Err Result if db_client is None.
+    /// May return Err Result if:
+    /// 1) Connection is closed
+    /// 2) Cursor with the same name is open
     #[pyo3(signature = (
         querystring,
         parameters=None,
         fetch_number=None,
         scroll=None,
         prepared=None,
+        cursor_name=None,
+        hold=None,
     ))]
+    #[allow(clippy::too_many_arguments)]
     pub fn cursor(
-        &self,
+        &mut self,
+        py: Python<'_>,
         querystring: String,
         parameters: Option<Py<PyAny>>,
         fetch_number: Option<usize>,
         scroll: Option<bool>,
         prepared: Option<bool>,
-    ) -> RustPSQLDriverPyResult<Cursor> {
-        if let Some(db_client) = &self.db_client {
-            return Ok(Cursor::new(
+        cursor_name: Option<String>,
+        hold: Option<bool>,
+    ) -> RustPSQLDriverPyResult<Py<Cursor>> {
+        let Some(db_client) = &self.db_client else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+        if dry_run_rollback(self.dry_run, &querystring)? {
+            reject_dry_run(true, "cursor with a mutating query")?;
+        }
+
+        self.cursors.retain(|cursor| {
+            cursor
+                .try_borrow(py)
+                .map_or(true, |cursor| !cursor.is_closed())
+        });
+        if let Some(cursor_name) = &cursor_name {
+            let is_name_taken = self.cursors.iter().any(|cursor| {
+                cursor
+                    .try_borrow(py)
+                    .is_ok_and(|cursor| cursor.cursor_name() == *cursor_name)
+            });
+            if is_name_taken {
+                return Err(RustPSQLDriverError::CursorStartError(format!(
+                    "Cursor with name {cursor_name} already exists on the connection"
+                )));
+            }
+        }
+
+        let cursor = Py::new(
+            py,
+            Cursor::new(
                 db_client.clone(),
                 self.pg_config.clone(),
                 querystring,
                 parameters,
-                "cur_name".into(),
+                prepare_cursor_name(cursor_name)?,
                 fetch_number.unwrap_or(10),
                 scroll,
+                hold,
                 prepared,
-            ));
+            ),
+        )?;
+        // Closed cursors are forgotten, so the list doesn't grow with every cursor.
+        self.cursors.retain(|cursor| {
+            cursor
+                .try_borrow(py)
+                .map_or(true, |cursor| !cursor.is_closed())
+        });
+        self.cursors.push(cursor.clone_ref(py));
+        Ok(cursor)
+    }
+
+    /// Return all open cursors created by this connection.
+    ///
+    /// Cursor is open if it was started and wasn't closed yet.
+    /// Cursors that are busy right now are considered open.
+    #[must_use]
+    pub fn cursors(&self, py: Python<'_>) -> Vec<Py<Cursor>> {
+        self.cursors
+            .iter()
+            .filter(|cursor| {
+                cursor
+                    .try_borrow(py)
+                    .map_or(true, |cursor| cursor.is_open())
+            })
+            .map(|cursor| cursor.clone_ref(py))
+            .collect()
+    }
+
+    /// Return state of the prepared statement cache of the connection.
+    ///
+    /// # Errors
+    /// May return Err Result if connection is closed.
+    pub fn statement_cache_info(&self) -> RustPSQLDriverPyResult<StatementCacheInfo> {
+        let Some(db_client) = &self.db_client else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+        Ok(db_client.statement_cache_info())
+    }
+
+    /// Remove all prepared statements from the cache of the connection.
+    ///
+    /// Statements are closed on the server, hit and miss counters are kept.
+    ///
+    /// # Errors
+    /// May return Err Result if connection is closed.
+    pub fn clear_statement_cache(&self) -> RustPSQLDriverPyResult<()> {
+        let Some(db_client) = &self.db_client else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+        db_client.clear_statement_cache();
+        Ok(())
+    }
+
+    /// Return snapshot of the session state for debugging.
+    ///
+    /// It includes activity of the backend, locks held by the session,
+    /// backends blocked by them and memory and temp files usage.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Connection is closed
+    /// 2) Cannot query statistics views
+    pub async fn diagnostics(self_: pyo3::Py<Self>) -> RustPSQLDriverPyResult<SessionDiagnostics> {
+        let db_client = pyo3::Python::with_gil(|gil| self_.borrow(gil).db_client.clone());
+
+        if let Some(db_client) = db_client {
+            return fetch_session_diagnostics(&db_client).await;
+        }
+
+        Err(RustPSQLDriverError::ConnectionClosedError)
+    }
+
+    /// Check is TLS used by the connection.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Connection is closed
+    /// 2) Cannot query `pg_stat_ssl`
+    pub async fn ssl_in_use(self_: pyo3::Py<Self>) -> RustPSQLDriverPyResult<bool> {
+        let db_client = pyo3::Python::with_gil(|gil| self_.borrow(gil).db_client.clone());
+
+        if let Some(db_client) = db_client {
+            let (ssl_in_use, _) = fetch_ssl_status(&db_client).await?;
+            return Ok(ssl_in_use);
+        }
+
+        Err(RustPSQLDriverError::ConnectionClosedError)
+    }
+
+    /// Return negotiated TLS protocol version, like `TLSv1.3`.
+    ///
+    /// Returns None if TLS is not used.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Connection is closed
+    /// 2) Cannot query `pg_stat_ssl`
+    pub async fn ssl_protocol(self_: pyo3::Py<Self>) -> RustPSQLDriverPyResult<Option<String>> {
+        let db_client = pyo3::Python::with_gil(|gil| self_.borrow(gil).db_client.clone());
+
+        if let Some(db_client) = db_client {
+            let (_, ssl_protocol) = fetch_ssl_status(&db_client).await?;
+            return Ok(ssl_protocol);
         }
 
         Err(RustPSQLDriverError::ConnectionClosedError)
     }
 
+    /// Return certificate presented by the server in the TLS handshake
+    /// of this connection.
+    ///
+    /// Certificate is captured when the connection is opened.
+    /// Returns None if TLS is not used by the connection.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Connection is closed
+    /// 2) Cannot query `pg_stat_ssl`
+    pub async fn ssl_peer_certificate(
+        self_: pyo3::Py<Self>,
+    ) -> RustPSQLDriverPyResult<Option<SslCertificate>> {
+        let db_client = pyo3::Python::with_gil(|gil| self_.borrow(gil).db_client.clone());
+
+        let Some(db_client) = db_client else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+
+        let (ssl_in_use, _) = fetch_ssl_status(&db_client).await?;
+        if !ssl_in_use {
+            return Ok(None);
+        }
+
+        fetch_peer_certificate(&db_client).await
+    }
+
+    /// Watch table changes.
+    ///
+    /// Install trigger which sends `NOTIFY` with JSON payload
+    /// on every change of the table and return stream of decoded changes.
+    /// Trigger is installed from the new dedicated connection.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Operations are invalid
+    /// 2) Cannot connect to the database
+    /// 3) Cannot install trigger
+    #[pyo3(signature = (
+        table_name,
+        operations=None,
+        schema_name=None,
+        channel=None,
+        temporary=true,
+    ))]
+    pub async fn watch_table(
+        self_: pyo3::Py<Self>,
+        table_name: String,
+        operations: Option<Vec<String>>,
+        schema_name: Option<String>,
+        channel: Option<String>,
+        temporary: bool,
+    ) -> RustPSQLDriverPyResult<TableWatcher> {
+        let (pg_config, tls_options, ssl_mode, dry_run) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (
+                self_.pg_config.clone(),
+                self_.tls_options.clone(),
+                self_.ssl_mode,
+                self_.dry_run,
+            )
+        });
+        reject_dry_run(dry_run, "watch_table")?;
+
+        TableWatcher::start(
+            pg_config,
+            tls_options,
+            ssl_mode,
+            table_name,
+            schema_name,
+            operations,
+            channel,
+            temporary,
+        )
+        .await
+    }
+
+    /// Listen notifications of the channels.
+    ///
+    /// Return async iterator over notifications,
+    /// they are received by the new dedicated connection.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Channels are empty
+    /// 2) Channels are not strings
+    #[pyo3(signature = (*channels))]
+    pub fn listener(
+        &self,
+        channels: &Bound<'_, PyTuple>,
+    ) -> RustPSQLDriverPyResult<ChannelListener> {
+        ChannelListener::new(
+            self.pg_config.clone(),
+            self.tls_options.clone(),
+            self.ssl_mode,
+            channels.extract::<Vec<String>>()?,
+        )
+    }
+
     #[allow(clippy::needless_pass_by_value)]
     pub fn back_to_pool(self_: pyo3::Py<Self>) {
         pyo3::Python::with_gil(|gil| {
             let mut connection = self_.borrow_mut(gil);
-            if connection.db_client.is_some() {
-                std::mem::take(&mut connection.db_client);
+            let cursor_names = connection.take_open_cursor_names(gil);
+            if let Some(db_client) = connection.db_client.clone() {
+                if !cursor_names.is_empty() {
+                    // Connection goes back to the pool when the cursors are closed.
+                    tokio_runtime().spawn(async move {
+                        close_cursors(&db_client, &cursor_names).await;
+                    });
+                }
             }
+            connection.release_db_client();
         });
     }
 
     /// Perform binary copy to postgres table.
     ///
+    /// If `validate` is true, header, trailer and number of fields
+    /// in every row are checked against the columns of the table
+    /// before the data is sent.
+    ///
     /// # Errors
     /// May return Err Result if cannot get bytes,
+    /// data is invalid,
     /// cannot perform request to the database,
     /// cannot write bytes to the database.
     #[pyo3(signature = (
@@ -439,6 +2165,7 @@ impl Connection {
         table_name,
         columns=None,
         schema_name=None,
+        validate=false,
     ))]
     pub async fn binary_copy_to_table(
         self_: pyo3::Py<Self>,
@@ -446,48 +2173,529 @@ impl Connection {
         table_name: String,
         columns: Option<Vec<String>>,
         schema_name: Option<String>,
+        validate: bool,
+    ) -> RustPSQLDriverPyResult<u64> {
+        let (db_client, dry_run) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (self_.db_client.clone(), self_.dry_run)
+        });
+        let copy_table = copy_target(&table_name, None, schema_name.clone());
+        let validate_columns = columns.clone().filter(|_| validate);
+        let copy_target = copy_target(&table_name, columns, schema_name);
+        let copy_qs = format!("COPY {copy_target} FROM STDIN (FORMAT binary)");
+
+        if let Some(db_client) = db_client {
+            let mut psql_bytes = Python::with_gil(|gil| copy_source_bytes(gil, &source))?;
+            if validate {
+                validate_binary_copy(
+                    &db_client,
+                    &copy_table,
+                    validate_columns.as_deref(),
+                    &psql_bytes,
+                )
+                .await?;
+            }
+
+            return run_dry(&db_client, dry_run, false, async {
+                let sink = db_client.copy_in(&copy_qs).await?;
+                let writer = BinaryCopyInWriter::new_empty_buffer(sink, &[]);
+                pin_mut!(writer);
+                writer.as_mut().write_raw_bytes(&mut psql_bytes).await?;
+                let rows_created = writer.as_mut().finish_empty().await?;
+                Ok(rows_created)
+            })
+            .await;
+        }
+
+        Ok(0)
+    }
+
+    /// Perform `CSV` or `TEXT` copy to postgres table.
+    ///
+    /// Execute `COPY table_name (<columns>) FROM STDIN` with the format options
+    /// and send `source` as is.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Connection is closed
+    /// 2) Cannot get bytes from `source`
+    /// 3) Options cannot be used with the format
+    /// 4) Cannot execute copy statement or data is invalid
+    #[pyo3(signature = (
+        source,
+        table_name,
+        columns=None,
+        schema_name=None,
+        format=CopyCommandFormat::CSV,
+        delimiter=None,
+        null=None,
+        header=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn copy_to_table(
+        self_: pyo3::Py<Self>,
+        source: Py<PyAny>,
+        table_name: String,
+        columns: Option<Vec<String>>,
+        schema_name: Option<String>,
+        format: CopyCommandFormat,
+        delimiter: Option<String>,
+        null: Option<String>,
+        header: Option<bool>,
+    ) -> RustPSQLDriverPyResult<u64> {
+        let (db_client, dry_run) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (self_.db_client.clone(), self_.dry_run)
+        });
+        let Some(db_client) = db_client else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+        let copy_options = format.copy_options(delimiter.as_deref(), null.as_deref(), header)?;
+        let copy_target = copy_target(&table_name, columns, schema_name);
+        let copy_qs = format!("COPY {copy_target} FROM STDIN ({copy_options})");
+
+        let psql_bytes = Python::with_gil(|gil| copy_source_bytes(gil, &source))?;
+        run_dry(&db_client, dry_run, false, async {
+            let sink = db_client.copy_in(&copy_qs).await?;
+            pin_mut!(sink);
+            sink.send(psql_bytes.freeze()).await?;
+            Ok(sink.as_mut().finish().await?)
+        })
+        .await
+    }
+
+    /// Copy records into the table with binary `COPY`.
+    ///
+    /// Records are lists or tuples of values in the order of `columns`,
+    /// values are encoded for the column types, so plain Python ints
+    /// fit into `SMALLINT`/`BIGINT` columns too.
+    /// Types are taken from the table if `types` aren't passed.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Connection is closed
+    /// 2) Table, columns or types don't exist
+    /// 3) Records have different number of values than columns
+    /// 4) Cannot convert python values
+    /// 5) Cannot execute copy statement
+    #[pyo3(signature = (
+        table_name,
+        records,
+        columns=None,
+        types=None,
+        schema_name=None,
+    ))]
+    pub async fn copy_records_to_table(
+        self_: pyo3::Py<Self>,
+        table_name: String,
+        records: Vec<Py<PyAny>>,
+        columns: Option<Vec<String>>,
+        types: Option<Vec<String>>,
+        schema_name: Option<String>,
     ) -> RustPSQLDriverPyResult<u64> {
+        let (db_client, column_codecs, dry_run) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (
+                self_.db_client.clone(),
+                self_.column_codecs.clone(),
+                self_.dry_run,
+            )
+        });
+        let Some(db_client) = db_client else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+
+        let table = copy_target(&table_name, None, schema_name);
+        run_dry(
+            &db_client,
+            dry_run,
+            false,
+            db_client.copy_records(&table, columns, types, records, &column_codecs),
+        )
+        .await
+    }
+
+    /// Perform binary copy from PostgreSQL.
+    ///
+    /// Execute `COPY table_name (<columns>) TO STDOUT (FORMAT binary)`
+    /// and collect the data into `bytes`.
+    /// Table and schema names are quoted, so they are case-sensitive.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Connection is closed
+    /// 2) Cannot execute copy statement
+    #[pyo3(signature = (table_name, columns=None, schema_name=None))]
+    pub async fn binary_copy_from_table(
+        self_: pyo3::Py<Self>,
+        table_name: String,
+        columns: Option<Vec<String>>,
+        schema_name: Option<String>,
+    ) -> RustPSQLDriverPyResult<Py<PyBytes>> {
         let db_client = pyo3::Python::with_gil(|gil| self_.borrow(gil).db_client.clone());
-        let mut table_name = quote_ident(&table_name);
-        if let Some(schema_name) = schema_name {
-            table_name = format!("{}.{}", quote_ident(&schema_name), table_name);
+        let Some(db_client) = db_client else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+        let copy_target = copy_target(&table_name, columns, schema_name);
+        let copy_qs = format!("COPY {copy_target} TO STDOUT (FORMAT binary)");
+
+        let copy_stream = db_client.copy_out(&copy_qs).await?;
+        pin_mut!(copy_stream);
+        let mut psql_bytes = BytesMut::new();
+        while let Some(chunk) = copy_stream.next().await {
+            psql_bytes.extend_from_slice(&chunk?);
         }
 
-        let mut formated_columns = String::default();
-        if let Some(columns) = columns {
-            formated_columns = format!("({})", columns.join(", "));
+        Ok(Python::with_gil(|gil| {
+            PyBytes::new(gil, &psql_bytes).unbind()
+        }))
+    }
+
+    /// Perform `CSV` or `TEXT` copy from PostgreSQL.
+    ///
+    /// Execute `COPY table_name (<columns>) TO STDOUT` with the format options
+    /// and collect the data into `bytes`.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Connection is closed
+    /// 2) Options cannot be used with the format
+    /// 3) Cannot execute copy statement
+    #[pyo3(signature = (
+        table_name,
+        columns=None,
+        schema_name=None,
+        format=CopyCommandFormat::CSV,
+        delimiter=None,
+        null=None,
+        header=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn copy_from_table(
+        self_: pyo3::Py<Self>,
+        table_name: String,
+        columns: Option<Vec<String>>,
+        schema_name: Option<String>,
+        format: CopyCommandFormat,
+        delimiter: Option<String>,
+        null: Option<String>,
+        header: Option<bool>,
+    ) -> RustPSQLDriverPyResult<Py<PyBytes>> {
+        let db_client = pyo3::Python::with_gil(|gil| self_.borrow(gil).db_client.clone());
+        let Some(db_client) = db_client else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+        let copy_options = format.copy_options(delimiter.as_deref(), null.as_deref(), header)?;
+        let copy_target = copy_target(&table_name, columns, schema_name);
+        let copy_qs = format!("COPY {copy_target} TO STDOUT ({copy_options})");
+
+        let copy_stream = db_client.copy_out(&copy_qs).await?;
+        pin_mut!(copy_stream);
+        let mut psql_bytes = BytesMut::new();
+        while let Some(chunk) = copy_stream.next().await {
+            psql_bytes.extend_from_slice(&chunk?);
         }
 
-        let copy_qs = format!("COPY {table_name}{formated_columns} FROM STDIN (FORMAT binary)");
+        Ok(Python::with_gil(|gil| {
+            PyBytes::new(gil, &psql_bytes).unbind()
+        }))
+    }
 
-        if let Some(db_client) = db_client {
-            let mut psql_bytes: BytesMut = Python::with_gil(|gil| {
-                let possible_py_buffer: Result<PyBuffer<u8>, PyErr> =
-                    source.extract::<PyBuffer<u8>>(gil);
-                if let Ok(py_buffer) = possible_py_buffer {
-                    let vec_buf = py_buffer.to_vec(gil)?;
-                    return Ok(BytesMut::from(vec_buf.as_slice()));
-                }
+    /// Perform `CSV` or `TEXT` copy from PostgreSQL chunk by chunk.
+    ///
+    /// Execute `COPY table_name (<columns>) TO STDOUT` with the format options
+    /// and return async iterator over chunks of the data.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Connection is closed
+    /// 2) Options cannot be used with the format
+    /// 3) Cannot execute copy statement
+    #[pyo3(signature = (
+        table_name,
+        columns=None,
+        schema_name=None,
+        format=CopyCommandFormat::CSV,
+        delimiter=None,
+        null=None,
+        header=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn copy_from_table_chunks(
+        self_: pyo3::Py<Self>,
+        table_name: String,
+        columns: Option<Vec<String>>,
+        schema_name: Option<String>,
+        format: CopyCommandFormat,
+        delimiter: Option<String>,
+        null: Option<String>,
+        header: Option<bool>,
+    ) -> RustPSQLDriverPyResult<CopyOutChunks> {
+        let db_client = pyo3::Python::with_gil(|gil| self_.borrow(gil).db_client.clone());
+        let Some(db_client) = db_client else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+        let copy_options = format.copy_options(delimiter.as_deref(), null.as_deref(), header)?;
+        let copy_target = copy_target(&table_name, columns, schema_name);
+        let copy_qs = format!("COPY {copy_target} TO STDOUT ({copy_options})");
 
-                if let Ok(py_bytes) = source.call_method0(gil, "getvalue") {
-                    if let Ok(bytes) = py_bytes.extract::<Vec<u8>>(gil) {
-                        return Ok(BytesMut::from(bytes.as_slice()));
-                    }
-                }
+        let copy_stream = db_client.copy_out(&copy_qs).await?;
+        Ok(CopyOutChunks::new(copy_stream, db_client))
+    }
 
-                Err(RustPSQLDriverError::PyToRustValueConversionError(
-                    "source must be bytes or support Buffer protocol".into(),
-                ))
-            })?;
+    /// Perform binary copy from PostgreSQL chunk by chunk.
+    ///
+    /// Execute `COPY table_name (<columns>) TO STDOUT (FORMAT binary)`
+    /// and return async iterator over chunks of the data.
+    /// Table and schema names are quoted, so they are case-sensitive.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Connection is closed
+    /// 2) Cannot execute copy statement
+    #[pyo3(signature = (table_name, columns=None, schema_name=None))]
+    pub async fn binary_copy_from_table_chunks(
+        self_: pyo3::Py<Self>,
+        table_name: String,
+        columns: Option<Vec<String>>,
+        schema_name: Option<String>,
+    ) -> RustPSQLDriverPyResult<CopyOutChunks> {
+        let db_client = pyo3::Python::with_gil(|gil| self_.borrow(gil).db_client.clone());
+        let Some(db_client) = db_client else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+        let copy_target = copy_target(&table_name, columns, schema_name);
+        let copy_qs = format!("COPY {copy_target} TO STDOUT (FORMAT binary)");
 
-            let sink = db_client.copy_in(&copy_qs).await?;
-            let writer = BinaryCopyInWriter::new_empty_buffer(sink, &[]);
-            pin_mut!(writer);
-            writer.as_mut().write_raw_bytes(&mut psql_bytes).await?;
-            let rows_created = writer.as_mut().finish_empty().await?;
-            return Ok(rows_created);
-        }
+        let copy_stream = db_client.copy_out(&copy_qs).await?;
+        Ok(CopyOutChunks::new(copy_stream, db_client))
+    }
+}
 
-        Ok(0)
+/// Open single connection to the database without the pool.
+///
+/// Connection parameters are the same as in `ConnectionPool`,
+/// TLS parameters of `dsn` are used if they aren't passed explicitly.
+/// Connection is closed when it's returned with `back_to_pool`
+/// or the `async with` block is over.
+///
+/// # Errors
+/// May return Err Result if:
+/// 1) Connection parameters are invalid
+/// 2) Cannot connect to the database
+#[pyfunction]
+#[pyo3(signature = (
+    dsn=None,
+    username=None,
+    password=None,
+    host=None,
+    hosts=None,
+    port=None,
+    ports=None,
+    db_name=None,
+    target_session_attrs=None,
+    options=None,
+    application_name=None,
+    connect_timeout_sec=None,
+    connect_timeout_nanosec=None,
+    tcp_user_timeout_sec=None,
+    tcp_user_timeout_nanosec=None,
+    keepalives=None,
+    keepalives_idle_sec=None,
+    keepalives_idle_nanosec=None,
+    keepalives_interval_sec=None,
+    keepalives_interval_nanosec=None,
+    keepalives_retries=None,
+    load_balance_hosts=None,
+    ssl_mode=None,
+    ca_file=None,
+    ssl_cert=None,
+    ssl_key=None,
+    verify_hostname=None,
+    server_settings=None,
+))]
+#[allow(clippy::too_many_arguments)]
+pub async fn open_connection(
+    dsn: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    host: Option<String>,
+    hosts: Option<Vec<String>>,
+    port: Option<u16>,
+    ports: Option<Vec<u16>>,
+    db_name: Option<String>,
+    target_session_attrs: Option<TargetSessionAttrs>,
+    options: Option<String>,
+    application_name: Option<String>,
+    connect_timeout_sec: Option<u64>,
+    connect_timeout_nanosec: Option<u32>,
+    tcp_user_timeout_sec: Option<u64>,
+    tcp_user_timeout_nanosec: Option<u32>,
+    keepalives: Option<bool>,
+    keepalives_idle_sec: Option<u64>,
+    keepalives_idle_nanosec: Option<u32>,
+    keepalives_interval_sec: Option<u64>,
+    keepalives_interval_nanosec: Option<u32>,
+    keepalives_retries: Option<u32>,
+    load_balance_hosts: Option<LoadBalanceHosts>,
+    ssl_mode: Option<SslMode>,
+    ca_file: Option<String>,
+    ssl_cert: Option<String>,
+    ssl_key: Option<String>,
+    verify_hostname: Option<bool>,
+    server_settings: Option<HashMap<String, String>>,
+) -> RustPSQLDriverPyResult<Connection> {
+    let dsn_options = match &dsn {
+        Some(dsn) => parse_dsn(dsn)?.options,
+        None => DsnOptions::default(),
+    };
+    let ssl_mode = ssl_mode.or(dsn_options.ssl_mode);
+    let mut pg_config = build_connection_config(
+        dsn,
+        username,
+        password,
+        host,
+        hosts,
+        port,
+        ports,
+        db_name,
+        target_session_attrs,
+        options,
+        application_name,
+        connect_timeout_sec,
+        connect_timeout_nanosec,
+        tcp_user_timeout_sec,
+        tcp_user_timeout_nanosec,
+        keepalives,
+        keepalives_idle_sec,
+        keepalives_idle_nanosec,
+        keepalives_interval_sec,
+        keepalives_interval_nanosec,
+        keepalives_retries,
+        load_balance_hosts,
+        ssl_mode,
+    )?;
+    if let Some(server_settings) = server_settings {
+        apply_server_settings(&mut pg_config, &server_settings)?;
+    }
+    let tls_options = TlsOptions {
+        ca_file: ca_file.or(dsn_options.ca_file),
+        ssl_cert: ssl_cert.or(dsn_options.ssl_cert),
+        ssl_key: ssl_key.or(dsn_options.ssl_key),
+        verify_hostname,
+    };
+
+    let client = connect_single(pg_config.clone(), &tls_options, &ssl_mode).await?;
+    Ok(Connection::new(
+        Some(Arc::new(PsqlpyConnection::single(client))),
+        None,
+        Arc::new(pg_config),
+        tls_options,
+        ssl_mode,
+    ))
+}
+
+/// Close cursors left open on the connection.
+///
+/// Errors are ignored, cursor may be already closed by the end of its transaction.
+async fn close_cursors(db_client: &PsqlpyConnection, cursor_names: &[String]) {
+    for cursor_name in cursor_names {
+        let _ = db_client
+            .batch_execute(&format!("CLOSE {}", quote_ident(cursor_name)))
+            .await;
+    }
+}
+
+/// Return quoted table name with the columns for `COPY` and `UPDATE` statements.
+pub(crate) fn copy_target(
+    table_name: &str,
+    columns: Option<Vec<String>>,
+    schema_name: Option<String>,
+) -> String {
+    let mut table_name = quote_ident(table_name);
+    if let Some(schema_name) = schema_name {
+        table_name = format!("{}.{}", quote_ident(&schema_name), table_name);
+    }
+
+    let mut formated_columns = String::default();
+    if let Some(columns) = columns {
+        formated_columns = format!("({})", columns.join(", "));
     }
+    format!("{table_name}{formated_columns}")
+}
+
+/// Read data for `COPY ... FROM STDIN` from Python object.
+///
+/// `source` can be `bytes`, `str`, object with Buffer protocol,
+/// `BytesIO`/`StringIO` or file opened for reading.
+pub(crate) fn copy_source_bytes(
+    gil: Python<'_>,
+    source: &Py<PyAny>,
+) -> RustPSQLDriverPyResult<BytesMut> {
+    let possible_py_buffer: Result<PyBuffer<u8>, PyErr> = source.extract::<PyBuffer<u8>>(gil);
+    if let Ok(py_buffer) = possible_py_buffer {
+        let vec_buf = py_buffer.to_vec(gil)?;
+        return Ok(BytesMut::from(vec_buf.as_slice()));
+    }
+    if let Ok(string) = source.extract::<String>(gil) {
+        return Ok(BytesMut::from(string.as_bytes()));
+    }
+
+    for method_name in ["getvalue", "read"] {
+        let Ok(content) = source.call_method0(gil, method_name) else {
+            continue;
+        };
+        if let Ok(bytes) = content.extract::<Vec<u8>>(gil) {
+            return Ok(BytesMut::from(bytes.as_slice()));
+        }
+        if let Ok(string) = content.extract::<String>(gil) {
+            return Ok(BytesMut::from(string.as_bytes()));
+        }
+    }
+
+    Err(RustPSQLDriverError::PyToRustValueConversionError(
+        "source must be bytes, str, file-like object or support Buffer protocol".into(),
+    ))
+}
+
+/// Convert dict rows to lists of values in the same order of the columns.
+///
+/// Columns are taken from the first row, all rows must have the same columns
+/// including the key columns.
+fn rows_to_values(
+    rows: &[Py<PyDict>],
+    key_columns: &[String],
+) -> RustPSQLDriverPyResult<(Vec<String>, Vec<Py<PyAny>>)> {
+    pyo3::Python::with_gil(|gil| {
+        let Some(first_row) = rows.first() else {
+            return Ok((vec![], vec![]));
+        };
+        let columns: Vec<String> = first_row.bind(gil).keys().extract()?;
+        if let Some(key_column) = key_columns.iter().find(|key| !columns.contains(key)) {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "Rows don't have key column `{key_column}`"
+            )));
+        }
+
+        let mut row_values = Vec::with_capacity(rows.len());
+        for row in rows {
+            let row = row.bind(gil);
+            if row.len() != columns.len() {
+                return Err(RustPSQLDriverError::PyToRustValueConversionError(
+                    "All rows must have the same columns".into(),
+                ));
+            }
+            let mut values = Vec::with_capacity(columns.len());
+            for column in &columns {
+                let Some(value) = row.get_item(column)? else {
+                    return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                        "Row doesn't have column `{column}`"
+                    )));
+                };
+                values.push(value);
+            }
+            row_values.push(PyList::new(gil, values)?.into_any().unbind());
+        }
+        Ok((columns, row_values))
+    })
 }
@@ -0,0 +1,135 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use deadpool_postgres::Object;
+
+/// Connection parked under the affinity key.
+struct ParkedConnection {
+    object: Object,
+    parked_at: Instant,
+}
+
+/// Connections parked for affinity keys.
+///
+/// When connection with affinity key is returned back,
+/// it isn't released to the pool but stays parked under its key,
+/// so next request with the same key gets the same connection.
+/// At most `max_parked` connections are parked, the oldest one
+/// goes back to the pool to make room for the new one.
+/// Connections parked longer than `idle_ttl` go back to the pool too.
+#[derive(Clone, Default)]
+pub struct ConnectionAffinity {
+    parked: Arc<Mutex<HashMap<String, ParkedConnection>>>,
+    max_parked: Option<usize>,
+    idle_ttl: Option<Duration>,
+}
+
+impl ConnectionAffinity {
+    #[must_use]
+    pub fn new(max_parked: Option<usize>, idle_ttl: Option<Duration>) -> Self {
+        ConnectionAffinity {
+            parked: Arc::default(),
+            max_parked,
+            idle_ttl,
+        }
+    }
+
+    fn is_expired(&self, parked: &ParkedConnection) -> bool {
+        self.idle_ttl
+            .is_some_and(|idle_ttl| parked.parked_at.elapsed() > idle_ttl)
+    }
+
+    fn remove_expired(&self, parked: &mut HashMap<String, ParkedConnection>) {
+        parked.retain(|_, connection| !self.is_expired(connection));
+    }
+
+    fn remove_oldest(parked: &mut HashMap<String, ParkedConnection>) -> bool {
+        let Some(affinity_key) = parked
+            .iter()
+            .min_by_key(|(_, connection)| connection.parked_at)
+            .map(|(affinity_key, _)| affinity_key.clone())
+        else {
+            return false;
+        };
+        parked.remove(&affinity_key).is_some()
+    }
+
+    /// Take parked connection for the key.
+    ///
+    /// Returns None if there is no parked connection
+    /// (it's busy or was never used), it was closed
+    /// or it was parked longer than `idle_ttl`.
+    #[must_use]
+    pub fn take(&self, affinity_key: &str) -> Option<Object> {
+        let parked = self.parked.lock().ok()?.remove(affinity_key)?;
+        if parked.object.is_closed() || self.is_expired(&parked) {
+            return None;
+        }
+        Some(parked.object)
+    }
+
+    /// Park connection under the key.
+    ///
+    /// Previously parked connection for the same key goes back to the pool,
+    /// so does the oldest parked connection if there are `max_parked` of them.
+    pub fn park(&self, affinity_key: String, object: Object) {
+        if self.max_parked == Some(0) {
+            return;
+        }
+        let Ok(mut parked) = self.parked.lock() else {
+            return;
+        };
+        self.remove_expired(&mut parked);
+        parked.remove(&affinity_key);
+        if let Some(max_parked) = self.max_parked {
+            while parked.len() >= max_parked && Self::remove_oldest(&mut parked) {}
+        }
+        parked.insert(
+            affinity_key,
+            ParkedConnection {
+                object,
+                parked_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Release expired connections and the oldest parked connection back to the pool.
+    ///
+    /// Returns true if any connection was released.
+    pub fn release_oldest(&self) -> bool {
+        let Ok(mut parked) = self.parked.lock() else {
+            return false;
+        };
+        let parked_count = parked.len();
+        self.remove_expired(&mut parked);
+        parked.len() < parked_count || Self::remove_oldest(&mut parked)
+    }
+
+    /// Release connections parked longer than `idle_ttl` back to the pool.
+    pub fn release_expired(&self) {
+        if let Ok(mut parked) = self.parked.lock() {
+            self.remove_expired(&mut parked);
+        }
+    }
+
+    /// Release all parked connections back to the pool.
+    pub fn clear(&self) {
+        if let Ok(mut parked) = self.parked.lock() {
+            parked.clear();
+        }
+    }
+
+    /// Number of parked connections.
+    ///
+    /// Expired connections are released first.
+    #[must_use]
+    pub fn parked_count(&self) -> usize {
+        self.parked.lock().map_or(0, |mut parked| {
+            self.remove_expired(&mut parked);
+            parked.len()
+        })
+    }
+}
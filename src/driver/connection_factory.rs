@@ -0,0 +1,110 @@
+use std::{io, sync::Arc};
+
+use pyo3::{types::PyAnyMethods, Py, PyAny, Python};
+use tokio::net::TcpStream;
+use tokio_postgres::Config;
+
+use crate::exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult};
+
+use super::utils::tcp_host_ports;
+
+/// Python callable which opens sockets to the database hosts.
+///
+/// Factory accepts host and port and returns connected `socket.socket`,
+/// ownership of the socket is taken by the pool.
+/// It allows to connect through proxies and tunnels
+/// which aren't supported by the driver.
+#[derive(Clone)]
+pub struct ConnectionFactory {
+    factory: Arc<Py<PyAny>>,
+}
+
+/// Take ownership of the socket by its file descriptor.
+#[cfg(unix)]
+fn socket_from_raw(raw_socket: i64) -> io::Result<std::net::TcpStream> {
+    use std::os::fd::{FromRawFd, RawFd};
+
+    let raw_fd = RawFd::try_from(raw_socket).map_err(io::Error::other)?;
+    // SAFETY: descriptor is detached from the Python socket,
+    // so nothing else owns or closes it.
+    Ok(unsafe { std::net::TcpStream::from_raw_fd(raw_fd) })
+}
+
+/// Take ownership of the socket by its handle.
+#[cfg(windows)]
+fn socket_from_raw(raw_socket: i64) -> io::Result<std::net::TcpStream> {
+    use std::os::windows::io::{FromRawSocket, RawSocket};
+
+    let raw_socket = RawSocket::try_from(raw_socket).map_err(io::Error::other)?;
+    // SAFETY: handle is detached from the Python socket,
+    // so nothing else owns or closes it.
+    Ok(unsafe { std::net::TcpStream::from_raw_socket(raw_socket) })
+}
+
+impl ConnectionFactory {
+    /// Create new connection factory.
+    ///
+    /// # Errors
+    /// May return Err Result if factory is not callable.
+    pub fn new(gil: Python<'_>, factory: Py<PyAny>) -> RustPSQLDriverPyResult<Self> {
+        if !factory.bind(gil).is_callable() {
+            return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                "Connection factory must be a callable".into(),
+            ));
+        }
+        Ok(ConnectionFactory {
+            factory: Arc::new(factory),
+        })
+    }
+
+    /// Open socket to the host with the Python factory.
+    async fn open_socket(&self, host: String, port: u16) -> io::Result<TcpStream> {
+        let factory = self.factory.clone();
+        let raw_socket = tokio::task::spawn_blocking(move || {
+            Python::with_gil(|gil| {
+                factory
+                    .call1(gil, (host, port))
+                    .and_then(|socket| socket.call_method0(gil, "detach"))
+                    .and_then(|raw_socket| raw_socket.extract::<i64>(gil))
+                    .map_err(|err| io::Error::other(format!("Connection factory failed: {err}")))
+            })
+        })
+        .await
+        .map_err(io::Error::other)??;
+
+        let stream = socket_from_raw(raw_socket)?;
+        stream.set_nonblocking(true)?;
+        let stream = TcpStream::from_std(stream)?;
+        let _ = stream.set_nodelay(true);
+        Ok(stream)
+    }
+
+    /// Open socket to the first available host from the config,
+    /// hosts before `first_host` are skipped.
+    ///
+    /// Returns stream, name of the host for TLS and index of the host.
+    ///
+    /// # Errors
+    /// May return Err if factory failed for all hosts,
+    /// error of the last host is returned.
+    pub async fn connect_stream(
+        &self,
+        pg_config: &Config,
+        first_host: usize,
+    ) -> io::Result<(TcpStream, String, usize)> {
+        let mut last_error = io::Error::other("Config doesn't have more TCP hosts");
+
+        for (index, (host, port)) in tcp_host_ports(pg_config)
+            .into_iter()
+            .enumerate()
+            .skip(first_host)
+        {
+            match self.open_socket(host.clone(), port).await {
+                Ok(stream) => return Ok((stream, host, index)),
+                Err(err) => last_error = err,
+            }
+        }
+
+        Err(last_error)
+    }
+}
@@ -1,17 +1,54 @@
-use crate::runtime::tokio_runtime;
-use deadpool_postgres::{Manager, ManagerConfig, Pool, RecyclingMethod};
-use pyo3::{pyclass, pyfunction, pymethods, Py, PyAny};
-use std::sync::Arc;
+use crate::runtime::{block_on_runtime, tokio_runtime};
+use deadpool_postgres::{Manager, ManagerConfig, Object, Pool, RecyclingMethod};
+use futures::{stream, StreamExt, TryStreamExt};
+use pyo3::{
+    pyclass, pyfunction, pymethods,
+    types::{PyAnyMethods, PyList, PyListMethods, PyTuple, PyTupleMethods},
+    Py, PyAny, PyResult, Python,
+};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio_postgres::Config;
 
-use crate::exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult};
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    query_result::PSQLDriverPyQueryResult,
+};
 
 use super::{
+    column_codecs::{ColumnCodec, ColumnCodecs},
     common_options::{ConnRecyclingMethod, LoadBalanceHosts, SslMode, TargetSessionAttrs},
     connection::Connection,
+    connection_affinity::ConnectionAffinity,
+    dsn::{parse_dsn, DsnOptions},
     inner_connection::PsqlpyConnection,
+    isolation_advisor::IsolationAdvisor,
+    leak_detector::LeakDetector,
     listener::core::Listener,
-    utils::{build_connection_config, build_manager, build_tls},
+    pool_hooks::{recycling_limit, PoolHooks},
+    pool_stats::{AcquireStats, CheckoutGuard, PoolStats},
+    prepared_transactions::{
+        fetch_prepared_transactions, resolve_prepared_transactions, PreparedTransaction,
+    },
+    query_queue::{QueryQueue, QueryQueueStats},
+    redaction::RedactionPolicy,
+    result_cache::ResultCache,
+    result_masking::{ColumnMask, ColumnMasks},
+    scheduler::{PoolScheduler, ScheduledJob, ScheduledTask, SchedulerContext},
+    schema_validation::{parse_expectations, validate_schema},
+    session_pooler::PooledSession,
+    sqlcommenter::SqlCommenter,
+    ssh_tunnel::SshTunnel,
+    statement_cache::{
+        check_prepare_threshold, PooledStatementCaches, DEFAULT_STATEMENT_CACHE_SIZE,
+    },
+    statement_stats::{StatementStat, StatementStats},
+    transaction_options::IsolationLevel,
+    utils::{apply_server_settings, build_connection_config, build_manager, build_tls, TlsOptions},
+    warm_statements::{WarmStatement, WarmStatements},
 };
 
 /// Make new connection pool.
@@ -46,6 +83,29 @@ use super::{
     ca_file=None,
     max_db_pool_size=None,
     conn_recycling_method=None,
+    sqlcommenter=None,
+    validate_on_checkout=None,
+    prepare_on_connect=None,
+    isolation_rules=None,
+    observer=None,
+    statement_cache_size=None,
+    column_codecs=None,
+    redaction_policy=None,
+    column_masks=None,
+    ssl_cert=None,
+    ssl_key=None,
+    verify_hostname=None,
+    max_connection_lifetime=None,
+    max_idle_time=None,
+    recycle_on_error=None,
+    max_concurrent_queries=None,
+    prepare_threshold=None,
+    server_settings=None,
+    leak_timeout=None,
+    leak_callback=None,
+    leak_capture_stack=None,
+    max_parked=None,
+    parked_idle_time=None,
 ))]
 #[allow(clippy::too_many_arguments)]
 pub fn connect(
@@ -75,6 +135,29 @@ pub fn connect(
     ca_file: Option<String>,
     max_db_pool_size: Option<usize>,
     conn_recycling_method: Option<ConnRecyclingMethod>,
+    sqlcommenter: Option<Py<PyAny>>,
+    validate_on_checkout: Option<bool>,
+    prepare_on_connect: Option<Vec<(String, String)>>,
+    isolation_rules: Option<Vec<(String, IsolationLevel)>>,
+    observer: Option<Py<PyAny>>,
+    statement_cache_size: Option<usize>,
+    column_codecs: Option<HashMap<String, ColumnCodec>>,
+    redaction_policy: Option<RedactionPolicy>,
+    column_masks: Option<Vec<(String, ColumnMask)>>,
+    ssl_cert: Option<String>,
+    ssl_key: Option<String>,
+    verify_hostname: Option<bool>,
+    max_connection_lifetime: Option<f64>,
+    max_idle_time: Option<f64>,
+    recycle_on_error: Option<bool>,
+    max_concurrent_queries: Option<usize>,
+    prepare_threshold: Option<usize>,
+    server_settings: Option<HashMap<String, String>>,
+    leak_timeout: Option<f64>,
+    leak_callback: Option<Py<PyAny>>,
+    leak_capture_stack: Option<bool>,
+    max_parked: Option<usize>,
+    parked_idle_time: Option<f64>,
 ) -> RustPSQLDriverPyResult<ConnectionPool> {
     if let Some(max_db_pool_size) = max_db_pool_size {
         if max_db_pool_size < 2 {
@@ -84,7 +167,21 @@ pub fn connect(
         }
     }
 
-    let pg_config = build_connection_config(
+    // TLS parameters of `dsn` are used if they aren't passed explicitly.
+    let dsn_options = match &dsn {
+        Some(dsn) => parse_dsn(dsn)?.options,
+        None => DsnOptions::default(),
+    };
+    let ssl_mode = ssl_mode.or(dsn_options.ssl_mode);
+    let ca_file = ca_file.or(dsn_options.ca_file);
+    let ssl_cert = ssl_cert.or(dsn_options.ssl_cert);
+    let ssl_key = ssl_key.or(dsn_options.ssl_key);
+    // Like other connection parameters, it's ignored if `dsn` is passed.
+    let prefer_standby = match dsn {
+        Some(_) => dsn_options.prefer_standby,
+        None => target_session_attrs == Some(TargetSessionAttrs::PreferStandby),
+    };
+    let mut pg_config = build_connection_config(
         dsn,
         username,
         password,
@@ -109,6 +206,9 @@ pub fn connect(
         load_balance_hosts,
         ssl_mode,
     )?;
+    if let Some(server_settings) = server_settings {
+        apply_server_settings(&mut pg_config, &server_settings)?;
+    }
 
     let mgr_config: ManagerConfig;
     if let Some(conn_recycling_method) = conn_recycling_method {
@@ -121,27 +221,138 @@ pub fn connect(
         };
     }
 
+    let tls_options = TlsOptions {
+        ca_file,
+        ssl_cert,
+        ssl_key,
+        verify_hostname,
+    };
     let mgr: Manager = build_manager(
         mgr_config,
         pg_config.clone(),
-        build_tls(&ca_file, &ssl_mode)?,
+        build_tls(&tls_options, &ssl_mode)?,
+        None,
+        None,
+        None,
+        prefer_standby,
     );
 
+    let warm_statements = WarmStatements::new(prepare_on_connect.unwrap_or_default())?;
     let mut db_pool_builder = Pool::builder(mgr);
     if let Some(max_db_pool_size) = max_db_pool_size {
         db_pool_builder = db_pool_builder.max_size(max_db_pool_size);
     }
+    let statement_caches = PooledStatementCaches::new(
+        statement_cache_size.unwrap_or(DEFAULT_STATEMENT_CACHE_SIZE),
+        check_prepare_threshold(prepare_threshold)?,
+    );
+    if !warm_statements.is_empty() {
+        db_pool_builder = db_pool_builder.post_create(warm_statements.post_create_hook());
+    }
+    let mut hooks = PoolHooks::default();
+    hooks.set_max_connection_lifetime(recycling_limit(
+        max_connection_lifetime,
+        "max_connection_lifetime",
+    )?);
+    let max_idle_time = recycling_limit(max_idle_time, "max_idle_time")?;
+    hooks.set_max_idle_time(max_idle_time);
+    db_pool_builder = hooks.apply(db_pool_builder);
+    // Parked connections follow `max_idle_time` unless they have their own limit.
+    let parked_idle_time = recycling_limit(parked_idle_time, "parked_idle_time")?.or(max_idle_time);
 
+    let query_queue = QueryQueue::new(max_concurrent_queries)?;
+    let leak_detector =
+        LeakDetector::from_options(leak_timeout, leak_callback, leak_capture_stack)?;
     let pool = db_pool_builder.build()?;
+    let sqlcommenter = match sqlcommenter {
+        Some(provider) => Some(Python::with_gil(|gil| SqlCommenter::new(gil, provider))?),
+        None => None,
+    };
+    let isolation_advisor = match (isolation_rules, observer) {
+        (Some(isolation_rules), Some(observer)) => Some(Python::with_gil(|gil| {
+            IsolationAdvisor::new(gil, isolation_rules, observer)
+        })?),
+        (Some(_), None) => {
+            return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                "observer must be specified to report isolation_rules".into(),
+            ))
+        }
+        (None, _) => None,
+    };
 
     Ok(ConnectionPool {
         pool: pool,
         pg_config: Arc::new(pg_config),
-        ca_file: ca_file,
+        tls_options,
         ssl_mode: ssl_mode,
+        affinity: ConnectionAffinity::new(max_parked, parked_idle_time),
+        statement_caches,
+        statement_stats: StatementStats::default(),
+        pool_stats: PoolStats::default().with_leak_detector(leak_detector),
+        sqlcommenter,
+        validate_on_checkout: validate_on_checkout.unwrap_or(false),
+        warm_statements,
+        result_cache: ResultCache::default(),
+        isolation_advisor,
+        column_codecs: ColumnCodecs::new(column_codecs.unwrap_or_default()),
+        redaction_policy,
+        column_masks: ColumnMasks::new(column_masks.unwrap_or_default()),
+        recycle_on_error: recycle_on_error.unwrap_or(false),
+        query_queue,
+        ssh_tunnel: None,
+        scheduler: PoolScheduler::default(),
     })
 }
 
+/// Check that connection is ready and return round trip time.
+///
+/// Empty query is the cheapest round trip, server doesn't parse
+/// or plan anything and replies with `EmptyQueryResponse` and `ReadyForQuery`.
+///
+/// # Errors
+/// May return Err Result if connection is broken.
+async fn check_connection(db_connection: &Object) -> RustPSQLDriverPyResult<Duration> {
+    let started_at = Instant::now();
+    db_connection.simple_query("").await?;
+    Ok(started_at.elapsed())
+}
+
+/// Get connection from the pool and optionally check it before reuse.
+///
+/// Connection which doesn't pass the check is removed from the pool
+/// and another one is taken.
+/// Returns connection, its checkout guard and round trip time of the check.
+/// Waits for the pool are recorded in `pool_stats`.
+///
+/// # Errors
+/// May return Err Result if:
+/// 1) Cannot get new connection from the pool
+/// 2) Another connection doesn't pass the check too
+pub async fn checkout_connection(
+    db_pool: Pool,
+    validate: bool,
+    pool_stats: PoolStats,
+) -> RustPSQLDriverPyResult<(Object, CheckoutGuard, Option<Duration>)> {
+    tokio_runtime()
+        .spawn(async move {
+            let (db_connection, checkout_guard) = pool_stats.get(&db_pool).await?;
+            if !validate {
+                return Ok((db_connection, checkout_guard, None));
+            }
+
+            if let Ok(check_latency) = check_connection(&db_connection).await {
+                return Ok((db_connection, checkout_guard, Some(check_latency)));
+            }
+            let _ = Object::take(db_connection);
+            drop(checkout_guard);
+
+            let (db_connection, checkout_guard) = pool_stats.get(&db_pool).await?;
+            let check_latency = check_connection(&db_connection).await?;
+            Ok((db_connection, checkout_guard, Some(check_latency)))
+        })
+        .await?
+}
+
 #[pyclass]
 #[allow(clippy::module_name_repetitions)]
 pub struct ConnectionPoolStatus {
@@ -156,15 +367,35 @@ pub struct ConnectionPoolStatus {
 
     /// The number of futures waiting for an object.
     pub waiting: usize,
+
+    /// The number of connections parked for affinity keys.
+    pub parked: usize,
+
+    /// Counters of the connections taken from the pool.
+    acquire_stats: AcquireStats,
+
+    /// State of the query queue.
+    query_queue_stats: QueryQueueStats,
 }
 
 impl ConnectionPoolStatus {
-    fn new(max_size: usize, size: usize, available: usize, waiting: usize) -> Self {
+    fn new(
+        max_size: usize,
+        size: usize,
+        available: usize,
+        waiting: usize,
+        parked: usize,
+        acquire_stats: AcquireStats,
+        query_queue_stats: QueryQueueStats,
+    ) -> Self {
         ConnectionPoolStatus {
             max_size,
             size,
             available,
             waiting,
+            parked,
+            acquire_stats,
+            query_queue_stats,
         }
     }
 }
@@ -191,10 +422,87 @@ impl ConnectionPoolStatus {
         self.waiting
     }
 
+    /// Number of connections parked for affinity keys.
+    #[getter]
+    fn get_parked(&self) -> usize {
+        self.parked
+    }
+
+    /// Number of connections handed out by the pool right now.
+    ///
+    /// Parked connections aren't counted.
+    #[getter]
+    fn get_in_use(&self) -> usize {
+        self.size
+            .saturating_sub(self.available)
+            .saturating_sub(self.parked)
+    }
+
+    /// Number of connections taken from the pool since its creation
+    /// or the last reset.
+    #[getter]
+    fn get_acquires(&self) -> u64 {
+        self.acquire_stats.acquires
+    }
+
+    /// Number of acquires which failed with the pool timeout.
+    #[getter]
+    fn get_timeouts(&self) -> u64 {
+        self.acquire_stats.timeouts
+    }
+
+    /// Mean time to get a connection in milliseconds.
+    #[getter]
+    #[allow(clippy::cast_precision_loss)]
+    fn get_acquire_wait_mean(&self) -> f64 {
+        if self.acquire_stats.acquires == 0 {
+            return 0.0;
+        }
+        self.acquire_stats.total_wait.as_secs_f64() * 1000.0 / self.acquire_stats.acquires as f64
+    }
+
+    #[getter]
+    fn get_acquire_wait_max(&self) -> f64 {
+        self.acquire_stats.max_wait.as_secs_f64() * 1000.0
+    }
+
+    #[getter]
+    fn get_acquire_wait_p50(&self) -> f64 {
+        self.acquire_stats.p50_wait.as_secs_f64() * 1000.0
+    }
+
+    #[getter]
+    fn get_acquire_wait_p95(&self) -> f64 {
+        self.acquire_stats.p95_wait.as_secs_f64() * 1000.0
+    }
+
+    #[getter]
+    fn get_acquire_wait_p99(&self) -> f64 {
+        self.acquire_stats.p99_wait.as_secs_f64() * 1000.0
+    }
+
+    /// Limit of the statements executed at once, None if there is no limit.
+    #[getter]
+    fn get_max_concurrent_queries(&self) -> Option<usize> {
+        self.query_queue_stats.max_concurrent_queries
+    }
+
+    /// Number of statements waiting in the query queue.
+    #[getter]
+    fn get_queued_queries(&self) -> usize {
+        self.query_queue_stats.queued
+    }
+
+    /// Number of statements executed through the query queue right now.
+    #[getter]
+    fn get_running_queries(&self) -> usize {
+        self.query_queue_stats.running
+    }
+
     fn __str__(&self) -> String {
         format!(
-            "Connection Pool Status - [max_size: {}, size: {}, available: {}, waiting: {}]",
-            self.max_size, self.size, self.available, self.waiting,
+            "Connection Pool Status - [max_size: {}, size: {}, available: {}, waiting: {}, parked: {}]",
+            self.max_size, self.size, self.available, self.waiting, self.parked,
         )
     }
 }
@@ -205,8 +513,24 @@ impl ConnectionPoolStatus {
 pub struct ConnectionPool {
     pool: Pool,
     pg_config: Arc<Config>,
-    ca_file: Option<String>,
+    tls_options: TlsOptions,
     ssl_mode: Option<SslMode>,
+    affinity: ConnectionAffinity,
+    statement_caches: PooledStatementCaches,
+    statement_stats: StatementStats,
+    pool_stats: PoolStats,
+    sqlcommenter: Option<SqlCommenter>,
+    validate_on_checkout: bool,
+    warm_statements: WarmStatements,
+    result_cache: ResultCache,
+    isolation_advisor: Option<IsolationAdvisor>,
+    column_codecs: ColumnCodecs,
+    redaction_policy: Option<RedactionPolicy>,
+    column_masks: ColumnMasks,
+    recycle_on_error: bool,
+    query_queue: QueryQueue,
+    ssh_tunnel: Option<Arc<SshTunnel>>,
+    scheduler: PoolScheduler,
 }
 
 impl ConnectionPool {
@@ -214,16 +538,130 @@ impl ConnectionPool {
     pub fn build(
         pool: Pool,
         pg_config: Config,
-        ca_file: Option<String>,
+        tls_options: TlsOptions,
         ssl_mode: Option<SslMode>,
     ) -> Self {
         ConnectionPool {
             pool: pool,
             pg_config: Arc::new(pg_config),
-            ca_file: ca_file,
+            tls_options,
             ssl_mode: ssl_mode,
+            affinity: ConnectionAffinity::default(),
+            statement_caches: PooledStatementCaches::default(),
+            statement_stats: StatementStats::default(),
+            pool_stats: PoolStats::default(),
+            sqlcommenter: None,
+            validate_on_checkout: false,
+            warm_statements: WarmStatements::default(),
+            result_cache: ResultCache::default(),
+            isolation_advisor: None,
+            column_codecs: ColumnCodecs::default(),
+            redaction_policy: None,
+            column_masks: ColumnMasks::default(),
+            recycle_on_error: false,
+            query_queue: QueryQueue::default(),
+            ssh_tunnel: None,
+            scheduler: PoolScheduler::default(),
         }
     }
+
+    /// Set commenter for the queries of the pool connections.
+    #[must_use]
+    pub fn with_sqlcommenter(mut self, sqlcommenter: Option<SqlCommenter>) -> Self {
+        self.sqlcommenter = sqlcommenter;
+        self
+    }
+
+    /// Check connections with empty query before handing them out.
+    #[must_use]
+    pub fn with_validate_on_checkout(mut self, validate_on_checkout: bool) -> Self {
+        self.validate_on_checkout = validate_on_checkout;
+        self
+    }
+
+    /// Set statements which are prepared on every new connection.
+    ///
+    /// Pool must be built with the hook of the same statements.
+    #[must_use]
+    pub fn with_warm_statements(mut self, warm_statements: WarmStatements) -> Self {
+        self.warm_statements = warm_statements;
+        self
+    }
+
+    /// Set advisor which reports statements needing stronger isolation level.
+    #[must_use]
+    pub fn with_isolation_advisor(mut self, isolation_advisor: Option<IsolationAdvisor>) -> Self {
+        self.isolation_advisor = isolation_advisor;
+        self
+    }
+
+    /// Set codecs of the encrypted columns.
+    #[must_use]
+    pub fn with_column_codecs(mut self, column_codecs: ColumnCodecs) -> Self {
+        self.column_codecs = column_codecs;
+        self
+    }
+
+    /// Set policy which hides sensitive parameters in the errors.
+    #[must_use]
+    pub fn with_redaction_policy(mut self, redaction_policy: Option<RedactionPolicy>) -> Self {
+        self.redaction_policy = redaction_policy;
+        self
+    }
+
+    /// Set masks of the sensitive columns in the results.
+    #[must_use]
+    pub fn with_column_masks(mut self, column_masks: ColumnMasks) -> Self {
+        self.column_masks = column_masks;
+        self
+    }
+
+    /// Close connections released after an error instead of returning them to the pool.
+    #[must_use]
+    pub fn with_recycle_on_error(mut self, recycle_on_error: bool) -> Self {
+        self.recycle_on_error = recycle_on_error;
+        self
+    }
+
+    /// Set detector of the connections which aren't returned to the pool in time.
+    #[must_use]
+    pub fn with_leak_detector(mut self, leak_detector: Option<LeakDetector>) -> Self {
+        self.pool_stats = self.pool_stats.with_leak_detector(leak_detector);
+        self
+    }
+
+    /// Set limit of the statements executed by the pool connections at once.
+    #[must_use]
+    pub fn with_query_queue(mut self, query_queue: QueryQueue) -> Self {
+        self.query_queue = query_queue;
+        self
+    }
+
+    /// Set statement caches of the pool connections.
+    #[must_use]
+    pub fn with_statement_caches(mut self, statement_caches: PooledStatementCaches) -> Self {
+        self.statement_caches = statement_caches;
+        self
+    }
+
+    /// Set limits of the connections parked for affinity keys.
+    #[must_use]
+    pub fn with_affinity(mut self, affinity: ConnectionAffinity) -> Self {
+        self.affinity = affinity;
+        self
+    }
+
+    /// Set SSH tunnel which is closed with the pool.
+    #[must_use]
+    pub fn with_ssh_tunnel(mut self, ssh_tunnel: Option<Arc<SshTunnel>>) -> Self {
+        self.ssh_tunnel = ssh_tunnel;
+        self
+    }
+
+    #[must_use]
+    pub fn db_pool(&self) -> Pool {
+        self.pool.clone()
+    }
 }
 
 #[pymethods]
@@ -260,6 +698,29 @@ impl ConnectionPool {
         conn_recycling_method=None,
         ssl_mode=None,
         ca_file=None,
+        sqlcommenter=None,
+        validate_on_checkout=None,
+        prepare_on_connect=None,
+        isolation_rules=None,
+        observer=None,
+        statement_cache_size=None,
+        column_codecs=None,
+        redaction_policy=None,
+        column_masks=None,
+        ssl_cert=None,
+        ssl_key=None,
+        verify_hostname=None,
+        max_connection_lifetime=None,
+        max_idle_time=None,
+        recycle_on_error=None,
+        max_concurrent_queries=None,
+        prepare_threshold=None,
+        server_settings=None,
+        leak_timeout=None,
+        leak_callback=None,
+        leak_capture_stack=None,
+        max_parked=None,
+        parked_idle_time=None,
     ))]
     #[allow(clippy::too_many_arguments)]
     pub fn new(
@@ -289,6 +750,29 @@ impl ConnectionPool {
         conn_recycling_method: Option<ConnRecyclingMethod>,
         ssl_mode: Option<SslMode>,
         ca_file: Option<String>,
+        sqlcommenter: Option<Py<PyAny>>,
+        validate_on_checkout: Option<bool>,
+        prepare_on_connect: Option<Vec<(String, String)>>,
+        isolation_rules: Option<Vec<(String, IsolationLevel)>>,
+        observer: Option<Py<PyAny>>,
+        statement_cache_size: Option<usize>,
+        column_codecs: Option<HashMap<String, ColumnCodec>>,
+        redaction_policy: Option<RedactionPolicy>,
+        column_masks: Option<Vec<(String, ColumnMask)>>,
+        ssl_cert: Option<String>,
+        ssl_key: Option<String>,
+        verify_hostname: Option<bool>,
+        max_connection_lifetime: Option<f64>,
+        max_idle_time: Option<f64>,
+        recycle_on_error: Option<bool>,
+        max_concurrent_queries: Option<usize>,
+        prepare_threshold: Option<usize>,
+        server_settings: Option<HashMap<String, String>>,
+        leak_timeout: Option<f64>,
+        leak_callback: Option<Py<PyAny>>,
+        leak_capture_stack: Option<bool>,
+        max_parked: Option<usize>,
+        parked_idle_time: Option<f64>,
     ) -> RustPSQLDriverPyResult<Self> {
         connect(
             dsn,
@@ -317,6 +801,29 @@ impl ConnectionPool {
             ca_file,
             max_db_pool_size,
             conn_recycling_method,
+            sqlcommenter,
+            validate_on_checkout,
+            prepare_on_connect,
+            isolation_rules,
+            observer,
+            statement_cache_size,
+            column_codecs,
+            redaction_policy,
+            column_masks,
+            ssl_cert,
+            ssl_key,
+            verify_hostname,
+            max_connection_lifetime,
+            max_idle_time,
+            recycle_on_error,
+            max_concurrent_queries,
+            prepare_threshold,
+            server_settings,
+            leak_timeout,
+            leak_callback,
+            leak_capture_stack,
+            max_parked,
+            parked_idle_time,
         )
     }
 
@@ -351,53 +858,571 @@ impl ConnectionPool {
             inner_status.size,
             inner_status.available,
             inner_status.waiting,
+            self.affinity.parked_count(),
+            self.pool_stats.snapshot(),
+            self.query_queue.snapshot(),
         )
     }
 
+    /// Reset counters of the connections taken from the pool.
+    pub fn reset_status(&self) {
+        self.pool_stats.reset();
+    }
+
     pub fn resize(&self, new_max_size: usize) {
+        tracing::info!(
+            max_size = self.pool.status().max_size,
+            new_max_size,
+            "Connection pool is resized"
+        );
         self.pool.resize(new_max_size);
     }
 
     #[must_use]
     pub fn acquire(&self) -> Connection {
-        Connection::new(None, Some(self.pool.clone()), self.pg_config.clone())
+        Connection::new(
+            None,
+            Some(self.pool.clone()),
+            self.pg_config.clone(),
+            self.tls_options.clone(),
+            self.ssl_mode,
+        )
+        .with_statement_stats(self.statement_stats.clone())
+        .with_statement_caches(self.statement_caches.clone())
+        .with_pool_stats(self.pool_stats.clone())
+        .with_sqlcommenter(self.sqlcommenter.clone())
+        .with_validate_on_checkout(self.validate_on_checkout)
+        .with_isolation_advisor(self.isolation_advisor.clone())
+        .with_column_codecs(self.column_codecs.clone())
+        .with_redaction_policy(self.redaction_policy.clone())
+        .with_column_masks(self.column_masks.clone())
+        .with_recycle_on_error(self.recycle_on_error)
+        .with_query_queue(self.query_queue.clone())
+    }
+
+    /// Return logical session of the transaction pooling mode.
+    ///
+    /// Session takes connection from the pool for every statement
+    /// and transaction instead of holding one.
+    #[must_use]
+    pub fn session(self_: pyo3::Py<Self>) -> PooledSession {
+        PooledSession::new(self_)
     }
 
     #[must_use]
     #[allow(clippy::needless_pass_by_value)]
     pub fn listener(self_: pyo3::Py<Self>) -> Listener {
-        let (pg_config, ca_file, ssl_mode) = pyo3::Python::with_gil(|gil| {
+        let (pg_config, tls_options, ssl_mode) = pyo3::Python::with_gil(|gil| {
             let b_gil = self_.borrow(gil);
             (
                 b_gil.pg_config.clone(),
-                b_gil.ca_file.clone(),
+                b_gil.tls_options.clone(),
                 b_gil.ssl_mode,
             )
         });
 
-        Listener::new(pg_config, ca_file, ssl_mode)
+        Listener::new(pg_config, tls_options, ssl_mode)
     }
 
     /// Return new single connection.
     ///
+    /// If `affinity_key` is passed, connection which was previously
+    /// used with the same key is returned if it's available.
+    /// If it's busy, new connection from the pool is returned
+    /// and bound to the key.
+    /// If `validate_on_checkout` is set, connection from the pool
+    /// is checked with empty query before it's returned.
+    ///
     /// # Errors
     /// May return Err Result if cannot get new connection from the pool.
-    pub async fn connection(self_: pyo3::Py<Self>) -> RustPSQLDriverPyResult<Connection> {
-        let (db_pool, pg_config) = pyo3::Python::with_gil(|gil| {
+    #[pyo3(signature = (affinity_key=None))]
+    pub async fn connection(
+        self_: pyo3::Py<Self>,
+        affinity_key: Option<String>,
+    ) -> RustPSQLDriverPyResult<Connection> {
+        let (
+            db_pool,
+            pg_config,
+            tls_options,
+            ssl_mode,
+            affinity,
+            statement_caches,
+            statement_stats,
+            pool_stats,
+            sqlcommenter,
+            validate_on_checkout,
+            isolation_advisor,
+            column_codecs,
+            redaction_policy,
+            column_masks,
+            recycle_on_error,
+            query_queue,
+            stack,
+        ) = pyo3::Python::with_gil(|gil| {
             let slf = self_.borrow(gil);
-            (slf.pool.clone(), slf.pg_config.clone())
+            (
+                slf.pool.clone(),
+                slf.pg_config.clone(),
+                slf.tls_options.clone(),
+                slf.ssl_mode,
+                slf.affinity.clone(),
+                slf.statement_caches.clone(),
+                slf.statement_stats.clone(),
+                slf.pool_stats.clone(),
+                slf.sqlcommenter.clone(),
+                slf.validate_on_checkout,
+                slf.isolation_advisor.clone(),
+                slf.column_codecs.clone(),
+                slf.redaction_policy.clone(),
+                slf.column_masks.clone(),
+                slf.recycle_on_error,
+                slf.query_queue.clone(),
+                slf.pool_stats.capture_stack(gil),
+            )
         });
-        let db_connection = tokio_runtime()
+
+        let parked_connection = affinity_key
+            .as_ref()
+            .and_then(|affinity_key| affinity.take(affinity_key));
+
+        let (db_connection, checkout_guard, check_latency) = match parked_connection {
+            Some(db_connection) => (db_connection, pool_stats.track_checkout(), None),
+            None => {
+                // Parked connections are not available for others,
+                // release the oldest one if the pool is exhausted
+                // instead of waiting for a connection.
+                affinity.release_expired();
+                let status = db_pool.status();
+                if status.available == 0 && status.size >= status.max_size {
+                    affinity.release_oldest();
+                }
+                checkout_connection(db_pool, validate_on_checkout, pool_stats).await?
+            }
+        };
+        // Stack is captured before the first await, checkout has no Python frames.
+        checkout_guard.set_stack(stack);
+
+        let statement_cache = statement_caches.checkout(&db_connection);
+        let connection = Connection::new(
+            Some(Arc::new(PsqlpyConnection::PoolConn(
+                db_connection,
+                Some(statement_stats),
+                Some(checkout_guard),
+                column_codecs.clone(),
+                statement_cache,
+            ))),
+            None,
+            pg_config,
+            tls_options,
+            ssl_mode,
+        )
+        .with_sqlcommenter(sqlcommenter)
+        .with_isolation_advisor(isolation_advisor)
+        .with_column_codecs(column_codecs)
+        .with_redaction_policy(redaction_policy)
+        .with_column_masks(column_masks)
+        .with_recycle_on_error(recycle_on_error)
+        .with_query_queue(query_queue)
+        .with_check_latency(check_latency);
+
+        match affinity_key {
+            Some(affinity_key) => Ok(connection.with_affinity(affinity_key, affinity)),
+            None => Ok(connection),
+        }
+    }
+
+    /// Execute querystring on the connection from the pool and wait for the result.
+    ///
+    /// Blocking version of `execute` for the code without event loop,
+    /// e.g. REPL or scripts. Connection is returned to the pool right after it.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Event loop is running in the current thread
+    /// 2) Cannot get new connection from the pool
+    /// 3) Cannot execute querystring
+    #[pyo3(signature = (querystring, parameters=None, prepared=None))]
+    pub fn execute_sync(
+        &self,
+        py: Python<'_>,
+        querystring: String,
+        parameters: Option<Py<PyAny>>,
+        prepared: Option<bool>,
+    ) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
+        let db_pool = self.pool.clone();
+        let validate_on_checkout = self.validate_on_checkout;
+        let statement_caches = self.statement_caches.clone();
+        let statement_stats = self.statement_stats.clone();
+        let pool_stats = self.pool_stats.clone();
+        let query_queue = self.query_queue.clone();
+        let column_codecs = self.column_codecs.clone();
+        let (querystring, prepared) =
+            SqlCommenter::apply(self.sqlcommenter.as_ref(), querystring, prepared)?;
+
+        block_on_runtime(py, async move {
+            let (db_connection, checkout_guard, _) =
+                checkout_connection(db_pool, validate_on_checkout, pool_stats).await?;
+            let statement_cache = statement_caches.checkout(&db_connection);
+            let db_client = PsqlpyConnection::PoolConn(
+                db_connection,
+                Some(statement_stats),
+                Some(checkout_guard),
+                column_codecs,
+                statement_cache,
+            );
+            query_queue
+                .run(db_client.execute(querystring, parameters, prepared))
+                .await
+        })
+    }
+
+    /// Return number of connections parked for affinity keys.
+    #[must_use]
+    pub fn affinity_parked(&self) -> usize {
+        self.affinity.parked_count()
+    }
+
+    /// Release all connections parked for affinity keys back to the pool.
+    pub fn clear_affinity(&self) {
+        self.affinity.clear();
+    }
+
+    /// Execute independent queries concurrently.
+    ///
+    /// Acquire up to `max_connections` connections from the pool
+    /// and execute queries on them concurrently.
+    /// Results are returned in the same order as queries.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Cannot convert python parameters
+    /// 2) Cannot get new connection from the pool
+    /// 3) Cannot execute any of querystring
+    #[pyo3(signature = (queries, max_connections=None, prepared=None))]
+    pub async fn gather(
+        self_: pyo3::Py<Self>,
+        queries: Py<PyList>,
+        max_connections: Option<usize>,
+        prepared: Option<bool>,
+    ) -> RustPSQLDriverPyResult<Vec<PSQLDriverPyQueryResult>> {
+        let (db_pool, statement_caches, statement_stats, pool_stats, query_queue, column_codecs) =
+            pyo3::Python::with_gil(|gil| {
+                let self_ = self_.borrow(gil);
+                (
+                    self_.pool.clone(),
+                    self_.statement_caches.clone(),
+                    self_.statement_stats.clone(),
+                    self_.pool_stats.clone(),
+                    self_.query_queue.clone(),
+                    self_.column_codecs.clone(),
+                )
+            });
+
+        let queries = Python::with_gil(|gil| -> PyResult<Vec<(String, Option<Py<PyAny>>)>> {
+            let mut parsed_queries = vec![];
+            for single_query in queries.into_bound(gil).iter() {
+                let query_tuple = single_query.downcast::<PyTuple>().map_err(|err| {
+                    RustPSQLDriverError::PyToRustValueConversionError(format!(
+                        "Cannot cast to tuple: {err}",
+                    ))
+                })?;
+
+                let querystring = query_tuple.get_item(0)?.extract::<String>()?;
+                let params = match query_tuple.get_item(1) {
+                    Ok(param) => Some(param.into()),
+                    Err(_) => None,
+                };
+                parsed_queries.push((querystring, params));
+            }
+            Ok(parsed_queries)
+        })?;
+
+        let max_connections = max_connections
+            .unwrap_or_else(|| db_pool.status().max_size)
+            .max(1);
+
+        let results = tokio_runtime()
             .spawn(async move {
-                Ok::<deadpool_postgres::Object, RustPSQLDriverError>(db_pool.get().await?)
+                stream::iter(queries)
+                    .map(|(querystring, parameters)| {
+                        let db_pool = db_pool.clone();
+                        let statement_caches = statement_caches.clone();
+                        let statement_stats = statement_stats.clone();
+                        let pool_stats = pool_stats.clone();
+                        let query_queue = query_queue.clone();
+                        let column_codecs = column_codecs.clone();
+                        async move {
+                            let (db_connection, checkout_guard) = pool_stats.get(&db_pool).await?;
+                            let statement_cache = statement_caches.checkout(&db_connection);
+                            let db_client = PsqlpyConnection::PoolConn(
+                                db_connection,
+                                Some(statement_stats),
+                                Some(checkout_guard),
+                                column_codecs,
+                                statement_cache,
+                            );
+                            query_queue
+                                .run(db_client.execute(querystring, parameters, prepared))
+                                .await
+                        }
+                    })
+                    .buffered(max_connections)
+                    .try_collect::<Vec<PSQLDriverPyQueryResult>>()
+                    .await
             })
             .await??;
 
-        Ok(Connection::new(
-            Some(Arc::new(PsqlpyConnection::PoolConn(db_connection))),
-            None,
-            pg_config,
-        ))
+        Ok(results)
+    }
+
+    /// Return execution statistics of the statements.
+    ///
+    /// Statements are grouped by the fingerprint,
+    /// the slowest in total go first.
+    #[must_use]
+    pub fn statement_stats(&self) -> Vec<StatementStat> {
+        self.statement_stats.snapshot()
+    }
+
+    /// Remove collected execution statistics.
+    pub fn reset_statement_stats(&self) {
+        self.statement_stats.reset();
+    }
+
+    /// Execute querystring or return its cached result.
+    ///
+    /// `validity_probe` is executed first, its result is the validity token
+    /// (e.g. `SELECT max(updated_at) FROM users`).
+    /// Cached result is returned only if it was stored with the same token,
+    /// otherwise querystring is executed and cached with the new token.
+    /// Probe is executed before the query, so data changed in between
+    /// only makes the next probe miss.
+    /// If `bypass_cache` is set, cached result is never returned,
+    /// but the new result is still cached.
+    /// Every call returns new result object, so it can be closed
+    /// without affecting the cache.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Cannot get new connection from the pool
+    /// 2) Cannot execute probe or querystring
+    /// 3) Cannot compare validity tokens
+    #[pyo3(signature = (
+        querystring,
+        parameters=None,
+        *,
+        validity_probe,
+        bypass_cache=false,
+        prepared=None,
+    ))]
+    pub async fn fetch_cached(
+        self_: pyo3::Py<Self>,
+        querystring: String,
+        parameters: Option<Py<PyAny>>,
+        validity_probe: String,
+        bypass_cache: bool,
+        prepared: Option<bool>,
+    ) -> RustPSQLDriverPyResult<Py<PSQLDriverPyQueryResult>> {
+        let (pool, result_cache, cache_key) = Python::with_gil(|gil| {
+            let cache_key =
+                ResultCache::cache_key(gil, &validity_probe, &querystring, parameters.as_ref())?;
+            Ok::<_, RustPSQLDriverError>((
+                self_.clone_ref(gil),
+                self_.borrow(gil).result_cache.clone(),
+                cache_key,
+            ))
+        })?;
+
+        let connection = Self::connection(pool, None).await?;
+        let Some(db_client) = connection.db_client() else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+
+        let probe_result = db_client.execute(validity_probe, None, Some(false)).await?;
+        let validity_token = Python::with_gil(|gil| probe_result.result(gil, None, None, None))?;
+
+        if !bypass_cache {
+            let cached_result =
+                Python::with_gil(|gil| result_cache.get(gil, &cache_key, &validity_token))?;
+            if let Some(cached_result) = cached_result {
+                return Python::with_gil(|gil| Ok(Py::new(gil, cached_result)?));
+            }
+        }
+
+        let result = db_client
+            .execute(querystring.clone(), parameters, prepared)
+            .await?;
+        Python::with_gil(|gil| {
+            result_cache.insert(cache_key, querystring, validity_token, result.copy_ref(gil));
+            Ok(Py::new(gil, result)?)
+        })
+    }
+
+    /// Return transactions prepared for two-phase commit
+    /// in the current database, the oldest go first.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Cannot get new connection from the pool
+    /// 2) Cannot query `pg_prepared_xacts`
+    #[pyo3(signature = (gid_prefix=None))]
+    pub async fn list_prepared_transactions(
+        self_: pyo3::Py<Self>,
+        gid_prefix: Option<String>,
+    ) -> RustPSQLDriverPyResult<Vec<PreparedTransaction>> {
+        let connection = Self::connection(self_, None).await?;
+        let Some(db_client) = connection.db_client() else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+        fetch_prepared_transactions(&db_client, gid_prefix.as_deref().unwrap_or_default()).await
+    }
+
+    /// Resolve orphaned prepared transactions with the GID prefix.
+    ///
+    /// `policy` is `RecoveryPolicy` or callable which accepts
+    /// `PreparedTransaction` and returns `RecoveryPolicy` or None to skip it.
+    /// Only transactions at least `min_age` seconds old are resolved,
+    /// so transactions of the running coordinators aren't touched.
+    /// Returns resolved transactions.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) GID prefix is empty or policy is invalid
+    /// 2) Cannot get new connection from the pool
+    /// 3) Cannot commit or roll back prepared transaction
+    #[pyo3(signature = (gid_prefix, policy, *, min_age=0.0))]
+    pub async fn recover_prepared_transactions(
+        self_: pyo3::Py<Self>,
+        gid_prefix: String,
+        policy: Py<PyAny>,
+        min_age: f64,
+    ) -> RustPSQLDriverPyResult<Vec<PreparedTransaction>> {
+        let connection = Self::connection(self_, None).await?;
+        let Some(db_client) = connection.db_client() else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+        resolve_prepared_transactions(&db_client, &gid_prefix, policy, min_age).await
+    }
+
+    /// Check that tables and columns of the database match the expectations.
+    ///
+    /// Meant to be called at startup, so the schema which isn't migrated yet
+    /// is found before the first query.
+    /// `expectations` is dict of table names to dicts of column names to types
+    /// or to lists of column names, or string with lines
+    /// like `users: id integer, name varchar(255), email`.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Expectations have wrong format
+    /// 2) Cannot get new connection from the pool
+    /// 3) Schema doesn't match the expectations, all mismatches are reported
+    pub async fn validate_schema(
+        self_: pyo3::Py<Self>,
+        expectations: Py<PyAny>,
+    ) -> RustPSQLDriverPyResult<()> {
+        let expected_tables = Python::with_gil(|gil| parse_expectations(expectations.bind(gil)))?;
+        let connection = Self::connection(self_, None).await?;
+        let Some(db_client) = connection.db_client() else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+        validate_schema(&db_client, &expected_tables).await
+    }
+
+    /// Remove cached results of the querystring.
+    ///
+    /// If querystring isn't passed, all cached results are removed.
+    #[pyo3(signature = (querystring=None))]
+    pub fn invalidate_cache(&self, querystring: Option<String>) {
+        self.result_cache.invalidate(querystring.as_deref());
+    }
+
+    /// Return statements which are prepared on every new connection.
+    ///
+    /// Every statement has number of connections it was prepared on
+    /// and error of the last failed prepare.
+    #[must_use]
+    pub fn warm_statements(&self) -> Vec<WarmStatement> {
+        self.warm_statements.snapshot()
+    }
+
+    /// Run querystring or async callable periodically in the background.
+    ///
+    /// Callable accepts `Connection` from the pool.
+    /// Every run waits `interval` seconds plus random part of `jitter` seconds
+    /// and holds session advisory lock on the task name,
+    /// so the same task isn't run by several processes at once,
+    /// such run is skipped.
+    /// Tasks are stopped when pool is closed.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Interval or jitter is invalid
+    /// 2) Callable is passed without running event loop
+    #[pyo3(signature = (
+        interval,
+        query_or_callable,
+        parameters=None,
+        *,
+        name=None,
+        jitter=0.0,
+        run_immediately=false,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn schedule(
+        &self,
+        py: Python<'_>,
+        interval: f64,
+        query_or_callable: Py<PyAny>,
+        parameters: Option<Py<PyAny>>,
+        name: Option<String>,
+        jitter: f64,
+        run_immediately: bool,
+    ) -> RustPSQLDriverPyResult<ScheduledTask> {
+        let query_or_callable = query_or_callable.into_bound(py);
+        let (job, default_name) = if let Ok(querystring) = query_or_callable.extract::<String>() {
+            (
+                ScheduledJob::Query {
+                    querystring: querystring.clone(),
+                    parameters,
+                },
+                querystring,
+            )
+        } else if query_or_callable.is_callable() {
+            let default_name = query_or_callable
+                .getattr("__qualname__")
+                .and_then(|qualname| qualname.extract::<String>())
+                .unwrap_or_else(|_| query_or_callable.to_string());
+            (
+                ScheduledJob::Callable {
+                    callback: query_or_callable.unbind(),
+                    task_locals: pyo3_async_runtimes::tokio::get_current_locals(py)?,
+                },
+                default_name,
+            )
+        } else {
+            return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                "Scheduled task must be querystring or async callable".into(),
+            ));
+        };
+
+        self.scheduler.schedule(
+            name.unwrap_or(default_name),
+            interval,
+            jitter,
+            run_immediately,
+            job,
+            SchedulerContext {
+                db_pool: self.pool.clone(),
+                pg_config: self.pg_config.clone(),
+                tls_options: self.tls_options.clone(),
+                ssl_mode: self.ssl_mode,
+                statement_stats: self.statement_stats.clone(),
+                statement_caches: self.statement_caches.clone(),
+                sqlcommenter: self.sqlcommenter.clone(),
+                column_codecs: self.column_codecs.clone(),
+            },
+        )
     }
 
     /// Close connection pool.
@@ -405,8 +1430,14 @@ impl ConnectionPool {
     /// # Errors
     /// May return Err Result if cannot get new connection from the pool.
     pub fn close(&self) {
+        tracing::info!("Connection pool is closed");
+        self.scheduler.stop_all();
+        self.affinity.clear();
         let db_pool = self.pool.clone();
 
         db_pool.close();
+        if let Some(ssh_tunnel) = &self.ssh_tunnel {
+            ssh_tunnel.close();
+        }
     }
 }
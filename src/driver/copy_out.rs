@@ -0,0 +1,78 @@
+use std::{pin::Pin, sync::Arc};
+
+use futures_util::StreamExt;
+use pyo3::{
+    exceptions::PyStopAsyncIteration, pyclass, pymethods, types::PyBytes, Py, PyAny, Python,
+};
+use tokio::sync::Mutex;
+use tokio_postgres::CopyOutStream;
+
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    runtime::rustdriver_future,
+};
+
+use super::inner_connection::PsqlpyConnection;
+
+/// Async iterator over chunks of the `COPY ... TO STDOUT` data.
+///
+/// Every chunk is `bytes` as it's received from PostgreSQL,
+/// so the whole table isn't kept in memory.
+/// Connection is busy until the iteration is over.
+#[pyclass]
+pub struct CopyOutChunks {
+    stream: Arc<Mutex<Option<Pin<Box<CopyOutStream>>>>>,
+    // Connection is kept, so it isn't returned to the pool during the copy.
+    _db_client: Arc<PsqlpyConnection>,
+}
+
+impl CopyOutChunks {
+    #[must_use]
+    pub fn new(stream: CopyOutStream, db_client: Arc<PsqlpyConnection>) -> Self {
+        CopyOutChunks {
+            stream: Arc::new(Mutex::new(Some(Box::pin(stream)))),
+            _db_client: db_client,
+        }
+    }
+}
+
+#[pymethods]
+impl CopyOutChunks {
+    #[must_use]
+    fn __aiter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    /// Return next chunk of the data.
+    ///
+    /// # Errors
+    /// May return Err Result if copy failed in the database.
+    fn __anext__(&self) -> RustPSQLDriverPyResult<Option<Py<PyAny>>> {
+        let stream = self.stream.clone();
+
+        let py_future = Python::with_gil(move |gil| {
+            rustdriver_future(gil, async move {
+                let mut stream = stream.lock().await;
+                let Some(copy_stream) = stream.as_mut() else {
+                    return Err(PyStopAsyncIteration::new_err("Copy is finished").into());
+                };
+
+                match copy_stream.next().await {
+                    Some(Ok(chunk)) => {
+                        Ok(Python::with_gil(|gil| PyBytes::new(gil, &chunk).unbind()))
+                    }
+                    Some(Err(err)) => {
+                        stream.take();
+                        Err(RustPSQLDriverError::from(err))
+                    }
+                    None => {
+                        stream.take();
+                        Err(PyStopAsyncIteration::new_err("Copy is finished").into())
+                    }
+                }
+            })
+        });
+
+        Ok(Some(py_future?))
+    }
+}
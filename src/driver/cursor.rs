@@ -1,25 +1,52 @@
 use std::{net::IpAddr, sync::Arc};
 
 use pyo3::{
-    exceptions::PyStopAsyncIteration, pyclass, pymethods, Py, PyAny, PyErr, PyObject, Python,
+    exceptions::PyStopAsyncIteration, pyclass, pymethods, types::PyAnyMethods, Py, PyAny, PyErr,
+    PyObject, Python,
 };
 use tokio_postgres::{config::Host, Config};
 
 use crate::{
     exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    format_helpers::{is_valid_ident, quote_ident, MAX_IDENT_LENGTH},
     query_result::PSQLDriverPyQueryResult,
     runtime::rustdriver_future,
 };
 
 use super::inner_connection::PsqlpyConnection;
 
+/// Return unique name for the cursor without user supplied name.
+#[must_use]
+pub fn generate_cursor_name() -> String {
+    format!("psqlpy_cursor_{}", uuid::Uuid::new_v4().simple())
+}
+
+/// Validate user supplied cursor name or generate a new unique one.
+///
+/// # Errors
+/// May return Err Result if cursor name doesn't follow
+/// `PostgreSQL` identifier rules.
+pub fn prepare_cursor_name(cursor_name: Option<String>) -> RustPSQLDriverPyResult<String> {
+    let Some(cursor_name) = cursor_name else {
+        return Ok(generate_cursor_name());
+    };
+    if !is_valid_ident(&cursor_name) {
+        return Err(RustPSQLDriverError::CursorStartError(format!(
+            "Invalid cursor name {cursor_name}, it must start with a letter or an underscore, contain only letters, digits, underscores or dollar signs and be no longer than {MAX_IDENT_LENGTH} bytes",
+        )));
+    }
+    Ok(cursor_name)
+}
+
 /// Additional implementation for the `Object` type.
 #[allow(clippy::ref_option)]
+#[allow(clippy::too_many_arguments)]
 trait CursorObjectTrait {
     async fn cursor_start(
         &self,
         cursor_name: &str,
         scroll: &Option<bool>,
+        hold: &Option<bool>,
         querystring: &str,
         prepared: &Option<bool>,
         parameters: &Option<Py<PyAny>>,
@@ -36,15 +63,18 @@ impl CursorObjectTrait for PsqlpyConnection {
     /// # Errors
     /// May return Err Result if cannot execute querystring.
     #[allow(clippy::ref_option)]
+    #[allow(clippy::too_many_arguments)]
     async fn cursor_start(
         &self,
         cursor_name: &str,
         scroll: &Option<bool>,
+        hold: &Option<bool>,
         querystring: &str,
         prepared: &Option<bool>,
         parameters: &Option<Py<PyAny>>,
     ) -> RustPSQLDriverPyResult<()> {
-        let mut cursor_init_query = format!("DECLARE {cursor_name}");
+        let mut cursor_init_query = format!("DECLARE {}", quote_ident(cursor_name));
+
         if let Some(scroll) = scroll {
             if *scroll {
                 cursor_init_query.push_str(" SCROLL");
@@ -53,7 +83,17 @@ impl CursorObjectTrait for PsqlpyConnection {
             }
         }
 
-        cursor_init_query.push_str(format!(" CURSOR FOR {querystring}").as_str());
+        cursor_init_query.push_str(" CURSOR");
+
+        if let Some(hold) = hold {
+            if *hold {
+                cursor_init_query.push_str(" WITH HOLD");
+            } else {
+                cursor_init_query.push_str(" WITHOUT HOLD");
+            }
+        }
+
+        cursor_init_query.push_str(format!(" FOR {querystring}").as_str());
 
         self.execute(cursor_init_query, parameters.clone(), *prepared)
             .await
@@ -78,7 +118,7 @@ impl CursorObjectTrait for PsqlpyConnection {
         }
 
         self.execute(
-            format!("CLOSE {cursor_name}"),
+            format!("CLOSE {}", quote_ident(cursor_name)),
             Option::default(),
             Some(false),
         )
@@ -97,12 +137,14 @@ pub struct Cursor {
     cursor_name: String,
     fetch_number: usize,
     scroll: Option<bool>,
+    hold: Option<bool>,
     prepared: Option<bool>,
     is_started: bool,
     closed: bool,
 }
 
 impl Cursor {
+    #[allow(clippy::too_many_arguments)]
     #[must_use]
     pub fn new(
         db_transaction: Arc<PsqlpyConnection>,
@@ -112,6 +154,7 @@ impl Cursor {
         cursor_name: String,
         fetch_number: usize,
         scroll: Option<bool>,
+        hold: Option<bool>,
         prepared: Option<bool>,
     ) -> Self {
         Cursor {
@@ -122,11 +165,68 @@ impl Cursor {
             cursor_name,
             fetch_number,
             scroll,
+            hold,
             prepared,
             is_started: false,
             closed: false,
         }
     }
+
+    /// Check that cursor is declared in the database and isn't closed yet.
+    #[must_use]
+    pub fn is_open(&self) -> bool {
+        self.is_started && !self.closed && self.db_transaction.is_some()
+    }
+
+    /// Check that cursor is closed or its connection is gone.
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.closed || self.db_transaction.is_none()
+    }
+
+    /// Mark cursor closed after `CLOSE` is executed by its owner.
+    pub fn mark_closed(&mut self) {
+        self.closed = true;
+        std::mem::take(&mut self.db_transaction);
+    }
+
+    /// Execute FETCH command and return rows as Python dicts.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Cursor is closed
+    /// 2) Cannot execute query
+    async fn fetch_dict_rows(
+        slf: &Py<Self>,
+        fetch_direction: String,
+    ) -> RustPSQLDriverPyResult<(bool, Py<PyAny>)> {
+        let (db_transaction, cursor_name) = Python::with_gil(|gil| {
+            let self_ = slf.borrow(gil);
+            (
+                self_.db_transaction.clone(),
+                quote_ident(&self_.cursor_name),
+            )
+        });
+
+        let Some(db_transaction) = db_transaction else {
+            return Err(RustPSQLDriverError::CursorClosedError);
+        };
+        let result = db_transaction
+            .execute(
+                format!("FETCH {fetch_direction} FROM {cursor_name}"),
+                None,
+                Some(false),
+            )
+            .await
+            .map_err(|err| {
+                RustPSQLDriverError::CursorFetchError(format!(
+                    "Cannot fetch data from cursor, error - {err}"
+                ))
+            })?;
+
+        let rows = Python::with_gil(|gil| result.result(gil, None, None, None))?;
+        Ok((result.is_empty(), rows))
+    }
 }
 
 #[pymethods]
@@ -204,7 +304,7 @@ impl Cursor {
     }
 
     #[getter]
-    fn cursor_name(&self) -> String {
+    pub fn cursor_name(&self) -> String {
         return self.cursor_name.clone();
     }
 
@@ -223,6 +323,32 @@ impl Cursor {
         return self.prepared.clone();
     }
 
+    #[getter]
+    fn fetch_number(&self) -> usize {
+        self.fetch_number
+    }
+
+    /// Number of rows fetched by `fetchmany` by default, as in DBAPI.
+    #[getter]
+    fn get_arraysize(&self) -> usize {
+        self.fetch_number
+    }
+
+    #[setter]
+    fn set_arraysize(&mut self, arraysize: usize) {
+        self.fetch_number = arraysize;
+    }
+
+    #[getter]
+    fn scroll(&self) -> Option<bool> {
+        self.scroll
+    }
+
+    #[getter]
+    fn hold(&self) -> Option<bool> {
+        self.hold
+    }
+
     #[must_use]
     fn __aiter__(slf: Py<Self>) -> Py<Self> {
         slf
@@ -233,13 +359,14 @@ impl Cursor {
     }
 
     async fn __aenter__<'a>(slf: Py<Self>) -> RustPSQLDriverPyResult<Py<Self>> {
-        let (db_transaction, cursor_name, scroll, querystring, prepared, parameters) =
+        let (db_transaction, cursor_name, scroll, hold, querystring, prepared, parameters) =
             Python::with_gil(|gil| {
                 let self_ = slf.borrow(gil);
                 (
                     self_.db_transaction.clone(),
                     self_.cursor_name.clone(),
                     self_.scroll,
+                    self_.hold,
                     self_.querystring.clone(),
                     self_.prepared,
                     self_.parameters.clone(),
@@ -248,7 +375,14 @@ impl Cursor {
 
         if let Some(db_transaction) = db_transaction {
             db_transaction
-                .cursor_start(&cursor_name, &scroll, &querystring, &prepared, &parameters)
+                .cursor_start(
+                    &cursor_name,
+                    &scroll,
+                    &hold,
+                    &querystring,
+                    &prepared,
+                    &parameters,
+                )
                 .await?;
             Python::with_gil(|gil| {
                 let mut self_ = slf.borrow_mut(gil);
@@ -289,6 +423,7 @@ impl Cursor {
                 })?;
             pyo3::Python::with_gil(|gil| {
                 let mut self_ = slf.borrow_mut(gil);
+                self_.closed = true;
                 std::mem::take(&mut self_.db_transaction);
             });
             if !is_exception_none {
@@ -310,7 +445,7 @@ impl Cursor {
     fn __anext__(&self) -> RustPSQLDriverPyResult<Option<PyObject>> {
         let db_transaction = self.db_transaction.clone();
         let fetch_number = self.fetch_number;
-        let cursor_name = self.cursor_name.clone();
+        let cursor_name = quote_ident(&self.cursor_name);
         let py_future = Python::with_gil(move |gil| {
             rustdriver_future(gil, async move {
                 if let Some(db_transaction) = db_transaction {
@@ -351,6 +486,7 @@ impl Cursor {
                 .cursor_start(
                     &self.cursor_name,
                     &self.scroll,
+                    &self.hold,
                     &self.querystring,
                     &self.prepared,
                     &self.parameters,
@@ -402,7 +538,7 @@ impl Cursor {
             (
                 self_.db_transaction.clone(),
                 self_.fetch_number,
-                self_.cursor_name.clone(),
+                quote_ident(&self_.cursor_name),
             )
         });
 
@@ -440,7 +576,10 @@ impl Cursor {
     pub async fn fetch_next<'a>(slf: Py<Self>) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
         let (db_transaction, cursor_name) = Python::with_gil(|gil| {
             let self_ = slf.borrow(gil);
-            (self_.db_transaction.clone(), self_.cursor_name.clone())
+            (
+                self_.db_transaction.clone(),
+                quote_ident(&self_.cursor_name),
+            )
         });
 
         if let Some(db_transaction) = db_transaction {
@@ -467,7 +606,10 @@ impl Cursor {
     pub async fn fetch_prior<'a>(slf: Py<Self>) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
         let (db_transaction, cursor_name) = Python::with_gil(|gil| {
             let self_ = slf.borrow(gil);
-            (self_.db_transaction.clone(), self_.cursor_name.clone())
+            (
+                self_.db_transaction.clone(),
+                quote_ident(&self_.cursor_name),
+            )
         });
 
         if let Some(db_transaction) = db_transaction {
@@ -494,7 +636,10 @@ impl Cursor {
     pub async fn fetch_first<'a>(slf: Py<Self>) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
         let (db_transaction, cursor_name) = Python::with_gil(|gil| {
             let self_ = slf.borrow(gil);
-            (self_.db_transaction.clone(), self_.cursor_name.clone())
+            (
+                self_.db_transaction.clone(),
+                quote_ident(&self_.cursor_name),
+            )
         });
 
         if let Some(db_transaction) = db_transaction {
@@ -521,7 +666,10 @@ impl Cursor {
     pub async fn fetch_last<'a>(slf: Py<Self>) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
         let (db_transaction, cursor_name) = Python::with_gil(|gil| {
             let self_ = slf.borrow(gil);
-            (self_.db_transaction.clone(), self_.cursor_name.clone())
+            (
+                self_.db_transaction.clone(),
+                quote_ident(&self_.cursor_name),
+            )
         });
 
         if let Some(db_transaction) = db_transaction {
@@ -551,7 +699,10 @@ impl Cursor {
     ) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
         let (db_transaction, cursor_name) = Python::with_gil(|gil| {
             let self_ = slf.borrow(gil);
-            (self_.db_transaction.clone(), self_.cursor_name.clone())
+            (
+                self_.db_transaction.clone(),
+                quote_ident(&self_.cursor_name),
+            )
         });
 
         if let Some(db_transaction) = db_transaction {
@@ -585,7 +736,10 @@ impl Cursor {
     ) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
         let (db_transaction, cursor_name) = Python::with_gil(|gil| {
             let self_ = slf.borrow(gil);
-            (self_.db_transaction.clone(), self_.cursor_name.clone())
+            (
+                self_.db_transaction.clone(),
+                quote_ident(&self_.cursor_name),
+            )
         });
 
         if let Some(db_transaction) = db_transaction {
@@ -618,7 +772,10 @@ impl Cursor {
     ) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
         let (db_transaction, cursor_name) = Python::with_gil(|gil| {
             let self_ = slf.borrow(gil);
-            (self_.db_transaction.clone(), self_.cursor_name.clone())
+            (
+                self_.db_transaction.clone(),
+                quote_ident(&self_.cursor_name),
+            )
         });
 
         if let Some(db_transaction) = db_transaction {
@@ -652,7 +809,10 @@ impl Cursor {
     ) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
         let (db_transaction, cursor_name) = Python::with_gil(|gil| {
             let self_ = slf.borrow(gil);
-            (self_.db_transaction.clone(), self_.cursor_name.clone())
+            (
+                self_.db_transaction.clone(),
+                quote_ident(&self_.cursor_name),
+            )
         });
 
         if let Some(db_transaction) = db_transaction {
@@ -685,7 +845,10 @@ impl Cursor {
     ) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
         let (db_transaction, cursor_name) = Python::with_gil(|gil| {
             let self_ = slf.borrow(gil);
-            (self_.db_transaction.clone(), self_.cursor_name.clone())
+            (
+                self_.db_transaction.clone(),
+                quote_ident(&self_.cursor_name),
+            )
         });
 
         if let Some(db_transaction) = db_transaction {
@@ -706,4 +869,49 @@ impl Cursor {
 
         Err(RustPSQLDriverError::CursorClosedError)
     }
+
+    /// Fetch next row from cursor with DBAPI semantics.
+    ///
+    /// Returns row as a dict or None if cursor is exhausted.
+    ///
+    /// # Errors
+    /// May return Err Result if cannot execute query.
+    pub async fn fetchone(slf: Py<Self>) -> RustPSQLDriverPyResult<Option<Py<PyAny>>> {
+        let (is_empty, rows) = Self::fetch_dict_rows(&slf, "NEXT".into()).await?;
+        if is_empty {
+            return Ok(None);
+        }
+
+        Ok(Some(Python::with_gil(|gil| {
+            rows.bind(gil).get_item(0).map(pyo3::Bound::unbind)
+        })?))
+    }
+
+    /// Fetch next rows from cursor with DBAPI semantics.
+    ///
+    /// `size` defaults to `arraysize`.
+    /// Returns list of dicts, it's empty if cursor is exhausted.
+    ///
+    /// # Errors
+    /// May return Err Result if cannot execute query.
+    #[pyo3(signature = (size=None))]
+    pub async fn fetchmany(
+        slf: Py<Self>,
+        size: Option<usize>,
+    ) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        let size = size.unwrap_or_else(|| Python::with_gil(|gil| slf.borrow(gil).fetch_number));
+        let (_, rows) = Self::fetch_dict_rows(&slf, format!("FORWARD {size}")).await?;
+        Ok(rows)
+    }
+
+    /// Fetch all remaining rows from cursor with DBAPI semantics.
+    ///
+    /// Returns list of dicts, it's empty if cursor is exhausted.
+    ///
+    /// # Errors
+    /// May return Err Result if cannot execute query.
+    pub async fn fetchall(slf: Py<Self>) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        let (_, rows) = Self::fetch_dict_rows(&slf, "FORWARD ALL".into()).await?;
+        Ok(rows)
+    }
 }
@@ -0,0 +1,237 @@
+use pyo3::{pyclass, pymethods};
+
+use crate::exceptions::rust_errors::RustPSQLDriverPyResult;
+
+use super::inner_connection::PsqlpyConnection;
+
+/// State of the session from `pg_stat_activity` and `pg_stat_database`.
+///
+/// Transaction duration is NULL outside of the explicit transaction,
+/// its start is the same as the start of the diagnostics query.
+const SESSION_QUERY: &str = "SELECT a.pid, a.application_name, \
+     EXTRACT(EPOCH FROM clock_timestamp() - a.backend_start)::float8, \
+     CASE WHEN a.xact_start <> a.query_start \
+     THEN EXTRACT(EPOCH FROM clock_timestamp() - a.xact_start)::float8 END, \
+     a.backend_xid::text, a.backend_xmin::text, \
+     ARRAY(SELECT b.pid FROM pg_stat_activity b \
+     WHERE a.pid = ANY(pg_blocking_pids(b.pid)))::int4[], \
+     (SELECT COALESCE(sum(pg_total_relation_size(c.oid)), 0)::int8 FROM pg_class c \
+     WHERE c.relnamespace = pg_my_temp_schema() AND c.relkind = 'r'), \
+     d.temp_files, d.temp_bytes, d.blks_hit, d.blks_read, \
+     COALESCE(has_table_privilege(\
+     to_regclass('pg_catalog.pg_backend_memory_contexts'), 'SELECT'), false) \
+     FROM pg_stat_activity a CROSS JOIN pg_stat_database d \
+     WHERE a.pid = pg_backend_pid() AND d.datname = current_database()";
+
+/// Locks of the session, locks taken by the diagnostics queries
+/// on the catalog are skipped.
+const LOCKS_QUERY: &str = "SELECT l.locktype, l.mode, l.granted, l.relation::regclass::text \
+     FROM pg_locks l \
+     WHERE l.pid = pg_backend_pid() AND l.locktype <> 'virtualxid' \
+     AND NOT (l.mode = 'AccessShareLock' AND l.relation IN \
+     (SELECT c.oid FROM pg_class c WHERE c.relnamespace = 'pg_catalog'::regnamespace))";
+
+const MEMORY_QUERY: &str = "SELECT sum(used_bytes)::int8 FROM pg_backend_memory_contexts";
+
+/// Lock held or awaited by the session.
+#[pyclass]
+#[derive(Clone)]
+pub struct LockInfo {
+    locktype: String,
+    mode: String,
+    granted: bool,
+    relation: Option<String>,
+}
+
+#[pymethods]
+impl LockInfo {
+    #[getter]
+    fn locktype(&self) -> String {
+        self.locktype.clone()
+    }
+
+    #[getter]
+    fn mode(&self) -> String {
+        self.mode.clone()
+    }
+
+    #[getter]
+    fn granted(&self) -> bool {
+        self.granted
+    }
+
+    /// Name of the locked relation, None for the other lock types.
+    #[getter]
+    fn relation(&self) -> Option<String> {
+        self.relation.clone()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "LockInfo(locktype={:?}, mode={:?}, granted={}, relation={:?})",
+            self.locktype, self.mode, self.granted, self.relation,
+        )
+    }
+}
+
+/// Snapshot of the session state for debugging.
+///
+/// PostgreSQL doesn't count buffers and temp files per session,
+/// so these counters are taken for the whole database.
+#[pyclass]
+#[derive(Clone)]
+pub struct SessionDiagnostics {
+    backend_pid: i32,
+    application_name: Option<String>,
+    session_duration: Option<f64>,
+    transaction_duration: Option<f64>,
+    backend_xid: Option<String>,
+    backend_xmin: Option<String>,
+    blocked_pids: Vec<i32>,
+    locks: Vec<LockInfo>,
+    temp_tables_bytes: i64,
+    memory_used_bytes: Option<i64>,
+    database_temp_files: i64,
+    database_temp_bytes: i64,
+    database_blocks_hit: i64,
+    database_blocks_read: i64,
+}
+
+#[pymethods]
+impl SessionDiagnostics {
+    #[getter]
+    fn backend_pid(&self) -> i32 {
+        self.backend_pid
+    }
+
+    #[getter]
+    fn application_name(&self) -> Option<String> {
+        self.application_name.clone()
+    }
+
+    /// Seconds since the session was started.
+    #[getter]
+    fn session_duration(&self) -> Option<f64> {
+        self.session_duration
+    }
+
+    /// Seconds since the transaction was started, None outside of it.
+    #[getter]
+    fn transaction_duration(&self) -> Option<f64> {
+        self.transaction_duration
+    }
+
+    #[getter]
+    fn backend_xid(&self) -> Option<String> {
+        self.backend_xid.clone()
+    }
+
+    #[getter]
+    fn backend_xmin(&self) -> Option<String> {
+        self.backend_xmin.clone()
+    }
+
+    /// PIDs of the backends waiting for the locks held by the session.
+    #[getter]
+    fn blocked_pids(&self) -> Vec<i32> {
+        self.blocked_pids.clone()
+    }
+
+    #[getter]
+    fn locks(&self) -> Vec<LockInfo> {
+        self.locks.clone()
+    }
+
+    /// Size of the temporary tables of the session.
+    #[getter]
+    fn temp_tables_bytes(&self) -> i64 {
+        self.temp_tables_bytes
+    }
+
+    /// Memory used by the backend, None if role cannot read
+    /// `pg_backend_memory_contexts` or server is older than 14.
+    #[getter]
+    fn memory_used_bytes(&self) -> Option<i64> {
+        self.memory_used_bytes
+    }
+
+    #[getter]
+    fn database_temp_files(&self) -> i64 {
+        self.database_temp_files
+    }
+
+    #[getter]
+    fn database_temp_bytes(&self) -> i64 {
+        self.database_temp_bytes
+    }
+
+    #[getter]
+    fn database_blocks_hit(&self) -> i64 {
+        self.database_blocks_hit
+    }
+
+    #[getter]
+    fn database_blocks_read(&self) -> i64 {
+        self.database_blocks_read
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "SessionDiagnostics(backend_pid={}, transaction_duration={:?}, locks={}, blocked_pids={:?})",
+            self.backend_pid,
+            self.transaction_duration,
+            self.locks.len(),
+            self.blocked_pids,
+        )
+    }
+}
+
+/// Take snapshot of the session state.
+///
+/// # Errors
+/// May return Err Result if cannot query statistics views.
+pub async fn fetch_session_diagnostics(
+    db_client: &PsqlpyConnection,
+) -> RustPSQLDriverPyResult<SessionDiagnostics> {
+    let session = db_client.query_one(SESSION_QUERY, &[]).await?;
+    let locks = db_client
+        .query(LOCKS_QUERY, &[])
+        .await?
+        .iter()
+        .map(|row| {
+            Ok(LockInfo {
+                locktype: row.try_get(0)?,
+                mode: row.try_get(1)?,
+                granted: row.try_get(2)?,
+                relation: row.try_get(3)?,
+            })
+        })
+        .collect::<RustPSQLDriverPyResult<Vec<_>>>()?;
+
+    let can_read_memory: bool = session.try_get(12)?;
+    let memory_used_bytes = if can_read_memory {
+        db_client
+            .query_one(MEMORY_QUERY, &[])
+            .await?
+            .try_get::<_, Option<i64>>(0)?
+    } else {
+        None
+    };
+
+    Ok(SessionDiagnostics {
+        backend_pid: session.try_get(0)?,
+        application_name: session.try_get(1)?,
+        session_duration: session.try_get(2)?,
+        transaction_duration: session.try_get(3)?,
+        backend_xid: session.try_get(4)?,
+        backend_xmin: session.try_get(5)?,
+        blocked_pids: session.try_get(6)?,
+        locks,
+        temp_tables_bytes: session.try_get(7)?,
+        memory_used_bytes,
+        database_temp_files: session.try_get(8)?,
+        database_temp_bytes: session.try_get(9)?,
+        database_blocks_hit: session.try_get(10)?,
+        database_blocks_read: session.try_get(11)?,
+    })
+}
@@ -0,0 +1,213 @@
+use std::{future::Future, sync::Arc, time::Duration};
+
+use deadpool_postgres::Object;
+
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    format_helpers::normalize_query,
+    runtime::tokio_runtime,
+};
+
+use super::inner_connection::PsqlpyConnection;
+
+/// Time to roll back the dry-run transaction of the dropped statement.
+const DROPPED_ROLLBACK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Statements which change data, schema or privileges.
+const MUTATING_COMMANDS: &[&str] = &[
+    "insert", "update", "delete", "merge", "truncate", "create", "alter", "drop", "grant",
+    "revoke", "comment", "security", "call", "do", "refresh", "reindex", "cluster", "vacuum",
+    "analyze", "lock", "import", "reassign",
+];
+
+/// Statements which end the dry-run transaction.
+const TRANSACTION_CONTROL_COMMANDS: &[&str] = &[
+    "begin",
+    "start",
+    "commit",
+    "end",
+    "rollback",
+    "abort",
+    "savepoint",
+    "release",
+];
+
+fn statement_words(statement: &str) -> Vec<&str> {
+    statement
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')' || c == ',')
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Check that normalized statement changes the database.
+///
+/// `WITH` queries are mutating if any of the CTEs is,
+/// `SELECT ... INTO` creates a table, `EXPLAIN ANALYZE` executes the statement.
+/// Functions called from `SELECT` aren't inspected.
+fn is_mutating_statement(statement: &str) -> bool {
+    let words = statement_words(statement);
+    match words.first().copied().unwrap_or_default() {
+        "with" => words
+            .iter()
+            .skip(1)
+            .any(|word| matches!(*word, "insert" | "update" | "delete" | "merge")),
+        "select" => words.contains(&"into"),
+        "explain" => {
+            words.contains(&"analyze")
+                && words
+                    .iter()
+                    .any(|word| matches!(*word, "insert" | "update" | "delete" | "merge"))
+        }
+        "copy" => words.contains(&"from"),
+        first_word => MUTATING_COMMANDS.contains(&first_word),
+    }
+}
+
+/// Check that any statement of the querystring changes the database.
+#[must_use]
+pub fn is_mutating_query(querystring: &str) -> bool {
+    normalize_query(querystring)
+        .split(';')
+        .any(|statement| is_mutating_statement(statement.trim()))
+}
+
+/// Check that querystring doesn't control the transaction,
+/// otherwise changes could be committed.
+///
+/// # Errors
+/// May return Err Result if any statement of the querystring
+/// begins or ends transaction or savepoint.
+pub fn check_dry_run_query(querystring: &str) -> RustPSQLDriverPyResult<()> {
+    for statement in normalize_query(querystring).split(';') {
+        let statement = statement.trim();
+        let words = statement_words(statement);
+        let is_transaction_control = match words.first().copied().unwrap_or_default() {
+            "prepare" => words.get(1) == Some(&"transaction"),
+            first_word => TRANSACTION_CONTROL_COMMANDS.contains(&first_word),
+        };
+        if is_transaction_control {
+            return Err(RustPSQLDriverError::ConnectionExecuteError(format!(
+                "Transaction control statement is not supported in dry-run mode, statement - {statement}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Check that statement must be rolled back in dry-run mode.
+///
+/// # Errors
+/// May return Err Result if querystring controls the transaction
+/// in dry-run mode.
+pub fn dry_run_rollback(dry_run: bool, querystring: &str) -> RustPSQLDriverPyResult<bool> {
+    if !dry_run {
+        return Ok(false);
+    }
+    check_dry_run_query(querystring)?;
+    Ok(is_mutating_query(querystring))
+}
+
+/// Fail if the method cannot be executed in dry-run mode.
+///
+/// # Errors
+/// May return Err Result if dry-run mode is on.
+pub fn reject_dry_run(dry_run: bool, method: &str) -> RustPSQLDriverPyResult<()> {
+    if dry_run {
+        return Err(RustPSQLDriverError::ConnectionExecuteError(format!(
+            "{method} is not supported in dry-run mode"
+        )));
+    }
+    Ok(())
+}
+
+/// Roll back the dry-run transaction of the dropped statement.
+///
+/// Rollback is answered only after the statement, so its changes are undone too.
+/// Connection from the pool is closed if it cannot be rolled back.
+async fn rollback_dropped(db_client: Arc<PsqlpyConnection>, rollback: &'static str) {
+    let rolled_back =
+        tokio::time::timeout(DROPPED_ROLLBACK_TIMEOUT, db_client.batch_execute(rollback))
+            .await
+            .is_ok_and(|result| result.is_ok());
+    if !rolled_back {
+        tracing::warn!("Cannot roll back dry-run statement of the dropped future");
+        if let Ok(PsqlpyConnection::PoolConn(object, ..)) = Arc::try_unwrap(db_client) {
+            let _ = Object::take(object);
+        }
+    }
+}
+
+/// Guard which rolls back the dry-run transaction if its future is dropped
+/// before the end, e.g. when Python coroutine is cancelled.
+struct RollbackOnDrop {
+    db_client: Option<Arc<PsqlpyConnection>>,
+    rollback: &'static str,
+}
+
+impl RollbackOnDrop {
+    /// Statement is finished and rolled back, nothing to do on drop.
+    fn disarm(mut self) {
+        self.db_client = None;
+    }
+}
+
+impl Drop for RollbackOnDrop {
+    fn drop(&mut self) {
+        if let Some(db_client) = self.db_client.take() {
+            tokio_runtime().spawn(rollback_dropped(db_client, self.rollback));
+        }
+    }
+}
+
+/// Execute statement and roll back its changes if `rollback` is set.
+///
+/// Outside of the transaction statement gets its own transaction,
+/// inside of the transaction savepoint is used,
+/// so the outer transaction stays usable.
+/// If the future is dropped before the end, changes are rolled back in background.
+///
+/// # Errors
+/// May return Err Result if:
+/// 1) Cannot begin or roll back the transaction
+/// 2) Statement failed
+pub async fn run_dry<T, F>(
+    db_client: &Arc<PsqlpyConnection>,
+    rollback: bool,
+    in_transaction: bool,
+    statement: F,
+) -> RustPSQLDriverPyResult<T>
+where
+    F: Future<Output = RustPSQLDriverPyResult<T>>,
+{
+    if !rollback {
+        return statement.await;
+    }
+
+    let (begin, rollback) = if in_transaction {
+        (
+            "SAVEPOINT psqlpy_dry_run",
+            "ROLLBACK TO SAVEPOINT psqlpy_dry_run; RELEASE SAVEPOINT psqlpy_dry_run",
+        )
+    } else {
+        ("BEGIN", "ROLLBACK")
+    };
+    let rollback_guard = |db_client: &Arc<PsqlpyConnection>| RollbackOnDrop {
+        db_client: Some(db_client.clone()),
+        rollback,
+    };
+    // `ROLLBACK` without the transaction is harmless, but rolling back to the savepoint
+    // which wasn't created breaks the outer transaction.
+    let guard = if in_transaction {
+        db_client.batch_execute(begin).await?;
+        rollback_guard(db_client)
+    } else {
+        let guard = rollback_guard(db_client);
+        db_client.batch_execute(begin).await?;
+        guard
+    };
+    let result = statement.await;
+    let rolled_back = db_client.batch_execute(rollback).await;
+    guard.disarm();
+    rolled_back?;
+    result
+}
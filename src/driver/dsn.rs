@@ -0,0 +1,285 @@
+use std::{
+    iter::Peekable,
+    str::{Chars, FromStr},
+};
+
+use crate::exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult};
+
+use super::common_options::SslMode;
+
+const DEFAULT_PORT: &str = "5432";
+const URI_SCHEMES: &[&str] = &["postgresql://", "postgres://"];
+/// libpq parameters which are accepted but have no effect,
+/// psqlpy doesn't read password and service files and doesn't check CRL.
+const IGNORED_PARAMETERS: &[&str] = &["passfile", "service", "sslpassword", "sslcrl"];
+
+/// Parameters of the DSN which are handled by psqlpy
+/// instead of `tokio-postgres`.
+#[derive(Clone, Default)]
+pub struct DsnOptions {
+    pub ssl_mode: Option<SslMode>,
+    /// `sslrootcert` parameter.
+    pub ca_file: Option<String>,
+    /// `sslcert` parameter.
+    pub ssl_cert: Option<String>,
+    /// `sslkey` parameter.
+    pub ssl_key: Option<String>,
+    /// `target_session_attrs=prefer-standby` parameter.
+    pub prefer_standby: bool,
+}
+
+/// Parsed libpq connection string.
+pub struct ParsedDsn {
+    pub config: tokio_postgres::Config,
+    pub options: DsnOptions,
+}
+
+/// Parse libpq connection string, URI or `key=value` pairs.
+///
+/// URI may have several comma-separated hosts with ports
+/// and percent-encoded parts, parameters go after `?`.
+///
+/// `passfile`, `service`, `sslpassword` and `sslcrl` are ignored,
+/// `gssencmode` can't be `require` and `client_encoding` must be `UTF8`.
+///
+/// # Errors
+/// May return Err Result if:
+/// 1) DSN has wrong syntax or invalid percent-encoding
+/// 2) DSN has unknown parameter or invalid value
+pub fn parse_dsn(dsn: &str) -> RustPSQLDriverPyResult<ParsedDsn> {
+    let parameters = match URI_SCHEMES
+        .iter()
+        .find_map(|scheme| dsn.strip_prefix(scheme))
+    {
+        Some(uri) => parse_uri(uri)?,
+        None => parse_key_value(dsn)?,
+    };
+
+    let mut options = DsnOptions::default();
+    let mut target_session_attrs = None;
+    let mut config_parameters = Vec::with_capacity(parameters.len());
+    for (key, value) in parameters {
+        match key.as_str() {
+            "sslmode" => options.ssl_mode = Some(parse_ssl_mode(&value)?),
+            "sslrootcert" => options.ca_file = Some(value),
+            "sslcert" => options.ssl_cert = Some(value),
+            "sslkey" => options.ssl_key = Some(value),
+            // Read-only session is tried separately before the config is used.
+            "target_session_attrs" if value == "prefer-standby" => {
+                options.prefer_standby = true;
+            }
+            "target_session_attrs" => {
+                target_session_attrs = Some(parse_target_session_attrs(&value)?);
+            }
+            // There is no GSSAPI encryption, so it can be only preferred.
+            "gssencmode" if value != "require" => {}
+            // Connection always uses UTF8 encoding.
+            "client_encoding"
+                if matches!(
+                    value.to_ascii_uppercase().as_str(),
+                    "UTF8" | "UTF-8" | "UNICODE" | "AUTO"
+                ) => {}
+            key if IGNORED_PARAMETERS.contains(&key) => {}
+            _ => config_parameters.push(format!("{key}={}", quote_value(&value))),
+        }
+    }
+
+    let mut config = tokio_postgres::Config::from_str(&config_parameters.join(" "))
+        .map_err(|err| dsn_error(&err.to_string()))?;
+    if let Some(ssl_mode) = options.ssl_mode {
+        config.ssl_mode(ssl_mode.to_internal());
+    }
+    if let Some(target_session_attrs) = target_session_attrs {
+        config.target_session_attrs(target_session_attrs);
+    }
+    Ok(ParsedDsn { config, options })
+}
+
+fn dsn_error(message: &str) -> RustPSQLDriverError {
+    RustPSQLDriverError::ConnectionPoolBuildError(format!(
+        "Cannot parse configuration from dsn string, error - {message}"
+    ))
+}
+
+fn parse_ssl_mode(value: &str) -> RustPSQLDriverPyResult<SslMode> {
+    match value {
+        "disable" => Ok(SslMode::Disable),
+        "allow" => Ok(SslMode::Allow),
+        "prefer" => Ok(SslMode::Prefer),
+        "require" => Ok(SslMode::Require),
+        "verify-ca" => Ok(SslMode::VerifyCa),
+        "verify-full" => Ok(SslMode::VerifyFull),
+        _ => Err(dsn_error(&format!("invalid sslmode {value:?}"))),
+    }
+}
+
+/// Parse `target_session_attrs` except `prefer-standby`.
+///
+/// Session is checked with `transaction_read_only`,
+/// so `primary` means read-write session and `standby` means read-only one.
+fn parse_target_session_attrs(
+    value: &str,
+) -> RustPSQLDriverPyResult<tokio_postgres::config::TargetSessionAttrs> {
+    match value {
+        "any" => Ok(tokio_postgres::config::TargetSessionAttrs::Any),
+        "read-write" | "primary" => Ok(tokio_postgres::config::TargetSessionAttrs::ReadWrite),
+        "read-only" | "standby" => Ok(tokio_postgres::config::TargetSessionAttrs::ReadOnly),
+        _ => Err(dsn_error(&format!(
+            "invalid target_session_attrs {value:?}"
+        ))),
+    }
+}
+
+/// Quote value for `key=value` format of `tokio-postgres`.
+fn quote_value(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// Decode `%XX` sequences of the URI part.
+fn percent_decode(value: &str) -> RustPSQLDriverPyResult<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx] == b'%' {
+            let byte = value
+                .get(idx + 1..idx + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| dsn_error(&format!("invalid percent-encoding in {value:?}")))?;
+            decoded.push(byte);
+            idx += 3;
+        } else {
+            decoded.push(bytes[idx]);
+            idx += 1;
+        }
+    }
+    String::from_utf8(decoded)
+        .map_err(|_| dsn_error(&format!("percent-encoded {value:?} isn't UTF-8")))
+}
+
+/// Parse URI without the scheme:
+/// `user:password@host1:port1,host2/dbname?key=value&key=value`.
+fn parse_uri(uri: &str) -> RustPSQLDriverPyResult<Vec<(String, String)>> {
+    let (uri, query) = uri.split_once('?').unwrap_or((uri, ""));
+    let (netloc, dbname) = uri.split_once('/').unwrap_or((uri, ""));
+    let (userinfo, hosts) = match netloc.rsplit_once('@') {
+        Some((userinfo, hosts)) => (Some(userinfo), hosts),
+        None => (None, netloc),
+    };
+
+    let mut parameters = vec![];
+    if let Some(userinfo) = userinfo {
+        let (user, password) = match userinfo.split_once(':') {
+            Some((user, password)) => (user, Some(password)),
+            None => (userinfo, None),
+        };
+        if !user.is_empty() {
+            parameters.push(("user".into(), percent_decode(user)?));
+        }
+        if let Some(password) = password {
+            parameters.push(("password".into(), percent_decode(password)?));
+        }
+    }
+
+    if !hosts.is_empty() {
+        let mut host_names = vec![];
+        let mut ports = vec![];
+        for host in hosts.split(',') {
+            let (host, port) = split_host_port(host)?;
+            host_names.push(percent_decode(host)?);
+            ports.push(port);
+        }
+        parameters.push(("host".into(), host_names.join(",")));
+        // Hosts without port use the default one, like in libpq.
+        if ports.iter().any(Option::is_some) {
+            let ports: Vec<&str> = ports
+                .into_iter()
+                .map(|port| port.unwrap_or(DEFAULT_PORT))
+                .collect();
+            parameters.push(("port".into(), ports.join(",")));
+        }
+    }
+
+    if !dbname.is_empty() {
+        parameters.push(("dbname".into(), percent_decode(dbname)?));
+    }
+
+    for parameter in query.split('&').filter(|parameter| !parameter.is_empty()) {
+        let Some((key, value)) = parameter.split_once('=') else {
+            return Err(dsn_error(&format!("parameter {parameter:?} has no value")));
+        };
+        parameters.push((percent_decode(key)?, percent_decode(value)?));
+    }
+    Ok(parameters)
+}
+
+/// Split `host:port`, IPv6 address must be in brackets.
+fn split_host_port(host: &str) -> RustPSQLDriverPyResult<(&str, Option<&str>)> {
+    if let Some(host) = host.strip_prefix('[') {
+        let Some((address, rest)) = host.split_once(']') else {
+            return Err(dsn_error(&format!("unclosed bracket in host {host:?}")));
+        };
+        return match rest.strip_prefix(':') {
+            Some(port) => Ok((address, Some(port))),
+            None if rest.is_empty() => Ok((address, None)),
+            None => Err(dsn_error(&format!("invalid host {host:?}"))),
+        };
+    }
+    match host.split_once(':') {
+        Some((host, port)) => Ok((host, Some(port))),
+        None => Ok((host, None)),
+    }
+}
+
+fn skip_whitespaces(chars: &mut Peekable<Chars<'_>>) {
+    while chars
+        .peek()
+        .is_some_and(|character| character.is_whitespace())
+    {
+        chars.next();
+    }
+}
+
+/// Parse `key=value key='quoted value'` pairs,
+/// backslash escapes the next character.
+fn parse_key_value(dsn: &str) -> RustPSQLDriverPyResult<Vec<(String, String)>> {
+    let mut parameters = vec![];
+    let mut chars = dsn.chars().peekable();
+    loop {
+        skip_whitespaces(&mut chars);
+        if chars.peek().is_none() {
+            return Ok(parameters);
+        }
+
+        let mut key = String::new();
+        while let Some(character) =
+            chars.next_if(|character| *character != '=' && !character.is_whitespace())
+        {
+            key.push(character);
+        }
+        skip_whitespaces(&mut chars);
+        if chars.next() != Some('=') {
+            return Err(dsn_error(&format!("parameter {key:?} has no value")));
+        }
+        skip_whitespaces(&mut chars);
+
+        let mut value = String::new();
+        let quoted = chars.next_if_eq(&'\'').is_some();
+        loop {
+            match chars.next() {
+                Some('\\') => match chars.next() {
+                    Some(character) => value.push(character),
+                    None => return Err(dsn_error("unfinished escape sequence")),
+                },
+                Some('\'') if quoted => break,
+                Some(character) if !quoted && character.is_whitespace() => break,
+                Some(character) => value.push(character),
+                None if quoted => {
+                    return Err(dsn_error(&format!("unclosed quote in parameter {key:?}")))
+                }
+                None => break,
+            }
+        }
+        parameters.push((key, value));
+    }
+}
@@ -1,32 +1,202 @@
 use bytes::Buf;
 use deadpool_postgres::Object;
+use futures_util::{pin_mut, TryStreamExt};
 use postgres_types::ToSql;
-use pyo3::{Py, PyAny, Python};
-use std::vec;
-use tokio_postgres::{Client, CopyInSink, Row, Statement, ToStatement};
+use pyo3::{
+    types::{PyAnyMethods, PyDict},
+    Py, PyAny, Python,
+};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+    vec,
+};
+use tokio_postgres::{
+    binary_copy::BinaryCopyInWriter, error::SqlState, types::Type, CancelToken, Client, Column,
+    CopyInSink, CopyOutStream, Row, RowStream, Statement, ToStatement,
+};
 
 use crate::{
+    driver::{
+        column_codecs::ColumnCodecs,
+        common_options::{MergeMatchedAction, MergeNotMatchedAction},
+        notices::{client_notice_counter, NoticeCounter},
+        pool_stats::CheckoutGuard,
+        spilled_result::{RowSpiller, SpilledQueryResult},
+        statement_cache::{StatementCache, StatementCacheInfo},
+        statement_stats::StatementStats,
+    },
     exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    format_helpers::{
+        cast_parameters, expand_values_marker, is_valid_type_name, query_fingerprint, quote_ident,
+    },
     query_result::{PSQLDriverPyQueryResult, PSQLDriverSinglePyQueryResult},
-    value_converter::{convert_parameters, postgres_to_py, PythonDTO, QueryParameter},
+    value_converter::{
+        convert_parameters, convert_parameters_with_casts, postgres_to_py, py_to_rust_for_type,
+        rows_parameter_casts, PythonDTO, QueryParameter,
+    },
 };
 
+/// Maximum number of parameters in one statement of `PostgreSQL` protocol.
+const MAX_STATEMENT_PARAMETERS: usize = 65535;
+
+/// Delay before the first retry of DDL statement, it's doubled on every retry.
+const DDL_RETRY_BACKOFF: Duration = Duration::from_millis(100);
+const DDL_MAX_RETRY_BACKOFF: Duration = Duration::from_secs(5);
+
 #[allow(clippy::module_name_repetitions)]
 pub enum PsqlpyConnection {
-    PoolConn(Object),
-    SingleConn(Client),
+    /// Connection from the pool, guard keeps it in the checked out
+    /// connections of the pool statistics until it's dropped.
+    /// Parameters of the columns with codecs are encrypted.
+    /// Statement cache belongs to the pooled connection and outlives the checkout.
+    PoolConn(
+        Object,
+        Option<StatementStats>,
+        Option<CheckoutGuard>,
+        ColumnCodecs,
+        StatementCache,
+    ),
+    SingleConn(Client, StatementCache),
 }
 
 impl PsqlpyConnection {
+    /// Record statement execution in the statistics of the pool.
+    fn record_statement(&self, querystring: &str, started_at: Instant, rows: usize) {
+        let duration = started_at.elapsed();
+        // Fingerprint is computed only if the event is enabled.
+        tracing::debug!(
+            fingerprint = %query_fingerprint(querystring),
+            duration_ms = duration.as_secs_f64() * 1000.0,
+            rows,
+            "Query is executed"
+        );
+        if let PsqlpyConnection::PoolConn(_, Some(statement_stats), ..) = self {
+            statement_stats.record(querystring, duration, rows);
+        }
+    }
+
+    /// Return counter of the notices received by the connection.
+    async fn notice_counter(&self) -> Option<NoticeCounter> {
+        match self {
+            PsqlpyConnection::PoolConn(pconn, ..) => client_notice_counter(pconn).await,
+            PsqlpyConnection::SingleConn(..) => None,
+        }
+    }
+
+    /// Return token to cancel the query running on the connection.
+    #[must_use]
+    pub fn cancel_token(&self) -> CancelToken {
+        match self {
+            PsqlpyConnection::PoolConn(pconn, ..) => pconn.cancel_token(),
+            PsqlpyConnection::SingleConn(sconn, _) => sconn.cancel_token(),
+        }
+    }
+
+    /// Create single connection with the statement cache of the default size.
+    #[must_use]
+    pub fn single(client: Client) -> Self {
+        PsqlpyConnection::SingleConn(client, StatementCache::default())
+    }
+
+    /// Return codecs of the columns, single connection doesn't have them.
+    fn column_codecs(&self) -> Option<&ColumnCodecs> {
+        match self {
+            PsqlpyConnection::PoolConn(_, _, _, column_codecs, _) => Some(column_codecs),
+            PsqlpyConnection::SingleConn(..) => None,
+        }
+    }
+
+    /// Encrypt parameters which are used with the columns with codecs.
+    ///
+    /// # Errors
+    /// May return Err Result if parameters cannot be matched with columns
+    /// or cannot be encrypted.
+    fn encrypt_parameters(
+        &self,
+        querystring: &str,
+        parameters: Py<PyAny>,
+    ) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        match self.column_codecs() {
+            Some(column_codecs) => column_codecs.encrypt_parameters(querystring, parameters),
+            None => Ok(parameters),
+        }
+    }
+
+    /// Return statement cache of the connection.
+    fn statement_cache(&self) -> StatementCache {
+        match self {
+            PsqlpyConnection::PoolConn(.., statement_cache)
+            | PsqlpyConnection::SingleConn(_, statement_cache) => statement_cache.clone(),
+        }
+    }
+
     /// Prepare cached statement.
     ///
+    /// The least recently used statement is evicted
+    /// when the cache of the connection is full.
+    ///
     /// # Errors
     /// May return Err if cannot prepare statement.
     pub async fn prepare_cached(&self, query: &str) -> RustPSQLDriverPyResult<Statement> {
+        let statement_cache = self.statement_cache();
         match self {
-            PsqlpyConnection::PoolConn(pconn) => return Ok(pconn.prepare_cached(query).await?),
-            PsqlpyConnection::SingleConn(sconn) => return Ok(sconn.prepare(query).await?),
+            PsqlpyConnection::PoolConn(pconn, ..) => {
+                let statement = pconn.prepare_cached(query).await?;
+                for evicted_query in statement_cache.insert(query, statement.clone()) {
+                    pconn.statement_cache.remove(&evicted_query, &[]);
+                }
+                Ok(statement)
+            }
+            PsqlpyConnection::SingleConn(sconn, _) => {
+                if let Some(statement) = statement_cache.get(query) {
+                    return Ok(statement);
+                }
+                let statement = sconn.prepare(query).await?;
+                statement_cache.insert(query, statement.clone());
+                Ok(statement)
+            }
+        }
+    }
+
+    /// Forget prepared statement which is no longer valid on the server.
+    ///
+    /// Server rejects the statement if the result type of the cached plan
+    /// changed or if the statement was deallocated behind the cache.
+    fn forget_invalidated_statement(&self, query: &str, error: &RustPSQLDriverError) {
+        let RustPSQLDriverError::RustDriverError(err) = error else {
+            return;
+        };
+        let invalidated = match err.code() {
+            Some(code) if *code == SqlState::INVALID_SQL_STATEMENT_NAME => true,
+            Some(code) if *code == SqlState::FEATURE_NOT_SUPPORTED => err
+                .as_db_error()
+                .is_some_and(|db_error| db_error.message().contains("cached plan")),
+            _ => false,
+        };
+        if !invalidated {
+            return;
+        }
+
+        if let PsqlpyConnection::PoolConn(pconn, ..) = self {
+            pconn.statement_cache.remove(query, &[]);
         }
+        self.statement_cache().invalidate(query);
+    }
+
+    /// Return state of the statement cache.
+    #[must_use]
+    pub fn statement_cache_info(&self) -> StatementCacheInfo {
+        self.statement_cache().info()
+    }
+
+    /// Remove all prepared statements from the cache.
+    pub fn clear_statement_cache(&self) {
+        if let PsqlpyConnection::PoolConn(pconn, ..) = self {
+            pconn.statement_cache.clear();
+        }
+        self.statement_cache().clear();
     }
 
     /// Prepare cached statement.
@@ -42,21 +212,68 @@ impl PsqlpyConnection {
         T: ?Sized + ToStatement,
     {
         match self {
-            PsqlpyConnection::PoolConn(pconn) => return Ok(pconn.query(statement, params).await?),
-            PsqlpyConnection::SingleConn(sconn) => {
+            PsqlpyConnection::PoolConn(pconn, ..) => {
+                return Ok(pconn.query(statement, params).await?)
+            }
+            PsqlpyConnection::SingleConn(sconn, _) => {
                 return Ok(sconn.query(statement, params).await?)
             }
         }
     }
 
+    /// Execute statement and return stream of the rows.
+    ///
+    /// # Errors
+    /// May return Err if cannot execute statement.
+    pub async fn query_raw<T>(
+        &self,
+        statement: &T,
+        params: &[&QueryParameter],
+    ) -> RustPSQLDriverPyResult<RowStream>
+    where
+        T: ?Sized + ToStatement,
+    {
+        match self {
+            PsqlpyConnection::PoolConn(pconn, ..) => {
+                return Ok(pconn.query_raw(statement, params.iter().copied()).await?)
+            }
+            PsqlpyConnection::SingleConn(sconn, _) => {
+                return Ok(sconn.query_raw(statement, params.iter().copied()).await?)
+            }
+        }
+    }
+
+    /// Execute statement and return its rows with number of affected rows.
+    ///
+    /// # Errors
+    /// May return Err if cannot execute statement.
+    pub async fn query_counted<T>(
+        &self,
+        statement: &T,
+        params: &[&QueryParameter],
+    ) -> RustPSQLDriverPyResult<(Vec<Row>, u64)>
+    where
+        T: ?Sized + ToStatement,
+    {
+        let row_stream = self.query_raw(statement, params).await?;
+        pin_mut!(row_stream);
+        let mut rows = vec![];
+        while let Some(row) = row_stream.try_next().await? {
+            rows.push(row);
+        }
+        Ok((rows, row_stream.rows_affected().unwrap_or_default()))
+    }
+
     /// Prepare cached statement.
     ///
     /// # Errors
     /// May return Err if cannot execute statement.
     pub async fn batch_execute(&self, query: &str) -> RustPSQLDriverPyResult<()> {
         match self {
-            PsqlpyConnection::PoolConn(pconn) => return Ok(pconn.batch_execute(query).await?),
-            PsqlpyConnection::SingleConn(sconn) => return Ok(sconn.batch_execute(query).await?),
+            PsqlpyConnection::PoolConn(pconn, ..) => return Ok(pconn.batch_execute(query).await?),
+            PsqlpyConnection::SingleConn(sconn, _) => {
+                return Ok(sconn.batch_execute(query).await?)
+            }
         }
     }
 
@@ -73,10 +290,10 @@ impl PsqlpyConnection {
         T: ?Sized + ToStatement,
     {
         match self {
-            PsqlpyConnection::PoolConn(pconn) => {
+            PsqlpyConnection::PoolConn(pconn, ..) => {
                 return Ok(pconn.query_one(statement, params).await?)
             }
-            PsqlpyConnection::SingleConn(sconn) => {
+            PsqlpyConnection::SingleConn(sconn, _) => {
                 return Ok(sconn.query_one(statement, params).await?)
             }
         }
@@ -88,12 +305,36 @@ impl PsqlpyConnection {
         parameters: Option<pyo3::Py<PyAny>>,
         prepared: Option<bool>,
     ) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
-        let prepared = prepared.unwrap_or(true);
+        let parameters = parameters
+            .map(|parameters| self.encrypt_parameters(&querystring, parameters))
+            .transpose()?;
+        self.execute_without_codecs(querystring, parameters, prepared)
+            .await
+    }
 
+    /// Execute querystring, parameters are sent without column codecs.
+    ///
+    /// It's used when the parameters are already encrypted by their names.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Cannot convert python parameters
+    /// 2) Cannot prepare statement
+    /// 3) Cannot execute statement
+    pub async fn execute_without_codecs(
+        &self,
+        querystring: String,
+        parameters: Option<pyo3::Py<PyAny>>,
+        prepared: Option<bool>,
+    ) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
+        let mut querystring = querystring;
         let mut params: Vec<PythonDTO> = vec![];
         if let Some(parameters) = parameters {
-            params = convert_parameters(parameters)?;
+            (querystring, params) = convert_parameters_with_casts(querystring, parameters)?;
         }
+        let prepared = self
+            .statement_cache()
+            .should_prepare(&querystring, prepared);
 
         let boxed_params = &params
             .iter()
@@ -101,8 +342,15 @@ impl PsqlpyConnection {
             .collect::<Vec<&QueryParameter>>()
             .into_boxed_slice();
 
-        let result = if prepared {
-            self.query(
+        // Notices left from the previous queries don't belong to this one.
+        let notice_counter = self.notice_counter().await;
+        if let Some(notice_counter) = &notice_counter {
+            let _ = notice_counter.take();
+        }
+
+        let started_at = Instant::now();
+        let (result, rows_affected) = if prepared {
+            self.query_counted(
                 &self.prepare_cached(&querystring).await.map_err(|err| {
                     RustPSQLDriverError::ConnectionExecuteError(format!(
                         "Cannot prepare statement, error - {err}"
@@ -112,21 +360,114 @@ impl PsqlpyConnection {
             )
             .await
             .map_err(|err| {
-                RustPSQLDriverError::ConnectionExecuteError(format!(
-                    "Cannot execute statement, error - {err}"
-                ))
+                self.forget_invalidated_statement(&querystring, &err);
+                RustPSQLDriverError::execute_error("Cannot execute statement", err)
             })?
         } else {
-            self.query(&querystring, boxed_params)
+            self.query_counted(querystring.as_str(), boxed_params)
                 .await
                 .map_err(|err| {
-                    RustPSQLDriverError::ConnectionExecuteError(format!(
-                        "Cannot execute statement, error - {err}"
-                    ))
+                    RustPSQLDriverError::execute_error("Cannot execute statement", err)
                 })?
         };
 
-        Ok(PSQLDriverPyQueryResult::new(result))
+        self.record_statement(&querystring, started_at, result.len());
+        let notice_summary = notice_counter
+            .map(|notice_counter| notice_counter.take())
+            .unwrap_or_default();
+
+        Ok(PSQLDriverPyQueryResult::new(result)
+            .with_querystring(querystring)
+            .with_notice_summary(notice_summary)
+            .with_rows_affected(rows_affected))
+    }
+
+    /// Execute querystring and return stream of the rows.
+    ///
+    /// Querystring with the casts of the parameters is returned too.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Cannot convert python parameters
+    /// 2) Cannot prepare statement
+    /// 3) Cannot execute statement
+    pub async fn execute_stream(
+        &self,
+        querystring: String,
+        parameters: Option<pyo3::Py<PyAny>>,
+        prepared: Option<bool>,
+    ) -> RustPSQLDriverPyResult<(String, RowStream)> {
+        let mut querystring = querystring;
+        let mut params: Vec<PythonDTO> = vec![];
+        if let Some(parameters) = parameters {
+            let parameters = self.encrypt_parameters(&querystring, parameters)?;
+            (querystring, params) = convert_parameters_with_casts(querystring, parameters)?;
+        }
+        let prepared = self
+            .statement_cache()
+            .should_prepare(&querystring, prepared);
+
+        let boxed_params = &params
+            .iter()
+            .map(|param| param as &QueryParameter)
+            .collect::<Vec<&QueryParameter>>()
+            .into_boxed_slice();
+
+        let row_stream = if prepared {
+            self.query_raw(
+                &self.prepare_cached(&querystring).await.map_err(|err| {
+                    RustPSQLDriverError::ConnectionExecuteError(format!(
+                        "Cannot prepare statement, error - {err}"
+                    ))
+                })?,
+                boxed_params,
+            )
+            .await
+        } else {
+            self.query_raw(&querystring, boxed_params).await
+        }
+        .map_err(|err| {
+            self.forget_invalidated_statement(&querystring, &err);
+            RustPSQLDriverError::execute_error("Cannot execute statement", err)
+        })?;
+
+        Ok((querystring, row_stream))
+    }
+
+    /// Execute querystring and keep raw row data above
+    /// the memory threshold in the temporary file.
+    ///
+    /// Rows are read from the stream one by one,
+    /// so the whole result is never held in memory.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Cannot convert python parameters
+    /// 2) Cannot execute statement
+    /// 3) Cannot write to the temporary file
+    pub async fn execute_spilled(
+        &self,
+        querystring: String,
+        parameters: Option<pyo3::Py<PyAny>>,
+        prepared: Option<bool>,
+        memory_threshold: usize,
+        custom_decoders: Option<Py<PyDict>>,
+    ) -> RustPSQLDriverPyResult<SpilledQueryResult> {
+        let started_at = Instant::now();
+        let (querystring, row_stream) = self
+            .execute_stream(querystring, parameters, prepared)
+            .await?;
+        pin_mut!(row_stream);
+
+        let mut row_spiller = RowSpiller::new(memory_threshold);
+        while let Some(row) = row_stream.try_next().await.map_err(|err| {
+            RustPSQLDriverError::ConnectionExecuteError(format!("Cannot fetch row, error - {err}"))
+        })? {
+            row_spiller.push(row).await?;
+        }
+
+        self.record_statement(&querystring, started_at, row_spiller.rows_count());
+        row_spiller.finish(custom_decoders).await
     }
 
     pub async fn execute_many(
@@ -135,14 +476,25 @@ impl PsqlpyConnection {
         parameters: Option<Vec<Py<PyAny>>>,
         prepared: Option<bool>,
     ) -> RustPSQLDriverPyResult<()> {
-        let prepared = prepared.unwrap_or(true);
-
         let mut params: Vec<Vec<PythonDTO>> = vec![];
+        let mut casts = HashMap::new();
         if let Some(parameters) = parameters {
+            casts = rows_parameter_casts(&parameters)?;
             for vec_of_py_any in parameters {
+                let vec_of_py_any = self.encrypt_parameters(&querystring, vec_of_py_any)?;
                 params.push(convert_parameters(vec_of_py_any)?);
             }
         }
+        // Querystring is cast once, all rows have the same casts.
+        let querystring = if casts.is_empty() {
+            querystring
+        } else {
+            cast_parameters(&querystring, &casts)
+        };
+
+        let prepared = self
+            .statement_cache()
+            .should_prepare(&querystring, prepared);
 
         for param in params {
             let boxed_params = &param
@@ -151,6 +503,7 @@ impl PsqlpyConnection {
                 .collect::<Vec<&QueryParameter>>()
                 .into_boxed_slice();
 
+            let started_at = Instant::now();
             let querystring_result = if prepared {
                 let prepared_stmt = &self.prepare_cached(&querystring).await;
                 if let Err(error) = prepared_stmt {
@@ -164,28 +517,401 @@ impl PsqlpyConnection {
                 self.query(&querystring, boxed_params).await
             };
 
-            if let Err(error) = querystring_result {
-                return Err(RustPSQLDriverError::ConnectionExecuteError(format!(
-                    "Error occured in `execute_many` statement: {error}"
-                )));
+            match querystring_result {
+                Ok(rows) => self.record_statement(&querystring, started_at, rows.len()),
+                Err(error) => {
+                    return Err(RustPSQLDriverError::execute_error(
+                        "Error occured in `execute_many` statement",
+                        error,
+                    ));
+                }
             }
         }
 
         return Ok(());
     }
 
+    /// Encrypt values of one `execute_values` row by the columns of the `VALUES` list.
+    ///
+    /// # Errors
+    /// May return Err Result if values cannot be matched with columns
+    /// or cannot be encrypted.
+    fn encrypt_values_row(
+        &self,
+        querystring: &str,
+        row: Py<PyAny>,
+    ) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        if self.column_codecs().is_none_or(ColumnCodecs::is_empty) {
+            return Ok(row);
+        }
+        let row_width = Python::with_gil(|gil| row.bind(gil).len()).unwrap_or_default();
+        if row_width == 0 {
+            return Ok(row);
+        }
+        let row_querystring = expand_values_marker(querystring, row_width, 1)?;
+        self.encrypt_parameters(&row_querystring, row)
+    }
+
+    /// Insert rows with statements which have `VALUES` list
+    /// of up to `page_size` rows.
+    ///
+    /// `%s` marker in the querystring is replaced with the list
+    /// of numbered parameters, values of all rows on the page
+    /// are passed as parameters of one statement.
+    /// Page is shrunk if it exceeds the limit of the parameters number.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Rows have different number of values or no values
+    /// 2) Querystring doesn't contain exactly one `%s` marker
+    /// 3) Cannot convert python parameters
+    /// 4) Cannot execute statement
+    pub async fn execute_values(
+        &self,
+        querystring: String,
+        values: Vec<Py<PyAny>>,
+        page_size: usize,
+        prepared: Option<bool>,
+    ) -> RustPSQLDriverPyResult<()> {
+        let prepared = self
+            .statement_cache()
+            .should_prepare(&querystring, prepared);
+
+        let casts = rows_parameter_casts(&values)?;
+        let mut rows: Vec<Vec<PythonDTO>> = Vec::with_capacity(values.len());
+        for row in values {
+            let row = self.encrypt_values_row(&querystring, row)?;
+            rows.push(convert_parameters(row)?);
+        }
+        let Some(row_width) = rows.first().map(Vec::len) else {
+            return Ok(());
+        };
+        if row_width == 0 || rows.iter().any(|row| row.len() != row_width) {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(
+                "All rows must have the same non-zero number of values".into(),
+            ));
+        }
+        let page_size = page_size.clamp(1, MAX_STATEMENT_PARAMETERS / row_width);
+
+        for page in rows.chunks(page_size) {
+            let mut page_querystring = expand_values_marker(&querystring, row_width, page.len())?;
+            if !casts.is_empty() {
+                let page_casts: HashMap<usize, String> = (0..page.len())
+                    .flat_map(|row_idx| {
+                        casts.iter().map(move |(param_num, type_name)| {
+                            (row_idx * row_width + param_num, type_name.clone())
+                        })
+                    })
+                    .collect();
+                page_querystring = cast_parameters(&page_querystring, &page_casts);
+            }
+            let boxed_params = &page
+                .iter()
+                .flatten()
+                .map(|param| param as &QueryParameter)
+                .collect::<Vec<&QueryParameter>>()
+                .into_boxed_slice();
+
+            let started_at = Instant::now();
+            let querystring_result = if prepared {
+                self.query(
+                    &self
+                        .prepare_cached(&page_querystring)
+                        .await
+                        .map_err(|err| {
+                            RustPSQLDriverError::ConnectionExecuteError(format!(
+                                "Cannot prepare statement in execute_values, error - {err}"
+                            ))
+                        })?,
+                    boxed_params,
+                )
+                .await
+            } else {
+                self.query(&page_querystring, boxed_params).await
+            };
+
+            match querystring_result {
+                Ok(rows) => self.record_statement(&page_querystring, started_at, rows.len()),
+                Err(error) => {
+                    return Err(RustPSQLDriverError::execute_error(
+                        "Error occured in `execute_values` statement",
+                        error,
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Return types of the table columns to cast `VALUES` parameters,
+    /// types are taken from the description of the columns.
+    async fn column_types(
+        &self,
+        table: &str,
+        quoted_columns: &[String],
+    ) -> RustPSQLDriverPyResult<Vec<String>> {
+        let columns_statement = self
+            .prepare_cached(&format!(
+                "SELECT {} FROM {table}",
+                quoted_columns.join(", ")
+            ))
+            .await
+            .map_err(|err| {
+                RustPSQLDriverError::ConnectionExecuteError(format!(
+                    "Cannot describe columns of {table}, error - {err}"
+                ))
+            })?;
+
+        Ok(columns_statement
+            .columns()
+            .iter()
+            .map(|column| {
+                format!(
+                    "{}.{}",
+                    quote_ident(column.type_().schema()),
+                    quote_ident(column.type_().name())
+                )
+            })
+            .collect())
+    }
+
+    /// Execute statement of one page of the rows.
+    ///
+    /// Returns number of affected rows.
+    async fn execute_rows_page(
+        &self,
+        querystring: &str,
+        page: &[Vec<PythonDTO>],
+        method_name: &str,
+    ) -> RustPSQLDriverPyResult<usize> {
+        let boxed_params = page
+            .iter()
+            .flatten()
+            .map(|param| param as &QueryParameter)
+            .collect::<Vec<&QueryParameter>>();
+
+        let started_at = Instant::now();
+        let statement = self.prepare_cached(querystring).await.map_err(|err| {
+            RustPSQLDriverError::ConnectionExecuteError(format!(
+                "Cannot prepare statement in {method_name}, error - {err}"
+            ))
+        })?;
+        let execute_error = |err| {
+            RustPSQLDriverError::execute_error(
+                &format!("Error occured in `{method_name}` statement"),
+                err,
+            )
+        };
+        let row_stream = self
+            .query_raw(&statement, &boxed_params)
+            .await
+            .map_err(execute_error)?;
+        pin_mut!(row_stream);
+        while row_stream
+            .try_next()
+            .await
+            .map_err(|err| execute_error(err.into()))?
+            .is_some()
+        {}
+
+        let affected_rows =
+            usize::try_from(row_stream.rows_affected().unwrap_or_default()).unwrap_or(usize::MAX);
+        self.record_statement(querystring, started_at, affected_rows);
+        Ok(affected_rows)
+    }
+
+    /// Update rows of the table matched by the key columns.
+    ///
+    /// Rows are passed in `UPDATE ... FROM (VALUES ...)` statement,
+    /// up to `page_size` rows in one statement.
+    /// Every value is cast to the type of its column.
+    /// `table` must be already quoted, `columns` are in order of row values.
+    ///
+    /// Returns number of updated rows.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Table or columns don't exist
+    /// 2) Rows have different number of values than columns
+    /// 3) Cannot convert python parameters
+    /// 4) Cannot execute statement
+    pub async fn update_many(
+        &self,
+        table: &str,
+        key_columns: &[String],
+        columns: &[String],
+        rows: Vec<Py<PyAny>>,
+        page_size: usize,
+    ) -> RustPSQLDriverPyResult<usize> {
+        let quoted_columns: Vec<String> =
+            columns.iter().map(|column| quote_ident(column)).collect();
+        let column_types = self.column_types(table, &quoted_columns).await?;
+        if let Some(column_codecs) = self.column_codecs() {
+            column_codecs.check_key_columns(key_columns)?;
+        }
+        let (converted_rows, casts) = convert_rows(rows, columns, self.column_codecs())?;
+
+        let set_list = columns
+            .iter()
+            .zip(&quoted_columns)
+            .filter(|(column, _)| !key_columns.contains(column))
+            .map(|(_, quoted)| format!("{quoted} = {VALUES_ALIAS}.{quoted}"))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let key_condition = key_condition(key_columns);
+
+        let mut updated_rows = 0;
+        for page in converted_rows.chunks(rows_page_size(page_size, columns.len())) {
+            let page_querystring = format!(
+                "UPDATE {table} AS {TARGET_ALIAS} SET {set_list} \
+                FROM ({}) AS {VALUES_ALIAS}({}) \
+                WHERE {key_condition}",
+                typed_values_list(&column_types, &casts, page.len()),
+                quoted_columns.join(", ")
+            );
+            updated_rows += self
+                .execute_rows_page(&page_querystring, page, "update_many")
+                .await?;
+        }
+
+        Ok(updated_rows)
+    }
+
+    /// Merge rows into the table by the key columns.
+    ///
+    /// Rows are the source of `MERGE ... USING (VALUES ...)` statement,
+    /// up to `page_size` rows in one statement.
+    /// Every value is cast to the type of its column.
+    /// `table` must be already quoted, `columns` are in order of row values.
+    ///
+    /// Returns number of inserted, updated and deleted rows.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Table or columns don't exist
+    /// 2) Rows have different number of values than columns
+    /// 3) Cannot convert python parameters
+    /// 4) Cannot execute statement, `MERGE` requires PostgreSQL 15+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn merge_many(
+        &self,
+        table: &str,
+        on_columns: &[String],
+        columns: &[String],
+        rows: Vec<Py<PyAny>>,
+        when_matched: MergeMatchedAction,
+        when_not_matched: MergeNotMatchedAction,
+        page_size: usize,
+    ) -> RustPSQLDriverPyResult<usize> {
+        let quoted_columns: Vec<String> =
+            columns.iter().map(|column| quote_ident(column)).collect();
+        let column_types = self.column_types(table, &quoted_columns).await?;
+        if let Some(column_codecs) = self.column_codecs() {
+            column_codecs.check_key_columns(on_columns)?;
+        }
+        let (converted_rows, casts) = convert_rows(rows, columns, self.column_codecs())?;
+
+        let matched_clause = match when_matched {
+            MergeMatchedAction::Update => {
+                let set_list = columns
+                    .iter()
+                    .zip(&quoted_columns)
+                    .filter(|(column, _)| !on_columns.contains(column))
+                    .map(|(_, quoted)| format!("{quoted} = {VALUES_ALIAS}.{quoted}"))
+                    .collect::<Vec<String>>();
+                if set_list.is_empty() {
+                    "DO NOTHING".to_string()
+                } else {
+                    format!("UPDATE SET {}", set_list.join(", "))
+                }
+            }
+            MergeMatchedAction::Delete => "DELETE".to_string(),
+            MergeMatchedAction::DoNothing => "DO NOTHING".to_string(),
+        };
+        let not_matched_clause = match when_not_matched {
+            MergeNotMatchedAction::Insert => format!(
+                "INSERT ({}) VALUES ({})",
+                quoted_columns.join(", "),
+                quoted_columns
+                    .iter()
+                    .map(|quoted| format!("{VALUES_ALIAS}.{quoted}"))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            MergeNotMatchedAction::DoNothing => "DO NOTHING".to_string(),
+        };
+        let key_condition = key_condition(on_columns);
+
+        let mut merged_rows = 0;
+        for page in converted_rows.chunks(rows_page_size(page_size, columns.len())) {
+            let page_querystring = format!(
+                "MERGE INTO {table} AS {TARGET_ALIAS} \
+                USING ({}) AS {VALUES_ALIAS}({}) \
+                ON {key_condition} \
+                WHEN MATCHED THEN {matched_clause} \
+                WHEN NOT MATCHED THEN {not_matched_clause}",
+                typed_values_list(&column_types, &casts, page.len()),
+                quoted_columns.join(", ")
+            );
+            merged_rows += self
+                .execute_rows_page(&page_querystring, page, "merge")
+                .await?;
+        }
+
+        Ok(merged_rows)
+    }
+
+    /// Execute row locking query and return the locked rows.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Cannot convert python parameters
+    /// 2) Rows are locked by another transaction and `NOWAIT` is used
+    /// 3) Cannot execute querystring
+    pub async fn claim_rows(
+        &self,
+        querystring: String,
+        parameters: Option<pyo3::Py<PyAny>>,
+    ) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
+        let mut querystring = querystring;
+        let mut params: Vec<PythonDTO> = vec![];
+        if let Some(parameters) = parameters {
+            let parameters = self.encrypt_parameters(&querystring, parameters)?;
+            (querystring, params) = convert_parameters_with_casts(querystring, parameters)?;
+        }
+        let boxed_params = params
+            .iter()
+            .map(|param| param as &QueryParameter)
+            .collect::<Vec<&QueryParameter>>();
+
+        let statement = self
+            .prepare_cached(&querystring)
+            .await
+            .map_err(claim_error)?;
+        let rows = self
+            .query(&statement, &boxed_params)
+            .await
+            .map_err(claim_error)?;
+
+        Ok(PSQLDriverPyQueryResult::new(rows).with_querystring(querystring))
+    }
+
     pub async fn fetch_row_raw(
         &self,
         querystring: String,
         parameters: Option<pyo3::Py<PyAny>>,
         prepared: Option<bool>,
     ) -> RustPSQLDriverPyResult<Row> {
-        let prepared = prepared.unwrap_or(true);
-
+        let mut querystring = querystring;
         let mut params: Vec<PythonDTO> = vec![];
         if let Some(parameters) = parameters {
-            params = convert_parameters(parameters)?;
+            let parameters = self.encrypt_parameters(&querystring, parameters)?;
+            (querystring, params) = convert_parameters_with_casts(querystring, parameters)?;
         }
+        let prepared = self
+            .statement_cache()
+            .should_prepare(&querystring, prepared);
 
         let boxed_params = &params
             .iter()
@@ -193,6 +919,7 @@ impl PsqlpyConnection {
             .collect::<Vec<&QueryParameter>>()
             .into_boxed_slice();
 
+        let started_at = Instant::now();
         let result = if prepared {
             self.query_one(
                 &self.prepare_cached(&querystring).await.map_err(|err| {
@@ -204,20 +931,19 @@ impl PsqlpyConnection {
             )
             .await
             .map_err(|err| {
-                RustPSQLDriverError::ConnectionExecuteError(format!(
-                    "Cannot execute statement, error - {err}"
-                ))
+                self.forget_invalidated_statement(&querystring, &err);
+                RustPSQLDriverError::execute_error("Cannot execute statement", err)
             })?
         } else {
             self.query_one(&querystring, boxed_params)
                 .await
                 .map_err(|err| {
-                    RustPSQLDriverError::ConnectionExecuteError(format!(
-                        "Cannot execute statement, error - {err}"
-                    ))
+                    RustPSQLDriverError::execute_error("Cannot execute statement", err)
                 })?
         };
 
+        self.record_statement(&querystring, started_at, 1);
+
         return Ok(result);
     }
 
@@ -228,10 +954,10 @@ impl PsqlpyConnection {
         prepared: Option<bool>,
     ) -> RustPSQLDriverPyResult<PSQLDriverSinglePyQueryResult> {
         let result = self
-            .fetch_row_raw(querystring, parameters, prepared)
+            .fetch_row_raw(querystring.clone(), parameters, prepared)
             .await?;
 
-        return Ok(PSQLDriverSinglePyQueryResult::new(result));
+        return Ok(PSQLDriverSinglePyQueryResult::new(result).with_querystring(querystring));
     }
 
     pub async fn fetch_val(
@@ -250,6 +976,317 @@ impl PsqlpyConnection {
         });
     }
 
+    /// Execute `EXPLAIN` with options for the querystring and return JSON plan.
+    ///
+    /// # Errors
+    /// May return Err if:
+    /// 1) Cannot convert python parameters
+    /// 2) Cannot execute `EXPLAIN`
+    async fn explain_json(
+        &self,
+        explain_options: &str,
+        querystring: String,
+        parameters: Option<pyo3::Py<PyAny>>,
+    ) -> RustPSQLDriverPyResult<Value> {
+        let result = self
+            .fetch_row_raw(
+                format!("EXPLAIN ({explain_options}, FORMAT JSON) {querystring}"),
+                parameters,
+                Some(false),
+            )
+            .await?;
+        let mut query_plan: Value = result.try_get(0)?;
+
+        Ok(query_plan.get_mut(0).map(Value::take).unwrap_or_default())
+    }
+
+    /// Return planner's estimate of the number of rows returned by the querystring.
+    ///
+    /// # Errors
+    /// May return Err if:
+    /// 1) Cannot convert python parameters
+    /// 2) Cannot execute `EXPLAIN`
+    /// 3) Plan has no row estimate
+    pub async fn estimate_count(
+        &self,
+        querystring: String,
+        parameters: Option<pyo3::Py<PyAny>>,
+    ) -> RustPSQLDriverPyResult<i64> {
+        let query_plan = self
+            .explain_json("COSTS TRUE", querystring, parameters)
+            .await?;
+
+        query_plan
+            .get("Plan")
+            .and_then(|plan| plan.get("Plan Rows"))
+            .and_then(Value::as_i64)
+            .ok_or_else(|| {
+                RustPSQLDriverError::ConnectionExecuteError(
+                    "Cannot find row estimate in the query plan".into(),
+                )
+            })
+    }
+
+    /// Return `PostgreSQL` query identifier of the querystring.
+    ///
+    /// Query identifier is the same as `queryid` in `pg_stat_statements`,
+    /// it's available since `PostgreSQL` 14 if `compute_query_id` is enabled.
+    ///
+    /// # Errors
+    /// May return Err if:
+    /// 1) Cannot convert python parameters
+    /// 2) Cannot execute `EXPLAIN`
+    pub async fn query_id(
+        &self,
+        querystring: String,
+        parameters: Option<pyo3::Py<PyAny>>,
+    ) -> RustPSQLDriverPyResult<Option<i64>> {
+        let query_plan = self
+            .explain_json("VERBOSE TRUE", querystring, parameters)
+            .await?;
+
+        Ok(query_plan.get("Query Identifier").and_then(Value::as_i64))
+    }
+
+    /// Read current values of the settings in one round trip.
+    ///
+    /// Value is None if setting doesn't exist.
+    ///
+    /// # Errors
+    /// May return Err if cannot execute query.
+    pub async fn capture_settings(
+        &self,
+        names: Vec<String>,
+    ) -> RustPSQLDriverPyResult<HashMap<String, Option<String>>> {
+        let rows = self
+            .query(
+                "SELECT name, current_setting(name, true) FROM unnest($1::text[]) AS settings(name)",
+                &[&names],
+            )
+            .await
+            .map_err(|err| {
+                RustPSQLDriverError::ConnectionExecuteError(format!(
+                    "Cannot capture settings, error - {err}"
+                ))
+            })?;
+
+        let mut settings = HashMap::with_capacity(rows.len());
+        for row in rows {
+            settings.insert(
+                row.try_get::<_, String>(0)?,
+                row.try_get::<_, Option<String>>(1)?,
+            );
+        }
+        Ok(settings)
+    }
+
+    /// Apply values of the settings in one round trip.
+    ///
+    /// Settings without value are skipped.
+    ///
+    /// # Errors
+    /// May return Err if cannot execute query.
+    pub async fn restore_settings(
+        &self,
+        settings: HashMap<String, Option<String>>,
+    ) -> RustPSQLDriverPyResult<()> {
+        let (names, values): (Vec<String>, Vec<String>) = settings
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|value| (name, value)))
+            .unzip();
+        if names.is_empty() {
+            return Ok(());
+        }
+
+        self.query(
+            "SELECT set_config(name, value, false) FROM unnest($1::text[], $2::text[]) AS settings(name, value)",
+            &[&names, &values],
+        )
+        .await
+        .map_err(|err| {
+            RustPSQLDriverError::ConnectionExecuteError(format!(
+                "Cannot restore settings, error - {err}"
+            ))
+        })?;
+        Ok(())
+    }
+
+    /// Execute DDL statements one by one with short `lock_timeout`.
+    ///
+    /// Statement which cannot acquire lock in time is retried
+    /// with exponential backoff, so it doesn't block other queries
+    /// while it waits in the lock queue.
+    /// `lock_timeout` of the connection is restored afterwards.
+    ///
+    /// # Errors
+    /// May return Err if:
+    /// 1) Cannot set `lock_timeout`
+    /// 2) Statement failed with error other than lock timeout
+    /// 3) Statement couldn't acquire lock after all retries
+    pub async fn run_ddl(
+        &self,
+        statements: Vec<String>,
+        lock_timeout: Duration,
+        retries: u32,
+    ) -> RustPSQLDriverPyResult<()> {
+        let previous_settings = self.capture_settings(vec!["lock_timeout".into()]).await?;
+        self.restore_settings(HashMap::from([(
+            "lock_timeout".into(),
+            Some(format!("{}ms", lock_timeout.as_millis())),
+        )]))
+        .await?;
+
+        let ddl_result = self.run_ddl_statements(statements, retries).await;
+        let restore_result = self.restore_settings(previous_settings).await;
+        ddl_result?;
+        restore_result
+    }
+
+    async fn run_ddl_statements(
+        &self,
+        statements: Vec<String>,
+        retries: u32,
+    ) -> RustPSQLDriverPyResult<()> {
+        for statement in statements {
+            let mut backoff = DDL_RETRY_BACKOFF;
+            let mut attempt = 0;
+            loop {
+                match self.batch_execute(&statement).await {
+                    Ok(()) => break,
+                    Err(RustPSQLDriverError::RustDriverError(err))
+                        if err.code() == Some(&SqlState::LOCK_NOT_AVAILABLE)
+                            && attempt < retries =>
+                    {
+                        tracing::debug!(
+                            attempt = attempt + 1,
+                            backoff_ms = backoff.as_secs_f64() * 1000.0,
+                            "DDL statement couldn't acquire lock and is retried"
+                        );
+                        tokio::time::sleep(backoff).await;
+                        backoff = (backoff * 2).min(DDL_MAX_RETRY_BACKOFF);
+                        attempt += 1;
+                    }
+                    Err(err) => {
+                        let attempts = attempt + 1;
+                        return Err(RustPSQLDriverError::ConnectionExecuteError(format!(
+                            "Cannot execute DDL statement {statement}, attempts - {attempts}, error - {err}"
+                        )));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy records into the table with binary `COPY`.
+    ///
+    /// Every value is encoded for the type of its column,
+    /// types are taken from the table if `type_names` aren't passed.
+    /// `table` must be already quoted, all columns are used if `columns` are None.
+    /// Values of the columns with codecs are encrypted first.
+    ///
+    /// Returns number of copied rows.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Table, columns or types don't exist
+    /// 2) Records have different number of values than columns
+    /// 3) Cannot convert python values
+    /// 4) Cannot execute copy statement
+    pub async fn copy_records(
+        &self,
+        table: &str,
+        columns: Option<Vec<String>>,
+        type_names: Option<Vec<String>>,
+        records: Vec<Py<PyAny>>,
+        column_codecs: &ColumnCodecs,
+    ) -> RustPSQLDriverPyResult<u64> {
+        let select_list = match columns {
+            Some(columns) => columns
+                .iter()
+                .map(|column| quote_ident(column))
+                .collect::<Vec<String>>()
+                .join(", "),
+            None => "*".to_string(),
+        };
+        let table_statement = self
+            .prepare_cached(&format!("SELECT {select_list} FROM {table}"))
+            .await
+            .map_err(|err| {
+                RustPSQLDriverError::ConnectionExecuteError(format!(
+                    "Cannot describe columns of {table}, error - {err}"
+                ))
+            })?;
+        let column_names: Vec<&str> = table_statement.columns().iter().map(Column::name).collect();
+        let quoted_columns: Vec<String> = column_names
+            .iter()
+            .map(|column_name| quote_ident(column_name))
+            .collect();
+
+        let column_types = match type_names {
+            Some(type_names) => {
+                if type_names.len() != quoted_columns.len() {
+                    return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                        "Expected {} types, one for every column, got {}",
+                        quoted_columns.len(),
+                        type_names.len()
+                    )));
+                }
+                self.resolve_types(&type_names).await?
+            }
+            None => table_statement
+                .columns()
+                .iter()
+                .map(|column| column.type_().clone())
+                .collect(),
+        };
+        let converted_records =
+            convert_records(records, &column_names, &column_types, column_codecs)?;
+
+        let sink = self
+            .copy_in(&format!(
+                "COPY {table} ({}) FROM STDIN (FORMAT binary)",
+                quoted_columns.join(", ")
+            ))
+            .await?;
+        let writer = BinaryCopyInWriter::new(sink, &column_types);
+        pin_mut!(writer);
+        for record in &converted_records {
+            let values: Vec<&QueryParameter> = record
+                .iter()
+                .map(|value| value as &QueryParameter)
+                .collect();
+            writer.as_mut().write(&values).await?;
+        }
+        Ok(writer.as_mut().finish().await?)
+    }
+
+    /// Return types by their names.
+    ///
+    /// Names are checked to be valid type names before they are put into the query.
+    async fn resolve_types(&self, type_names: &[String]) -> RustPSQLDriverPyResult<Vec<Type>> {
+        if let Some(type_name) = type_names
+            .iter()
+            .find(|type_name| !is_valid_type_name(type_name))
+        {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "Invalid type name {type_name}"
+            )));
+        }
+        let null_list = type_names
+            .iter()
+            .map(|type_name| format!("NULL::{type_name}"))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let types_statement = self.prepare_cached(&format!("SELECT {null_list}")).await?;
+
+        Ok(types_statement
+            .columns()
+            .iter()
+            .map(|column| column.type_().clone())
+            .collect())
+    }
+
     /// Prepare cached statement.
     ///
     /// # Errors
@@ -260,8 +1297,152 @@ impl PsqlpyConnection {
         U: Buf + 'static + Send,
     {
         match self {
-            PsqlpyConnection::PoolConn(pconn) => return Ok(pconn.copy_in(statement).await?),
-            PsqlpyConnection::SingleConn(sconn) => return Ok(sconn.copy_in(statement).await?),
+            PsqlpyConnection::PoolConn(pconn, ..) => return Ok(pconn.copy_in(statement).await?),
+            PsqlpyConnection::SingleConn(sconn, _) => return Ok(sconn.copy_in(statement).await?),
+        }
+    }
+
+    /// Start `COPY ... TO STDOUT` and return stream of the data.
+    ///
+    /// # Errors
+    /// May return Err if cannot execute copy statement.
+    pub async fn copy_out<T>(&self, statement: &T) -> RustPSQLDriverPyResult<CopyOutStream>
+    where
+        T: ?Sized + ToStatement,
+    {
+        match self {
+            PsqlpyConnection::PoolConn(pconn, ..) => return Ok(pconn.copy_out(statement).await?),
+            PsqlpyConnection::SingleConn(sconn, _) => return Ok(sconn.copy_out(statement).await?),
+        }
+    }
+}
+
+/// Aliases of the target table and the rows in `UPDATE` and `MERGE` statements.
+const TARGET_ALIAS: &str = "psqlpy_target";
+const VALUES_ALIAS: &str = "psqlpy_values";
+
+/// Convert rows to parameters, every row must have value for every column.
+///
+/// Values of the columns with codecs are encrypted.
+/// Casts of `PyCast` values are returned by the column numbers,
+/// every row must have the same casts.
+fn convert_rows(
+    rows: Vec<Py<PyAny>>,
+    columns: &[String],
+    column_codecs: Option<&ColumnCodecs>,
+) -> RustPSQLDriverPyResult<(Vec<Vec<PythonDTO>>, HashMap<usize, String>)> {
+    let casts = rows_parameter_casts(&rows)?;
+    let row_width = columns.len();
+    let mut converted_rows: Vec<Vec<PythonDTO>> = Vec::with_capacity(rows.len());
+    for row in rows {
+        let row = match column_codecs {
+            Some(column_codecs) => column_codecs.encrypt_row(columns, row)?,
+            None => row,
+        };
+        let converted_row = convert_parameters(row)?;
+        if converted_row.len() != row_width {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "Every row must have {row_width} values"
+            )));
+        }
+        converted_rows.push(converted_row);
+    }
+    Ok((converted_rows, casts))
+}
+
+/// Convert records to values of the column types, every record must have value for every column.
+fn convert_records(
+    records: Vec<Py<PyAny>>,
+    column_names: &[&str],
+    column_types: &[Type],
+    column_codecs: &ColumnCodecs,
+) -> RustPSQLDriverPyResult<Vec<Vec<PythonDTO>>> {
+    Python::with_gil(|gil| {
+        let mut converted_records: Vec<Vec<PythonDTO>> = Vec::with_capacity(records.len());
+        for record in records {
+            let values = record.extract::<Vec<Py<PyAny>>>(gil).map_err(|_| {
+                RustPSQLDriverError::PyToRustValueConversionError(
+                    "Every record must be a list or tuple of values".into(),
+                )
+            })?;
+            if values.len() != column_types.len() {
+                return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                    "Every record must have {} values",
+                    column_types.len()
+                )));
+            }
+            let mut converted_record = Vec::with_capacity(values.len());
+            for ((value, column_name), column_type) in
+                values.iter().zip(column_names).zip(column_types)
+            {
+                let value = column_codecs.encrypt_value(column_name, value.bind(gil).clone())?;
+                converted_record.push(py_to_rust_for_type(&value, column_type)?);
+            }
+            converted_records.push(converted_record);
+        }
+        Ok(converted_records)
+    })
+}
+
+/// Return number of rows in one statement within the limit of the parameters number.
+fn rows_page_size(page_size: usize, row_width: usize) -> usize {
+    page_size.clamp(1, MAX_STATEMENT_PARAMETERS / row_width.max(1))
+}
+
+/// Return `VALUES` list of `rows_count` rows,
+/// every parameter is cast to the type of its column.
+///
+/// Parameters with `PyCast` casts are cast to the `PyCast` type first.
+fn typed_values_list(
+    column_types: &[String],
+    casts: &HashMap<usize, String>,
+    rows_count: usize,
+) -> String {
+    let rows = (0..rows_count)
+        .map(|row_idx| {
+            let row_params = column_types
+                .iter()
+                .enumerate()
+                .map(|(column_idx, column_type)| {
+                    let param_num = row_idx * column_types.len() + column_idx + 1;
+                    match casts.get(&(column_idx + 1)) {
+                        Some(type_name) => format!("${param_num}::{type_name}::{column_type}"),
+                        None => format!("${param_num}::{column_type}"),
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join(", ");
+            format!("({row_params})")
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+    format!("VALUES {rows}")
+}
+
+/// Return condition matching target rows with the rows from `VALUES`.
+fn key_condition(key_columns: &[String]) -> String {
+    key_columns
+        .iter()
+        .map(|column| {
+            let quoted = quote_ident(column);
+            format!("{TARGET_ALIAS}.{quoted} = {VALUES_ALIAS}.{quoted}")
+        })
+        .collect::<Vec<String>>()
+        .join(" AND ")
+}
+
+/// Convert error of the row locking query, lock conflict gets its own error.
+fn claim_error(err: RustPSQLDriverError) -> RustPSQLDriverError {
+    match err {
+        RustPSQLDriverError::RustDriverError(err)
+            if err.code() == Some(&SqlState::LOCK_NOT_AVAILABLE) =>
+        {
+            RustPSQLDriverError::LockNotAvailableError(format!(
+                "Rows are locked by another transaction, error - {err}"
+            ))
         }
+        err => RustPSQLDriverError::TransactionExecuteError(format!(
+            "Cannot claim rows, error - {err}"
+        )),
     }
 }
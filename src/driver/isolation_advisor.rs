@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use pyo3::{pyclass, pymethods, types::PyAnyMethods, Py, PyAny, Python};
+
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    format_helpers::normalize_query,
+};
+
+use super::transaction_options::IsolationLevel;
+
+/// Statements which contain the pattern require the isolation level.
+struct IsolationRule {
+    pattern: String,
+    normalized_pattern: String,
+    required_isolation_level: IsolationLevel,
+}
+
+/// Detects statements which need stronger isolation level
+/// than the transaction has and reports them to the observer.
+///
+/// Patterns are matched against the normalized querystring,
+/// so case, whitespaces and literals don't matter.
+#[derive(Clone)]
+pub struct IsolationAdvisor {
+    rules: Arc<Vec<IsolationRule>>,
+    observer: Arc<Py<PyAny>>,
+}
+
+impl IsolationAdvisor {
+    /// Create new advisor.
+    ///
+    /// # Errors
+    /// May return Err Result if observer is not callable.
+    pub fn new(
+        gil: Python<'_>,
+        rules: Vec<(String, IsolationLevel)>,
+        observer: Py<PyAny>,
+    ) -> RustPSQLDriverPyResult<Self> {
+        if !observer.bind(gil).is_callable() {
+            return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                "observer must be a callable".into(),
+            ));
+        }
+
+        let rules = rules
+            .into_iter()
+            .map(|(pattern, required_isolation_level)| IsolationRule {
+                normalized_pattern: normalize_query(&pattern),
+                pattern,
+                required_isolation_level,
+            })
+            .collect();
+        Ok(IsolationAdvisor {
+            rules: Arc::new(rules),
+            observer: Arc::new(observer),
+        })
+    }
+
+    /// Report the first rule which requires stronger isolation level.
+    ///
+    /// Transaction without isolation level is considered `READ COMMITTED`,
+    /// it's the default level of `PostgreSQL`.
+    ///
+    /// # Errors
+    /// May return Err Result if observer raised an exception.
+    pub fn check(
+        isolation_advisor: Option<&IsolationAdvisor>,
+        gil: Python<'_>,
+        querystring: &str,
+        isolation_level: Option<IsolationLevel>,
+    ) -> RustPSQLDriverPyResult<()> {
+        let Some(isolation_advisor) = isolation_advisor else {
+            return Ok(());
+        };
+        let isolation_level = isolation_level.unwrap_or(IsolationLevel::ReadCommitted);
+
+        let normalized_query = normalize_query(querystring);
+        let Some(rule) = isolation_advisor.rules.iter().find(|rule| {
+            rule.required_isolation_level > isolation_level
+                && normalized_query.contains(&rule.normalized_pattern)
+        }) else {
+            return Ok(());
+        };
+
+        let warning = IsolationUpgradeWarning {
+            querystring: querystring.to_string(),
+            pattern: rule.pattern.clone(),
+            isolation_level,
+            required_isolation_level: rule.required_isolation_level,
+        };
+        isolation_advisor.observer.call1(gil, (warning,))?;
+        Ok(())
+    }
+}
+
+/// Statement was executed in the transaction with weaker isolation level
+/// than the matched pattern requires.
+#[pyclass]
+#[derive(Clone)]
+pub struct IsolationUpgradeWarning {
+    querystring: String,
+    pattern: String,
+    isolation_level: IsolationLevel,
+    required_isolation_level: IsolationLevel,
+}
+
+#[pymethods]
+impl IsolationUpgradeWarning {
+    #[getter]
+    fn querystring(&self) -> String {
+        self.querystring.clone()
+    }
+
+    #[getter]
+    fn pattern(&self) -> String {
+        self.pattern.clone()
+    }
+
+    #[getter]
+    fn isolation_level(&self) -> IsolationLevel {
+        self.isolation_level
+    }
+
+    #[getter]
+    fn required_isolation_level(&self) -> IsolationLevel {
+        self.required_isolation_level
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "IsolationUpgradeWarning(pattern={}, isolation_level={}, required_isolation_level={})",
+            self.pattern,
+            self.isolation_level.to_str_level(),
+            self.required_isolation_level.to_str_level(),
+        )
+    }
+}
@@ -0,0 +1,180 @@
+use std::{sync::Arc, time::Duration};
+
+use pyo3::{
+    exceptions::PyResourceWarning,
+    pyclass, pymethods,
+    types::{PyAnyMethods, PyList, PyListMethods, PyStringMethods},
+    Py, PyAny, Python,
+};
+
+use crate::exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult};
+
+/// Reports connections which aren't returned to the pool
+/// for longer than `leak_timeout`.
+///
+/// Every leaked connection is reported once, to the callback
+/// if it's set or as `ResourceWarning` otherwise.
+#[derive(Clone)]
+pub struct LeakDetector {
+    leak_timeout: Duration,
+    callback: Option<Arc<Py<PyAny>>>,
+    capture_stack: bool,
+}
+
+impl LeakDetector {
+    /// Create new detector.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) `leak_timeout` is negative or not finite
+    /// 2) Callback is not callable
+    pub fn new(
+        gil: Python<'_>,
+        leak_timeout: f64,
+        callback: Option<Py<PyAny>>,
+        capture_stack: bool,
+    ) -> RustPSQLDriverPyResult<Self> {
+        let leak_timeout = Duration::try_from_secs_f64(leak_timeout).map_err(|_| {
+            RustPSQLDriverError::ConnectionPoolConfigurationError(format!(
+                "leak_timeout must be non-negative number of seconds, got {leak_timeout}"
+            ))
+        })?;
+        if let Some(callback) = &callback {
+            if !callback.bind(gil).is_callable() {
+                return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                    "leak_callback must be a callable".into(),
+                ));
+            }
+        }
+        Ok(LeakDetector {
+            leak_timeout,
+            callback: callback.map(Arc::new),
+            capture_stack,
+        })
+    }
+
+    /// Build detector from the pool options, it's None without `leak_timeout`.
+    ///
+    /// # Errors
+    /// May return Err Result if options are invalid.
+    pub fn from_options(
+        leak_timeout: Option<f64>,
+        callback: Option<Py<PyAny>>,
+        capture_stack: Option<bool>,
+    ) -> RustPSQLDriverPyResult<Option<Self>> {
+        match (leak_timeout, callback) {
+            (Some(leak_timeout), callback) => Ok(Some(Python::with_gil(|gil| {
+                LeakDetector::new(gil, leak_timeout, callback, capture_stack.unwrap_or(false))
+            })?)),
+            (None, Some(_)) => Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                "leak_timeout must be specified to use leak_callback".into(),
+            )),
+            (None, None) => Ok(None),
+        }
+    }
+
+    #[must_use]
+    pub fn leak_timeout(&self) -> Duration {
+        self.leak_timeout
+    }
+
+    /// Return Python stack of the current thread if it must be captured.
+    ///
+    /// It must be called before the first await of the checkout,
+    /// later the checkout runs without Python frames.
+    #[must_use]
+    pub fn capture_stack(&self, gil: Python<'_>) -> Option<String> {
+        if !self.capture_stack {
+            return None;
+        }
+        let stack = gil
+            .import("traceback")
+            .and_then(|traceback| traceback.call_method0("format_stack"))
+            .ok()?;
+        let stack = stack.downcast_into::<PyList>().ok()?;
+        Some(
+            stack
+                .iter()
+                .filter_map(|frame| Some(frame.str().ok()?.to_str().ok()?.to_owned()))
+                .collect(),
+        )
+    }
+
+    /// Report connection which is held for `held` already.
+    pub fn report(&self, held: Duration, stack: Option<String>) {
+        tracing::warn!(
+            held_ms = held.as_secs_f64() * 1000.0,
+            leak_timeout_ms = self.leak_timeout.as_secs_f64() * 1000.0,
+            "Connection is not returned to the pool within leak_timeout"
+        );
+        let warning = ConnectionLeakWarning {
+            held_ms: held.as_secs_f64() * 1000.0,
+            leak_timeout_ms: self.leak_timeout.as_secs_f64() * 1000.0,
+            stack,
+        };
+        Python::with_gil(|gil| {
+            let result = match &self.callback {
+                Some(callback) => callback.call1(gil, (warning,)).map(|_| ()),
+                None => gil.import("warnings").and_then(|warnings| {
+                    let category = gil.get_type::<PyResourceWarning>();
+                    warnings
+                        .call_method1("warn", (warning.message(), category))
+                        .map(|_| ())
+                }),
+            };
+            // There is no caller to raise the error to.
+            if let Err(err) = result {
+                err.write_unraisable(gil, None);
+            }
+        });
+    }
+}
+
+/// Connection wasn't returned to the pool within `leak_timeout`.
+#[pyclass]
+#[derive(Clone)]
+pub struct ConnectionLeakWarning {
+    held_ms: f64,
+    leak_timeout_ms: f64,
+    stack: Option<String>,
+}
+
+impl ConnectionLeakWarning {
+    /// Text of the warning with the stack of the checkout if it's captured.
+    fn message(&self) -> String {
+        let mut message = format!(
+            "Connection is not returned to the pool for {:.3} ms, leak_timeout is {:.3} ms",
+            self.held_ms, self.leak_timeout_ms,
+        );
+        if let Some(stack) = &self.stack {
+            message.push_str(", it was taken at:\n");
+            message.push_str(stack);
+        }
+        message
+    }
+}
+
+#[pymethods]
+impl ConnectionLeakWarning {
+    #[getter]
+    fn held_ms(&self) -> f64 {
+        self.held_ms
+    }
+
+    #[getter]
+    fn leak_timeout_ms(&self) -> f64 {
+        self.leak_timeout_ms
+    }
+
+    #[getter]
+    fn stack(&self) -> Option<String> {
+        self.stack.clone()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ConnectionLeakWarning(held_ms={:.3}, leak_timeout_ms={:.3})",
+            self.held_ms, self.leak_timeout_ms,
+        )
+    }
+}
@@ -0,0 +1,231 @@
+use std::sync::Arc;
+
+use futures::StreamExt;
+use futures_channel::mpsc::UnboundedReceiver;
+use pyo3::{exceptions::PyStopAsyncIteration, pyclass, pymethods, Py, PyAny, PyErr, Python};
+use tokio::sync::Mutex;
+use tokio_postgres::{AsyncMessage, Config};
+
+use crate::{
+    driver::{
+        common_options::SslMode, connection::Connection, inner_connection::PsqlpyConnection,
+        utils::TlsOptions,
+    },
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    format_helpers::quote_ident,
+    runtime::rustdriver_future,
+};
+
+use super::{
+    core::connect_for_notifications,
+    structs::{ListenerNotification, ListenerNotificationMsg},
+};
+
+/// Dedicated connection which receives notifications of the channels.
+struct ListenConnection {
+    connection: Connection,
+    receiver: UnboundedReceiver<AsyncMessage>,
+}
+
+/// Settings and state shared between the listener and its futures.
+struct ChannelListenerInner {
+    pg_config: Arc<Config>,
+    tls_options: TlsOptions,
+    ssl_mode: Option<SslMode>,
+    channels: Vec<String>,
+    listen_connection: Mutex<Option<ListenConnection>>,
+    is_closed: Mutex<bool>,
+}
+
+impl ChannelListenerInner {
+    /// Connect and execute `LISTEN` if it's not done yet.
+    async fn ensure_listening(
+        &self,
+        listen_connection: &mut Option<ListenConnection>,
+    ) -> RustPSQLDriverPyResult<()> {
+        if *self.is_closed.lock().await {
+            return Err(RustPSQLDriverError::ListenerClosedError);
+        }
+        if listen_connection.is_some() {
+            return Ok(());
+        }
+
+        let (client, receiver) =
+            connect_for_notifications(self.pg_config.clone(), &self.tls_options, &self.ssl_mode)
+                .await?;
+        let db_client = Arc::new(PsqlpyConnection::single(client));
+
+        let listen_query: String = self
+            .channels
+            .iter()
+            .map(|channel| format!("LISTEN {};", quote_ident(channel)))
+            .collect();
+        db_client
+            .batch_execute(&listen_query)
+            .await
+            .map_err(|err| {
+                RustPSQLDriverError::ListenerStartError(format!(
+                    "Cannot listen the channels, error - {err}"
+                ))
+            })?;
+
+        *listen_connection = Some(ListenConnection {
+            connection: Connection::new(
+                Some(db_client),
+                None,
+                self.pg_config.clone(),
+                self.tls_options.clone(),
+                self.ssl_mode,
+            ),
+            receiver,
+        });
+        Ok(())
+    }
+}
+
+/// Async iterator over notifications of the channels.
+///
+/// Notifications are received by the dedicated connection,
+/// because pool connections don't keep `LISTEN` between checkouts.
+/// Listening starts on `async with` or on the first iteration.
+#[pyclass]
+pub struct ChannelListener {
+    inner: Arc<ChannelListenerInner>,
+}
+
+impl ChannelListener {
+    /// Create new listener of the channels.
+    ///
+    /// # Errors
+    /// May return Err Result if channels are empty.
+    pub fn new(
+        pg_config: Arc<Config>,
+        tls_options: TlsOptions,
+        ssl_mode: Option<SslMode>,
+        channels: Vec<String>,
+    ) -> RustPSQLDriverPyResult<Self> {
+        if channels.is_empty() {
+            return Err(RustPSQLDriverError::ListenerStartError(
+                "At least one channel must be listened".into(),
+            ));
+        }
+
+        Ok(ChannelListener {
+            inner: Arc::new(ChannelListenerInner {
+                pg_config,
+                tls_options,
+                ssl_mode,
+                channels,
+                listen_connection: Mutex::new(None),
+                is_closed: Mutex::new(false),
+            }),
+        })
+    }
+}
+
+#[pymethods]
+impl ChannelListener {
+    #[getter]
+    fn channels(&self) -> Vec<String> {
+        self.inner.channels.clone()
+    }
+
+    #[must_use]
+    fn __aiter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    async fn __aenter__<'a>(slf: Py<Self>) -> RustPSQLDriverPyResult<Py<Self>> {
+        let inner = Python::with_gil(|gil| slf.borrow(gil).inner.clone());
+        {
+            let mut listen_connection = inner.listen_connection.lock().await;
+            inner.ensure_listening(&mut listen_connection).await?;
+        }
+        Ok(slf)
+    }
+
+    async fn __aexit__<'a>(
+        &mut self,
+        _exception_type: Py<PyAny>,
+        exception: Py<PyAny>,
+        _traceback: Py<PyAny>,
+    ) -> RustPSQLDriverPyResult<()> {
+        let (is_exception_none, py_err) = pyo3::Python::with_gil(|gil| {
+            (
+                exception.is_none(gil),
+                PyErr::from_value(exception.into_bound(gil)),
+            )
+        });
+
+        self.close().await?;
+
+        if !is_exception_none {
+            return Err(RustPSQLDriverError::RustPyError(py_err));
+        }
+        Ok(())
+    }
+
+    /// Return next notification of the channels.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Listener is closed
+    /// 2) Cannot connect to the database or execute `LISTEN`
+    fn __anext__(&self) -> RustPSQLDriverPyResult<Option<Py<PyAny>>> {
+        let inner = self.inner.clone();
+
+        let py_future = Python::with_gil(move |gil| {
+            rustdriver_future(gil, async move {
+                let mut listen_connection = inner.listen_connection.lock().await;
+                inner.ensure_listening(&mut listen_connection).await?;
+                let Some(ListenConnection {
+                    connection,
+                    receiver,
+                }) = listen_connection.as_mut()
+                else {
+                    return Err(RustPSQLDriverError::ListenerClosedError);
+                };
+
+                loop {
+                    let Some(message) = receiver.next().await else {
+                        return Err(
+                            PyStopAsyncIteration::new_err("Listener connection is closed").into(),
+                        );
+                    };
+                    let AsyncMessage::Notification(notification) = message else {
+                        continue;
+                    };
+
+                    return Ok(ListenerNotificationMsg::new(
+                        ListenerNotification::from(notification),
+                        connection.clone(),
+                    ));
+                }
+            })
+        });
+
+        Ok(Some(py_future?))
+    }
+
+    /// Stop listening and close the dedicated connection.
+    ///
+    /// # Errors
+    /// May return Err Result if listener is already closed.
+    pub async fn close(&mut self) -> RustPSQLDriverPyResult<()> {
+        {
+            let mut is_closed = self.inner.is_closed.lock().await;
+            if *is_closed {
+                return Err(RustPSQLDriverError::ListenerClosedError);
+            }
+            *is_closed = true;
+        }
+
+        let listen_connection = self.inner.listen_connection.lock().await.take();
+        if let Some(ListenConnection { connection, .. }) = listen_connection {
+            if let Some(db_client) = connection.db_client() {
+                db_client.batch_execute("UNLISTEN *;").await?;
+            }
+        }
+        Ok(())
+    }
+}
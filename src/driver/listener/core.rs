@@ -9,14 +9,16 @@ use tokio::{
     sync::RwLock,
     task::{AbortHandle, JoinHandle},
 };
-use tokio_postgres::{AsyncMessage, Config};
+use tokio_postgres::{AsyncMessage, Client, Config};
 
 use crate::{
     driver::{
         common_options::SslMode,
         connection::Connection,
         inner_connection::PsqlpyConnection,
-        utils::{build_tls, is_coroutine_function, ConfiguredTLS},
+        utils::{
+            build_tls, connect_with_tls_fallback, is_coroutine_function, ConfiguredTLS, TlsOptions,
+        },
     },
     exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
     runtime::{rustdriver_future, tokio_runtime},
@@ -29,7 +31,7 @@ use super::structs::{
 #[pyclass]
 pub struct Listener {
     pg_config: Arc<Config>,
-    ca_file: Option<String>,
+    tls_options: TlsOptions,
     ssl_mode: Option<SslMode>,
     channel_callbacks: Arc<RwLock<ChannelCallbacks>>,
     listen_abort_handler: Option<AbortHandle>,
@@ -42,14 +44,14 @@ pub struct Listener {
 
 impl Listener {
     #[must_use]
-    pub fn new(pg_config: Arc<Config>, ca_file: Option<String>, ssl_mode: Option<SslMode>) -> Self {
+    pub fn new(pg_config: Arc<Config>, tls_options: TlsOptions, ssl_mode: Option<SslMode>) -> Self {
         Listener {
             pg_config: pg_config.clone(),
-            ca_file,
+            tls_options,
             ssl_mode,
             channel_callbacks: Arc::default(),
             listen_abort_handler: Option::default(),
-            connection: Connection::new(None, None, pg_config.clone()),
+            connection: Connection::new(None, None, pg_config.clone(), TlsOptions::default(), None),
             receiver: Option::default(),
             listen_query: Arc::default(),
             is_listened: Arc::new(RwLock::new(false)),
@@ -184,44 +186,17 @@ impl Listener {
             ));
         }
 
-        let tls_ = build_tls(&self.ca_file, &self.ssl_mode)?;
-
-        let mut builder = SslConnector::builder(SslMethod::tls())?;
-        builder.set_verify(SslVerifyMode::NONE);
-
-        let pg_config = self.pg_config.clone();
-        let connect_future = async move {
-            match tls_ {
-                ConfiguredTLS::NoTls => {
-                    return pg_config
-                        .connect(MakeTlsConnector::new(builder.build()))
-                        .await;
-                }
-                ConfiguredTLS::TlsConnector(connector) => {
-                    return pg_config.connect(connector).await;
-                }
-            }
-        };
-
-        let (client, mut connection) = tokio_runtime().spawn(connect_future).await??;
-
-        let (transmitter, receiver) = futures_channel::mpsc::unbounded::<AsyncMessage>();
-
-        let stream =
-            stream::poll_fn(move |cx| connection.poll_message(cx)).map_err(|e| panic!("{}", e));
-
-        let connection = stream.forward(transmitter).map(|r| {
-            r.map_err(|_| {
-                RustPSQLDriverError::ListenerStartError("Cannot startup the listener".into())
-            })
-        });
-        tokio_runtime().spawn(connection);
+        let (client, receiver) =
+            connect_for_notifications(self.pg_config.clone(), &self.tls_options, &self.ssl_mode)
+                .await?;
 
         self.receiver = Some(Arc::new(RwLock::new(receiver)));
         self.connection = Connection::new(
-            Some(Arc::new(PsqlpyConnection::SingleConn(client))),
+            Some(Arc::new(PsqlpyConnection::single(client))),
             None,
             self.pg_config.clone(),
+            self.tls_options.clone(),
+            self.ssl_mode,
         );
 
         self.is_started = true;
@@ -339,6 +314,54 @@ impl Listener {
     }
 }
 
+/// Create new single connection which forwards asynchronous messages.
+///
+/// All `NOTIFY` and `NOTICE` messages from the server
+/// are sent to the returned receiver.
+///
+/// # Errors
+/// May return Err Result if cannot build TLS or connect to the database.
+#[allow(clippy::ref_option)]
+pub(crate) async fn connect_for_notifications(
+    pg_config: Arc<Config>,
+    tls_options: &TlsOptions,
+    ssl_mode: &Option<SslMode>,
+) -> RustPSQLDriverPyResult<(Client, UnboundedReceiver<AsyncMessage>)> {
+    let tls_ = build_tls(tls_options, ssl_mode)?;
+
+    let mut builder = SslConnector::builder(SslMethod::tls())?;
+    builder.set_verify(SslVerifyMode::NONE);
+
+    let connect_future = async move {
+        match tls_ {
+            ConfiguredTLS::NoTls => {
+                return connect_with_tls_fallback(
+                    &pg_config,
+                    MakeTlsConnector::new(builder.build()),
+                )
+                .await;
+            }
+            ConfiguredTLS::TlsConnector(connector) => {
+                return connect_with_tls_fallback(&pg_config, connector).await;
+            }
+        }
+    };
+
+    let (client, mut connection) = tokio_runtime().spawn(connect_future).await??;
+
+    let (transmitter, receiver) = futures_channel::mpsc::unbounded::<AsyncMessage>();
+
+    let stream =
+        stream::poll_fn(move |cx| connection.poll_message(cx)).map_err(|e| panic!("{}", e));
+
+    let connection = stream.forward(transmitter).map(|r| {
+        r.map_err(|_| RustPSQLDriverError::ListenerStartError("Cannot startup the listener".into()))
+    });
+    tokio_runtime().spawn(connection);
+
+    Ok((client, receiver))
+}
+
 async fn dispatch_callback(
     listener_callback: &ListenerCallback,
     listener_notification: ListenerNotification,
@@ -371,7 +394,9 @@ async fn execute_listen(
     Ok(())
 }
 
-fn process_message(message: Option<AsyncMessage>) -> RustPSQLDriverPyResult<ListenerNotification> {
+pub(crate) fn process_message(
+    message: Option<AsyncMessage>,
+) -> RustPSQLDriverPyResult<ListenerNotification> {
     let Some(async_message) = message else {
         return Err(RustPSQLDriverError::ListenerError("Wow".into()));
     };
@@ -1,2 +1,4 @@
+pub mod channel_listener;
 pub mod core;
 pub mod structs;
+pub mod table_watcher;
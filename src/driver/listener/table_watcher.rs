@@ -0,0 +1,419 @@
+use std::sync::Arc;
+
+use futures::StreamExt;
+use futures_channel::mpsc::UnboundedReceiver;
+use openssl::sha::sha256;
+use pyo3::{exceptions::PyStopAsyncIteration, pyclass, pymethods, Py, PyAny, PyErr, Python};
+use serde_json::Value;
+use tokio::sync::RwLock;
+use tokio_postgres::{AsyncMessage, Config};
+
+use crate::{
+    driver::{common_options::SslMode, inner_connection::PsqlpyConnection, utils::TlsOptions},
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    format_helpers::{quote_ident, quote_literal, MAX_IDENT_LENGTH},
+    runtime::rustdriver_future,
+    value_converter::build_python_from_serde_value,
+};
+
+use super::core::connect_for_notifications;
+
+const ALLOWED_OPERATIONS: [&str; 3] = ["INSERT", "UPDATE", "DELETE"];
+
+/// `PostgreSQL` rejects notification payloads of 8000 bytes and longer.
+const MAX_PAYLOAD_LENGTH: usize = 8000;
+
+/// Length of the hex hash suffix of the generated names.
+const NAME_HASH_LENGTH: usize = 8;
+
+/// Return hex hash of the text for the suffix of the generated name.
+fn name_hash(text: &str) -> String {
+    sha256(text.as_bytes())[..NAME_HASH_LENGTH / 2]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Cut `name` to fit into the identifier together with the hash suffix.
+fn truncate_name(name: &str) -> &str {
+    let max_length = MAX_IDENT_LENGTH - NAME_HASH_LENGTH - 1;
+    let mut end = name.len().min(max_length);
+    while !name.is_char_boundary(end) {
+        end -= 1;
+    }
+    &name[..end]
+}
+
+/// Build identifier which is never truncated by `PostgreSQL`.
+///
+/// Long names are cut and get hash of the full name as suffix,
+/// so different long names don't collide.
+fn bounded_ident(name: &str) -> String {
+    if name.len() <= MAX_IDENT_LENGTH {
+        return name.to_string();
+    }
+    format!("{}_{}", truncate_name(name), name_hash(name))
+}
+
+/// Build default channel name for the table.
+///
+/// Hash of the schema and table names is always added,
+/// so `a_b.c` and `a.b_c` get different channels.
+fn default_channel(schema_name: Option<&str>, table_name: &str) -> String {
+    let full_name = match schema_name {
+        Some(schema_name) => format!("{schema_name}.{table_name}"),
+        None => table_name.to_string(),
+    };
+    format!(
+        "{}_{}",
+        truncate_name(&format!("psqlpy_watch_{table_name}")),
+        name_hash(&full_name),
+    )
+}
+
+/// Stream of the table change events.
+///
+/// Changes are delivered by the trigger which executes `pg_notify`
+/// with JSON payload, every payload is decoded into python dict
+/// with `operation`, `schema`, `table` and `row` keys.
+#[pyclass]
+pub struct TableWatcher {
+    db_client: Option<Arc<PsqlpyConnection>>,
+    receiver: Option<Arc<RwLock<UnboundedReceiver<AsyncMessage>>>>,
+    channel: String,
+    table_name: String,
+    trigger_name: String,
+    function_name: String,
+    operations: Vec<String>,
+    key_columns: Vec<String>,
+    temporary: bool,
+}
+
+impl TableWatcher {
+    /// Create new dedicated connection, install trigger and start listening.
+    ///
+    /// If `temporary` is true, trigger function is created in `pg_temp` schema,
+    /// so scaffolding is removed by `PostgreSQL` when the watcher connection is closed.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Operations are invalid
+    /// 2) Cannot connect to the database
+    /// 3) Cannot install trigger or execute `LISTEN`
+    #[allow(clippy::too_many_arguments)]
+    pub async fn start(
+        pg_config: Arc<Config>,
+        tls_options: TlsOptions,
+        ssl_mode: Option<SslMode>,
+        table_name: String,
+        schema_name: Option<String>,
+        operations: Option<Vec<String>>,
+        channel: Option<String>,
+        temporary: bool,
+    ) -> RustPSQLDriverPyResult<Self> {
+        let operations = match operations {
+            Some(operations) => {
+                let mut upper_operations = Vec::with_capacity(operations.len());
+                for operation in operations {
+                    let upper_operation = operation.to_uppercase();
+                    if !ALLOWED_OPERATIONS.contains(&upper_operation.as_str()) {
+                        return Err(RustPSQLDriverError::ListenerStartError(format!(
+                            "Cannot watch {operation} operation, allowed operations are {}",
+                            ALLOWED_OPERATIONS.join(", "),
+                        )));
+                    }
+                    if !upper_operations.contains(&upper_operation) {
+                        upper_operations.push(upper_operation);
+                    }
+                }
+                upper_operations
+            }
+            None => ALLOWED_OPERATIONS
+                .iter()
+                .map(|op| (*op).to_string())
+                .collect(),
+        };
+        if operations.is_empty() {
+            return Err(RustPSQLDriverError::ListenerStartError(
+                "At least one operation must be watched".into(),
+            ));
+        }
+
+        let channel = match channel {
+            Some(channel) if channel.is_empty() || channel.len() > MAX_IDENT_LENGTH => {
+                return Err(RustPSQLDriverError::ListenerStartError(format!(
+                    "Channel name must be from 1 to {MAX_IDENT_LENGTH} bytes long, got {channel}"
+                )));
+            }
+            Some(channel) => channel,
+            None => default_channel(schema_name.as_deref(), &table_name),
+        };
+
+        let full_table_name = match &schema_name {
+            Some(schema_name) => {
+                format!("{}.{}", quote_ident(schema_name), quote_ident(&table_name))
+            }
+            None => quote_ident(&table_name),
+        };
+        let trigger_name = quote_ident(&bounded_ident(&format!("{channel}_trigger")));
+        let mut function_name = quote_ident(&bounded_ident(&format!("{channel}_notify")));
+        if temporary {
+            function_name = format!("pg_temp.{function_name}");
+        }
+
+        let (client, receiver) =
+            connect_for_notifications(pg_config, &tls_options, &ssl_mode).await?;
+        let db_client = Arc::new(PsqlpyConnection::single(client));
+
+        let key_columns = db_client
+            .query(
+                "SELECT attribute.attname::text FROM pg_index AS index \
+                JOIN pg_attribute AS attribute ON attribute.attrelid = index.indrelid \
+                AND attribute.attnum = ANY(index.indkey) \
+                WHERE index.indrelid = $1::text::regclass AND index.indisprimary \
+                ORDER BY array_position(index.indkey, attribute.attnum)",
+                &[&full_table_name],
+            )
+            .await
+            .map_err(|err| {
+                RustPSQLDriverError::ListenerStartError(format!(
+                    "Cannot find primary key of the table, error - {err}"
+                ))
+            })?
+            .iter()
+            .map(|row| row.get::<_, String>(0))
+            .collect();
+
+        let watcher = TableWatcher {
+            db_client: Some(db_client.clone()),
+            receiver: Some(Arc::new(RwLock::new(receiver))),
+            channel,
+            table_name: full_table_name,
+            trigger_name,
+            function_name,
+            operations,
+            key_columns,
+            temporary,
+        };
+
+        db_client
+            .batch_execute(&watcher.install_query())
+            .await
+            .map_err(|err| {
+                RustPSQLDriverError::ListenerStartError(format!(
+                    "Cannot install table watcher trigger, error - {err}"
+                ))
+            })?;
+
+        Ok(watcher)
+    }
+
+    /// Build expression with primary key of the changed row,
+    /// it's sent instead of the row which doesn't fit into the payload.
+    fn key_expression(&self) -> String {
+        if self.key_columns.is_empty() {
+            return "NULL".into();
+        }
+        let key_fields = self
+            .key_columns
+            .iter()
+            .map(|column| {
+                format!(
+                    "{}, changed_row.{}",
+                    quote_literal(column),
+                    quote_ident(column)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!("json_build_object({key_fields})")
+    }
+
+    /// Build querystring which creates trigger function, trigger and starts listening.
+    ///
+    /// Row which doesn't fit into the payload is replaced with its primary key
+    /// and `truncated` flag is set, so the change of the huge row doesn't fail.
+    fn install_query(&self) -> String {
+        let channel = quote_literal(&self.channel);
+        let function_name = &self.function_name;
+        let trigger_name = &self.trigger_name;
+        let table_name = &self.table_name;
+        let events = self.operations.join(" OR ");
+        let listen_channel = quote_ident(&self.channel);
+        let key_expression = self.key_expression();
+
+        format!(
+            "CREATE OR REPLACE FUNCTION {function_name}() RETURNS trigger LANGUAGE plpgsql AS $psqlpy$
+DECLARE
+    changed_row record;
+    payload text;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        changed_row := OLD;
+    ELSE
+        changed_row := NEW;
+    END IF;
+    payload := json_build_object(
+        'operation', TG_OP,
+        'schema', TG_TABLE_SCHEMA,
+        'table', TG_TABLE_NAME,
+        'row', row_to_json(changed_row),
+        'truncated', false
+    )::text;
+    IF octet_length(payload) >= {MAX_PAYLOAD_LENGTH} THEN
+        payload := json_build_object(
+            'operation', TG_OP,
+            'schema', TG_TABLE_SCHEMA,
+            'table', TG_TABLE_NAME,
+            'row', {key_expression},
+            'truncated', true
+        )::text;
+    END IF;
+    PERFORM pg_notify({channel}, payload);
+    RETURN NULL;
+END;
+$psqlpy$;
+DROP TRIGGER IF EXISTS {trigger_name} ON {table_name};
+CREATE TRIGGER {trigger_name} AFTER {events} ON {table_name}
+    FOR EACH ROW EXECUTE FUNCTION {function_name}();
+LISTEN {listen_channel};"
+        )
+    }
+
+    /// Build querystring which removes trigger and trigger function.
+    fn uninstall_query(&self) -> String {
+        let function_name = &self.function_name;
+        let trigger_name = &self.trigger_name;
+        let table_name = &self.table_name;
+        let listen_channel = quote_ident(&self.channel);
+
+        format!(
+            "UNLISTEN {listen_channel};
+DROP TRIGGER IF EXISTS {trigger_name} ON {table_name};
+DROP FUNCTION IF EXISTS {function_name}();"
+        )
+    }
+}
+
+#[pymethods]
+impl TableWatcher {
+    #[getter]
+    fn channel(&self) -> String {
+        self.channel.clone()
+    }
+
+    #[getter]
+    fn operations(&self) -> Vec<String> {
+        self.operations.clone()
+    }
+
+    #[getter]
+    fn temporary(&self) -> bool {
+        self.temporary
+    }
+
+    #[must_use]
+    fn __aiter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    #[allow(clippy::unused_async)]
+    async fn __aenter__<'a>(slf: Py<Self>) -> RustPSQLDriverPyResult<Py<Self>> {
+        Ok(slf)
+    }
+
+    async fn __aexit__<'a>(
+        &mut self,
+        _exception_type: Py<PyAny>,
+        exception: Py<PyAny>,
+        _traceback: Py<PyAny>,
+    ) -> RustPSQLDriverPyResult<()> {
+        let (is_exception_none, py_err) = pyo3::Python::with_gil(|gil| {
+            (
+                exception.is_none(gil),
+                PyErr::from_value(exception.into_bound(gil)),
+            )
+        });
+
+        self.close().await?;
+
+        if !is_exception_none {
+            return Err(RustPSQLDriverError::RustPyError(py_err));
+        }
+        Ok(())
+    }
+
+    /// Return next decoded change event.
+    ///
+    /// Iteration stops when the watcher is closed.
+    ///
+    /// # Errors
+    /// May return Err Result if cannot decode payload.
+    fn __anext__(&self) -> RustPSQLDriverPyResult<Option<Py<PyAny>>> {
+        let Some(receiver) = self.receiver.clone() else {
+            return Err(PyStopAsyncIteration::new_err("Table watcher is closed").into());
+        };
+        let channel = self.channel.clone();
+
+        let py_future = Python::with_gil(move |gil| {
+            rustdriver_future(gil, async move {
+                loop {
+                    let next_element = {
+                        let mut write_receiver = receiver.write().await;
+                        write_receiver.next().await
+                    };
+
+                    let Some(message) = next_element else {
+                        return Err(PyStopAsyncIteration::new_err(
+                            "Table watcher connection is closed",
+                        )
+                        .into());
+                    };
+                    let AsyncMessage::Notification(notification) = message else {
+                        continue;
+                    };
+                    if notification.channel() != channel {
+                        continue;
+                    }
+
+                    let payload: Value =
+                        serde_json::from_str(notification.payload()).map_err(|err| {
+                            RustPSQLDriverError::RustToPyValueConversionError(format!(
+                                "Cannot decode table change payload, error - {err}"
+                            ))
+                        })?;
+                    return Python::with_gil(|gil| build_python_from_serde_value(gil, payload));
+                }
+            })
+        });
+
+        Ok(Some(py_future?))
+    }
+
+    /// Stop watching the table.
+    ///
+    /// If watcher is temporary, trigger and trigger function are removed.
+    ///
+    /// # Errors
+    /// May return Err Result if cannot remove trigger.
+    pub async fn close(&mut self) -> RustPSQLDriverPyResult<()> {
+        let Some(db_client) = self.db_client.take() else {
+            return Err(RustPSQLDriverError::ListenerClosedError);
+        };
+        std::mem::take(&mut self.receiver);
+
+        let querystring = if self.temporary {
+            self.uninstall_query()
+        } else {
+            format!("UNLISTEN {};", quote_ident(&self.channel))
+        };
+
+        db_client.batch_execute(&querystring).await.map_err(|err| {
+            RustPSQLDriverError::ListenerError(format!(
+                "Cannot remove table watcher trigger, error - {err}"
+            ))
+        })?;
+
+        Ok(())
+    }
+}
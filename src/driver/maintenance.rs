@@ -0,0 +1,174 @@
+use pyo3::{
+    types::{PyAnyMethods, PyBool, PyDict, PyDictMethods, PyInt, PyString},
+    Bound,
+};
+use tokio_postgres::error::SqlState;
+
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    format_helpers::quote_literal,
+};
+
+use super::inner_connection::PsqlpyConnection;
+
+/// Options accepted by `VACUUM`.
+const VACUUM_OPTIONS: &[&str] = &[
+    "FULL",
+    "FREEZE",
+    "VERBOSE",
+    "DISABLE_PAGE_SKIPPING",
+    "SKIP_LOCKED",
+    "INDEX_CLEANUP",
+    "PROCESS_MAIN",
+    "PROCESS_TOAST",
+    "TRUNCATE",
+    "PARALLEL",
+    "SKIP_DATABASE_STATS",
+    "ONLY_DATABASE_STATS",
+    "BUFFER_USAGE_LIMIT",
+];
+
+/// Options accepted by `ANALYZE`.
+const ANALYZE_OPTIONS: &[&str] = &["VERBOSE", "SKIP_LOCKED", "BUFFER_USAGE_LIMIT"];
+
+/// Value of the `VACUUM`/`ANALYZE` option.
+pub enum MaintenanceOptionValue {
+    Bool(bool),
+    Int(i64),
+    Str(String),
+}
+
+impl MaintenanceOptionValue {
+    fn to_sql(&self) -> String {
+        match self {
+            MaintenanceOptionValue::Bool(true) => "TRUE".into(),
+            MaintenanceOptionValue::Bool(false) => "FALSE".into(),
+            MaintenanceOptionValue::Int(value) => value.to_string(),
+            MaintenanceOptionValue::Str(value) => quote_literal(value),
+        }
+    }
+}
+
+/// Convert Python dict of options into option names and values.
+///
+/// Names are case-insensitive, order of the dict is kept.
+///
+/// # Errors
+/// May return Err Result if key isn't a string or value
+/// isn't a bool, int or string.
+pub fn extract_maintenance_options(
+    options: &Bound<'_, PyDict>,
+) -> RustPSQLDriverPyResult<Vec<(String, MaintenanceOptionValue)>> {
+    let mut maintenance_options = Vec::with_capacity(options.len());
+    for (name, value) in options.iter() {
+        let name = name.extract::<String>()?.to_uppercase();
+        let value = if value.is_instance_of::<PyBool>() {
+            MaintenanceOptionValue::Bool(value.extract()?)
+        } else if value.is_instance_of::<PyInt>() {
+            MaintenanceOptionValue::Int(value.extract()?)
+        } else if value.is_instance_of::<PyString>() {
+            MaintenanceOptionValue::Str(value.extract()?)
+        } else {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "Value of the option {name} must be bool, int or str"
+            )));
+        };
+        maintenance_options.push((name, value));
+    }
+    Ok(maintenance_options)
+}
+
+/// Build `VACUUM` or `ANALYZE` command for the quoted table name.
+///
+/// `VACUUM` with `analyze` runs both in one command.
+///
+/// # Errors
+/// May return Err Result if:
+/// 1) Neither `vacuum` nor `analyze` is set
+/// 2) Option isn't supported by the command
+pub fn build_maintenance_command(
+    target: &str,
+    vacuum: bool,
+    analyze: bool,
+    options: &[(String, MaintenanceOptionValue)],
+) -> RustPSQLDriverPyResult<String> {
+    let (command, supported_options) = match (vacuum, analyze) {
+        (true, _) => ("VACUUM", VACUUM_OPTIONS),
+        (false, true) => ("ANALYZE", ANALYZE_OPTIONS),
+        (false, false) => {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(
+                "At least one of vacuum and analyze must be True".into(),
+            ))
+        }
+    };
+
+    let mut command_options = vec![];
+    if vacuum && analyze {
+        command_options.push("ANALYZE".to_string());
+    }
+    for (name, value) in options {
+        if !supported_options.contains(&name.as_str()) {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "{command} doesn't support option {name}"
+            )));
+        }
+        command_options.push(format!("{name} {}", value.to_sql()));
+    }
+
+    if command_options.is_empty() {
+        return Ok(format!("{command} {target}"));
+    }
+    Ok(format!(
+        "{command} ({}) {target}",
+        command_options.join(", ")
+    ))
+}
+
+/// Run `VACUUM`/`ANALYZE` command.
+///
+/// Command is sent with the simple query protocol,
+/// so it isn't wrapped into the implicit transaction with other statements.
+/// `VACUUM` is checked to run outside of the transaction block
+/// before it's sent, so the open transaction isn't aborted by it.
+///
+/// # Errors
+/// May return Err Result if:
+/// 1) `VACUUM` is run inside of the transaction block
+/// 2) Cannot execute the command
+pub async fn run_maintenance(
+    db_client: &PsqlpyConnection,
+    querystring: &str,
+    vacuum: bool,
+) -> RustPSQLDriverPyResult<()> {
+    if vacuum {
+        // Both timestamps are the same for the first statement of the transaction.
+        let in_transaction_block: bool = db_client
+            .query_one(
+                "SELECT transaction_timestamp() <> statement_timestamp()",
+                &[],
+            )
+            .await?
+            .try_get(0)?;
+        if in_transaction_block {
+            return Err(RustPSQLDriverError::ConnectionExecuteError(
+                "VACUUM cannot run inside of the transaction block".into(),
+            ));
+        }
+    }
+
+    db_client
+        .batch_execute(querystring)
+        .await
+        .map_err(|err| match err {
+            RustPSQLDriverError::RustDriverError(err)
+                if err.code() == Some(&SqlState::ACTIVE_SQL_TRANSACTION) =>
+            {
+                RustPSQLDriverError::ConnectionExecuteError(format!(
+                    "VACUUM cannot run inside of the transaction block, error - {err}"
+                ))
+            }
+            err => RustPSQLDriverError::ConnectionExecuteError(format!(
+                "Cannot run {querystring}, error - {err}"
+            )),
+        })
+}
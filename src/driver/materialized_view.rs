@@ -0,0 +1,204 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use pyo3::{
+    types::{PyDict, PyDictMethods},
+    Py, PyAny, Python,
+};
+use tokio_postgres::{error::SqlState, Client, Config, NoTls};
+
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    format_helpers::quote_ident,
+    runtime::tokio_runtime,
+};
+
+use super::{
+    common_options::SslMode,
+    inner_connection::PsqlpyConnection,
+    utils::{build_tls, ConfiguredTLS, TlsOptions},
+};
+
+/// `lock_timeout` used when refresh must not wait for the lock.
+const NO_WAIT_LOCK_TIMEOUT: &str = "1ms";
+
+/// Find materialized view visible from the connection.
+///
+/// Returns its schema and whether it's populated.
+async fn find_materialized_view(
+    db_client: &PsqlpyConnection,
+    name: &str,
+    schema_name: Option<&str>,
+) -> RustPSQLDriverPyResult<(String, bool)> {
+    let rows = db_client
+        .query(
+            "SELECT n.nspname::text, c.relispopulated \
+             FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace \
+             WHERE c.relkind = 'm' AND c.relname = $1 \
+             AND (n.nspname = $2 OR ($2 IS NULL AND pg_table_is_visible(c.oid)))",
+            &[&name, &schema_name],
+        )
+        .await?;
+
+    let Some(row) = rows.first() else {
+        let full_name = match schema_name {
+            Some(schema_name) => format!("{schema_name}.{name}"),
+            None => name.to_string(),
+        };
+        return Err(RustPSQLDriverError::ConnectionExecuteError(format!(
+            "Materialized view {full_name} doesn't exist"
+        )));
+    };
+    Ok((row.try_get(0)?, row.try_get(1)?))
+}
+
+/// Refresh materialized view.
+///
+/// View which isn't populated yet cannot be refreshed concurrently,
+/// it's refreshed as usual.
+/// If `wait` is false, refresh fails right away when the view
+/// is locked by another refresh or query.
+///
+/// # Errors
+/// May return Err Result if:
+/// 1) View doesn't exist
+/// 2) `concurrently` is used together with `with_data=False`
+/// 3) View is locked and `wait` is false
+/// 4) Cannot refresh the view
+pub async fn refresh_materialized_view(
+    db_client: &PsqlpyConnection,
+    name: &str,
+    schema_name: Option<&str>,
+    concurrently: bool,
+    with_data: bool,
+    wait: bool,
+) -> RustPSQLDriverPyResult<()> {
+    if concurrently && !with_data {
+        return Err(RustPSQLDriverError::PyToRustValueConversionError(
+            "Materialized view cannot be refreshed concurrently with_data=False".into(),
+        ));
+    }
+    let (schema_name, is_populated) = find_materialized_view(db_client, name, schema_name).await?;
+
+    let mut querystring = "REFRESH MATERIALIZED VIEW ".to_string();
+    if concurrently && is_populated {
+        querystring.push_str("CONCURRENTLY ");
+    }
+    querystring.push_str(&format!(
+        "{}.{}",
+        quote_ident(&schema_name),
+        quote_ident(name)
+    ));
+    if !with_data {
+        querystring.push_str(" WITH NO DATA");
+    }
+
+    if wait {
+        return db_client
+            .batch_execute(&querystring)
+            .await
+            .map_err(refresh_error);
+    }
+
+    let previous_settings = db_client
+        .capture_settings(vec!["lock_timeout".into()])
+        .await?;
+    db_client
+        .restore_settings(HashMap::from([(
+            "lock_timeout".into(),
+            Some(NO_WAIT_LOCK_TIMEOUT.into()),
+        )]))
+        .await?;
+    let refresh_result = db_client
+        .batch_execute(&querystring)
+        .await
+        .map_err(refresh_error);
+    let restore_result = db_client.restore_settings(previous_settings).await;
+    refresh_result?;
+    restore_result
+}
+
+/// Open separate connection which watches the refresh.
+async fn connect_monitor(
+    pg_config: Arc<Config>,
+    tls_options: TlsOptions,
+    ssl_mode: Option<SslMode>,
+) -> RustPSQLDriverPyResult<Client> {
+    tokio_runtime()
+        .spawn(async move {
+            match build_tls(&tls_options, &ssl_mode)? {
+                ConfiguredTLS::NoTls => {
+                    let (client, connection) = pg_config.connect(NoTls).await?;
+                    tokio::spawn(connection);
+                    Ok(client)
+                }
+                ConfiguredTLS::TlsConnector(connector) => {
+                    let (client, connection) = pg_config.connect(connector).await?;
+                    tokio::spawn(connection);
+                    Ok(client)
+                }
+            }
+        })
+        .await?
+}
+
+/// Call `progress_callback` every `poll_interval` with activity of the backend.
+///
+/// Refresh doesn't report progress itself, so callback gets its
+/// state, wait event, elapsed seconds and PIDs of the blocking backends.
+/// It never returns Ok, future must be dropped when refresh is over.
+///
+/// # Errors
+/// May return Err Result if:
+/// 1) Cannot connect to the database
+/// 2) Callback raises an exception
+pub async fn watch_refresh_progress(
+    pg_config: Arc<Config>,
+    tls_options: TlsOptions,
+    ssl_mode: Option<SslMode>,
+    backend_pid: i32,
+    poll_interval: Duration,
+    progress_callback: Py<PyAny>,
+) -> RustPSQLDriverPyResult<()> {
+    let monitor = connect_monitor(pg_config, tls_options, ssl_mode).await?;
+    loop {
+        tokio::time::sleep(poll_interval).await;
+        let Some(row) = monitor
+            .query_opt(
+                "SELECT state, wait_event_type, wait_event, \
+                 EXTRACT(EPOCH FROM clock_timestamp() - query_start)::float8, \
+                 pg_blocking_pids(pid) \
+                 FROM pg_stat_activity WHERE pid = $1",
+                &[&backend_pid],
+            )
+            .await?
+        else {
+            continue;
+        };
+
+        Python::with_gil(|gil| -> RustPSQLDriverPyResult<()> {
+            let progress = PyDict::new_bound(gil);
+            progress.set_item("state", row.try_get::<_, Option<String>>(0)?)?;
+            progress.set_item("wait_event_type", row.try_get::<_, Option<String>>(1)?)?;
+            progress.set_item("wait_event", row.try_get::<_, Option<String>>(2)?)?;
+            progress.set_item("elapsed", row.try_get::<_, Option<f64>>(3)?)?;
+            progress.set_item("blocked_by", row.try_get::<_, Vec<i32>>(4)?)?;
+            progress_callback.call1(gil, (progress,))?;
+            Ok(())
+        })?;
+    }
+}
+
+fn refresh_error(err: RustPSQLDriverError) -> RustPSQLDriverError {
+    match err {
+        RustPSQLDriverError::RustDriverError(err)
+            if err.code() == Some(&SqlState::LOCK_NOT_AVAILABLE) =>
+        {
+            RustPSQLDriverError::LockNotAvailableError(format!(
+                "Materialized view is locked by another refresh or query, error - {err}"
+            ))
+        }
+        err => RustPSQLDriverError::ConnectionExecuteError(format!(
+            "Cannot refresh materialized view, error - {err}"
+        )),
+    }
+}
@@ -1,10 +1,54 @@
+pub mod annotation;
+pub mod binary_copy_validation;
+pub mod column_buffers;
+pub mod column_codecs;
 pub mod common_options;
 pub mod connection;
+pub mod connection_affinity;
+pub mod connection_factory;
 pub mod connection_pool;
 pub mod connection_pool_builder;
+pub mod copy_out;
 pub mod cursor;
+pub mod diagnostics;
+pub mod dry_run;
+pub mod dsn;
 pub mod inner_connection;
+pub mod isolation_advisor;
+pub mod leak_detector;
 pub mod listener;
+pub mod maintenance;
+pub mod materialized_view;
+pub mod notices;
+pub mod partial_statement;
+pub mod pipeline;
+pub mod pool_hooks;
+pub mod pool_stats;
+pub mod prepared_transactions;
+pub mod proxy;
+pub mod query_cancel;
+pub mod query_queue;
+pub mod query_registry;
+pub mod redaction;
+pub mod replica_pool;
+pub mod resolver;
+pub mod result_cache;
+pub mod result_masking;
+pub mod result_schema;
+pub mod row_iterator;
+pub mod savepoint;
+pub mod scheduler;
+pub mod schema_validation;
+pub mod session_pooler;
+pub mod shared_connection;
+pub mod spilled_result;
+pub mod sqlcommenter;
+pub mod ssh_tunnel;
+pub mod ssl_info;
+pub mod statement_cache;
+pub mod statement_stats;
+pub mod tenant_pool;
 pub mod transaction;
 pub mod transaction_options;
 pub mod utils;
+pub mod warm_statements;
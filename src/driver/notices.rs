@@ -0,0 +1,191 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+};
+
+use deadpool_postgres::Object;
+use pyo3::{pyclass, pymethods};
+use tokio_postgres::{error::DbError, Client};
+
+/// Backend process id and start time of the postmaster.
+///
+/// Process id is unique only within one server,
+/// start time distinguishes connections to different servers.
+pub(crate) type ConnectionKey = (i32, String);
+
+/// Severity of the notice, from the lowest to the highest.
+#[pyclass(eq, eq_int)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NoticeSeverity {
+    Debug,
+    Log,
+    Info,
+    Notice,
+    Warning,
+}
+
+impl NoticeSeverity {
+    fn from_severity(severity: &str) -> Self {
+        match severity {
+            severity if severity.starts_with("DEBUG") => NoticeSeverity::Debug,
+            "LOG" => NoticeSeverity::Log,
+            "INFO" => NoticeSeverity::Info,
+            "WARNING" => NoticeSeverity::Warning,
+            _ => NoticeSeverity::Notice,
+        }
+    }
+}
+
+/// Summary of the notices received while the query was executed.
+#[pyclass]
+#[derive(Clone, Copy, Default)]
+pub struct NoticeSummary {
+    count: u32,
+    warnings: u32,
+    max_severity: Option<NoticeSeverity>,
+}
+
+impl NoticeSummary {
+    fn record(&mut self, severity: NoticeSeverity) {
+        self.count += 1;
+        if severity == NoticeSeverity::Warning {
+            self.warnings += 1;
+        }
+        self.max_severity = self.max_severity.max(Some(severity));
+    }
+}
+
+#[pymethods]
+impl NoticeSummary {
+    #[getter]
+    fn count(&self) -> u32 {
+        self.count
+    }
+
+    #[getter]
+    fn warnings(&self) -> u32 {
+        self.warnings
+    }
+
+    #[getter]
+    fn max_severity(&self) -> Option<NoticeSeverity> {
+        self.max_severity
+    }
+
+    fn __bool__(&self) -> bool {
+        self.count > 0
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "NoticeSummary(count={}, warnings={})",
+            self.count, self.warnings,
+        )
+    }
+}
+
+/// Counter of the notices received by one connection.
+///
+/// Notices are recorded by the task which drives the connection,
+/// it processes messages in order, so notices of the query
+/// are recorded before the query result is returned.
+#[derive(Clone, Default)]
+pub struct NoticeCounter {
+    summary: Arc<Mutex<NoticeSummary>>,
+    is_closed: Arc<AtomicBool>,
+}
+
+impl NoticeCounter {
+    /// Record notice from the server.
+    pub fn record(&self, notice: &DbError) {
+        if let Ok(mut summary) = self.summary.lock() {
+            summary.record(NoticeSeverity::from_severity(notice.severity()));
+        }
+    }
+
+    /// Return summary of the notices since the last call and reset it.
+    #[must_use]
+    pub fn take(&self) -> NoticeSummary {
+        self.summary
+            .lock()
+            .map(|mut summary| std::mem::take(&mut *summary))
+            .unwrap_or_default()
+    }
+
+    /// Mark counter as closed, connection doesn't receive messages anymore.
+    pub fn close(&self) {
+        self.is_closed.store(true, Ordering::Relaxed);
+    }
+
+    fn is_closed(&self) -> bool {
+        self.is_closed.load(Ordering::Relaxed)
+    }
+}
+
+/// Counters of the opened connections by the connection key.
+fn connection_counters() -> &'static Mutex<HashMap<ConnectionKey, NoticeCounter>> {
+    static COUNTERS: OnceLock<Mutex<HashMap<ConnectionKey, NoticeCounter>>> = OnceLock::new();
+    COUNTERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Counters of the pooled clients by the address of their statement cache.
+///
+/// Statement cache lives as long as the client,
+/// so its address identifies the client while it's in the pool.
+fn client_counters() -> &'static Mutex<HashMap<usize, NoticeCounter>> {
+    static COUNTERS: OnceLock<Mutex<HashMap<usize, NoticeCounter>>> = OnceLock::new();
+    COUNTERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Return key of the connection of the client.
+pub(crate) async fn connection_key(client: &Client) -> Option<ConnectionKey> {
+    let row = client
+        .query_one(
+            "SELECT pg_backend_pid(), pg_postmaster_start_time()::text",
+            &[],
+        )
+        .await
+        .ok()?;
+    Some((row.try_get(0).ok()?, row.try_get(1).ok()?))
+}
+
+/// Make counter of the new connection available for its client.
+pub async fn register_connection(client: &Client, notice_counter: NoticeCounter) {
+    let Some(connection_key) = connection_key(client).await else {
+        return;
+    };
+    if let Ok(mut counters) = connection_counters().lock() {
+        counters.retain(|_, counter| !counter.is_closed());
+        counters.insert(connection_key, notice_counter);
+    }
+}
+
+/// Return counter of the notices of the pooled client.
+///
+/// Counter is found once per client, it costs one round trip.
+pub async fn client_notice_counter(client: &Object) -> Option<NoticeCounter> {
+    let client_address = Arc::as_ptr(&client.statement_cache) as usize;
+    if let Some(notice_counter) = client_counters()
+        .lock()
+        .ok()?
+        .get(&client_address)
+        .filter(|counter| !counter.is_closed())
+    {
+        return Some(notice_counter.clone());
+    }
+
+    let connection_key = connection_key(client).await?;
+    let notice_counter = connection_counters()
+        .lock()
+        .ok()?
+        .get(&connection_key)?
+        .clone();
+
+    let mut counters = client_counters().lock().ok()?;
+    counters.retain(|_, counter| !counter.is_closed());
+    counters.insert(client_address, notice_counter.clone());
+    Some(notice_counter)
+}
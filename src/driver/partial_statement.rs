@@ -0,0 +1,289 @@
+use std::sync::Arc;
+
+use pyo3::{pyclass, pymethods, Py, PyAny, Python};
+
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    format_helpers::is_cursor_query,
+    query_result::PSQLDriverPyQueryResult,
+};
+
+use super::inner_connection::PsqlpyConnection;
+
+/// Return unique name for the cursor of the statement.
+#[must_use]
+pub fn generate_partial_cursor_name() -> String {
+    format!("psqlpy_partial_{}", uuid::Uuid::new_v4().simple())
+}
+
+/// Lifecycle of the statement.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PartialState {
+    Created,
+    Suspended,
+    Completed,
+    Closed,
+}
+
+/// Statement which rows are returned by portions inside the transaction.
+///
+/// It's a helper over `DECLARE` and `FETCH`, not the protocol portal.
+/// Cursor is declared on the first `execute_partial`,
+/// every call returns at most `max_rows` rows.
+/// One row more is fetched to know whether there are rows left,
+/// statement is suspended only if they are, otherwise it's completed.
+/// Statements which cannot be declared as a cursor, like `INSERT ... RETURNING`,
+/// are executed completely and their rows are returned by the same portions.
+/// Cursor lives until it's closed or the transaction is over.
+#[pyclass]
+pub struct PartialStatement {
+    db_client: Option<Arc<PsqlpyConnection>>,
+    querystring: String,
+    parameters: Option<Py<PyAny>>,
+    prepared: Option<bool>,
+    cursor_name: Option<String>,
+    state: PartialState,
+    // Rows received from the database and not returned yet.
+    pending: Option<PSQLDriverPyQueryResult>,
+    exhausted: bool,
+}
+
+impl PartialStatement {
+    #[must_use]
+    pub fn new(
+        db_client: Arc<PsqlpyConnection>,
+        querystring: String,
+        parameters: Option<Py<PyAny>>,
+        prepared: Option<bool>,
+    ) -> Self {
+        let cursor_name = is_cursor_query(&querystring).then(generate_partial_cursor_name);
+        PartialStatement {
+            db_client: Some(db_client),
+            querystring,
+            parameters,
+            prepared,
+            cursor_name,
+            state: PartialState::Created,
+            pending: None,
+            exhausted: false,
+        }
+    }
+
+    /// Return up to `max_rows` rows, all rows if it's 0.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Statement is closed
+    /// 2) Cannot fetch rows
+    async fn fetch_rows(
+        self_: &Py<Self>,
+        max_rows: usize,
+    ) -> RustPSQLDriverPyResult<(PSQLDriverPyQueryResult, bool)> {
+        let (db_client, cursor_name, pending, mut exhausted) = Python::with_gil(|gil| {
+            let mut self_ = self_.borrow_mut(gil);
+            (
+                self_.db_client.clone(),
+                self_.cursor_name.clone(),
+                self_.pending.take(),
+                self_.exhausted,
+            )
+        });
+        let Some(db_client) = db_client else {
+            return Err(RustPSQLDriverError::CursorClosedError);
+        };
+
+        let mut rows = pending
+            .as_ref()
+            .map_or_else(Vec::new, |pending| pending.rows().to_vec());
+        let mut template = pending;
+        if let Some(cursor_name) = cursor_name.filter(|_| !exhausted) {
+            if max_rows == 0 || rows.len() <= max_rows {
+                // One row more than requested shows whether the cursor has rows left.
+                let fetch_count = (max_rows != 0).then(|| max_rows + 1 - rows.len());
+                let fetched = db_client
+                    .execute(
+                        format!(
+                            "FETCH FORWARD {} FROM {cursor_name}",
+                            fetch_count
+                                .map_or_else(|| "ALL".to_string(), |count| count.to_string())
+                        ),
+                        None,
+                        Some(false),
+                    )
+                    .await
+                    .map_err(|err| {
+                        RustPSQLDriverError::CursorFetchError(format!(
+                            "Cannot fetch rows from cursor, error - {err}"
+                        ))
+                    })?;
+                exhausted = fetch_count.map_or(true, |count| fetched.rows().len() < count);
+                rows.extend_from_slice(fetched.rows());
+                template = Some(fetched);
+            }
+        }
+
+        let returned_count = if max_rows == 0 {
+            rows.len()
+        } else {
+            max_rows.min(rows.len())
+        };
+        let rest = rows.split_off(returned_count);
+        let suspended = !rest.is_empty();
+        Ok(Python::with_gil(|gil| {
+            let result = match &template {
+                Some(template) => template.with_other_rows(gil, rows),
+                None => PSQLDriverPyQueryResult::new(rows),
+            };
+            let mut self_ = self_.borrow_mut(gil);
+            self_.pending = template
+                .filter(|_| suspended)
+                .map(|template| template.with_other_rows(gil, rest));
+            self_.exhausted = exhausted;
+            self_.state = if suspended {
+                PartialState::Suspended
+            } else {
+                PartialState::Completed
+            };
+            (result, suspended)
+        }))
+    }
+}
+
+#[pymethods]
+impl PartialStatement {
+    #[getter]
+    fn querystring(&self) -> String {
+        self.querystring.clone()
+    }
+
+    /// Name of the cursor, None if the statement cannot be declared as a cursor.
+    #[getter]
+    fn cursor_name(&self) -> Option<String> {
+        self.cursor_name.clone()
+    }
+
+    /// Statement has rows which aren't returned yet and can be resumed.
+    #[getter]
+    fn suspended(&self) -> bool {
+        self.state == PartialState::Suspended
+    }
+
+    /// Execute statement and return first `max_rows` rows with suspension flag.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Statement is already executed or closed
+    /// 2) Cannot declare cursor or execute statement
+    /// 3) Cannot fetch rows
+    #[pyo3(signature = (max_rows=0))]
+    pub async fn execute_partial(
+        self_: Py<Self>,
+        max_rows: usize,
+    ) -> RustPSQLDriverPyResult<(PSQLDriverPyQueryResult, bool)> {
+        let (db_client, state, cursor_name, querystring, parameters, prepared) =
+            Python::with_gil(|gil| {
+                let self_ = self_.borrow(gil);
+                (
+                    self_.db_client.clone(),
+                    self_.state,
+                    self_.cursor_name.clone(),
+                    self_.querystring.clone(),
+                    self_
+                        .parameters
+                        .as_ref()
+                        .map(|parameters| parameters.clone_ref(gil)),
+                    self_.prepared,
+                )
+            });
+        let Some(db_client) = db_client else {
+            return Err(RustPSQLDriverError::CursorClosedError);
+        };
+        if state != PartialState::Created {
+            return Err(RustPSQLDriverError::CursorStartError(
+                "Statement is already executed, use resume to fetch next rows".into(),
+            ));
+        }
+
+        if let Some(cursor_name) = cursor_name {
+            db_client
+                .execute(
+                    format!("DECLARE {cursor_name} NO SCROLL CURSOR FOR {querystring}"),
+                    parameters,
+                    prepared,
+                )
+                .await
+                .map_err(|err| {
+                    RustPSQLDriverError::CursorStartError(format!(
+                        "Cannot declare cursor, error - {err}"
+                    ))
+                })?;
+            Python::with_gil(|gil| self_.borrow_mut(gil).state = PartialState::Completed);
+        } else {
+            let result = db_client.execute(querystring, parameters, prepared).await?;
+            Python::with_gil(|gil| {
+                let mut self_ = self_.borrow_mut(gil);
+                self_.state = PartialState::Completed;
+                self_.pending = Some(result);
+                self_.exhausted = true;
+            });
+        }
+
+        PartialStatement::fetch_rows(&self_, max_rows).await
+    }
+
+    /// Return next `max_rows` rows of the suspended statement with suspension flag.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Statement isn't suspended
+    /// 2) Cannot fetch rows
+    #[pyo3(signature = (max_rows=0))]
+    pub async fn resume(
+        self_: Py<Self>,
+        max_rows: usize,
+    ) -> RustPSQLDriverPyResult<(PSQLDriverPyQueryResult, bool)> {
+        let state = Python::with_gil(|gil| self_.borrow(gil).state);
+        match state {
+            PartialState::Suspended => PartialStatement::fetch_rows(&self_, max_rows).await,
+            PartialState::Closed => Err(RustPSQLDriverError::CursorClosedError),
+            PartialState::Created | PartialState::Completed => {
+                Err(RustPSQLDriverError::CursorFetchError(
+                    "Statement isn't suspended, there is nothing to resume".into(),
+                ))
+            }
+        }
+    }
+
+    /// Close the cursor and forget rows which aren't returned.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Statement is already closed
+    /// 2) Cannot execute `CLOSE`
+    pub async fn close(self_: Py<Self>) -> RustPSQLDriverPyResult<()> {
+        let (db_client, state, cursor_name) = Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (
+                self_.db_client.clone(),
+                self_.state,
+                self_.cursor_name.clone(),
+            )
+        });
+        let Some(db_client) = db_client else {
+            return Err(RustPSQLDriverError::CursorClosedError);
+        };
+
+        if let Some(cursor_name) = cursor_name.filter(|_| state != PartialState::Created) {
+            db_client
+                .execute(format!("CLOSE {cursor_name}"), None, Some(false))
+                .await?;
+        }
+        Python::with_gil(|gil| {
+            let mut self_ = self_.borrow_mut(gil);
+            self_.state = PartialState::Closed;
+            self_.pending = None;
+            std::mem::take(&mut self_.db_client);
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use futures_util::future;
+use pyo3::{pyclass, pymethods, Py, PyAny, PyErr, Python};
+
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    query_result::PSQLDriverPyQueryResult,
+};
+
+use super::{inner_connection::PsqlpyConnection, sqlcommenter::SqlCommenter};
+
+/// Query waiting to be sent in the pipeline.
+struct QueuedQuery {
+    querystring: String,
+    parameters: Option<Py<PyAny>>,
+    prepared: Option<bool>,
+}
+
+/// Queue of queries which are sent to the database together.
+///
+/// Queries are sent without waiting for the results of the previous ones,
+/// so the whole batch takes about one round-trip.
+/// Queue is flushed on `flush()` and on exit from `async with`.
+#[pyclass]
+pub struct Pipeline {
+    db_client: Arc<PsqlpyConnection>,
+    sqlcommenter: Option<SqlCommenter>,
+    queue: Vec<QueuedQuery>,
+    results: Vec<Py<PSQLDriverPyQueryResult>>,
+}
+
+impl Pipeline {
+    #[must_use]
+    pub fn new(db_client: Arc<PsqlpyConnection>, sqlcommenter: Option<SqlCommenter>) -> Self {
+        Pipeline {
+            db_client,
+            sqlcommenter,
+            queue: vec![],
+            results: vec![],
+        }
+    }
+
+    /// Put query into the queue and return its position in the results.
+    fn queue_query(
+        &mut self,
+        querystring: String,
+        parameters: Option<Py<PyAny>>,
+        prepared: Option<bool>,
+    ) -> RustPSQLDriverPyResult<usize> {
+        let (querystring, prepared) =
+            SqlCommenter::apply(self.sqlcommenter.as_ref(), querystring, prepared)?;
+        self.queue.push(QueuedQuery {
+            querystring,
+            parameters,
+            prepared,
+        });
+        Ok(self.results.len() + self.queue.len() - 1)
+    }
+}
+
+#[pymethods]
+impl Pipeline {
+    async fn __aenter__<'a>(slf: Py<Self>) -> RustPSQLDriverPyResult<Py<Self>> {
+        Ok(slf)
+    }
+
+    async fn __aexit__<'a>(
+        slf: Py<Self>,
+        _exception_type: Py<PyAny>,
+        exception: Py<PyAny>,
+        _traceback: Py<PyAny>,
+    ) -> RustPSQLDriverPyResult<()> {
+        let (is_exception_none, py_err) = pyo3::Python::with_gil(|gil| {
+            (
+                exception.is_none(gil),
+                PyErr::from_value(exception.into_bound(gil)),
+            )
+        });
+
+        if !is_exception_none {
+            // Queries of the failed block are dropped.
+            Python::with_gil(|gil| slf.borrow_mut(gil).queue.clear());
+            return Err(RustPSQLDriverError::RustPyError(py_err));
+        }
+
+        Pipeline::flush(slf).await?;
+        Ok(())
+    }
+
+    /// Queue query, result is returned by `flush()`.
+    ///
+    /// Returns position of the query result in `results()`.
+    ///
+    /// # Errors
+    /// May return Err Result if sqlcommenter cannot be applied.
+    #[pyo3(signature = (querystring, parameters=None, prepared=None))]
+    pub fn execute(
+        &mut self,
+        querystring: String,
+        parameters: Option<Py<PyAny>>,
+        prepared: Option<bool>,
+    ) -> RustPSQLDriverPyResult<usize> {
+        self.queue_query(querystring, parameters, prepared)
+    }
+
+    /// Queue query, result is returned by `flush()`.
+    ///
+    /// The same as `execute`, it's here for the symmetry with `Connection`.
+    ///
+    /// # Errors
+    /// May return Err Result if sqlcommenter cannot be applied.
+    #[pyo3(signature = (querystring, parameters=None, prepared=None))]
+    pub fn fetch(
+        &mut self,
+        querystring: String,
+        parameters: Option<Py<PyAny>>,
+        prepared: Option<bool>,
+    ) -> RustPSQLDriverPyResult<usize> {
+        self.queue_query(querystring, parameters, prepared)
+    }
+
+    /// Send all queued queries and return their results in order.
+    ///
+    /// Flush stops on the first failed query: its error is returned,
+    /// results of the other queries are discarded and not added to `results()`.
+    /// Queries sent before the failure may still be executed by the database,
+    /// so use the pipeline inside a transaction to apply all or nothing.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Cannot convert python parameters
+    /// 2) Cannot execute any of the queries
+    pub async fn flush(slf: Py<Self>) -> RustPSQLDriverPyResult<Vec<Py<PSQLDriverPyQueryResult>>> {
+        let (db_client, queue) = Python::with_gil(|gil| {
+            let mut slf = slf.borrow_mut(gil);
+            (slf.db_client.clone(), std::mem::take(&mut slf.queue))
+        });
+
+        let results =
+            future::try_join_all(queue.into_iter().map(|query| {
+                db_client.execute(query.querystring, query.parameters, query.prepared)
+            }))
+            .await?;
+
+        Python::with_gil(|gil| -> RustPSQLDriverPyResult<_> {
+            let mut py_results = Vec::with_capacity(results.len());
+            for result in results {
+                py_results.push(Py::new(gil, result)?);
+            }
+            let mut slf = slf.borrow_mut(gil);
+            slf.results
+                .extend(py_results.iter().map(|result| result.clone_ref(gil)));
+            Ok(py_results)
+        })
+    }
+
+    /// Return number of queries waiting in the queue.
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Return results of all flushed queries in order.
+    #[must_use]
+    pub fn results(&self, py: Python<'_>) -> Vec<Py<PSQLDriverPyQueryResult>> {
+        self.results
+            .iter()
+            .map(|result| result.clone_ref(py))
+            .collect()
+    }
+}
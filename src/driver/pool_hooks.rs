@@ -0,0 +1,244 @@
+use std::{sync::Arc, time::Duration};
+
+use deadpool_postgres::{ClientWrapper, Hook, HookError, Metrics, PoolBuilder};
+use pyo3::{pyclass, pymethods, types::PyAnyMethods, Py, PyAny, Python};
+
+use crate::exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult};
+
+/// Python callables which are called on the pool connection lifecycle events.
+///
+/// Hook accepts `HookMetrics` of the connection and may return
+/// querystring, it's executed on the connection.
+/// Exception in the hook fails the event: connection isn't created
+/// or it's removed from the pool instead of recycling.
+#[derive(Clone, Default)]
+pub struct PoolHooks {
+    post_create: Vec<Arc<Py<PyAny>>>,
+    pre_recycle: Vec<Arc<Py<PyAny>>>,
+    post_recycle: Vec<Arc<Py<PyAny>>>,
+    max_connection_lifetime: Option<Duration>,
+    max_idle_time: Option<Duration>,
+}
+
+/// Check that hook is a callable.
+fn check_hook(gil: Python<'_>, hook: &Py<PyAny>) -> RustPSQLDriverPyResult<()> {
+    if !hook.bind(gil).is_callable() {
+        return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+            "Hook must be a callable".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Wrap Python callable into the pool hook.
+fn python_hook(hook: Arc<Py<PyAny>>) -> Hook {
+    Hook::async_fn(move |client: &mut ClientWrapper, metrics: &Metrics| {
+        let hook = hook.clone();
+        let hook_metrics = HookMetrics::from(metrics);
+        Box::pin(async move {
+            let querystring = tokio::task::spawn_blocking(move || {
+                Python::with_gil(|gil| {
+                    hook.call1(gil, (hook_metrics,))
+                        .and_then(|querystring| querystring.extract::<Option<String>>(gil))
+                        .map_err(|err| err.to_string())
+                })
+            })
+            .await
+            .map_err(|err| err.to_string())
+            .and_then(|result| result)
+            .map_err(|err| {
+                tracing::warn!(error = %err, "Pool hook failed");
+                HookError::Message(format!("Pool hook failed: {err}").into())
+            })?;
+
+            if let Some(querystring) = querystring {
+                client
+                    .batch_execute(&querystring)
+                    .await
+                    .map_err(HookError::Backend)?;
+            }
+            Ok(())
+        })
+    })
+}
+
+/// Convert seconds of the recycling limit into duration.
+///
+/// # Errors
+/// May return Err Result if seconds are negative or not finite.
+pub fn recycling_limit(
+    seconds: Option<f64>,
+    name: &str,
+) -> RustPSQLDriverPyResult<Option<Duration>> {
+    seconds
+        .map(|seconds| {
+            Duration::try_from_secs_f64(seconds).map_err(|_| {
+                RustPSQLDriverError::ConnectionPoolConfigurationError(format!(
+                    "{name} must be non-negative number of seconds, got {seconds}"
+                ))
+            })
+        })
+        .transpose()
+}
+
+/// Hook which fails for the connections over the limits,
+/// so they are removed from the pool instead of recycling.
+fn recycling_limits_hook(
+    max_connection_lifetime: Option<Duration>,
+    max_idle_time: Option<Duration>,
+) -> Hook {
+    Hook::sync_fn(move |_, metrics: &Metrics| {
+        if max_connection_lifetime.is_some_and(|lifetime| metrics.age() > lifetime) {
+            tracing::info!(
+                age_ms = metrics.age().as_secs_f64() * 1000.0,
+                "Connection exceeded max_connection_lifetime and is removed from the pool"
+            );
+            return Err(HookError::Message(
+                "Connection exceeded max_connection_lifetime".into(),
+            ));
+        }
+        if max_idle_time.is_some_and(|idle_time| metrics.last_used() > idle_time) {
+            tracing::info!(
+                idle_ms = metrics.last_used().as_secs_f64() * 1000.0,
+                "Connection exceeded max_idle_time and is removed from the pool"
+            );
+            return Err(HookError::Message(
+                "Connection exceeded max_idle_time".into(),
+            ));
+        }
+        Ok(())
+    })
+}
+
+impl PoolHooks {
+    /// Remove connections older than `max_connection_lifetime`
+    /// on checkout instead of handing them out.
+    pub fn set_max_connection_lifetime(&mut self, max_connection_lifetime: Option<Duration>) {
+        self.max_connection_lifetime = max_connection_lifetime;
+    }
+
+    /// Remove connections unused longer than `max_idle_time`
+    /// on checkout instead of handing them out.
+    pub fn set_max_idle_time(&mut self, max_idle_time: Option<Duration>) {
+        self.max_idle_time = max_idle_time;
+    }
+
+    #[must_use]
+    pub fn max_idle_time(&self) -> Option<Duration> {
+        self.max_idle_time
+    }
+
+    /// Add hook called after new connection is created.
+    ///
+    /// # Errors
+    /// May return Err Result if hook is not callable.
+    pub fn add_post_create(
+        &mut self,
+        gil: Python<'_>,
+        hook: Py<PyAny>,
+    ) -> RustPSQLDriverPyResult<()> {
+        check_hook(gil, &hook)?;
+        self.post_create.push(Arc::new(hook));
+        Ok(())
+    }
+
+    /// Add hook called before connection is recycled.
+    ///
+    /// # Errors
+    /// May return Err Result if hook is not callable.
+    pub fn add_pre_recycle(
+        &mut self,
+        gil: Python<'_>,
+        hook: Py<PyAny>,
+    ) -> RustPSQLDriverPyResult<()> {
+        check_hook(gil, &hook)?;
+        self.pre_recycle.push(Arc::new(hook));
+        Ok(())
+    }
+
+    /// Add hook called after connection is recycled.
+    ///
+    /// # Errors
+    /// May return Err Result if hook is not callable.
+    pub fn add_post_recycle(
+        &mut self,
+        gil: Python<'_>,
+        hook: Py<PyAny>,
+    ) -> RustPSQLDriverPyResult<()> {
+        check_hook(gil, &hook)?;
+        self.post_recycle.push(Arc::new(hook));
+        Ok(())
+    }
+
+    /// Register hooks in the pool builder.
+    #[must_use]
+    pub fn apply(&self, mut db_pool_builder: PoolBuilder) -> PoolBuilder {
+        db_pool_builder = db_pool_builder.post_create(Hook::sync_fn(|_, _| {
+            tracing::debug!("New connection is created in the pool");
+            Ok(())
+        }));
+        if self.max_connection_lifetime.is_some() || self.max_idle_time.is_some() {
+            db_pool_builder = db_pool_builder.pre_recycle(recycling_limits_hook(
+                self.max_connection_lifetime,
+                self.max_idle_time,
+            ));
+        }
+        for hook in &self.post_create {
+            db_pool_builder = db_pool_builder.post_create(python_hook(hook.clone()));
+        }
+        for hook in &self.pre_recycle {
+            db_pool_builder = db_pool_builder.pre_recycle(python_hook(hook.clone()));
+        }
+        for hook in &self.post_recycle {
+            db_pool_builder = db_pool_builder.post_recycle(python_hook(hook.clone()));
+        }
+        db_pool_builder
+    }
+}
+
+/// Metrics of the connection passed to the pool hooks.
+#[pyclass]
+#[derive(Clone, Copy)]
+pub struct HookMetrics {
+    age: f64,
+    last_used: f64,
+    recycle_count: usize,
+}
+
+impl From<&Metrics> for HookMetrics {
+    fn from(metrics: &Metrics) -> Self {
+        HookMetrics {
+            age: metrics.age().as_secs_f64(),
+            last_used: metrics.last_used().as_secs_f64(),
+            recycle_count: metrics.recycle_count,
+        }
+    }
+}
+
+#[pymethods]
+impl HookMetrics {
+    /// Seconds since the connection was created.
+    #[getter]
+    fn age(&self) -> f64 {
+        self.age
+    }
+
+    /// Seconds since the connection was recycled or created.
+    #[getter]
+    fn last_used(&self) -> f64 {
+        self.last_used
+    }
+
+    /// Number of times the connection was recycled.
+    #[getter]
+    fn recycle_count(&self) -> usize {
+        self.recycle_count
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "HookMetrics(age={:.3}, last_used={:.3}, recycle_count={})",
+            self.age, self.last_used, self.recycle_count,
+        )
+    }
+}
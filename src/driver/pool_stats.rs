@@ -0,0 +1,281 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use deadpool_postgres::{Object, Pool, PoolError};
+use pyo3::Python;
+
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    runtime::tokio_runtime,
+};
+
+use super::{leak_detector::LeakDetector, statement_stats::latency_percentile};
+
+/// Number of the latest acquire waits used to calculate percentiles.
+const MAX_WAIT_SAMPLES: usize = 1024;
+
+#[derive(Default)]
+struct AcquireEntry {
+    acquires: u64,
+    timeouts: u64,
+    total_wait: Duration,
+    max_wait: Duration,
+    waits: VecDeque<Duration>,
+}
+
+struct CheckoutEntry {
+    taken_at: Instant,
+    /// Python stack of the checkout, it's captured only for leak detection.
+    stack: Option<String>,
+}
+
+/// Connections taken from the pool and not returned yet.
+#[derive(Default)]
+struct Checkouts {
+    next_id: u64,
+    taken: HashMap<u64, CheckoutEntry>,
+}
+
+/// Marks connection as taken from the pool until it's dropped.
+///
+/// It must live as long as the connection.
+pub struct CheckoutGuard {
+    id: u64,
+    checkouts: Arc<Mutex<Checkouts>>,
+}
+
+impl CheckoutGuard {
+    /// Remember Python stack of the checkout to report it if connection leaks.
+    pub fn set_stack(&self, stack: Option<String>) {
+        if stack.is_none() {
+            return;
+        }
+        if let Ok(mut checkouts) = self.checkouts.lock() {
+            if let Some(entry) = checkouts.taken.get_mut(&self.id) {
+                entry.stack = stack;
+            }
+        }
+    }
+}
+
+impl Drop for CheckoutGuard {
+    fn drop(&mut self) {
+        if let Ok(mut checkouts) = self.checkouts.lock() {
+            checkouts.taken.remove(&self.id);
+        }
+    }
+}
+
+/// Counters of the connections taken from the pool.
+#[derive(Clone, Default)]
+pub struct PoolStats {
+    entry: Arc<Mutex<AcquireEntry>>,
+    checkouts: Arc<Mutex<Checkouts>>,
+    leak_detector: Option<LeakDetector>,
+}
+
+impl PoolStats {
+    /// Report connections which aren't returned to the pool in time.
+    #[must_use]
+    pub fn with_leak_detector(mut self, leak_detector: Option<LeakDetector>) -> Self {
+        self.leak_detector = leak_detector;
+        self
+    }
+
+    /// Return Python stack of the checkout if leak detector needs it.
+    #[must_use]
+    pub fn capture_stack(&self, gil: Python<'_>) -> Option<String> {
+        self.leak_detector
+            .as_ref()
+            .and_then(|leak_detector| leak_detector.capture_stack(gil))
+    }
+
+    /// Get connection from the pool and record how long it was awaited.
+    ///
+    /// Returned guard keeps the connection in the taken connections
+    /// until it's dropped.
+    ///
+    /// # Errors
+    /// May return Err Result if cannot get new connection from the pool,
+    /// error has the snapshot of the pool.
+    pub async fn get(&self, db_pool: &Pool) -> RustPSQLDriverPyResult<(Object, CheckoutGuard)> {
+        let started_at = Instant::now();
+        match db_pool.get().await {
+            Ok(db_connection) => {
+                self.record_acquire(started_at.elapsed());
+                Ok((db_connection, self.track_checkout()))
+            }
+            Err(err) => {
+                if matches!(err, PoolError::Timeout(_)) {
+                    self.record_timeout();
+                }
+                let snapshot = self.pool_snapshot(db_pool);
+                tracing::warn!(
+                    error = %err,
+                    max_size = snapshot.max_size,
+                    size = snapshot.size,
+                    in_use = snapshot.in_use,
+                    waiting = snapshot.waiting,
+                    "Cannot check out connection from the pool"
+                );
+                Err(RustPSQLDriverError::ConnectionPoolCheckoutError(
+                    err.to_string(),
+                    snapshot,
+                ))
+            }
+        }
+    }
+
+    /// Remember that connection is taken from the pool right now.
+    #[must_use]
+    pub fn track_checkout(&self) -> CheckoutGuard {
+        let id = match self.checkouts.lock() {
+            Ok(mut checkouts) => {
+                checkouts.next_id += 1;
+                let id = checkouts.next_id;
+                checkouts.taken.insert(
+                    id,
+                    CheckoutEntry {
+                        taken_at: Instant::now(),
+                        stack: None,
+                    },
+                );
+                id
+            }
+            Err(_) => 0,
+        };
+        if let Some(leak_detector) = &self.leak_detector {
+            self.watch_leak(id, leak_detector.clone());
+        }
+        CheckoutGuard {
+            id,
+            checkouts: self.checkouts.clone(),
+        }
+    }
+
+    /// Report the checkout if it isn't returned within `leak_timeout`.
+    fn watch_leak(&self, id: u64, leak_detector: LeakDetector) {
+        let checkouts = self.checkouts.clone();
+        tokio_runtime().spawn(async move {
+            tokio::time::sleep(leak_detector.leak_timeout()).await;
+            let leaked = checkouts.lock().ok().and_then(|mut checkouts| {
+                let entry = checkouts.taken.get_mut(&id)?;
+                Some((entry.taken_at.elapsed(), entry.stack.take()))
+            });
+            if let Some((held, stack)) = leaked {
+                leak_detector.report(held, stack);
+            }
+        });
+    }
+
+    /// Return current state of the pool with the age of the longest held connection.
+    #[must_use]
+    pub fn pool_snapshot(&self, db_pool: &Pool) -> PoolSnapshot {
+        let status = db_pool.status();
+        let longest_held = self.checkouts.lock().ok().and_then(|checkouts| {
+            checkouts
+                .taken
+                .values()
+                .map(|entry| entry.taken_at)
+                .min()
+                .map(|taken_at| taken_at.elapsed())
+        });
+        PoolSnapshot {
+            max_size: status.max_size,
+            size: status.size,
+            in_use: status.size.saturating_sub(status.available),
+            waiting: status.waiting,
+            longest_held,
+        }
+    }
+
+    fn record_acquire(&self, waited: Duration) {
+        let Ok(mut entry) = self.entry.lock() else {
+            return;
+        };
+
+        entry.acquires += 1;
+        entry.total_wait += waited;
+        entry.max_wait = entry.max_wait.max(waited);
+        if entry.waits.len() >= MAX_WAIT_SAMPLES {
+            entry.waits.pop_front();
+        }
+        entry.waits.push_back(waited);
+    }
+
+    fn record_timeout(&self) {
+        if let Ok(mut entry) = self.entry.lock() {
+            entry.timeouts += 1;
+        }
+    }
+
+    /// Return current counters.
+    #[must_use]
+    pub fn snapshot(&self) -> AcquireStats {
+        let Ok(entry) = self.entry.lock() else {
+            return AcquireStats::default();
+        };
+
+        AcquireStats {
+            acquires: entry.acquires,
+            timeouts: entry.timeouts,
+            total_wait: entry.total_wait,
+            max_wait: entry.max_wait,
+            p50_wait: latency_percentile(&entry.waits, 0.5),
+            p95_wait: latency_percentile(&entry.waits, 0.95),
+            p99_wait: latency_percentile(&entry.waits, 0.99),
+        }
+    }
+
+    /// Reset all counters.
+    pub fn reset(&self) {
+        if let Ok(mut entry) = self.entry.lock() {
+            *entry = AcquireEntry::default();
+        }
+    }
+}
+
+/// State of the pool at the moment of the failed checkout.
+#[derive(Clone, Copy, Debug)]
+pub struct PoolSnapshot {
+    pub max_size: usize,
+    pub size: usize,
+    pub in_use: usize,
+    pub waiting: usize,
+    /// How long the oldest taken connection isn't returned.
+    pub longest_held: Option<Duration>,
+}
+
+impl fmt::Display for PoolSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "pool size {}/{}, in use {}, waiting {}",
+            self.size, self.max_size, self.in_use, self.waiting
+        )?;
+        if let Some(longest_held) = self.longest_held {
+            write!(
+                f,
+                ", longest held connection {:.3}s",
+                longest_held.as_secs_f64()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Snapshot of the acquire counters.
+#[derive(Clone, Copy, Default)]
+pub struct AcquireStats {
+    pub acquires: u64,
+    pub timeouts: u64,
+    pub total_wait: Duration,
+    pub max_wait: Duration,
+    pub p50_wait: Duration,
+    pub p95_wait: Duration,
+    pub p99_wait: Duration,
+}
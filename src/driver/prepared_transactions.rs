@@ -0,0 +1,186 @@
+use pyo3::{pyclass, pymethods, types::PyAnyMethods, Py, PyAny, Python};
+use tokio_postgres::error::SqlState;
+
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    format_helpers::quote_literal,
+};
+
+use super::inner_connection::PsqlpyConnection;
+
+/// Prepared transactions of the current database with the GID prefix,
+/// the oldest go first.
+const PREPARED_TRANSACTIONS_QUERY: &str = "SELECT gid, transaction::text, owner::text, \
+     database::text, EXTRACT(EPOCH FROM clock_timestamp() - prepared)::float8 \
+     FROM pg_prepared_xacts \
+     WHERE database = current_database() AND left(gid, length($1)) = $1 \
+     ORDER BY prepared";
+
+/// How orphaned prepared transaction is resolved.
+#[pyclass(eq, eq_int)]
+#[derive(Clone, Copy, PartialEq)]
+pub enum RecoveryPolicy {
+    /// Execute `COMMIT PREPARED`.
+    Commit,
+    /// Execute `ROLLBACK PREPARED`.
+    Rollback,
+}
+
+impl RecoveryPolicy {
+    fn command(self) -> &'static str {
+        match self {
+            RecoveryPolicy::Commit => "COMMIT PREPARED",
+            RecoveryPolicy::Rollback => "ROLLBACK PREPARED",
+        }
+    }
+}
+
+/// Transaction prepared for two-phase commit with `PREPARE TRANSACTION`.
+#[pyclass]
+#[derive(Clone)]
+pub struct PreparedTransaction {
+    gid: String,
+    transaction: String,
+    owner: String,
+    database: String,
+    age: f64,
+}
+
+#[pymethods]
+impl PreparedTransaction {
+    /// Global identifier passed to `PREPARE TRANSACTION`.
+    #[getter]
+    fn gid(&self) -> String {
+        self.gid.clone()
+    }
+
+    /// Transaction ID of the prepared transaction.
+    #[getter]
+    fn transaction(&self) -> String {
+        self.transaction.clone()
+    }
+
+    #[getter]
+    fn owner(&self) -> String {
+        self.owner.clone()
+    }
+
+    #[getter]
+    fn database(&self) -> String {
+        self.database.clone()
+    }
+
+    /// Seconds since the transaction was prepared.
+    #[getter]
+    fn age(&self) -> f64 {
+        self.age
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "PreparedTransaction(gid={:?}, owner={:?}, age={:.3})",
+            self.gid, self.owner, self.age,
+        )
+    }
+}
+
+/// Return prepared transactions of the current database with the GID prefix.
+///
+/// # Errors
+/// May return Err Result if cannot query `pg_prepared_xacts`.
+pub async fn fetch_prepared_transactions(
+    db_client: &PsqlpyConnection,
+    gid_prefix: &str,
+) -> RustPSQLDriverPyResult<Vec<PreparedTransaction>> {
+    db_client
+        .query(PREPARED_TRANSACTIONS_QUERY, &[&gid_prefix])
+        .await?
+        .iter()
+        .map(|row| {
+            Ok(PreparedTransaction {
+                gid: row.try_get(0)?,
+                transaction: row.try_get(1)?,
+                owner: row.try_get(2)?,
+                database: row.try_get(3)?,
+                age: row.try_get(4)?,
+            })
+        })
+        .collect::<RustPSQLDriverPyResult<Vec<_>>>()
+}
+
+/// Return policy for the prepared transaction.
+///
+/// `policy` is `RecoveryPolicy` or callable which accepts
+/// `PreparedTransaction` and returns `RecoveryPolicy` or None to skip it.
+fn choose_policy(
+    gil: Python<'_>,
+    policy: &Py<PyAny>,
+    prepared_transaction: &PreparedTransaction,
+) -> RustPSQLDriverPyResult<Option<RecoveryPolicy>> {
+    let policy = policy.bind(gil);
+    if let Ok(policy) = policy.extract::<RecoveryPolicy>() {
+        return Ok(Some(policy));
+    }
+    if !policy.is_callable() {
+        return Err(RustPSQLDriverError::PyToRustValueConversionError(
+            "policy must be RecoveryPolicy or callable".into(),
+        ));
+    }
+    policy
+        .call1((prepared_transaction.clone(),))?
+        .extract::<Option<RecoveryPolicy>>()
+        .map_err(|_| {
+            RustPSQLDriverError::PyToRustValueConversionError(
+                "policy callable must return RecoveryPolicy or None".into(),
+            )
+        })
+}
+
+/// Commit or roll back prepared transactions with the GID prefix
+/// which are at least `min_age` seconds old.
+///
+/// Transactions resolved by another process in between are skipped.
+/// Returns resolved transactions.
+///
+/// # Errors
+/// May return Err Result if:
+/// 1) GID prefix is empty
+/// 2) Policy is invalid
+/// 3) Cannot commit or roll back prepared transaction
+pub async fn resolve_prepared_transactions(
+    db_client: &PsqlpyConnection,
+    gid_prefix: &str,
+    policy: Py<PyAny>,
+    min_age: f64,
+) -> RustPSQLDriverPyResult<Vec<PreparedTransaction>> {
+    if gid_prefix.is_empty() {
+        return Err(RustPSQLDriverError::PyToRustValueConversionError(
+            "gid_prefix must not be empty".into(),
+        ));
+    }
+
+    let mut resolved = vec![];
+    for prepared_transaction in fetch_prepared_transactions(db_client, gid_prefix).await? {
+        if prepared_transaction.age < min_age {
+            continue;
+        }
+        let Some(policy) =
+            Python::with_gil(|gil| choose_policy(gil, &policy, &prepared_transaction))?
+        else {
+            continue;
+        };
+
+        let querystring = format!(
+            "{} {}",
+            policy.command(),
+            quote_literal(&prepared_transaction.gid)
+        );
+        match db_client.batch_execute(&querystring).await {
+            Ok(()) => resolved.push(prepared_transaction),
+            Err(RustPSQLDriverError::RustDriverError(err))
+                if err.code() == Some(&SqlState::UNDEFINED_OBJECT) => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(resolved)
+}
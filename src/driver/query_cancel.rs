@@ -0,0 +1,211 @@
+use std::{future::Future, sync::Arc, time::Duration};
+
+use deadpool_postgres::Object;
+use futures_util::{
+    future::{self, Either},
+    pin_mut,
+};
+use tokio_postgres::{CancelToken, NoTls};
+
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    runtime::tokio_runtime,
+};
+
+use super::{
+    common_options::SslMode,
+    inner_connection::PsqlpyConnection,
+    utils::{build_tls, ConfiguredTLS, TlsOptions},
+};
+
+/// Time to wait for the cancelled query before the connection is closed.
+const CANCELLED_QUERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Send cancel request with the new connection, it needs tokio runtime.
+async fn send_cancel_request(
+    cancel_token: CancelToken,
+    tls_options: TlsOptions,
+    ssl_mode: Option<SslMode>,
+) -> RustPSQLDriverPyResult<()> {
+    let cancel_result = match build_tls(&tls_options, &ssl_mode)? {
+        ConfiguredTLS::NoTls => cancel_token.cancel_query(NoTls).await,
+        ConfiguredTLS::TlsConnector(connector) => cancel_token.cancel_query(connector).await,
+    };
+
+    cancel_result.map_err(|err| {
+        RustPSQLDriverError::ConnectionExecuteError(format!("Cannot cancel query, error - {err}"))
+    })
+}
+
+/// Send cancel request for the query running on the connection of the token.
+///
+/// Request is sent by the new connection with the same TLS settings,
+/// it's not an error if nothing is running.
+///
+/// # Errors
+/// May return Err Result if cannot connect to the database.
+pub async fn cancel_query(
+    cancel_token: CancelToken,
+    tls_options: TlsOptions,
+    ssl_mode: Option<SslMode>,
+) -> RustPSQLDriverPyResult<()> {
+    tokio_runtime()
+        .spawn(send_cancel_request(cancel_token, tls_options, ssl_mode))
+        .await?
+}
+
+/// Hold the connection until its cancelled query finishes.
+///
+/// Connection goes back to the pool only when the last reference is released,
+/// so the late cancel request cannot hit the next query on it.
+/// Connection from the pool is closed if the query doesn't finish in time.
+async fn hold_until_finished(db_client: Arc<PsqlpyConnection>) {
+    // Empty query is answered only after the cancelled one.
+    let finished = tokio::time::timeout(CANCELLED_QUERY_TIMEOUT, db_client.batch_execute(""))
+        .await
+        .is_ok_and(|result| result.is_ok());
+    if !finished {
+        if let Ok(PsqlpyConnection::PoolConn(object, ..)) = Arc::try_unwrap(db_client) {
+            let _ = Object::take(object);
+        }
+    }
+}
+
+/// Cancel the query of the dropped future and hold the connection until it finishes.
+async fn cancel_dropped_query(
+    db_client: Arc<PsqlpyConnection>,
+    tls_options: TlsOptions,
+    ssl_mode: Option<SslMode>,
+) {
+    if let Err(err) = send_cancel_request(db_client.cancel_token(), tls_options, ssl_mode).await {
+        tracing::warn!(error = %err, "Cannot cancel query of the dropped future");
+    }
+    hold_until_finished(db_client).await;
+}
+
+/// Guard which cancels the query if its future is dropped before the end.
+///
+/// Future is dropped when Python coroutine is cancelled,
+/// without the guard the query keeps running on the server.
+/// Guard keeps the connection until the cancelled query finishes.
+pub struct CancelOnDrop {
+    db_client: Option<Arc<PsqlpyConnection>>,
+    tls_options: TlsOptions,
+    ssl_mode: Option<SslMode>,
+}
+
+impl CancelOnDrop {
+    #[must_use]
+    pub fn new(
+        db_client: Arc<PsqlpyConnection>,
+        tls_options: TlsOptions,
+        ssl_mode: Option<SslMode>,
+    ) -> Self {
+        CancelOnDrop {
+            db_client: Some(db_client),
+            tls_options,
+            ssl_mode,
+        }
+    }
+
+    /// Query is finished, nothing to cancel.
+    pub fn disarm(mut self) {
+        self.db_client = None;
+    }
+
+    /// Cancel the query and wait until its future finishes.
+    ///
+    /// Future is awaited at most `CANCELLED_QUERY_TIMEOUT`,
+    /// after that it's dropped and the connection is held
+    /// in the background until the query finishes.
+    ///
+    /// # Errors
+    /// May return Err Result if cannot connect to the database
+    /// to send cancel request, the future is awaited anyway.
+    pub async fn cancel<T>(
+        mut self,
+        query: impl Future<Output = RustPSQLDriverPyResult<T>>,
+    ) -> RustPSQLDriverPyResult<()> {
+        let Some(db_client) = self.db_client.take() else {
+            return Ok(());
+        };
+        let cancel_result = cancel_query(
+            db_client.cancel_token(),
+            std::mem::take(&mut self.tls_options),
+            self.ssl_mode,
+        )
+        .await;
+
+        // Timer needs tokio runtime, query future is polled by the event loop.
+        let timer = tokio_runtime().spawn(tokio::time::sleep(CANCELLED_QUERY_TIMEOUT));
+        pin_mut!(query);
+        match future::select(query, timer).await {
+            Either::Left((_, timer)) => timer.abort(),
+            Either::Right(_) => {
+                tokio_runtime().spawn(hold_until_finished(db_client));
+            }
+        }
+        cancel_result
+    }
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        let Some(db_client) = self.db_client.take() else {
+            return;
+        };
+        tokio_runtime().spawn(cancel_dropped_query(
+            db_client,
+            std::mem::take(&mut self.tls_options),
+            self.ssl_mode,
+        ));
+    }
+}
+
+/// Run the query, it's cancelled on the server if it's dropped
+/// before the end or doesn't finish in `timeout` seconds.
+///
+/// After timeout the cancelled query is awaited, so the connection
+/// isn't used for the next query while the old one is running.
+/// Timeout error is returned even if cancel request fails.
+///
+/// # Errors
+/// May return Err Result if:
+/// 1) Timeout is negative
+/// 2) Query doesn't finish in time
+/// 3) Query returns error
+pub async fn run_cancellable<T>(
+    query: impl Future<Output = RustPSQLDriverPyResult<T>>,
+    cancel_guard: CancelOnDrop,
+    timeout: Option<f64>,
+) -> RustPSQLDriverPyResult<T> {
+    let Some(timeout) = timeout else {
+        let result = query.await;
+        cancel_guard.disarm();
+        return result;
+    };
+    let timeout_duration = Duration::try_from_secs_f64(timeout).map_err(|_| {
+        RustPSQLDriverError::PyToRustValueConversionError(format!(
+            "Timeout must be non-negative number of seconds, got {timeout}"
+        ))
+    })?;
+
+    // Timer needs tokio runtime, query future is polled by the event loop.
+    let timer = tokio_runtime().spawn(tokio::time::sleep(timeout_duration));
+    pin_mut!(query);
+    match future::select(query, timer).await {
+        Either::Left((result, timer)) => {
+            timer.abort();
+            cancel_guard.disarm();
+            result
+        }
+        Either::Right((_, query)) => match cancel_guard.cancel(query).await {
+            Ok(()) => Err(RustPSQLDriverError::QueryTimeoutError(format!(
+                "query didn't finish in {timeout} seconds and was cancelled"
+            ))),
+            Err(err) => Err(RustPSQLDriverError::QueryTimeoutError(format!(
+                "query didn't finish in {timeout} seconds, cancel request failed: {err}"
+            ))),
+        },
+    }
+}
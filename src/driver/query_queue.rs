@@ -0,0 +1,116 @@
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use tokio::sync::Semaphore;
+
+use crate::exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult};
+
+struct QueueEntry {
+    semaphore: Semaphore,
+    max_concurrent_queries: usize,
+    queued: AtomicUsize,
+    running: AtomicUsize,
+}
+
+/// Limit of the statements executed by the pool connections at once.
+///
+/// Excess statements wait in the queue in the order they came,
+/// the semaphore of tokio is fair.
+/// Default queue doesn't limit anything.
+#[derive(Clone, Default)]
+pub struct QueryQueue {
+    entry: Option<Arc<QueueEntry>>,
+}
+
+/// Decrease the counter when the statement leaves the queue or finishes,
+/// even if its future is dropped.
+struct CounterGuard<'a>(&'a AtomicUsize);
+
+impl<'a> CounterGuard<'a> {
+    fn new(counter: &'a AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::Relaxed);
+        CounterGuard(counter)
+    }
+}
+
+impl Drop for CounterGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl QueryQueue {
+    /// Create queue with the limit of concurrent statements.
+    ///
+    /// # Errors
+    /// May return Err Result if the limit is zero.
+    pub fn new(max_concurrent_queries: Option<usize>) -> RustPSQLDriverPyResult<Self> {
+        let Some(max_concurrent_queries) = max_concurrent_queries else {
+            return Ok(QueryQueue::default());
+        };
+        if max_concurrent_queries == 0 {
+            return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                "max_concurrent_queries must be more than 0".into(),
+            ));
+        }
+
+        Ok(QueryQueue {
+            entry: Some(Arc::new(QueueEntry {
+                semaphore: Semaphore::new(max_concurrent_queries),
+                max_concurrent_queries,
+                queued: AtomicUsize::new(0),
+                running: AtomicUsize::new(0),
+            })),
+        })
+    }
+
+    /// Execute statement when there is a free slot.
+    ///
+    /// # Errors
+    /// May return Err Result if the statement fails.
+    pub async fn run<T, F>(&self, statement: F) -> RustPSQLDriverPyResult<T>
+    where
+        F: Future<Output = RustPSQLDriverPyResult<T>>,
+    {
+        let Some(entry) = &self.entry else {
+            return statement.await;
+        };
+
+        let queued = CounterGuard::new(&entry.queued);
+        // Semaphore is never closed.
+        let _permit = entry.semaphore.acquire().await.map_err(|_| {
+            RustPSQLDriverError::ConnectionExecuteError("Query queue is closed".into())
+        })?;
+        drop(queued);
+
+        let _running = CounterGuard::new(&entry.running);
+        statement.await
+    }
+
+    /// Return current state of the queue.
+    #[must_use]
+    pub fn snapshot(&self) -> QueryQueueStats {
+        let Some(entry) = &self.entry else {
+            return QueryQueueStats::default();
+        };
+
+        QueryQueueStats {
+            max_concurrent_queries: Some(entry.max_concurrent_queries),
+            queued: entry.queued.load(Ordering::Relaxed),
+            running: entry.running.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Snapshot of the query queue.
+#[derive(Clone, Copy, Default)]
+pub struct QueryQueueStats {
+    pub max_concurrent_queries: Option<usize>,
+    pub queued: usize,
+    pub running: usize,
+}
@@ -0,0 +1,384 @@
+use std::{collections::HashMap, path::Path};
+
+use pyo3::{
+    exceptions::PyAttributeError,
+    pyclass, pymethods,
+    types::{PyAnyMethods, PyList, PyTuple, PyTupleMethods},
+    Py, PyAny, Python,
+};
+
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    runtime::rustdriver_future,
+};
+
+use super::{connection::Connection, connection_pool::ConnectionPool, transaction::Transaction};
+
+/// Attributes of `QueryRegistry` which cannot be used as query names.
+const RESERVED_NAMES: &[&str] = &["load_dir", "names"];
+
+/// Where the registered query is executed.
+enum QueryTarget {
+    Pool(Py<ConnectionPool>),
+    Connection(Py<Connection>),
+    Transaction(Py<Transaction>),
+}
+
+/// Query loaded from the SQL file and described by the database.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct RegisteredQuery {
+    name: String,
+    querystring: String,
+    source: String,
+    parameter_types: Vec<String>,
+    columns: Vec<String>,
+    pool: Py<ConnectionPool>,
+}
+
+#[pymethods]
+impl RegisteredQuery {
+    #[getter]
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    #[getter]
+    fn querystring(&self) -> String {
+        self.querystring.clone()
+    }
+
+    /// Path of the file the query is loaded from.
+    #[getter]
+    fn source(&self) -> String {
+        self.source.clone()
+    }
+
+    /// Types of `$N` parameters in order.
+    #[getter]
+    fn parameter_types(&self) -> Vec<String> {
+        self.parameter_types.clone()
+    }
+
+    /// Names of the result columns.
+    #[getter]
+    fn columns(&self) -> Vec<String> {
+        self.columns.clone()
+    }
+
+    /// Execute the query with positional parameters.
+    ///
+    /// Query is executed on `connection` if it's passed,
+    /// it must be `Connection` or `Transaction`.
+    /// Otherwise connection is taken from the pool.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Number of parameters doesn't match the query
+    /// 2) `connection` has wrong type
+    /// 3) Cannot execute the query
+    #[pyo3(signature = (*parameters, connection=None))]
+    fn __call__(
+        &self,
+        py: Python<'_>,
+        parameters: Py<PyTuple>,
+        connection: Option<Py<PyAny>>,
+    ) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        let parameters_count = parameters.bind(py).len();
+        if parameters_count != self.parameter_types.len() {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "Query {} expects {} parameters, {parameters_count} passed",
+                self.name,
+                self.parameter_types.len(),
+            )));
+        }
+        let parameters: Py<PyAny> = PyList::new(py, parameters.bind(py).iter())?
+            .into_any()
+            .unbind();
+
+        let target = match connection {
+            None => QueryTarget::Pool(self.pool.clone_ref(py)),
+            Some(connection) => {
+                let connection = connection.bind(py);
+                if let Ok(connection) = connection.extract::<Py<Connection>>() {
+                    QueryTarget::Connection(connection)
+                } else if let Ok(transaction) = connection.extract::<Py<Transaction>>() {
+                    QueryTarget::Transaction(transaction)
+                } else {
+                    return Err(RustPSQLDriverError::PyToRustValueConversionError(
+                        "connection must be Connection or Transaction".into(),
+                    ));
+                }
+            }
+        };
+        let querystring = self.querystring.clone();
+
+        rustdriver_future(py, async move {
+            let connection = match target {
+                QueryTarget::Transaction(transaction) => {
+                    return Transaction::execute(
+                        transaction,
+                        querystring,
+                        Some(parameters),
+                        None,
+                        None,
+                        None,
+                        None,
+                    )
+                    .await;
+                }
+                QueryTarget::Connection(connection) => connection,
+                QueryTarget::Pool(pool) => {
+                    let connection = ConnectionPool::connection(pool, None).await?;
+                    Python::with_gil(|gil| Py::new(gil, connection))?
+                }
+            };
+            Connection::execute(
+                connection,
+                querystring,
+                Some(parameters),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RegisteredQuery(name={:?}, source={:?}, parameters={})",
+            self.name,
+            self.source,
+            self.parameter_types.len(),
+        )
+    }
+}
+
+/// Named queries loaded from SQL files.
+///
+/// Every query is described by the database when it's loaded,
+/// so invalid queries are found at startup.
+/// Loaded queries are available as attributes.
+#[pyclass]
+pub struct QueryRegistry {
+    pool: Py<ConnectionPool>,
+    queries: HashMap<String, RegisteredQuery>,
+}
+
+#[pymethods]
+impl QueryRegistry {
+    #[new]
+    fn new(pool: Py<ConnectionPool>) -> Self {
+        QueryRegistry {
+            pool,
+            queries: HashMap::new(),
+        }
+    }
+
+    /// Names of the loaded queries.
+    #[getter]
+    fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.queries.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Load named queries from `.sql` files of the directory.
+    ///
+    /// Returns names of the loaded queries.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Cannot read the directory or the files
+    /// 2) Query name is invalid or isn't unique
+    /// 3) Query cannot be described by the database
+    pub async fn load_dir(self_: Py<Self>, path: String) -> RustPSQLDriverPyResult<Vec<String>> {
+        let parsed_queries = read_sql_dir(&path).await?;
+
+        let (pool, existing_names) = Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (
+                self_.pool.clone_ref(gil),
+                self_.queries.keys().cloned().collect::<Vec<_>>(),
+            )
+        });
+        for (name, _, source) in &parsed_queries {
+            if existing_names.contains(name) {
+                return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                    format!("Query {name} from {source} is already loaded"),
+                ));
+            }
+        }
+
+        let connection =
+            ConnectionPool::connection(Python::with_gil(|gil| pool.clone_ref(gil)), None).await?;
+        let Some(db_client) = connection.db_client() else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+
+        let mut loaded_queries = Vec::with_capacity(parsed_queries.len());
+        for (name, querystring, source) in parsed_queries {
+            let statement = db_client
+                .prepare_cached(&querystring)
+                .await
+                .map_err(|err| {
+                    RustPSQLDriverError::execute_error(
+                        &format!("Cannot describe query {name} from {source}"),
+                        err,
+                    )
+                })?;
+            loaded_queries.push(RegisteredQuery {
+                parameter_types: statement
+                    .params()
+                    .iter()
+                    .map(|param| param.name().to_string())
+                    .collect(),
+                columns: statement
+                    .columns()
+                    .iter()
+                    .map(|column| column.name().to_string())
+                    .collect(),
+                name,
+                querystring,
+                source,
+                pool: Python::with_gil(|gil| pool.clone_ref(gil)),
+            });
+        }
+
+        Python::with_gil(|gil| {
+            let mut self_ = self_.borrow_mut(gil);
+            Ok(loaded_queries
+                .into_iter()
+                .map(|query| {
+                    let name = query.name.clone();
+                    self_.queries.insert(name.clone(), query);
+                    name
+                })
+                .collect())
+        })
+    }
+
+    fn __getattr__(&self, name: &str) -> RustPSQLDriverPyResult<RegisteredQuery> {
+        self.queries.get(name).cloned().ok_or_else(|| {
+            RustPSQLDriverError::RustPyError(PyAttributeError::new_err(format!(
+                "Query {name} isn't loaded"
+            )))
+        })
+    }
+
+    fn __contains__(&self, name: &str) -> bool {
+        self.queries.contains_key(name)
+    }
+
+    fn __len__(&self) -> usize {
+        self.queries.len()
+    }
+}
+
+/// Read named queries of `.sql` files in the directory, files are read in name order.
+///
+/// Returns name, querystring and path of the file of every query.
+async fn read_sql_dir(path: &str) -> RustPSQLDriverPyResult<Vec<(String, String, String)>> {
+    let read_error = |err: std::io::Error| {
+        RustPSQLDriverError::ConnectionPoolConfigurationError(format!(
+            "Cannot read queries from {path}, error - {err}"
+        ))
+    };
+
+    let mut sql_files = vec![];
+    let mut entries = tokio::fs::read_dir(path).await.map_err(read_error)?;
+    while let Some(entry) = entries.next_entry().await.map_err(read_error)? {
+        let file_path = entry.path();
+        if file_path
+            .extension()
+            .is_some_and(|extension| extension == "sql")
+        {
+            sql_files.push(file_path);
+        }
+    }
+    sql_files.sort();
+
+    let mut queries: Vec<(String, String, String)> = vec![];
+    for file_path in sql_files {
+        let contents = tokio::fs::read_to_string(&file_path)
+            .await
+            .map_err(read_error)?;
+        let source = file_path.display().to_string();
+        for (name, querystring) in parse_sql_file(&file_path, &contents)? {
+            if queries
+                .iter()
+                .any(|(loaded_name, _, _)| *loaded_name == name)
+            {
+                return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                    format!("Query {name} from {source} is defined more than once"),
+                ));
+            }
+            queries.push((name, querystring, source.clone()));
+        }
+    }
+    Ok(queries)
+}
+
+/// Split SQL file into named queries.
+///
+/// Every query starts with `-- name: query_name` line.
+/// File without such lines is one query named by the file name.
+fn parse_sql_file(
+    file_path: &Path,
+    contents: &str,
+) -> RustPSQLDriverPyResult<Vec<(String, String)>> {
+    let mut queries: Vec<(String, Vec<&str>)> = vec![];
+    for line in contents.lines() {
+        let query_name = line
+            .trim()
+            .strip_prefix("--")
+            .and_then(|comment| comment.trim_start().strip_prefix("name:"))
+            .map(str::trim);
+        match (query_name, queries.last_mut()) {
+            (Some(query_name), _) => queries.push((query_name.into(), vec![])),
+            (None, Some((_, query_lines))) => query_lines.push(line),
+            (None, None) => {}
+        }
+    }
+    if queries.is_empty() {
+        let file_stem = file_path
+            .file_stem()
+            .map(|file_stem| file_stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        queries.push((file_stem, contents.lines().collect()));
+    }
+
+    queries
+        .into_iter()
+        .map(|(name, query_lines)| {
+            let is_identifier = name
+                .chars()
+                .next()
+                .is_some_and(|first| first.is_alphabetic() || first == '_')
+                && name
+                    .chars()
+                    .all(|character| character.is_alphanumeric() || character == '_');
+            if !is_identifier || RESERVED_NAMES.contains(&name.as_str()) {
+                return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                    format!("Invalid query name {name:?} in {}", file_path.display()),
+                ));
+            }
+
+            let querystring = query_lines
+                .join("\n")
+                .trim()
+                .trim_end_matches(';')
+                .trim_end()
+                .to_string();
+            if querystring.is_empty() {
+                return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                    format!("Query {name} in {} is empty", file_path.display()),
+                ));
+            }
+            Ok((name, querystring))
+        })
+        .collect()
+}
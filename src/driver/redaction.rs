@@ -0,0 +1,239 @@
+use pyo3::{
+    pyclass, pymethods,
+    types::{PyAnyMethods, PyDict, PyDictMethods, PyList, PyType},
+    Bound, IntoPy, Py, PyAny, Python,
+};
+
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    format_helpers::{guess_known_parameter_names, matches_name_pattern},
+};
+
+const DEFAULT_PLACEHOLDER: &str = "<redacted>";
+
+/// Policy which hides values of the sensitive parameters.
+///
+/// Parameter is sensitive if its `$N` number is listed,
+/// its name matches one of the column patterns
+/// or its value is an instance of one of the types.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct RedactionPolicy {
+    parameter_numbers: Vec<usize>,
+    column_patterns: Vec<String>,
+    types: Vec<Py<PyType>>,
+    placeholder: String,
+}
+
+impl RedactionPolicy {
+    fn is_sensitive(
+        &self,
+        number: Option<usize>,
+        name: Option<&str>,
+        value: &Bound<'_, PyAny>,
+    ) -> bool {
+        if number.is_some_and(|number| self.parameter_numbers.contains(&number)) {
+            return true;
+        }
+        if let Some(name) = name {
+            if self
+                .column_patterns
+                .iter()
+                .any(|pattern| matches_name_pattern(pattern, name))
+            {
+                return true;
+            }
+        }
+        self.types.iter().any(|type_| {
+            value
+                .is_instance(type_.bind(value.py()).as_any())
+                .unwrap_or(false)
+        })
+    }
+
+    /// Return string forms of the sensitive parameters.
+    fn sensitive_strings(
+        &self,
+        py: Python<'_>,
+        querystring: &str,
+        parameters: &Py<PyAny>,
+    ) -> Vec<String> {
+        let Ok(parameters) = parameters.extract::<Vec<Py<PyAny>>>(py) else {
+            return vec![];
+        };
+        let names = guess_known_parameter_names(querystring, parameters.len()).unwrap_or_default();
+
+        let mut sensitive_strings = vec![];
+        for (idx, parameter) in parameters.iter().enumerate() {
+            let parameter = parameter.bind(py);
+            let name = names.get(idx).and_then(Option::as_deref);
+            if !self.is_sensitive(Some(idx + 1), name, parameter) {
+                continue;
+            }
+            for string in [parameter.str(), parameter.repr()].into_iter().flatten() {
+                let string = string.to_string();
+                if !string.is_empty() && !sensitive_strings.contains(&string) {
+                    sensitive_strings.push(string);
+                }
+            }
+        }
+        // Longer strings first, so repr isn't left half-redacted.
+        sensitive_strings.sort_by_key(|string| std::cmp::Reverse(string.len()));
+        sensitive_strings
+    }
+
+    fn redact_message(&self, message: String, sensitive_strings: &[String]) -> String {
+        sensitive_strings
+            .iter()
+            .fold(message, |message, sensitive_string| {
+                message.replace(sensitive_string, &self.placeholder)
+            })
+    }
+
+    /// Return function which hides values of the sensitive parameters
+    /// in the error message.
+    ///
+    /// Error of the database driver is turned into `ConnectionExecuteError`
+    /// if its message has sensitive values.
+    /// Nothing is copied if there is no policy.
+    pub fn error_redactor(
+        policy: Option<&RedactionPolicy>,
+        querystring: &str,
+        parameters: Option<&Py<PyAny>>,
+    ) -> impl Fn(RustPSQLDriverError) -> RustPSQLDriverError {
+        let context = match (policy, parameters) {
+            (Some(policy), Some(parameters)) => Some((
+                policy.clone(),
+                querystring.to_string(),
+                Python::with_gil(|gil| parameters.clone_ref(gil)),
+            )),
+            _ => None,
+        };
+        move |err| {
+            let Some((policy, querystring, parameters)) = &context else {
+                return err;
+            };
+            let sensitive_strings =
+                Python::with_gil(|gil| policy.sensitive_strings(gil, querystring, parameters));
+            if sensitive_strings.is_empty() {
+                return err;
+            }
+            match err {
+                RustPSQLDriverError::PyToRustValueConversionError(message) => {
+                    RustPSQLDriverError::PyToRustValueConversionError(
+                        policy.redact_message(message, &sensitive_strings),
+                    )
+                }
+                RustPSQLDriverError::ConnectionExecuteError(message) => {
+                    RustPSQLDriverError::ConnectionExecuteError(
+                        policy.redact_message(message, &sensitive_strings),
+                    )
+                }
+                RustPSQLDriverError::RustDriverError(err) => {
+                    let message = err.to_string();
+                    let redacted_message =
+                        policy.redact_message(message.clone(), &sensitive_strings);
+                    if redacted_message == message {
+                        return RustPSQLDriverError::RustDriverError(err);
+                    }
+                    RustPSQLDriverError::ConnectionExecuteError(redacted_message)
+                }
+                err => err,
+            }
+        }
+    }
+}
+
+#[pymethods]
+impl RedactionPolicy {
+    /// Create new policy.
+    ///
+    /// `parameter_numbers` are `$N` numbers starting from 1,
+    /// `column_patterns` are case-insensitive names with `*` wildcards.
+    #[new]
+    #[pyo3(signature = (
+        parameter_numbers=None,
+        column_patterns=None,
+        types=None,
+        placeholder=None,
+    ))]
+    fn new(
+        parameter_numbers: Option<Vec<usize>>,
+        column_patterns: Option<Vec<String>>,
+        types: Option<Vec<Py<PyType>>>,
+        placeholder: Option<String>,
+    ) -> Self {
+        RedactionPolicy {
+            parameter_numbers: parameter_numbers.unwrap_or_default(),
+            column_patterns: column_patterns.unwrap_or_default(),
+            types: types.unwrap_or_default(),
+            placeholder: placeholder.unwrap_or_else(|| DEFAULT_PLACEHOLDER.into()),
+        }
+    }
+
+    /// Return copy of the parameters with the sensitive values
+    /// replaced by the placeholder.
+    ///
+    /// Names of the parameters in the sequence are guessed
+    /// from the querystring if `parameter_names` aren't passed.
+    /// Keys of the dict are used as names.
+    ///
+    /// # Errors
+    /// May return Err Result if parameters aren't a sequence or a dict.
+    #[pyo3(signature = (parameters, querystring=None, parameter_names=None))]
+    pub fn redact(
+        &self,
+        py: Python<'_>,
+        parameters: Py<PyAny>,
+        querystring: Option<String>,
+        parameter_names: Option<Vec<String>>,
+    ) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        let parameters = parameters.bind(py);
+        if let Ok(parameters) = parameters.downcast::<PyDict>() {
+            let redacted = PyDict::new(py);
+            for (name, value) in parameters.iter() {
+                let name_str = name.extract::<String>().ok();
+                if self.is_sensitive(None, name_str.as_deref(), &value) {
+                    redacted.set_item(name, &self.placeholder)?;
+                } else {
+                    redacted.set_item(name, value)?;
+                }
+            }
+            return Ok(redacted.into_any().unbind());
+        }
+
+        let values = parameters.extract::<Vec<Py<PyAny>>>().map_err(|_| {
+            RustPSQLDriverError::PyToRustValueConversionError(
+                "Parameters must be a sequence or a dict".into(),
+            )
+        })?;
+        let names = match (parameter_names, querystring) {
+            (Some(parameter_names), _) => parameter_names.into_iter().map(Some).collect(),
+            (None, Some(querystring)) => {
+                guess_known_parameter_names(&querystring, values.len()).unwrap_or_default()
+            }
+            (None, None) => vec![],
+        };
+
+        let mut redacted = Vec::with_capacity(values.len());
+        for (idx, value) in values.into_iter().enumerate() {
+            let name = names.get(idx).and_then(Option::as_deref);
+            if self.is_sensitive(Some(idx + 1), name, value.bind(py)) {
+                redacted.push(self.placeholder.clone().into_py(py));
+            } else {
+                redacted.push(value);
+            }
+        }
+        Ok(PyList::new(py, redacted)?.into_any().unbind())
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RedactionPolicy(parameter_numbers={:?}, column_patterns={:?}, types={}, placeholder={:?})",
+            self.parameter_numbers,
+            self.column_patterns,
+            self.types.len(),
+            self.placeholder,
+        )
+    }
+}
@@ -0,0 +1,400 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use deadpool_postgres::{ManagerConfig, Pool, RecyclingMethod};
+use futures::future::join_all;
+use pyo3::{pyclass, pymethods, Py, PyAny, Python};
+use tokio::task::JoinHandle;
+
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    query_result::PSQLDriverPyQueryResult,
+    runtime::tokio_runtime,
+};
+
+use super::{
+    common_options::{ConnRecyclingMethod, SslMode},
+    connection::Connection,
+    connection_pool::ConnectionPool,
+    utils::{build_connection_config, build_manager, build_tls, TlsOptions},
+};
+
+const DEFAULT_LAG_CHECK_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// Replication lag of the replicas in bytes of WAL, None if it's unknown.
+type ReplicaLags = Arc<Mutex<Vec<Option<u64>>>>;
+
+/// Pool which routes read-only connections to the replicas.
+///
+/// Replication lag of every replica is measured periodically,
+/// replicas which lag beyond the budget are excluded from the routing.
+#[pyclass]
+pub struct ReplicaPool {
+    primary: Py<ConnectionPool>,
+    replicas: Vec<Py<ConnectionPool>>,
+    max_replica_lag: Option<u64>,
+    replica_lags: ReplicaLags,
+    next_replica: AtomicUsize,
+    lag_monitor: Option<JoinHandle<()>>,
+}
+
+impl ReplicaPool {
+    /// Choose replica which lag fits into the budget.
+    ///
+    /// Replicas are chosen in round-robin order,
+    /// replicas with unknown lag are skipped.
+    fn choose_replica(
+        &self,
+        gil: Python<'_>,
+        lag_tolerance: Option<u64>,
+    ) -> Option<Py<ConnectionPool>> {
+        let lag_budget = match (self.max_replica_lag, lag_tolerance) {
+            (Some(max_replica_lag), Some(lag_tolerance)) => {
+                Some(max_replica_lag.min(lag_tolerance))
+            }
+            (max_replica_lag, lag_tolerance) => max_replica_lag.or(lag_tolerance),
+        };
+        let replica_lags = self.replica_lags.lock().ok()?.clone();
+
+        let start_index = self.next_replica.fetch_add(1, Ordering::Relaxed);
+        (0..self.replicas.len())
+            .map(|offset| (start_index + offset) % self.replicas.len())
+            .find(|index| {
+                let Some(replica_lag) = replica_lags.get(*index).copied().flatten() else {
+                    return false;
+                };
+                lag_budget.is_none_or(|lag_budget| replica_lag <= lag_budget)
+            })
+            .map(|index| self.replicas[index].clone_ref(gil))
+    }
+
+    /// Choose pool for the connection.
+    fn route(
+        &self,
+        gil: Python<'_>,
+        read_only: bool,
+        lag_tolerance: Option<u64>,
+    ) -> Py<ConnectionPool> {
+        if read_only {
+            if let Some(replica) = self.choose_replica(gil, lag_tolerance) {
+                return replica;
+            }
+        }
+        self.primary.clone_ref(gil)
+    }
+}
+
+/// Build connection pool for one node.
+///
+/// # Errors
+/// May return Err Result if cannot build config or pool.
+fn build_node_pool(
+    dsn: String,
+    max_db_pool_size: Option<usize>,
+    conn_recycling_method: Option<ConnRecyclingMethod>,
+    ssl_mode: Option<SslMode>,
+    tls_options: TlsOptions,
+) -> RustPSQLDriverPyResult<ConnectionPool> {
+    let pg_config = build_connection_config(
+        Some(dsn),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        ssl_mode,
+    )?;
+
+    let mgr_config = ManagerConfig {
+        recycling_method: conn_recycling_method
+            .map_or(RecyclingMethod::Fast, |method| method.to_internal()),
+    };
+    let mgr = build_manager(
+        mgr_config,
+        pg_config.clone(),
+        build_tls(&tls_options, &ssl_mode)?,
+        None,
+        None,
+        None,
+        false,
+    );
+
+    let mut db_pool_builder = Pool::builder(mgr);
+    if let Some(max_db_pool_size) = max_db_pool_size {
+        db_pool_builder = db_pool_builder.max_size(max_db_pool_size);
+    }
+
+    Ok(ConnectionPool::build(
+        db_pool_builder.build()?,
+        pg_config,
+        tls_options,
+        ssl_mode,
+    ))
+}
+
+/// Return current WAL position of the primary.
+async fn primary_wal_lsn(primary: &Pool) -> Option<String> {
+    let client = primary.get().await.ok()?;
+    let row = client
+        .query_one("SELECT pg_current_wal_lsn()::text", &[])
+        .await
+        .ok()?;
+    row.try_get::<_, String>(0).ok()
+}
+
+/// Return how many bytes of WAL replica has to replay to reach the primary position.
+///
+/// Returns None if replica is unavailable or isn't in recovery.
+async fn replica_lag(replica: &Pool, primary_lsn: &str) -> Option<u64> {
+    let client = replica.get().await.ok()?;
+    let row = client
+        .query_one(
+            "SELECT pg_wal_lsn_diff($1::text::pg_lsn, pg_last_wal_replay_lsn())::bigint",
+            &[&primary_lsn],
+        )
+        .await
+        .ok()?;
+    let replica_lag = row.try_get::<_, Option<i64>>(0).ok()??;
+    // Replica can replay WAL written after the primary position was taken.
+    Some(u64::try_from(replica_lag).unwrap_or(0))
+}
+
+/// Measure replication lag of the replicas periodically.
+async fn monitor_replica_lags(
+    primary: Pool,
+    replicas: Vec<Pool>,
+    replica_lags: ReplicaLags,
+    lag_check_interval: Duration,
+) {
+    loop {
+        let measured_lags = match primary_wal_lsn(&primary).await {
+            Some(primary_lsn) => {
+                join_all(
+                    replicas
+                        .iter()
+                        .map(|replica| replica_lag(replica, &primary_lsn)),
+                )
+                .await
+            }
+            None => vec![None; replicas.len()],
+        };
+        if let Ok(mut replica_lags) = replica_lags.lock() {
+            *replica_lags = measured_lags;
+        }
+
+        tokio::time::sleep(lag_check_interval).await;
+    }
+}
+
+#[pymethods]
+impl ReplicaPool {
+    /// Create new replica pool.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) There are no replicas
+    /// 2) Cannot build pool for any node
+    #[new]
+    #[pyo3(signature = (
+        primary_dsn,
+        replica_dsns,
+        max_db_pool_size=None,
+        max_replica_lag=None,
+        lag_check_interval_ms=None,
+        ssl_mode=None,
+        ca_file=None,
+        conn_recycling_method=None,
+        ssl_cert=None,
+        ssl_key=None,
+        verify_hostname=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        py: Python<'_>,
+        primary_dsn: String,
+        replica_dsns: Vec<String>,
+        max_db_pool_size: Option<usize>,
+        max_replica_lag: Option<u64>,
+        lag_check_interval_ms: Option<u64>,
+        ssl_mode: Option<SslMode>,
+        ca_file: Option<String>,
+        conn_recycling_method: Option<ConnRecyclingMethod>,
+        ssl_cert: Option<String>,
+        ssl_key: Option<String>,
+        verify_hostname: Option<bool>,
+    ) -> RustPSQLDriverPyResult<Self> {
+        if replica_dsns.is_empty() {
+            return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                "At least one replica must be specified".into(),
+            ));
+        }
+        if let Some(max_db_pool_size) = max_db_pool_size {
+            if max_db_pool_size < 2 {
+                return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                    "Maximum database pool size must be more than 1".into(),
+                ));
+            }
+        }
+
+        let tls_options = TlsOptions {
+            ca_file,
+            ssl_cert,
+            ssl_key,
+            verify_hostname,
+        };
+        let primary = build_node_pool(
+            primary_dsn,
+            max_db_pool_size,
+            conn_recycling_method,
+            ssl_mode,
+            tls_options.clone(),
+        )?;
+        let mut replicas = vec![];
+        for replica_dsn in replica_dsns {
+            replicas.push(build_node_pool(
+                replica_dsn,
+                max_db_pool_size,
+                conn_recycling_method,
+                ssl_mode,
+                tls_options.clone(),
+            )?);
+        }
+
+        let replica_lags: ReplicaLags = Arc::new(Mutex::new(vec![None; replicas.len()]));
+        let lag_monitor = tokio_runtime().spawn(monitor_replica_lags(
+            primary.db_pool(),
+            replicas.iter().map(ConnectionPool::db_pool).collect(),
+            replica_lags.clone(),
+            lag_check_interval_ms.map_or(DEFAULT_LAG_CHECK_INTERVAL, Duration::from_millis),
+        ));
+
+        let mut replica_pools = vec![];
+        for replica in replicas {
+            replica_pools.push(Py::new(py, replica)?);
+        }
+
+        Ok(ReplicaPool {
+            primary: Py::new(py, primary)?,
+            replicas: replica_pools,
+            max_replica_lag,
+            replica_lags,
+            next_replica: AtomicUsize::new(0),
+            lag_monitor: Some(lag_monitor),
+        })
+    }
+
+    /// Return new connection.
+    ///
+    /// Read-only connection is taken from the replica
+    /// which lag fits into `max_replica_lag` and `lag_tolerance`,
+    /// if there is no such replica connection is taken from the primary.
+    ///
+    /// # Errors
+    /// May return Err Result if cannot get new connection from the pool.
+    #[pyo3(signature = (read_only=false, lag_tolerance=None))]
+    pub async fn connection(
+        self_: Py<Self>,
+        read_only: bool,
+        lag_tolerance: Option<u64>,
+    ) -> RustPSQLDriverPyResult<Connection> {
+        let node_pool =
+            Python::with_gil(|gil| self_.borrow(gil).route(gil, read_only, lag_tolerance));
+
+        ConnectionPool::connection(node_pool, None).await
+    }
+
+    /// Execute querystring on the primary or on the replica.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Cannot get new connection from the pool
+    /// 2) Cannot execute querystring
+    #[pyo3(signature = (
+        querystring,
+        parameters=None,
+        prepared=None,
+        read_only=false,
+        lag_tolerance=None,
+    ))]
+    pub async fn execute(
+        self_: Py<Self>,
+        querystring: String,
+        parameters: Option<Py<PyAny>>,
+        prepared: Option<bool>,
+        read_only: bool,
+        lag_tolerance: Option<u64>,
+    ) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
+        let connection = Self::connection(self_, read_only, lag_tolerance).await?;
+        let Some(db_client) = connection.db_client() else {
+            return Err(RustPSQLDriverError::ConnectionClosedError);
+        };
+
+        db_client.execute(querystring, parameters, prepared).await
+    }
+
+    /// Return connection pool of the primary.
+    #[must_use]
+    pub fn primary(&self, py: Python<'_>) -> Py<ConnectionPool> {
+        self.primary.clone_ref(py)
+    }
+
+    /// Return connection pools of the replicas.
+    #[must_use]
+    pub fn replicas(&self, py: Python<'_>) -> Vec<Py<ConnectionPool>> {
+        self.replicas
+            .iter()
+            .map(|replica| replica.clone_ref(py))
+            .collect()
+    }
+
+    /// Return the last measured lag of the replicas in bytes.
+    ///
+    /// Lag is None if replica is unavailable or isn't in recovery.
+    #[must_use]
+    pub fn replica_lags(&self) -> Vec<Option<u64>> {
+        self.replica_lags
+            .lock()
+            .map(|replica_lags| replica_lags.clone())
+            .unwrap_or_default()
+    }
+
+    /// Stop lag monitoring and close all pools.
+    pub fn close(&mut self, py: Python<'_>) {
+        if let Some(lag_monitor) = self.lag_monitor.take() {
+            lag_monitor.abort();
+        }
+        self.primary.borrow(py).close();
+        for replica in &self.replicas {
+            replica.borrow(py).close();
+        }
+    }
+}
+
+impl Drop for ReplicaPool {
+    fn drop(&mut self) {
+        if let Some(lag_monitor) = self.lag_monitor.take() {
+            lag_monitor.abort();
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use std::{
+    collections::HashMap,
+    io,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+use futures::{stream::FuturesUnordered, StreamExt};
+use pyo3::{Py, PyAny, Python};
+use tokio::net::TcpStream;
+use tokio_postgres::{config::Host, Config};
+
+/// Delay between staggered connection attempts, recommended by RFC 8305.
+pub const DEFAULT_HAPPY_EYEBALLS_DELAY: Duration = Duration::from_millis(250);
+const DEFAULT_PORT: u16 = 5432;
+
+/// Custom host resolution for the new connections.
+///
+/// Addresses are taken from pre-resolved addresses,
+/// custom python resolver or system resolver, in this order.
+/// Connection attempts to the resolved addresses are staggered
+/// and interleave IPv6 and IPv4 addresses (happy eyeballs).
+#[derive(Clone)]
+pub struct HostResolver {
+    addresses: HashMap<String, Vec<IpAddr>>,
+    resolver: Option<Arc<Py<PyAny>>>,
+    happy_eyeballs_delay: Duration,
+}
+
+impl Default for HostResolver {
+    fn default() -> Self {
+        HostResolver {
+            addresses: HashMap::new(),
+            resolver: None,
+            happy_eyeballs_delay: DEFAULT_HAPPY_EYEBALLS_DELAY,
+        }
+    }
+}
+
+impl HostResolver {
+    pub fn set_resolver(&mut self, resolver: Py<PyAny>) {
+        self.resolver = Some(Arc::new(resolver));
+    }
+
+    pub fn add_addresses(&mut self, host: String, addresses: Vec<IpAddr>) {
+        self.addresses.entry(host).or_default().extend(addresses);
+    }
+
+    pub fn set_happy_eyeballs_delay(&mut self, happy_eyeballs_delay: Duration) {
+        self.happy_eyeballs_delay = happy_eyeballs_delay;
+    }
+
+    /// Resolve host into socket addresses.
+    ///
+    /// # Errors
+    /// May return Err if:
+    /// 1) Python resolver raised an exception or returned invalid addresses
+    /// 2) System resolver failed
+    pub async fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+        if let Some(addresses) = self.addresses.get(host) {
+            return Ok(addresses
+                .iter()
+                .map(|address| SocketAddr::new(*address, port))
+                .collect());
+        }
+
+        if let Some(resolver) = self.resolver.clone() {
+            let host = host.to_string();
+            let addresses = tokio::task::spawn_blocking(move || {
+                Python::with_gil(|gil| {
+                    resolver
+                        .call1(gil, (host, port))
+                        .and_then(|addresses| addresses.extract::<Vec<IpAddr>>(gil))
+                        .map_err(|err| io::Error::other(format!("Custom resolver failed: {err}")))
+                })
+            })
+            .await
+            .map_err(io::Error::other)??;
+
+            return Ok(addresses
+                .into_iter()
+                .map(|address| SocketAddr::new(address, port))
+                .collect());
+        }
+
+        Ok(tokio::net::lookup_host((host, port)).await?.collect())
+    }
+
+    /// Open TCP stream to the first available host from the config,
+    /// hosts before `first_host` are skipped.
+    ///
+    /// Returns stream, name of the host for TLS and index of the host,
+    /// or None if stream cannot be opened to any TCP host.
+    pub async fn connect_stream(
+        &self,
+        pg_config: &Config,
+        first_host: usize,
+    ) -> Option<(TcpStream, String, usize)> {
+        let ports = pg_config.get_ports();
+
+        for (index, host) in pg_config.get_hosts().iter().enumerate().skip(first_host) {
+            let Host::Tcp(host) = host else {
+                continue;
+            };
+            let port = match ports.len() {
+                0 => DEFAULT_PORT,
+                1 => ports[0],
+                _ => ports.get(index).copied().unwrap_or(DEFAULT_PORT),
+            };
+
+            let Ok(addresses) = self.resolve(host, port).await else {
+                continue;
+            };
+
+            let connect_future =
+                happy_eyeballs_connect(interleave_addresses(addresses), self.happy_eyeballs_delay);
+            let stream = match pg_config.get_connect_timeout() {
+                Some(connect_timeout) => tokio::time::timeout(*connect_timeout, connect_future)
+                    .await
+                    .unwrap_or_else(|_| Err(io::ErrorKind::TimedOut.into())),
+                None => connect_future.await,
+            };
+
+            if let Ok(stream) = stream {
+                let _ = stream.set_nodelay(true);
+                return Some((stream, host.clone(), index));
+            }
+        }
+
+        None
+    }
+}
+
+/// Order addresses by alternating IPv6 and IPv4 families, IPv6 goes first.
+fn interleave_addresses(addresses: Vec<SocketAddr>) -> Vec<SocketAddr> {
+    let (ipv6_addresses, ipv4_addresses): (Vec<SocketAddr>, Vec<SocketAddr>) =
+        addresses.into_iter().partition(SocketAddr::is_ipv6);
+
+    let mut ipv6_addresses = ipv6_addresses.into_iter();
+    let mut ipv4_addresses = ipv4_addresses.into_iter();
+    let mut ordered_addresses = vec![];
+    loop {
+        match (ipv6_addresses.next(), ipv4_addresses.next()) {
+            (None, None) => return ordered_addresses,
+            (ipv6_address, ipv4_address) => {
+                ordered_addresses.extend(ipv6_address);
+                ordered_addresses.extend(ipv4_address);
+            }
+        }
+    }
+}
+
+/// Connect to the addresses with staggered attempts.
+///
+/// Next attempt starts when previous one failed or after the delay,
+/// first established stream wins.
+async fn happy_eyeballs_connect(
+    addresses: Vec<SocketAddr>,
+    delay: Duration,
+) -> io::Result<TcpStream> {
+    let mut addresses = addresses.into_iter();
+    let mut attempts = FuturesUnordered::new();
+    let mut last_error = None;
+
+    loop {
+        if let Some(address) = addresses.next() {
+            attempts.push(TcpStream::connect(address));
+        }
+        if attempts.is_empty() {
+            return Err(last_error.unwrap_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, "No addresses to connect")
+            }));
+        }
+
+        let has_more_addresses = !addresses.as_slice().is_empty();
+        tokio::select! {
+            Some(result) = attempts.next() => match result {
+                Ok(stream) => return Ok(stream),
+                Err(err) => last_error = Some(err),
+            },
+            () = tokio::time::sleep(delay), if has_more_addresses => {},
+        }
+    }
+}
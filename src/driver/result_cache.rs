@@ -0,0 +1,161 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use pyo3::{types::PyAnyMethods, Py, PyAny, Python};
+
+use crate::{
+    exceptions::rust_errors::RustPSQLDriverPyResult, query_result::PSQLDriverPyQueryResult,
+    value_converter::convert_parameters_with_casts,
+};
+
+/// Maximum number of cached results, the least recently used one is evicted after it.
+const MAX_CACHED_RESULTS: usize = 1000;
+
+struct CachedResult {
+    querystring: String,
+    validity_token: Py<PyAny>,
+    result: PSQLDriverPyQueryResult,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<String, CachedResult>,
+    clock: u64,
+}
+
+impl CacheState {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    /// Remove the least recently used result.
+    fn evict_one(&mut self) {
+        let cache_key = self
+            .entries
+            .iter()
+            .min_by_key(|(_, cached)| cached.last_used)
+            .map(|(cache_key, _)| cache_key.clone());
+        if let Some(cache_key) = cache_key {
+            self.entries.remove(&cache_key);
+        }
+    }
+}
+
+/// Client-side cache of the query results with conditional revalidation.
+///
+/// Every result is stored with the validity token, the result of the cheap
+/// probe query (e.g. `SELECT max(updated_at) FROM users`).
+/// Cached result is served only while the probe returns the same token.
+/// Every hit returns new result object, so closing it doesn't affect the cache.
+#[derive(Clone, Default)]
+pub struct ResultCache {
+    state: Arc<Mutex<CacheState>>,
+}
+
+impl ResultCache {
+    /// Build cache key from the probe, the querystring and the parameters.
+    ///
+    /// Parameters are converted like for the execution,
+    /// so the key depends on their values and types, not on `repr`.
+    ///
+    /// # Errors
+    /// May return Err Result if parameters cannot be converted.
+    pub fn cache_key(
+        gil: Python<'_>,
+        validity_probe: &str,
+        querystring: &str,
+        parameters: Option<&Py<PyAny>>,
+    ) -> RustPSQLDriverPyResult<String> {
+        let (querystring, parameters) = match parameters {
+            Some(parameters) => {
+                convert_parameters_with_casts(querystring.to_string(), parameters.clone_ref(gil))?
+            }
+            None => (querystring.to_string(), vec![]),
+        };
+        Ok(format!("{validity_probe}\0{querystring}\0{parameters:?}"))
+    }
+
+    /// Return copy of the cached result if its token is equal to the current one.
+    ///
+    /// # Errors
+    /// May return Err Result if tokens cannot be compared.
+    pub fn get(
+        &self,
+        gil: Python<'_>,
+        cache_key: &str,
+        validity_token: &Py<PyAny>,
+    ) -> RustPSQLDriverPyResult<Option<PSQLDriverPyQueryResult>> {
+        // Comparison may run Python code, it must not be done with the lock.
+        let cached_token = match self.state.lock() {
+            Ok(state) => state
+                .entries
+                .get(cache_key)
+                .map(|cached_result| cached_result.validity_token.clone_ref(gil)),
+            Err(_) => None,
+        };
+        let Some(cached_token) = cached_token else {
+            return Ok(None);
+        };
+        if !cached_token.bind(gil).eq(validity_token.bind(gil))? {
+            return Ok(None);
+        }
+
+        let Ok(mut state) = self.state.lock() else {
+            return Ok(None);
+        };
+        let last_used = state.tick();
+        match state.entries.get_mut(cache_key) {
+            // Result could be replaced while tokens were compared.
+            Some(cached_result) if cached_result.validity_token.is(&cached_token) => {
+                cached_result.last_used = last_used;
+                Ok(Some(cached_result.result.copy_ref(gil)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Store result with the token of the probe executed before the query.
+    ///
+    /// The least recently used result is evicted if the cache is full.
+    pub fn insert(
+        &self,
+        cache_key: String,
+        querystring: String,
+        validity_token: Py<PyAny>,
+        result: PSQLDriverPyQueryResult,
+    ) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        if !state.entries.contains_key(&cache_key) && state.entries.len() >= MAX_CACHED_RESULTS {
+            state.evict_one();
+        }
+        let last_used = state.tick();
+        state.entries.insert(
+            cache_key,
+            CachedResult {
+                querystring,
+                validity_token,
+                result,
+                last_used,
+            },
+        );
+    }
+
+    /// Remove cached results of the querystring or all results.
+    pub fn invalidate(&self, querystring: Option<&str>) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        match querystring {
+            Some(querystring) => state
+                .entries
+                .retain(|_, entry| entry.querystring != querystring),
+            None => state.entries.clear(),
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use std::sync::Arc;
+
+use openssl::sha::Sha256;
+use pyo3::{
+    pyclass, pymethods,
+    types::{PyAnyMethods, PyBytes, PyDict, PyDictMethods, PyString},
+    Bound, IntoPy, Py, PyAny, Python,
+};
+use tokio_postgres::Row;
+
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    format_helpers::matches_name_pattern,
+};
+
+const DEFAULT_VISIBLE_CHARS: usize = 4;
+const DEFAULT_MASK_CHAR: char = '*';
+
+#[derive(Clone)]
+enum MaskKind {
+    /// Hex SHA-256 of the salt and the value.
+    Hash { salt: Vec<u8> },
+    /// Value with all characters but the last `visible` replaced.
+    Partial { visible: usize, mask_char: char },
+    /// Column is removed from the row.
+    Drop,
+}
+
+/// Masks values of the column in the results.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct ColumnMask {
+    kind: MaskKind,
+}
+
+impl ColumnMask {
+    /// Check that mask removes the column.
+    #[must_use]
+    pub fn is_drop(&self) -> bool {
+        matches!(self.kind, MaskKind::Drop)
+    }
+
+    /// Return bytes of the value, non str and bytes values
+    /// are converted with `str()`.
+    fn value_bytes(value: &Bound<'_, PyAny>) -> RustPSQLDriverPyResult<Vec<u8>> {
+        if value.is_instance_of::<PyBytes>() {
+            return Ok(value.extract::<Vec<u8>>()?);
+        }
+        Ok(value.str()?.to_string().into_bytes())
+    }
+
+    /// Return masked value, None if column must be dropped.
+    ///
+    /// None value stays None.
+    ///
+    /// # Errors
+    /// May return Err Result if value cannot be converted to string.
+    pub fn mask_value(
+        &self,
+        py: Python<'_>,
+        value: Py<PyAny>,
+    ) -> RustPSQLDriverPyResult<Option<Py<PyAny>>> {
+        if self.is_drop() {
+            return Ok(None);
+        }
+        if value.is_none(py) {
+            return Ok(Some(value));
+        }
+        let value = value.bind(py);
+        let masked_value: String = match &self.kind {
+            MaskKind::Hash { salt } => {
+                let mut hasher = Sha256::new();
+                hasher.update(salt);
+                hasher.update(&Self::value_bytes(value)?);
+                hasher
+                    .finish()
+                    .iter()
+                    .map(|byte| format!("{byte:02x}"))
+                    .collect()
+            }
+            MaskKind::Partial { visible, mask_char } => {
+                let value = value.str()?.to_string();
+                let chars_count = value.chars().count();
+                let masked_count = if chars_count > *visible {
+                    chars_count - visible
+                } else {
+                    chars_count
+                };
+                value
+                    .chars()
+                    .enumerate()
+                    .map(|(idx, character)| {
+                        if idx < masked_count {
+                            *mask_char
+                        } else {
+                            character
+                        }
+                    })
+                    .collect()
+            }
+            MaskKind::Drop => return Ok(None),
+        };
+        Ok(Some(masked_value.into_py(py)))
+    }
+}
+
+#[pymethods]
+impl ColumnMask {
+    /// Replace value with hex SHA-256 of the salt and the value.
+    ///
+    /// Equal values have equal hashes, so they can still be
+    /// grouped and joined by the consumer.
+    #[staticmethod]
+    #[pyo3(signature = (salt=None))]
+    fn hash(salt: Option<Vec<u8>>) -> Self {
+        ColumnMask {
+            kind: MaskKind::Hash {
+                salt: salt.unwrap_or_default(),
+            },
+        }
+    }
+
+    /// Replace all characters but the last `visible` with `mask_char`.
+    ///
+    /// Values not longer than `visible` are masked completely.
+    ///
+    /// # Errors
+    /// May return Err Result if `mask_char` isn't a single character.
+    #[staticmethod]
+    #[pyo3(signature = (visible=DEFAULT_VISIBLE_CHARS, mask_char=None))]
+    fn partial(visible: usize, mask_char: Option<String>) -> RustPSQLDriverPyResult<Self> {
+        let mask_char = match mask_char {
+            Some(mask_char) => {
+                let mut chars = mask_char.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(mask_char), None) => mask_char,
+                    _ => {
+                        return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                            "mask_char must be a single character".into(),
+                        ))
+                    }
+                }
+            }
+            None => DEFAULT_MASK_CHAR,
+        };
+        Ok(ColumnMask {
+            kind: MaskKind::Partial { visible, mask_char },
+        })
+    }
+
+    /// Remove column from the rows.
+    #[staticmethod]
+    fn drop() -> Self {
+        ColumnMask {
+            kind: MaskKind::Drop,
+        }
+    }
+
+    /// Mask the value, return None if column is dropped.
+    ///
+    /// # Errors
+    /// May return Err Result if value cannot be converted to string.
+    fn mask(&self, py: Python<'_>, value: Py<PyAny>) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        Ok(self.mask_value(py, value)?.unwrap_or_else(|| py.None()))
+    }
+
+    fn __repr__(&self) -> String {
+        match &self.kind {
+            MaskKind::Hash { .. } => "ColumnMask.hash()".into(),
+            MaskKind::Partial { visible, mask_char } => {
+                format!("ColumnMask.partial(visible={visible}, mask_char={mask_char:?})")
+            }
+            MaskKind::Drop => "ColumnMask.drop()".into(),
+        }
+    }
+}
+
+/// Masks of the columns by the column name patterns.
+///
+/// The first matching pattern wins.
+#[derive(Clone, Default)]
+pub struct ColumnMasks {
+    masks: Arc<Vec<(String, ColumnMask)>>,
+}
+
+impl ColumnMasks {
+    #[must_use]
+    pub fn new(masks: Vec<(String, ColumnMask)>) -> Self {
+        ColumnMasks {
+            masks: Arc::new(masks),
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.masks.is_empty()
+    }
+
+    /// Find mask of the column.
+    #[must_use]
+    pub fn find(&self, column_name: &str) -> Option<&ColumnMask> {
+        self.masks
+            .iter()
+            .find(|(pattern, _)| matches_name_pattern(pattern, column_name))
+            .map(|(_, mask)| mask)
+    }
+
+    /// Mask values of the row dict in place.
+    ///
+    /// Masks are found by the database column names,
+    /// dict keys can be renamed with `column_names`.
+    ///
+    /// # Errors
+    /// May return Err Result if value cannot be masked.
+    pub fn mask_row(
+        &self,
+        row: &Row,
+        python_dict: &Bound<'_, PyDict>,
+        column_names: Option<&[Py<PyAny>]>,
+    ) -> RustPSQLDriverPyResult<()> {
+        if self.masks.is_empty() {
+            return Ok(());
+        }
+        let py = python_dict.py();
+        for (column_idx, column) in row.columns().iter().enumerate() {
+            let Some(mask) = self.find(column.name()) else {
+                continue;
+            };
+            let key = match column_names.and_then(|column_names| column_names.get(column_idx)) {
+                Some(column_name) => column_name.clone_ref(py).into_bound(py),
+                None => PyString::new(py, column.name()).into_any(),
+            };
+            let Some(value) = python_dict.get_item(&key)? else {
+                continue;
+            };
+            match mask.mask_value(py, value.unbind())? {
+                Some(masked_value) => python_dict.set_item(&key, masked_value)?,
+                None => python_dict.del_item(&key)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+use postgres_types::{Kind, Type};
+use pyo3::{
+    types::{PyAnyMethods, PyDict, PyDictMethods, PyList, PyModule},
+    Bound, Py, PyAny, Python,
+};
+use tokio_postgres::Column;
+
+use crate::exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult};
+
+use super::common_options::ResultSchemaFormat;
+
+const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Return schema of the result columns in the format.
+///
+/// Server doesn't report nullability of the result columns,
+/// so every column is nullable.
+///
+/// # Errors
+/// May return Err Result if:
+/// 1) Column names aren't unique for JSON Schema
+/// 2) pyarrow cannot be imported for Arrow schema
+pub fn build_result_schema(
+    py: Python<'_>,
+    columns: &[Column],
+    format: ResultSchemaFormat,
+    title: Option<String>,
+) -> RustPSQLDriverPyResult<Py<PyAny>> {
+    match format {
+        ResultSchemaFormat::JsonSchema => json_schema(py, columns, title),
+        ResultSchemaFormat::Arrow => arrow_schema(py, columns),
+    }
+}
+
+fn json_schema(
+    py: Python<'_>,
+    columns: &[Column],
+    title: Option<String>,
+) -> RustPSQLDriverPyResult<Py<PyAny>> {
+    let properties = PyDict::new(py);
+    for column in columns {
+        if properties.contains(column.name())? {
+            return Err(RustPSQLDriverError::RustToPyValueConversionError(format!(
+                "Column {} is returned more than once, name it with AS",
+                column.name()
+            )));
+        }
+        let property = json_type(py, column.type_())?;
+        property.set_item("x-postgres-type", column.type_().name())?;
+        properties.set_item(column.name(), property)?;
+    }
+
+    let schema = PyDict::new(py);
+    schema.set_item("$schema", JSON_SCHEMA_DIALECT)?;
+    if let Some(title) = title {
+        schema.set_item("title", title)?;
+    }
+    schema.set_item("type", "object")?;
+    schema.set_item("properties", properties)?;
+    schema.set_item(
+        "required",
+        PyList::new(py, columns.iter().map(Column::name))?,
+    )?;
+    schema.set_item("additionalProperties", false)?;
+    Ok(schema.into_any().unbind())
+}
+
+/// Return nullable JSON Schema of the type.
+///
+/// Types without JSON counterpart, like `json` itself, accept any value.
+fn json_type<'py>(py: Python<'py>, pg_type: &Type) -> RustPSQLDriverPyResult<Bound<'py, PyDict>> {
+    let schema = PyDict::new(py);
+    if let Kind::Array(element_type) = pg_type.kind() {
+        schema.set_item("type", PyList::new(py, ["array", "null"])?)?;
+        schema.set_item("items", json_type(py, element_type)?)?;
+        return Ok(schema);
+    }
+
+    let (json_type, format) = match *pg_type {
+        Type::BOOL => ("boolean", None),
+        Type::INT2 | Type::INT4 | Type::INT8 | Type::OID => ("integer", None),
+        Type::FLOAT4 | Type::FLOAT8 | Type::NUMERIC => ("number", None),
+        Type::UUID => ("string", Some("uuid")),
+        Type::DATE => ("string", Some("date")),
+        Type::TIME | Type::TIMETZ => ("string", Some("time")),
+        Type::TIMESTAMP | Type::TIMESTAMPTZ => ("string", Some("date-time")),
+        Type::INTERVAL => ("string", Some("duration")),
+        Type::JSON | Type::JSONB => return Ok(schema),
+        _ => ("string", None),
+    };
+    schema.set_item("type", PyList::new(py, [json_type, "null"])?)?;
+    if let Some(format) = format {
+        schema.set_item("format", format)?;
+    }
+    if *pg_type == Type::BYTEA {
+        schema.set_item("contentEncoding", "base64")?;
+    }
+    Ok(schema)
+}
+
+fn arrow_schema(py: Python<'_>, columns: &[Column]) -> RustPSQLDriverPyResult<Py<PyAny>> {
+    let pyarrow = py.import("pyarrow").map_err(|err| {
+        RustPSQLDriverError::RustToPyValueConversionError(format!(
+            "pyarrow is required to build Arrow schema, error - {err}"
+        ))
+    })?;
+
+    let fields = columns
+        .iter()
+        .map(|column| {
+            let data_type = arrow_type(&pyarrow, column.type_())?;
+            Ok(pyarrow.call_method1("field", (column.name(), data_type, true))?)
+        })
+        .collect::<RustPSQLDriverPyResult<Vec<_>>>()?;
+    Ok(pyarrow.call_method1("schema", (fields,))?.unbind())
+}
+
+/// Return Arrow type of the Postgres type.
+///
+/// `numeric` is a string because its precision isn't known
+/// without the type modifier, unknown types are strings too.
+fn arrow_type<'py>(
+    pyarrow: &Bound<'py, PyModule>,
+    pg_type: &Type,
+) -> RustPSQLDriverPyResult<Bound<'py, PyAny>> {
+    if let Kind::Array(element_type) = pg_type.kind() {
+        let element_type = arrow_type(pyarrow, element_type)?;
+        return Ok(pyarrow.call_method1("list_", (element_type,))?);
+    }
+
+    let data_type = match *pg_type {
+        Type::BOOL => pyarrow.call_method0("bool_")?,
+        Type::INT2 => pyarrow.call_method0("int16")?,
+        Type::INT4 => pyarrow.call_method0("int32")?,
+        Type::INT8 => pyarrow.call_method0("int64")?,
+        Type::OID => pyarrow.call_method0("uint32")?,
+        Type::FLOAT4 => pyarrow.call_method0("float32")?,
+        Type::FLOAT8 => pyarrow.call_method0("float64")?,
+        Type::BYTEA => pyarrow.call_method0("binary")?,
+        Type::DATE => pyarrow.call_method0("date32")?,
+        Type::TIME => pyarrow.call_method1("time64", ("us",))?,
+        Type::TIMESTAMP => pyarrow.call_method1("timestamp", ("us",))?,
+        Type::TIMESTAMPTZ => pyarrow.call_method1("timestamp", ("us", "UTC"))?,
+        Type::INTERVAL => pyarrow.call_method0("month_day_nano_interval")?,
+        _ => pyarrow.call_method0("string")?,
+    };
+    Ok(data_type)
+}
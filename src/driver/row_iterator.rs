@@ -0,0 +1,119 @@
+use std::{collections::VecDeque, pin::Pin, sync::Arc};
+
+use futures_util::TryStreamExt;
+use pyo3::{
+    exceptions::PyStopAsyncIteration, pyclass, pymethods, types::PyDict, Py, PyAny, Python,
+};
+use tokio::sync::Mutex;
+use tokio_postgres::{Row, RowStream};
+
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    query_result::row_to_dict,
+    runtime::rustdriver_future,
+};
+
+use super::inner_connection::PsqlpyConnection;
+
+/// Default number of rows read from the connection at once.
+pub const DEFAULT_FETCH_CHUNK_SIZE: usize = 1000;
+
+/// Rows read from the connection but not returned yet.
+struct RowIteratorState {
+    row_stream: Option<Pin<Box<RowStream>>>,
+    rows: VecDeque<Row>,
+    // Connection is kept, so it isn't returned to the pool during the iteration.
+    _db_client: Arc<PsqlpyConnection>,
+}
+
+impl RowIteratorState {
+    /// Read up to `chunk_size` rows from the connection.
+    async fn read_chunk(&mut self, chunk_size: usize) -> RustPSQLDriverPyResult<()> {
+        while self.rows.len() < chunk_size {
+            let Some(row_stream) = self.row_stream.as_mut() else {
+                return Ok(());
+            };
+            match row_stream.try_next().await {
+                Ok(Some(row)) => self.rows.push_back(row),
+                Ok(None) => self.row_stream = None,
+                Err(err) => {
+                    self.row_stream = None;
+                    return Err(RustPSQLDriverError::ConnectionExecuteError(format!(
+                        "Cannot fetch row, error - {err}"
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Async iterator over rows of the query result.
+///
+/// Rows are read from the connection by chunks and converted
+/// to Python one by one, so the whole result is never held in memory.
+/// Connection is busy until the iteration is over.
+#[pyclass]
+pub struct RowIterator {
+    state: Arc<Mutex<RowIteratorState>>,
+    chunk_size: usize,
+    custom_decoders: Option<Py<PyDict>>,
+}
+
+impl RowIterator {
+    #[must_use]
+    pub fn new(
+        row_stream: RowStream,
+        db_client: Arc<PsqlpyConnection>,
+        chunk_size: usize,
+        custom_decoders: Option<Py<PyDict>>,
+    ) -> Self {
+        RowIterator {
+            state: Arc::new(Mutex::new(RowIteratorState {
+                row_stream: Some(Box::pin(row_stream)),
+                rows: VecDeque::with_capacity(chunk_size),
+                _db_client: db_client,
+            })),
+            chunk_size,
+            custom_decoders,
+        }
+    }
+}
+
+#[pymethods]
+impl RowIterator {
+    #[must_use]
+    fn __aiter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    /// Return next row as a dict.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Cannot read rows from the connection
+    /// 2) Cannot convert postgres type to python
+    fn __anext__(&self) -> RustPSQLDriverPyResult<Option<Py<PyAny>>> {
+        let state = self.state.clone();
+        let chunk_size = self.chunk_size;
+        let custom_decoders = self.custom_decoders.clone();
+
+        let py_future = Python::with_gil(move |gil| {
+            rustdriver_future(gil, async move {
+                let mut state = state.lock().await;
+                if state.rows.is_empty() {
+                    state.read_chunk(chunk_size).await?;
+                }
+                let Some(row) = state.rows.pop_front() else {
+                    return Err(
+                        PyStopAsyncIteration::new_err("Iteration is over, no more rows").into(),
+                    );
+                };
+
+                Python::with_gil(|gil| Ok(row_to_dict(gil, &row, &custom_decoders, None)?.unbind()))
+            })
+        });
+
+        Ok(Some(py_future?))
+    }
+}
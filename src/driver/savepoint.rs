@@ -0,0 +1,80 @@
+use pyo3::{pyclass, pymethods, Py, PyAny, Python};
+
+use crate::exceptions::rust_errors::RustPSQLDriverPyResult;
+
+use super::transaction::Transaction;
+
+/// Async context manager of the savepoint inside the transaction.
+///
+/// Savepoint is created on enter, rolled back to and released on exit
+/// with an exception, released on exit without it.
+/// Blocks can be nested, every block gets its own savepoint.
+#[pyclass]
+pub struct Savepoint {
+    transaction: Py<Transaction>,
+    savepoint_name: Option<String>,
+}
+
+impl Savepoint {
+    #[must_use]
+    pub fn new(transaction: Py<Transaction>, savepoint_name: Option<String>) -> Self {
+        Savepoint {
+            transaction,
+            savepoint_name,
+        }
+    }
+}
+
+#[pymethods]
+impl Savepoint {
+    /// Name of the savepoint, generated one is known after enter.
+    #[getter]
+    fn name(&self) -> Option<String> {
+        self.savepoint_name.clone()
+    }
+
+    async fn __aenter__<'a>(self_: Py<Self>) -> RustPSQLDriverPyResult<Py<Self>> {
+        let (transaction, savepoint_name) = Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (
+                self_.transaction.clone_ref(gil),
+                self_.savepoint_name.clone(),
+            )
+        });
+
+        let savepoint_name = Transaction::create_savepoint(transaction, savepoint_name).await?;
+
+        Python::with_gil(|gil| {
+            self_.borrow_mut(gil).savepoint_name = Some(savepoint_name);
+        });
+        Ok(self_)
+    }
+
+    async fn __aexit__<'a>(
+        self_: Py<Self>,
+        _exception_type: Py<PyAny>,
+        exception: Py<PyAny>,
+        _traceback: Py<PyAny>,
+    ) -> RustPSQLDriverPyResult<()> {
+        let (transaction, savepoint_name, is_exception_none) = Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (
+                self_.transaction.clone_ref(gil),
+                self_.savepoint_name.clone(),
+                exception.is_none(gil),
+            )
+        });
+        let Some(savepoint_name) = savepoint_name else {
+            return Ok(());
+        };
+
+        if !is_exception_none {
+            Transaction::rollback_savepoint(
+                Python::with_gil(|gil| transaction.clone_ref(gil)),
+                savepoint_name.clone(),
+            )
+            .await?;
+        }
+        Transaction::release_savepoint(transaction, savepoint_name).await
+    }
+}
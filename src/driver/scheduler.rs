@@ -0,0 +1,353 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use deadpool_postgres::Pool;
+use pyo3::{pyclass, pymethods, Py, PyAny, Python};
+use pyo3_async_runtimes::TaskLocals;
+use tokio::sync::Notify;
+use tokio_postgres::Config;
+
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    runtime::tokio_runtime,
+};
+
+use super::{
+    column_codecs::ColumnCodecs, common_options::SslMode, connection::Connection,
+    inner_connection::PsqlpyConnection, sqlcommenter::SqlCommenter,
+    statement_cache::PooledStatementCaches, statement_stats::StatementStats, utils::TlsOptions,
+};
+
+/// What is executed on every run of the scheduled task.
+pub enum ScheduledJob {
+    /// Querystring executed on the pool connection.
+    Query {
+        querystring: String,
+        parameters: Option<Py<PyAny>>,
+    },
+    /// Async callable which accepts `Connection`.
+    Callable {
+        callback: Py<PyAny>,
+        task_locals: TaskLocals,
+    },
+}
+
+/// Pool settings needed to run scheduled tasks.
+pub struct SchedulerContext {
+    pub db_pool: Pool,
+    pub pg_config: Arc<Config>,
+    pub tls_options: TlsOptions,
+    pub ssl_mode: Option<SslMode>,
+    pub statement_stats: StatementStats,
+    pub statement_caches: PooledStatementCaches,
+    pub sqlcommenter: Option<SqlCommenter>,
+    pub column_codecs: ColumnCodecs,
+}
+
+/// Counters and stop signal of one scheduled task.
+struct TaskState {
+    name: String,
+    interval: Duration,
+    jitter: Duration,
+    runs: AtomicU64,
+    skipped: AtomicU64,
+    failures: AtomicU64,
+    last_error: Mutex<Option<String>>,
+    is_stopped: AtomicBool,
+    stop_signal: Notify,
+}
+
+impl TaskState {
+    fn stop(&self) {
+        self.is_stopped.store(true, Ordering::SeqCst);
+        self.stop_signal.notify_one();
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.is_stopped.load(Ordering::SeqCst)
+    }
+
+    /// Wait for the next run, returns false if task was stopped meanwhile.
+    async fn wait_next_run(&self) -> bool {
+        let delay = self.interval + self.jitter.mul_f64(random_fraction());
+        let stopped = tokio::time::timeout(delay, self.stop_signal.notified())
+            .await
+            .is_ok();
+        !stopped && !self.is_stopped()
+    }
+
+    fn record_result(&self, result: RustPSQLDriverPyResult<bool>) {
+        match result {
+            Ok(true) => {
+                self.runs.fetch_add(1, Ordering::SeqCst);
+            }
+            Ok(false) => {
+                self.skipped.fetch_add(1, Ordering::SeqCst);
+            }
+            Err(err) => {
+                self.failures.fetch_add(1, Ordering::SeqCst);
+                if let Ok(mut last_error) = self.last_error.lock() {
+                    *last_error = Some(err.to_string());
+                }
+            }
+        }
+    }
+}
+
+/// Random number in `[0, 1)` for the jitter.
+#[allow(clippy::cast_precision_loss)]
+fn random_fraction() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Tasks scheduled on the pool, they are stopped when pool is closed.
+#[derive(Clone, Default)]
+pub struct PoolScheduler {
+    tasks: Arc<Mutex<Vec<Arc<TaskState>>>>,
+}
+
+impl PoolScheduler {
+    /// Start the task in the background.
+    ///
+    /// Every run takes connection from the pool and acquires session
+    /// advisory lock on the task name, so only one process runs
+    /// the task at a time. Run is skipped if lock is held by someone else.
+    ///
+    /// # Errors
+    /// May return Err Result if interval or jitter is negative.
+    pub fn schedule(
+        &self,
+        name: String,
+        interval: f64,
+        jitter: f64,
+        run_immediately: bool,
+        job: ScheduledJob,
+        context: SchedulerContext,
+    ) -> RustPSQLDriverPyResult<ScheduledTask> {
+        let to_duration = |seconds: f64, what: &str| {
+            Duration::try_from_secs_f64(seconds).map_err(|_| {
+                RustPSQLDriverError::ConnectionPoolConfigurationError(format!(
+                    "{what} must be non-negative number of seconds, got {seconds}"
+                ))
+            })
+        };
+        let interval = to_duration(interval, "Interval")?;
+        if interval.is_zero() {
+            return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                "Interval must be greater than zero".into(),
+            ));
+        }
+
+        let state = Arc::new(TaskState {
+            name,
+            interval,
+            jitter: to_duration(jitter, "Jitter")?,
+            runs: AtomicU64::new(0),
+            skipped: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
+            last_error: Mutex::new(None),
+            is_stopped: AtomicBool::new(false),
+            stop_signal: Notify::new(),
+        });
+        if let Ok(mut tasks) = self.tasks.lock() {
+            tasks.retain(|task| !task.is_stopped());
+            tasks.push(state.clone());
+        }
+
+        tokio_runtime().spawn(run_schedule(
+            state.clone(),
+            run_immediately,
+            Arc::new(job),
+            Arc::new(context),
+        ));
+        Ok(ScheduledTask { state })
+    }
+
+    /// Stop all tasks, running ones finish their current run.
+    pub fn stop_all(&self) {
+        if let Ok(mut tasks) = self.tasks.lock() {
+            for task in tasks.drain(..) {
+                task.stop();
+            }
+        }
+    }
+}
+
+async fn run_schedule(
+    state: Arc<TaskState>,
+    run_immediately: bool,
+    job: Arc<ScheduledJob>,
+    context: Arc<SchedulerContext>,
+) {
+    if !run_immediately && !state.wait_next_run().await {
+        return;
+    }
+    loop {
+        if context.db_pool.is_closed() {
+            state.stop();
+            return;
+        }
+        state.record_result(run_once(&state.name, &job, &context).await);
+        if !state.wait_next_run().await {
+            return;
+        }
+    }
+}
+
+/// Run the job under the advisory lock.
+///
+/// Returns false if the lock is held by another connection.
+async fn run_once(
+    name: &str,
+    job: &ScheduledJob,
+    context: &SchedulerContext,
+) -> RustPSQLDriverPyResult<bool> {
+    let db_connection = context.db_pool.get().await?;
+    let statement_cache = context.statement_caches.checkout(&db_connection);
+    let db_client = Arc::new(PsqlpyConnection::PoolConn(
+        db_connection,
+        Some(context.statement_stats.clone()),
+        None,
+        context.column_codecs.clone(),
+        statement_cache,
+    ));
+
+    let is_locked: bool = db_client
+        .query_one("SELECT pg_try_advisory_lock(hashtext($1))", &[&name])
+        .await?
+        .try_get(0)?;
+    if !is_locked {
+        return Ok(false);
+    }
+
+    let job_result = run_job(db_client.clone(), job, context).await;
+    db_client
+        .query_one("SELECT pg_advisory_unlock(hashtext($1))", &[&name])
+        .await?;
+    job_result.map(|()| true)
+}
+
+async fn run_job(
+    db_client: Arc<PsqlpyConnection>,
+    job: &ScheduledJob,
+    context: &SchedulerContext,
+) -> RustPSQLDriverPyResult<()> {
+    match job {
+        ScheduledJob::Query {
+            querystring,
+            parameters,
+        } => {
+            let parameters = Python::with_gil(|gil| {
+                parameters
+                    .as_ref()
+                    .map(|parameters| parameters.clone_ref(gil))
+            });
+            let (querystring, prepared) =
+                SqlCommenter::apply(context.sqlcommenter.as_ref(), querystring.clone(), None)?;
+            db_client.execute(querystring, parameters, prepared).await?;
+        }
+        ScheduledJob::Callable {
+            callback,
+            task_locals,
+        } => {
+            let connection = Connection::new(
+                Some(db_client),
+                None,
+                context.pg_config.clone(),
+                context.tls_options.clone(),
+                context.ssl_mode,
+            )
+            .with_sqlcommenter(context.sqlcommenter.clone())
+            .with_column_codecs(context.column_codecs.clone());
+            let (callback, task_locals) =
+                Python::with_gil(|gil| (callback.clone_ref(gil), task_locals.clone_ref(gil)));
+
+            pyo3_async_runtimes::tokio::scope(task_locals, async move {
+                let future = Python::with_gil(|gil| {
+                    let awaitable = callback.call1(gil, (connection,))?;
+                    pyo3_async_runtimes::tokio::into_future(awaitable.into_bound(gil))
+                })?;
+                future.await?;
+                Ok::<(), RustPSQLDriverError>(())
+            })
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Handle of the task scheduled with `ConnectionPool.schedule`.
+#[pyclass]
+pub struct ScheduledTask {
+    state: Arc<TaskState>,
+}
+
+#[pymethods]
+impl ScheduledTask {
+    #[getter]
+    fn name(&self) -> String {
+        self.state.name.clone()
+    }
+
+    #[getter]
+    fn interval(&self) -> f64 {
+        self.state.interval.as_secs_f64()
+    }
+
+    /// Number of finished runs.
+    #[getter]
+    fn runs(&self) -> u64 {
+        self.state.runs.load(Ordering::SeqCst)
+    }
+
+    /// Number of runs skipped because task was running elsewhere.
+    #[getter]
+    fn skipped(&self) -> u64 {
+        self.state.skipped.load(Ordering::SeqCst)
+    }
+
+    /// Number of failed runs.
+    #[getter]
+    fn failures(&self) -> u64 {
+        self.state.failures.load(Ordering::SeqCst)
+    }
+
+    /// Error of the last failed run.
+    #[getter]
+    fn last_error(&self) -> Option<String> {
+        self.state
+            .last_error
+            .lock()
+            .ok()
+            .and_then(|last_error| last_error.clone())
+    }
+
+    #[getter]
+    fn is_running(&self) -> bool {
+        !self.state.is_stopped()
+    }
+
+    /// Stop the task, current run isn't interrupted.
+    fn stop(&self) {
+        self.state.stop();
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ScheduledTask(name={:?}, interval={}, runs={}, skipped={}, failures={})",
+            self.state.name,
+            self.state.interval.as_secs_f64(),
+            self.runs(),
+            self.skipped(),
+            self.failures(),
+        )
+    }
+}
@@ -0,0 +1,262 @@
+use pyo3::{
+    types::{PyAnyMethods, PyDict, PyDictMethods, PyString},
+    Bound, PyAny,
+};
+
+use crate::exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult};
+
+use super::inner_connection::PsqlpyConnection;
+
+/// Columns of the table with the formatted type and the name of the type.
+///
+/// `to_regclass` resolves the name with `search_path`,
+/// so the name may be schema-qualified.
+const TABLE_COLUMNS_QUERY: &str = "SELECT a.attname::text, \
+     format_type(a.atttypid, a.atttypmod), t.typname::text \
+     FROM pg_attribute a JOIN pg_type t ON t.oid = a.atttypid \
+     WHERE a.attrelid = to_regclass($1) AND a.attnum > 0 AND NOT a.attisdropped";
+
+const TABLE_EXISTS_QUERY: &str = "SELECT to_regclass($1) IS NOT NULL";
+
+/// Type names which differ from the `pg_type` names
+/// and from the names returned by `format_type`.
+const TYPE_ALIASES: &[(&str, &str)] = &[
+    ("int", "int4"),
+    ("serial", "int4"),
+    ("smallserial", "int2"),
+    ("bigserial", "int8"),
+    ("float", "float8"),
+    ("decimal", "numeric"),
+    ("char", "bpchar"),
+];
+
+/// Column expected in the table, type isn't checked if it's None.
+struct ExpectedColumn {
+    name: String,
+    data_type: Option<String>,
+}
+
+/// Table expected in the database.
+pub struct ExpectedTable {
+    name: String,
+    columns: Vec<ExpectedColumn>,
+}
+
+/// Parse expectations of the schema.
+///
+/// Expectations are dict where the key is table name and the value is
+/// dict of column names to types (None skips the type check)
+/// or list of column names.
+/// Or string where every line is `table: column type, column, ...`.
+///
+/// # Errors
+/// May return Err Result if expectations have wrong format.
+pub fn parse_expectations(
+    expectations: &Bound<'_, PyAny>,
+) -> RustPSQLDriverPyResult<Vec<ExpectedTable>> {
+    if let Ok(expectations) = expectations.downcast::<PyString>() {
+        return parse_expectations_dsl(&expectations.to_string());
+    }
+    let Ok(expectations) = expectations.downcast::<PyDict>() else {
+        return Err(RustPSQLDriverError::PyToRustValueConversionError(
+            "expectations must be dict or str".into(),
+        ));
+    };
+
+    let mut expected_tables = vec![];
+    for (table, columns) in expectations.iter() {
+        let name = table.extract::<String>()?;
+        let columns = if let Ok(columns) = columns.downcast::<PyDict>() {
+            columns
+                .iter()
+                .map(|(column, data_type)| {
+                    Ok(ExpectedColumn {
+                        name: column.extract()?,
+                        data_type: data_type.extract()?,
+                    })
+                })
+                .collect::<RustPSQLDriverPyResult<Vec<_>>>()?
+        } else if let Ok(columns) = columns.extract::<Vec<String>>() {
+            columns
+                .into_iter()
+                .map(|name| ExpectedColumn {
+                    name,
+                    data_type: None,
+                })
+                .collect()
+        } else {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "columns of the table {name} must be dict or list of names"
+            )));
+        };
+        expected_tables.push(ExpectedTable { name, columns });
+    }
+    Ok(expected_tables)
+}
+
+/// Parse lines like `users: id integer, name varchar(255), email`.
+///
+/// Empty lines and lines starting with `#` are skipped.
+fn parse_expectations_dsl(expectations: &str) -> RustPSQLDriverPyResult<Vec<ExpectedTable>> {
+    let mut expected_tables = vec![];
+    for line in expectations.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (name, columns) = line.split_once(':').unwrap_or((line, ""));
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "table name is missing in line {line:?}"
+            )));
+        }
+
+        let columns = split_top_level(columns)
+            .into_iter()
+            .map(str::trim)
+            .filter(|column| !column.is_empty())
+            .map(|column| match column.split_once(char::is_whitespace) {
+                Some((column, data_type)) => ExpectedColumn {
+                    name: column.into(),
+                    data_type: Some(data_type.trim().into()),
+                },
+                None => ExpectedColumn {
+                    name: column.into(),
+                    data_type: None,
+                },
+            })
+            .collect();
+        expected_tables.push(ExpectedTable {
+            name: name.into(),
+            columns,
+        });
+    }
+    Ok(expected_tables)
+}
+
+/// Split by commas which aren't inside of parentheses,
+/// so `numeric(10, 2)` stays whole.
+fn split_top_level(value: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (idx, character) in value.char_indices() {
+        match character {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&value[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&value[start..]);
+    parts
+}
+
+/// Lowercase the type and collapse whitespaces,
+/// spaces inside of the modifier are removed.
+fn normalize_type(data_type: &str) -> String {
+    let data_type = data_type
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    match data_type.split_once('(') {
+        Some((base, modifier)) => format!("{}({})", base.trim_end(), modifier.replace(' ', "")),
+        None => data_type,
+    }
+}
+
+/// Split type to the base and the modifier in parentheses.
+fn split_modifier(data_type: &str) -> (&str, Option<&str>) {
+    match data_type.split_once('(') {
+        Some((base, modifier)) => (base, Some(modifier.trim_end_matches(')'))),
+        None => (data_type, None),
+    }
+}
+
+/// Check that the column type matches the expected one.
+///
+/// Expected type may be the name from `format_type` (`character varying(255)`),
+/// the name of the type (`varchar`) or the common alias (`int`).
+/// Modifier is checked only if it's expected.
+fn type_matches(expected: &str, formatted_type: &str, type_name: &str) -> bool {
+    let expected = normalize_type(expected);
+    let formatted_type = normalize_type(formatted_type);
+    if expected == formatted_type {
+        return true;
+    }
+
+    let (expected_base, expected_modifier) = split_modifier(&expected);
+    let (formatted_base, formatted_modifier) = split_modifier(&formatted_type);
+    let expected_base = TYPE_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == expected_base)
+        .map_or(expected_base, |(_, canonical)| *canonical);
+
+    (expected_base == formatted_base || expected_base == type_name)
+        && (expected_modifier.is_none() || expected_modifier == formatted_modifier)
+}
+
+/// Check tables and columns of the database against the expectations.
+///
+/// Returns all mismatches at once.
+///
+/// # Errors
+/// May return Err Result if:
+/// 1) Cannot query the catalog
+/// 2) Schema doesn't match the expectations
+pub async fn validate_schema(
+    db_client: &PsqlpyConnection,
+    expected_tables: &[ExpectedTable],
+) -> RustPSQLDriverPyResult<()> {
+    let mut mismatches = vec![];
+    for expected_table in expected_tables {
+        let table_exists: bool = db_client
+            .query(TABLE_EXISTS_QUERY, &[&expected_table.name])
+            .await?
+            .first()
+            .map(|row| row.try_get(0))
+            .transpose()?
+            .unwrap_or_default();
+        if !table_exists {
+            mismatches.push(format!("table {} doesn't exist", expected_table.name));
+            continue;
+        }
+
+        let columns = db_client
+            .query(TABLE_COLUMNS_QUERY, &[&expected_table.name])
+            .await?
+            .iter()
+            .map(|row| Ok((row.try_get(0)?, row.try_get(1)?, row.try_get(2)?)))
+            .collect::<RustPSQLDriverPyResult<Vec<(String, String, String)>>>()?;
+
+        for expected_column in &expected_table.columns {
+            let Some((_, formatted_type, type_name)) = columns
+                .iter()
+                .find(|(name, _, _)| *name == expected_column.name)
+            else {
+                mismatches.push(format!(
+                    "column {}.{} doesn't exist",
+                    expected_table.name, expected_column.name
+                ));
+                continue;
+            };
+            if let Some(expected_type) = &expected_column.data_type {
+                if !type_matches(expected_type, formatted_type, type_name) {
+                    mismatches.push(format!(
+                        "column {}.{} has type {formatted_type}, expected {expected_type}",
+                        expected_table.name, expected_column.name
+                    ));
+                }
+            }
+        }
+    }
+
+    if mismatches.is_empty() {
+        return Ok(());
+    }
+    Err(RustPSQLDriverError::SchemaValidationError(mismatches))
+}
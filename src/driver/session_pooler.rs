@@ -0,0 +1,412 @@
+use pyo3::{pyclass, pymethods, Py, PyAny, Python};
+
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    format_helpers::normalize_query,
+    query_result::{PSQLDriverPyQueryResult, PSQLDriverSinglePyQueryResult},
+};
+
+use super::{
+    connection::Connection,
+    connection_pool::ConnectionPool,
+    transaction::Transaction,
+    transaction_options::{IsolationLevel, ReadVariant, SynchronousCommit},
+};
+
+/// Functions which take locks held until the end of the session.
+const SESSION_LOCK_FUNCTIONS: &[&str] = &[
+    "pg_advisory_lock(",
+    "pg_advisory_lock_shared(",
+    "pg_try_advisory_lock(",
+    "pg_try_advisory_lock_shared(",
+];
+
+/// Return trimmed third argument of the call,
+/// `arguments` start after the opening parenthesis.
+fn third_argument(arguments: &str) -> Option<&str> {
+    let mut depth = 0;
+    let mut in_quotes = false;
+    let mut argument_idx = 0;
+    let mut argument_start = 0;
+    for (position, character) in arguments.char_indices() {
+        match character {
+            '"' => in_quotes = !in_quotes,
+            _ if in_quotes => {}
+            '(' => depth += 1,
+            ')' if depth > 0 => depth -= 1,
+            ',' | ')' if depth == 0 => {
+                if argument_idx == 2 {
+                    return Some(arguments[argument_start..position].trim());
+                }
+                if character == ')' {
+                    return None;
+                }
+                argument_idx += 1;
+                argument_start = position + 1;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Check whether the normalized statement calls `set_config`
+/// which changes the setting for the session.
+///
+/// Only `true` literal as `is_local` keeps the setting in the transaction,
+/// parameter or expression may be false.
+fn has_session_set_config(statement: &str) -> bool {
+    statement
+        .match_indices("set_config")
+        .any(|(position, function)| {
+            let is_word_start = statement[..position]
+                .chars()
+                .next_back()
+                .is_none_or(|c| !(c.is_alphanumeric() || c == '_'));
+            let Some(arguments) = statement[position + function.len()..]
+                .trim_start()
+                .strip_prefix('(')
+            else {
+                return false;
+            };
+            is_word_start && third_argument(arguments) != Some("true")
+        })
+}
+
+/// Return feature of the normalized statement which depends on the session state.
+///
+/// Transaction control statements are rejected outside of the transaction,
+/// the next statement may run on another connection.
+fn session_state_feature(statement: &str, in_transaction: bool) -> Option<&'static str> {
+    let words: Vec<&str> = statement
+        .split(|c: char| c.is_whitespace() || c == '(')
+        .filter(|word| !word.is_empty())
+        .collect();
+    let first_word = words.first().copied().unwrap_or_default();
+    let second_word = words.get(1).copied().unwrap_or_default();
+
+    match first_word {
+        "set" if !matches!(second_word, "local" | "transaction" | "constraints") => {
+            Some("SET without LOCAL")
+        }
+        "reset" => Some("RESET"),
+        "discard" => Some("DISCARD"),
+        "listen" | "unlisten" => Some("LISTEN"),
+        "prepare" if second_word != "transaction" => Some("PREPARE"),
+        "deallocate" => Some("DEALLOCATE"),
+        "load" => Some("LOAD"),
+        "declare" if statement.contains(" with hold") => Some("DECLARE CURSOR WITH HOLD"),
+        "create"
+            if words
+                .iter()
+                .skip(1)
+                .take(3)
+                .any(|word| matches!(*word, "temp" | "temporary")) =>
+        {
+            Some("Temporary table")
+        }
+        "begin" | "start" | "commit" | "end" | "rollback" | "abort" | "savepoint" | "release"
+            if !in_transaction =>
+        {
+            Some("Transaction control statement")
+        }
+        _ if has_session_set_config(statement) => Some("set_config without is_local"),
+        _ if SESSION_LOCK_FUNCTIONS
+            .iter()
+            .any(|function| statement.contains(function)) =>
+        {
+            Some("Session advisory lock")
+        }
+        _ => None,
+    }
+}
+
+/// Check that querystring doesn't depend on the session state.
+///
+/// # Errors
+/// May return Err Result if any statement of the querystring
+/// changes the session or relies on it.
+pub fn check_transaction_pooling(
+    querystring: &str,
+    in_transaction: bool,
+) -> RustPSQLDriverPyResult<()> {
+    for statement in normalize_query(querystring).split(';') {
+        let statement = statement.trim();
+        if let Some(feature) = session_state_feature(statement, in_transaction) {
+            return Err(RustPSQLDriverError::ConnectionExecuteError(format!(
+                "{feature} is not supported in transaction pooling mode, statement - {statement}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Logical session of the transaction pooling mode.
+///
+/// Connection is taken from the pool for every statement
+/// and for the whole transaction, so many sessions share
+/// fewer connections.
+/// Statements which depend on the session state are rejected,
+/// the next statement may run on another connection.
+#[pyclass]
+pub struct PooledSession {
+    pool: Py<ConnectionPool>,
+}
+
+impl PooledSession {
+    #[must_use]
+    pub fn new(pool: Py<ConnectionPool>) -> Self {
+        PooledSession { pool }
+    }
+
+    /// Check the querystring and take connection from the pool.
+    async fn checkout(
+        self_: &Py<Self>,
+        querystring: Option<&str>,
+    ) -> RustPSQLDriverPyResult<Py<Connection>> {
+        if let Some(querystring) = querystring {
+            check_transaction_pooling(querystring, false)?;
+        }
+        let pool = Python::with_gil(|gil| self_.borrow(gil).pool.clone_ref(gil));
+        let connection = ConnectionPool::connection(pool, None).await?;
+        Python::with_gil(|gil| Ok(Py::new(gil, connection)?))
+    }
+}
+
+#[pymethods]
+impl PooledSession {
+    /// Execute querystring on the connection from the pool.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Querystring depends on the session state
+    /// 2) Cannot get new connection from the pool
+    /// 3) Cannot execute querystring
+    #[pyo3(signature = (
+        querystring,
+        parameters=None,
+        prepared=None,
+        hints=None,
+        comment=None,
+        timeout=None,
+        row_factory=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn execute(
+        self_: Py<Self>,
+        querystring: String,
+        parameters: Option<Py<PyAny>>,
+        prepared: Option<bool>,
+        hints: Option<String>,
+        comment: Option<String>,
+        timeout: Option<f64>,
+        row_factory: Option<Py<PyAny>>,
+    ) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
+        let connection = Self::checkout(&self_, Some(&querystring)).await?;
+        let result = Connection::execute(
+            Python::with_gil(|gil| connection.clone_ref(gil)),
+            querystring,
+            parameters,
+            prepared,
+            hints,
+            comment,
+            timeout,
+            row_factory,
+        )
+        .await;
+        Connection::back_to_pool(connection);
+        result
+    }
+
+    /// Execute querystring with every set of parameters
+    /// on one connection from the pool.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Querystring depends on the session state
+    /// 2) Cannot get new connection from the pool
+    /// 3) Cannot execute querystring
+    #[pyo3(signature = (querystring, parameters=None, prepared=None, hints=None, comment=None))]
+    pub async fn execute_many(
+        self_: Py<Self>,
+        querystring: String,
+        parameters: Option<Vec<Py<PyAny>>>,
+        prepared: Option<bool>,
+        hints: Option<String>,
+        comment: Option<String>,
+    ) -> RustPSQLDriverPyResult<()> {
+        let connection = Self::checkout(&self_, Some(&querystring)).await?;
+        let result = Connection::execute_many(
+            Python::with_gil(|gil| connection.clone_ref(gil)),
+            querystring,
+            parameters,
+            prepared,
+            hints,
+            comment,
+        )
+        .await;
+        Connection::back_to_pool(connection);
+        result
+    }
+
+    /// Fetch result on the connection from the pool.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Querystring depends on the session state
+    /// 2) Cannot get new connection from the pool
+    /// 3) Cannot execute querystring
+    #[pyo3(signature = (
+        querystring,
+        parameters=None,
+        prepared=None,
+        hints=None,
+        comment=None,
+        timeout=None,
+        row_factory=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn fetch(
+        self_: Py<Self>,
+        querystring: String,
+        parameters: Option<Py<PyAny>>,
+        prepared: Option<bool>,
+        hints: Option<String>,
+        comment: Option<String>,
+        timeout: Option<f64>,
+        row_factory: Option<Py<PyAny>>,
+    ) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
+        let connection = Self::checkout(&self_, Some(&querystring)).await?;
+        let result = Connection::fetch(
+            Python::with_gil(|gil| connection.clone_ref(gil)),
+            querystring,
+            parameters,
+            prepared,
+            hints,
+            comment,
+            timeout,
+            row_factory,
+        )
+        .await;
+        Connection::back_to_pool(connection);
+        result
+    }
+
+    /// Fetch exactly one row on the connection from the pool.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Querystring depends on the session state
+    /// 2) Cannot get new connection from the pool
+    /// 3) Cannot execute querystring
+    /// 4) Query returns not exactly one row
+    #[pyo3(signature = (
+        querystring,
+        parameters=None,
+        prepared=None,
+        hints=None,
+        comment=None,
+        timeout=None,
+    ))]
+    pub async fn fetch_row(
+        self_: Py<Self>,
+        querystring: String,
+        parameters: Option<Py<PyAny>>,
+        prepared: Option<bool>,
+        hints: Option<String>,
+        comment: Option<String>,
+        timeout: Option<f64>,
+    ) -> RustPSQLDriverPyResult<PSQLDriverSinglePyQueryResult> {
+        let connection = Self::checkout(&self_, Some(&querystring)).await?;
+        let result = Connection::fetch_row(
+            Python::with_gil(|gil| connection.clone_ref(gil)),
+            querystring,
+            parameters,
+            prepared,
+            hints,
+            comment,
+            timeout,
+        )
+        .await;
+        Connection::back_to_pool(connection);
+        result
+    }
+
+    /// Fetch the first value of the row on the connection from the pool.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Querystring depends on the session state
+    /// 2) Cannot get new connection from the pool
+    /// 3) Cannot execute querystring
+    /// 4) Query returns not exactly one row
+    #[pyo3(signature = (
+        querystring,
+        parameters=None,
+        prepared=None,
+        hints=None,
+        comment=None,
+        timeout=None,
+    ))]
+    pub async fn fetch_val(
+        self_: Py<Self>,
+        querystring: String,
+        parameters: Option<Py<PyAny>>,
+        prepared: Option<bool>,
+        hints: Option<String>,
+        comment: Option<String>,
+        timeout: Option<f64>,
+    ) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        let connection = Self::checkout(&self_, Some(&querystring)).await?;
+        let result = Connection::fetch_val(
+            Python::with_gil(|gil| connection.clone_ref(gil)),
+            querystring,
+            parameters,
+            prepared,
+            hints,
+            comment,
+            timeout,
+        )
+        .await;
+        Connection::back_to_pool(connection);
+        result
+    }
+
+    /// Return transaction which holds connection from the pool
+    /// until it's committed or rolled back.
+    ///
+    /// Statements of the transaction are checked too,
+    /// `SET LOCAL` and transaction control are allowed there.
+    ///
+    /// # Errors
+    /// May return Err Result if cannot get new connection from the pool.
+    #[pyo3(signature = (
+        isolation_level=None,
+        read_variant=None,
+        deferrable=None,
+        synchronous_commit=None,
+        snapshot=None,
+    ))]
+    pub async fn transaction(
+        self_: Py<Self>,
+        isolation_level: Option<IsolationLevel>,
+        read_variant: Option<ReadVariant>,
+        deferrable: Option<bool>,
+        synchronous_commit: Option<SynchronousCommit>,
+        snapshot: Option<String>,
+    ) -> RustPSQLDriverPyResult<Transaction> {
+        let connection = Self::checkout(&self_, None).await?;
+        let transaction = Python::with_gil(|gil| {
+            connection.borrow(gil).transaction(
+                isolation_level,
+                read_variant,
+                deferrable,
+                synchronous_commit,
+                snapshot,
+            )
+        });
+        // Transaction keeps the connection until its end.
+        Connection::back_to_pool(connection);
+        Ok(transaction?.with_transaction_pooling(true))
+    }
+}
@@ -0,0 +1,276 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use pyo3::{pyclass, pymethods, Py, PyAny, Python};
+use tokio::sync::Mutex;
+
+use crate::{
+    exceptions::rust_errors::RustPSQLDriverPyResult,
+    query_result::{PSQLDriverPyQueryResult, PSQLDriverSinglePyQueryResult},
+};
+
+use super::connection::Connection;
+
+/// Count the operation while it waits in the queue or runs.
+struct PendingGuard<'a>(&'a AtomicUsize);
+
+impl<'a> PendingGuard<'a> {
+    fn new(pending: &'a AtomicUsize) -> Self {
+        pending.fetch_add(1, Ordering::Relaxed);
+        PendingGuard(pending)
+    }
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Queue of the operations of the shared connection.
+///
+/// Queue is stored by the connection, so every handle
+/// of one connection puts operations into the same queue.
+#[derive(Clone, Default)]
+pub struct SharedQueue {
+    lock: Arc<Mutex<()>>,
+    pending: Arc<AtomicUsize>,
+}
+
+/// Connection shared by several asyncio tasks.
+///
+/// Handle is immutable, operations called from different tasks
+/// are executed on the connection one by one in the order they are called,
+/// the mutex of tokio is fair.
+/// All handles of the connection share one queue.
+/// Cancelled operation leaves the queue.
+#[pyclass(frozen)]
+pub struct SharedConnectionHandle {
+    connection: Py<Connection>,
+    lock: Arc<Mutex<()>>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl SharedConnectionHandle {
+    #[must_use]
+    pub fn new(connection: Py<Connection>, queue: SharedQueue) -> Self {
+        SharedConnectionHandle {
+            connection,
+            lock: queue.lock,
+            pending: queue.pending,
+        }
+    }
+
+    /// Return the connection and the queue of the handle.
+    fn parts(self_: &Py<Self>) -> (Py<Connection>, Arc<Mutex<()>>, Arc<AtomicUsize>) {
+        let handle = self_.get();
+        Python::with_gil(|gil| {
+            (
+                handle.connection.clone_ref(gil),
+                handle.lock.clone(),
+                handle.pending.clone(),
+            )
+        })
+    }
+}
+
+#[pymethods]
+impl SharedConnectionHandle {
+    /// Number of the operations which wait in the queue or run.
+    #[getter]
+    fn pending(&self) -> usize {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    /// Execute querystring after the previous operations.
+    ///
+    /// # Errors
+    /// May return Err Result if `Connection.execute` fails.
+    #[pyo3(signature = (
+        querystring,
+        parameters=None,
+        prepared=None,
+        hints=None,
+        comment=None,
+        timeout=None,
+        row_factory=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn execute(
+        self_: Py<Self>,
+        querystring: String,
+        parameters: Option<Py<PyAny>>,
+        prepared: Option<bool>,
+        hints: Option<String>,
+        comment: Option<String>,
+        timeout: Option<f64>,
+        row_factory: Option<Py<PyAny>>,
+    ) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
+        let (connection, lock, pending) = SharedConnectionHandle::parts(&self_);
+        let _pending = PendingGuard::new(&pending);
+        let _turn = lock.lock().await;
+        Connection::execute(
+            connection,
+            querystring,
+            parameters,
+            prepared,
+            hints,
+            comment,
+            timeout,
+            row_factory,
+        )
+        .await
+    }
+
+    /// Fetch rows of querystring after the previous operations.
+    ///
+    /// # Errors
+    /// May return Err Result if `Connection.fetch` fails.
+    #[pyo3(signature = (
+        querystring,
+        parameters=None,
+        prepared=None,
+        hints=None,
+        comment=None,
+        timeout=None,
+        row_factory=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn fetch(
+        self_: Py<Self>,
+        querystring: String,
+        parameters: Option<Py<PyAny>>,
+        prepared: Option<bool>,
+        hints: Option<String>,
+        comment: Option<String>,
+        timeout: Option<f64>,
+        row_factory: Option<Py<PyAny>>,
+    ) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
+        let (connection, lock, pending) = SharedConnectionHandle::parts(&self_);
+        let _pending = PendingGuard::new(&pending);
+        let _turn = lock.lock().await;
+        Connection::fetch(
+            connection,
+            querystring,
+            parameters,
+            prepared,
+            hints,
+            comment,
+            timeout,
+            row_factory,
+        )
+        .await
+    }
+
+    /// Execute querystring with every set of parameters after the previous operations.
+    ///
+    /// # Errors
+    /// May return Err Result if `Connection.execute_many` fails.
+    #[pyo3(signature = (querystring, parameters=None, prepared=None, hints=None, comment=None))]
+    pub async fn execute_many(
+        self_: Py<Self>,
+        querystring: String,
+        parameters: Option<Vec<Py<PyAny>>>,
+        prepared: Option<bool>,
+        hints: Option<String>,
+        comment: Option<String>,
+    ) -> RustPSQLDriverPyResult<()> {
+        let (connection, lock, pending) = SharedConnectionHandle::parts(&self_);
+        let _pending = PendingGuard::new(&pending);
+        let _turn = lock.lock().await;
+        Connection::execute_many(
+            connection,
+            querystring,
+            parameters,
+            prepared,
+            hints,
+            comment,
+        )
+        .await
+    }
+
+    /// Execute statements with the simple query protocol after the previous operations.
+    ///
+    /// # Errors
+    /// May return Err Result if `Connection.execute_batch` fails.
+    pub async fn execute_batch(self_: Py<Self>, querystring: String) -> RustPSQLDriverPyResult<()> {
+        let (connection, lock, pending) = SharedConnectionHandle::parts(&self_);
+        let _pending = PendingGuard::new(&pending);
+        let _turn = lock.lock().await;
+        Connection::execute_batch(connection, querystring).await
+    }
+
+    /// Fetch exactly one row after the previous operations.
+    ///
+    /// # Errors
+    /// May return Err Result if `Connection.fetch_row` fails.
+    #[pyo3(signature = (
+        querystring,
+        parameters=None,
+        prepared=None,
+        hints=None,
+        comment=None,
+        timeout=None,
+    ))]
+    pub async fn fetch_row(
+        self_: Py<Self>,
+        querystring: String,
+        parameters: Option<Py<PyAny>>,
+        prepared: Option<bool>,
+        hints: Option<String>,
+        comment: Option<String>,
+        timeout: Option<f64>,
+    ) -> RustPSQLDriverPyResult<PSQLDriverSinglePyQueryResult> {
+        let (connection, lock, pending) = SharedConnectionHandle::parts(&self_);
+        let _pending = PendingGuard::new(&pending);
+        let _turn = lock.lock().await;
+        Connection::fetch_row(
+            connection,
+            querystring,
+            parameters,
+            prepared,
+            hints,
+            comment,
+            timeout,
+        )
+        .await
+    }
+
+    /// Fetch first value of the only row after the previous operations.
+    ///
+    /// # Errors
+    /// May return Err Result if `Connection.fetch_val` fails.
+    #[pyo3(signature = (
+        querystring,
+        parameters=None,
+        prepared=None,
+        hints=None,
+        comment=None,
+        timeout=None,
+    ))]
+    pub async fn fetch_val(
+        self_: Py<Self>,
+        querystring: String,
+        parameters: Option<Py<PyAny>>,
+        prepared: Option<bool>,
+        hints: Option<String>,
+        comment: Option<String>,
+        timeout: Option<f64>,
+    ) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        let (connection, lock, pending) = SharedConnectionHandle::parts(&self_);
+        let _pending = PendingGuard::new(&pending);
+        let _turn = lock.lock().await;
+        Connection::fetch_val(
+            connection,
+            querystring,
+            parameters,
+            prepared,
+            hints,
+            comment,
+            timeout,
+        )
+        .await
+    }
+}
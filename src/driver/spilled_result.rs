@@ -0,0 +1,310 @@
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::PathBuf,
+};
+
+use postgres_types::Type;
+use pyo3::{
+    pyclass, pymethods,
+    types::{PyDict, PyDictMethods},
+    Py, PyAny, PyRef, Python,
+};
+use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio_postgres::Row;
+
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    value_converter::raw_bytes_data_process,
+};
+
+/// Default size of the raw row data kept in memory, 64 MiB.
+pub const DEFAULT_SPILL_THRESHOLD: usize = 64 * 1024 * 1024;
+
+/// Length written to the spill file instead of NULL value.
+const NULL_VALUE_LENGTH: i32 = -1;
+
+fn spill_error(err: &std::io::Error) -> RustPSQLDriverError {
+    RustPSQLDriverError::ConnectionExecuteError(format!("Cannot use spill file, error - {err}"))
+}
+
+/// Return size of the raw data of the row.
+fn row_bytes(row: &Row) -> usize {
+    (0..row.len())
+        .filter_map(|column_idx| row.col_buffer(column_idx))
+        .map(<[u8]>::len)
+        .sum()
+}
+
+/// Write raw data of the row as length-prefixed values.
+async fn write_row(
+    writer: &mut BufWriter<tokio::fs::File>,
+    row: &Row,
+) -> RustPSQLDriverPyResult<()> {
+    for column_idx in 0..row.len() {
+        let Some(value) = row.col_buffer(column_idx) else {
+            writer
+                .write_all(&NULL_VALUE_LENGTH.to_be_bytes())
+                .await
+                .map_err(|err| spill_error(&err))?;
+            continue;
+        };
+
+        let length = i32::try_from(value.len()).map_err(|_| {
+            RustPSQLDriverError::ConnectionExecuteError(
+                "Value is too big to be spilled to disk".into(),
+            )
+        })?;
+        writer
+            .write_all(&length.to_be_bytes())
+            .await
+            .map_err(|err| spill_error(&err))?;
+        writer
+            .write_all(value)
+            .await
+            .map_err(|err| spill_error(&err))?;
+    }
+    Ok(())
+}
+
+/// Read values of one row written by `write_row`.
+fn read_row(
+    reader: &mut BufReader<File>,
+    columns_count: usize,
+) -> std::io::Result<Vec<Option<Vec<u8>>>> {
+    let mut values = Vec::with_capacity(columns_count);
+    for _ in 0..columns_count {
+        let mut length = [0; 4];
+        reader.read_exact(&mut length)?;
+        let Ok(length) = usize::try_from(i32::from_be_bytes(length)) else {
+            values.push(None);
+            continue;
+        };
+
+        let mut value = vec![0; length];
+        reader.read_exact(&mut value)?;
+        values.push(Some(value));
+    }
+    Ok(values)
+}
+
+/// Temporary file which is removed with the result.
+struct SpillPath(PathBuf);
+
+impl Drop for SpillPath {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Collects rows in memory until their raw data exceeds the threshold,
+/// then moves them and all next rows to the temporary file.
+pub struct RowSpiller {
+    memory_threshold: usize,
+    columns: Vec<(String, Type)>,
+    memory_rows: Vec<Row>,
+    memory_bytes: usize,
+    spill_path: Option<SpillPath>,
+    spill_writer: Option<BufWriter<tokio::fs::File>>,
+    rows_count: usize,
+}
+
+impl RowSpiller {
+    #[must_use]
+    pub fn new(memory_threshold: usize) -> Self {
+        RowSpiller {
+            memory_threshold,
+            columns: vec![],
+            memory_rows: vec![],
+            memory_bytes: 0,
+            spill_path: None,
+            spill_writer: None,
+            rows_count: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn rows_count(&self) -> usize {
+        self.rows_count
+    }
+
+    /// Add row to the result.
+    ///
+    /// # Errors
+    /// May return Err Result if cannot write to the spill file.
+    pub async fn push(&mut self, row: Row) -> RustPSQLDriverPyResult<()> {
+        if self.rows_count == 0 {
+            self.columns = row
+                .columns()
+                .iter()
+                .map(|column| (column.name().to_string(), column.type_().clone()))
+                .collect();
+        }
+        self.rows_count += 1;
+
+        if let Some(spill_writer) = &mut self.spill_writer {
+            return write_row(spill_writer, &row).await;
+        }
+
+        self.memory_bytes += row_bytes(&row);
+        self.memory_rows.push(row);
+        if self.memory_bytes > self.memory_threshold {
+            self.spill().await?;
+        }
+        Ok(())
+    }
+
+    /// Move rows from memory to the new temporary file.
+    ///
+    /// File is readable only by the owner and is never opened
+    /// if it already exists, so other users cannot read or replace rows.
+    async fn spill(&mut self) -> RustPSQLDriverPyResult<()> {
+        let spill_path =
+            std::env::temp_dir().join(format!("psqlpy-spill-{}", uuid::Uuid::new_v4()));
+        let mut open_options = tokio::fs::OpenOptions::new();
+        open_options.write(true).create_new(true);
+        #[cfg(unix)]
+        open_options.mode(0o600);
+        let spill_file = open_options
+            .open(&spill_path)
+            .await
+            .map_err(|err| spill_error(&err))?;
+        self.spill_path = Some(SpillPath(spill_path));
+
+        let mut spill_writer = BufWriter::new(spill_file);
+        for row in std::mem::take(&mut self.memory_rows) {
+            write_row(&mut spill_writer, &row).await?;
+        }
+        self.memory_bytes = 0;
+        self.spill_writer = Some(spill_writer);
+        Ok(())
+    }
+
+    /// Finish writing and return result for the iteration.
+    ///
+    /// # Errors
+    /// May return Err Result if cannot flush or open the spill file.
+    pub async fn finish(
+        mut self,
+        custom_decoders: Option<Py<PyDict>>,
+    ) -> RustPSQLDriverPyResult<SpilledQueryResult> {
+        let mut spill_reader = None;
+        if let (Some(spill_path), Some(mut spill_writer)) =
+            (&self.spill_path, self.spill_writer.take())
+        {
+            spill_writer
+                .flush()
+                .await
+                .map_err(|err| spill_error(&err))?;
+            let spill_file = File::open(&spill_path.0).map_err(|err| spill_error(&err))?;
+            spill_reader = Some(BufReader::new(spill_file));
+        }
+
+        let spilled_rows_left = if spill_reader.is_some() {
+            self.rows_count
+        } else {
+            0
+        };
+        Ok(SpilledQueryResult {
+            columns: self.columns,
+            custom_decoders,
+            memory_rows: self.memory_rows.into_iter(),
+            spill_reader,
+            spill_path: self.spill_path,
+            spilled_rows_left,
+            rows_count: self.rows_count,
+        })
+    }
+}
+
+/// Convert raw values of the row into Python dict.
+fn values_to_dict<'a>(
+    py: Python<'_>,
+    columns: &[(String, Type)],
+    custom_decoders: &Option<Py<PyDict>>,
+    values: impl Iterator<Item = Option<&'a [u8]>>,
+) -> RustPSQLDriverPyResult<Py<PyAny>> {
+    let python_dict = PyDict::new(py);
+    for ((column_name, column_type), value) in columns.iter().zip(values) {
+        let python_value = match value {
+            Some(mut raw_bytes_data) => raw_bytes_data_process(
+                py,
+                &mut raw_bytes_data,
+                column_name,
+                column_type,
+                custom_decoders,
+            )?,
+            None => py.None(),
+        };
+        python_dict.set_item(column_name, python_value)?;
+    }
+    Ok(python_dict.unbind().into_any())
+}
+
+/// Result of the query which can be kept on disk.
+///
+/// Raw row data above the memory threshold is written to the temporary file,
+/// rows are converted to Python dicts one by one on iteration.
+/// Temporary file is removed when the result is garbage collected.
+#[pyclass]
+pub struct SpilledQueryResult {
+    columns: Vec<(String, Type)>,
+    custom_decoders: Option<Py<PyDict>>,
+    memory_rows: std::vec::IntoIter<Row>,
+    spill_reader: Option<BufReader<File>>,
+    spill_path: Option<SpillPath>,
+    spilled_rows_left: usize,
+    rows_count: usize,
+}
+
+#[pymethods]
+impl SpilledQueryResult {
+    /// Return `True` if rows were written to the temporary file.
+    #[getter]
+    fn spilled(&self) -> bool {
+        self.spill_path.is_some()
+    }
+
+    fn __len__(&self) -> usize {
+        self.rows_count
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// Return next row as a dict.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Cannot read the spill file
+    /// 2) Cannot convert postgres type to python
+    fn __next__(&mut self, py: Python<'_>) -> RustPSQLDriverPyResult<Option<Py<PyAny>>> {
+        if let Some(row) = self.memory_rows.next() {
+            let values = (0..row.len()).map(|column_idx| row.col_buffer(column_idx));
+            return values_to_dict(py, &self.columns, &self.custom_decoders, values).map(Some);
+        }
+
+        let Some(spill_reader) = &mut self.spill_reader else {
+            return Ok(None);
+        };
+        if self.spilled_rows_left == 0 {
+            self.spill_reader = None;
+            return Ok(None);
+        }
+
+        // Reading from disk may block, other threads can work meanwhile.
+        let columns_count = self.columns.len();
+        let values = py
+            .allow_threads(|| read_row(spill_reader, columns_count))
+            .map_err(|err| spill_error(&err))?;
+        self.spilled_rows_left -= 1;
+        values_to_dict(
+            py,
+            &self.columns,
+            &self.custom_decoders,
+            values.iter().map(Option::as_deref),
+        )
+        .map(Some)
+    }
+}
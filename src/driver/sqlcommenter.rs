@@ -0,0 +1,98 @@
+use std::{collections::BTreeMap, fmt::Write, sync::Arc};
+
+use pyo3::{types::PyAnyMethods, Py, PyAny, Python};
+
+use crate::exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult};
+
+/// Appends `sqlcommenter` comments to the outgoing queries.
+///
+/// Key-value pairs (e.g. `controller`, `route`, `traceparent`)
+/// are taken from the Python provider right before the query is sent,
+/// so provider can read them from the current context.
+#[derive(Clone)]
+pub struct SqlCommenter {
+    provider: Arc<Py<PyAny>>,
+}
+
+impl SqlCommenter {
+    /// Create new commenter.
+    ///
+    /// # Errors
+    /// May return Err Result if provider is not callable.
+    pub fn new(gil: Python<'_>, provider: Py<PyAny>) -> RustPSQLDriverPyResult<Self> {
+        if !provider.bind(gil).is_callable() {
+            return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                "sqlcommenter provider must be a callable".into(),
+            ));
+        }
+        Ok(SqlCommenter {
+            provider: Arc::new(provider),
+        })
+    }
+
+    /// Append comment from the provider to the querystring.
+    ///
+    /// Querystring which already has a comment isn't changed.
+    /// Comment is different for every request, so querystring
+    /// with comment isn't prepared to keep the statement cache small.
+    ///
+    /// # Errors
+    /// May return Err Result if provider raised an exception
+    /// or returned not a mapping of strings.
+    pub fn apply(
+        sqlcommenter: Option<&SqlCommenter>,
+        querystring: String,
+        prepared: Option<bool>,
+    ) -> RustPSQLDriverPyResult<(String, Option<bool>)> {
+        let Some(sqlcommenter) = sqlcommenter else {
+            return Ok((querystring, prepared));
+        };
+        if querystring.contains("/*") || querystring.contains("--") {
+            return Ok((querystring, prepared));
+        }
+
+        let attributes = Python::with_gil(|gil| {
+            let attributes = sqlcommenter.provider.call0(gil)?;
+            if attributes.is_none(gil) {
+                return Ok(BTreeMap::new());
+            }
+            attributes.extract::<BTreeMap<String, String>>(gil)
+        })
+        .map_err(|err| {
+            RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "sqlcommenter provider must return mapping of strings, error - {err}"
+            ))
+        })?;
+        if attributes.is_empty() {
+            return Ok((querystring, prepared));
+        }
+
+        let comment = attributes
+            .iter()
+            .map(|(key, value)| format!("{}='{}'", url_encode(key), url_encode(value)))
+            .collect::<Vec<String>>()
+            .join(",");
+
+        let statement = querystring.trim_end();
+        let commented_query = match statement.strip_suffix(';') {
+            Some(statement) => format!("{statement} /*{comment}*/;"),
+            None => format!("{statement} /*{comment}*/"),
+        };
+        Ok((commented_query, Some(false)))
+    }
+}
+
+/// Percent-encode everything except unreserved characters.
+///
+/// Single quotes are encoded too, so values are safe inside quotes.
+fn url_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
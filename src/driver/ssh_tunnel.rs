@@ -0,0 +1,370 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    io,
+    process::Stdio,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use tokio::{
+    io::{AsyncBufReadExt, BufReader},
+    net::{TcpListener, TcpStream},
+    process::{Child, ChildStderr, Command},
+    sync::Mutex,
+    time::Instant,
+};
+use tokio_postgres::Config;
+
+use crate::exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult};
+
+use super::utils::tcp_host_ports;
+
+pub const DEFAULT_SSH_PORT: u16 = 22;
+const DEFAULT_TUNNEL_READY_TIMEOUT: Duration = Duration::from_secs(10);
+const TUNNEL_READY_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// Number of the last `ssh` output lines kept for the error message.
+const STDERR_TAIL_LINES: usize = 20;
+/// Message of `ssh` after it started listening on the local port of the forward.
+const FORWARD_LISTENING_PREFIX: &str = "Local forwarding listening on 127.0.0.1 port ";
+
+type Target = (String, u16);
+
+/// Output of the `ssh` process collected by the draining task.
+#[derive(Default)]
+struct SshOutput {
+    /// Local ports which `ssh` itself listens on.
+    listening_ports: HashSet<u16>,
+    tail: VecDeque<String>,
+}
+
+impl SshOutput {
+    fn record(&mut self, line: &str) {
+        let message = line.strip_prefix("debug1: ").unwrap_or(line);
+        if let Some(port) = message
+            .strip_prefix(FORWARD_LISTENING_PREFIX)
+            .and_then(|port| port.trim_end_matches('.').parse().ok())
+        {
+            self.listening_ports.insert(port);
+        }
+        // Debug messages are needed only to find the listening ports.
+        if message.len() == line.len() {
+            if self.tail.len() == STDERR_TAIL_LINES {
+                self.tail.pop_front();
+            }
+            self.tail.push_back(line.to_string());
+        }
+    }
+}
+
+/// Read `ssh` output until the process exits, so the pipe is never full.
+fn drain_stderr(stderr: ChildStderr, output: Arc<std::sync::Mutex<SshOutput>>) {
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(stderr).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if let Ok(mut output) = output.lock() {
+                output.record(&line);
+            }
+        }
+    });
+}
+
+/// Running `ssh` process and local ports of its forwards.
+struct RunningTunnel {
+    process: Child,
+    local_ports: HashMap<Target, u16>,
+    output: Arc<std::sync::Mutex<SshOutput>>,
+}
+
+impl RunningTunnel {
+    fn is_alive(&mut self) -> bool {
+        matches!(self.process.try_wait(), Ok(None))
+    }
+}
+
+fn tunnel_error(message: &str) -> io::Error {
+    io::Error::other(format!("SSH tunnel failed: {message}"))
+}
+
+/// Return free local port for the forward.
+///
+/// Port is released before `ssh` binds it, so the tunnel is used
+/// only after `ssh` reports that it listens on the port.
+async fn free_local_port() -> io::Result<u16> {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+    Ok(listener.local_addr()?.port())
+}
+
+/// SSH port forward owned by the pool.
+///
+/// Forward is opened by the `ssh` client on the first connection,
+/// it's restarted if the process exits and stopped when the pool is closed.
+/// Authentication must be non-interactive: key file or SSH agent.
+pub struct SshTunnel {
+    ssh_host: String,
+    ssh_port: u16,
+    ssh_user: String,
+    key_file: Option<String>,
+    jump_host: Option<String>,
+    strict_host_key_checking: bool,
+    running: Mutex<Option<RunningTunnel>>,
+    closed: AtomicBool,
+}
+
+impl SshTunnel {
+    /// Create new tunnel.
+    ///
+    /// # Errors
+    /// May return Err Result if host, user or jump host is empty
+    /// or starts with `-`, so it could be taken as `ssh` option.
+    pub fn new(
+        ssh_host: String,
+        ssh_port: u16,
+        ssh_user: String,
+        key_file: Option<String>,
+        jump_host: Option<String>,
+        strict_host_key_checking: bool,
+    ) -> RustPSQLDriverPyResult<Self> {
+        let arguments = [
+            ("host", Some(&ssh_host)),
+            ("user", Some(&ssh_user)),
+            ("jump_host", jump_host.as_ref()),
+        ];
+        for (name, value) in arguments {
+            if let Some(value) = value {
+                if value.is_empty() || value.starts_with('-') {
+                    return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                        format!(
+                            "SSH tunnel {name} must not be empty or start with '-', got {value:?}"
+                        ),
+                    ));
+                }
+            }
+        }
+
+        Ok(SshTunnel {
+            ssh_host,
+            ssh_port,
+            ssh_user,
+            key_file,
+            jump_host,
+            strict_host_key_checking,
+            running: Mutex::new(None),
+            closed: AtomicBool::new(false),
+        })
+    }
+
+    /// Return tunnel with the same settings which isn't started.
+    #[must_use]
+    pub fn fresh(&self) -> Self {
+        SshTunnel {
+            ssh_host: self.ssh_host.clone(),
+            ssh_port: self.ssh_port,
+            ssh_user: self.ssh_user.clone(),
+            key_file: self.key_file.clone(),
+            jump_host: self.jump_host.clone(),
+            strict_host_key_checking: self.strict_host_key_checking,
+            running: Mutex::new(None),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    /// Stop `ssh` process, new connections aren't opened after it.
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        // If tunnel is starting, it's stopped by the starting task.
+        if let Ok(mut running) = self.running.try_lock() {
+            running.take();
+        }
+    }
+
+    /// Spawn `ssh` process with the local forwards to the targets.
+    async fn spawn(&self, targets: &[Target]) -> io::Result<RunningTunnel> {
+        let mut command = Command::new("ssh");
+        command
+            .arg("-N")
+            .args(["-o", "ExitOnForwardFailure=yes"])
+            .args(["-o", "BatchMode=yes"])
+            // Debug output tells when forwards are listening.
+            .args(["-o", "LogLevel=DEBUG1"])
+            .arg("-o")
+            .arg(if self.strict_host_key_checking {
+                "StrictHostKeyChecking=yes"
+            } else {
+                "StrictHostKeyChecking=no"
+            })
+            .arg("-p")
+            .arg(self.ssh_port.to_string());
+        if let Some(key_file) = &self.key_file {
+            command.arg("-i").arg(key_file);
+        }
+        if let Some(jump_host) = &self.jump_host {
+            command.arg("-J").arg(jump_host);
+        }
+
+        let mut local_ports = HashMap::new();
+        for (host, port) in targets {
+            let local_port = free_local_port().await?;
+            let forward_host = if host.contains(':') {
+                format!("[{host}]")
+            } else {
+                host.clone()
+            };
+            command
+                .arg("-L")
+                .arg(format!("127.0.0.1:{local_port}:{forward_host}:{port}"));
+            local_ports.insert((host.clone(), *port), local_port);
+        }
+        // Destination is never taken as an option.
+        command
+            .arg("--")
+            .arg(format!("{}@{}", self.ssh_user, self.ssh_host));
+
+        let mut process = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|err| tunnel_error(&format!("cannot start ssh client, {err}")))?;
+
+        let output = Arc::new(std::sync::Mutex::new(SshOutput::default()));
+        if let Some(stderr) = process.stderr.take() {
+            drain_stderr(stderr, output.clone());
+        }
+
+        Ok(RunningTunnel {
+            process,
+            local_ports,
+            output,
+        })
+    }
+
+    /// Wait until `ssh` listens on all local ports and forward accepts connections.
+    ///
+    /// Local ports are used only after `ssh` reports that it listens on them,
+    /// so the port taken by other process is never used.
+    /// Returns the first accepted stream, so it's not wasted.
+    async fn wait_ready(
+        running: &mut RunningTunnel,
+        local_port: u16,
+        ready_timeout: Duration,
+    ) -> io::Result<TcpStream> {
+        let deadline = Instant::now() + ready_timeout;
+        loop {
+            if let Some(status) = running.process.try_wait()? {
+                // Let the draining task read the rest of the output.
+                tokio::time::sleep(TUNNEL_READY_POLL_INTERVAL).await;
+                let stderr = running
+                    .output
+                    .lock()
+                    .map(|output| Vec::from(output.tail.clone()).join("\n"))
+                    .unwrap_or_default();
+                return Err(tunnel_error(&format!(
+                    "ssh client exited with {status}: {}",
+                    stderr.trim()
+                )));
+            }
+
+            let is_listening = running.output.lock().is_ok_and(|output| {
+                running
+                    .local_ports
+                    .values()
+                    .all(|port| output.listening_ports.contains(port))
+            });
+            if is_listening {
+                if let Ok(stream) = TcpStream::connect(("127.0.0.1", local_port)).await {
+                    return Ok(stream);
+                }
+            }
+            if Instant::now() >= deadline {
+                return Err(tunnel_error("timeout"));
+            }
+            tokio::time::sleep(TUNNEL_READY_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Return local ports of the running forwards, start `ssh` if needed.
+    ///
+    /// If new process is started, the stream to the first target is returned too.
+    async fn ensure_running(
+        &self,
+        targets: &[Target],
+        ready_timeout: Duration,
+    ) -> io::Result<(HashMap<Target, u16>, Option<TcpStream>)> {
+        let mut running = self.running.lock().await;
+        if self.closed.load(Ordering::SeqCst) {
+            running.take();
+            return Err(tunnel_error("tunnel is closed"));
+        }
+
+        if let Some(running_tunnel) = running.as_mut() {
+            let has_targets = targets
+                .iter()
+                .all(|target| running_tunnel.local_ports.contains_key(target));
+            if has_targets && running_tunnel.is_alive() {
+                return Ok((running_tunnel.local_ports.clone(), None));
+            }
+        }
+        // Previous process is killed on drop.
+        running.take();
+
+        let Some((first_target, _)) = targets.split_first() else {
+            return Err(io::Error::other("Config doesn't have TCP hosts"));
+        };
+        let mut running_tunnel = self.spawn(targets).await?;
+        let first_local_port = running_tunnel.local_ports[first_target];
+        let ready_stream =
+            Self::wait_ready(&mut running_tunnel, first_local_port, ready_timeout).await?;
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(tunnel_error("tunnel is closed"));
+        }
+
+        let local_ports = running_tunnel.local_ports.clone();
+        *running = Some(running_tunnel);
+        Ok((local_ports, Some(ready_stream)))
+    }
+
+    /// Open stream to the first available host from the config through the tunnel,
+    /// hosts before `first_host` are skipped.
+    ///
+    /// Returns stream, name of the host for TLS and index of the host.
+    ///
+    /// # Errors
+    /// May return Err if tunnel cannot be started
+    /// or forwards to all hosts failed, error of the last host is returned.
+    pub async fn connect_stream(
+        &self,
+        pg_config: &Config,
+        first_host: usize,
+    ) -> io::Result<(TcpStream, String, usize)> {
+        let targets = tcp_host_ports(pg_config);
+        let ready_timeout = pg_config
+            .get_connect_timeout()
+            .copied()
+            .unwrap_or(DEFAULT_TUNNEL_READY_TIMEOUT);
+        let (local_ports, mut ready_stream) = self.ensure_running(&targets, ready_timeout).await?;
+
+        let mut last_error = io::Error::other("Config doesn't have more TCP hosts");
+        for (index, (host, port)) in targets.into_iter().enumerate().skip(first_host) {
+            // Ready stream goes to the first target.
+            if let Some(stream) = ready_stream.take().filter(|_| index == 0) {
+                let _ = stream.set_nodelay(true);
+                return Ok((stream, host, index));
+            }
+
+            let local_port = local_ports[&(host.clone(), port)];
+            match TcpStream::connect(("127.0.0.1", local_port)).await {
+                Ok(stream) => {
+                    let _ = stream.set_nodelay(true);
+                    return Ok((stream, host, index));
+                }
+                Err(err) => last_error = err,
+            }
+        }
+
+        Err(last_error)
+    }
+}
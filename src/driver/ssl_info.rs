@@ -0,0 +1,208 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex, OnceLock, Weak},
+};
+
+use chrono::{DateTime, Duration, Utc};
+use openssl::{
+    asn1::{Asn1Time, Asn1TimeRef},
+    error::ErrorStack,
+    x509::{X509NameRef, X509StoreContextRef, X509},
+};
+use pyo3::{pyclass, pymethods};
+use tokio_postgres::Client;
+
+use crate::exceptions::rust_errors::RustPSQLDriverPyResult;
+
+use super::{
+    inner_connection::PsqlpyConnection,
+    notices::{connection_key, ConnectionKey},
+};
+
+tokio::task_local! {
+    /// Certificate presented by the server to the connection
+    /// which is opened by the current task.
+    static PEER_CERTIFICATE: Arc<Mutex<Option<X509>>>;
+}
+
+/// Certificate presented by the server.
+#[pyclass]
+#[derive(Clone)]
+pub struct SslCertificate {
+    subject: String,
+    issuer: String,
+    not_before: DateTime<Utc>,
+    not_after: DateTime<Utc>,
+}
+
+impl SslCertificate {
+    fn from_x509(certificate: &X509) -> Result<Self, ErrorStack> {
+        Ok(SslCertificate {
+            subject: format_x509_name(certificate.subject_name()),
+            issuer: format_x509_name(certificate.issuer_name()),
+            not_before: asn1_time_to_datetime(certificate.not_before())?,
+            not_after: asn1_time_to_datetime(certificate.not_after())?,
+        })
+    }
+}
+
+#[pymethods]
+impl SslCertificate {
+    #[getter]
+    fn subject(&self) -> String {
+        self.subject.clone()
+    }
+
+    #[getter]
+    fn issuer(&self) -> String {
+        self.issuer.clone()
+    }
+
+    #[getter]
+    fn not_before(&self) -> DateTime<Utc> {
+        self.not_before
+    }
+
+    #[getter]
+    fn not_after(&self) -> DateTime<Utc> {
+        self.not_after
+    }
+
+    /// Check is certificate already expired.
+    fn is_expired(&self) -> bool {
+        self.not_after < Utc::now()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "SslCertificate(subject={}, issuer={}, not_after={})",
+            self.subject, self.issuer, self.not_after,
+        )
+    }
+}
+
+/// Format name in `KEY=value` form, entries are separated with comma.
+fn format_x509_name(name: &X509NameRef) -> String {
+    name.entries()
+        .map(|entry| {
+            let key = entry.object().nid().short_name().unwrap_or("UNDEF");
+            let value = entry
+                .data()
+                .as_utf8()
+                .map(|value| value.to_string())
+                .unwrap_or_default();
+            format!("{key}={value}")
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+fn asn1_time_to_datetime(time: &Asn1TimeRef) -> Result<DateTime<Utc>, ErrorStack> {
+    let now = Utc::now();
+    let diff = Asn1Time::days_from_now(0)?.diff(time)?;
+    Ok(now + Duration::days(i64::from(diff.days)) + Duration::seconds(i64::from(diff.secs)))
+}
+
+/// Return is TLS used by the connection and negotiated protocol version.
+///
+/// # Errors
+/// May return Err Result if cannot query `pg_stat_ssl`.
+pub async fn fetch_ssl_status(
+    db_client: &PsqlpyConnection,
+) -> RustPSQLDriverPyResult<(bool, Option<String>)> {
+    let row = db_client
+        .query_one(
+            "SELECT ssl, version FROM pg_stat_ssl WHERE pid = pg_backend_pid()",
+            &[],
+        )
+        .await?;
+
+    Ok((
+        row.try_get::<_, bool>(0)?,
+        row.try_get::<_, Option<String>>(1)?,
+    ))
+}
+
+/// Remember certificate of the server for the connection.
+///
+/// It's called by TLS verification for every certificate of the chain,
+/// certificate of the server has depth 0. Certificate is captured only
+/// while the connection is opened by `with_peer_certificate`.
+pub fn capture_peer_certificate(store_context: &X509StoreContextRef) {
+    if store_context.error_depth() != 0 {
+        return;
+    }
+    let Some(certificate) = store_context.current_cert() else {
+        return;
+    };
+    let _ = PEER_CERTIFICATE.try_with(|peer_certificate| {
+        if let Ok(mut peer_certificate) = peer_certificate.lock() {
+            *peer_certificate = Some(certificate.to_owned());
+        }
+    });
+}
+
+/// Open the connection and return certificate which the server
+/// presented in its TLS handshake.
+pub async fn with_peer_certificate<F: Future>(connect: F) -> (F::Output, Option<X509>) {
+    let peer_certificate: Arc<Mutex<Option<X509>>> = Arc::default();
+    let output = PEER_CERTIFICATE
+        .scope(peer_certificate.clone(), connect)
+        .await;
+    let peer_certificate = peer_certificate
+        .lock()
+        .ok()
+        .and_then(|mut peer_certificate| peer_certificate.take());
+    (output, peer_certificate)
+}
+
+/// Certificates of the opened connections by the connection key.
+///
+/// Certificate is kept while its connection is alive.
+fn peer_certificates() -> &'static Mutex<HashMap<ConnectionKey, (Weak<()>, SslCertificate)>> {
+    static CERTIFICATES: OnceLock<Mutex<HashMap<ConnectionKey, (Weak<()>, SslCertificate)>>> =
+        OnceLock::new();
+    CERTIFICATES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Make certificate of the new connection available for its client.
+///
+/// Returned guard must live as long as the connection.
+pub async fn register_peer_certificate(
+    client: &Client,
+    peer_certificate: Option<X509>,
+) -> Option<Arc<()>> {
+    let peer_certificate = SslCertificate::from_x509(&peer_certificate?).ok()?;
+    let connection_key = connection_key(client).await?;
+    let connection_guard = Arc::new(());
+    let mut certificates = peer_certificates().lock().ok()?;
+    certificates.retain(|_, (connection_alive, _)| connection_alive.strong_count() > 0);
+    certificates.insert(
+        connection_key,
+        (Arc::downgrade(&connection_guard), peer_certificate),
+    );
+    Some(connection_guard)
+}
+
+/// Return certificate presented by the server in the handshake of the connection.
+///
+/// # Errors
+/// May return Err Result if cannot query the connection key.
+pub async fn fetch_peer_certificate(
+    db_client: &PsqlpyConnection,
+) -> RustPSQLDriverPyResult<Option<SslCertificate>> {
+    let row = db_client
+        .query_one(
+            "SELECT pg_backend_pid(), pg_postmaster_start_time()::text",
+            &[],
+        )
+        .await?;
+    let connection_key: ConnectionKey = (row.try_get(0)?, row.try_get(1)?);
+
+    Ok(peer_certificates().lock().ok().and_then(|certificates| {
+        certificates
+            .get(&connection_key)
+            .map(|(_, peer_certificate)| peer_certificate.clone())
+    }))
+}
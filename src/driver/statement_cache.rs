@@ -0,0 +1,354 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, Weak},
+    time::Instant,
+};
+
+use deadpool_postgres::Object;
+use pyo3::{pyclass, pymethods};
+use tokio_postgres::Statement;
+
+use crate::exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult};
+
+/// Default maximum number of prepared statements kept by one connection.
+pub const DEFAULT_STATEMENT_CACHE_SIZE: usize = 256;
+
+/// How many times more querystrings than statements are counted
+/// before the counters are dropped.
+const TRACKED_USES_FACTOR: usize = 4;
+
+struct CachedStatement {
+    statement: Statement,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct CacheState {
+    statements: HashMap<String, CachedStatement>,
+    uses: HashMap<String, usize>,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+impl CacheState {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    /// Remove the least recently used statement.
+    fn evict_one(&mut self) -> Option<String> {
+        let query = self
+            .statements
+            .iter()
+            .min_by_key(|(_, cached)| cached.last_used)
+            .map(|(query, _)| query.clone())?;
+        self.statements.remove(&query);
+        self.evictions += 1;
+        Some(query)
+    }
+}
+
+/// Prepared statements of one connection with LRU eviction.
+///
+/// Statement evicted from the cache is dropped,
+/// so it's closed on the server.
+#[derive(Clone)]
+pub struct StatementCache {
+    max_size: usize,
+    prepare_threshold: Option<usize>,
+    state: Arc<Mutex<CacheState>>,
+}
+
+impl Default for StatementCache {
+    fn default() -> Self {
+        StatementCache::new(DEFAULT_STATEMENT_CACHE_SIZE)
+    }
+}
+
+impl StatementCache {
+    #[must_use]
+    pub fn new(max_size: usize) -> Self {
+        StatementCache {
+            max_size,
+            prepare_threshold: None,
+            state: Arc::new(Mutex::new(CacheState::default())),
+        }
+    }
+
+    /// Prepare statements executed without explicit `prepared`
+    /// only after they are used `prepare_threshold` times.
+    #[must_use]
+    pub fn with_prepare_threshold(mut self, prepare_threshold: Option<usize>) -> Self {
+        self.prepare_threshold = prepare_threshold;
+        self
+    }
+
+    /// Decide if the statement is executed as the cached prepared statement.
+    ///
+    /// Explicit `prepared` always wins. Without the threshold every
+    /// statement is prepared, with it uses of the querystring are counted
+    /// and the statement is prepared since the threshold is reached.
+    /// Uses are counted by the exact querystring, the key of the cache,
+    /// so queries which differ only in literals don't share the counter.
+    #[must_use]
+    pub fn should_prepare(&self, query: &str, prepared: Option<bool>) -> bool {
+        if let Some(prepared) = prepared {
+            return prepared;
+        }
+        let Some(prepare_threshold) = self.prepare_threshold else {
+            return true;
+        };
+        let Ok(mut state) = self.state.lock() else {
+            return true;
+        };
+        if state.statements.contains_key(query) {
+            return true;
+        }
+
+        if !state.uses.contains_key(query)
+            && state.uses.len() >= self.max_size.max(1) * TRACKED_USES_FACTOR
+        {
+            state.uses.clear();
+        }
+        let uses = state.uses.entry(query.to_string()).or_default();
+        *uses += 1;
+        *uses >= prepare_threshold
+    }
+
+    /// Remove statement which is no longer valid on the server.
+    ///
+    /// Its uses are counted from zero again, so it's executed
+    /// unprepared until the threshold is reached.
+    pub fn invalidate(&self, query: &str) {
+        if let Ok(mut state) = self.state.lock() {
+            state.statements.remove(query);
+            state.uses.remove(query);
+        }
+    }
+
+    /// Return cached statement and mark it as the most recently used.
+    #[must_use]
+    pub fn get(&self, query: &str) -> Option<Statement> {
+        let mut state = self.state.lock().ok()?;
+        let tick = state.tick();
+        let statement = state.statements.get_mut(query).map(|cached| {
+            cached.last_used = tick;
+            cached.statement.clone()
+        })?;
+        state.hits += 1;
+        Some(statement)
+    }
+
+    /// Put statement into the cache.
+    ///
+    /// Returns querystrings of the statements evicted to stay within `max_size`.
+    pub fn insert(&self, query: &str, statement: Statement) -> Vec<String> {
+        let Ok(mut state) = self.state.lock() else {
+            return vec![];
+        };
+        let last_used = state.tick();
+        let cached = CachedStatement {
+            statement,
+            last_used,
+        };
+        if state.statements.insert(query.to_string(), cached).is_some() {
+            state.hits += 1;
+            return vec![];
+        }
+        state.misses += 1;
+
+        let mut evicted = vec![];
+        while state.statements.len() > self.max_size {
+            match state.evict_one() {
+                Some(query) => evicted.push(query),
+                None => break,
+            }
+        }
+        // Event may wait for the GIL, it must not be held with the lock.
+        drop(state);
+        for query in &evicted {
+            tracing::debug!(
+                query = %query,
+                max_size = self.max_size,
+                "Prepared statement is evicted from the cache"
+            );
+        }
+        evicted
+    }
+
+    /// Forget statements if the cache of the pooled connection was cleared.
+    ///
+    /// Pool clears it on recycle with `DISCARD ALL`.
+    fn sync_with(&self, pooled_size: usize) {
+        if let Ok(mut state) = self.state.lock() {
+            if pooled_size < state.statements.len() {
+                state.statements.clear();
+            }
+        }
+    }
+
+    /// Remove all statements, counters are kept.
+    pub fn clear(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.statements.clear();
+        }
+    }
+
+    #[must_use]
+    pub fn info(&self) -> StatementCacheInfo {
+        let Ok(state) = self.state.lock() else {
+            return StatementCacheInfo::default();
+        };
+        StatementCacheInfo {
+            size: state.statements.len(),
+            max_size: self.max_size,
+            hits: state.hits,
+            misses: state.misses,
+            evictions: state.evictions,
+        }
+    }
+}
+
+/// Statement cache of one pooled connection.
+struct PooledStatementCache {
+    client_cache: Weak<deadpool_postgres::StatementCache>,
+    statement_cache: StatementCache,
+}
+
+/// Statement caches of the connections of one pool.
+///
+/// Deadpool keeps the statements, these caches keep their order of use
+/// and evict the least recently used ones from the deadpool cache.
+/// Cache is created on the first checkout of the connection
+/// and is found by its creation time from the pool metrics.
+/// Connections created at the same time are told apart by the deadpool cache.
+#[derive(Clone)]
+pub struct PooledStatementCaches {
+    max_size: usize,
+    prepare_threshold: Option<usize>,
+    caches: Arc<Mutex<HashMap<Instant, Vec<PooledStatementCache>>>>,
+}
+
+impl Default for PooledStatementCaches {
+    fn default() -> Self {
+        PooledStatementCaches::new(DEFAULT_STATEMENT_CACHE_SIZE, None)
+    }
+}
+
+impl PooledStatementCaches {
+    /// Caches of `max_size` statements for every connection of the pool.
+    ///
+    /// With `prepare_threshold` statements executed without explicit `prepared`
+    /// are prepared only after they are used the number of times.
+    #[must_use]
+    pub fn new(max_size: usize, prepare_threshold: Option<usize>) -> Self {
+        PooledStatementCaches {
+            max_size,
+            prepare_threshold,
+            caches: Arc::default(),
+        }
+    }
+
+    /// Return cache of the connection taken from the pool.
+    #[must_use]
+    pub fn checkout(&self, client: &Object) -> StatementCache {
+        let created = Object::metrics(client).created;
+        let client_cache = Arc::downgrade(&client.statement_cache);
+        let Ok(mut caches) = self.caches.lock() else {
+            return StatementCache::new(self.max_size)
+                .with_prepare_threshold(self.prepare_threshold);
+        };
+
+        let found = caches.get(&created).and_then(|pooled_caches| {
+            pooled_caches
+                .iter()
+                .find(|pooled_cache| pooled_cache.client_cache.ptr_eq(&client_cache))
+                .map(|pooled_cache| pooled_cache.statement_cache.clone())
+        });
+        let statement_cache = found.unwrap_or_else(|| {
+            // Caches of the closed connections are dropped.
+            caches.retain(|_, pooled_caches| {
+                pooled_caches.retain(|pooled_cache| pooled_cache.client_cache.strong_count() > 0);
+                !pooled_caches.is_empty()
+            });
+            let statement_cache =
+                StatementCache::new(self.max_size).with_prepare_threshold(self.prepare_threshold);
+            caches
+                .entry(created)
+                .or_default()
+                .push(PooledStatementCache {
+                    client_cache,
+                    statement_cache: statement_cache.clone(),
+                });
+            statement_cache
+        });
+        drop(caches);
+
+        statement_cache.sync_with(client.statement_cache.size());
+        statement_cache
+    }
+}
+
+/// Check that statements are prepared after at least one use.
+///
+/// # Errors
+/// May return Err Result if the threshold is zero.
+pub fn check_prepare_threshold(
+    prepare_threshold: Option<usize>,
+) -> RustPSQLDriverPyResult<Option<usize>> {
+    if prepare_threshold == Some(0) {
+        return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+            "prepare_threshold must be more than 0".into(),
+        ));
+    }
+    Ok(prepare_threshold)
+}
+
+/// State of the statement cache of the connection.
+#[pyclass]
+#[derive(Clone, Default)]
+pub struct StatementCacheInfo {
+    size: usize,
+    max_size: usize,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+#[pymethods]
+impl StatementCacheInfo {
+    #[getter]
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    #[getter]
+    fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    #[getter]
+    fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    #[getter]
+    fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    #[getter]
+    fn evictions(&self) -> u64 {
+        self.evictions
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "StatementCacheInfo(size={}, max_size={}, hits={}, misses={}, evictions={})",
+            self.size, self.max_size, self.hits, self.misses, self.evictions,
+        )
+    }
+}
@@ -0,0 +1,197 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use pyo3::{pyclass, pymethods};
+
+use crate::format_helpers::{normalize_query, query_fingerprint};
+
+/// Maximum number of tracked statements, new statements are ignored after it.
+const MAX_STATEMENTS: usize = 5000;
+/// Number of the latest latencies used to calculate percentiles.
+const MAX_LATENCY_SAMPLES: usize = 1024;
+
+struct StatementEntry {
+    query: String,
+    calls: u64,
+    rows: u64,
+    total_time: Duration,
+    max_time: Duration,
+    latencies: VecDeque<Duration>,
+}
+
+impl StatementEntry {
+    fn new(query: String) -> Self {
+        StatementEntry {
+            query,
+            calls: 0,
+            rows: 0,
+            total_time: Duration::ZERO,
+            max_time: Duration::ZERO,
+            latencies: VecDeque::new(),
+        }
+    }
+
+    fn percentile(&self, percentile: f64) -> Duration {
+        latency_percentile(&self.latencies, percentile)
+    }
+}
+
+/// Return percentile of the latencies with the nearest-rank method.
+#[must_use]
+pub fn latency_percentile(latencies: &VecDeque<Duration>, percentile: f64) -> Duration {
+    let mut latencies: Vec<Duration> = latencies.iter().copied().collect();
+    if latencies.is_empty() {
+        return Duration::ZERO;
+    }
+    latencies.sort_unstable();
+
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    let rank = (percentile * latencies.len() as f64).ceil() as usize;
+    latencies[rank.clamp(1, latencies.len()) - 1]
+}
+
+/// Aggregated execution statistics keyed by the statement fingerprint.
+#[derive(Clone, Default)]
+pub struct StatementStats {
+    statements: Arc<Mutex<HashMap<String, StatementEntry>>>,
+}
+
+impl StatementStats {
+    /// Record execution of the statement.
+    pub fn record(&self, querystring: &str, elapsed: Duration, rows: usize) {
+        let Ok(mut statements) = self.statements.lock() else {
+            return;
+        };
+
+        let fingerprint = query_fingerprint(querystring);
+        if !statements.contains_key(&fingerprint) {
+            if statements.len() >= MAX_STATEMENTS {
+                return;
+            }
+            statements.insert(
+                fingerprint.clone(),
+                StatementEntry::new(normalize_query(querystring)),
+            );
+        }
+        let Some(entry) = statements.get_mut(&fingerprint) else {
+            return;
+        };
+
+        entry.calls += 1;
+        entry.rows += rows as u64;
+        entry.total_time += elapsed;
+        entry.max_time = entry.max_time.max(elapsed);
+        if entry.latencies.len() >= MAX_LATENCY_SAMPLES {
+            entry.latencies.pop_front();
+        }
+        entry.latencies.push_back(elapsed);
+    }
+
+    /// Return statistics of all statements, the slowest in total go first.
+    #[must_use]
+    pub fn snapshot(&self) -> Vec<StatementStat> {
+        let Ok(statements) = self.statements.lock() else {
+            return vec![];
+        };
+
+        let mut snapshot: Vec<StatementStat> = statements
+            .iter()
+            .map(|(fingerprint, entry)| StatementStat {
+                fingerprint: fingerprint.clone(),
+                query: entry.query.clone(),
+                calls: entry.calls,
+                rows: entry.rows,
+                total_time: entry.total_time,
+                max_time: entry.max_time,
+                p95_time: entry.percentile(0.95),
+            })
+            .collect();
+        snapshot.sort_by(|left, right| right.total_time.cmp(&left.total_time));
+        snapshot
+    }
+
+    /// Remove all collected statistics.
+    pub fn reset(&self) {
+        if let Ok(mut statements) = self.statements.lock() {
+            statements.clear();
+        }
+    }
+}
+
+/// Execution statistics of the statement.
+///
+/// Times are in milliseconds.
+#[pyclass]
+#[derive(Clone)]
+pub struct StatementStat {
+    fingerprint: String,
+    query: String,
+    calls: u64,
+    rows: u64,
+    total_time: Duration,
+    max_time: Duration,
+    p95_time: Duration,
+}
+
+#[pymethods]
+impl StatementStat {
+    #[getter]
+    fn fingerprint(&self) -> String {
+        self.fingerprint.clone()
+    }
+
+    #[getter]
+    fn query(&self) -> String {
+        self.query.clone()
+    }
+
+    #[getter]
+    fn calls(&self) -> u64 {
+        self.calls
+    }
+
+    #[getter]
+    fn rows(&self) -> u64 {
+        self.rows
+    }
+
+    #[getter]
+    fn total_time(&self) -> f64 {
+        self.total_time.as_secs_f64() * 1000.0
+    }
+
+    #[getter]
+    #[allow(clippy::cast_precision_loss)]
+    fn mean_time(&self) -> f64 {
+        if self.calls == 0 {
+            return 0.0;
+        }
+        self.total_time() / self.calls as f64
+    }
+
+    #[getter]
+    fn max_time(&self) -> f64 {
+        self.max_time.as_secs_f64() * 1000.0
+    }
+
+    #[getter]
+    fn p95_time(&self) -> f64 {
+        self.p95_time.as_secs_f64() * 1000.0
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "StatementStat(query={}, calls={}, mean_time={:.3})",
+            self.query,
+            self.calls,
+            self.mean_time(),
+        )
+    }
+}
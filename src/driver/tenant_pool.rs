@@ -0,0 +1,317 @@
+use std::{cell::Cell, collections::HashMap, time::Duration};
+
+use deadpool_postgres::{ManagerConfig, Pool, RecyclingMethod};
+use pyo3::{pyclass, pymethods, Py, Python};
+use tokio_postgres::Config;
+
+use crate::exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult};
+
+use super::{
+    common_options::{ConnRecyclingMethod, SslMode},
+    connection::Connection,
+    connection_pool::ConnectionPool,
+    utils::{build_connection_config, build_manager, build_tls, ConnectionLimit, TlsOptions},
+};
+
+/// How long new connection waits for the limit of total connections by default.
+const DEFAULT_ACQUIRE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Database and user of the tenant.
+type TenantKey = (String, String);
+
+/// Pool of connection pools, one per database and user.
+///
+/// Sub-pools are created on demand from the template config,
+/// number of opened connections across all sub-pools can be limited.
+#[pyclass]
+pub struct TenantPool {
+    pg_config: Config,
+    tls_options: TlsOptions,
+    ssl_mode: Option<SslMode>,
+    conn_recycling_method: Option<ConnRecyclingMethod>,
+    max_tenant_pool_size: Option<usize>,
+    connection_limit: Option<ConnectionLimit>,
+    tenants: HashMap<TenantKey, Py<ConnectionPool>>,
+}
+
+impl TenantPool {
+    fn tenant_key(&self, db_name: Option<String>, username: Option<String>) -> TenantKey {
+        (
+            db_name
+                .or_else(|| self.pg_config.get_dbname().map(ToString::to_string))
+                .unwrap_or_default(),
+            username
+                .or_else(|| self.pg_config.get_user().map(ToString::to_string))
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Return sub-pool of the tenant, create it if it doesn't exist.
+    ///
+    /// # Errors
+    /// May return Err Result if cannot build new sub-pool.
+    fn tenant_pool(
+        &mut self,
+        gil: Python<'_>,
+        tenant_key: TenantKey,
+        password: Option<String>,
+    ) -> RustPSQLDriverPyResult<Py<ConnectionPool>> {
+        if let Some(tenant_pool) = self.tenants.get(&tenant_key) {
+            return Ok(tenant_pool.clone_ref(gil));
+        }
+
+        let mut pg_config = self.pg_config.clone();
+        let (db_name, username) = &tenant_key;
+        if !db_name.is_empty() {
+            pg_config.dbname(db_name);
+        }
+        if !username.is_empty() {
+            pg_config.user(username);
+        }
+        if let Some(password) = password {
+            pg_config.password(password);
+        }
+
+        let mgr_config = ManagerConfig {
+            recycling_method: self
+                .conn_recycling_method
+                .map_or(RecyclingMethod::Fast, |method| method.to_internal()),
+        };
+        let mgr = build_manager(
+            mgr_config,
+            pg_config.clone(),
+            build_tls(&self.tls_options, &self.ssl_mode)?,
+            None,
+            None,
+            self.connection_limit.clone(),
+            false,
+        );
+
+        let mut db_pool_builder = Pool::builder(mgr);
+        if let Some(max_tenant_pool_size) = self.max_tenant_pool_size {
+            db_pool_builder = db_pool_builder.max_size(max_tenant_pool_size);
+        }
+        let db_pool = db_pool_builder.build()?;
+
+        let tenant_pool = Py::new(
+            gil,
+            ConnectionPool::build(db_pool, pg_config, self.tls_options.clone(), self.ssl_mode),
+        )?;
+        self.tenants.insert(tenant_key, tenant_pool.clone_ref(gil));
+        Ok(tenant_pool)
+    }
+
+    /// Close one idle connection of other tenants if the connection limit is reached.
+    fn release_idle_connection(&self, gil: Python<'_>, tenant_pool: &Py<ConnectionPool>) {
+        let Some(connection_limit) = &self.connection_limit else {
+            return;
+        };
+        if connection_limit.available_permits() > 0
+            || tenant_pool.borrow(gil).db_pool().status().available > 0
+        {
+            return;
+        }
+
+        for other_pool in self.tenants.values() {
+            if other_pool.is(tenant_pool) {
+                continue;
+            }
+            let other_pool = other_pool.borrow(gil).db_pool();
+            if other_pool.status().available == 0 {
+                continue;
+            }
+
+            let is_released = Cell::new(false);
+            other_pool.retain(|_, _| {
+                if is_released.get() {
+                    return true;
+                }
+                is_released.set(true);
+                false
+            });
+            return;
+        }
+    }
+}
+
+#[pymethods]
+impl TenantPool {
+    /// Create new tenant pool.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Cannot build template config
+    /// 2) Pool sizes are invalid
+    /// 3) `acquire_timeout` is negative or not finite
+    #[new]
+    #[pyo3(signature = (
+        dsn=None,
+        username=None,
+        password=None,
+        host=None,
+        port=None,
+        db_name=None,
+        ssl_mode=None,
+        ca_file=None,
+        max_tenant_pool_size=None,
+        max_total_connections=None,
+        conn_recycling_method=None,
+        ssl_cert=None,
+        ssl_key=None,
+        verify_hostname=None,
+        acquire_timeout=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        dsn: Option<String>,
+        username: Option<String>,
+        password: Option<String>,
+        host: Option<String>,
+        port: Option<u16>,
+        db_name: Option<String>,
+        ssl_mode: Option<SslMode>,
+        ca_file: Option<String>,
+        max_tenant_pool_size: Option<usize>,
+        max_total_connections: Option<usize>,
+        conn_recycling_method: Option<ConnRecyclingMethod>,
+        ssl_cert: Option<String>,
+        ssl_key: Option<String>,
+        verify_hostname: Option<bool>,
+        acquire_timeout: Option<f64>,
+    ) -> RustPSQLDriverPyResult<Self> {
+        if let Some(max_tenant_pool_size) = max_tenant_pool_size {
+            if max_tenant_pool_size < 2 {
+                return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                    "Maximum tenant pool size must be more than 1".into(),
+                ));
+            }
+        }
+        if max_total_connections == Some(0) {
+            return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                "Maximum number of total connections must be more than 0".into(),
+            ));
+        }
+        let acquire_timeout = match acquire_timeout {
+            Some(acquire_timeout) => {
+                Duration::try_from_secs_f64(acquire_timeout).map_err(|_| {
+                    RustPSQLDriverError::ConnectionPoolConfigurationError(format!(
+                    "acquire_timeout must be non-negative number of seconds, got {acquire_timeout}"
+                ))
+                })?
+            }
+            None => DEFAULT_ACQUIRE_TIMEOUT,
+        };
+
+        let pg_config = build_connection_config(
+            dsn, username, password, host, None, port, None, db_name, None, None, None, None, None,
+            None, None, None, None, None, None, None, None, None, ssl_mode,
+        )?;
+        let tls_options = TlsOptions {
+            ca_file,
+            ssl_cert,
+            ssl_key,
+            verify_hostname,
+        };
+        // Sub-pools are created later, check TLS files right away.
+        build_tls(&tls_options, &ssl_mode)?;
+
+        Ok(TenantPool {
+            pg_config,
+            tls_options,
+            ssl_mode,
+            conn_recycling_method,
+            max_tenant_pool_size,
+            connection_limit: max_total_connections.map(|max_total_connections| {
+                ConnectionLimit::new(max_total_connections, acquire_timeout)
+            }),
+            tenants: HashMap::new(),
+        })
+    }
+
+    /// Return connection pool of the tenant.
+    ///
+    /// Pool is created on the first call,
+    /// `password` is used only for the new pool.
+    ///
+    /// # Errors
+    /// May return Err Result if cannot build new pool.
+    #[pyo3(signature = (db_name=None, username=None, password=None))]
+    pub fn pool(
+        self_: Py<Self>,
+        db_name: Option<String>,
+        username: Option<String>,
+        password: Option<String>,
+    ) -> RustPSQLDriverPyResult<Py<ConnectionPool>> {
+        Python::with_gil(|gil| {
+            let mut self_ = self_.borrow_mut(gil);
+            let tenant_key = self_.tenant_key(db_name, username);
+            self_.tenant_pool(gil, tenant_key, password)
+        })
+    }
+
+    /// Return new connection from the pool of the tenant.
+    ///
+    /// If limit of total connections is reached,
+    /// one idle connection of other tenants is closed.
+    /// If there is no idle connection, new connection waits
+    /// for `acquire_timeout` until any connection is closed.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Cannot build new pool
+    /// 2) Cannot get new connection from the pool
+    /// 3) Limit of total connections is reached for `acquire_timeout`
+    #[pyo3(signature = (db_name=None, username=None, password=None))]
+    pub async fn connection(
+        self_: Py<Self>,
+        db_name: Option<String>,
+        username: Option<String>,
+        password: Option<String>,
+    ) -> RustPSQLDriverPyResult<Connection> {
+        let tenant_pool = Python::with_gil(|gil| {
+            let mut self_ = self_.borrow_mut(gil);
+            let tenant_key = self_.tenant_key(db_name, username);
+            let tenant_pool = self_.tenant_pool(gil, tenant_key, password)?;
+            self_.release_idle_connection(gil, &tenant_pool);
+            Ok::<Py<ConnectionPool>, RustPSQLDriverError>(tenant_pool)
+        })?;
+
+        ConnectionPool::connection(tenant_pool, None).await
+    }
+
+    /// Return databases and users of the created pools.
+    #[must_use]
+    pub fn tenants(&self) -> Vec<TenantKey> {
+        self.tenants.keys().cloned().collect()
+    }
+
+    /// Return number of opened connections across all pools.
+    #[must_use]
+    pub fn total_connections(&self, py: Python<'_>) -> usize {
+        self.tenants
+            .values()
+            .map(|tenant_pool| tenant_pool.borrow(py).db_pool().status().size)
+            .sum()
+    }
+
+    /// Close and remove pool of the tenant.
+    #[pyo3(signature = (db_name=None, username=None))]
+    pub fn remove_tenant(
+        &mut self,
+        py: Python<'_>,
+        db_name: Option<String>,
+        username: Option<String>,
+    ) {
+        let tenant_key = self.tenant_key(db_name, username);
+        if let Some(tenant_pool) = self.tenants.remove(&tenant_key) {
+            tenant_pool.borrow(py).close();
+        }
+    }
+
+    /// Close all pools.
+    pub fn close(&mut self, py: Python<'_>) {
+        for (_, tenant_pool) in self.tenants.drain() {
+            tenant_pool.borrow(py).close();
+        }
+    }
+}
@@ -1,25 +1,38 @@
 use bytes::BytesMut;
-use futures_util::{future, pin_mut};
+use futures_util::{future, pin_mut, SinkExt, StreamExt};
 use pyo3::{
-    buffer::PyBuffer,
     prelude::*,
     pyclass,
-    types::{PyList, PyTuple},
+    types::{PyBytes, PyList, PyTuple},
 };
 use tokio_postgres::{binary_copy::BinaryCopyInWriter, config::Host, Config};
 
 use crate::{
     exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
-    format_helpers::quote_ident,
+    format_helpers::{annotate_query, is_valid_ident, quote_ident, MAX_IDENT_LENGTH},
     query_result::{PSQLDriverPyQueryResult, PSQLDriverSinglePyQueryResult},
 };
 
 use super::{
-    cursor::Cursor,
+    binary_copy_validation::validate_binary_copy,
+    column_codecs::ColumnCodecs,
+    common_options::CopyCommandFormat,
+    connection::{copy_source_bytes, copy_target},
+    copy_out::CopyOutChunks,
+    cursor::{prepare_cursor_name, Cursor},
+    dry_run::{dry_run_rollback, reject_dry_run, run_dry},
     inner_connection::PsqlpyConnection,
+    isolation_advisor::IsolationAdvisor,
+    partial_statement::PartialStatement,
+    query_queue::QueryQueue,
+    redaction::RedactionPolicy,
+    result_masking::ColumnMasks,
+    savepoint::Savepoint,
+    session_pooler::check_transaction_pooling,
+    sqlcommenter::SqlCommenter,
     transaction_options::{IsolationLevel, ReadVariant, SynchronousCommit},
 };
-use std::{collections::HashSet, net::IpAddr, sync::Arc};
+use std::{net::IpAddr, sync::Arc};
 
 #[allow(clippy::module_name_repetitions)]
 pub trait TransactionObjectTrait {
@@ -29,9 +42,20 @@ pub trait TransactionObjectTrait {
         read_variant: Option<ReadVariant>,
         defferable: Option<bool>,
         synchronous_commit: Option<SynchronousCommit>,
+        snapshot: Option<String>,
+    ) -> impl std::future::Future<Output = RustPSQLDriverPyResult<()>> + Send;
+    fn set_synchronous_commit(
+        &self,
+        synchronous_commit: Option<SynchronousCommit>,
+    ) -> impl std::future::Future<Output = RustPSQLDriverPyResult<()>> + Send;
+    fn commit(
+        &self,
+        chain: bool,
+    ) -> impl std::future::Future<Output = RustPSQLDriverPyResult<()>> + Send;
+    fn rollback(
+        &self,
+        chain: bool,
     ) -> impl std::future::Future<Output = RustPSQLDriverPyResult<()>> + Send;
-    fn commit(&self) -> impl std::future::Future<Output = RustPSQLDriverPyResult<()>> + Send;
-    fn rollback(&self) -> impl std::future::Future<Output = RustPSQLDriverPyResult<()>> + Send;
 }
 
 impl TransactionObjectTrait for PsqlpyConnection {
@@ -41,6 +65,7 @@ impl TransactionObjectTrait for PsqlpyConnection {
         read_variant: Option<ReadVariant>,
         deferrable: Option<bool>,
         synchronous_commit: Option<SynchronousCommit>,
+        snapshot: Option<String>,
     ) -> RustPSQLDriverPyResult<()> {
         let mut querystring = "START TRANSACTION".to_string();
 
@@ -61,12 +86,28 @@ impl TransactionObjectTrait for PsqlpyConnection {
             None => "",
         });
 
+        // Snapshot must be imported before any other statement.
+        if let Some(snapshot) = snapshot {
+            if !is_valid_snapshot_id(&snapshot) {
+                return Err(RustPSQLDriverError::TransactionBeginError(format!(
+                    "Invalid snapshot identifier {snapshot}"
+                )));
+            }
+            querystring.push_str(format!("; SET TRANSACTION SNAPSHOT '{snapshot}'").as_str());
+        }
+
         self.batch_execute(&querystring).await.map_err(|err| {
             RustPSQLDriverError::TransactionBeginError(format!(
                 "Cannot execute statement to start transaction, err - {err}"
             ))
         })?;
 
+        self.set_synchronous_commit(synchronous_commit).await
+    }
+    async fn set_synchronous_commit(
+        &self,
+        synchronous_commit: Option<SynchronousCommit>,
+    ) -> RustPSQLDriverPyResult<()> {
         if let Some(synchronous_commit) = synchronous_commit {
             let str_synchronous_commit = synchronous_commit.to_str_level();
 
@@ -84,16 +125,26 @@ impl TransactionObjectTrait for PsqlpyConnection {
 
         Ok(())
     }
-    async fn commit(&self) -> RustPSQLDriverPyResult<()> {
-        self.batch_execute("COMMIT;").await.map_err(|err| {
+    async fn commit(&self, chain: bool) -> RustPSQLDriverPyResult<()> {
+        let querystring = if chain {
+            "COMMIT AND CHAIN;"
+        } else {
+            "COMMIT;"
+        };
+        self.batch_execute(querystring).await.map_err(|err| {
             RustPSQLDriverError::TransactionCommitError(format!(
                 "Cannot execute COMMIT statement, error - {err}"
             ))
         })?;
         Ok(())
     }
-    async fn rollback(&self) -> RustPSQLDriverPyResult<()> {
-        self.batch_execute("ROLLBACK;").await.map_err(|err| {
+    async fn rollback(&self, chain: bool) -> RustPSQLDriverPyResult<()> {
+        let querystring = if chain {
+            "ROLLBACK AND CHAIN;"
+        } else {
+            "ROLLBACK;"
+        };
+        self.batch_execute(querystring).await.map_err(|err| {
             RustPSQLDriverError::TransactionRollbackError(format!(
                 "Cannot execute ROLLBACK statement, error - {err}"
             ))
@@ -102,19 +153,83 @@ impl TransactionObjectTrait for PsqlpyConnection {
     }
 }
 
+/// Check that snapshot identifier looks like one from `pg_export_snapshot`,
+/// e.g. `00000003-0000001B-1`.
+fn is_valid_snapshot_id(snapshot: &str) -> bool {
+    !snapshot.is_empty()
+        && snapshot
+            .chars()
+            .all(|symbol| symbol.is_ascii_hexdigit() || symbol == '-')
+}
+
+/// Return locking clause of `SELECT` for the lock strength and wait policy.
+///
+/// # Errors
+/// May return Err Result if:
+/// 1) Lock strength is unknown
+/// 2) Both `skip_locked` and `nowait` are set
+fn row_lock_clause(
+    lock: &str,
+    skip_locked: Option<bool>,
+    nowait: bool,
+) -> RustPSQLDriverPyResult<String> {
+    let strength = match lock.to_lowercase().as_str() {
+        "update" => "UPDATE",
+        "no key update" => "NO KEY UPDATE",
+        "share" => "SHARE",
+        "key share" => "KEY SHARE",
+        _ => {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "Unknown lock {lock}, expected update, no key update, share or key share"
+            )))
+        }
+    };
+
+    match (skip_locked, nowait) {
+        (Some(true), true) => Err(RustPSQLDriverError::PyToRustValueConversionError(
+            "skip_locked and nowait cannot be used together".into(),
+        )),
+        (_, true) => Ok(format!("FOR {strength} NOWAIT")),
+        (Some(false), false) => Ok(format!("FOR {strength}")),
+        (_, false) => Ok(format!("FOR {strength} SKIP LOCKED")),
+    }
+}
+
+/// State of the transaction.
+///
+/// Transaction can move only forward:
+/// `Created` -> `Begun` -> `Committed` or `RolledBack`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TransactionState {
+    Created,
+    Begun,
+    Committed,
+    RolledBack,
+}
+
 #[pyclass(subclass)]
 pub struct Transaction {
     pub db_client: Option<Arc<PsqlpyConnection>>,
     pg_config: Arc<Config>,
-    is_started: bool,
-    is_done: bool,
+    state: TransactionState,
 
     isolation_level: Option<IsolationLevel>,
     synchronous_commit: Option<SynchronousCommit>,
     read_variant: Option<ReadVariant>,
     deferrable: Option<bool>,
-
-    savepoints_map: HashSet<String>,
+    snapshot: Option<String>,
+
+    savepoints_stack: Vec<String>,
+    savepoints_counter: usize,
+    cursors: Vec<Py<Cursor>>,
+    sqlcommenter: Option<SqlCommenter>,
+    isolation_advisor: Option<IsolationAdvisor>,
+    column_codecs: ColumnCodecs,
+    redaction_policy: Option<RedactionPolicy>,
+    column_masks: ColumnMasks,
+    transaction_pooling: bool,
+    dry_run: bool,
+    query_queue: QueryQueue,
 }
 
 impl Transaction {
@@ -123,39 +238,230 @@ impl Transaction {
     pub fn new(
         db_client: Arc<PsqlpyConnection>,
         pg_config: Arc<Config>,
-        is_started: bool,
-        is_done: bool,
         isolation_level: Option<IsolationLevel>,
         synchronous_commit: Option<SynchronousCommit>,
         read_variant: Option<ReadVariant>,
         deferrable: Option<bool>,
-        savepoints_map: HashSet<String>,
+        savepoints_stack: Vec<String>,
     ) -> Self {
         Self {
             db_client: Some(db_client),
             pg_config,
-            is_started,
-            is_done,
+            state: TransactionState::Created,
             isolation_level,
             synchronous_commit,
             read_variant,
             deferrable,
-            savepoints_map,
+            snapshot: None,
+            savepoints_stack,
+            savepoints_counter: 0,
+            cursors: vec![],
+            sqlcommenter: None,
+            isolation_advisor: None,
+            column_codecs: ColumnCodecs::default(),
+            redaction_policy: None,
+            column_masks: ColumnMasks::default(),
+            transaction_pooling: false,
+            dry_run: false,
+            query_queue: QueryQueue::default(),
         }
     }
 
+    /// Set commenter for the outgoing queries.
+    #[must_use]
+    pub fn with_sqlcommenter(mut self, sqlcommenter: Option<SqlCommenter>) -> Self {
+        self.sqlcommenter = sqlcommenter;
+        self
+    }
+
+    /// Set advisor which reports statements needing stronger isolation level.
+    #[must_use]
+    pub fn with_isolation_advisor(mut self, isolation_advisor: Option<IsolationAdvisor>) -> Self {
+        self.isolation_advisor = isolation_advisor;
+        self
+    }
+
+    /// Set codecs of the encrypted columns.
+    #[must_use]
+    pub fn with_column_codecs(mut self, column_codecs: ColumnCodecs) -> Self {
+        self.column_codecs = column_codecs;
+        self
+    }
+
+    /// Set policy which hides sensitive parameters in the errors.
+    #[must_use]
+    pub fn with_redaction_policy(mut self, redaction_policy: Option<RedactionPolicy>) -> Self {
+        self.redaction_policy = redaction_policy;
+        self
+    }
+
+    /// Set masks of the sensitive columns in the results.
+    #[must_use]
+    pub fn with_column_masks(mut self, column_masks: ColumnMasks) -> Self {
+        self.column_masks = column_masks;
+        self
+    }
+
+    /// Reject statements which leave state in the session,
+    /// connection goes back to the pool after the transaction.
+    #[must_use]
+    pub fn with_transaction_pooling(mut self, transaction_pooling: bool) -> Self {
+        self.transaction_pooling = transaction_pooling;
+        self
+    }
+
+    /// Roll back every mutating statement to its savepoint.
+    #[must_use]
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Set limit of the statements executed by the pool connections at once.
+    #[must_use]
+    pub fn with_query_queue(mut self, query_queue: QueryQueue) -> Self {
+        self.query_queue = query_queue;
+        self
+    }
+
+    /// Set snapshot exported by another transaction to start with.
+    #[must_use]
+    pub fn with_snapshot(mut self, snapshot: Option<String>) -> Self {
+        self.snapshot = snapshot;
+        self
+    }
+
+    #[must_use]
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Check that transaction is ready and report statement
+    /// which needs stronger isolation level than the transaction has.
+    ///
+    /// # Errors
+    /// May return Err Result if transaction is not ready,
+    /// statement depends on the session in transaction pooling mode
+    /// or observer raised an exception.
+    fn check_statement(&self, gil: Python<'_>, querystring: &str) -> RustPSQLDriverPyResult<()> {
+        self.check_is_transaction_ready()?;
+        if self.transaction_pooling {
+            check_transaction_pooling(querystring, true)?;
+        }
+        IsolationAdvisor::check(
+            self.isolation_advisor.as_ref(),
+            gil,
+            querystring,
+            self.isolation_level,
+        )
+    }
+
+    /// Check that transaction is begun and isn't finished.
+    ///
+    /// # Errors
+    /// May return Err Result if transaction is not started,
+    /// already committed or rolled back.
     fn check_is_transaction_ready(&self) -> RustPSQLDriverPyResult<()> {
-        if !self.is_started {
-            return Err(RustPSQLDriverError::TransactionBeginError(
-                "Transaction is not started, please call begin() on transaction".into(),
-            ));
+        match self.state {
+            TransactionState::Begun => Ok(()),
+            TransactionState::Created => Err(RustPSQLDriverError::TransactionNotStartedError),
+            TransactionState::Committed => {
+                Err(RustPSQLDriverError::TransactionAlreadyCommittedError)
+            }
+            TransactionState::RolledBack => {
+                Err(RustPSQLDriverError::TransactionAlreadyRolledBackError)
+            }
         }
-        if self.is_done {
-            return Err(RustPSQLDriverError::TransactionBeginError(
-                "Transaction is already committed or rolled back".into(),
-            ));
+    }
+
+    /// Return connection for `COPY` statements of the begun transaction.
+    ///
+    /// # Errors
+    /// May return Err Result if transaction is not started or is done already.
+    fn copy_db_client(&self) -> RustPSQLDriverPyResult<Arc<PsqlpyConnection>> {
+        self.check_is_transaction_ready()?;
+        self.db_client
+            .clone()
+            .ok_or(RustPSQLDriverError::TransactionClosedError)
+    }
+
+    /// Check that transaction can be begun.
+    ///
+    /// # Errors
+    /// May return Err Result if transaction is already started,
+    /// committed or rolled back.
+    fn check_is_transaction_startable(&self) -> RustPSQLDriverPyResult<()> {
+        match self.state {
+            TransactionState::Created => Ok(()),
+            TransactionState::Begun => Err(RustPSQLDriverError::TransactionBeginError(
+                "Transaction is already started".into(),
+            )),
+            TransactionState::Committed => {
+                Err(RustPSQLDriverError::TransactionAlreadyCommittedError)
+            }
+            TransactionState::RolledBack => {
+                Err(RustPSQLDriverError::TransactionAlreadyRolledBackError)
+            }
         }
-        Ok(())
+    }
+
+    /// Validate user-provided savepoint name or generate a new unique one.
+    ///
+    /// # Errors
+    /// May return Err Result if savepoint name doesn't follow
+    /// `PostgreSQL` identifier rules.
+    fn prepare_savepoint_name(
+        &mut self,
+        savepoint_name: Option<String>,
+    ) -> RustPSQLDriverPyResult<String> {
+        if let Some(savepoint_name) = savepoint_name {
+            if !is_valid_ident(&savepoint_name) {
+                return Err(RustPSQLDriverError::TransactionSavepointError(format!(
+                    "Invalid SAVEPOINT name {savepoint_name}, it must start with a letter or an underscore, contain only letters, digits, underscores or dollar signs and be no longer than {MAX_IDENT_LENGTH} bytes",
+                )));
+            }
+            return Ok(savepoint_name);
+        }
+
+        loop {
+            self.savepoints_counter += 1;
+            let savepoint_name = format!("psqlpy_savepoint_{}", self.savepoints_counter);
+            if !self.savepoints_stack.contains(&savepoint_name) {
+                return Ok(savepoint_name);
+            }
+        }
+    }
+
+    /// Find position of the savepoint in the savepoints stack.
+    ///
+    /// # Errors
+    /// May return Err Result if there is no savepoint with this name.
+    fn savepoint_position(&self, savepoint_name: &str) -> RustPSQLDriverPyResult<usize> {
+        self.savepoints_stack
+            .iter()
+            .rposition(|name| name == savepoint_name)
+            .ok_or_else(|| {
+                RustPSQLDriverError::TransactionSavepointError(format!(
+                    "Don't have savepoint with name {savepoint_name}"
+                ))
+            })
+    }
+
+    /// Prepare transaction started by `COMMIT AND CHAIN` or `ROLLBACK AND CHAIN`.
+    ///
+    /// Chained transaction keeps isolation level, read variant and deferrable,
+    /// but savepoints and `SET LOCAL` settings are gone.
+    ///
+    /// # Errors
+    /// May return Err Result if cannot set `synchronous_commit` again.
+    async fn restart_chained(
+        &mut self,
+        db_client: &PsqlpyConnection,
+    ) -> RustPSQLDriverPyResult<()> {
+        self.savepoints_stack.clear();
+        db_client
+            .set_synchronous_commit(self.synchronous_commit)
+            .await
     }
 }
 
@@ -244,37 +550,27 @@ impl Transaction {
 
     async fn __aenter__<'a>(self_: Py<Self>) -> RustPSQLDriverPyResult<Py<Self>> {
         let (
-            is_started,
-            is_done,
+            is_transaction_startable,
             isolation_level,
             synchronous_commit,
             read_variant,
             deferrable,
+            snapshot,
             db_client,
         ) = pyo3::Python::with_gil(|gil| {
             let self_ = self_.borrow(gil);
             (
-                self_.is_started,
-                self_.is_done,
+                self_.check_is_transaction_startable(),
                 self_.isolation_level,
                 self_.synchronous_commit,
                 self_.read_variant,
                 self_.deferrable,
+                self_.snapshot.clone(),
                 self_.db_client.clone(),
             )
         });
 
-        if is_started {
-            return Err(RustPSQLDriverError::TransactionBeginError(
-                "Transaction is already started".into(),
-            ));
-        }
-
-        if is_done {
-            return Err(RustPSQLDriverError::TransactionBeginError(
-                "Transaction is already committed or rolled back".into(),
-            ));
-        }
+        is_transaction_startable?;
 
         if let Some(db_client) = db_client {
             db_client
@@ -283,12 +579,13 @@ impl Transaction {
                     read_variant,
                     deferrable,
                     synchronous_commit,
+                    snapshot,
                 )
                 .await?;
 
             Python::with_gil(|gil| {
                 let mut self_ = self_.borrow_mut(gil);
-                self_.is_started = true;
+                self_.state = TransactionState::Begun;
             });
             return Ok(self_);
         }
@@ -316,17 +613,20 @@ impl Transaction {
         is_transaction_ready?;
 
         if let Some(db_client) = db_client {
-            let exit_result = if is_exception_none {
-                db_client.commit().await?;
-                Ok(())
+            let (exit_result, state) = if is_exception_none {
+                db_client.commit(false).await?;
+                (Ok(()), TransactionState::Committed)
             } else {
-                db_client.rollback().await?;
-                Err(RustPSQLDriverError::RustPyError(py_err))
+                db_client.rollback(false).await?;
+                (
+                    Err(RustPSQLDriverError::RustPyError(py_err)),
+                    TransactionState::RolledBack,
+                )
             };
 
             pyo3::Python::with_gil(|gil| {
                 let mut self_ = self_.borrow_mut(gil);
-                self_.is_done = true;
+                self_.state = state;
                 std::mem::take(&mut self_.db_client);
             });
             return exit_result;
@@ -338,18 +638,26 @@ impl Transaction {
     /// Commit the transaction.
     ///
     /// Execute `COMMIT` command and mark transaction as `done`.
+    /// If `chain` is true, `COMMIT AND CHAIN` is executed instead,
+    /// new transaction with the same characteristics is started
+    /// and transaction stays ready.
     ///
     /// # Errors
     ///
     /// May return Err Result if:
     /// 1) Transaction is not started
-    /// 2) Transaction is done
+    /// 2) Transaction is already committed or rolled back
     /// 3) Cannot execute `COMMIT` command
-    pub async fn commit(&mut self) -> RustPSQLDriverPyResult<()> {
+    #[pyo3(signature = (chain=false))]
+    pub async fn commit(&mut self, chain: bool) -> RustPSQLDriverPyResult<()> {
         self.check_is_transaction_ready()?;
-        if let Some(db_client) = &self.db_client {
-            db_client.commit().await?;
-            self.is_done = true;
+        if let Some(db_client) = self.db_client.clone() {
+            db_client.commit(chain).await?;
+            if chain {
+                self.restart_chained(&db_client).await?;
+                return Ok(());
+            }
+            self.state = TransactionState::Committed;
             std::mem::take(&mut self.db_client);
             return Ok(());
         }
@@ -360,17 +668,25 @@ impl Transaction {
     /// Execute ROLLBACK command.
     ///
     /// Run ROLLBACK command and mark the transaction as done.
+    /// If `chain` is true, `ROLLBACK AND CHAIN` is executed instead,
+    /// new transaction with the same characteristics is started
+    /// and transaction stays ready.
     ///
     /// # Errors
     /// May return Err Result if:
     /// 1) Transaction is not started
-    /// 2) Transaction is done
+    /// 2) Transaction is already committed or rolled back
     /// 3) Can not execute ROLLBACK command
-    pub async fn rollback(&mut self) -> RustPSQLDriverPyResult<()> {
+    #[pyo3(signature = (chain=false))]
+    pub async fn rollback(&mut self, chain: bool) -> RustPSQLDriverPyResult<()> {
         self.check_is_transaction_ready()?;
-        if let Some(db_client) = &self.db_client {
-            db_client.rollback().await?;
-            self.is_done = true;
+        if let Some(db_client) = self.db_client.clone() {
+            db_client.rollback(chain).await?;
+            if chain {
+                self.restart_chained(&db_client).await?;
+                return Ok(());
+            }
+            self.state = TransactionState::RolledBack;
             std::mem::take(&mut self.db_client);
             return Ok(());
         }
@@ -388,20 +704,71 @@ impl Transaction {
     /// May return Err Result if:
     /// 1) Cannot convert python parameters
     /// 2) Cannot execute querystring.
-    #[pyo3(signature = (querystring, parameters=None, prepared=None))]
+    #[pyo3(signature = (
+        querystring,
+        parameters=None,
+        prepared=None,
+        hints=None,
+        comment=None,
+        row_factory=None,
+    ))]
     pub async fn execute(
         self_: Py<Self>,
         querystring: String,
         parameters: Option<pyo3::Py<PyAny>>,
         prepared: Option<bool>,
+        hints: Option<String>,
+        comment: Option<String>,
+        row_factory: Option<pyo3::Py<PyAny>>,
     ) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
-        let (is_transaction_ready, db_client) = pyo3::Python::with_gil(|gil| {
+        let (
+            is_transaction_ready,
+            db_client,
+            sqlcommenter,
+            column_codecs,
+            column_masks,
+            redaction_policy,
+            dry_run,
+            query_queue,
+        ) = pyo3::Python::with_gil(|gil| {
             let self_ = self_.borrow(gil);
-            (self_.check_is_transaction_ready(), self_.db_client.clone())
+            (
+                self_.check_statement(gil, &querystring),
+                self_.db_client.clone(),
+                self_.sqlcommenter.clone(),
+                self_.column_codecs.clone(),
+                self_.column_masks.clone(),
+                self_.redaction_policy.clone(),
+                self_.dry_run,
+                self_.query_queue.clone(),
+            )
         });
         is_transaction_ready?;
+        let (querystring, prepared) =
+            SqlCommenter::apply(sqlcommenter.as_ref(), querystring, prepared)?;
+        let (querystring, prepared) = annotate_query(querystring, hints, comment, prepared)?;
+        let rollback = dry_run_rollback(dry_run, &querystring)?;
+        let redact = RedactionPolicy::error_redactor(
+            redaction_policy.as_ref(),
+            &querystring,
+            parameters.as_ref(),
+        );
         if let Some(db_client) = db_client {
-            return db_client.execute(querystring, parameters, prepared).await;
+            return query_queue
+                .run(run_dry(
+                    &db_client,
+                    rollback,
+                    true,
+                    db_client.execute(querystring, parameters, prepared),
+                ))
+                .await
+                .map_err(redact)
+                .map(|result| {
+                    result
+                        .with_row_factory(row_factory)
+                        .with_column_codecs(column_codecs)
+                        .with_column_masks(column_masks)
+                });
         }
 
         Err(RustPSQLDriverError::TransactionClosedError)
@@ -420,13 +787,27 @@ impl Transaction {
     /// 1) Transaction is closed.
     /// 2) Cannot execute querystring.
     pub async fn execute_batch(self_: Py<Self>, querystring: String) -> RustPSQLDriverPyResult<()> {
-        let (is_transaction_ready, db_client) = pyo3::Python::with_gil(|gil| {
-            let self_ = self_.borrow(gil);
-            (self_.check_is_transaction_ready(), self_.db_client.clone())
-        });
+        let (is_transaction_ready, db_client, dry_run, query_queue) =
+            pyo3::Python::with_gil(|gil| {
+                let self_ = self_.borrow(gil);
+                (
+                    self_.check_statement(gil, &querystring),
+                    self_.db_client.clone(),
+                    self_.dry_run,
+                    self_.query_queue.clone(),
+                )
+            });
         is_transaction_ready?;
+        let rollback = dry_run_rollback(dry_run, &querystring)?;
         if let Some(db_client) = db_client {
-            return db_client.batch_execute(&querystring).await;
+            return query_queue
+                .run(run_dry(
+                    &db_client,
+                    rollback,
+                    true,
+                    db_client.batch_execute(&querystring),
+                ))
+                .await;
         }
 
         Err(RustPSQLDriverError::TransactionClosedError)
@@ -442,20 +823,71 @@ impl Transaction {
     /// May return Err Result if:
     /// 1) Cannot convert python parameters
     /// 2) Cannot execute querystring.
-    #[pyo3(signature = (querystring, parameters=None, prepared=None))]
+    #[pyo3(signature = (
+        querystring,
+        parameters=None,
+        prepared=None,
+        hints=None,
+        comment=None,
+        row_factory=None,
+    ))]
     pub async fn fetch(
         self_: Py<Self>,
         querystring: String,
         parameters: Option<pyo3::Py<PyAny>>,
         prepared: Option<bool>,
+        hints: Option<String>,
+        comment: Option<String>,
+        row_factory: Option<pyo3::Py<PyAny>>,
     ) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
-        let (is_transaction_ready, db_client) = pyo3::Python::with_gil(|gil| {
+        let (
+            is_transaction_ready,
+            db_client,
+            sqlcommenter,
+            column_codecs,
+            column_masks,
+            redaction_policy,
+            dry_run,
+            query_queue,
+        ) = pyo3::Python::with_gil(|gil| {
             let self_ = self_.borrow(gil);
-            (self_.check_is_transaction_ready(), self_.db_client.clone())
+            (
+                self_.check_statement(gil, &querystring),
+                self_.db_client.clone(),
+                self_.sqlcommenter.clone(),
+                self_.column_codecs.clone(),
+                self_.column_masks.clone(),
+                self_.redaction_policy.clone(),
+                self_.dry_run,
+                self_.query_queue.clone(),
+            )
         });
         is_transaction_ready?;
+        let (querystring, prepared) =
+            SqlCommenter::apply(sqlcommenter.as_ref(), querystring, prepared)?;
+        let (querystring, prepared) = annotate_query(querystring, hints, comment, prepared)?;
+        let rollback = dry_run_rollback(dry_run, &querystring)?;
+        let redact = RedactionPolicy::error_redactor(
+            redaction_policy.as_ref(),
+            &querystring,
+            parameters.as_ref(),
+        );
         if let Some(db_client) = db_client {
-            return db_client.execute(querystring, parameters, prepared).await;
+            return query_queue
+                .run(run_dry(
+                    &db_client,
+                    rollback,
+                    true,
+                    db_client.execute(querystring, parameters, prepared),
+                ))
+                .await
+                .map_err(redact)
+                .map(|result| {
+                    result
+                        .with_row_factory(row_factory)
+                        .with_column_codecs(column_codecs)
+                        .with_column_masks(column_masks)
+                });
         }
 
         Err(RustPSQLDriverError::TransactionClosedError)
@@ -475,21 +907,63 @@ impl Transaction {
     /// 3) Can not create/retrieve prepared statement
     /// 4) Can not execute statement
     /// 5) Query returns more than one row
-    #[pyo3(signature = (querystring, parameters=None, prepared=None))]
+    #[pyo3(signature = (querystring, parameters=None, prepared=None, hints=None, comment=None))]
     pub async fn fetch_row(
         self_: Py<Self>,
         querystring: String,
         parameters: Option<pyo3::Py<PyAny>>,
         prepared: Option<bool>,
+        hints: Option<String>,
+        comment: Option<String>,
     ) -> RustPSQLDriverPyResult<PSQLDriverSinglePyQueryResult> {
-        let (is_transaction_ready, db_client) = pyo3::Python::with_gil(|gil| {
+        let (
+            is_transaction_ready,
+            db_client,
+            sqlcommenter,
+            column_codecs,
+            column_masks,
+            redaction_policy,
+            dry_run,
+            query_queue,
+        ) = pyo3::Python::with_gil(|gil| {
             let self_ = self_.borrow(gil);
-            (self_.check_is_transaction_ready(), self_.db_client.clone())
+            (
+                self_.check_statement(gil, &querystring),
+                self_.db_client.clone(),
+                self_.sqlcommenter.clone(),
+                self_.column_codecs.clone(),
+                self_.column_masks.clone(),
+                self_.redaction_policy.clone(),
+                self_.dry_run,
+                self_.query_queue.clone(),
+            )
         });
         is_transaction_ready?;
+        let (querystring, prepared) =
+            SqlCommenter::apply(sqlcommenter.as_ref(), querystring, prepared)?;
+        let (querystring, prepared) = annotate_query(querystring, hints, comment, prepared)?;
+        let rollback = dry_run_rollback(dry_run, &querystring)?;
+        let redact = RedactionPolicy::error_redactor(
+            redaction_policy.as_ref(),
+            &querystring,
+            parameters.as_ref(),
+        );
 
         if let Some(db_client) = db_client {
-            return db_client.fetch_row(querystring, parameters, prepared).await;
+            return query_queue
+                .run(run_dry(
+                    &db_client,
+                    rollback,
+                    true,
+                    db_client.fetch_row(querystring, parameters, prepared),
+                ))
+                .await
+                .map_err(redact)
+                .map(|result| {
+                    result
+                        .with_column_codecs(column_codecs)
+                        .with_column_masks(column_masks)
+                });
         }
 
         Err(RustPSQLDriverError::TransactionClosedError)
@@ -505,20 +979,47 @@ impl Transaction {
     /// 1) Cannot convert python parameters
     /// 2) Cannot execute querystring.
     /// 3) Query returns more than one row
-    #[pyo3(signature = (querystring, parameters=None, prepared=None))]
+    #[pyo3(signature = (querystring, parameters=None, prepared=None, hints=None, comment=None))]
     pub async fn fetch_val(
         self_: Py<Self>,
         querystring: String,
         parameters: Option<pyo3::Py<PyAny>>,
         prepared: Option<bool>,
+        hints: Option<String>,
+        comment: Option<String>,
     ) -> RustPSQLDriverPyResult<Py<PyAny>> {
-        let (is_transaction_ready, db_client) = pyo3::Python::with_gil(|gil| {
-            let self_ = self_.borrow(gil);
-            (self_.check_is_transaction_ready(), self_.db_client.clone())
-        });
+        let (is_transaction_ready, db_client, sqlcommenter, redaction_policy, dry_run, query_queue) =
+            pyo3::Python::with_gil(|gil| {
+                let self_ = self_.borrow(gil);
+                (
+                    self_.check_statement(gil, &querystring),
+                    self_.db_client.clone(),
+                    self_.sqlcommenter.clone(),
+                    self_.redaction_policy.clone(),
+                    self_.dry_run,
+                    self_.query_queue.clone(),
+                )
+            });
         is_transaction_ready?;
+        let (querystring, prepared) =
+            SqlCommenter::apply(sqlcommenter.as_ref(), querystring, prepared)?;
+        let (querystring, prepared) = annotate_query(querystring, hints, comment, prepared)?;
+        let rollback = dry_run_rollback(dry_run, &querystring)?;
+        let redact = RedactionPolicy::error_redactor(
+            redaction_policy.as_ref(),
+            &querystring,
+            parameters.as_ref(),
+        );
         if let Some(db_client) = db_client {
-            return db_client.fetch_val(querystring, parameters, prepared).await;
+            return query_queue
+                .run(run_dry(
+                    &db_client,
+                    rollback,
+                    true,
+                    db_client.fetch_val(querystring, parameters, prepared),
+                ))
+                .await
+                .map_err(redact);
         }
 
         Err(RustPSQLDriverError::TransactionClosedError)
@@ -533,23 +1034,121 @@ impl Transaction {
     /// May return Err Result if:
     /// 1) Cannot convert python parameters
     /// 2) Cannot execute querystring.
-    #[pyo3(signature = (querystring, parameters=None, prepared=None))]
+    #[pyo3(signature = (querystring, parameters=None, prepared=None, hints=None, comment=None))]
     pub async fn execute_many(
         self_: Py<Self>,
         querystring: String,
         parameters: Option<Vec<Py<PyAny>>>,
         prepared: Option<bool>,
+        hints: Option<String>,
+        comment: Option<String>,
     ) -> RustPSQLDriverPyResult<()> {
+        let (is_transaction_ready, db_client, sqlcommenter, dry_run, query_queue) =
+            pyo3::Python::with_gil(|gil| {
+                let self_ = self_.borrow(gil);
+                (
+                    self_.check_statement(gil, &querystring),
+                    self_.db_client.clone(),
+                    self_.sqlcommenter.clone(),
+                    self_.dry_run,
+                    self_.query_queue.clone(),
+                )
+            });
+
+        is_transaction_ready?;
+        let (querystring, prepared) =
+            SqlCommenter::apply(sqlcommenter.as_ref(), querystring, prepared)?;
+        let (querystring, prepared) = annotate_query(querystring, hints, comment, prepared)?;
+        let rollback = dry_run_rollback(dry_run, &querystring)?;
+        if let Some(db_client) = db_client {
+            return query_queue
+                .run(run_dry(
+                    &db_client,
+                    rollback,
+                    true,
+                    db_client.execute_many(querystring, parameters, prepared),
+                ))
+                .await;
+        }
+
+        Err(RustPSQLDriverError::TransactionClosedError)
+    }
+
+    /// Lock rows of the table and return them.
+    ///
+    /// It's the job queue pattern: workers claim rows with
+    /// `SELECT ... FOR UPDATE SKIP LOCKED` and don't wait for each other.
+    /// Rows stay locked until the end of the transaction.
+    /// With `nowait` locked rows raise `LockNotAvailableError` instead.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Transaction is not ready
+    /// 2) Lock options are wrong
+    /// 3) Rows are locked by another transaction and `nowait` is set
+    /// 4) Cannot execute the query
+    #[pyo3(signature = (
+        table_name,
+        r#where=None,
+        limit=1,
+        lock=None,
+        skip_locked=None,
+        nowait=false,
+        returning=None,
+        order_by=None,
+        parameters=None,
+        schema_name=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn claim_rows(
+        self_: Py<Self>,
+        table_name: String,
+        r#where: Option<String>,
+        limit: usize,
+        lock: Option<String>,
+        skip_locked: Option<bool>,
+        nowait: bool,
+        returning: Option<Vec<String>>,
+        order_by: Option<String>,
+        parameters: Option<Py<PyAny>>,
+        schema_name: Option<String>,
+    ) -> RustPSQLDriverPyResult<PSQLDriverPyQueryResult> {
+        let dry_run = Python::with_gil(|gil| self_.borrow(gil).dry_run);
+        reject_dry_run(dry_run, "claim_rows")?;
+        let lock_clause =
+            row_lock_clause(lock.as_deref().unwrap_or("update"), skip_locked, nowait)?;
+
+        let mut table_name = quote_ident(&table_name);
+        if let Some(schema_name) = schema_name {
+            table_name = format!("{}.{}", quote_ident(&schema_name), table_name);
+        }
+        let returning = match returning {
+            Some(columns) => columns
+                .iter()
+                .map(|column| quote_ident(column))
+                .collect::<Vec<String>>()
+                .join(", "),
+            None => "*".into(),
+        };
+        let mut querystring = format!("SELECT {returning} FROM {table_name}");
+        if let Some(where_clause) = r#where {
+            querystring.push_str(&format!(" WHERE {where_clause}"));
+        }
+        if let Some(order_by) = order_by {
+            querystring.push_str(&format!(" ORDER BY {order_by}"));
+        }
+        querystring.push_str(&format!(" LIMIT {limit} {lock_clause}"));
+
         let (is_transaction_ready, db_client) = pyo3::Python::with_gil(|gil| {
             let self_ = self_.borrow(gil);
-            (self_.check_is_transaction_ready(), self_.db_client.clone())
+            (
+                self_.check_statement(gil, &querystring),
+                self_.db_client.clone(),
+            )
         });
-
         is_transaction_ready?;
         if let Some(db_client) = db_client {
-            return db_client
-                .execute_many(querystring, parameters, prepared)
-                .await;
+            return db_client.claim_rows(querystring, parameters).await;
         }
 
         Err(RustPSQLDriverError::TransactionClosedError)
@@ -562,54 +1161,46 @@ impl Transaction {
     ///
     /// May return Err Result if:
     /// 1) Transaction is already started.
-    /// 2) Transaction is done.
+    /// 2) Transaction is already committed or rolled back.
     /// 3) Cannot execute `BEGIN` command.
     pub async fn begin(self_: Py<Self>) -> RustPSQLDriverPyResult<()> {
         let (
-            is_started,
-            is_done,
+            is_transaction_startable,
             isolation_level,
             synchronous_commit,
             read_variant,
             deferrable,
+            snapshot,
             db_client,
         ) = pyo3::Python::with_gil(|gil| {
             let self_ = self_.borrow(gil);
             (
-                self_.is_started,
-                self_.is_done,
+                self_.check_is_transaction_startable(),
                 self_.isolation_level,
                 self_.synchronous_commit,
                 self_.read_variant,
                 self_.deferrable,
+                self_.snapshot.clone(),
                 self_.db_client.clone(),
             )
         });
 
-        if let Some(db_client) = db_client {
-            if is_started {
-                return Err(RustPSQLDriverError::TransactionBeginError(
-                    "Transaction is already started".into(),
-                ));
-            }
+        is_transaction_startable?;
 
-            if is_done {
-                return Err(RustPSQLDriverError::TransactionBeginError(
-                    "Transaction is already committed or rolled back".into(),
-                ));
-            }
+        if let Some(db_client) = db_client {
             db_client
                 .start_transaction(
                     isolation_level,
                     read_variant,
                     deferrable,
                     synchronous_commit,
+                    snapshot,
                 )
                 .await?;
 
             pyo3::Python::with_gil(|gil| {
                 let mut self_ = self_.borrow_mut(gil);
-                self_.is_started = true;
+                self_.state = TransactionState::Begun;
             });
 
             return Ok(());
@@ -618,47 +1209,87 @@ impl Transaction {
         Err(RustPSQLDriverError::TransactionClosedError)
     }
 
+    /// Export snapshot of the transaction.
+    ///
+    /// Other transactions can start with the same snapshot
+    /// and see exactly the same data, until this transaction ends.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Transaction is not started or already finished
+    /// 2) Cannot execute `pg_export_snapshot`
+    pub async fn export_snapshot(self_: Py<Self>) -> RustPSQLDriverPyResult<String> {
+        let (is_transaction_ready, db_client) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (self_.check_is_transaction_ready(), self_.db_client.clone())
+        });
+        is_transaction_ready?;
+
+        if let Some(db_client) = db_client {
+            let row = db_client
+                .query_one("SELECT pg_export_snapshot()", &[])
+                .await
+                .map_err(|err| {
+                    RustPSQLDriverError::TransactionExecuteError(format!(
+                        "Cannot export snapshot, error - {err}"
+                    ))
+                })?;
+            return Ok(row.try_get::<_, String>(0)?);
+        }
+
+        Err(RustPSQLDriverError::TransactionClosedError)
+    }
+
     /// Create new SAVEPOINT.
     ///
     /// Execute SAVEPOINT <name of the savepoint> and
-    /// add it to the transaction `rollback_savepoint` `HashSet`
+    /// push it to the transaction savepoints stack.
+    /// If name isn't specified, new unique name is generated.
     ///
     /// # Errors
     /// May return Err Result if:
     /// 1) Transaction is not started
     /// 2) Transaction is done
-    /// 3) Specified savepoint name is exists
-    /// 4) Can not execute SAVEPOINT command
+    /// 3) Specified savepoint name is invalid
+    /// 4) Specified savepoint name is exists
+    /// 5) Can not execute SAVEPOINT command
+    #[pyo3(signature = (savepoint_name=None))]
     pub async fn create_savepoint(
         self_: Py<Self>,
-        savepoint_name: String,
-    ) -> RustPSQLDriverPyResult<()> {
-        let (is_transaction_ready, is_savepoint_name_exists, db_client) =
-            pyo3::Python::with_gil(|gil| {
-                let self_ = self_.borrow(gil);
-                (
-                    self_.check_is_transaction_ready(),
-                    self_.savepoints_map.contains(&savepoint_name),
-                    self_.db_client.clone(),
-                )
-            });
+        savepoint_name: Option<String>,
+    ) -> RustPSQLDriverPyResult<String> {
+        let (is_transaction_ready, savepoint_name, db_client) = pyo3::Python::with_gil(|gil| {
+            let mut self_ = self_.borrow_mut(gil);
+            (
+                self_.check_is_transaction_ready(),
+                self_.prepare_savepoint_name(savepoint_name),
+                self_.db_client.clone(),
+            )
+        });
 
+        is_transaction_ready?;
         if let Some(db_client) = db_client {
-            is_transaction_ready?;
+            let savepoint_name = savepoint_name?;
 
+            let is_savepoint_name_exists = pyo3::Python::with_gil(|gil| {
+                self_.borrow(gil).savepoints_stack.contains(&savepoint_name)
+            });
             if is_savepoint_name_exists {
                 return Err(RustPSQLDriverError::TransactionSavepointError(format!(
                     "SAVEPOINT name {savepoint_name} is already taken by this transaction",
                 )));
             }
             db_client
-                .batch_execute(format!("SAVEPOINT {savepoint_name}").as_str())
+                .batch_execute(format!("SAVEPOINT {}", quote_ident(&savepoint_name)).as_str())
                 .await?;
 
             pyo3::Python::with_gil(|gil| {
-                self_.borrow_mut(gil).savepoints_map.insert(savepoint_name);
+                self_
+                    .borrow_mut(gil)
+                    .savepoints_stack
+                    .push(savepoint_name.clone());
             });
-            return Ok(());
+            return Ok(savepoint_name);
         }
 
         Err(RustPSQLDriverError::TransactionClosedError)
@@ -666,6 +1297,8 @@ impl Transaction {
     /// Execute RELEASE SAVEPOINT.
     ///
     /// Run RELEASE SAVEPOINT command.
+    /// Savepoint and all savepoints created after it
+    /// are removed from the savepoints stack.
     ///
     /// # Errors
     /// May return Err Result if:
@@ -677,30 +1310,30 @@ impl Transaction {
         self_: Py<Self>,
         savepoint_name: String,
     ) -> RustPSQLDriverPyResult<()> {
-        let (is_transaction_ready, is_savepoint_name_exists, db_client) =
-            pyo3::Python::with_gil(|gil| {
-                let self_ = self_.borrow(gil);
-                (
-                    self_.check_is_transaction_ready(),
-                    self_.savepoints_map.contains(&savepoint_name),
-                    self_.db_client.clone(),
-                )
-            });
+        let (is_transaction_ready, savepoint_position, db_client) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (
+                self_.check_is_transaction_ready(),
+                self_.savepoint_position(&savepoint_name),
+                self_.db_client.clone(),
+            )
+        });
 
+        is_transaction_ready?;
         if let Some(db_client) = db_client {
-            is_transaction_ready?;
+            let savepoint_position = savepoint_position?;
 
-            if !is_savepoint_name_exists {
-                return Err(RustPSQLDriverError::TransactionSavepointError(
-                    "Don't have rollback with this name".into(),
-                ));
-            }
             db_client
-                .batch_execute(format!("RELEASE SAVEPOINT {savepoint_name}").as_str())
+                .batch_execute(
+                    format!("RELEASE SAVEPOINT {}", quote_ident(&savepoint_name)).as_str(),
+                )
                 .await?;
 
             pyo3::Python::with_gil(|gil| {
-                self_.borrow_mut(gil).savepoints_map.remove(&savepoint_name);
+                self_
+                    .borrow_mut(gil)
+                    .savepoints_stack
+                    .truncate(savepoint_position);
             });
             return Ok(());
         }
@@ -710,6 +1343,8 @@ impl Transaction {
     /// ROLLBACK to the specified savepoint
     ///
     /// Execute ROLLBACK TO SAVEPOINT <name of the savepoint>.
+    /// Savepoint stays valid, but all savepoints created after it
+    /// are removed from the savepoints stack.
     ///
     /// # Errors
     /// May return Err Result if:
@@ -721,36 +1356,62 @@ impl Transaction {
         self_: Py<Self>,
         savepoint_name: String,
     ) -> RustPSQLDriverPyResult<()> {
-        let (is_transaction_ready, is_savepoint_name_exists, db_client) =
-            pyo3::Python::with_gil(|gil| {
-                let self_ = self_.borrow(gil);
-                (
-                    self_.check_is_transaction_ready(),
-                    self_.savepoints_map.contains(&savepoint_name),
-                    self_.db_client.clone(),
-                )
-            });
+        let (is_transaction_ready, savepoint_position, db_client) = pyo3::Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (
+                self_.check_is_transaction_ready(),
+                self_.savepoint_position(&savepoint_name),
+                self_.db_client.clone(),
+            )
+        });
 
+        is_transaction_ready?;
         if let Some(db_client) = db_client {
-            is_transaction_ready?;
+            let savepoint_position = savepoint_position?;
 
-            if !is_savepoint_name_exists {
-                return Err(RustPSQLDriverError::TransactionSavepointError(
-                    "Don't have rollback with this name".into(),
-                ));
-            }
             db_client
-                .batch_execute(format!("ROLLBACK TO SAVEPOINT {savepoint_name}").as_str())
+                .batch_execute(
+                    format!("ROLLBACK TO SAVEPOINT {}", quote_ident(&savepoint_name)).as_str(),
+                )
                 .await?;
 
             pyo3::Python::with_gil(|gil| {
-                self_.borrow_mut(gil).savepoints_map.remove(&savepoint_name);
+                self_
+                    .borrow_mut(gil)
+                    .savepoints_stack
+                    .truncate(savepoint_position + 1);
             });
             return Ok(());
         }
 
         Err(RustPSQLDriverError::TransactionClosedError)
     }
+
+    /// Return names of the active savepoints.
+    ///
+    /// The first one is the oldest savepoint.
+    #[must_use]
+    pub fn savepoints(&self) -> Vec<String> {
+        self.savepoints_stack.clone()
+    }
+
+    /// Return async context manager of the savepoint.
+    ///
+    /// Savepoint is released if the block succeeds
+    /// and rolled back to if it raises.
+    #[must_use]
+    #[pyo3(signature = (savepoint_name=None))]
+    pub fn savepoint(self_: Py<Self>, savepoint_name: Option<String>) -> Savepoint {
+        Savepoint::new(self_, savepoint_name)
+    }
+
+    /// Return nested transaction.
+    ///
+    /// Nested transaction is a savepoint with the generated name.
+    #[must_use]
+    pub fn transaction(self_: Py<Self>) -> Savepoint {
+        Savepoint::new(self_, None)
+    }
     /// Execute querystrings with parameters and return all results.
     ///
     /// Create pipeline of queries.
@@ -766,13 +1427,18 @@ impl Transaction {
         queries: Option<Py<PyList>>,
         prepared: Option<bool>,
     ) -> RustPSQLDriverPyResult<Vec<PSQLDriverPyQueryResult>> {
-        let (is_transaction_ready, db_client) = pyo3::Python::with_gil(|gil| {
+        let (is_transaction_ready, db_client, dry_run) = pyo3::Python::with_gil(|gil| {
             let self_ = self_.borrow(gil);
 
-            (self_.check_is_transaction_ready(), self_.db_client.clone())
+            (
+                self_.check_is_transaction_ready(),
+                self_.db_client.clone(),
+                self_.dry_run,
+            )
         });
 
         is_transaction_ready?;
+        reject_dry_run(dry_run, "pipeline")?;
 
         if let Some(db_client) = db_client {
             let mut futures = vec![];
@@ -811,94 +1477,409 @@ impl Transaction {
 
     /// Create new cursor object.
     ///
+    /// Created cursor is remembered by the transaction,
+    /// so it can be found later with `cursors()`.
+    ///
     /// # Errors
-    /// May return Err Result if db_client is None
+    /// May return Err Result if:
+    /// 1) Transaction is not started
+    /// 2) Transaction is already committed or rolled back
     #[pyo3(signature = (
         querystring,
         parameters=None,
         fetch_number=None,
         scroll=None,
         prepared=None,
+        cursor_name=None,
+        hold=None,
     ))]
+    #[allow(clippy::too_many_arguments)]
     pub fn cursor(
-        &self,
+        &mut self,
+        py: Python<'_>,
         querystring: String,
         parameters: Option<Py<PyAny>>,
         fetch_number: Option<usize>,
         scroll: Option<bool>,
         prepared: Option<bool>,
-    ) -> RustPSQLDriverPyResult<Cursor> {
+        cursor_name: Option<String>,
+        hold: Option<bool>,
+    ) -> RustPSQLDriverPyResult<Py<Cursor>> {
+        self.check_is_transaction_ready()?;
+        if dry_run_rollback(self.dry_run, &querystring)? {
+            reject_dry_run(true, "cursor with a mutating query")?;
+        }
         if let Some(db_client) = &self.db_client {
-            return Ok(Cursor::new(
-                db_client.clone(),
-                self.pg_config.clone(),
-                querystring,
-                parameters,
-                "cur_name".into(),
-                fetch_number.unwrap_or(10),
-                scroll,
-                prepared,
-            ));
+            let cursor = Py::new(
+                py,
+                Cursor::new(
+                    db_client.clone(),
+                    self.pg_config.clone(),
+                    querystring,
+                    parameters,
+                    prepare_cursor_name(cursor_name)?,
+                    fetch_number.unwrap_or(10),
+                    scroll,
+                    hold,
+                    prepared,
+                ),
+            )?;
+            // Closed cursors are forgotten, so the list doesn't grow with every cursor.
+            self.cursors.retain(|cursor| {
+                cursor
+                    .try_borrow(py)
+                    .map_or(true, |cursor| !cursor.is_closed())
+            });
+            self.cursors.push(cursor.clone_ref(py));
+            return Ok(cursor);
         }
 
         Err(RustPSQLDriverError::TransactionClosedError)
     }
 
+    /// Create statement which rows are returned by portions with a cursor.
+    ///
+    /// Rows are returned by `execute_partial` and `resume`
+    /// in portions of `max_rows`.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Transaction is not started
+    /// 2) Transaction is already committed or rolled back
+    #[pyo3(signature = (querystring, parameters=None, prepared=None))]
+    pub fn statement(
+        &self,
+        querystring: String,
+        parameters: Option<Py<PyAny>>,
+        prepared: Option<bool>,
+    ) -> RustPSQLDriverPyResult<PartialStatement> {
+        self.check_is_transaction_ready()?;
+        if dry_run_rollback(self.dry_run, &querystring)? {
+            reject_dry_run(true, "statement with a mutating query")?;
+        }
+        let Some(db_client) = &self.db_client else {
+            return Err(RustPSQLDriverError::TransactionClosedError);
+        };
+        Ok(PartialStatement::new(
+            db_client.clone(),
+            querystring,
+            parameters,
+            prepared,
+        ))
+    }
+
+    /// Return all open cursors created by this transaction.
+    ///
+    /// Cursor is open if it was started and wasn't closed yet.
+    /// Cursors that are busy right now are considered open.
+    #[must_use]
+    pub fn cursors(&self, py: Python<'_>) -> Vec<Py<Cursor>> {
+        self.cursors
+            .iter()
+            .filter(|cursor| {
+                cursor
+                    .try_borrow(py)
+                    .map_or(true, |cursor| cursor.is_open())
+            })
+            .map(|cursor| cursor.clone_ref(py))
+            .collect()
+    }
+
     /// Perform binary copy to postgres table.
     ///
+    /// If `validate` is true, header, trailer and number of fields
+    /// in every row are checked against the columns of the table
+    /// before the data is sent.
+    ///
     /// # Errors
     /// May return Err Result if cannot get bytes,
+    /// data is invalid,
     /// cannot perform request to the database,
     /// cannot write bytes to the database.
-    #[pyo3(signature = (source, table_name, columns=None, schema_name=None))]
+    #[pyo3(signature = (source, table_name, columns=None, schema_name=None, validate=false))]
     pub async fn binary_copy_to_table(
         self_: pyo3::Py<Self>,
         source: Py<PyAny>,
         table_name: String,
         columns: Option<Vec<String>>,
         schema_name: Option<String>,
+        validate: bool,
     ) -> RustPSQLDriverPyResult<u64> {
-        let db_client = pyo3::Python::with_gil(|gil| self_.borrow(gil).db_client.clone());
-        let mut table_name = quote_ident(&table_name);
-        if let Some(schema_name) = schema_name {
-            table_name = format!("{}.{}", quote_ident(&schema_name), table_name);
+        let (db_client, dry_run) = Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (self_.copy_db_client(), self_.dry_run)
+        });
+        let db_client = db_client?;
+        let copy_table = copy_target(&table_name, None, schema_name.clone());
+        let validate_columns = columns.clone().filter(|_| validate);
+        let copy_target = copy_target(&table_name, columns, schema_name);
+        let copy_qs = format!("COPY {copy_target} FROM STDIN (FORMAT binary)");
+
+        let mut psql_bytes = Python::with_gil(|gil| copy_source_bytes(gil, &source))?;
+        if validate {
+            validate_binary_copy(
+                &db_client,
+                &copy_table,
+                validate_columns.as_deref(),
+                &psql_bytes,
+            )
+            .await?;
         }
+        run_dry(&db_client, dry_run, true, async {
+            let sink = db_client.copy_in(&copy_qs).await?;
+            let writer = BinaryCopyInWriter::new_empty_buffer(sink, &[]);
+            pin_mut!(writer);
+            writer.as_mut().write_raw_bytes(&mut psql_bytes).await?;
+            let rows_created = writer.as_mut().finish_empty().await?;
+            Ok(rows_created)
+        })
+        .await
+    }
 
-        let mut formated_columns = String::default();
-        if let Some(columns) = columns {
-            formated_columns = format!("({})", columns.join(", "));
-        }
+    /// Perform `CSV` or `TEXT` copy to postgres table.
+    ///
+    /// Rows are rolled back together with the transaction.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Transaction is not started or is done already
+    /// 2) Cannot get bytes from `source`
+    /// 3) Options cannot be used with the format
+    /// 4) Cannot execute copy statement or data is invalid
+    #[pyo3(signature = (
+        source,
+        table_name,
+        columns=None,
+        schema_name=None,
+        format=CopyCommandFormat::CSV,
+        delimiter=None,
+        null=None,
+        header=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn copy_to_table(
+        self_: pyo3::Py<Self>,
+        source: Py<PyAny>,
+        table_name: String,
+        columns: Option<Vec<String>>,
+        schema_name: Option<String>,
+        format: CopyCommandFormat,
+        delimiter: Option<String>,
+        null: Option<String>,
+        header: Option<bool>,
+    ) -> RustPSQLDriverPyResult<u64> {
+        let (db_client, dry_run) = Python::with_gil(|gil| {
+            let self_ = self_.borrow(gil);
+            (self_.copy_db_client(), self_.dry_run)
+        });
+        let db_client = db_client?;
+        let copy_options = format.copy_options(delimiter.as_deref(), null.as_deref(), header)?;
+        let copy_target = copy_target(&table_name, columns, schema_name);
+        let copy_qs = format!("COPY {copy_target} FROM STDIN ({copy_options})");
 
-        let copy_qs = format!("COPY {table_name}{formated_columns} FROM STDIN (FORMAT binary)");
+        let psql_bytes = Python::with_gil(|gil| copy_source_bytes(gil, &source))?;
+        run_dry(&db_client, dry_run, true, async {
+            let sink = db_client.copy_in(&copy_qs).await?;
+            pin_mut!(sink);
+            sink.send(psql_bytes.freeze()).await?;
+            Ok(sink.as_mut().finish().await?)
+        })
+        .await
+    }
 
-        if let Some(db_client) = db_client {
-            let mut psql_bytes: BytesMut = Python::with_gil(|gil| {
-                let possible_py_buffer: Result<PyBuffer<u8>, PyErr> =
-                    source.extract::<PyBuffer<u8>>(gil);
-                if let Ok(py_buffer) = possible_py_buffer {
-                    let vec_buf = py_buffer.to_vec(gil)?;
-                    return Ok(BytesMut::from(vec_buf.as_slice()));
-                }
+    /// Copy records into the table with binary `COPY`.
+    ///
+    /// Records are lists or tuples of values in the order of `columns`,
+    /// values are encoded for the column types.
+    /// Types are taken from the table if `types` aren't passed.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Transaction is not started or is done already
+    /// 2) Table, columns or types don't exist
+    /// 3) Records have different number of values than columns
+    /// 4) Cannot convert python values
+    /// 5) Cannot execute copy statement
+    #[pyo3(signature = (
+        table_name,
+        records,
+        columns=None,
+        types=None,
+        schema_name=None,
+    ))]
+    pub async fn copy_records_to_table(
+        self_: pyo3::Py<Self>,
+        table_name: String,
+        records: Vec<Py<PyAny>>,
+        columns: Option<Vec<String>>,
+        types: Option<Vec<String>>,
+        schema_name: Option<String>,
+    ) -> RustPSQLDriverPyResult<u64> {
+        let (is_transaction_ready, db_client, column_codecs, dry_run) =
+            pyo3::Python::with_gil(|gil| {
+                let self_ = self_.borrow(gil);
+                (
+                    self_.check_is_transaction_ready(),
+                    self_.db_client.clone(),
+                    self_.column_codecs.clone(),
+                    self_.dry_run,
+                )
+            });
+        is_transaction_ready?;
+        let Some(db_client) = db_client else {
+            return Err(RustPSQLDriverError::TransactionClosedError);
+        };
 
-                if let Ok(py_bytes) = source.call_method0(gil, "getvalue") {
-                    if let Ok(bytes) = py_bytes.extract::<Vec<u8>>(gil) {
-                        return Ok(BytesMut::from(bytes.as_slice()));
-                    }
-                }
+        let table = copy_target(&table_name, None, schema_name);
+        run_dry(
+            &db_client,
+            dry_run,
+            true,
+            db_client.copy_records(&table, columns, types, records, &column_codecs),
+        )
+        .await
+    }
 
-                Err(RustPSQLDriverError::PyToRustValueConversionError(
-                    "source must be bytes or support Buffer protocol".into(),
-                ))
-            })?;
+    /// Perform binary copy from PostgreSQL.
+    ///
+    /// Data changed by the transaction is copied too.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Transaction is not started or is done already
+    /// 2) Cannot execute copy statement
+    #[pyo3(signature = (table_name, columns=None, schema_name=None))]
+    pub async fn binary_copy_from_table(
+        self_: pyo3::Py<Self>,
+        table_name: String,
+        columns: Option<Vec<String>>,
+        schema_name: Option<String>,
+    ) -> RustPSQLDriverPyResult<Py<PyBytes>> {
+        let db_client = Python::with_gil(|gil| self_.borrow(gil).copy_db_client())?;
+        let copy_target = copy_target(&table_name, columns, schema_name);
+        let copy_qs = format!("COPY {copy_target} TO STDOUT (FORMAT binary)");
+
+        let psql_bytes = collect_copy_out(&db_client, &copy_qs).await?;
+        Ok(Python::with_gil(|gil| {
+            PyBytes::new(gil, &psql_bytes).unbind()
+        }))
+    }
 
-            let sink = db_client.copy_in(&copy_qs).await?;
-            let writer = BinaryCopyInWriter::new_empty_buffer(sink, &[]);
-            pin_mut!(writer);
-            writer.as_mut().write_raw_bytes(&mut psql_bytes).await?;
-            let rows_created = writer.as_mut().finish_empty().await?;
-            return Ok(rows_created);
-        }
+    /// Perform `CSV` or `TEXT` copy from PostgreSQL.
+    ///
+    /// Data changed by the transaction is copied too.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Transaction is not started or is done already
+    /// 2) Options cannot be used with the format
+    /// 3) Cannot execute copy statement
+    #[pyo3(signature = (
+        table_name,
+        columns=None,
+        schema_name=None,
+        format=CopyCommandFormat::CSV,
+        delimiter=None,
+        null=None,
+        header=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn copy_from_table(
+        self_: pyo3::Py<Self>,
+        table_name: String,
+        columns: Option<Vec<String>>,
+        schema_name: Option<String>,
+        format: CopyCommandFormat,
+        delimiter: Option<String>,
+        null: Option<String>,
+        header: Option<bool>,
+    ) -> RustPSQLDriverPyResult<Py<PyBytes>> {
+        let db_client = Python::with_gil(|gil| self_.borrow(gil).copy_db_client())?;
+        let copy_options = format.copy_options(delimiter.as_deref(), null.as_deref(), header)?;
+        let copy_target = copy_target(&table_name, columns, schema_name);
+        let copy_qs = format!("COPY {copy_target} TO STDOUT ({copy_options})");
+
+        let psql_bytes = collect_copy_out(&db_client, &copy_qs).await?;
+        Ok(Python::with_gil(|gil| {
+            PyBytes::new(gil, &psql_bytes).unbind()
+        }))
+    }
+
+    /// Perform `CSV` or `TEXT` copy from PostgreSQL chunk by chunk.
+    ///
+    /// Transaction is busy until the iteration is over.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Transaction is not started or is done already
+    /// 2) Options cannot be used with the format
+    /// 3) Cannot execute copy statement
+    #[pyo3(signature = (
+        table_name,
+        columns=None,
+        schema_name=None,
+        format=CopyCommandFormat::CSV,
+        delimiter=None,
+        null=None,
+        header=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn copy_from_table_chunks(
+        self_: pyo3::Py<Self>,
+        table_name: String,
+        columns: Option<Vec<String>>,
+        schema_name: Option<String>,
+        format: CopyCommandFormat,
+        delimiter: Option<String>,
+        null: Option<String>,
+        header: Option<bool>,
+    ) -> RustPSQLDriverPyResult<CopyOutChunks> {
+        let db_client = Python::with_gil(|gil| self_.borrow(gil).copy_db_client())?;
+        let copy_options = format.copy_options(delimiter.as_deref(), null.as_deref(), header)?;
+        let copy_target = copy_target(&table_name, columns, schema_name);
+        let copy_qs = format!("COPY {copy_target} TO STDOUT ({copy_options})");
+
+        let copy_stream = db_client.copy_out(&copy_qs).await?;
+        Ok(CopyOutChunks::new(copy_stream, db_client))
+    }
+
+    /// Perform binary copy from PostgreSQL chunk by chunk.
+    ///
+    /// Transaction is busy until the iteration is over.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Transaction is not started or is done already
+    /// 2) Cannot execute copy statement
+    #[pyo3(signature = (table_name, columns=None, schema_name=None))]
+    pub async fn binary_copy_from_table_chunks(
+        self_: pyo3::Py<Self>,
+        table_name: String,
+        columns: Option<Vec<String>>,
+        schema_name: Option<String>,
+    ) -> RustPSQLDriverPyResult<CopyOutChunks> {
+        let db_client = Python::with_gil(|gil| self_.borrow(gil).copy_db_client())?;
+        let copy_target = copy_target(&table_name, columns, schema_name);
+        let copy_qs = format!("COPY {copy_target} TO STDOUT (FORMAT binary)");
+
+        let copy_stream = db_client.copy_out(&copy_qs).await?;
+        Ok(CopyOutChunks::new(copy_stream, db_client))
+    }
+}
 
-        Ok(0)
+/// Execute `COPY ... TO STDOUT` statement and collect all the data.
+///
+/// # Errors
+/// May return Err Result if cannot execute copy statement.
+async fn collect_copy_out(
+    db_client: &PsqlpyConnection,
+    copy_qs: &str,
+) -> RustPSQLDriverPyResult<BytesMut> {
+    let copy_stream = db_client.copy_out(copy_qs).await?;
+    pin_mut!(copy_stream);
+    let mut psql_bytes = BytesMut::new();
+    while let Some(chunk) = copy_stream.next().await {
+        psql_bytes.extend_from_slice(&chunk?);
     }
+    Ok(psql_bytes)
 }
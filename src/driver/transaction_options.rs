@@ -1,7 +1,8 @@
 use pyo3::pyclass;
 
+/// Isolation level, from the weakest to the strongest.
 #[pyclass(eq, eq_int)]
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum IsolationLevel {
     ReadUncommitted,
     ReadCommitted,
@@ -1,14 +1,45 @@
-use std::{str::FromStr, time::Duration};
-
-use deadpool_postgres::{Manager, ManagerConfig};
-use openssl::ssl::{SslConnector, SslMethod, SslVerifyMode};
-use postgres_openssl::MakeTlsConnector;
+use std::{
+    collections::HashMap,
+    future::{poll_fn, Future},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use deadpool_postgres::{Connect, Manager, ManagerConfig};
+use futures::future::BoxFuture;
+use openssl::ssl::{SslConnector, SslFiletype, SslMethod, SslVerifyMode};
+use postgres_openssl::{MakeTlsConnector, TlsStream};
 use pyo3::{types::PyAnyMethods, Py, PyAny, Python};
-use tokio_postgres::{Config, NoTls};
-
-use crate::exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult};
-
-use super::common_options::{self, LoadBalanceHosts, SslMode, TargetSessionAttrs};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::TcpStream,
+    sync::Semaphore,
+    task::JoinHandle,
+};
+use tokio_postgres::{
+    config::Host, tls::MakeTlsConnect, AsyncMessage, Client, Config, Connection, NoTls,
+    SimpleQueryMessage, Socket,
+};
+
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    runtime::tokio_runtime,
+};
+
+use super::{
+    common_options::{LoadBalanceHosts, SslMode, TargetSessionAttrs},
+    connection_factory::ConnectionFactory,
+    dsn::parse_dsn,
+    notices::{register_connection, NoticeCounter},
+    proxy::Proxy,
+    resolver::HostResolver,
+    ssh_tunnel::SshTunnel,
+    ssl_info::{capture_peer_certificate, register_peer_certificate, with_peer_certificate},
+};
+
+const DEFAULT_PORT: u16 = 5432;
 
 /// Create new config.
 ///
@@ -68,11 +99,7 @@ pub fn build_connection_config(
     let mut pg_config: tokio_postgres::Config;
 
     if let Some(dsn_string) = dsn {
-        pg_config = tokio_postgres::Config::from_str(&dsn_string).map_err(|err| {
-            RustPSQLDriverError::ConnectionPoolBuildError(format!(
-                "Cannot parse configuration from dsn string, error - {err}"
-            ))
-        })?;
+        pg_config = parse_dsn(&dsn_string)?.config;
     } else {
         pg_config = tokio_postgres::Config::new();
 
@@ -170,36 +197,124 @@ pub fn build_connection_config(
     Ok(pg_config)
 }
 
+/// Add session parameters to the startup `options` of the config,
+/// so every new connection has them before the first statement.
+///
+/// Parameters are added after `options` which were set before,
+/// spaces and backslashes of the values are escaped.
+///
+/// # Errors
+/// May return Err Result if the name of the parameter is empty
+/// or contains whitespaces or `=`.
+pub fn apply_server_settings(
+    pg_config: &mut Config,
+    server_settings: &HashMap<String, String>,
+) -> RustPSQLDriverPyResult<()> {
+    let mut server_settings: Vec<_> = server_settings.iter().collect();
+    server_settings.sort();
+
+    let mut options: Vec<String> = pg_config
+        .get_options()
+        .map(String::from)
+        .into_iter()
+        .collect();
+    for (name, value) in server_settings {
+        if name.is_empty()
+            || name.contains(|character: char| character.is_whitespace() || character == '=')
+        {
+            return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                format!("Invalid server setting name {name:?}"),
+            ));
+        }
+        let value = value.replace('\\', "\\\\").replace(' ', "\\ ");
+        options.push(format!("-c {name}={value}"));
+    }
+    pg_config.options(&options.join(" "));
+    Ok(())
+}
+
+#[derive(Clone)]
 pub enum ConfiguredTLS {
     NoTls,
     TlsConnector(MakeTlsConnector),
 }
 
+/// Files and checks of the TLS connection.
+#[derive(Clone, Default)]
+pub struct TlsOptions {
+    /// Root certificate to verify the server with.
+    pub ca_file: Option<String>,
+    /// Client certificate chain in PEM format.
+    pub ssl_cert: Option<String>,
+    /// Private key of the client certificate in PEM format.
+    pub ssl_key: Option<String>,
+    /// Check that server certificate matches the host,
+    /// defaults to false for `VerifyCa` and true otherwise.
+    pub verify_hostname: Option<bool>,
+}
+
 /// Create TLS.
 ///
+/// Server certificate is verified if `ca_file` is passed or
+/// `ssl_mode` is `VerifyCa`/`VerifyFull`, system root certificates are
+/// used without `ca_file`. `Allow`, `Prefer` and `Require` without `ca_file`
+/// encrypt the connection without verification, like `libpq` does.
+///
 /// # Errors
-/// May return Err Result if cannot create builder.
+/// May return Err Result if:
+/// 1) Only one of `ssl_cert` and `ssl_key` is passed
+/// 2) Cannot create builder or load the files
 pub fn build_tls(
-    ca_file: &Option<String>,
+    tls_options: &TlsOptions,
     ssl_mode: &Option<SslMode>,
 ) -> RustPSQLDriverPyResult<ConfiguredTLS> {
-    if let Some(ca_file) = ca_file {
-        let mut builder = SslConnector::builder(SslMethod::tls())?;
-        builder.set_ca_file(ca_file)?;
-        return Ok(ConfiguredTLS::TlsConnector(MakeTlsConnector::new(
-            builder.build(),
-        )));
-    } else if let Some(ssl_mode) = ssl_mode {
-        if *ssl_mode == common_options::SslMode::Require {
-            let mut builder = SslConnector::builder(SslMethod::tls())?;
-            builder.set_verify(SslVerifyMode::NONE);
-            return Ok(ConfiguredTLS::TlsConnector(MakeTlsConnector::new(
-                builder.build(),
-            )));
-        }
+    if tls_options.ssl_cert.is_some() != tls_options.ssl_key.is_some() {
+        return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+            "ssl_cert and ssl_key must be passed together".into(),
+        ));
     }
+    let verify_peer = match ssl_mode {
+        Some(SslMode::VerifyCa | SslMode::VerifyFull) => true,
+        Some(SslMode::Allow | SslMode::Prefer | SslMode::Require) => tls_options.ca_file.is_some(),
+        Some(SslMode::Disable) | None => {
+            if tls_options.ca_file.is_none() && tls_options.ssl_cert.is_none() {
+                return Ok(ConfiguredTLS::NoTls);
+            }
+            tls_options.ca_file.is_some()
+        }
+    };
+    let verify_hostname = tls_options
+        .verify_hostname
+        .unwrap_or(!matches!(ssl_mode, Some(SslMode::VerifyCa)));
 
-    Ok(ConfiguredTLS::NoTls)
+    let mut builder = SslConnector::builder(SslMethod::tls())?;
+    if let Some(ca_file) = &tls_options.ca_file {
+        builder.set_ca_file(ca_file)?;
+    }
+    if let (Some(ssl_cert), Some(ssl_key)) = (&tls_options.ssl_cert, &tls_options.ssl_key) {
+        builder.set_certificate_chain_file(ssl_cert)?;
+        builder.set_private_key_file(ssl_key, SslFiletype::PEM)?;
+        builder.check_private_key()?;
+    }
+    let verify_mode = if verify_peer {
+        SslVerifyMode::PEER
+    } else {
+        SslVerifyMode::NONE
+    };
+    builder.set_verify_callback(verify_mode, move |preverify_ok, store_context| {
+        capture_peer_certificate(store_context);
+        // Without verification errors of the chain are ignored.
+        preverify_ok || !verify_peer
+    });
+
+    let mut connector = MakeTlsConnector::new(builder.build());
+    if verify_peer && !verify_hostname {
+        connector.set_callback(|config, _| {
+            config.set_verify_hostname(false);
+            Ok(())
+        });
+    }
+    Ok(ConfiguredTLS::TlsConnector(connector))
 }
 
 #[must_use]
@@ -207,15 +322,503 @@ pub fn build_manager(
     mgr_config: ManagerConfig,
     pg_config: Config,
     configured_tls: ConfiguredTLS,
+    resolver: Option<HostResolver>,
+    stream_opener: Option<StreamOpener>,
+    connection_limit: Option<ConnectionLimit>,
+    prefer_standby: bool,
 ) -> Manager {
-    let mgr: Manager = match configured_tls {
-        ConfiguredTLS::NoTls => Manager::from_config(pg_config, NoTls, mgr_config),
-        ConfiguredTLS::TlsConnector(connector) => {
-            Manager::from_config(pg_config, connector, mgr_config)
+    Manager::from_connect(
+        pg_config,
+        PsqlpyConnect {
+            configured_tls,
+            resolver,
+            stream_opener,
+            connection_limit,
+            prefer_standby,
+        },
+        mgr_config,
+    )
+}
+
+/// Return ssl mode for the second connection attempt.
+///
+/// Follows `libpq` rules:
+/// `prefer` tries TLS connection first and then connection without TLS,
+/// `allow` tries connection without TLS first and then TLS connection.
+/// Other ssl modes have only one attempt.
+fn ssl_fallback_mode(
+    ssl_mode: tokio_postgres::config::SslMode,
+) -> Option<tokio_postgres::config::SslMode> {
+    match ssl_mode {
+        tokio_postgres::config::SslMode::Prefer => Some(tokio_postgres::config::SslMode::Disable),
+        tokio_postgres::config::SslMode::Allow => Some(tokio_postgres::config::SslMode::Require),
+        _ => None,
+    }
+}
+
+/// Execute connection attempts according to the ssl mode of the config.
+///
+/// # Errors
+/// May return Err Result if all connection attempts failed,
+/// error of the last attempt is returned.
+async fn connect_with_ssl_fallback<F, Fut, R>(
+    pg_config: &Config,
+    connect: F,
+) -> Result<R, tokio_postgres::Error>
+where
+    F: Fn(Config) -> Fut,
+    Fut: Future<Output = Result<R, tokio_postgres::Error>>,
+{
+    let Some(fallback_mode) = ssl_fallback_mode(pg_config.get_ssl_mode()) else {
+        return connect(pg_config.clone()).await;
+    };
+
+    let mut attempt_config = pg_config.clone();
+    if pg_config.get_ssl_mode() == tokio_postgres::config::SslMode::Allow {
+        attempt_config.ssl_mode(tokio_postgres::config::SslMode::Disable);
+    }
+
+    match connect(attempt_config.clone()).await {
+        Ok(connection) => Ok(connection),
+        Err(_) => {
+            attempt_config.ssl_mode(fallback_mode);
+            connect(attempt_config).await
+        }
+    }
+}
+
+/// Connect to the database with TLS fallback for `prefer` and `allow` ssl modes.
+///
+/// # Errors
+/// May return Err Result if all connection attempts failed,
+/// error of the last attempt is returned.
+pub async fn connect_with_tls_fallback(
+    pg_config: &Config,
+    connector: MakeTlsConnector,
+) -> Result<(Client, Connection<Socket, TlsStream<Socket>>), tokio_postgres::Error> {
+    connect_with_ssl_fallback(pg_config, |attempt_config| {
+        let connector = connector.clone();
+        async move { attempt_config.connect(connector).await }
+    })
+    .await
+}
+
+/// Drive the connection and report the error it's closed with.
+async fn log_connection_error(connection: impl Future<Output = Result<(), tokio_postgres::Error>>) {
+    if let Err(err) = connection.await {
+        tracing::error!(error = %err, "Connection is closed with error");
+    }
+}
+
+/// Open single connection to the database without the pool.
+///
+/// Connection is driven by a background task until the client is dropped.
+///
+/// # Errors
+/// May return Err Result if cannot build TLS connector or connect.
+pub async fn connect_single(
+    pg_config: Config,
+    tls_options: &TlsOptions,
+    ssl_mode: &Option<SslMode>,
+) -> RustPSQLDriverPyResult<Client> {
+    let configured_tls = build_tls(tls_options, ssl_mode)?;
+    tokio_runtime()
+        .spawn(async move {
+            match configured_tls {
+                ConfiguredTLS::NoTls => {
+                    let (client, connection) = pg_config.connect(NoTls).await?;
+                    tokio::spawn(log_connection_error(connection));
+                    Ok(client)
+                }
+                ConfiguredTLS::TlsConnector(connector) => {
+                    let (connection, peer_certificate) =
+                        with_peer_certificate(connect_with_tls_fallback(&pg_config, connector))
+                            .await;
+                    let (client, connection) = connection?;
+                    let connection_guard =
+                        register_peer_certificate(&client, peer_certificate).await;
+                    tokio::spawn(async move {
+                        log_connection_error(connection).await;
+                        drop(connection_guard);
+                    });
+                    Ok(client)
+                }
+            }
+        })
+        .await?
+}
+
+/// Drive the connection and count notices from the server.
+fn spawn_connection<S, T>(
+    mut connection: Connection<S, T>,
+    notice_counter: NoticeCounter,
+) -> JoinHandle<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    T: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        while let Some(message) = poll_fn(|cx| connection.poll_message(cx)).await {
+            match message {
+                Ok(AsyncMessage::Notice(notice)) => notice_counter.record(&notice),
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+        notice_counter.close();
+    })
+}
+
+/// Return TCP hosts of the config with their ports.
+#[must_use]
+pub fn tcp_host_ports(pg_config: &Config) -> Vec<(String, u16)> {
+    let ports = pg_config.get_ports();
+    pg_config
+        .get_hosts()
+        .iter()
+        .enumerate()
+        .filter_map(|(index, host)| {
+            let Host::Tcp(host) = host else {
+                return None;
+            };
+            let port = match ports.len() {
+                0 => DEFAULT_PORT,
+                1 => ports[0],
+                _ => ports.get(index).copied().unwrap_or(DEFAULT_PORT),
+            };
+            Some((host.clone(), port))
+        })
+        .collect()
+}
+
+/// Opens TCP streams to the database hosts instead of the driver.
+#[derive(Clone)]
+pub enum StreamOpener {
+    ConnectionFactory(ConnectionFactory),
+    Proxy(Proxy),
+    SshTunnel(Arc<SshTunnel>),
+}
+
+impl StreamOpener {
+    async fn connect_stream(
+        &self,
+        pg_config: &Config,
+        first_host: usize,
+    ) -> std::io::Result<(TcpStream, String, usize)> {
+        match self {
+            StreamOpener::ConnectionFactory(connection_factory) => {
+                connection_factory
+                    .connect_stream(pg_config, first_host)
+                    .await
+            }
+            StreamOpener::Proxy(proxy) => proxy.connect_stream(pg_config, first_host).await,
+            StreamOpener::SshTunnel(ssh_tunnel) => {
+                ssh_tunnel.connect_stream(pg_config, first_host).await
+            }
         }
+    }
+}
+
+/// Check that session of the new connection matches `target_session_attrs`.
+///
+/// `connect` checks it for every host itself,
+/// connections over opened streams are checked with this function.
+async fn session_attrs_match(
+    client: &Client,
+    pg_config: &Config,
+) -> Result<bool, tokio_postgres::Error> {
+    let read_only_expected = match pg_config.get_target_session_attrs() {
+        tokio_postgres::config::TargetSessionAttrs::ReadWrite => false,
+        tokio_postgres::config::TargetSessionAttrs::ReadOnly => true,
+        _ => return Ok(true),
     };
+    let is_read_only = client
+        .simple_query("SHOW transaction_read_only")
+        .await?
+        .iter()
+        .find_map(|message| match message {
+            SimpleQueryMessage::Row(row) => row.get(0).map(|value| value == "on"),
+            _ => None,
+        })
+        .unwrap_or(false);
+    Ok(is_read_only == read_only_expected)
+}
+
+/// Stream which fails every operation with the error of the stream opener.
+struct FailedStream(Option<std::io::Error>);
+
+impl FailedStream {
+    fn take_error(&mut self) -> std::io::Error {
+        self.0
+            .take()
+            .unwrap_or_else(|| std::io::ErrorKind::NotConnected.into())
+    }
+}
+
+impl AsyncRead for FailedStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        _buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Err(self.get_mut().take_error()))
+    }
+}
+
+impl AsyncWrite for FailedStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        _buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Poll::Ready(Err(self.get_mut().take_error()))
+    }
 
-    mgr
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Err(self.get_mut().take_error()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Convert error of the stream opener into the driver error.
+///
+/// Driver error cannot be created from the IO error directly,
+/// so the handshake is started on the stream which fails with it.
+async fn open_stream_error(pg_config: &Config, err: std::io::Error) -> tokio_postgres::Error {
+    let mut pg_config = pg_config.clone();
+    pg_config.ssl_mode(tokio_postgres::config::SslMode::Disable);
+    match pg_config.connect_raw(FailedStream(Some(err)), NoTls).await {
+        Err(err) => err,
+        // Handshake cannot succeed without the stream.
+        Ok(_) => tokio_postgres::Error::__private_api_timeout(),
+    }
+}
+
+/// Limit of opened connections shared between pools.
+#[derive(Clone)]
+pub struct ConnectionLimit {
+    semaphore: Arc<Semaphore>,
+    acquire_timeout: Duration,
+}
+
+impl ConnectionLimit {
+    #[must_use]
+    pub fn new(max_connections: usize, acquire_timeout: Duration) -> Self {
+        ConnectionLimit {
+            semaphore: Arc::new(Semaphore::new(max_connections)),
+            acquire_timeout,
+        }
+    }
+
+    /// Return number of connections which can be opened right now.
+    #[must_use]
+    pub fn available_permits(&self) -> usize {
+        self.semaphore.available_permits()
+    }
+}
+
+/// Connector for the connection pool.
+///
+/// Adds TLS fallback for `prefer` and `allow` ssl modes,
+/// custom host resolution with staggered connection attempts,
+/// streams opened by Python connection factory, proxy or SSH tunnel,
+/// limit of opened connections shared between pools
+/// and counting of the notices.
+struct PsqlpyConnect {
+    configured_tls: ConfiguredTLS,
+    resolver: Option<HostResolver>,
+    stream_opener: Option<StreamOpener>,
+    connection_limit: Option<ConnectionLimit>,
+    /// Try read-only session first, like `prefer-standby` of `libpq`.
+    prefer_standby: bool,
+}
+
+impl PsqlpyConnect {
+    /// Open the connection over the stream opened to the `host`.
+    async fn connect_raw(
+        &self,
+        stream: TcpStream,
+        host: &str,
+        pg_config: &Config,
+        notice_counter: NoticeCounter,
+    ) -> Result<(Client, JoinHandle<()>), tokio_postgres::Error> {
+        match &self.configured_tls {
+            ConfiguredTLS::NoTls => {
+                let (client, connection) = pg_config.connect_raw(stream, NoTls).await?;
+                Ok((client, spawn_connection(connection, notice_counter)))
+            }
+            ConfiguredTLS::TlsConnector(connector) => {
+                let mut connector = connector.clone();
+                let tls = match MakeTlsConnect::<TcpStream>::make_tls_connect(&mut connector, host)
+                {
+                    Ok(tls) => tls,
+                    Err(err) => {
+                        return Err(open_stream_error(pg_config, std::io::Error::other(err)).await)
+                    }
+                };
+                let (client, connection) = pg_config.connect_raw(stream, tls).await?;
+                Ok((client, spawn_connection(connection, notice_counter)))
+            }
+        }
+    }
+
+    /// Connect through the stream of the stream opener.
+    ///
+    /// Hosts are tried one by one until the session matches
+    /// `target_session_attrs`, like `connect` does.
+    /// There is no fallback to the direct connection,
+    /// so traffic doesn't bypass proxy or tunnel.
+    async fn connect_with_opener(
+        &self,
+        stream_opener: &StreamOpener,
+        pg_config: Config,
+        notice_counter: NoticeCounter,
+    ) -> Result<(Client, JoinHandle<()>), tokio_postgres::Error> {
+        let mut first_host = 0;
+        loop {
+            let (stream, host, host_index) = match stream_opener
+                .connect_stream(&pg_config, first_host)
+                .await
+            {
+                Ok(opened_stream) => opened_stream,
+                Err(err) if first_host == 0 => return Err(open_stream_error(&pg_config, err).await),
+                Err(err) => {
+                    let err = std::io::Error::other(format!(
+                        "none of the hosts matches target_session_attrs, last error - {err}"
+                    ));
+                    return Err(open_stream_error(&pg_config, err).await);
+                }
+            };
+
+            let connection = self
+                .connect_raw(stream, &host, &pg_config, notice_counter.clone())
+                .await?;
+            if session_attrs_match(&connection.0, &pg_config).await? {
+                return Ok(connection);
+            }
+            // Task is aborted, so the shared notice counter isn't closed.
+            connection.1.abort();
+            first_host = host_index + 1;
+        }
+    }
+
+    async fn connect_once(
+        &self,
+        pg_config: Config,
+        notice_counter: NoticeCounter,
+    ) -> Result<(Client, JoinHandle<()>), tokio_postgres::Error> {
+        if let Some(stream_opener) = &self.stream_opener {
+            return self
+                .connect_with_opener(stream_opener, pg_config, notice_counter)
+                .await;
+        }
+
+        if let Some(resolver) = &self.resolver {
+            // If stream cannot be opened or no host matches `target_session_attrs`,
+            // default connect is used, it returns proper error
+            // or tries hosts in its own way.
+            let mut first_host = 0;
+            while let Some((stream, host, host_index)) =
+                resolver.connect_stream(&pg_config, first_host).await
+            {
+                let connection = self
+                    .connect_raw(stream, &host, &pg_config, notice_counter.clone())
+                    .await?;
+                if session_attrs_match(&connection.0, &pg_config).await? {
+                    return Ok(connection);
+                }
+                connection.1.abort();
+                first_host = host_index + 1;
+            }
+        }
+
+        match &self.configured_tls {
+            ConfiguredTLS::NoTls => {
+                let (client, connection) = pg_config.connect(NoTls).await?;
+                Ok((client, spawn_connection(connection, notice_counter)))
+            }
+            ConfiguredTLS::TlsConnector(connector) => {
+                let (client, connection) = pg_config.connect(connector.clone()).await?;
+                Ok((client, spawn_connection(connection, notice_counter)))
+            }
+        }
+    }
+
+    /// Connect with TLS fallback, read-only session is tried first
+    /// if standby is preferred.
+    async fn connect_preferred(
+        &self,
+        pg_config: &Config,
+        notice_counter: &NoticeCounter,
+    ) -> Result<(Client, JoinHandle<()>), tokio_postgres::Error> {
+        if self.prefer_standby {
+            let mut standby_config = pg_config.clone();
+            standby_config
+                .target_session_attrs(tokio_postgres::config::TargetSessionAttrs::ReadOnly);
+            let standby_connection = connect_with_ssl_fallback(&standby_config, |attempt_config| {
+                self.connect_once(attempt_config, notice_counter.clone())
+            })
+            .await;
+            if let Ok(standby_connection) = standby_connection {
+                return Ok(standby_connection);
+            }
+        }
+
+        connect_with_ssl_fallback(pg_config, |attempt_config| {
+            self.connect_once(attempt_config, notice_counter.clone())
+        })
+        .await
+    }
+}
+
+impl Connect for PsqlpyConnect {
+    fn connect(
+        &self,
+        pg_config: &Config,
+    ) -> BoxFuture<'_, Result<(Client, JoinHandle<()>), tokio_postgres::Error>> {
+        let pg_config = pg_config.clone();
+        Box::pin(async move {
+            // Permit is held while connection is opened.
+            let permit = match &self.connection_limit {
+                Some(connection_limit) => {
+                    let acquire_permit = connection_limit.semaphore.clone().acquire_owned();
+                    match tokio::time::timeout(connection_limit.acquire_timeout, acquire_permit)
+                        .await
+                    {
+                        Ok(permit) => permit.ok(),
+                        Err(_) => {
+                            let err = std::io::Error::new(
+                                std::io::ErrorKind::TimedOut,
+                                "limit of total connections is reached",
+                            );
+                            return Err(open_stream_error(&pg_config, err).await);
+                        }
+                    }
+                }
+                None => None,
+            };
+
+            let notice_counter = NoticeCounter::default();
+            let (connection, peer_certificate) =
+                with_peer_certificate(self.connect_preferred(&pg_config, &notice_counter)).await;
+            let (client, conn_task) = connection?;
+            register_connection(&client, notice_counter).await;
+            let connection_guard = register_peer_certificate(&client, peer_certificate).await;
+
+            if permit.is_none() && connection_guard.is_none() {
+                return Ok((client, conn_task));
+            }
+            let conn_task = tokio::spawn(async move {
+                let _ = conn_task.await;
+                drop(permit);
+                drop(connection_guard);
+            });
+            Ok((client, conn_task))
+        })
+    }
 }
 
 /// Check is python object async or not.
@@ -0,0 +1,151 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use deadpool_postgres::{ClientWrapper, Hook};
+use pyo3::{pyclass, pymethods};
+
+use crate::exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult};
+
+#[derive(Default)]
+struct WarmState {
+    prepared_count: u64,
+    last_error: Option<String>,
+}
+
+/// Statements which are prepared on every new connection of the pool.
+///
+/// Statements are put into the statement cache of the connection,
+/// so the first query with the same querystring isn't prepared again.
+#[derive(Clone, Default)]
+pub struct WarmStatements {
+    statements: Arc<Vec<(String, String)>>,
+    states: Arc<Mutex<HashMap<String, WarmState>>>,
+}
+
+impl WarmStatements {
+    /// Create warm list from pairs of name and querystring.
+    ///
+    /// # Errors
+    /// May return Err Result if names are not unique.
+    pub fn new(statements: Vec<(String, String)>) -> RustPSQLDriverPyResult<Self> {
+        let mut states = HashMap::with_capacity(statements.len());
+        for (name, _) in &statements {
+            if states.insert(name.clone(), WarmState::default()).is_some() {
+                return Err(RustPSQLDriverError::ConnectionPoolConfigurationError(
+                    format!("Statement {name} is specified more than once"),
+                ));
+            }
+        }
+
+        Ok(WarmStatements {
+            statements: Arc::new(statements),
+            states: Arc::new(Mutex::new(states)),
+        })
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.statements.is_empty()
+    }
+
+    /// Prepare all statements on the new connection.
+    ///
+    /// Statement which cannot be prepared doesn't fail the connection,
+    /// error is kept for the introspection.
+    async fn warm(&self, client: &ClientWrapper) {
+        for (name, querystring) in self.statements.iter() {
+            let prepare_result = client.prepare_cached(querystring).await;
+            let Ok(mut states) = self.states.lock() else {
+                return;
+            };
+            let state = states.entry(name.clone()).or_default();
+            match prepare_result {
+                Ok(_) => state.prepared_count += 1,
+                Err(err) => state.last_error = Some(err.to_string()),
+            }
+        }
+    }
+
+    /// Hook for the pool which warms every new connection.
+    #[must_use]
+    pub fn post_create_hook(&self) -> Hook {
+        let warm_statements = self.clone();
+        Hook::async_fn(move |client: &mut ClientWrapper, _| {
+            let warm_statements = warm_statements.clone();
+            Box::pin(async move {
+                warm_statements.warm(client).await;
+                Ok(())
+            })
+        })
+    }
+
+    /// Return state of the statements in the warm list order.
+    #[must_use]
+    pub fn snapshot(&self) -> Vec<WarmStatement> {
+        let Ok(states) = self.states.lock() else {
+            return vec![];
+        };
+
+        self.statements
+            .iter()
+            .map(|(name, querystring)| {
+                let state = states.get(name);
+                WarmStatement {
+                    name: name.clone(),
+                    querystring: querystring.clone(),
+                    prepared_count: state.map_or(0, |state| state.prepared_count),
+                    last_error: state.and_then(|state| state.last_error.clone()),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Statement from the warm list of the pool.
+#[pyclass]
+#[derive(Clone)]
+pub struct WarmStatement {
+    name: String,
+    querystring: String,
+    prepared_count: u64,
+    last_error: Option<String>,
+}
+
+#[pymethods]
+impl WarmStatement {
+    #[getter]
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    #[getter]
+    fn querystring(&self) -> String {
+        self.querystring.clone()
+    }
+
+    /// Number of connections the statement was prepared on.
+    #[getter]
+    fn prepared_count(&self) -> u64 {
+        self.prepared_count
+    }
+
+    /// Error of the last failed prepare.
+    #[getter]
+    fn last_error(&self) -> Option<String> {
+        self.last_error.clone()
+    }
+
+    #[getter]
+    fn is_warmed(&self) -> bool {
+        self.prepared_count > 0
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "WarmStatement(name={}, prepared_count={})",
+            self.name, self.prepared_count,
+        )
+    }
+}
@@ -40,6 +40,16 @@ create_exception!(
     ConnectionPoolExecuteError,
     BaseConnectionPoolError
 );
+create_exception!(
+    psqlpy.exceptions,
+    SchemaValidationError,
+    BaseConnectionPoolError
+);
+create_exception!(
+    psqlpy.exceptions,
+    ConnectionPoolCheckoutError,
+    BaseConnectionPoolError
+);
 
 // Connection exceptions
 create_exception!(
@@ -57,6 +67,72 @@ create_exception!(
     ConnectionClosedError,
     BaseConnectionError
 );
+create_exception!(psqlpy.exceptions, QueryTimeoutError, ConnectionExecuteError);
+
+// Database exceptions
+// Errors returned by the server, classes follow SQLSTATE classes.
+create_exception!(psqlpy.exceptions, DatabaseError, ConnectionExecuteError);
+create_exception!(psqlpy.exceptions, DataError, DatabaseError);
+create_exception!(
+    psqlpy.exceptions,
+    IntegrityConstraintViolationError,
+    DatabaseError
+);
+create_exception!(
+    psqlpy.exceptions,
+    UniqueViolationError,
+    IntegrityConstraintViolationError
+);
+create_exception!(
+    psqlpy.exceptions,
+    ForeignKeyViolationError,
+    IntegrityConstraintViolationError
+);
+create_exception!(
+    psqlpy.exceptions,
+    NotNullViolationError,
+    IntegrityConstraintViolationError
+);
+create_exception!(
+    psqlpy.exceptions,
+    CheckViolationError,
+    IntegrityConstraintViolationError
+);
+create_exception!(
+    psqlpy.exceptions,
+    ExclusionViolationError,
+    IntegrityConstraintViolationError
+);
+create_exception!(
+    psqlpy.exceptions,
+    TransactionRollbackSqlError,
+    DatabaseError
+);
+create_exception!(
+    psqlpy.exceptions,
+    SerializationError,
+    TransactionRollbackSqlError
+);
+create_exception!(
+    psqlpy.exceptions,
+    DeadlockDetectedError,
+    TransactionRollbackSqlError
+);
+create_exception!(psqlpy.exceptions, SyntaxOrAccessError, DatabaseError);
+create_exception!(psqlpy.exceptions, SqlSyntaxError, SyntaxOrAccessError);
+create_exception!(psqlpy.exceptions, UndefinedTableError, SyntaxOrAccessError);
+create_exception!(psqlpy.exceptions, UndefinedColumnError, SyntaxOrAccessError);
+create_exception!(
+    psqlpy.exceptions,
+    InsufficientPrivilegeError,
+    SyntaxOrAccessError
+);
+create_exception!(psqlpy.exceptions, OperatorInterventionError, DatabaseError);
+create_exception!(
+    psqlpy.exceptions,
+    QueryCanceledError,
+    OperatorInterventionError
+);
 
 // Transaction exceptions
 create_exception!(
@@ -89,11 +165,31 @@ create_exception!(
     TransactionExecuteError,
     BaseTransactionError
 );
+create_exception!(
+    psqlpy.exceptions,
+    LockNotAvailableError,
+    TransactionExecuteError
+);
 create_exception!(
     psqlpy.exceptions,
     TransactionClosedError,
     BaseTransactionError
 );
+create_exception!(
+    psqlpy.exceptions,
+    TransactionNotStartedError,
+    TransactionBeginError
+);
+create_exception!(
+    psqlpy.exceptions,
+    TransactionAlreadyCommittedError,
+    TransactionBeginError
+);
+create_exception!(
+    psqlpy.exceptions,
+    TransactionAlreadyRolledBackError,
+    TransactionBeginError
+);
 
 // Cursor exceptions
 create_exception!(
@@ -142,6 +238,18 @@ create_exception!(
 
 create_exception!(psqlpy.exceptions, SSLError, RustPSQLDriverPyBaseError);
 
+create_exception!(
+    psqlpy.exceptions,
+    QueryResultClosedError,
+    RustPSQLDriverPyBaseError
+);
+
+create_exception!(
+    psqlpy.exceptions,
+    BlockingCallError,
+    RustPSQLDriverPyBaseError
+);
+
 #[allow(clippy::missing_errors_doc)]
 #[allow(clippy::too_many_lines)]
 pub fn python_exceptions_module(py: Python<'_>, pymod: &Bound<'_, PyModule>) -> PyResult<()> {
@@ -166,6 +274,14 @@ pub fn python_exceptions_module(py: Python<'_>, pymod: &Bound<'_, PyModule>) ->
         "ConnectionPoolExecuteError",
         py.get_type::<ConnectionPoolExecuteError>(),
     )?;
+    pymod.add(
+        "SchemaValidationError",
+        py.get_type::<SchemaValidationError>(),
+    )?;
+    pymod.add(
+        "ConnectionPoolCheckoutError",
+        py.get_type::<ConnectionPoolCheckoutError>(),
+    )?;
 
     pymod.add("BaseConnectionError", py.get_type::<BaseConnectionError>())?;
     pymod.add(
@@ -176,6 +292,56 @@ pub fn python_exceptions_module(py: Python<'_>, pymod: &Bound<'_, PyModule>) ->
         "ConnectionClosedError",
         py.get_type::<ConnectionClosedError>(),
     )?;
+    pymod.add("QueryTimeoutError", py.get_type::<QueryTimeoutError>())?;
+
+    pymod.add("DatabaseError", py.get_type::<DatabaseError>())?;
+    pymod.add("DataError", py.get_type::<DataError>())?;
+    pymod.add(
+        "IntegrityConstraintViolationError",
+        py.get_type::<IntegrityConstraintViolationError>(),
+    )?;
+    pymod.add(
+        "UniqueViolationError",
+        py.get_type::<UniqueViolationError>(),
+    )?;
+    pymod.add(
+        "ForeignKeyViolationError",
+        py.get_type::<ForeignKeyViolationError>(),
+    )?;
+    pymod.add(
+        "NotNullViolationError",
+        py.get_type::<NotNullViolationError>(),
+    )?;
+    pymod.add("CheckViolationError", py.get_type::<CheckViolationError>())?;
+    pymod.add(
+        "ExclusionViolationError",
+        py.get_type::<ExclusionViolationError>(),
+    )?;
+    pymod.add(
+        "TransactionRollbackSqlError",
+        py.get_type::<TransactionRollbackSqlError>(),
+    )?;
+    pymod.add("SerializationError", py.get_type::<SerializationError>())?;
+    pymod.add(
+        "DeadlockDetectedError",
+        py.get_type::<DeadlockDetectedError>(),
+    )?;
+    pymod.add("SyntaxOrAccessError", py.get_type::<SyntaxOrAccessError>())?;
+    pymod.add("SqlSyntaxError", py.get_type::<SqlSyntaxError>())?;
+    pymod.add("UndefinedTableError", py.get_type::<UndefinedTableError>())?;
+    pymod.add(
+        "UndefinedColumnError",
+        py.get_type::<UndefinedColumnError>(),
+    )?;
+    pymod.add(
+        "InsufficientPrivilegeError",
+        py.get_type::<InsufficientPrivilegeError>(),
+    )?;
+    pymod.add(
+        "OperatorInterventionError",
+        py.get_type::<OperatorInterventionError>(),
+    )?;
+    pymod.add("QueryCanceledError", py.get_type::<QueryCanceledError>())?;
 
     pymod.add(
         "BaseTransactionError",
@@ -201,10 +367,26 @@ pub fn python_exceptions_module(py: Python<'_>, pymod: &Bound<'_, PyModule>) ->
         "TransactionExecuteError",
         py.get_type::<TransactionExecuteError>(),
     )?;
+    pymod.add(
+        "LockNotAvailableError",
+        py.get_type::<LockNotAvailableError>(),
+    )?;
     pymod.add(
         "TransactionClosedError",
         py.get_type::<TransactionClosedError>(),
     )?;
+    pymod.add(
+        "TransactionNotStartedError",
+        py.get_type::<TransactionNotStartedError>(),
+    )?;
+    pymod.add(
+        "TransactionAlreadyCommittedError",
+        py.get_type::<TransactionAlreadyCommittedError>(),
+    )?;
+    pymod.add(
+        "TransactionAlreadyRolledBackError",
+        py.get_type::<TransactionAlreadyRolledBackError>(),
+    )?;
 
     pymod.add("BaseCursorError", py.get_type::<BaseCursorError>())?;
     pymod.add("CursorStartError", py.get_type::<CursorStartError>())?;
@@ -228,6 +410,11 @@ pub fn python_exceptions_module(py: Python<'_>, pymod: &Bound<'_, PyModule>) ->
         "MacAddrConversionError",
         py.get_type::<MacAddrConversionError>(),
     )?;
+    pymod.add(
+        "QueryResultClosedError",
+        py.get_type::<QueryResultClosedError>(),
+    )?;
+    pymod.add("BlockingCallError", py.get_type::<BlockingCallError>())?;
     pymod.add("BaseListenerError", py.get_type::<BaseListenerError>())?;
     pymod.add("ListenerStartError", py.get_type::<ListenerStartError>())?;
     pymod.add("ListenerClosedError", py.get_type::<ListenerClosedError>())?;
@@ -1,17 +1,33 @@
+use deadpool_postgres::tokio_postgres;
 use openssl::error::ErrorStack;
+use pyo3::{
+    types::{PyAnyMethods, PyType},
+    Bound, PyErr, Python,
+};
 use thiserror::Error;
 use tokio::task::JoinError;
 
-use crate::exceptions::python_errors::{PyToRustValueMappingError, RustToPyValueMappingError};
+use crate::{
+    driver::pool_stats::PoolSnapshot,
+    exceptions::python_errors::{PyToRustValueMappingError, RustToPyValueMappingError},
+};
 
 use super::python_errors::{
     BaseConnectionError, BaseConnectionPoolError, BaseCursorError, BaseListenerError,
-    BaseTransactionError, ConnectionClosedError, ConnectionExecuteError, ConnectionPoolBuildError,
+    BaseTransactionError, BlockingCallError, CheckViolationError, ConnectionClosedError,
+    ConnectionExecuteError, ConnectionPoolBuildError, ConnectionPoolCheckoutError,
     ConnectionPoolConfigurationError, ConnectionPoolExecuteError, CursorCloseError,
-    CursorClosedError, CursorFetchError, CursorStartError, DriverError, ListenerCallbackError,
-    ListenerClosedError, ListenerStartError, MacAddrParseError, RuntimeJoinError, SSLError,
-    TransactionBeginError, TransactionClosedError, TransactionCommitError, TransactionExecuteError,
-    TransactionRollbackError, TransactionSavepointError, UUIDValueConvertError,
+    CursorClosedError, CursorFetchError, CursorStartError, DataError, DatabaseError,
+    DeadlockDetectedError, DriverError, ExclusionViolationError, ForeignKeyViolationError,
+    InsufficientPrivilegeError, IntegrityConstraintViolationError, ListenerCallbackError,
+    ListenerClosedError, ListenerStartError, LockNotAvailableError, MacAddrParseError,
+    NotNullViolationError, OperatorInterventionError, QueryCanceledError, QueryResultClosedError,
+    QueryTimeoutError, RuntimeJoinError, SSLError, SchemaValidationError, SerializationError,
+    SqlSyntaxError, SyntaxOrAccessError, TransactionAlreadyCommittedError,
+    TransactionAlreadyRolledBackError, TransactionBeginError, TransactionClosedError,
+    TransactionCommitError, TransactionExecuteError, TransactionNotStartedError,
+    TransactionRollbackError, TransactionRollbackSqlError, TransactionSavepointError,
+    UUIDValueConvertError, UndefinedColumnError, UndefinedTableError, UniqueViolationError,
 };
 
 pub type RustPSQLDriverPyResult<T> = Result<T, RustPSQLDriverError>;
@@ -27,6 +43,10 @@ pub enum RustPSQLDriverError {
     ConnectionPoolConfigurationError(String),
     #[error("Connection pool execute error: {0}.")]
     ConnectionPoolExecuteError(String),
+    #[error("Schema validation error: {}.", .0.join("; "))]
+    SchemaValidationError(Vec<String>),
+    #[error("Cannot get connection from the pool: {0}, {1}.")]
+    ConnectionPoolCheckoutError(String, PoolSnapshot),
 
     // Connection Errors
     #[error("Connection error: {0}.")]
@@ -35,6 +55,10 @@ pub enum RustPSQLDriverError {
     ConnectionExecuteError(String),
     #[error("Underlying connection is returned to the pool")]
     ConnectionClosedError,
+    #[error("Query timeout: {0}.")]
+    QueryTimeoutError(String),
+    #[error("Connection execute error: {0}.")]
+    DatabaseError(String, tokio_postgres::Error),
 
     // Transaction Errors
     #[error("Transaction error: {0}")]
@@ -49,8 +73,16 @@ pub enum RustPSQLDriverError {
     TransactionSavepointError(String),
     #[error("Transaction execute error: {0}")]
     TransactionExecuteError(String),
+    #[error("Lock not available: {0}")]
+    LockNotAvailableError(String),
     #[error("Underlying connection is returned to the pool")]
     TransactionClosedError,
+    #[error("Transaction is not started, please call begin() on transaction")]
+    TransactionNotStartedError,
+    #[error("Transaction is already committed")]
+    TransactionAlreadyCommittedError,
+    #[error("Transaction is already rolled back")]
+    TransactionAlreadyRolledBackError,
 
     // Cursor Errors
     #[error("Cursor error: {0}")]
@@ -79,10 +111,16 @@ pub enum RustPSQLDriverError {
     #[error("Can't convert value from python to rust type: {0}")]
     PyToRustValueConversionError(String),
 
+    #[error("Query result is closed, its rows are released")]
+    QueryResultClosedError,
+
+    #[error("Blocking call error: {0}.")]
+    BlockingCallError(String),
+
     #[error("Python exception: {0}.")]
     RustPyError(#[from] pyo3::PyErr),
     #[error("Database engine exception: {0}.")]
-    RustDriverError(#[from] deadpool_postgres::tokio_postgres::Error),
+    RustDriverError(#[from] tokio_postgres::Error),
     #[error("Database engine pool exception: {0}")]
     RustConnectionPoolError(#[from] deadpool_postgres::PoolError),
     #[error("Database engine build failed: {0}")]
@@ -99,12 +137,82 @@ pub enum RustPSQLDriverError {
     SSLError(#[from] ErrorStack),
 }
 
+impl RustPSQLDriverError {
+    /// Wrap error of the statement execution with the context.
+    ///
+    /// Errors returned by the server keep the original error,
+    /// so SQLSTATE and the other fields reach the Python exception.
+    #[must_use]
+    pub fn execute_error(context: &str, error: RustPSQLDriverError) -> Self {
+        let message = format!("{context}, error - {error}");
+        match error {
+            RustPSQLDriverError::RustDriverError(err) if err.as_db_error().is_some() => {
+                RustPSQLDriverError::DatabaseError(message, err)
+            }
+            _ => RustPSQLDriverError::ConnectionExecuteError(message),
+        }
+    }
+}
+
+/// Return exception class for the SQLSTATE code.
+///
+/// Unknown codes fall back to the class of the SQLSTATE class,
+/// the first two characters of the code.
+fn database_error_type<'py>(py: Python<'py>, code: &str) -> Bound<'py, PyType> {
+    match code {
+        "23505" => py.get_type::<UniqueViolationError>(),
+        "23503" => py.get_type::<ForeignKeyViolationError>(),
+        "23502" => py.get_type::<NotNullViolationError>(),
+        "23514" => py.get_type::<CheckViolationError>(),
+        "23P01" => py.get_type::<ExclusionViolationError>(),
+        "40001" => py.get_type::<SerializationError>(),
+        "40P01" => py.get_type::<DeadlockDetectedError>(),
+        "42601" => py.get_type::<SqlSyntaxError>(),
+        "42P01" => py.get_type::<UndefinedTableError>(),
+        "42703" => py.get_type::<UndefinedColumnError>(),
+        "42501" => py.get_type::<InsufficientPrivilegeError>(),
+        "57014" => py.get_type::<QueryCanceledError>(),
+        _ => match code.get(..2) {
+            Some("22") => py.get_type::<DataError>(),
+            Some("23") => py.get_type::<IntegrityConstraintViolationError>(),
+            Some("40") => py.get_type::<TransactionRollbackSqlError>(),
+            Some("42") => py.get_type::<SyntaxOrAccessError>(),
+            Some("57") => py.get_type::<OperatorInterventionError>(),
+            _ => py.get_type::<DatabaseError>(),
+        },
+    }
+}
+
+/// Set fields of the server error on the Python exception.
+fn with_database_fields(py: Python<'_>, py_err: PyErr, error: &tokio_postgres::Error) -> PyErr {
+    let Some(db_error) = error.as_db_error() else {
+        return py_err;
+    };
+
+    let value = py_err.value(py);
+    let fields = [
+        ("sqlstate", Some(db_error.code().code())),
+        ("detail", db_error.detail()),
+        ("hint", db_error.hint()),
+        ("schema", db_error.schema()),
+        ("table", db_error.table()),
+        ("column", db_error.column()),
+        ("constraint", db_error.constraint()),
+    ];
+    for (name, field) in fields {
+        let _ = value.setattr(name, field);
+    }
+    py_err
+}
+
 impl From<RustPSQLDriverError> for pyo3::PyErr {
     fn from(error: RustPSQLDriverError) -> Self {
         let error_desc = error.to_string();
         match error {
             RustPSQLDriverError::RustPyError(err) => err,
-            RustPSQLDriverError::RustDriverError(_) => DriverError::new_err((error_desc,)),
+            RustPSQLDriverError::RustDriverError(err) => Python::with_gil(|gil| {
+                with_database_fields(gil, DriverError::new_err((error_desc,)), &err)
+            }),
             RustPSQLDriverError::RustMacAddrConversionError(_) => {
                 MacAddrParseError::new_err((error_desc,))
             }
@@ -135,6 +243,28 @@ impl From<RustPSQLDriverError> for pyo3::PyErr {
             RustPSQLDriverError::ConnectionPoolExecuteError(_) => {
                 ConnectionPoolExecuteError::new_err((error_desc,))
             }
+            RustPSQLDriverError::ConnectionPoolCheckoutError(_, snapshot) => {
+                Python::with_gil(|gil| {
+                    let py_err = ConnectionPoolCheckoutError::new_err((error_desc,));
+                    let value = py_err.value(gil);
+                    let _ = value.setattr("max_size", snapshot.max_size);
+                    let _ = value.setattr("size", snapshot.size);
+                    let _ = value.setattr("in_use", snapshot.in_use);
+                    let _ = value.setattr("waiting", snapshot.waiting);
+                    let _ = value.setattr(
+                        "longest_held_ms",
+                        snapshot
+                            .longest_held
+                            .map(|longest_held| longest_held.as_secs_f64() * 1000.0),
+                    );
+                    py_err
+                })
+            }
+            RustPSQLDriverError::SchemaValidationError(mismatches) => Python::with_gil(|gil| {
+                let py_err = SchemaValidationError::new_err((error_desc,));
+                let _ = py_err.value(gil).setattr("mismatches", mismatches);
+                py_err
+            }),
             RustPSQLDriverError::BaseConnectionError(_) => {
                 BaseConnectionError::new_err((error_desc,))
             }
@@ -144,6 +274,12 @@ impl From<RustPSQLDriverError> for pyo3::PyErr {
             RustPSQLDriverError::ConnectionClosedError => {
                 ConnectionClosedError::new_err((error_desc,))
             }
+            RustPSQLDriverError::QueryTimeoutError(_) => QueryTimeoutError::new_err((error_desc,)),
+            RustPSQLDriverError::DatabaseError(_, err) => Python::with_gil(|gil| {
+                let code = err.code().map(|code| code.code()).unwrap_or_default();
+                let py_err = PyErr::from_type(database_error_type(gil, code), (error_desc,));
+                with_database_fields(gil, py_err, &err)
+            }),
             RustPSQLDriverError::BaseTransactionError(_) => {
                 BaseTransactionError::new_err((error_desc,))
             }
@@ -162,9 +298,21 @@ impl From<RustPSQLDriverError> for pyo3::PyErr {
             RustPSQLDriverError::TransactionExecuteError(_) => {
                 TransactionExecuteError::new_err((error_desc,))
             }
+            RustPSQLDriverError::LockNotAvailableError(_) => {
+                LockNotAvailableError::new_err((error_desc,))
+            }
             RustPSQLDriverError::TransactionClosedError => {
                 TransactionClosedError::new_err((error_desc,))
             }
+            RustPSQLDriverError::TransactionNotStartedError => {
+                TransactionNotStartedError::new_err((error_desc,))
+            }
+            RustPSQLDriverError::TransactionAlreadyCommittedError => {
+                TransactionAlreadyCommittedError::new_err((error_desc,))
+            }
+            RustPSQLDriverError::TransactionAlreadyRolledBackError => {
+                TransactionAlreadyRolledBackError::new_err((error_desc,))
+            }
             RustPSQLDriverError::BaseCursorError(_) => BaseCursorError::new_err((error_desc,)),
             RustPSQLDriverError::CursorStartError(_) => CursorStartError::new_err((error_desc,)),
             RustPSQLDriverError::CursorCloseError(_) => CursorCloseError::new_err((error_desc,)),
@@ -179,6 +327,10 @@ impl From<RustPSQLDriverError> for pyo3::PyErr {
             RustPSQLDriverError::ListenerCallbackError => {
                 ListenerCallbackError::new_err((error_desc,))
             }
+            RustPSQLDriverError::QueryResultClosedError => {
+                QueryResultClosedError::new_err((error_desc,))
+            }
+            RustPSQLDriverError::BlockingCallError(_) => BlockingCallError::new_err((error_desc,)),
         }
     }
 }
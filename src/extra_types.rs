@@ -4,17 +4,18 @@ use geo_types::{Line as RustLineSegment, LineString, Point as RustPoint, Rect as
 use macaddr::{MacAddr6 as RustMacAddr6, MacAddr8 as RustMacAddr8};
 use pyo3::{
     pyclass, pymethods,
-    types::{PyModule, PyModuleMethods},
+    types::{PyAnyMethods, PyDict, PyDictMethods, PyModule, PyModuleMethods},
     Bound, Py, PyAny, PyResult, Python,
 };
 use serde_json::Value;
 
 use crate::{
-    additional_types::{Circle as RustCircle, Line as RustLine},
+    additional_types::{Circle as RustCircle, Line as RustLine, PgNumeric},
     exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    format_helpers::is_valid_type_name,
     value_converter::{
-        build_flat_geo_coords, build_geo_coords, build_serde_value,
-        py_sequence_into_postgres_array, PythonDTO,
+        build_flat_geo_coords, build_geo_coords, build_serde_value, extract_decimal,
+        is_numpy_array, py_sequence_into_postgres_array, py_to_rust, PythonDTO, PythonRange,
     },
 };
 
@@ -37,6 +38,71 @@ impl PgVector {
     }
 }
 
+/// Represents pgvector `vector` in `PostgreSQL`.
+///
+/// Can be created from the sequence of numbers or `numpy.ndarray`.
+#[pyclass]
+#[derive(Clone)]
+pub struct PyVector(Vec<f32>);
+
+#[pymethods]
+impl PyVector {
+    /// Create new vector.
+    ///
+    /// # Errors
+    /// May return Err Result if value isn't a sequence of numbers.
+    #[new]
+    fn new(vector: &Bound<'_, PyAny>) -> RustPSQLDriverPyResult<Self> {
+        let values = if is_numpy_array(vector) {
+            vector.call_method0("tolist")?.extract::<Vec<f32>>()
+        } else {
+            vector.extract::<Vec<f32>>()
+        };
+        Ok(Self(values.map_err(|_| {
+            RustPSQLDriverError::PyToRustValueConversionError(
+                "PyVector can be created only from sequence of numbers".into(),
+            )
+        })?))
+    }
+
+    #[must_use]
+    pub fn to_list(&self) -> Vec<f32> {
+        self.0.clone()
+    }
+
+    /// Return vector as `numpy.ndarray` with `float32` dtype.
+    ///
+    /// # Errors
+    /// May return Err Result if numpy isn't installed.
+    pub fn to_numpy(&self, py: Python<'_>) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("dtype", "float32")?;
+        Ok(py
+            .import("numpy")?
+            .call_method("array", (self.0.clone(),), Some(&kwargs))?
+            .unbind())
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+
+    fn __repr__(&self) -> String {
+        format!("PyVector({:?})", self.0)
+    }
+}
+
+impl PyVector {
+    #[must_use]
+    pub fn inner_value(&self) -> Vec<f32> {
+        self.0.clone()
+    }
+}
+
 macro_rules! build_python_type {
     ($st_name:ident, $rust_type:ty) => {
         #[pyclass]
@@ -75,6 +141,46 @@ build_python_type!(Money, i64);
 build_python_type!(Float32, f32);
 build_python_type!(Float64, f64);
 
+/// Represents `NUMERIC` in `PostgreSQL` without loss of precision.
+#[pyclass]
+#[derive(Clone)]
+pub struct PyDecimal {
+    inner_value: PgNumeric,
+}
+
+impl PyDecimal {
+    #[must_use]
+    pub fn retrieve_value(&self) -> PgNumeric {
+        self.inner_value.clone()
+    }
+}
+
+#[pymethods]
+impl PyDecimal {
+    /// Create NUMERIC from `decimal.Decimal`, int, float or str.
+    ///
+    /// Float is converted by its shortest representation, e.g. `0.1` stays `0.1`.
+    ///
+    /// # Errors
+    /// May return Err Result if value isn't a valid decimal number.
+    #[new]
+    pub fn new_class(inner_value: &Bound<'_, PyAny>) -> RustPSQLDriverPyResult<Self> {
+        let decimal = inner_value
+            .py()
+            .import_bound("decimal")?
+            .getattr("Decimal")?
+            .call1((inner_value.str()?,))?;
+        Ok(Self {
+            inner_value: extract_decimal(&decimal)?,
+        })
+    }
+
+    #[must_use]
+    pub fn __str__(&self) -> String {
+        format!("PyDecimal, {}", self.inner_value)
+    }
+}
+
 #[pyclass]
 #[derive(Clone)]
 pub struct Text {
@@ -123,6 +229,99 @@ impl VarChar {
     }
 }
 
+/// Parameter which is sent in text format instead of binary.
+///
+/// It's useful for types whose binary encoding isn't accepted
+/// by the server-side extension.
+#[pyclass]
+#[derive(Clone)]
+pub struct AsText {
+    inner: String,
+}
+
+impl AsText {
+    #[must_use]
+    pub fn inner(&self) -> String {
+        self.inner.clone()
+    }
+}
+
+#[pymethods]
+impl AsText {
+    /// Create new `AsText` from the text representation of the value.
+    ///
+    /// # Errors
+    /// May return Err Result if `str()` of the value raised an exception.
+    #[new]
+    pub fn new_as_text(value: &Bound<'_, PyAny>) -> RustPSQLDriverPyResult<Self> {
+        Ok(Self {
+            inner: value.str()?.to_string(),
+        })
+    }
+
+    #[must_use]
+    pub fn __str__(&self) -> String {
+        format!("AsText, {}", self.inner)
+    }
+}
+
+/// Parameter with explicit cast, it's rendered as `$N::type_name`.
+///
+/// Value is sent in text format, so `PostgreSQL` parses it
+/// with the input function of the type.
+#[pyclass]
+#[derive(Clone)]
+pub struct PyCast {
+    inner: Option<String>,
+    type_name: String,
+}
+
+impl PyCast {
+    #[must_use]
+    pub fn inner(&self) -> Option<String> {
+        self.inner.clone()
+    }
+
+    #[must_use]
+    pub fn type_name(&self) -> String {
+        self.type_name.clone()
+    }
+}
+
+#[pymethods]
+impl PyCast {
+    /// Create new `PyCast` from the value and the name of the type.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Type name contains not allowed characters
+    /// 2) `str()` of the value raised an exception
+    #[new]
+    pub fn new_cast(value: &Bound<'_, PyAny>, type_name: String) -> RustPSQLDriverPyResult<Self> {
+        if !is_valid_type_name(&type_name) {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "Invalid type name for cast - {type_name}"
+            )));
+        }
+
+        let inner = if value.is_none() {
+            None
+        } else {
+            Some(value.str()?.to_string())
+        };
+        Ok(Self { inner, type_name })
+    }
+
+    #[must_use]
+    pub fn __str__(&self) -> String {
+        format!(
+            "PyCast, {}::{}",
+            self.inner.as_deref().unwrap_or("NULL"),
+            self.type_name
+        )
+    }
+}
+
 macro_rules! build_json_py_type {
     ($st_name:ident, $rust_type:ty) => {
         #[pyclass]
@@ -312,6 +511,175 @@ impl Circle {
     }
 }
 
+/// Value of `PostgreSQL` range type, like `int4range` or `tstzrange`.
+///
+/// Bound which is None is infinite.
+#[pyclass]
+pub struct Range {
+    lower: Py<PyAny>,
+    upper: Py<PyAny>,
+    lower_inc: bool,
+    upper_inc: bool,
+    empty: bool,
+}
+
+#[pymethods]
+impl Range {
+    /// Create new range.
+    ///
+    /// `bounds` is one of `[)`, `(]`, `[]` and `()`,
+    /// square bracket means that the bound is included.
+    ///
+    /// # Errors
+    /// May return Err Result if `bounds` are invalid.
+    #[new]
+    #[pyo3(signature = (lower=None, upper=None, bounds="[)", empty=false))]
+    fn new_class(
+        py: Python<'_>,
+        lower: Option<Py<PyAny>>,
+        upper: Option<Py<PyAny>>,
+        bounds: &str,
+        empty: bool,
+    ) -> RustPSQLDriverPyResult<Self> {
+        if empty {
+            return Ok(Self::empty_range(py));
+        }
+        let (lower_inc, upper_inc) = match bounds {
+            "[)" => (true, false),
+            "(]" => (false, true),
+            "[]" => (true, true),
+            "()" => (false, false),
+            _ => {
+                return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                    "Range bounds must be one of [), (], [] or (), got {bounds}"
+                )))
+            }
+        };
+        Ok(Self::from_bounds(py, lower, upper, lower_inc, upper_inc))
+    }
+
+    #[getter]
+    fn lower(&self, py: Python<'_>) -> Py<PyAny> {
+        self.lower.clone_ref(py)
+    }
+
+    #[getter]
+    fn upper(&self, py: Python<'_>) -> Py<PyAny> {
+        self.upper.clone_ref(py)
+    }
+
+    #[getter]
+    fn lower_inc(&self) -> bool {
+        self.lower_inc
+    }
+
+    #[getter]
+    fn upper_inc(&self) -> bool {
+        self.upper_inc
+    }
+
+    #[getter]
+    fn lower_inf(&self, py: Python<'_>) -> bool {
+        !self.empty && self.lower.is_none(py)
+    }
+
+    #[getter]
+    fn upper_inf(&self, py: Python<'_>) -> bool {
+        !self.empty && self.upper.is_none(py)
+    }
+
+    #[getter]
+    fn isempty(&self) -> bool {
+        self.empty
+    }
+
+    fn __bool__(&self) -> bool {
+        !self.empty
+    }
+
+    fn __eq__(&self, other: &Bound<'_, PyAny>) -> PyResult<bool> {
+        let Ok(other) = other.downcast::<Range>() else {
+            return Ok(false);
+        };
+        let other = other.borrow();
+        if self.empty || other.empty {
+            return Ok(self.empty == other.empty);
+        }
+        let py = other.py();
+        Ok(self.lower_inc == other.lower_inc
+            && self.upper_inc == other.upper_inc
+            && self.lower.bind(py).eq(&other.lower)?
+            && self.upper.bind(py).eq(&other.upper)?)
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> String {
+        if self.empty {
+            return "Range(empty=True)".into();
+        }
+        let bounds = format!(
+            "{}{}",
+            if self.lower_inc { '[' } else { '(' },
+            if self.upper_inc { ']' } else { ')' },
+        );
+        format!(
+            "Range({}, {}, '{bounds}')",
+            self.lower.bind(py),
+            self.upper.bind(py),
+        )
+    }
+}
+
+impl Range {
+    /// Create non-empty range, infinite bound is never included.
+    #[must_use]
+    pub fn from_bounds(
+        py: Python<'_>,
+        lower: Option<Py<PyAny>>,
+        upper: Option<Py<PyAny>>,
+        lower_inc: bool,
+        upper_inc: bool,
+    ) -> Self {
+        Self {
+            lower_inc: lower_inc && lower.is_some(),
+            upper_inc: upper_inc && upper.is_some(),
+            lower: lower.unwrap_or_else(|| py.None()),
+            upper: upper.unwrap_or_else(|| py.None()),
+            empty: false,
+        }
+    }
+
+    #[must_use]
+    pub fn empty_range(py: Python<'_>) -> Self {
+        Self {
+            lower: py.None(),
+            upper: py.None(),
+            lower_inc: false,
+            upper_inc: false,
+            empty: true,
+        }
+    }
+
+    /// Convert range into internal `PythonDTO`.
+    ///
+    /// # Errors
+    /// May return Err Result if cannot convert bounds.
+    pub fn to_python_dto(&self, py: Python<'_>) -> RustPSQLDriverPyResult<PythonDTO> {
+        let bound_to_dto = |bound: &Py<PyAny>| -> RustPSQLDriverPyResult<Option<PythonDTO>> {
+            if bound.is_none(py) {
+                return Ok(None);
+            }
+            Ok(Some(py_to_rust(bound.bind(py))?))
+        };
+        Ok(PythonDTO::PyRange(std::boxed::Box::new(PythonRange {
+            lower: bound_to_dto(&self.lower)?,
+            upper: bound_to_dto(&self.upper)?,
+            lower_inc: self.lower_inc,
+            upper_inc: self.upper_inc,
+            empty: self.empty,
+        })))
+    }
+}
+
 macro_rules! build_array_type {
     ($st_name:ident, $kind:path) => {
         #[pyclass]
@@ -391,6 +759,7 @@ pub fn extra_types_module(_py: Python<'_>, pymod: &Bound<'_, PyModule>) -> PyRes
     pymod.add_class::<Money>()?;
     pymod.add_class::<Float32>()?;
     pymod.add_class::<Float64>()?;
+    pymod.add_class::<PyDecimal>()?;
     pymod.add_class::<Text>()?;
     pymod.add_class::<VarChar>()?;
     pymod.add_class::<JSONB>()?;
@@ -398,6 +767,8 @@ pub fn extra_types_module(_py: Python<'_>, pymod: &Bound<'_, PyModule>) -> PyRes
     pymod.add_class::<MacAddr6>()?;
     pymod.add_class::<MacAddr8>()?;
     pymod.add_class::<CustomType>()?;
+    pymod.add_class::<AsText>()?;
+    pymod.add_class::<PyCast>()?;
     pymod.add_class::<Point>()?;
     pymod.add_class::<Box>()?;
     pymod.add_class::<Path>()?;
@@ -432,5 +803,7 @@ pub fn extra_types_module(_py: Python<'_>, pymod: &Bound<'_, PyModule>) -> PyRes
     pymod.add_class::<CircleArray>()?;
     pymod.add_class::<IntervalArray>()?;
     pymod.add_class::<PgVector>()?;
+    pymod.add_class::<PyVector>()?;
+    pymod.add_class::<Range>()?;
     Ok(())
 }
@@ -1,3 +1,9 @@
+use std::collections::HashMap;
+
+use pyo3::pyfunction;
+
+use crate::exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult};
+
 #[must_use]
 pub fn quote_ident(ident: &str) -> String {
     format!("\"{}\"", ident.replace('"', "\"\""))
@@ -7,3 +13,785 @@ pub fn quote_ident(ident: &str) -> String {
 pub fn quote_literal(string: &str) -> String {
     format!("'{}'", string.replace('\'', "''"))
 }
+
+/// Maximum length of the identifier in `PostgreSQL` (`NAMEDATALEN` - 1).
+pub const MAX_IDENT_LENGTH: usize = 63;
+
+/// Check that string follows `PostgreSQL` rules for unquoted identifiers.
+///
+/// Identifier must begin with a letter or an underscore,
+/// subsequent characters can be letters, underscores, digits or dollar signs.
+#[must_use]
+pub fn is_valid_ident(ident: &str) -> bool {
+    let mut chars = ident.chars();
+    let Some(first_char) = chars.next() else {
+        return false;
+    };
+
+    ident.len() <= MAX_IDENT_LENGTH
+        && (first_char.is_alphabetic() || first_char == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Fold identifier like `PostgreSQL` does.
+///
+/// Unquoted identifier is lowercased, quoted one keeps its case
+/// and loses the quotes, doubled quotes inside it become single ones.
+#[must_use]
+pub fn fold_identifier(ident: &str) -> String {
+    match ident
+        .strip_prefix('"')
+        .and_then(|ident| ident.strip_suffix('"'))
+    {
+        Some(quoted) => quoted.replace("\"\"", "\""),
+        None => ident.to_lowercase(),
+    }
+}
+
+/// Check that text can be safely placed inside the comment block.
+fn check_comment_text(text: &str) -> RustPSQLDriverPyResult<()> {
+    if text.contains("*/") || text.contains("/*") {
+        return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+            "Comment cannot contain comment delimiters, got - {text}"
+        )));
+    }
+    Ok(())
+}
+
+/// Prepend hints and comment blocks to the querystring.
+///
+/// Hints go first in `/*+ ... */` block as `pg_hint_plan` expects,
+/// comment goes after them in `/* ... */` block.
+/// Hints are part of the prepared statement, but comment usually
+/// differs from query to query (e.g. trace context),
+/// so querystring with comment isn't prepared
+/// and doesn't bloat the statement cache.
+///
+/// # Errors
+/// May return Err Result if hints or comment contain comment delimiters.
+pub fn annotate_query(
+    querystring: String,
+    hints: Option<String>,
+    comment: Option<String>,
+    prepared: Option<bool>,
+) -> RustPSQLDriverPyResult<(String, Option<bool>)> {
+    if hints.is_none() && comment.is_none() {
+        return Ok((querystring, prepared));
+    }
+
+    let mut annotated_query = String::with_capacity(querystring.len() + 64);
+    if let Some(hints) = hints {
+        check_comment_text(&hints)?;
+        annotated_query.push_str(&format!("/*+ {hints} */ "));
+    }
+
+    let mut prepared = prepared;
+    if let Some(comment) = comment {
+        check_comment_text(&comment)?;
+        annotated_query.push_str(&format!("/* {comment} */ "));
+        prepared = Some(false);
+    }
+
+    annotated_query.push_str(&querystring);
+    Ok((annotated_query, prepared))
+}
+
+/// Marker in the querystring which is replaced with the values list.
+pub const VALUES_MARKER: &str = "%s";
+
+/// Replace `%s` marker with the list of `rows_count` rows
+/// of `row_width` numbered parameters each.
+///
+/// `%s` in literals, quoted identifiers and comments isn't a marker.
+///
+/// `INSERT INTO users VALUES %s` with 2 rows of 2 values becomes
+/// `INSERT INTO users VALUES ($1, $2), ($3, $4)`.
+///
+/// # Errors
+/// May return Err Result if querystring doesn't contain exactly one marker.
+pub fn expand_values_marker(
+    querystring: &str,
+    row_width: usize,
+    rows_count: usize,
+) -> RustPSQLDriverPyResult<String> {
+    // Marker is searched outside of literals, quoted identifiers and comments.
+    let chars: Vec<char> = querystring.chars().collect();
+    let tokens = query_token_offsets(querystring);
+    let markers: Vec<usize> = tokens
+        .windows(2)
+        .filter_map(|pair| match pair {
+            [(offset, QueryToken::Symbol(symbol)), (word_offset, QueryToken::Word(word))]
+                if symbol == "%"
+                    && *word_offset == offset + 1
+                    && word == "s"
+                    && chars[*word_offset] == 's' =>
+            {
+                Some(*offset)
+            }
+            _ => None,
+        })
+        .collect();
+    let marker_offset = match markers.as_slice() {
+        [] => {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "Querystring must contain {VALUES_MARKER} marker, got - {querystring}"
+            )))
+        }
+        [marker_offset] => *marker_offset,
+        _ => {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "Querystring must contain only one {VALUES_MARKER} marker, got - {querystring}"
+            )))
+        }
+    };
+    let before_marker: String = chars[..marker_offset].iter().collect();
+    let after_marker: String = chars[marker_offset + VALUES_MARKER.len()..]
+        .iter()
+        .collect();
+
+    let values_list = (0..rows_count)
+        .map(|row_idx| {
+            let row_params = (1..=row_width)
+                .map(|column_idx| format!("${}", row_idx * row_width + column_idx))
+                .collect::<Vec<String>>()
+                .join(", ");
+            format!("({row_params})")
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+    Ok(format!("{before_marker}{values_list}{after_marker}"))
+}
+
+/// Check that type name can be safely placed after `::` in the querystring.
+///
+/// Type name can contain schema, modifiers and array brackets,
+/// like `public.my_type`, `varchar(10)`, `double precision[]`.
+#[must_use]
+pub fn is_valid_type_name(type_name: &str) -> bool {
+    let Some(first_char) = type_name.chars().next() else {
+        return false;
+    };
+
+    (first_char.is_alphabetic() || first_char == '_')
+        && type_name.chars().all(|c| {
+            c.is_alphanumeric() || matches!(c, '_' | '$' | '.' | ' ' | ',' | '(' | ')' | '[' | ']')
+        })
+}
+
+/// Add `::type_name` casts after `$N` placeholders of the querystring.
+///
+/// Placeholders inside string literals, quoted identifiers,
+/// dollar-quoted strings and comments aren't changed.
+#[must_use]
+pub fn cast_parameters(querystring: &str, casts: &HashMap<usize, String>) -> String {
+    let chars: Vec<char> = querystring.chars().collect();
+    let mut cast_query = String::with_capacity(querystring.len() + casts.len() * 8);
+    let mut index = 0;
+
+    let skip_until = |start: usize, end_token: &[char]| {
+        let mut index = start;
+        while index < chars.len() && !chars[index..].starts_with(end_token) {
+            index += 1;
+        }
+        (index + end_token.len()).min(chars.len())
+    };
+
+    while index < chars.len() {
+        let current = chars[index];
+        let next = chars.get(index + 1).copied();
+        let is_after_ident = index > 0
+            && (chars[index - 1].is_alphanumeric()
+                || chars[index - 1] == '_'
+                || chars[index - 1] == '$');
+
+        let token_end = if current == '-' && next == Some('-') {
+            skip_until(index, &['\n'])
+        } else if current == '/' && next == Some('*') {
+            skip_until(index + 2, &['*', '/'])
+        } else if current == '\'' || current == '"' {
+            // Doubled quote inside the literal is parsed as two literals.
+            skip_until(index + 1, &[current])
+        } else if current == '$' && !is_after_ident && next.is_some_and(|c| c.is_ascii_digit()) {
+            let number_end = chars[index + 1..]
+                .iter()
+                .position(|c| !c.is_ascii_digit())
+                .map_or(chars.len(), |position| index + 1 + position);
+            let number: String = chars[index + 1..number_end].iter().collect();
+            cast_query.push('$');
+            cast_query.push_str(&number);
+            if let Some(type_name) = number
+                .parse::<usize>()
+                .ok()
+                .and_then(|number| casts.get(&number))
+            {
+                cast_query.push_str("::");
+                cast_query.push_str(type_name);
+            }
+            index = number_end;
+            continue;
+        } else if current == '$' && !is_after_ident {
+            // Dollar-quoted string, like $tag$text$tag$.
+            match chars[index + 1..].iter().position(|c| *c == '$') {
+                Some(position) => {
+                    let tag: Vec<char> = chars[index..=index + 1 + position].to_vec();
+                    skip_until(index + tag.len(), &tag)
+                }
+                None => index + 1,
+            }
+        } else {
+            index + 1
+        };
+
+        cast_query.extend(&chars[index..token_end]);
+        index = token_end;
+    }
+
+    cast_query
+}
+
+/// Normalize querystring for the fingerprint.
+///
+/// Comments are removed, string and numeric literals are replaced with `?`,
+/// whitespaces are collapsed and everything except quoted identifiers
+/// is lowercased, so the same statement with different constants
+/// has the same normalized form.
+#[must_use]
+pub fn normalize_query(querystring: &str) -> String {
+    let chars: Vec<char> = querystring.chars().collect();
+    let mut normalized = String::with_capacity(querystring.len());
+    let mut index = 0;
+
+    let push_token = |normalized: &mut String, token: &str| {
+        if normalized.ends_with(' ') && token == " " {
+            return;
+        }
+        normalized.push_str(token);
+    };
+
+    while index < chars.len() {
+        let current = chars[index];
+        let next = chars.get(index + 1).copied();
+        let is_after_ident = index > 0
+            && (chars[index - 1].is_alphanumeric()
+                || chars[index - 1] == '_'
+                || chars[index - 1] == '$');
+
+        if current == '-' && next == Some('-') {
+            while index < chars.len() && chars[index] != '\n' {
+                index += 1;
+            }
+            push_token(&mut normalized, " ");
+        } else if current == '/' && next == Some('*') {
+            index += 2;
+            while index < chars.len()
+                && !(chars[index] == '*' && chars.get(index + 1) == Some(&'/'))
+            {
+                index += 1;
+            }
+            index += 2;
+            push_token(&mut normalized, " ");
+        } else if current == '\'' {
+            index += 1;
+            while index < chars.len() {
+                if chars[index] == '\'' {
+                    if chars.get(index + 1) == Some(&'\'') {
+                        index += 1;
+                    } else {
+                        break;
+                    }
+                }
+                index += 1;
+            }
+            index += 1;
+            push_token(&mut normalized, "?");
+        } else if current == '"' {
+            let start = index;
+            index += 1;
+            while index < chars.len() && chars[index] != '"' {
+                index += 1;
+            }
+            index += 1;
+            let quoted: String = chars[start..index.min(chars.len())].iter().collect();
+            push_token(&mut normalized, &quoted);
+        } else if current == '$' && !is_after_ident && next.is_some_and(|c| !c.is_ascii_digit()) {
+            // Dollar-quoted string, like $tag$text$tag$.
+            let tag_end = chars[index + 1..]
+                .iter()
+                .position(|c| *c == '$')
+                .map(|position| index + 1 + position);
+            let Some(tag_end) = tag_end else {
+                push_token(&mut normalized, "$");
+                index += 1;
+                continue;
+            };
+            let tag: Vec<char> = chars[index..=tag_end].to_vec();
+            index = tag_end + 1;
+            while index < chars.len() && !chars[index..].starts_with(&tag) {
+                index += 1;
+            }
+            index += tag.len();
+            push_token(&mut normalized, "?");
+        } else if current.is_ascii_digit() && !is_after_ident {
+            while index < chars.len() && (chars[index].is_ascii_digit() || chars[index] == '.') {
+                index += 1;
+            }
+            push_token(&mut normalized, "?");
+        } else if current.is_whitespace() {
+            index += 1;
+            push_token(&mut normalized, " ");
+        } else {
+            index += 1;
+            normalized.extend(current.to_lowercase());
+        }
+    }
+
+    normalized.trim().to_string()
+}
+
+/// Return fingerprint of the querystring.
+///
+/// Fingerprint is a hex encoded 64-bit FNV-1a hash of the normalized querystring,
+/// it's stable between processes and versions of the library.
+#[must_use]
+#[pyfunction]
+pub fn query_fingerprint(querystring: &str) -> String {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+    let hash = normalize_query(querystring)
+        .bytes()
+        .fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+        });
+    format!("{hash:016x}")
+}
+
+/// Token of the querystring used to guess names of the parameters.
+enum QueryToken {
+    Word(String),
+    Parameter(usize),
+    Symbol(String),
+}
+
+/// Split querystring into words, `$N` parameters and symbols.
+///
+/// Comments are skipped, literals and dollar-quoted strings
+/// become `?` symbols, words are lowercased unless they are quoted.
+fn query_tokens(querystring: &str) -> Vec<QueryToken> {
+    query_token_offsets(querystring)
+        .into_iter()
+        .map(|(_, token)| token)
+        .collect()
+}
+
+/// Split querystring into tokens with offsets of their first chars.
+fn query_token_offsets(querystring: &str) -> Vec<(usize, QueryToken)> {
+    let chars: Vec<char> = querystring.chars().collect();
+    let mut tokens = vec![];
+    let mut index = 0;
+
+    let skip_until = |start: usize, end_token: &[char]| {
+        let mut index = start;
+        while index < chars.len() && !chars[index..].starts_with(end_token) {
+            index += 1;
+        }
+        (index + end_token.len()).min(chars.len())
+    };
+    let take_while = |start: usize, predicate: &dyn Fn(char) -> bool| {
+        chars[start..]
+            .iter()
+            .position(|c| !predicate(*c))
+            .map_or(chars.len(), |position| start + position)
+    };
+
+    while index < chars.len() {
+        let start = index;
+        let current = chars[index];
+        let next = chars.get(index + 1).copied();
+
+        if current.is_whitespace() {
+            index += 1;
+        } else if current == '-' && next == Some('-') {
+            index = skip_until(index, &['\n']);
+        } else if current == '/' && next == Some('*') {
+            index = skip_until(index + 2, &['*', '/']);
+        } else if current == '\'' {
+            // Doubled quote inside the literal is parsed as two literals.
+            index = skip_until(index + 1, &['\'']);
+            tokens.push((start, QueryToken::Symbol("?".into())));
+        } else if current == '"' {
+            let ident_end = skip_until(index + 1, &['"']);
+            let ident: String = chars[index + 1..ident_end.saturating_sub(1).max(index + 1)]
+                .iter()
+                .collect();
+            tokens.push((start, QueryToken::Word(ident)));
+            index = ident_end;
+        } else if current == '$' && next.is_some_and(|c| c.is_ascii_digit()) {
+            let number_end = take_while(index + 1, &|c| c.is_ascii_digit());
+            let number: String = chars[index + 1..number_end].iter().collect();
+            tokens.push((
+                start,
+                QueryToken::Parameter(number.parse().unwrap_or_default()),
+            ));
+            index = number_end;
+        } else if current == '$' {
+            // Dollar-quoted string, like $tag$text$tag$.
+            index = match chars[index + 1..].iter().position(|c| *c == '$') {
+                Some(position) => {
+                    let tag: Vec<char> = chars[index..=index + 1 + position].to_vec();
+                    skip_until(index + tag.len(), &tag)
+                }
+                None => index + 1,
+            };
+            tokens.push((start, QueryToken::Symbol("?".into())));
+        } else if current.is_alphabetic() || current == '_' {
+            let word_end = take_while(index, &|c| c.is_alphanumeric() || c == '_' || c == '$');
+            let word: String = chars[index..word_end].iter().collect();
+            tokens.push((start, QueryToken::Word(word.to_lowercase())));
+            index = word_end;
+        } else if current.is_ascii_digit() {
+            index = take_while(index, &|c| c.is_ascii_digit() || c == '.');
+            tokens.push((start, QueryToken::Symbol("?".into())));
+        } else if "<>=!".contains(current) {
+            let operator_end = take_while(index, &|c| "<>=!".contains(c));
+            tokens.push((
+                start,
+                QueryToken::Symbol(chars[index..operator_end].iter().collect()),
+            ));
+            index = operator_end;
+        } else {
+            tokens.push((start, QueryToken::Symbol(current.to_string())));
+            index += 1;
+        }
+    }
+
+    tokens
+}
+
+/// How the `$N` parameter is used with its column.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ParameterUse {
+    /// Value is stored into the column: `INSERT` column list or `SET column = $1`.
+    Assignment,
+    /// Value is compared with the column.
+    Comparison,
+    /// Value of `LIMIT` or `OFFSET`.
+    Clause,
+}
+
+/// Columns and uses of the `$N` parameters.
+struct ParameterColumns {
+    names: Vec<Option<String>>,
+    uses: Vec<Option<ParameterUse>>,
+}
+
+impl ParameterColumns {
+    fn new(parameters_count: usize) -> Self {
+        ParameterColumns {
+            names: vec![None; parameters_count],
+            uses: vec![None; parameters_count],
+        }
+    }
+
+    /// Set name of the `$N` parameter, names from different places must match.
+    ///
+    /// Parameter which is both stored and compared is a compared one.
+    fn set(
+        &mut self,
+        number: usize,
+        name: &str,
+        parameter_use: ParameterUse,
+    ) -> RustPSQLDriverPyResult<()> {
+        let Some(idx) = number.checked_sub(1).filter(|idx| *idx < self.names.len()) else {
+            return Ok(());
+        };
+        match &self.names[idx] {
+            Some(parameter_name) if parameter_name != name => {
+                return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                    "Parameter ${number} is used for `{parameter_name}` and `{name}`, pass parameter_names"
+                )));
+            }
+            _ => self.names[idx] = Some(name.to_string()),
+        }
+        if self.uses[idx] != Some(ParameterUse::Comparison) {
+            self.uses[idx] = Some(parameter_use);
+        }
+        Ok(())
+    }
+}
+
+/// Set names of the parameters from the first `VALUES` row of `INSERT` statement.
+///
+/// Parameter gets name of the column in the same position of the column list.
+fn set_insert_parameter_names(
+    tokens: &[QueryToken],
+    parameter_columns: &mut ParameterColumns,
+) -> RustPSQLDriverPyResult<()> {
+    let is_word =
+        |idx: usize, word: &str| matches!(tokens.get(idx), Some(QueryToken::Word(w)) if w == word);
+    let is_symbol = |idx: usize, symbol: &str| matches!(tokens.get(idx), Some(QueryToken::Symbol(s)) if s == symbol);
+
+    let Some(insert_idx) =
+        (0..tokens.len()).find(|idx| is_word(*idx, "insert") && is_word(idx + 1, "into"))
+    else {
+        return Ok(());
+    };
+    // Skip qualified table name.
+    let mut idx = insert_idx + 3;
+    while is_symbol(idx, ".") {
+        idx += 2;
+    }
+    if !is_symbol(idx, "(") {
+        return Ok(());
+    }
+
+    let mut columns = vec![];
+    idx += 1;
+    while let Some(QueryToken::Word(column)) = tokens.get(idx) {
+        columns.push(column.clone());
+        idx += 1;
+        if !is_symbol(idx, ",") {
+            break;
+        }
+        idx += 1;
+    }
+    if !is_symbol(idx, ")") || !is_word(idx + 1, "values") || !is_symbol(idx + 2, "(") {
+        return Ok(());
+    }
+
+    // Split the first row into expressions by top level commas.
+    let mut expressions: Vec<Vec<&QueryToken>> = vec![vec![]];
+    let mut depth = 0;
+    for token in &tokens[idx + 3..] {
+        match token {
+            QueryToken::Symbol(symbol) if symbol == "(" => depth += 1,
+            QueryToken::Symbol(symbol) if symbol == ")" && depth == 0 => break,
+            QueryToken::Symbol(symbol) if symbol == ")" => depth -= 1,
+            QueryToken::Symbol(symbol) if symbol == "," && depth == 0 => {
+                expressions.push(vec![]);
+                continue;
+            }
+            _ => {}
+        }
+        if let Some(expression) = expressions.last_mut() {
+            expression.push(token);
+        }
+    }
+
+    for (column, expression) in columns.iter().zip(expressions) {
+        // Only plain parameter, optionally with the cast, is named.
+        let is_cast = |token: Option<&&QueryToken>| {
+            token.map_or(
+                true,
+                |token| matches!(token, QueryToken::Symbol(s) if s == ":"),
+            )
+        };
+        if let Some(QueryToken::Parameter(number)) = expression.first() {
+            if is_cast(expression.get(1)) {
+                parameter_columns.set(*number, column, ParameterUse::Assignment)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Guess names of the `$N` parameters of the querystring.
+///
+/// Parameter gets name of the column it's compared with (`column = $1`),
+/// name of the column from `INSERT` column list for the first `VALUES` row,
+/// or `limit`/`offset` name.
+/// Names are lowercased unless the column is quoted.
+///
+/// # Errors
+/// May return Err Result if name of some parameter can't be guessed
+/// or the parameter is used for different columns.
+pub fn guess_parameter_names(
+    querystring: &str,
+    parameters_count: usize,
+) -> RustPSQLDriverPyResult<Vec<String>> {
+    let names = guess_known_parameter_names(querystring, parameters_count)?;
+    let unnamed: Vec<String> = names
+        .iter()
+        .enumerate()
+        .filter(|(_, name)| name.is_none())
+        .map(|(idx, _)| format!("${}", idx + 1))
+        .collect();
+    if !unnamed.is_empty() {
+        return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+            "Cannot guess names of the parameters {}, pass parameter_names",
+            unnamed.join(", ")
+        )));
+    }
+    Ok(names.into_iter().flatten().collect())
+}
+
+/// Guess names of the `$N` parameters, None for the parameters
+/// which name cannot be guessed.
+///
+/// # Errors
+/// May return Err Result if the parameter is used for different columns.
+pub fn guess_known_parameter_names(
+    querystring: &str,
+    parameters_count: usize,
+) -> RustPSQLDriverPyResult<Vec<Option<String>>> {
+    Ok(guess_parameter_columns(querystring, parameters_count)?
+        .into_iter()
+        .map(|column| column.map(|(name, _)| name))
+        .collect())
+}
+
+/// Guess columns of the `$N` parameters and how the parameters are used with them,
+/// None for the parameters which column cannot be guessed.
+///
+/// `column = $1` in `SET` clause of `UPDATE`, `ON CONFLICT` or `MERGE`
+/// and `INSERT` column list are assignments, other operators are comparisons.
+///
+/// # Errors
+/// May return Err Result if the parameter is used for different columns.
+pub fn guess_parameter_columns(
+    querystring: &str,
+    parameters_count: usize,
+) -> RustPSQLDriverPyResult<Vec<Option<(String, ParameterUse)>>> {
+    const COMPARISON_OPERATORS: [&str; 9] =
+        ["=", "<>", "!=", "<", ">", "<=", ">=", "like", "ilike"];
+    const SET_CLAUSE_ENDS: [&str; 4] = ["where", "from", "returning", "when"];
+
+    let tokens = query_tokens(querystring);
+    let mut parameter_columns = ParameterColumns::new(parameters_count);
+    let mut depth = 0_usize;
+    // Depth of the parentheses where the current `SET` clause is.
+    let mut set_depth: Option<usize> = None;
+
+    for (idx, token) in tokens.iter().enumerate() {
+        match token {
+            QueryToken::Symbol(symbol) if symbol == "(" => depth += 1,
+            QueryToken::Symbol(symbol) if symbol == ")" => {
+                depth = depth.saturating_sub(1);
+                set_depth = set_depth.filter(|set_depth| *set_depth <= depth);
+            }
+            QueryToken::Symbol(symbol) if symbol == ";" => set_depth = None,
+            QueryToken::Word(word) if word == "set" => set_depth = Some(depth),
+            QueryToken::Word(word)
+                if set_depth == Some(depth) && SET_CLAUSE_ENDS.contains(&word.as_str()) =>
+            {
+                set_depth = None;
+            }
+            _ => {}
+        }
+
+        let QueryToken::Parameter(number) = token else {
+            continue;
+        };
+        let previous = idx.checked_sub(1).and_then(|idx| tokens.get(idx));
+        let before_previous = idx.checked_sub(2).and_then(|idx| tokens.get(idx));
+        match (before_previous, previous) {
+            (
+                Some(QueryToken::Word(column)),
+                Some(QueryToken::Symbol(operator) | QueryToken::Word(operator)),
+            ) if COMPARISON_OPERATORS.contains(&operator.as_str()) => {
+                // Assigned column follows `SET` or the comma of the previous assignment.
+                let before_column = idx.checked_sub(3).and_then(|idx| tokens.get(idx));
+                let is_assignment = operator == "="
+                    && set_depth == Some(depth)
+                    && matches!(
+                        before_column,
+                        Some(QueryToken::Word(word) | QueryToken::Symbol(word))
+                            if word == "set" || word == ","
+                    );
+                let parameter_use = if is_assignment {
+                    ParameterUse::Assignment
+                } else {
+                    ParameterUse::Comparison
+                };
+                parameter_columns.set(*number, column, parameter_use)?;
+            }
+            (_, Some(QueryToken::Word(keyword))) if keyword == "limit" || keyword == "offset" => {
+                parameter_columns.set(*number, keyword, ParameterUse::Clause)?;
+            }
+            _ => {}
+        }
+    }
+    set_insert_parameter_names(&tokens, &mut parameter_columns)?;
+
+    Ok(parameter_columns
+        .names
+        .into_iter()
+        .zip(parameter_columns.uses)
+        .map(|(name, parameter_use)| name.zip(parameter_use))
+        .collect())
+}
+
+/// Return words of the querystring outside of literals and comments.
+///
+/// Unquoted words are lowercased, quoted ones keep their case.
+#[must_use]
+pub fn query_words(querystring: &str) -> Vec<String> {
+    query_tokens(querystring)
+        .into_iter()
+        .filter_map(|token| match token {
+            QueryToken::Word(word) => Some(word),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Check that querystring can be declared as a cursor.
+///
+/// Only `SELECT`, `VALUES` and `TABLE` queries can be, without `INTO`
+/// and without data-modifying statements in `WITH`.
+/// `FOR UPDATE` and `FOR NO KEY UPDATE` row locks are allowed.
+#[must_use]
+pub fn is_cursor_query(querystring: &str) -> bool {
+    let tokens = query_tokens(querystring);
+    let first_word = tokens
+        .iter()
+        .find(|token| !matches!(token, QueryToken::Symbol(symbol) if symbol == "("));
+    if !matches!(
+        first_word,
+        Some(QueryToken::Word(word)) if ["select", "values", "table", "with"].contains(&word.as_str())
+    ) {
+        return false;
+    }
+
+    let mut previous_word = "";
+    for token in &tokens {
+        let QueryToken::Word(word) = token else {
+            continue;
+        };
+        let is_row_lock = word == "update" && ["for", "key"].contains(&previous_word);
+        if ["into", "insert", "update", "delete", "merge"].contains(&word.as_str()) && !is_row_lock
+        {
+            return false;
+        }
+        previous_word = word;
+    }
+    true
+}
+
+/// Check that name matches the pattern, case-insensitive.
+///
+/// `*` in the pattern matches any number of characters.
+#[must_use]
+pub fn matches_name_pattern(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let name = name.to_lowercase();
+    let mut parts = pattern.split('*');
+    let Some(first_part) = parts.next() else {
+        return name.is_empty();
+    };
+    let Some(mut rest) = name.strip_prefix(first_part) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last_part, middle_parts)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle_parts {
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last_part.len() && rest.ends_with(last_part)
+}
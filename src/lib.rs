@@ -4,9 +4,12 @@ pub mod driver;
 pub mod exceptions;
 pub mod extra_types;
 pub mod format_helpers;
+pub mod logging_bridge;
 pub mod query_result;
 pub mod row_factories;
 pub mod runtime;
+pub mod tools;
+pub mod type_registry;
 pub mod value_converter;
 
 use common::add_module;
@@ -18,20 +21,58 @@ use pyo3::{
     wrap_pyfunction, Bound, PyResult, Python,
 };
 use row_factories::row_factories_module;
+use tools::tools_module;
 
 #[pymodule]
 #[pyo3(name = "_internal")]
 fn psqlpy(py: Python<'_>, pymod: &Bound<'_, PyModule>) -> PyResult<()> {
     pymod.add_class::<driver::connection_pool::ConnectionPool>()?;
     pymod.add_class::<driver::connection_pool::ConnectionPoolStatus>()?;
+    pymod.add_class::<driver::statement_cache::StatementCacheInfo>()?;
+    pymod.add_class::<driver::statement_stats::StatementStat>()?;
+    pymod.add_class::<driver::warm_statements::WarmStatement>()?;
+    pymod.add_class::<driver::pool_hooks::HookMetrics>()?;
+    pymod.add_class::<driver::scheduler::ScheduledTask>()?;
+    pymod.add_class::<driver::session_pooler::PooledSession>()?;
+    pymod.add_class::<driver::prepared_transactions::PreparedTransaction>()?;
+    pymod.add_class::<driver::prepared_transactions::RecoveryPolicy>()?;
+    pymod.add_class::<driver::query_registry::QueryRegistry>()?;
+    pymod.add_class::<driver::query_registry::RegisteredQuery>()?;
     pymod.add_class::<driver::connection_pool_builder::ConnectionPoolBuilder>()?;
+    pymod.add_class::<driver::tenant_pool::TenantPool>()?;
+    pymod.add_class::<driver::replica_pool::ReplicaPool>()?;
+    pymod.add_class::<driver::column_codecs::ColumnCodec>()?;
+    pymod.add_class::<driver::redaction::RedactionPolicy>()?;
+    pymod.add_class::<driver::result_masking::ColumnMask>()?;
     pymod.add_function(wrap_pyfunction!(driver::connection_pool::connect, pymod)?)?;
+    pymod.add_function(wrap_pyfunction!(
+        driver::connection::open_connection,
+        pymod
+    )?)?;
+    pymod.add_function(wrap_pyfunction!(type_registry::register_composite, pymod)?)?;
+    pymod.add_function(wrap_pyfunction!(format_helpers::query_fingerprint, pymod)?)?;
+    pymod.add_function(wrap_pyfunction!(runtime::debug_loop_integration, pymod)?)?;
+    pymod.add_function(wrap_pyfunction!(logging_bridge::enable_logging, pymod)?)?;
+    pymod.add_function(wrap_pyfunction!(logging_bridge::disable_logging, pymod)?)?;
     pymod.add_class::<driver::connection::Connection>()?;
+    pymod.add_class::<driver::shared_connection::SharedConnectionHandle>()?;
+    pymod.add_class::<driver::annotation::ConnectionAnnotation>()?;
+    pymod.add_class::<driver::ssl_info::SslCertificate>()?;
+    pymod.add_class::<driver::diagnostics::SessionDiagnostics>()?;
+    pymod.add_class::<driver::diagnostics::LockInfo>()?;
     pymod.add_class::<driver::transaction::Transaction>()?;
+    pymod.add_class::<driver::savepoint::Savepoint>()?;
     pymod.add_class::<driver::cursor::Cursor>()?;
+    pymod.add_class::<driver::partial_statement::PartialStatement>()?;
+    pymod.add_class::<driver::copy_out::CopyOutChunks>()?;
+    pymod.add_class::<driver::pipeline::Pipeline>()?;
     pymod.add_class::<driver::listener::core::Listener>()?;
     pymod.add_class::<driver::listener::structs::ListenerNotificationMsg>()?;
+    pymod.add_class::<driver::listener::channel_listener::ChannelListener>()?;
+    pymod.add_class::<driver::listener::table_watcher::TableWatcher>()?;
     pymod.add_class::<driver::transaction_options::IsolationLevel>()?;
+    pymod.add_class::<driver::isolation_advisor::IsolationUpgradeWarning>()?;
+    pymod.add_class::<driver::leak_detector::ConnectionLeakWarning>()?;
     pymod.add_class::<driver::transaction_options::SynchronousCommit>()?;
     pymod.add_class::<driver::transaction_options::ReadVariant>()?;
     pymod.add_class::<driver::common_options::ConnRecyclingMethod>()?;
@@ -39,10 +80,20 @@ fn psqlpy(py: Python<'_>, pymod: &Bound<'_, PyModule>) -> PyResult<()> {
     pymod.add_class::<driver::common_options::TargetSessionAttrs>()?;
     pymod.add_class::<driver::common_options::SslMode>()?;
     pymod.add_class::<driver::common_options::KeepaliveConfig>()?;
+    pymod.add_class::<driver::common_options::MergeMatchedAction>()?;
+    pymod.add_class::<driver::common_options::MergeNotMatchedAction>()?;
+    pymod.add_class::<driver::common_options::CopyCommandFormat>()?;
+    pymod.add_class::<driver::common_options::ResultSchemaFormat>()?;
+    pymod.add_class::<query_result::ColumnCase>()?;
     pymod.add_class::<query_result::PSQLDriverPyQueryResult>()?;
     pymod.add_class::<query_result::PSQLDriverSinglePyQueryResult>()?;
+    pymod.add_class::<driver::spilled_result::SpilledQueryResult>()?;
+    pymod.add_class::<driver::row_iterator::RowIterator>()?;
+    pymod.add_class::<driver::notices::NoticeSeverity>()?;
+    pymod.add_class::<driver::notices::NoticeSummary>()?;
     add_module(py, pymod, "extra_types", extra_types_module)?;
     add_module(py, pymod, "exceptions", python_exceptions_module)?;
     add_module(py, pymod, "row_factories", row_factories_module)?;
+    add_module(py, pymod, "tools", tools_module)?;
     Ok(())
 }
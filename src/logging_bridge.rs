@@ -0,0 +1,187 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicI32, Ordering},
+        Once,
+    },
+};
+
+use pyo3::{
+    pyfunction,
+    types::{PyAnyMethods, PyDict, PyDictMethods},
+    PyResult, Python,
+};
+use tracing::{
+    field::{Field, Visit},
+    subscriber::Interest,
+    Event, Level, Metadata, Subscriber,
+};
+use tracing_subscriber::{
+    layer::{Context, SubscriberExt},
+    Layer, Registry,
+};
+
+/// Target prefix of the driver events, other events aren't forwarded.
+const DRIVER_TARGET: &str = "psqlpy";
+
+/// Python level of the `logging.INFO`.
+const PY_LOGGING_INFO: i32 = 20;
+
+/// Minimal Python level of the forwarded events.
+///
+/// Nothing is forwarded until `enable_logging` is called.
+static MIN_LEVEL: AtomicI32 = AtomicI32::new(i32::MAX);
+static INSTALL_LAYER: Once = Once::new();
+
+/// Convert `tracing` level into the Python `logging` level.
+fn python_level(level: &Level) -> i32 {
+    match *level {
+        Level::TRACE => 5,
+        Level::DEBUG => 10,
+        Level::INFO => 20,
+        Level::WARN => 30,
+        Level::ERROR => 40,
+    }
+}
+
+/// Name of the Python logger for the event target,
+/// `psqlpy::driver::connection_pool` becomes `psqlpy.driver.connection_pool`.
+fn logger_name(target: &str) -> String {
+    target.replace("::", ".")
+}
+
+/// Value of the event field.
+enum FieldValue {
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Str(String),
+}
+
+/// Message and fields of the event.
+#[derive(Default)]
+struct EventFields {
+    message: String,
+    fields: Vec<(&'static str, FieldValue)>,
+}
+
+impl Visit for EventFields {
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.fields.push((field.name(), FieldValue::Bool(value)));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.fields.push((field.name(), FieldValue::Int(value)));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.fields.push((field.name(), FieldValue::UInt(value)));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.fields.push((field.name(), FieldValue::Float(value)));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_owned();
+        } else {
+            self.fields
+                .push((field.name(), FieldValue::Str(value.to_owned())));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        } else {
+            self.fields
+                .push((field.name(), FieldValue::Str(format!("{value:?}"))));
+        }
+    }
+}
+
+/// Layer which forwards driver events into Python `logging`.
+///
+/// Every event goes to the logger named after its module,
+/// fields of the event are passed in the `psqlpy` attribute of the record.
+struct PythonLoggingLayer;
+
+impl<S: Subscriber> Layer<S> for PythonLoggingLayer {
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        // Level can be changed at any moment, so it's checked on every event.
+        if metadata.target().starts_with(DRIVER_TARGET) {
+            Interest::sometimes()
+        } else {
+            Interest::never()
+        }
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+        metadata.target().starts_with(DRIVER_TARGET)
+            && python_level(metadata.level()) >= MIN_LEVEL.load(Ordering::Relaxed)
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut event_fields = EventFields::default();
+        event.record(&mut event_fields);
+        let metadata = event.metadata();
+        Python::with_gil(|gil| {
+            // There is no caller to raise the error to.
+            if let Err(err) = log_event(gil, metadata, event_fields) {
+                err.write_unraisable(gil, None);
+            }
+        });
+    }
+}
+
+/// Pass the event to the Python logger if it's enabled for the level.
+fn log_event(gil: Python<'_>, metadata: &Metadata<'_>, event_fields: EventFields) -> PyResult<()> {
+    let level = python_level(metadata.level());
+    let logger = gil
+        .import("logging")?
+        .call_method1("getLogger", (logger_name(metadata.target()),))?;
+    if !logger.call_method1("isEnabledFor", (level,))?.is_truthy()? {
+        return Ok(());
+    }
+
+    let fields = PyDict::new(gil);
+    for (name, value) in event_fields.fields {
+        match value {
+            FieldValue::Bool(value) => fields.set_item(name, value)?,
+            FieldValue::Int(value) => fields.set_item(name, value)?,
+            FieldValue::UInt(value) => fields.set_item(name, value)?,
+            FieldValue::Float(value) => fields.set_item(name, value)?,
+            FieldValue::Str(value) => fields.set_item(name, value)?,
+        }
+    }
+    let extra = PyDict::new(gil);
+    extra.set_item("psqlpy", fields)?;
+    let kwargs = PyDict::new(gil);
+    kwargs.set_item("extra", extra)?;
+    logger.call_method("log", (level, event_fields.message), Some(&kwargs))?;
+    Ok(())
+}
+
+/// Forward driver events with `level` and higher into Python `logging`.
+///
+/// Events go to the loggers under `psqlpy`,
+/// structured fields of the event are available as `record.psqlpy` dict.
+#[pyfunction]
+#[pyo3(signature = (level=PY_LOGGING_INFO))]
+pub fn enable_logging(level: i32) {
+    INSTALL_LAYER.call_once(|| {
+        // Another subscriber may be installed by the embedding application,
+        // then events go there.
+        let _ =
+            tracing::subscriber::set_global_default(Registry::default().with(PythonLoggingLayer));
+    });
+    MIN_LEVEL.store(level, Ordering::Relaxed);
+}
+
+/// Stop forwarding driver events into Python `logging`.
+#[pyfunction]
+pub fn disable_logging() {
+    MIN_LEVEL.store(i32::MAX, Ordering::Relaxed);
+}
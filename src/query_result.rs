@@ -1,7 +1,107 @@
-use pyo3::{prelude::*, pyclass, pymethods, types::PyDict, Py, PyAny, Python, ToPyObject};
-use tokio_postgres::Row;
+use pyo3::{
+    prelude::*,
+    pyclass, pymethods,
+    types::{PyDict, PyType},
+    Py, PyAny, Python, ToPyObject,
+};
+use tokio_postgres::{Column, Row};
 
-use crate::{exceptions::rust_errors::RustPSQLDriverPyResult, value_converter::postgres_to_py};
+use crate::{
+    driver::{
+        column_codecs::ColumnCodecs,
+        notices::NoticeSummary,
+        result_masking::{ColumnMask, ColumnMasks},
+    },
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    format_helpers::query_fingerprint,
+    value_converter::postgres_to_py,
+};
+
+/// Case of the column names in the dict rows.
+#[pyclass(eq, eq_int)]
+#[derive(Clone, Copy, PartialEq)]
+pub enum ColumnCase {
+    /// `user_id` -> `user_id`, `UserId` -> `userid`.
+    Lower,
+    /// `user_id` -> `USER_ID`.
+    Upper,
+    /// `user_id` -> `userId`.
+    Camel,
+    /// `userId` -> `user_id`.
+    Snake,
+}
+
+impl ColumnCase {
+    #[must_use]
+    pub fn apply(&self, column_name: &str) -> String {
+        match self {
+            ColumnCase::Lower => column_name.to_lowercase(),
+            ColumnCase::Upper => column_name.to_uppercase(),
+            ColumnCase::Camel => {
+                let mut camel_name = String::with_capacity(column_name.len());
+                for (index, part) in column_name
+                    .split('_')
+                    .filter(|part| !part.is_empty())
+                    .enumerate()
+                {
+                    let mut chars = part.chars();
+                    if let Some(first_char) = chars.next() {
+                        if index == 0 {
+                            camel_name.extend(first_char.to_lowercase());
+                        } else {
+                            camel_name.extend(first_char.to_uppercase());
+                        }
+                        camel_name.push_str(&chars.as_str().to_lowercase());
+                    }
+                }
+                camel_name
+            }
+            ColumnCase::Snake => {
+                let mut snake_name = String::with_capacity(column_name.len() + 4);
+                let mut previous_char: Option<char> = None;
+                for current_char in column_name.chars() {
+                    if current_char.is_uppercase()
+                        && previous_char.is_some_and(|c| c.is_lowercase() || c.is_ascii_digit())
+                    {
+                        snake_name.push('_');
+                    }
+                    snake_name.extend(current_char.to_lowercase());
+                    previous_char = Some(current_char);
+                }
+                snake_name
+            }
+        }
+    }
+}
+
+/// Build names of the dict keys for the columns.
+///
+/// Name from `column_mapping` has priority over `column_case`.
+///
+/// # Errors
+/// May return Err Result if mapping value is not a string.
+fn build_column_names(
+    py: Python<'_>,
+    columns: &[Column],
+    column_case: Option<ColumnCase>,
+    column_mapping: Option<&Py<PyDict>>,
+) -> RustPSQLDriverPyResult<Vec<Py<PyAny>>> {
+    let mut column_names = Vec::with_capacity(columns.len());
+    for column in columns {
+        if let Some(column_mapping) = column_mapping {
+            if let Some(mapped_name) = column_mapping.bind(py).get_item(column.name())? {
+                column_names.push(mapped_name.extract::<String>()?.to_object(py));
+                continue;
+            }
+        }
+        let column_name = match column_case {
+            Some(column_case) => column_case.apply(column.name()),
+            None => column.name().to_string(),
+        };
+        column_names.push(column_name.to_object(py));
+    }
+    Ok(column_names)
+}
 
 /// Convert postgres `Row` into Python Dict.
 ///
@@ -11,23 +111,52 @@ use crate::{exceptions::rust_errors::RustPSQLDriverPyResult, value_converter::po
 /// postgres type to python or set new key-value pair
 /// in python dict.
 #[allow(clippy::ref_option)]
-fn row_to_dict<'a>(
+pub fn row_to_dict<'a>(
     py: Python<'a>,
     postgres_row: &'a Row,
     custom_decoders: &Option<Py<PyDict>>,
+    column_names: Option<&[Py<PyAny>]>,
 ) -> RustPSQLDriverPyResult<pyo3::Bound<'a, PyDict>> {
     let python_dict = PyDict::new(py);
     for (column_idx, column) in postgres_row.columns().iter().enumerate() {
         let python_type = postgres_to_py(py, postgres_row, column, column_idx, custom_decoders)?;
-        python_dict.set_item(column.name().to_object(py), python_type)?;
+        match column_names.and_then(|column_names| column_names.get(column_idx)) {
+            Some(column_name) => python_dict.set_item(column_name, python_type)?,
+            None => python_dict.set_item(column.name().to_object(py), python_type)?,
+        }
     }
     Ok(python_dict)
 }
 
+/// Build object of the row with factory passed to `execute`/`fetch`.
+///
+/// Class (dataclass, named tuple, pydantic model) gets columns
+/// as keyword arguments, any other callable gets the dict.
+///
+/// # Errors
+/// May return Err Result if factory raises an exception.
+fn apply_row_factory(
+    py: Python<'_>,
+    row_factory: &Py<PyAny>,
+    python_dict: &pyo3::Bound<'_, PyDict>,
+) -> RustPSQLDriverPyResult<Py<PyAny>> {
+    if row_factory.bind(py).is_instance_of::<PyType>() {
+        return Ok(row_factory.call(py, (), Some(python_dict))?);
+    }
+    Ok(row_factory.call1(py, (python_dict,))?)
+}
+
 #[pyclass(name = "QueryResult")]
 #[allow(clippy::module_name_repetitions)]
 pub struct PSQLDriverPyQueryResult {
     inner: Vec<Row>,
+    querystring: Option<String>,
+    notice_summary: NoticeSummary,
+    row_factory: Option<Py<PyAny>>,
+    column_codecs: ColumnCodecs,
+    column_masks: ColumnMasks,
+    rows_affected: Option<u64>,
+    closed: bool,
 }
 
 impl PSQLDriverPyQueryResult {
@@ -35,41 +164,303 @@ impl PSQLDriverPyQueryResult {
     pub fn new(database_result: Vec<Row>) -> Self {
         PSQLDriverPyQueryResult {
             inner: database_result,
+            querystring: None,
+            notice_summary: NoticeSummary::default(),
+            row_factory: None,
+            column_codecs: ColumnCodecs::default(),
+            column_masks: ColumnMasks::default(),
+            rows_affected: None,
+            closed: false,
         }
     }
 
+    /// Set number of the rows affected by the statement.
+    #[must_use]
+    pub fn with_rows_affected(mut self, rows_affected: u64) -> Self {
+        self.rows_affected = Some(rows_affected);
+        self
+    }
+
+    /// Set summary of the notices received while the query was executed.
+    #[must_use]
+    pub fn with_notice_summary(mut self, notice_summary: NoticeSummary) -> Self {
+        self.notice_summary = notice_summary;
+        self
+    }
+
+    /// Set querystring which produced the result.
+    #[must_use]
+    pub fn with_querystring(mut self, querystring: String) -> Self {
+        self.querystring = Some(querystring);
+        self
+    }
+
+    /// Set factory which builds rows returned by `result()`.
+    #[must_use]
+    pub fn with_row_factory(mut self, row_factory: Option<Py<PyAny>>) -> Self {
+        self.row_factory = row_factory;
+        self
+    }
+
+    /// Set codecs which decrypt values of the encrypted columns.
+    #[must_use]
+    pub fn with_column_codecs(mut self, column_codecs: ColumnCodecs) -> Self {
+        self.column_codecs = column_codecs;
+        self
+    }
+
+    /// Set masks of the sensitive columns.
+    #[must_use]
+    pub fn with_column_masks(mut self, column_masks: ColumnMasks) -> Self {
+        self.column_masks = column_masks;
+        self
+    }
+
     #[must_use]
     pub fn is_empty(&self) -> bool {
         self.inner.is_empty()
     }
+
+    /// Return new result with the same rows and settings.
+    ///
+    /// Closing the copy doesn't affect the original result.
+    #[must_use]
+    pub fn copy_ref(&self, py: Python<'_>) -> Self {
+        PSQLDriverPyQueryResult {
+            inner: self.inner.clone(),
+            querystring: self.querystring.clone(),
+            notice_summary: self.notice_summary,
+            row_factory: self
+                .row_factory
+                .as_ref()
+                .map(|row_factory| row_factory.clone_ref(py)),
+            column_codecs: self.column_codecs.clone(),
+            column_masks: self.column_masks.clone(),
+            rows_affected: self.rows_affected,
+            closed: self.closed,
+        }
+    }
+
+    /// Return new result with the same settings and other rows.
+    ///
+    /// Number of the affected rows isn't copied,
+    /// because it belongs to the original rows.
+    #[must_use]
+    pub fn with_other_rows(&self, py: Python<'_>, rows: Vec<Row>) -> Self {
+        let mut result = self.copy_ref(py);
+        result.inner = rows;
+        result.rows_affected = None;
+        result
+    }
+
+    /// Return rows of the result.
+    #[must_use]
+    pub fn rows(&self) -> &[Row] {
+        &self.inner
+    }
+
+    /// Return rows of the result if it isn't closed.
+    ///
+    /// # Errors
+    /// May return Err Result if result is closed.
+    fn open_rows(&self) -> RustPSQLDriverPyResult<&[Row]> {
+        if self.closed {
+            return Err(RustPSQLDriverError::QueryResultClosedError);
+        }
+        Ok(&self.inner)
+    }
+
+    /// Return number of bytes retained by the rows.
+    ///
+    /// Column descriptions are shared between the rows
+    /// of one statement, so they aren't counted.
+    fn retained_bytes(&self) -> usize {
+        let row_buffers: usize = self
+            .inner
+            .iter()
+            .map(|row| {
+                (0..row.len())
+                    .filter_map(|column_idx| row.col_buffer(column_idx))
+                    .map(<[u8]>::len)
+                    .sum::<usize>()
+            })
+            .sum();
+        self.inner.capacity() * std::mem::size_of::<Row>() + row_buffers
+    }
 }
 
 #[pymethods]
 impl PSQLDriverPyQueryResult {
+    /// Return fingerprint of the querystring which produced the result.
+    #[getter]
+    fn fingerprint(&self) -> Option<String> {
+        self.querystring.as_deref().map(query_fingerprint)
+    }
+
+    /// Return summary of the notices received while the query was executed.
+    ///
+    /// Notices themselves aren't kept, summary only shows
+    /// that the query produced them.
+    #[getter]
+    fn notice_summary(&self) -> NoticeSummary {
+        self.notice_summary
+    }
+
+    /// Return number of the rows affected by the statement.
+    ///
+    /// None if the result wasn't produced by `execute` or `fetch`.
+    #[getter]
+    fn rows_affected(&self) -> Option<u64> {
+        self.rows_affected
+    }
+
+    /// Return `True` if rows of the result are released.
+    #[getter]
+    fn closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Release rows of the result.
+    ///
+    /// Rows are freed immediately instead of waiting for the garbage
+    /// collector, any access to the rows after it raises an error.
+    fn close(&mut self) {
+        self.inner = Vec::new();
+        self.closed = true;
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    fn __exit__(
+        &mut self,
+        _exception_type: Py<PyAny>,
+        _exception: Py<PyAny>,
+        _traceback: Py<PyAny>,
+    ) {
+        self.close();
+    }
+
+    /// Return size of the result including retained rows.
+    fn __sizeof__(&self) -> usize {
+        std::mem::size_of::<Self>() + self.retained_bytes()
+    }
+
     /// Return result as a Python list of dicts.
     ///
     /// It's a common variant how to return a result for the future
     /// processing.
+    /// Column names are converted once for all rows
+    /// with `column_mapping` and `column_case`.
+    /// If query was executed with `row_factory`, list contains
+    /// objects built by it from every row.
     ///
     /// # Errors
     ///
     /// May return Err Result if can not convert
     /// postgres type to python or set new key-value pair
     /// in python dict.
-    #[pyo3(signature = (custom_decoders=None))]
+    #[pyo3(signature = (custom_decoders=None, column_case=None, column_mapping=None))]
     #[allow(clippy::needless_pass_by_value)]
     pub fn result(
         &self,
         py: Python<'_>,
         custom_decoders: Option<Py<PyDict>>,
+        column_case: Option<ColumnCase>,
+        column_mapping: Option<Py<PyDict>>,
     ) -> RustPSQLDriverPyResult<Py<PyAny>> {
-        let mut result: Vec<pyo3::Bound<'_, PyDict>> = vec![];
-        for row in &self.inner {
-            result.push(row_to_dict(py, row, &custom_decoders)?);
+        let rows = self.open_rows()?;
+        let column_names = match rows.first() {
+            Some(first_row) if column_case.is_some() || column_mapping.is_some() => {
+                Some(build_column_names(
+                    py,
+                    first_row.columns(),
+                    column_case,
+                    column_mapping.as_ref(),
+                )?)
+            }
+            _ => None,
+        };
+
+        let mut result: Vec<Py<PyAny>> = Vec::with_capacity(rows.len());
+        for row in rows {
+            let python_dict = row_to_dict(py, row, &custom_decoders, column_names.as_deref())?;
+            self.column_codecs
+                .decrypt_row(row, &python_dict, column_names.as_deref())?;
+            self.column_masks
+                .mask_row(row, &python_dict, column_names.as_deref())?;
+            match &self.row_factory {
+                Some(row_factory) => result.push(apply_row_factory(py, row_factory, &python_dict)?),
+                None => result.push(python_dict.unbind().into_any()),
+            }
         }
         Ok(result.to_object(py))
     }
 
+    /// Return result as a Python dict of columns.
+    ///
+    /// Keys are column names, values are lists of the column values
+    /// in the row order. All columns are filled in one pass over the rows.
+    /// Empty result is an empty dict.
+    ///
+    /// # Errors
+    ///
+    /// May return Err Result if can not convert
+    /// postgres type to python or set new key-value pair
+    /// in python dict.
+    #[pyo3(signature = (custom_decoders=None, column_case=None, column_mapping=None))]
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn columnar(
+        &self,
+        py: Python<'_>,
+        custom_decoders: Option<Py<PyDict>>,
+        column_case: Option<ColumnCase>,
+        column_mapping: Option<Py<PyDict>>,
+    ) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        let rows = self.open_rows()?;
+        let python_dict = PyDict::new(py);
+        let Some(first_row) = rows.first() else {
+            return Ok(python_dict.unbind().into_any());
+        };
+        let columns = first_row.columns();
+        let column_names = build_column_names(py, columns, column_case, column_mapping.as_ref())?;
+
+        let column_masks: Vec<_> = columns
+            .iter()
+            .map(|column| self.column_masks.find(column.name()))
+            .collect();
+
+        let mut column_values: Vec<Vec<Py<PyAny>>> = columns
+            .iter()
+            .map(|_| Vec::with_capacity(rows.len()))
+            .collect();
+        for row in rows {
+            for (column_idx, column) in columns.iter().enumerate() {
+                if column_masks[column_idx].is_some_and(ColumnMask::is_drop) {
+                    continue;
+                }
+                let value = postgres_to_py(py, row, column, column_idx, &custom_decoders)?;
+                let value = self.column_codecs.decrypt_value(py, column.name(), value)?;
+                column_values[column_idx].push(match column_masks[column_idx] {
+                    Some(mask) => mask.mask_value(py, value)?.unwrap_or_else(|| py.None()),
+                    None => value,
+                });
+            }
+        }
+
+        for ((column_name, values), column_mask) in
+            column_names.iter().zip(column_values).zip(column_masks)
+        {
+            if column_mask.is_some_and(ColumnMask::is_drop) {
+                continue;
+            }
+            python_dict.set_item(column_name, values.to_object(py))?;
+        }
+        Ok(python_dict.unbind().into_any())
+    }
+
     /// Convert result from database to any class passed from Python.
     ///
     /// # Errors
@@ -83,8 +474,10 @@ impl PSQLDriverPyQueryResult {
         as_class: Py<PyAny>,
     ) -> RustPSQLDriverPyResult<Py<PyAny>> {
         let mut res: Vec<Py<PyAny>> = vec![];
-        for row in &self.inner {
-            let pydict: pyo3::Bound<'_, PyDict> = row_to_dict(py, row, &None)?;
+        for row in self.open_rows()? {
+            let pydict: pyo3::Bound<'_, PyDict> = row_to_dict(py, row, &None, None)?;
+            self.column_codecs.decrypt_row(row, &pydict, None)?;
+            self.column_masks.mask_row(row, &pydict, None)?;
             let convert_class_inst = as_class.call(py, (), Some(&pydict))?;
             res.push(convert_class_inst);
         }
@@ -107,8 +500,10 @@ impl PSQLDriverPyQueryResult {
         custom_decoders: Option<Py<PyDict>>,
     ) -> RustPSQLDriverPyResult<Py<PyAny>> {
         let mut res: Vec<Py<PyAny>> = vec![];
-        for row in &self.inner {
-            let pydict: pyo3::Bound<'_, PyDict> = row_to_dict(py, row, &custom_decoders)?;
+        for row in self.open_rows()? {
+            let pydict: pyo3::Bound<'_, PyDict> = row_to_dict(py, row, &custom_decoders, None)?;
+            self.column_codecs.decrypt_row(row, &pydict, None)?;
+            self.column_masks.mask_row(row, &pydict, None)?;
             let row_factory_class = row_factory.call(py, (pydict,), None)?;
             res.push(row_factory_class);
         }
@@ -120,6 +515,9 @@ impl PSQLDriverPyQueryResult {
 #[allow(clippy::module_name_repetitions)]
 pub struct PSQLDriverSinglePyQueryResult {
     inner: Row,
+    querystring: Option<String>,
+    column_codecs: ColumnCodecs,
+    column_masks: ColumnMasks,
 }
 
 impl PSQLDriverSinglePyQueryResult {
@@ -127,9 +525,33 @@ impl PSQLDriverSinglePyQueryResult {
     pub fn new(database_row: Row) -> Self {
         PSQLDriverSinglePyQueryResult {
             inner: database_row,
+            querystring: None,
+            column_codecs: ColumnCodecs::default(),
+            column_masks: ColumnMasks::default(),
         }
     }
 
+    /// Set querystring which produced the result.
+    #[must_use]
+    pub fn with_querystring(mut self, querystring: String) -> Self {
+        self.querystring = Some(querystring);
+        self
+    }
+
+    /// Set codecs which decrypt values of the encrypted columns.
+    #[must_use]
+    pub fn with_column_codecs(mut self, column_codecs: ColumnCodecs) -> Self {
+        self.column_codecs = column_codecs;
+        self
+    }
+
+    /// Set masks of the sensitive columns.
+    #[must_use]
+    pub fn with_column_masks(mut self, column_masks: ColumnMasks) -> Self {
+        self.column_masks = column_masks;
+        self
+    }
+
     pub fn get_inner(self) -> Row {
         self.inner
     }
@@ -137,9 +559,16 @@ impl PSQLDriverSinglePyQueryResult {
 
 #[pymethods]
 impl PSQLDriverSinglePyQueryResult {
+    /// Return fingerprint of the querystring which produced the result.
+    #[getter]
+    fn fingerprint(&self) -> Option<String> {
+        self.querystring.as_deref().map(query_fingerprint)
+    }
+
     /// Return result as a Python dict.
     ///
     /// This result is used to return single row.
+    /// Column names are converted with `column_mapping` and `column_case`.
     ///
     /// # Errors
     ///
@@ -147,13 +576,31 @@ impl PSQLDriverSinglePyQueryResult {
     /// postgres type to python, can not set new key-value pair
     /// in python dict or there are no result.
     #[allow(clippy::needless_pass_by_value)]
-    #[pyo3(signature = (custom_decoders=None))]
+    #[pyo3(signature = (custom_decoders=None, column_case=None, column_mapping=None))]
     pub fn result(
         &self,
         py: Python<'_>,
         custom_decoders: Option<Py<PyDict>>,
+        column_case: Option<ColumnCase>,
+        column_mapping: Option<Py<PyDict>>,
     ) -> RustPSQLDriverPyResult<Py<PyAny>> {
-        Ok(row_to_dict(py, &self.inner, &custom_decoders)?.to_object(py))
+        let column_names = if column_case.is_some() || column_mapping.is_some() {
+            Some(build_column_names(
+                py,
+                self.inner.columns(),
+                column_case,
+                column_mapping.as_ref(),
+            )?)
+        } else {
+            None
+        };
+
+        let python_dict = row_to_dict(py, &self.inner, &custom_decoders, column_names.as_deref())?;
+        self.column_codecs
+            .decrypt_row(&self.inner, &python_dict, column_names.as_deref())?;
+        self.column_masks
+            .mask_row(&self.inner, &python_dict, column_names.as_deref())?;
+        Ok(python_dict.to_object(py))
     }
 
     /// Convert result from database to any class passed from Python.
@@ -169,7 +616,9 @@ impl PSQLDriverSinglePyQueryResult {
         py: Python<'a>,
         as_class: Py<PyAny>,
     ) -> RustPSQLDriverPyResult<Py<PyAny>> {
-        let pydict: pyo3::Bound<'_, PyDict> = row_to_dict(py, &self.inner, &None)?;
+        let pydict: pyo3::Bound<'_, PyDict> = row_to_dict(py, &self.inner, &None, None)?;
+        self.column_codecs.decrypt_row(&self.inner, &pydict, None)?;
+        self.column_masks.mask_row(&self.inner, &pydict, None)?;
         Ok(as_class.call(py, (), Some(&pydict))?)
     }
 
@@ -187,7 +636,9 @@ impl PSQLDriverSinglePyQueryResult {
         row_factory: Py<PyAny>,
         custom_decoders: Option<Py<PyDict>>,
     ) -> RustPSQLDriverPyResult<Py<PyAny>> {
-        let pydict = row_to_dict(py, &self.inner, &custom_decoders)?.to_object(py);
+        let pydict = row_to_dict(py, &self.inner, &custom_decoders, None)?;
+        self.column_codecs.decrypt_row(&self.inner, &pydict, None)?;
+        self.column_masks.mask_row(&self.inner, &pydict, None)?;
         Ok(row_factory.call(py, (pydict,), None)?)
     }
 }
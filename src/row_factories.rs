@@ -1,10 +1,21 @@
+use std::sync::Arc;
+
 use pyo3::{
+    exceptions::PyKeyError,
     pyclass, pyfunction, pymethods,
-    types::{PyDict, PyDictMethods, PyModule, PyModuleMethods, PyTuple},
+    types::{PyAnyMethods, PyDict, PyDictMethods, PyList, PyModule, PyModuleMethods, PyTuple},
     wrap_pyfunction, Bound, Py, PyAny, PyResult, Python, ToPyObject,
 };
 
-use crate::exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult};
+use crate::{
+    driver::{
+        connection::{copy_target, Connection},
+        dry_run::run_dry,
+        inner_connection::PsqlpyConnection,
+        transaction::Transaction,
+    },
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+};
 
 #[pyfunction]
 #[allow(clippy::needless_pass_by_value)]
@@ -39,10 +50,244 @@ impl class_row {
     }
 }
 
+/// Row converter to `TrackedRow` of the table.
+#[pyclass]
+#[allow(non_camel_case_types)]
+struct tracked_row {
+    table: String,
+    primary_key: Vec<String>,
+}
+
+#[pymethods]
+impl tracked_row {
+    /// Create converter for the rows of the table.
+    ///
+    /// # Errors
+    /// May return Err Result if primary key is empty.
+    #[new]
+    #[pyo3(signature = (table_name, primary_key=vec!["id".to_string()], schema_name=None))]
+    fn new(
+        table_name: &str,
+        primary_key: Vec<String>,
+        schema_name: Option<String>,
+    ) -> RustPSQLDriverPyResult<Self> {
+        if primary_key.is_empty() {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(
+                "At least one primary key column must be passed".into(),
+            ));
+        }
+        Ok(Self {
+            table: copy_target(table_name, None, schema_name),
+            primary_key,
+        })
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    fn __call__(&self, py: Python<'_>, dict_: Py<PyAny>) -> RustPSQLDriverPyResult<TrackedRow> {
+        let dict_ = dict_.downcast_bound::<PyDict>(py).map_err(|_| {
+            RustPSQLDriverError::RustToPyValueConversionError(
+                "tracked_row accepts only dict as a parameter".into(),
+            )
+        })?;
+        if let Some(key_column) = self
+            .primary_key
+            .iter()
+            .find(|key_column| !dict_.contains(key_column.as_str()).unwrap_or(false))
+        {
+            return Err(RustPSQLDriverError::RustToPyValueConversionError(format!(
+                "Row doesn't have primary key column `{key_column}`"
+            )));
+        }
+        Ok(TrackedRow {
+            table: self.table.clone(),
+            primary_key: self.primary_key.clone(),
+            values: dict_.copy()?.unbind(),
+            dirty: vec![],
+        })
+    }
+}
+
+/// Row which remembers its table, primary key and changed columns.
+///
+/// Only changed columns are updated by `save`,
+/// primary key columns cannot be changed.
+#[pyclass]
+pub struct TrackedRow {
+    table: String,
+    primary_key: Vec<String>,
+    values: Py<PyDict>,
+    dirty: Vec<String>,
+}
+
+/// Return connection of `Connection` or `Transaction`
+/// with its dry-run flag and whether it's a transaction.
+fn target_db_client(
+    connection: &Bound<'_, PyAny>,
+) -> RustPSQLDriverPyResult<(Arc<PsqlpyConnection>, bool, bool)> {
+    if let Ok(connection) = connection.downcast::<Connection>() {
+        let connection = connection.borrow();
+        return connection
+            .db_client()
+            .map(|db_client| (db_client, connection.is_dry_run(), false))
+            .ok_or(RustPSQLDriverError::ConnectionClosedError);
+    }
+    if let Ok(transaction) = connection.downcast::<Transaction>() {
+        let transaction = transaction.borrow();
+        return transaction
+            .db_client
+            .clone()
+            .map(|db_client| (db_client, transaction.is_dry_run(), true))
+            .ok_or(RustPSQLDriverError::TransactionClosedError);
+    }
+    Err(RustPSQLDriverError::PyToRustValueConversionError(
+        "connection must be Connection or Transaction".into(),
+    ))
+}
+
+#[pymethods]
+impl TrackedRow {
+    fn __getitem__(&self, py: Python<'_>, column: &str) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        match self.values.bind(py).get_item(column)? {
+            Some(value) => Ok(value.unbind()),
+            None => Err(RustPSQLDriverError::RustPyError(PyKeyError::new_err(
+                column.to_string(),
+            ))),
+        }
+    }
+
+    /// Change value of the column and mark it as dirty.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) Row doesn't have the column
+    /// 2) Column is a part of the primary key
+    fn __setitem__(
+        &mut self,
+        py: Python<'_>,
+        column: String,
+        value: Py<PyAny>,
+    ) -> RustPSQLDriverPyResult<()> {
+        let values = self.values.bind(py);
+        if !values.contains(&column)? {
+            return Err(RustPSQLDriverError::RustPyError(PyKeyError::new_err(
+                column,
+            )));
+        }
+        if self.primary_key.contains(&column) {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "Primary key column `{column}` cannot be changed"
+            )));
+        }
+        values.set_item(&column, value)?;
+        if !self.dirty.contains(&column) {
+            self.dirty.push(column);
+        }
+        Ok(())
+    }
+
+    fn __contains__(&self, py: Python<'_>, column: &str) -> RustPSQLDriverPyResult<bool> {
+        Ok(self.values.bind(py).contains(column)?)
+    }
+
+    fn __len__(&self, py: Python<'_>) -> usize {
+        self.values.bind(py).len()
+    }
+
+    /// Columns changed since the row was fetched or saved, in order of changes.
+    #[getter]
+    fn dirty_fields(&self) -> Vec<String> {
+        self.dirty.clone()
+    }
+
+    /// Return copy of the row values.
+    ///
+    /// # Errors
+    /// May return Err Result if cannot copy dict.
+    fn as_dict(&self, py: Python<'_>) -> RustPSQLDriverPyResult<Py<PyDict>> {
+        Ok(self.values.bind(py).copy()?.unbind())
+    }
+
+    /// Update changed columns of the row in the table.
+    ///
+    /// Row is found by the primary key, values are cast to the types
+    /// of the columns. Nothing is executed if there are no changes.
+    /// Returns number of updated rows, 0 means the row doesn't exist anymore.
+    /// In dry-run mode the update is rolled back and columns stay dirty.
+    ///
+    /// # Errors
+    /// May return Err Result if:
+    /// 1) `connection` isn't `Connection` or `Transaction` or it's closed
+    /// 2) Cannot convert python parameters
+    /// 3) Cannot execute statement
+    pub async fn save(self_: Py<Self>, connection: Py<PyAny>) -> RustPSQLDriverPyResult<usize> {
+        let update = Python::with_gil(|gil| {
+            let (db_client, dry_run, in_transaction) = target_db_client(connection.bind(gil))?;
+            let self_ = self_.borrow(gil);
+            if self_.dirty.is_empty() {
+                return Ok::<_, RustPSQLDriverError>(None);
+            }
+            let columns: Vec<String> = self_
+                .primary_key
+                .iter()
+                .chain(&self_.dirty)
+                .cloned()
+                .collect();
+            let values = self_.values.bind(gil);
+            let mut row_values = Vec::with_capacity(columns.len());
+            for column in &columns {
+                row_values.push(values.get_item(column)?);
+            }
+            let row = PyList::new(gil, row_values)?.into_any().unbind();
+            Ok(Some((
+                db_client,
+                dry_run,
+                in_transaction,
+                self_.table.clone(),
+                self_.primary_key.clone(),
+                columns,
+                row,
+            )))
+        })?;
+        let Some((db_client, dry_run, in_transaction, table, primary_key, columns, row)) = update
+        else {
+            return Ok(0);
+        };
+
+        let updated_rows = run_dry(
+            &db_client,
+            dry_run,
+            in_transaction,
+            db_client.update_many(&table, &primary_key, &columns, vec![row], 1),
+        )
+        .await?;
+        if dry_run {
+            return Ok(updated_rows);
+        }
+        Python::with_gil(|gil| {
+            self_
+                .borrow_mut(gil)
+                .dirty
+                .retain(|column| !columns.contains(column));
+        });
+        Ok(updated_rows)
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> String {
+        format!(
+            "TrackedRow(table={}, values={}, dirty_fields={:?})",
+            self.table,
+            self.values.bind(py),
+            self.dirty,
+        )
+    }
+}
+
 #[allow(clippy::module_name_repetitions)]
 #[allow(clippy::missing_errors_doc)]
 pub fn row_factories_module(_py: Python<'_>, pymod: &Bound<'_, PyModule>) -> PyResult<()> {
     pymod.add_function(wrap_pyfunction!(tuple_row, pymod)?)?;
     pymod.add_class::<class_row>()?;
+    pymod.add_class::<tracked_row>()?;
+    pymod.add_class::<TrackedRow>()?;
     Ok(())
 }
@@ -1,8 +1,19 @@
+use std::time::Instant;
+
 use futures_util::Future;
-use pyo3::{IntoPyObject, Py, PyAny, Python};
+use pyo3::{
+    exceptions::PyRuntimeError,
+    pyclass, pyfunction, pymethods,
+    types::{PyAnyMethods, PyDict, PyDictMethods},
+    IntoPyObject, Py, PyAny, Python,
+};
+use tokio::sync::oneshot;
 
-use crate::exceptions::rust_errors::RustPSQLDriverPyResult;
+use crate::exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult};
 
+/// Return runtime which executes all database futures.
+///
+/// Runtime is shared by the whole process, it doesn't hold Python objects.
 #[allow(clippy::missing_panics_doc)]
 #[allow(clippy::module_name_repetitions)]
 pub fn tokio_runtime() -> &'static tokio::runtime::Runtime {
@@ -28,3 +39,146 @@ where
             .map(Into::into)?;
     Ok(res)
 }
+
+/// Run future on the driver runtime and wait for the result.
+///
+/// It's used by the blocking methods, so they work
+/// without event loop, e.g. in REPL or scripts.
+/// GIL is released while waiting.
+///
+/// # Errors
+/// May return Err Result if:
+/// 1) Event loop is running in the current thread
+/// 2) It's called from the driver runtime
+/// 3) Future returns error
+pub fn block_on_runtime<F, T>(py: Python<'_>, future: F) -> RustPSQLDriverPyResult<T>
+where
+    F: Future<Output = RustPSQLDriverPyResult<T>> + Send + 'static,
+    T: Send + 'static,
+{
+    let has_running_loop = py
+        .import("asyncio")?
+        .call_method0("get_running_loop")
+        .is_ok();
+    if has_running_loop {
+        return Err(RustPSQLDriverError::BlockingCallError(
+            "event loop is running in the current thread, await the async method instead".into(),
+        ));
+    }
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return Err(RustPSQLDriverError::BlockingCallError(
+            "cannot block the driver runtime thread".into(),
+        ));
+    }
+
+    py.allow_threads(|| tokio_runtime().block_on(future))
+}
+
+/// Number of wakeups measured by `debug_loop_integration`.
+const LOOP_LATENCY_SAMPLES: u32 = 5;
+
+/// Callback which reports the moment when event loop ran it.
+#[pyclass]
+struct LoopWakeup {
+    sender: Option<oneshot::Sender<Instant>>,
+}
+
+#[pymethods]
+impl LoopWakeup {
+    fn __call__(&mut self) {
+        if let Some(sender) = self.sender.take() {
+            let _ = sender.send(Instant::now());
+        }
+    }
+}
+
+/// Return flavor of the event loop by its class.
+fn loop_flavor(loop_module: &str, loop_name: &str) -> &'static str {
+    if loop_module.starts_with("uvloop") {
+        "uvloop"
+    } else if loop_module.starts_with("winloop") {
+        "winloop"
+    } else if loop_name.contains("Proactor") {
+        "proactor"
+    } else if loop_name.contains("Selector") {
+        "selector"
+    } else {
+        "other"
+    }
+}
+
+/// Return report about integration of the driver with the running event loop.
+///
+/// Results of the driver are sent from the runtime threads to the event loop
+/// with `call_soon_threadsafe`, the report contains the latency of such wakeups.
+/// It's useful to find out blocked event loop or futures attached to the different loop.
+///
+/// # Errors
+/// May return Err Result if:
+/// 1) Event loop isn't running in the current thread
+/// 2) Event loop is stopped before the wakeups are measured
+#[pyfunction]
+pub fn debug_loop_integration(py: Python<'_>) -> RustPSQLDriverPyResult<Py<PyAny>> {
+    let asyncio = py.import("asyncio")?;
+    let event_loop = asyncio.call_method0("get_running_loop")?;
+    let loop_type = event_loop.get_type();
+    let loop_module: String = loop_type.getattr("__module__")?.extract()?;
+    let loop_name: String = loop_type.getattr("__qualname__")?.extract()?;
+    let policy_name: String = asyncio
+        .call_method0("get_event_loop_policy")?
+        .get_type()
+        .getattr("__qualname__")?
+        .extract()?;
+    let loop_debug: bool = event_loop.call_method0("get_debug")?.extract()?;
+    let threading = py.import("threading")?;
+    let in_main_thread = threading
+        .call_method0("current_thread")?
+        .is(&threading.call_method0("main_thread")?);
+    // Loop which the driver attaches its futures to.
+    let driver_loop_matches = pyo3_async_runtimes::tokio::get_current_loop(py)
+        .is_ok_and(|driver_loop| driver_loop.is(&event_loop));
+    let event_loop = event_loop.unbind();
+
+    rustdriver_future(py, async move {
+        let mut latencies = vec![];
+        for _ in 0..LOOP_LATENCY_SAMPLES {
+            let (sender, receiver) = oneshot::channel();
+            let scheduled_at = Instant::now();
+            Python::with_gil(|gil| -> RustPSQLDriverPyResult<()> {
+                let wakeup = Py::new(
+                    gil,
+                    LoopWakeup {
+                        sender: Some(sender),
+                    },
+                )?;
+                event_loop.call_method1(gil, "call_soon_threadsafe", (wakeup,))?;
+                Ok(())
+            })?;
+            let woken_at = receiver.await.map_err(|_| {
+                RustPSQLDriverError::RustPyError(PyRuntimeError::new_err(
+                    "Event loop is stopped before the wakeup",
+                ))
+            })?;
+            latencies.push(woken_at.duration_since(scheduled_at).as_secs_f64() * 1000.0);
+        }
+
+        let min_latency = latencies.iter().copied().fold(f64::INFINITY, f64::min);
+        let max_latency = latencies.iter().copied().fold(0.0, f64::max);
+        let avg_latency = latencies.iter().sum::<f64>() / f64::from(LOOP_LATENCY_SAMPLES);
+
+        Python::with_gil(|gil| -> RustPSQLDriverPyResult<Py<PyDict>> {
+            let report = PyDict::new(gil);
+            report.set_item("loop_class", format!("{loop_module}.{loop_name}"))?;
+            report.set_item("loop_flavor", loop_flavor(&loop_module, &loop_name))?;
+            report.set_item("policy_class", policy_name)?;
+            report.set_item("loop_debug", loop_debug)?;
+            report.set_item("in_main_thread", in_main_thread)?;
+            report.set_item("driver_loop_matches", driver_loop_matches)?;
+            report.set_item("runtime_workers", tokio_runtime().metrics().num_workers())?;
+            report.set_item("wakeup_latency_min_ms", min_latency)?;
+            report.set_item("wakeup_latency_avg_ms", avg_latency)?;
+            report.set_item("wakeup_latency_max_ms", max_latency)?;
+            Ok(report.unbind())
+        })
+    })
+}
@@ -0,0 +1,193 @@
+use pyo3::{
+    pyclass, pyfunction, pymethods,
+    types::{PyAnyMethods, PyDict, PyDictMethods, PyModule, PyModuleMethods, PyTuple},
+    wrap_pyfunction, Bound, Py, PyAny, PyResult, Python,
+};
+
+use crate::{
+    exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
+    query_result::PSQLDriverPyQueryResult,
+};
+
+/// Difference between two results matched by the key columns.
+#[pyclass(frozen)]
+pub struct ResultDiff {
+    added: Vec<Py<PyDict>>,
+    removed: Vec<Py<PyDict>>,
+    changed: Vec<(Py<PyDict>, Py<PyDict>)>,
+}
+
+#[pymethods]
+impl ResultDiff {
+    /// Rows of the second result which aren't in the first one.
+    #[getter]
+    fn added(&self, py: Python<'_>) -> Vec<Py<PyDict>> {
+        self.added.iter().map(|row| row.clone_ref(py)).collect()
+    }
+
+    /// Rows of the first result which aren't in the second one.
+    #[getter]
+    fn removed(&self, py: Python<'_>) -> Vec<Py<PyDict>> {
+        self.removed.iter().map(|row| row.clone_ref(py)).collect()
+    }
+
+    /// Pairs of the rows with the same keys and different values.
+    #[getter]
+    fn changed(&self, py: Python<'_>) -> Vec<(Py<PyDict>, Py<PyDict>)> {
+        self.changed
+            .iter()
+            .map(|(before, after)| (before.clone_ref(py), after.clone_ref(py)))
+            .collect()
+    }
+
+    fn __bool__(&self) -> bool {
+        !(self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty())
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ResultDiff(added={}, removed={}, changed={})",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len(),
+        )
+    }
+}
+
+/// Return rows of `QueryResult` or sequence of dicts.
+fn result_rows<'py>(
+    py: Python<'py>,
+    result: &Bound<'py, PyAny>,
+) -> RustPSQLDriverPyResult<Vec<Bound<'py, PyDict>>> {
+    let rows = match result.downcast::<PSQLDriverPyQueryResult>() {
+        Ok(query_result) => query_result
+            .borrow()
+            .result(py, None, None, None)?
+            .into_bound(py),
+        Err(_) => result.clone(),
+    };
+    rows.extract::<Vec<Bound<'py, PyAny>>>()
+        .ok()
+        .and_then(|rows| {
+            rows.into_iter()
+                .map(|row| row.downcast_into::<PyDict>().ok())
+                .collect::<Option<Vec<_>>>()
+        })
+        .ok_or_else(|| {
+            RustPSQLDriverError::PyToRustValueConversionError(
+                "Result must be QueryResult without row_factory or a sequence of dicts".into(),
+            )
+        })
+}
+
+/// Return tuple of the key values of the row.
+fn row_key<'py>(
+    py: Python<'py>,
+    row: &Bound<'py, PyDict>,
+    keys: &[String],
+) -> RustPSQLDriverPyResult<Bound<'py, PyTuple>> {
+    let mut key_values = Vec::with_capacity(keys.len());
+    for key in keys {
+        let Some(key_value) = row.get_item(key)? else {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "Row doesn't have key column {key}"
+            )));
+        };
+        key_values.push(key_value);
+    }
+    Ok(PyTuple::new(py, key_values)?)
+}
+
+/// Index positions of the rows by the key values.
+fn index_rows<'py>(
+    py: Python<'py>,
+    rows: &[Bound<'py, PyDict>],
+    keys: &[String],
+) -> RustPSQLDriverPyResult<Bound<'py, PyDict>> {
+    let index = PyDict::new(py);
+    for (position, row) in rows.iter().enumerate() {
+        let key = row_key(py, row, keys)?;
+        if index.contains(&key)? {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "Key {} isn't unique",
+                key.repr()?
+            )));
+        }
+        index.set_item(key, position)?;
+    }
+    Ok(index)
+}
+
+/// Compare two results by the key columns.
+///
+/// Rows of both results are indexed by the key values,
+/// then rows of the first result are looked up in the index
+/// of the second one, so every result is traversed once.
+/// Results can be `QueryResult` or sequences of dicts,
+/// e.g. results of the same query on two databases.
+///
+/// # Errors
+/// May return Err Result if:
+/// 1) Keys are empty
+/// 2) Result isn't a sequence of dicts
+/// 3) Row doesn't have key column
+/// 4) Key values aren't unique or hashable
+#[pyfunction]
+#[pyo3(signature = (result_a, result_b, keys))]
+#[allow(clippy::needless_pass_by_value)]
+pub fn diff_results(
+    py: Python<'_>,
+    result_a: Py<PyAny>,
+    result_b: Py<PyAny>,
+    keys: Vec<String>,
+) -> RustPSQLDriverPyResult<ResultDiff> {
+    if keys.is_empty() {
+        return Err(RustPSQLDriverError::PyToRustValueConversionError(
+            "At least one key column must be passed".into(),
+        ));
+    }
+
+    let rows_a = result_rows(py, result_a.bind(py))?;
+    let rows_b = result_rows(py, result_b.bind(py))?;
+    let index_a = index_rows(py, &rows_a, &keys)?;
+    let index_b = index_rows(py, &rows_b, &keys)?;
+
+    let mut matched_b = vec![false; rows_b.len()];
+    let mut removed = vec![];
+    let mut changed = vec![];
+    for (key, position_a) in index_a.iter() {
+        let row_a = &rows_a[position_a.extract::<usize>()?];
+        let Some(position_b) = index_b.get_item(&key)? else {
+            removed.push(row_a.clone().unbind());
+            continue;
+        };
+        let position_b = position_b.extract::<usize>()?;
+        matched_b[position_b] = true;
+
+        let row_b = &rows_b[position_b];
+        if !row_a.as_any().eq(row_b)? {
+            changed.push((row_a.clone().unbind(), row_b.clone().unbind()));
+        }
+    }
+
+    let added = rows_b
+        .into_iter()
+        .zip(matched_b)
+        .filter(|(_, is_matched)| !is_matched)
+        .map(|(row_b, _)| row_b.unbind())
+        .collect();
+
+    Ok(ResultDiff {
+        added,
+        removed,
+        changed,
+    })
+}
+
+#[allow(clippy::module_name_repetitions)]
+#[allow(clippy::missing_errors_doc)]
+pub fn tools_module(_py: Python<'_>, pymod: &Bound<'_, PyModule>) -> PyResult<()> {
+    pymod.add_function(wrap_pyfunction!(diff_results, pymod)?)?;
+    pymod.add_class::<ResultDiff>()?;
+    Ok(())
+}
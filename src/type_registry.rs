@@ -0,0 +1,159 @@
+use pyo3::{
+    pyfunction,
+    sync::GILOnceCell,
+    types::{PyAnyMethods, PyDict, PyDictMethods, PyString, PyType},
+    Bound, Py, PyAny, PyResult, Python,
+};
+use tokio_postgres::types::Type;
+
+use crate::exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult};
+
+/// Python classes registered for `PostgreSQL` types by the type name.
+static REGISTERED_TYPES: GILOnceCell<Py<PyDict>> = GILOnceCell::new();
+
+fn registered_types(py: Python<'_>) -> &Bound<'_, PyDict> {
+    REGISTERED_TYPES
+        .get_or_init(py, || PyDict::new_bound(py).unbind())
+        .bind(py)
+}
+
+/// Return Python class registered for the type.
+///
+/// Schema-qualified name is looked up first, then the name without schema.
+#[must_use]
+pub fn registered_class<'py>(py: Python<'py>, type_: &Type) -> Option<Bound<'py, PyAny>> {
+    let types = registered_types(py);
+    if types.is_empty() {
+        return None;
+    }
+    let qualified_name = format!("{}.{}", type_.schema(), type_.name());
+    [qualified_name.as_str(), type_.name()]
+        .into_iter()
+        .find_map(|name| types.get_item(name).ok().flatten())
+}
+
+/// Check that object is an instance of one of the registered classes.
+#[must_use]
+pub fn is_registered_instance(parameter: &Bound<'_, PyAny>) -> bool {
+    let types = registered_types(parameter.py());
+    !types.is_empty()
+        && types
+            .values()
+            .iter()
+            .any(|cls| parameter.get_type().is(&cls))
+}
+
+/// Register Python class for the composite type.
+///
+/// Composite values of the type are decoded into `cls(**fields)`
+/// instead of `dict`, instances of `cls` can be passed as parameters,
+/// their attributes are used as fields.
+/// `cls` must be a class, pass `None` to remove the registration.
+///
+/// # Errors
+/// May return Err Result if `cls` isn't a class.
+#[pyfunction]
+#[pyo3(signature = (type_name, cls))]
+pub fn register_composite(
+    py: Python<'_>,
+    type_name: &str,
+    cls: Option<Py<PyAny>>,
+) -> RustPSQLDriverPyResult<()> {
+    register_class(py, type_name, cls)
+}
+
+/// Save class for the type name or remove it if `cls` is None.
+///
+/// # Errors
+/// May return Err Result if `cls` isn't a class.
+pub fn register_class(
+    py: Python<'_>,
+    type_name: &str,
+    cls: Option<Py<PyAny>>,
+) -> RustPSQLDriverPyResult<()> {
+    let types = registered_types(py);
+    let Some(cls) = cls else {
+        if types.contains(type_name)? {
+            types.del_item(type_name)?;
+        }
+        return Ok(());
+    };
+    if !cls.bind(py).is_instance_of::<PyType>() {
+        return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+            "Cannot register {type_name}, {} isn't a class",
+            cls.bind(py)
+        )));
+    }
+    types.set_item(type_name, cls)?;
+    Ok(())
+}
+
+fn enum_class(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+    py.import("enum")?.getattr("Enum")
+}
+
+/// Register Python `Enum` for the `PostgreSQL` enum type.
+///
+/// Labels of the type are decoded into members of `cls`,
+/// members are encoded back into labels.
+/// `cls` must be a subclass of `enum.Enum`, pass `None` to remove the registration.
+///
+/// # Errors
+/// May return Err Result if `cls` isn't a subclass of `enum.Enum`.
+pub fn register_enum(
+    py: Python<'_>,
+    type_name: &str,
+    cls: Option<Py<PyAny>>,
+) -> RustPSQLDriverPyResult<()> {
+    if let Some(cls) = &cls {
+        let cls = cls.bind(py);
+        let is_enum = match cls.downcast::<PyType>() {
+            Ok(cls_type) => cls_type.is_subclass(&enum_class(py)?)?,
+            Err(_) => false,
+        };
+        if !is_enum {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "Cannot register {type_name}, {cls} isn't a subclass of enum.Enum"
+            )));
+        }
+    }
+    register_class(py, type_name, cls)
+}
+
+/// Return label of the `PostgreSQL` enum for the member of Python `Enum`.
+///
+/// Member's value is the label if it's a string, otherwise member's name is used.
+/// Returns None if object isn't a member of `Enum`.
+///
+/// # Errors
+/// May return Err Result if cannot get value or name of the member.
+pub fn enum_member_label(parameter: &Bound<'_, PyAny>) -> RustPSQLDriverPyResult<Option<String>> {
+    if !parameter.is_instance(&enum_class(parameter.py())?)? {
+        return Ok(None);
+    }
+    let value = parameter.getattr("value")?;
+    if value.is_instance_of::<PyString>() {
+        return Ok(Some(value.extract::<String>()?));
+    }
+    Ok(Some(parameter.getattr("name")?.extract::<String>()?))
+}
+
+/// Convert label of the `PostgreSQL` enum into the member of registered `Enum`.
+///
+/// Member is looked up by value first, then by name.
+///
+/// # Errors
+/// May return Err Result if `Enum` has no member for the label.
+pub fn enum_label_to_member<'py>(
+    cls: &Bound<'py, PyAny>,
+    label: &str,
+) -> RustPSQLDriverPyResult<Bound<'py, PyAny>> {
+    if let Ok(member) = cls.call1((label,)) {
+        return Ok(member);
+    }
+    cls.get_item(label).map_err(|_| {
+        RustPSQLDriverError::RustToPyValueConversionError(format!(
+            "{cls} has no member for the label {label}"
+        ))
+    })
+}
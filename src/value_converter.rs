@@ -1,16 +1,17 @@
-use chrono::{self, DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use chrono::{
+    self, DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike,
+};
 use chrono_tz::Tz;
 use geo_types::{coord, Coord, Line as LineSegment, LineString, Point, Rect};
 use itertools::Itertools;
 use macaddr::{MacAddr6, MacAddr8};
 use pg_interval::Interval;
 use postgres_types::{Field, FromSql, Kind, ToSql};
-use rust_decimal::Decimal;
 use serde_json::{json, Map, Value};
-use std::{fmt::Debug, net::IpAddr};
+use std::{collections::HashMap, fmt::Debug, net::IpAddr};
 use uuid::Uuid;
 
-use bytes::{BufMut, BytesMut};
+use bytes::{Buf, BufMut, BytesMut};
 use postgres_protocol::types;
 use pyo3::{
     sync::GILOnceCell,
@@ -19,20 +20,24 @@ use pyo3::{
         PyInt, PyList, PyListMethods, PySequence, PySet, PyString, PyTime, PyTuple, PyType,
         PyTypeMethods,
     },
-    Bound, FromPyObject, IntoPy, Py, PyAny, PyObject, PyResult, Python, ToPyObject,
+    Bound, FromPyObject, IntoPy, IntoPyObject, Py, PyAny, PyObject, PyResult, Python, ToPyObject,
 };
 use tokio_postgres::{
-    types::{to_sql_checked, Type},
+    types::{to_sql_checked, Format, Type},
     Column, Row,
 };
 
 use crate::{
     additional_types::{
-        Circle, Line, RustLineSegment, RustLineString, RustMacAddr6, RustMacAddr8, RustPoint,
-        RustRect,
+        Circle, Line, PgNumeric, RustLineSegment, RustLineString, RustMacAddr6, RustMacAddr8,
+        RustPoint, RustRect,
     },
     exceptions::rust_errors::{RustPSQLDriverError, RustPSQLDriverPyResult},
     extra_types,
+    format_helpers::cast_parameters,
+    type_registry::{
+        enum_label_to_member, enum_member_label, is_registered_instance, registered_class,
+    },
 };
 use pgvector::Vector as PgVector;
 use postgres_array::{array::Array, Dimension};
@@ -140,11 +145,60 @@ impl<'a> FromSql<'a> for InternalSerdeValue {
     }
 }
 
+/// Struct for TIME WITH TIME ZONE.
+///
+/// There is no `FromSql` for it, value is time since midnight
+/// and offset of the zone in seconds west of UTC.
+struct InnerTimeTz(NaiveTime, FixedOffset);
+
+impl InnerTimeTz {
+    /// Convert into aware `datetime.time`.
+    ///
+    /// # Errors
+    /// May return Err Result if Python cannot create time or timezone.
+    #[allow(clippy::cast_possible_truncation)]
+    fn to_py(&self, py: Python<'_>) -> RustPSQLDriverPyResult<Py<PyAny>> {
+        let tzinfo = self.1.into_pyobject(py)?;
+        // Time is built from microseconds, so there is no leap second.
+        let time = PyTime::new(
+            py,
+            self.0.hour() as u8,
+            self.0.minute() as u8,
+            self.0.second() as u8,
+            self.0.nanosecond() / 1000,
+            Some(&tzinfo),
+        )?;
+        Ok(time.into_any().unbind())
+    }
+}
+
+impl<'a> FromSql<'a> for InnerTimeTz {
+    fn from_sql(
+        _ty: &Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        let mut raw = raw;
+        if raw.len() != 12 {
+            return Err("invalid message length: timetz size mismatch".into());
+        }
+        let microseconds = raw.get_i64();
+        let zone_west = raw.get_i32();
+
+        let time = NaiveTime::default() + chrono::Duration::microseconds(microseconds);
+        let offset = FixedOffset::west_opt(zone_west).ok_or("invalid timetz zone offset")?;
+        Ok(InnerTimeTz(time, offset))
+    }
+
+    fn accepts(_ty: &Type) -> bool {
+        true
+    }
+}
+
 /// Struct for Decimal.
 ///
 /// It's necessary because we use custom forks and there is
 /// no implementation of `ToPyObject` for Decimal.
-struct InnerDecimal(Decimal);
+struct InnerDecimal(PgNumeric);
 
 impl ToPyObject for InnerDecimal {
     fn to_object(&self, py: Python<'_>) -> PyObject {
@@ -161,7 +215,7 @@ impl<'a> FromSql<'a> for InnerDecimal {
         ty: &Type,
         raw: &'a [u8],
     ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
-        Ok(InnerDecimal(<Decimal as FromSql>::from_sql(ty, raw)?))
+        Ok(InnerDecimal(<PgNumeric as FromSql>::from_sql(ty, raw)?))
     }
 
     fn accepts(_ty: &Type) -> bool {
@@ -223,6 +277,7 @@ pub enum PythonDTO {
     PyMoney(i64),
     PyDate(NaiveDate),
     PyTime(NaiveTime),
+    PyTimeTz(NaiveTime, FixedOffset),
     PyDateTime(NaiveDateTime),
     PyDateTimeTz(DateTime<FixedOffset>),
     PyInterval(Interval),
@@ -234,8 +289,9 @@ pub enum PythonDTO {
     PyJson(Value),
     PyMacAddr6(MacAddr6),
     PyMacAddr8(MacAddr8),
-    PyDecimal(Decimal),
+    PyDecimal(PgNumeric),
     PyCustomType(Vec<u8>),
+    PyTextFormat(String),
     PyPoint(Point),
     PyBox(Rect),
     PyPath(LineString),
@@ -272,6 +328,40 @@ pub enum PythonDTO {
     PyIntervalArray(Array<PythonDTO>),
     // PgVector
     PyPgVector(Vec<f32>),
+    // Range
+    PyRange(Box<PythonRange>),
+}
+
+/// Value of the range type, bound which is None is infinite.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PythonRange {
+    pub lower: Option<PythonDTO>,
+    pub upper: Option<PythonDTO>,
+    pub lower_inc: bool,
+    pub upper_inc: bool,
+    pub empty: bool,
+}
+
+impl PythonRange {
+    /// Create range from tuple of lower and upper bounds, lower bound is included.
+    ///
+    /// # Errors
+    /// May return Err Result if there are not two elements.
+    fn from_elements(elements: &[PythonDTO]) -> RustPSQLDriverPyResult<Self> {
+        let [lower, upper] = elements else {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "Range must be passed as tuple of lower and upper bounds, got {} elements",
+                elements.len()
+            )));
+        };
+        Ok(PythonRange {
+            lower: Some(lower.clone()),
+            upper: Some(upper.clone()),
+            lower_inc: true,
+            upper_inc: false,
+            empty: false,
+        })
+    }
 }
 
 impl ToPyObject for PythonDTO {
@@ -322,6 +412,7 @@ impl PythonDTO {
             PythonDTO::PyJson(_) => Ok(tokio_postgres::types::Type::JSON_ARRAY),
             PythonDTO::PyDate(_) => Ok(tokio_postgres::types::Type::DATE_ARRAY),
             PythonDTO::PyTime(_) => Ok(tokio_postgres::types::Type::TIME_ARRAY),
+            PythonDTO::PyTimeTz(..) => Ok(tokio_postgres::types::Type::TIMETZ_ARRAY),
             PythonDTO::PyDateTime(_) => Ok(tokio_postgres::types::Type::TIMESTAMP_ARRAY),
             PythonDTO::PyDateTimeTz(_) => Ok(tokio_postgres::types::Type::TIMESTAMPTZ_ARRAY),
             PythonDTO::PyMacAddr6(_) => Ok(tokio_postgres::types::Type::MACADDR_ARRAY),
@@ -351,6 +442,7 @@ impl PythonDTO {
             PythonDTO::PyString(pystring)
             | PythonDTO::PyText(pystring)
             | PythonDTO::PyVarChar(pystring) => Ok(json!(pystring)),
+            PythonDTO::PyIntI16(pyint) => Ok(json!(pyint)),
             PythonDTO::PyIntI32(pyint) => Ok(json!(pyint)),
             PythonDTO::PyIntI64(pyint) => Ok(json!(pyint)),
             PythonDTO::PyIntU64(pyint) => Ok(json!(pyint)),
@@ -367,6 +459,14 @@ impl PythonDTO {
             }
             PythonDTO::PyArray(array) => Ok(json!(pythondto_array_to_serde(Some(array.clone()))?)),
             PythonDTO::PyJsonb(py_dict) | PythonDTO::PyJson(py_dict) => Ok(py_dict.clone()),
+            PythonDTO::PyUUID(pyuuid) => Ok(json!(pyuuid.to_string())),
+            PythonDTO::PyDecimal(pydecimal) => Ok(json!(pydecimal.to_string())),
+            PythonDTO::PyDate(pydate) => Ok(json!(pydate.to_string())),
+            PythonDTO::PyTime(pytime) => Ok(json!(pytime.to_string())),
+            PythonDTO::PyDateTime(pydatetime) => {
+                Ok(json!(pydatetime.format("%Y-%m-%dT%H:%M:%S%.f").to_string()))
+            }
+            PythonDTO::PyDateTimeTz(pydatetime) => Ok(json!(pydatetime.to_rfc3339())),
             _ => Err(RustPSQLDriverError::PyToRustValueConversionError(
                 "Cannot convert your type into Rust type".into(),
             )),
@@ -417,6 +517,9 @@ impl ToSql for PythonDTO {
             PythonDTO::PyCustomType(some_bytes) => {
                 <&[u8] as ToSql>::to_sql(&some_bytes.as_slice(), ty, out)?;
             }
+            PythonDTO::PyTextFormat(text) => {
+                out.extend_from_slice(text.as_bytes());
+            }
             PythonDTO::PyBytes(pybytes) => {
                 <Vec<u8> as ToSql>::to_sql(pybytes, ty, out)?;
             }
@@ -430,22 +533,49 @@ impl ToSql for PythonDTO {
             PythonDTO::PyUUID(pyuuid) => {
                 <Uuid as ToSql>::to_sql(pyuuid, ty, out)?;
             }
+            PythonDTO::PyString(string) if *ty == Type::UUID => {
+                <Uuid as ToSql>::to_sql(&Uuid::parse_str(string)?, ty, out)?;
+            }
             PythonDTO::PyString(string) => {
                 <&str as ToSql>::to_sql(&string.as_str(), ty, out)?;
             }
-            PythonDTO::PyIntI16(int) => out.put_i16(*int),
-            PythonDTO::PyIntI32(int) => out.put_i32(*int),
-            PythonDTO::PyIntI64(int) | PythonDTO::PyMoney(int) => out.put_i64(*int),
+            PythonDTO::PyIntI16(int) => {
+                if !int_to_sql(i64::from(*int), ty, out)? {
+                    out.put_i16(*int);
+                }
+            }
+            PythonDTO::PyIntI32(int) => {
+                if !int_to_sql(i64::from(*int), ty, out)? {
+                    out.put_i32(*int);
+                }
+            }
+            PythonDTO::PyIntI64(int) => {
+                if !int_to_sql(*int, ty, out)? {
+                    out.put_i64(*int);
+                }
+            }
+            PythonDTO::PyMoney(int) => out.put_i64(*int),
             PythonDTO::PyIntU32(int) => out.put_u32(*int),
             PythonDTO::PyIntU64(int) => out.put_u64(*int),
             PythonDTO::PyFloat32(float) => out.put_f32(*float),
-            PythonDTO::PyFloat64(float) => out.put_f64(*float),
+            PythonDTO::PyFloat64(float) => match *ty {
+                #[allow(clippy::cast_possible_truncation)]
+                Type::FLOAT4 => out.put_f32(*float as f32),
+                Type::NUMERIC => {
+                    <PgNumeric as ToSql>::to_sql(&float_to_numeric(*float)?, ty, out)?;
+                }
+                _ => out.put_f64(*float),
+            },
             PythonDTO::PyDate(pydate) => {
                 <&NaiveDate as ToSql>::to_sql(&pydate, ty, out)?;
             }
             PythonDTO::PyTime(pytime) => {
                 <&NaiveTime as ToSql>::to_sql(&pytime, ty, out)?;
             }
+            PythonDTO::PyTimeTz(pytime, offset) => {
+                <&NaiveTime as ToSql>::to_sql(&pytime, ty, out)?;
+                out.put_i32(-offset.local_minus_utc());
+            }
             PythonDTO::PyDateTime(pydatetime_no_tz) => {
                 <&NaiveDateTime as ToSql>::to_sql(&pydatetime_no_tz, ty, out)?;
             }
@@ -491,31 +621,44 @@ impl ToSql for PythonDTO {
                 for inner in py_iterable {
                     items.push(inner);
                 }
-                if items.is_empty() {
+                if let Kind::Composite(fields) = ty.kind() {
+                    composite_values_to_sql(py_iterable.iter(), fields, out)?;
+                } else if let Kind::Range(_) = ty.kind() {
+                    range_to_sql(&PythonRange::from_elements(py_iterable)?, ty, out)?;
+                } else if let Kind::Multirange(_) = ty.kind() {
+                    multirange_to_sql(py_iterable, ty, out)?;
+                } else if ty.name() == "vector" {
+                    vector_to_sql(py_iterable.iter(), ty, out)?;
+                } else if items.is_empty() && !matches!(ty.kind(), Kind::Array(_)) {
                     return_is_null_true = true;
                 } else {
-                    items.to_sql(&items[0].array_type()?, out)?;
+                    items.to_sql(&parameter_array_type(ty, py_iterable.iter())?, out)?;
                 }
             }
             PythonDTO::PyArray(array) => {
-                if let Some(first_elem) = array.iter().nth(0) {
-                    match first_elem.array_type() {
-                        Ok(ok_type) => {
-                            array.to_sql(&ok_type, out)?;
-                        }
-                        Err(_) => {
-                            return Err(RustPSQLDriverError::PyToRustValueConversionError(
-                                "Cannot define array type.".into(),
-                            ))?
-                        }
-                    }
+                if let Kind::Composite(fields) = ty.kind() {
+                    composite_values_to_sql(array.iter(), fields, out)?;
+                } else if let Kind::Range(_) = ty.kind() {
+                    let elements: Vec<PythonDTO> = array.iter().cloned().collect();
+                    range_to_sql(&PythonRange::from_elements(&elements)?, ty, out)?;
+                } else if let Kind::Multirange(_) = ty.kind() {
+                    let ranges: Vec<PythonDTO> = array.iter().cloned().collect();
+                    multirange_to_sql(&ranges, ty, out)?;
+                } else if ty.name() == "vector" {
+                    vector_to_sql(array.iter(), ty, out)?;
+                } else {
+                    array.to_sql(&parameter_array_type(ty, array.iter())?, out)?;
                 }
             }
             PythonDTO::PyJsonb(py_dict) | PythonDTO::PyJson(py_dict) => {
-                <&Value as ToSql>::to_sql(&py_dict, ty, out)?;
+                if let Kind::Composite(fields) = ty.kind() {
+                    composite_to_sql(py_dict, fields, out)?;
+                } else {
+                    <&Value as ToSql>::to_sql(&py_dict, ty, out)?;
+                }
             }
             PythonDTO::PyDecimal(py_decimal) => {
-                <Decimal as ToSql>::to_sql(py_decimal, ty, out)?;
+                <PgNumeric as ToSql>::to_sql(py_decimal, ty, out)?;
             }
             PythonDTO::PyBoolArray(array) => {
                 array.to_sql(&Type::BOOL_ARRAY, out)?;
@@ -601,6 +744,7 @@ impl ToSql for PythonDTO {
             PythonDTO::PyPgVector(vector) => {
                 <PgVector as ToSql>::to_sql(&PgVector::from(vector.clone()), ty, out)?;
             }
+            PythonDTO::PyRange(range) => range_to_sql(range, ty, out)?,
         }
 
         if return_is_null_true {
@@ -610,9 +754,357 @@ impl ToSql for PythonDTO {
         }
     }
 
+    /// Send values wrapped in `AsText` in text format,
+    /// all other values are sent in binary format.
+    fn encode_format(&self, _ty: &Type) -> Format {
+        match self {
+            PythonDTO::PyTextFormat(_) => Format::Text,
+            _ => Format::Binary,
+        }
+    }
+
     to_sql_checked!();
 }
 
+/// Return array type for the parameter.
+///
+/// Type of the statement parameter is used if it's an array,
+/// so elements are encoded for it. Otherwise type is taken
+/// from the first not NULL element.
+///
+/// # Errors
+/// May return Err Result if all elements are NULL or the array is empty.
+fn parameter_array_type<'a>(
+    ty: &Type,
+    mut elements: impl Iterator<Item = &'a PythonDTO>,
+) -> RustPSQLDriverPyResult<Type> {
+    if let Kind::Array(_) = ty.kind() {
+        return Ok(ty.clone());
+    }
+    elements
+        .find(|element| **element != PythonDTO::PyNone)
+        .ok_or_else(|| {
+            RustPSQLDriverError::PyToRustValueConversionError("Cannot define array type.".into())
+        })?
+        .array_type()
+}
+
+/// Write integer with the size of the numeric type.
+///
+/// Returns false if type isn't numeric, so integer must be written as is.
+///
+/// # Errors
+/// May return Err Result if integer doesn't fit into the type.
+#[allow(clippy::cast_precision_loss)]
+fn int_to_sql(
+    int: i64,
+    ty: &Type,
+    out: &mut BytesMut,
+) -> Result<bool, Box<dyn std::error::Error + Sync + Send>> {
+    match *ty {
+        Type::INT2 => out.put_i16(i16::try_from(int)?),
+        Type::INT4 => out.put_i32(i32::try_from(int)?),
+        Type::INT8 => out.put_i64(int),
+        Type::FLOAT4 => out.put_f32(int as f32),
+        Type::FLOAT8 => out.put_f64(int as f64),
+        Type::NUMERIC => {
+            let digits: Vec<u8> = int
+                .unsigned_abs()
+                .to_string()
+                .bytes()
+                .map(|digit| digit - b'0')
+                .collect();
+            PgNumeric::from_parts(int < 0, &digits, 0)?.to_sql(ty, out)?;
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+/// Encode range, bounds are encoded for the element type of the range.
+///
+/// # Errors
+/// May return Err Result if `ty` isn't a range type or bounds cannot be encoded.
+fn range_to_sql(
+    range: &PythonRange,
+    ty: &Type,
+    out: &mut BytesMut,
+) -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+    let Kind::Range(element_type) = ty.kind() else {
+        return Err(format!("Cannot encode range as {ty}").into());
+    };
+    if range.empty {
+        types::empty_range_to_sql(out);
+        return Ok(());
+    }
+    types::range_to_sql(
+        |buf| range_bound_to_sql(range.lower.as_ref(), range.lower_inc, element_type, buf),
+        |buf| range_bound_to_sql(range.upper.as_ref(), range.upper_inc, element_type, buf),
+        out,
+    )
+}
+
+/// Encode multirange, every element is encoded as range.
+///
+/// # Errors
+/// May return Err Result if `ty` isn't a multirange type
+/// or any element cannot be encoded as range.
+fn multirange_to_sql(
+    ranges: &[PythonDTO],
+    ty: &Type,
+    out: &mut BytesMut,
+) -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+    let Kind::Multirange(range_type) = ty.kind() else {
+        return Err(format!("Cannot encode multirange as {ty}").into());
+    };
+    out.put_i32(i32::try_from(ranges.len())?);
+    for range in ranges {
+        if *range == PythonDTO::PyNone {
+            return Err(RustPSQLDriverError::PyToRustValueConversionError(
+                "Multirange cannot contain NULL".into(),
+            ))?;
+        }
+        let length_position = out.len();
+        out.put_i32(0);
+        range.to_sql(range_type, out)?;
+        let length = i32::try_from(out.len() - length_position - 4)?;
+        out[length_position..length_position + 4].copy_from_slice(&length.to_be_bytes());
+    }
+    Ok(())
+}
+
+/// Encode sequence of numbers as pgvector `vector`.
+///
+/// # Errors
+/// May return Err Result if any element isn't a number.
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+fn vector_to_sql<'a>(
+    elements: impl Iterator<Item = &'a PythonDTO>,
+    ty: &Type,
+    out: &mut BytesMut,
+) -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+    let vector = elements
+        .map(|element| match element {
+            PythonDTO::PyFloat32(float) => Ok(*float),
+            PythonDTO::PyFloat64(float) => Ok(*float as f32),
+            PythonDTO::PyIntI16(int) => Ok(f32::from(*int)),
+            PythonDTO::PyIntI32(int) => Ok(*int as f32),
+            PythonDTO::PyIntI64(int) => Ok(*int as f32),
+            PythonDTO::PyIntU32(int) => Ok(*int as f32),
+            PythonDTO::PyIntU64(int) => Ok(*int as f32),
+            _ => Err(RustPSQLDriverError::PyToRustValueConversionError(
+                "Vector can contain only numbers".into(),
+            )),
+        })
+        .collect::<Result<Vec<f32>, _>>()?;
+    <PgVector as ToSql>::to_sql(&PgVector::from(vector), ty, out)?;
+    Ok(())
+}
+
+/// Encode bound of the range, None and NULL bounds are infinite.
+///
+/// # Errors
+/// May return Err Result if bound cannot be encoded as the element type.
+fn range_bound_to_sql(
+    bound: Option<&PythonDTO>,
+    inclusive: bool,
+    element_type: &Type,
+    buf: &mut BytesMut,
+) -> Result<types::RangeBound<postgres_protocol::IsNull>, Box<dyn std::error::Error + Sync + Send>>
+{
+    let Some(bound) = bound.filter(|bound| **bound != PythonDTO::PyNone) else {
+        return Ok(types::RangeBound::Unbounded);
+    };
+    bound.to_sql(element_type, buf)?;
+    if inclusive {
+        Ok(types::RangeBound::Inclusive(postgres_protocol::IsNull::No))
+    } else {
+        Ok(types::RangeBound::Exclusive(postgres_protocol::IsNull::No))
+    }
+}
+
+/// Convert float to NUMERIC with the shortest decimal representation.
+///
+/// # Errors
+/// May return Err Result if value doesn't fit into `PostgreSQL` NUMERIC.
+fn float_to_numeric(float: f64) -> Result<PgNumeric, Box<dyn std::error::Error + Sync + Send>> {
+    if float.is_nan() {
+        return Ok(PgNumeric::NaN);
+    }
+    if float.is_infinite() {
+        return Ok(PgNumeric::Infinity {
+            negative: float.is_sign_negative(),
+        });
+    }
+    let text = float.abs().to_string();
+    let (integer, fraction) = text.split_once('.').unwrap_or((&text, ""));
+    let digits: Vec<u8> = integer
+        .bytes()
+        .chain(fraction.bytes())
+        .map(|digit| digit - b'0')
+        .collect();
+    PgNumeric::from_parts(
+        float.is_sign_negative(),
+        &digits,
+        -i64::try_from(fraction.len())?,
+    )
+}
+
+/// Write dict converted to JSON object as composite value.
+///
+/// Fields are taken by name, missing fields are NULL.
+///
+/// # Errors
+/// May return Err Result if value isn't an object
+/// or field cannot be written with its type.
+fn composite_to_sql(
+    value: &Value,
+    fields: &[Field],
+    out: &mut BytesMut,
+) -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+    let Value::Object(object) = value else {
+        return Err(RustPSQLDriverError::PyToRustValueConversionError(
+            "Composite value must be a dict".into(),
+        ))?;
+    };
+    out.put_i32(i32::try_from(fields.len())?);
+    for field in fields {
+        let field_value = serde_value_to_dto(
+            object.get(field.name()).unwrap_or(&Value::Null),
+            field.type_(),
+        );
+        composite_field_to_sql(field, &field_value, out)?;
+    }
+    Ok(())
+}
+
+/// Write values as composite value, fields are taken by position.
+///
+/// # Errors
+/// May return Err Result if number of values differs from number of fields
+/// or field cannot be written with its type.
+fn composite_values_to_sql<'a>(
+    values: impl ExactSizeIterator<Item = &'a PythonDTO>,
+    fields: &[Field],
+    out: &mut BytesMut,
+) -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+    if values.len() != fields.len() {
+        return Err(RustPSQLDriverError::PyToRustValueConversionError(format!(
+            "Composite value must have {} fields, got {}",
+            fields.len(),
+            values.len()
+        )))?;
+    }
+    out.put_i32(i32::try_from(fields.len())?);
+    for (field, field_value) in fields.iter().zip(values) {
+        composite_field_to_sql(field, field_value, out)?;
+    }
+    Ok(())
+}
+
+/// Write OID, length and value of the composite field.
+///
+/// # Errors
+/// May return Err Result if value cannot be written with the field type.
+fn composite_field_to_sql(
+    field: &Field,
+    field_value: &PythonDTO,
+    out: &mut BytesMut,
+) -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+    out.put_u32(field.type_().oid());
+    let length_position = out.len();
+    out.put_i32(0);
+    let length = match field_value.to_sql(field.type_(), out)? {
+        tokio_postgres::types::IsNull::Yes => -1,
+        tokio_postgres::types::IsNull::No => i32::try_from(out.len() - length_position - 4)?,
+    };
+    out[length_position..length_position + 4].copy_from_slice(&length.to_be_bytes());
+    Ok(())
+}
+
+/// Convert serde `Value` to `PythonDTO` for the field of the type.
+fn serde_value_to_dto(value: &Value, ty: &Type) -> PythonDTO {
+    match value {
+        _ if *ty == Type::JSON => PythonDTO::PyJson(value.clone()),
+        _ if *ty == Type::JSONB => PythonDTO::PyJsonb(value.clone()),
+        Value::Null => PythonDTO::PyNone,
+        Value::Bool(boolean) => PythonDTO::PyBool(*boolean),
+        Value::Number(number) => number.as_i64().map_or_else(
+            || PythonDTO::PyFloat64(number.as_f64().unwrap_or_default()),
+            PythonDTO::PyIntI64,
+        ),
+        Value::String(string) => string_to_dto(string, ty),
+        Value::Array(elements) => {
+            let element_type = match ty.kind() {
+                Kind::Array(element_type) => element_type.clone(),
+                _ => Type::JSONB,
+            };
+            PythonDTO::PyArray(Array::from_vec(
+                elements
+                    .iter()
+                    .map(|element| serde_value_to_dto(element, &element_type))
+                    .collect(),
+                1,
+            ))
+        }
+        Value::Object(_) => PythonDTO::PyJsonb(value.clone()),
+    }
+}
+
+/// Convert string from serde `Value` to `PythonDTO` for the field of the type.
+///
+/// Decimals, dates and times are kept as strings in JSON,
+/// they are parsed back for the fields of these types.
+/// String is returned as is if it cannot be parsed.
+fn string_to_dto(string: &str, ty: &Type) -> PythonDTO {
+    let parsed = match *ty {
+        Type::NUMERIC => numeric_from_str(string).map(PythonDTO::PyDecimal),
+        Type::DATE => string.parse::<NaiveDate>().ok().map(PythonDTO::PyDate),
+        Type::TIME => string.parse::<NaiveTime>().ok().map(PythonDTO::PyTime),
+        Type::TIMESTAMP => string
+            .parse::<NaiveDateTime>()
+            .ok()
+            .map(PythonDTO::PyDateTime),
+        Type::TIMESTAMPTZ => DateTime::parse_from_rfc3339(string)
+            .ok()
+            .map(PythonDTO::PyDateTimeTz),
+        _ => None,
+    };
+    parsed.unwrap_or_else(|| PythonDTO::PyString(string.to_string()))
+}
+
+/// Parse NUMERIC from the string like `-12.50`, `1E+3`, `NaN` or `Infinity`.
+fn numeric_from_str(string: &str) -> Option<PgNumeric> {
+    let (negative, unsigned) = match string.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, string.strip_prefix('+').unwrap_or(string)),
+    };
+    match unsigned {
+        "NaN" => return Some(PgNumeric::NaN),
+        "Infinity" => return Some(PgNumeric::Infinity { negative }),
+        _ => {}
+    }
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i64>().ok()?),
+        None => (unsigned, 0),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if integer.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    let digits = integer
+        .bytes()
+        .chain(fraction.bytes())
+        .map(|digit| digit.is_ascii_digit().then(|| digit - b'0'))
+        .collect::<Option<Vec<u8>>>()?;
+    PgNumeric::from_parts(
+        negative,
+        &digits,
+        exponent - i64::try_from(fraction.len()).ok()?,
+    )
+    .ok()
+}
+
 /// Convert parameters come from python.
 ///
 /// Parameters for `execute()` method can be either
@@ -643,6 +1135,56 @@ pub fn convert_parameters(parameters: Py<PyAny>) -> RustPSQLDriverPyResult<Vec<P
     Ok(result_vec)
 }
 
+/// Return type names of `PyCast` parameters by the parameter numbers.
+#[must_use]
+pub fn parameter_casts(parameters: &Py<PyAny>) -> HashMap<usize, String> {
+    Python::with_gil(|gil| {
+        let mut casts: HashMap<usize, String> = HashMap::new();
+        if let Ok(params) = parameters.extract::<Vec<Py<PyAny>>>(gil) {
+            for (param_idx, parameter) in params.iter().enumerate() {
+                if let Ok(cast) = parameter.extract::<extra_types::PyCast>(gil) {
+                    casts.insert(param_idx + 1, cast.type_name());
+                }
+            }
+        }
+        casts
+    })
+}
+
+/// Return casts of `PyCast` parameters of the rows,
+/// every row must have the same casts at the same positions.
+///
+/// # Errors
+/// May return Err Result if rows have different casts.
+pub fn rows_parameter_casts(rows: &[Py<PyAny>]) -> RustPSQLDriverPyResult<HashMap<usize, String>> {
+    let Some((first_row, other_rows)) = rows.split_first() else {
+        return Ok(HashMap::new());
+    };
+    let casts = parameter_casts(first_row);
+    if other_rows.iter().any(|row| parameter_casts(row) != casts) {
+        return Err(RustPSQLDriverError::PyToRustValueConversionError(
+            "All rows must have the same PyCast types at the same positions".into(),
+        ));
+    }
+    Ok(casts)
+}
+
+/// Convert parameters and add casts of `PyCast` parameters to the querystring.
+///
+/// # Errors
+/// May return Err Result if cannot convert parameters.
+pub fn convert_parameters_with_casts(
+    querystring: String,
+    parameters: Py<PyAny>,
+) -> RustPSQLDriverPyResult<(String, Vec<PythonDTO>)> {
+    let casts = parameter_casts(&parameters);
+    let params = convert_parameters(parameters)?;
+    if casts.is_empty() {
+        return Ok((querystring, params));
+    }
+    Ok((cast_parameters(&querystring, &casts), params))
+}
+
 /// Convert Sequence from Python (except String) into flat vec.
 ///
 /// # Errors
@@ -820,6 +1362,127 @@ fn extract_datetime_from_python_object_attrs(
     Ok(fixed_offset_datetime)
 }
 
+/// Return UTC offset of the aware `datetime.datetime` or `datetime.time`.
+///
+/// Offset is taken from `utcoffset()`, so any `tzinfo` implementation is supported.
+/// Returns None for the naive object.
+///
+/// # Errors
+/// May return Err Result if `utcoffset()` fails or offset is out of range.
+fn extract_utcoffset(
+    parameter: &pyo3::Bound<'_, PyAny>,
+) -> RustPSQLDriverPyResult<Option<FixedOffset>> {
+    let utcoffset = parameter.call_method0("utcoffset")?;
+    if utcoffset.is_none() {
+        return Ok(None);
+    }
+
+    let offset_seconds = utcoffset.extract::<chrono::Duration>()?.num_seconds();
+    let offset = i32::try_from(offset_seconds)
+        .ok()
+        .and_then(FixedOffset::east_opt)
+        .ok_or_else(|| {
+            RustPSQLDriverError::PyToRustValueConversionError(format!(
+                "UTC offset {offset_seconds} seconds is out of range"
+            ))
+        })?;
+    Ok(Some(offset))
+}
+
+/// Extract an aware datetime with the offset from `utcoffset()`.
+///
+/// It's the fallback for `tzinfo` implementations without `key`, e.g. `pytz` or `dateutil`.
+///
+/// # Errors
+/// May return Err Result if datetime is naive or cannot be converted.
+fn extract_datetime_with_utcoffset(
+    parameter: &pyo3::Bound<'_, PyAny>,
+) -> RustPSQLDriverPyResult<DateTime<FixedOffset>> {
+    let offset = extract_utcoffset(parameter)?.ok_or_else(|| {
+        RustPSQLDriverError::PyToRustValueConversionError("Datetime is naive".into())
+    })?;
+    let kwargs = PyDict::new_bound(parameter.py());
+    kwargs.set_item("tzinfo", parameter.py().None())?;
+    let naive_datetime = parameter
+        .call_method("replace", (), Some(&kwargs))?
+        .extract::<NaiveDateTime>()?;
+
+    offset
+        .from_local_datetime(&naive_datetime)
+        .single()
+        .ok_or_else(|| {
+            RustPSQLDriverError::PyToRustValueConversionError(
+                "Ambiguous or invalid datetime".into(),
+            )
+        })
+}
+
+/// Extract `decimal.Decimal` with all its digits.
+///
+/// Digits are taken from `as_tuple()`, so precision isn't limited
+/// and `NaN` and `Infinity` are supported too.
+///
+/// # Errors
+/// May return Err Result if value doesn't fit into `PostgreSQL` NUMERIC.
+pub fn extract_decimal(parameter: &pyo3::Bound<'_, PyAny>) -> RustPSQLDriverPyResult<PgNumeric> {
+    let decimal_tuple = parameter.call_method0("as_tuple")?;
+    let negative = decimal_tuple.getattr("sign")?.extract::<u8>()? == 1;
+    let exponent = decimal_tuple.getattr("exponent")?;
+
+    let Ok(exponent) = exponent.extract::<i64>() else {
+        // Exponent of the special values is 'F' for Infinity, 'n' or 'N' for NaN.
+        if exponent.extract::<String>()? == "F" {
+            return Ok(PgNumeric::Infinity { negative });
+        }
+        return Ok(PgNumeric::NaN);
+    };
+    let digits = decimal_tuple.getattr("digits")?.extract::<Vec<u8>>()?;
+    PgNumeric::from_parts(negative, &digits, exponent).map_err(|err| {
+        RustPSQLDriverError::PyToRustValueConversionError(format!(
+            "Cannot convert Decimal into NUMERIC: {err}"
+        ))
+    })
+}
+
+/// Convert python parameter to `PythonDTO` for the known `PostgreSQL` type.
+///
+/// Numbers are converted to the size of the column type and
+/// dicts and lists are converted to JSON for `JSON`/`JSONB`,
+/// other values are converted as usual with `py_to_rust`.
+///
+/// # Errors
+///
+/// May return Err Result if value doesn't fit into the type
+/// or cannot be converted.
+pub fn py_to_rust_for_type(
+    parameter: &pyo3::Bound<'_, PyAny>,
+    ty: &Type,
+) -> RustPSQLDriverPyResult<PythonDTO> {
+    let is_int = parameter.is_instance_of::<PyInt>() && !parameter.is_instance_of::<PyBool>();
+    let is_number = is_int || parameter.is_instance_of::<PyFloat>();
+    match *ty {
+        Type::INT2 if is_int => Ok(PythonDTO::PyIntI16(parameter.extract::<i16>()?)),
+        Type::INT4 if is_int => Ok(PythonDTO::PyIntI32(parameter.extract::<i32>()?)),
+        Type::INT8 if is_int => Ok(PythonDTO::PyIntI64(parameter.extract::<i64>()?)),
+        Type::FLOAT4 if is_number => Ok(PythonDTO::PyFloat32(parameter.extract::<f32>()?)),
+        Type::FLOAT8 if is_number => Ok(PythonDTO::PyFloat64(parameter.extract::<f64>()?)),
+        Type::NUMERIC if is_number => {
+            let decimal = get_decimal_cls(parameter.py())?.call1((parameter.str()?,))?;
+            Ok(PythonDTO::PyDecimal(extract_decimal(&decimal)?))
+        }
+        Type::JSON | Type::JSONB
+            if parameter.is_instance_of::<PyDict>() || parameter.is_instance_of::<PyList>() =>
+        {
+            let value = build_serde_value(parameter.clone().unbind())?;
+            if *ty == Type::JSON {
+                return Ok(PythonDTO::PyJson(value));
+            }
+            Ok(PythonDTO::PyJsonb(value))
+        }
+        _ => py_to_rust(parameter),
+    }
+}
+
 /// Convert single python parameter to `PythonDTO` enum.
 ///
 /// # Errors
@@ -832,12 +1495,34 @@ pub fn py_to_rust(parameter: &pyo3::Bound<'_, PyAny>) -> RustPSQLDriverPyResult<
         return Ok(PythonDTO::PyNone);
     }
 
+    // Registered classes are checked first, members of registered `IntEnum`
+    // are encoded as labels, not as integers.
+    if is_registered_instance(parameter) {
+        if let Some(label) = enum_member_label(parameter)? {
+            return Ok(PythonDTO::PyString(label));
+        }
+        return py_to_rust(&parameter.getattr("__dict__")?);
+    }
+
     if parameter.is_instance_of::<extra_types::CustomType>() {
         return Ok(PythonDTO::PyCustomType(
             parameter.extract::<extra_types::CustomType>()?.inner(),
         ));
     }
 
+    if parameter.is_instance_of::<extra_types::PyCast>() {
+        return Ok(parameter
+            .extract::<extra_types::PyCast>()?
+            .inner()
+            .map_or(PythonDTO::PyNone, PythonDTO::PyTextFormat));
+    }
+
+    if parameter.is_instance_of::<extra_types::AsText>() {
+        return Ok(PythonDTO::PyTextFormat(
+            parameter.extract::<extra_types::AsText>()?.inner(),
+        ));
+    }
+
     if parameter.is_instance_of::<PyBool>() {
         return Ok(PythonDTO::PyBool(parameter.extract::<bool>()?));
     }
@@ -930,6 +1615,11 @@ pub fn py_to_rust(parameter: &pyo3::Bound<'_, PyAny>) -> RustPSQLDriverPyResult<
             return Ok(PythonDTO::PyDateTimeTz(pydatetime_tz));
         }
 
+        let timestamp_tz = extract_datetime_with_utcoffset(parameter);
+        if let Ok(pydatetime_tz) = timestamp_tz {
+            return Ok(PythonDTO::PyDateTimeTz(pydatetime_tz));
+        }
+
         return Err(RustPSQLDriverError::PyToRustValueConversionError(
             "Can not convert you datetime to rust type".into(),
         ));
@@ -940,7 +1630,11 @@ pub fn py_to_rust(parameter: &pyo3::Bound<'_, PyAny>) -> RustPSQLDriverPyResult<
     }
 
     if parameter.is_instance_of::<PyTime>() {
-        return Ok(PythonDTO::PyTime(parameter.extract::<NaiveTime>()?));
+        let time = parameter.extract::<NaiveTime>()?;
+        if let Some(offset) = extract_utcoffset(parameter)? {
+            return Ok(PythonDTO::PyTimeTz(time, offset));
+        }
+        return Ok(PythonDTO::PyTime(time));
     }
 
     if parameter.is_instance_of::<PyDelta>() {
@@ -953,7 +1647,34 @@ pub fn py_to_rust(parameter: &pyo3::Bound<'_, PyAny>) -> RustPSQLDriverPyResult<
         ));
     }
 
-    if parameter.is_instance_of::<PyList>() | parameter.is_instance_of::<PyTuple>() {
+    // numpy arrays are passed as lists, `vector` is encoded from them too.
+    if is_numpy_array(parameter) {
+        return py_to_rust(&parameter.call_method0("tolist")?);
+    }
+
+    if parameter.is_instance_of::<PyTuple>() {
+        // Tuple with the values of different dimensions can't be an array,
+        // but it can be a composite value.
+        return match py_sequence_into_postgres_array(parameter) {
+            Ok(array) => Ok(PythonDTO::PyArray(array)),
+            Err(err) => {
+                let mut values = vec![];
+                for value in parameter.iter()? {
+                    values.push(py_to_rust(&value?)?);
+                }
+                if values
+                    .iter()
+                    .any(|value| matches!(value, PythonDTO::PyArray(_)))
+                {
+                    Ok(PythonDTO::PyTuple(values))
+                } else {
+                    Err(err)
+                }
+            }
+        };
+    }
+
+    if parameter.is_instance_of::<PyList>() {
         return Ok(PythonDTO::PyArray(py_sequence_into_postgres_array(
             parameter,
         )?));
@@ -1017,9 +1738,15 @@ pub fn py_to_rust(parameter: &pyo3::Bound<'_, PyAny>) -> RustPSQLDriverPyResult<
     if parameter.get_type().name()? == "decimal.Decimal"
         || parameter.get_type().name()? == "Decimal"
     {
-        return Ok(PythonDTO::PyDecimal(Decimal::from_str_exact(
-            parameter.str()?.extract::<&str>()?,
-        )?));
+        return Ok(PythonDTO::PyDecimal(extract_decimal(parameter)?));
+    }
+
+    if parameter.is_instance_of::<extra_types::PyDecimal>() {
+        return Ok(PythonDTO::PyDecimal(
+            parameter
+                .extract::<extra_types::PyDecimal>()?
+                .retrieve_value(),
+        ));
     }
 
     if parameter.is_instance_of::<extra_types::Point>() {
@@ -1228,6 +1955,14 @@ pub fn py_to_rust(parameter: &pyo3::Bound<'_, PyAny>) -> RustPSQLDriverPyResult<
         ));
     }
 
+    if let Ok(vector) = parameter.downcast::<extra_types::PyVector>() {
+        return Ok(PythonDTO::PyPgVector(vector.borrow().inner_value()));
+    }
+
+    if let Ok(range) = parameter.downcast::<extra_types::Range>() {
+        return range.borrow().to_python_dto(parameter.py());
+    }
+
     if let Ok(id_address) = parameter.extract::<IpAddr>() {
         return Ok(PythonDTO::PyIpAddress(id_address));
     }
@@ -1246,6 +1981,21 @@ pub fn py_to_rust(parameter: &pyo3::Bound<'_, PyAny>) -> RustPSQLDriverPyResult<
     )))
 }
 
+/// Check that value is `numpy.ndarray`.
+///
+/// Type is checked by name, so numpy isn't required.
+#[must_use]
+pub fn is_numpy_array(value: &Bound<'_, PyAny>) -> bool {
+    let value_type = value.get_type();
+    let attr_is = |attr: &str, expected: &str| {
+        value_type
+            .getattr(attr)
+            .and_then(|attr_value| attr_value.extract::<String>())
+            .is_ok_and(|attr_value| attr_value == expected)
+    };
+    attr_is("__module__", "numpy") && attr_is("__name__", "ndarray")
+}
+
 fn composite_field_postgres_to_py<'a, T: FromSql<'a>>(
     type_: &Type,
     buf: &mut &'a [u8],
@@ -1291,33 +2041,23 @@ fn inner_pythondto_array_to_serde(
     let current_dimension = dimensions.get(dimension_index);
 
     if let Some(current_dimension) = current_dimension {
-        let possible_next_dimension = dimensions.get(dimension_index + 1);
-        match possible_next_dimension {
-            Some(next_dimension) => {
-                let mut final_list: Value = Value::Array(vec![]);
-
-                for _ in 0..current_dimension.len as usize {
-                    if dimensions.get(dimension_index + 1).is_some() {
-                        let inner_pylist = inner_pythondto_array_to_serde(
-                            dimensions,
-                            &data[lower_bound..next_dimension.len as usize + lower_bound],
-                            dimension_index + 1,
-                            0,
-                        )?;
-                        match final_list {
-                            Value::Array(ref mut array) => array.push(inner_pylist),
-                            _ => unreachable!(),
-                        }
-                        lower_bound += next_dimension.len as usize;
-                    };
-                }
+        if dimensions.get(dimension_index + 1).is_none() {
+            return data.iter().map(|x| x.to_serde_value()).collect();
+        }
 
-                return Ok(final_list);
-            }
-            None => {
-                return data.iter().map(|x| x.to_serde_value()).collect();
-            }
+        let inner_len = inner_dimensions_len(dimensions, dimension_index);
+        let mut final_list = vec![];
+        for _ in 0..current_dimension.len as usize {
+            final_list.push(inner_pythondto_array_to_serde(
+                dimensions,
+                &data[lower_bound..inner_len + lower_bound],
+                dimension_index + 1,
+                0,
+            )?);
+            lower_bound += inner_len;
         }
+
+        return Ok(Value::Array(final_list));
     }
 
     Ok(Value::Array(vec![]))
@@ -1341,6 +2081,25 @@ fn postgres_array_to_py<T: ToPyObject>(
     })
 }
 
+/// Convert elements of TIMETZ ARRAY into aware `datetime.time`, keeping dimensions.
+///
+/// # Errors
+/// May return Err Result if any time cannot be converted.
+fn timetz_array_to_py(
+    py: Python<'_>,
+    array: &Array<Option<InnerTimeTz>>,
+) -> RustPSQLDriverPyResult<Array<Option<Py<PyAny>>>> {
+    let times = array
+        .iter()
+        .map(|time| time.as_ref().map(|time| time.to_py(py)).transpose())
+        .collect::<RustPSQLDriverPyResult<Vec<_>>>()?;
+    postgres_array::Array::from_parts_no_panic(times, array.dimensions().to_vec()).map_err(|err| {
+        RustPSQLDriverError::RustToPyValueConversionError(format!(
+            "Cannot convert TIMETZ ARRAY, error - {err}"
+        ))
+    })
+}
+
 /// Inner postgres array conversion to python list.
 #[allow(clippy::cast_sign_loss)]
 fn inner_postgres_array_to_py<T>(
@@ -1356,36 +2115,145 @@ where
     let current_dimension = dimensions.get(dimension_index);
 
     if let Some(current_dimension) = current_dimension {
-        let possible_next_dimension = dimensions.get(dimension_index + 1);
-        match possible_next_dimension {
-            Some(next_dimension) => {
-                let final_list = PyList::empty_bound(py);
-
-                for _ in 0..current_dimension.len as usize {
-                    if dimensions.get(dimension_index + 1).is_some() {
-                        let inner_pylist = inner_postgres_array_to_py(
-                            py,
-                            dimensions,
-                            &data[lower_bound..next_dimension.len as usize + lower_bound],
-                            dimension_index + 1,
-                            0,
-                        );
-                        final_list.append(inner_pylist).unwrap();
-                        lower_bound += next_dimension.len as usize;
-                    };
-                }
+        if dimensions.get(dimension_index + 1).is_none() {
+            return PyList::new_bound(py, data).unbind();
+        }
 
-                return final_list.unbind();
-            }
-            None => {
-                return PyList::new_bound(py, data).unbind();
-            }
+        // Every element of the dimension holds all elements of the inner dimensions.
+        let inner_len = inner_dimensions_len(dimensions, dimension_index);
+        let final_list = PyList::empty_bound(py);
+        for _ in 0..current_dimension.len as usize {
+            let inner_pylist = inner_postgres_array_to_py(
+                py,
+                dimensions,
+                &data[lower_bound..inner_len + lower_bound],
+                dimension_index + 1,
+                0,
+            );
+            final_list.append(inner_pylist).unwrap();
+            lower_bound += inner_len;
         }
+
+        return final_list.unbind();
     }
 
     PyList::empty_bound(py).unbind()
 }
 
+/// Return number of elements in one element of the dimension.
+#[allow(clippy::cast_sign_loss)]
+fn inner_dimensions_len(dimensions: &[Dimension], dimension_index: usize) -> usize {
+    dimensions[dimension_index + 1..]
+        .iter()
+        .map(|dimension| dimension.len as usize)
+        .product()
+}
+
+/// Read big-endian i32 from `PostgreSQL` bytes.
+fn read_postgres_i32(buf: &mut &[u8]) -> RustPSQLDriverPyResult<i32> {
+    postgres_types::private::read_be_i32(buf).map_err(|err| {
+        RustPSQLDriverError::RustToPyValueConversionError(format!(
+            "Cannot read bytes data from PostgreSQL: {err}"
+        ))
+    })
+}
+
+/// Convert array with not simple elements from `PostgreSQL` to python list.
+///
+/// It's used for arrays of composite, enum and domain types,
+/// every element is decoded by its own type.
+///
+/// # Errors
+/// May return Err Result if there is any problem with bytes.
+#[allow(clippy::cast_sign_loss)]
+fn element_array_postgres_to_py(
+    py: Python<'_>,
+    element_type: &Type,
+    buf: &mut &[u8],
+    custom_decoders: &Option<Py<PyDict>>,
+) -> RustPSQLDriverPyResult<Py<PyAny>> {
+    let dimensions_number = read_postgres_i32(buf)?;
+    // Flag of NULL elements and OID of the elements aren't needed.
+    read_postgres_i32(buf)?;
+    read_postgres_i32(buf)?;
+
+    let mut dimensions = vec![];
+    for _ in 0..dimensions_number {
+        dimensions.push(Dimension {
+            len: read_postgres_i32(buf)?,
+            lower_bound: read_postgres_i32(buf)?,
+        });
+    }
+
+    let elements_number = if dimensions.is_empty() {
+        0
+    } else {
+        dimensions
+            .iter()
+            .map(|dimension| dimension.len as usize)
+            .product()
+    };
+    let mut elements = Vec::with_capacity(elements_number);
+    for _ in 0..elements_number {
+        let element_len = read_postgres_i32(buf)?;
+        if element_len < 0 {
+            elements.push(py.None());
+            continue;
+        }
+        let element_len = element_len as usize;
+        if buf.len() < element_len {
+            return Err(RustPSQLDriverError::RustToPyValueConversionError(
+                "Cannot read bytes data from PostgreSQL: invalid array element length".into(),
+            ));
+        }
+        let (mut element_buf, tail) = buf.split_at(element_len);
+        *buf = tail;
+        elements.push(element_postgres_to_py(
+            py,
+            element_type,
+            &mut element_buf,
+            custom_decoders,
+        )?);
+    }
+
+    Ok(inner_postgres_array_to_py(py, &dimensions, &elements, 0, 0).into_any())
+}
+
+/// Convert single element of the array from `PostgreSQL` to python type.
+///
+/// # Errors
+/// May return Err Result if there is any problem with bytes.
+fn element_postgres_to_py(
+    py: Python<'_>,
+    element_type: &Type,
+    buf: &mut &[u8],
+    custom_decoders: &Option<Py<PyDict>>,
+) -> RustPSQLDriverPyResult<Py<PyAny>> {
+    match element_type.kind() {
+        Kind::Composite(_) => composite_type_postgres_to_py(py, element_type, buf, custom_decoders),
+        Kind::Enum(_) => enum_type_postgres_to_py(py, element_type, buf, true),
+        Kind::Domain(inner_type) => element_postgres_to_py(py, inner_type, buf, custom_decoders),
+        Kind::Range(inner_type) => range_postgres_to_py(py, inner_type, buf, custom_decoders),
+        Kind::Multirange(inner_type) => {
+            multirange_postgres_to_py(py, inner_type, buf, custom_decoders)
+        }
+        Kind::Array(inner_type) if !is_simple_type(element_type) => {
+            element_array_postgres_to_py(py, inner_type, buf, custom_decoders)
+        }
+        _ => postgres_bytes_to_py(py, element_type, buf, true),
+    }
+}
+
+/// Check that type or elements of the array type can be converted
+/// without information about inner types.
+fn is_simple_type(type_: &Type) -> bool {
+    match type_.kind() {
+        Kind::Simple => true,
+        Kind::Array(element_type) => matches!(element_type.kind(), Kind::Simple),
+        _ => false,
+    }
+}
+
 #[allow(clippy::too_many_lines)]
 fn postgres_bytes_to_py(
     py: Python<'_>,
@@ -1447,6 +2315,13 @@ fn postgres_bytes_to_py(
             type_, buf, is_simple,
         )?
         .to_object(py)),
+        // Convert TIMETZ into NaiveTime with offset, then into aware datetime.time
+        Type::TIMETZ => Ok(composite_field_postgres_to_py::<Option<InnerTimeTz>>(
+            type_, buf, is_simple,
+        )?
+        .map(|time| time.to_py(py))
+        .transpose()?
+        .to_object(py)),
         // Convert TIMESTAMP into NaiveDateTime, then into datetime.datetime
         Type::TIMESTAMP => Ok(composite_field_postgres_to_py::<Option<NaiveDateTime>>(
             type_, buf, is_simple,
@@ -1502,9 +2377,9 @@ fn postgres_bytes_to_py(
         }
         Type::NUMERIC => {
             if let Some(numeric_) =
-                composite_field_postgres_to_py::<Option<Decimal>>(type_, buf, is_simple)?
+                composite_field_postgres_to_py::<Option<InnerDecimal>>(type_, buf, is_simple)?
             {
-                return Ok(InnerDecimal(numeric_).to_object(py));
+                return Ok(numeric_.to_object(py));
             }
             Ok(py.None().to_object(py))
         }
@@ -1571,75 +2446,89 @@ fn postgres_bytes_to_py(
         // ---------- Array Text Types ----------
         Type::BOOL_ARRAY => Ok(postgres_array_to_py(
             py,
-            composite_field_postgres_to_py::<Option<Array<bool>>>(type_, buf, is_simple)?,
+            composite_field_postgres_to_py::<Option<Array<Option<bool>>>>(type_, buf, is_simple)?,
         )
         .to_object(py)),
         // Convert ARRAY of TEXT or VARCHAR into Vec<String>, then into list[str]
         Type::TEXT_ARRAY | Type::VARCHAR_ARRAY | Type::XML_ARRAY => Ok(postgres_array_to_py(
             py,
-            composite_field_postgres_to_py::<Option<Array<String>>>(type_, buf, is_simple)?,
+            composite_field_postgres_to_py::<Option<Array<Option<String>>>>(type_, buf, is_simple)?,
         )
         .to_object(py)),
         // ---------- Array Integer Types ----------
         // Convert ARRAY of SmallInt into Vec<i16>, then into list[int]
         Type::INT2_ARRAY => Ok(postgres_array_to_py(
             py,
-            composite_field_postgres_to_py::<Option<Array<i16>>>(type_, buf, is_simple)?,
+            composite_field_postgres_to_py::<Option<Array<Option<i16>>>>(type_, buf, is_simple)?,
         )
         .to_object(py)),
         // Convert ARRAY of Integer into Vec<i32>, then into list[int]
         Type::INT4_ARRAY => Ok(postgres_array_to_py(
             py,
-            composite_field_postgres_to_py::<Option<Array<i32>>>(type_, buf, is_simple)?,
+            composite_field_postgres_to_py::<Option<Array<Option<i32>>>>(type_, buf, is_simple)?,
         )
         .to_object(py)),
         // Convert ARRAY of BigInt into Vec<i64>, then into list[int]
         Type::INT8_ARRAY | Type::MONEY_ARRAY => Ok(postgres_array_to_py(
             py,
-            composite_field_postgres_to_py::<Option<Array<i64>>>(type_, buf, is_simple)?,
+            composite_field_postgres_to_py::<Option<Array<Option<i64>>>>(type_, buf, is_simple)?,
         )
         .to_object(py)),
         // Convert ARRAY of Float4 into Vec<f32>, then into list[float]
         Type::FLOAT4_ARRAY => Ok(postgres_array_to_py(
             py,
-            composite_field_postgres_to_py::<Option<Array<f32>>>(type_, buf, is_simple)?,
+            composite_field_postgres_to_py::<Option<Array<Option<f32>>>>(type_, buf, is_simple)?,
         )
         .to_object(py)),
         // Convert ARRAY of Float8 into Vec<f64>, then into list[float]
         Type::FLOAT8_ARRAY => Ok(postgres_array_to_py(
             py,
-            composite_field_postgres_to_py::<Option<Array<f64>>>(type_, buf, is_simple)?,
+            composite_field_postgres_to_py::<Option<Array<Option<f64>>>>(type_, buf, is_simple)?,
         )
         .to_object(py)),
         // Convert ARRAY of Date into Vec<NaiveDate>, then into list[datetime.date]
         Type::DATE_ARRAY => Ok(postgres_array_to_py(
             py,
-            composite_field_postgres_to_py::<Option<Array<NaiveDate>>>(type_, buf, is_simple)?,
+            composite_field_postgres_to_py::<Option<Array<Option<NaiveDate>>>>(
+                type_, buf, is_simple,
+            )?,
         )
         .to_object(py)),
         // Convert ARRAY of Time into Vec<NaiveTime>, then into list[datetime.date]
         Type::TIME_ARRAY => Ok(postgres_array_to_py(
             py,
-            composite_field_postgres_to_py::<Option<Array<NaiveTime>>>(type_, buf, is_simple)?,
+            composite_field_postgres_to_py::<Option<Array<Option<NaiveTime>>>>(
+                type_, buf, is_simple,
+            )?,
         )
         .to_object(py)),
+        Type::TIMETZ_ARRAY => {
+            let array = composite_field_postgres_to_py::<Option<Array<Option<InnerTimeTz>>>>(
+                type_, buf, is_simple,
+            )?
+            .map(|array| timetz_array_to_py(py, &array))
+            .transpose()?;
+            Ok(postgres_array_to_py(py, array).to_object(py))
+        }
         // Convert ARRAY of TIMESTAMP into Vec<NaiveDateTime>, then into list[datetime.date]
         Type::TIMESTAMP_ARRAY => Ok(postgres_array_to_py(
             py,
-            composite_field_postgres_to_py::<Option<Array<NaiveDateTime>>>(type_, buf, is_simple)?,
+            composite_field_postgres_to_py::<Option<Array<Option<NaiveDateTime>>>>(
+                type_, buf, is_simple,
+            )?,
         )
         .to_object(py)),
         // Convert ARRAY of TIMESTAMPTZ into Vec<DateTime<FixedOffset>>, then into list[datetime.date]
         Type::TIMESTAMPTZ_ARRAY => Ok(postgres_array_to_py(
             py,
-            composite_field_postgres_to_py::<Option<Array<DateTime<FixedOffset>>>>(
+            composite_field_postgres_to_py::<Option<Array<Option<DateTime<FixedOffset>>>>>(
                 type_, buf, is_simple,
             )?,
         )
         .to_object(py)),
         // Convert ARRAY of UUID into Vec<Array<InternalUuid>>, then into list[UUID]
         Type::UUID_ARRAY => {
-            let uuid_array = composite_field_postgres_to_py::<Option<Array<InternalUuid>>>(
+            let uuid_array = composite_field_postgres_to_py::<Option<Array<Option<InternalUuid>>>>(
                 type_, buf, is_simple,
             )?;
             Ok(postgres_array_to_py(py, uuid_array).to_object(py))
@@ -1647,63 +2536,69 @@ fn postgres_bytes_to_py(
         // Convert ARRAY of INET into Vec<INET>, then into list[IPv4Address | IPv6Address]
         Type::INET_ARRAY => Ok(postgres_array_to_py(
             py,
-            composite_field_postgres_to_py::<Option<Array<IpAddr>>>(type_, buf, is_simple)?,
+            composite_field_postgres_to_py::<Option<Array<Option<IpAddr>>>>(type_, buf, is_simple)?,
         )
         .to_object(py)),
         Type::JSONB_ARRAY | Type::JSON_ARRAY => {
-            let db_json_array = composite_field_postgres_to_py::<Option<Array<InternalSerdeValue>>>(
-                type_, buf, is_simple,
-            )?;
+            let db_json_array = composite_field_postgres_to_py::<
+                Option<Array<Option<InternalSerdeValue>>>,
+            >(type_, buf, is_simple)?;
             Ok(postgres_array_to_py(py, db_json_array).to_object(py))
         }
         Type::NUMERIC_ARRAY => Ok(postgres_array_to_py(
             py,
-            composite_field_postgres_to_py::<Option<Array<InnerDecimal>>>(type_, buf, is_simple)?,
+            composite_field_postgres_to_py::<Option<Array<Option<InnerDecimal>>>>(
+                type_, buf, is_simple,
+            )?,
         )
         .to_object(py)),
         // ---------- Array Geo Types ----------
         Type::POINT_ARRAY => {
-            let point_array_ =
-                composite_field_postgres_to_py::<Option<Array<RustPoint>>>(type_, buf, is_simple)?;
+            let point_array_ = composite_field_postgres_to_py::<Option<Array<Option<RustPoint>>>>(
+                type_, buf, is_simple,
+            )?;
 
             Ok(postgres_array_to_py(py, point_array_).to_object(py))
         }
         Type::BOX_ARRAY => {
-            let box_array_ =
-                composite_field_postgres_to_py::<Option<Array<RustRect>>>(type_, buf, is_simple)?;
+            let box_array_ = composite_field_postgres_to_py::<Option<Array<Option<RustRect>>>>(
+                type_, buf, is_simple,
+            )?;
 
             Ok(postgres_array_to_py(py, box_array_).to_object(py))
         }
         Type::PATH_ARRAY => {
-            let path_array_ = composite_field_postgres_to_py::<Option<Array<RustLineString>>>(
-                type_, buf, is_simple,
-            )?;
+            let path_array_ = composite_field_postgres_to_py::<
+                Option<Array<Option<RustLineString>>>,
+            >(type_, buf, is_simple)?;
 
             Ok(postgres_array_to_py(py, path_array_).to_object(py))
         }
         Type::LINE_ARRAY => {
-            let line_array_ =
-                composite_field_postgres_to_py::<Option<Array<Line>>>(type_, buf, is_simple)?;
+            let line_array_ = composite_field_postgres_to_py::<Option<Array<Option<Line>>>>(
+                type_, buf, is_simple,
+            )?;
 
             Ok(postgres_array_to_py(py, line_array_).to_object(py))
         }
         Type::LSEG_ARRAY => {
-            let lseg_array_ = composite_field_postgres_to_py::<Option<Array<RustLineSegment>>>(
-                type_, buf, is_simple,
-            )?;
+            let lseg_array_ = composite_field_postgres_to_py::<
+                Option<Array<Option<RustLineSegment>>>,
+            >(type_, buf, is_simple)?;
 
             Ok(postgres_array_to_py(py, lseg_array_).to_object(py))
         }
         Type::CIRCLE_ARRAY => {
-            let circle_array_ =
-                composite_field_postgres_to_py::<Option<Array<Circle>>>(type_, buf, is_simple)?;
+            let circle_array_ = composite_field_postgres_to_py::<Option<Array<Option<Circle>>>>(
+                type_, buf, is_simple,
+            )?;
 
             Ok(postgres_array_to_py(py, circle_array_).to_object(py))
         }
         Type::INTERVAL_ARRAY => {
-            let interval_array_ = composite_field_postgres_to_py::<Option<Array<InnerInterval>>>(
-                type_, buf, is_simple,
-            )?;
+            let interval_array_ = composite_field_postgres_to_py::<
+                Option<Array<Option<InnerInterval>>>,
+            >(type_, buf, is_simple)?;
 
             Ok(postgres_array_to_py(py, interval_array_).to_object(py))
         }
@@ -1776,7 +2671,7 @@ pub fn composite_postgres_to_py(
         }
 
         match field.type_().kind() {
-            Kind::Simple | Kind::Array(_) => {
+            Kind::Simple | Kind::Array(_) if is_simple_type(field.type_()) => {
                 result_py_dict.set_item(
                     field.name(),
                     postgres_bytes_to_py(py, field.type_(), buf, false)?.to_object(py),
@@ -1785,16 +2680,20 @@ pub fn composite_postgres_to_py(
             Kind::Enum(_) => {
                 result_py_dict.set_item(
                     field.name(),
-                    postgres_bytes_to_py(py, &Type::VARCHAR, buf, false)?.to_object(py),
+                    enum_type_postgres_to_py(py, field.type_(), buf, false)?,
                 )?;
             }
             _ => {
-                let (_, tail) = buf.split_at(4_usize);
+                let field_len = read_postgres_i32(buf)?;
+                if field_len < 0 {
+                    result_py_dict.set_item(field.name(), py.None())?;
+                    continue;
+                }
+                let (mut field_buf, tail) = buf.split_at((field_len as usize).min(buf.len()));
                 *buf = tail;
                 result_py_dict.set_item(
                     field.name(),
-                    raw_bytes_data_process(py, buf, field.name(), field.type_(), custom_decoders)?
-                        .to_object(py),
+                    element_postgres_to_py(py, field.type_(), &mut field_buf, custom_decoders)?,
                 )?;
             }
         }
@@ -1803,6 +2702,144 @@ pub fn composite_postgres_to_py(
     Ok(result_py_dict.to_object(py))
 }
 
+/// Convert composite type from `PostgreSQL` to the registered Python class
+/// or to `dict` if there is no registered class.
+///
+/// # Errors
+/// May return error if there is any problem with bytes
+/// or the class cannot be created from the fields.
+pub fn composite_type_postgres_to_py(
+    py: Python<'_>,
+    type_: &Type,
+    buf: &mut &[u8],
+    custom_decoders: &Option<Py<PyDict>>,
+) -> RustPSQLDriverPyResult<Py<PyAny>> {
+    let Kind::Composite(fields) = type_.kind() else {
+        return Err(RustPSQLDriverError::RustToPyValueConversionError(format!(
+            "{type_} isn't a composite type"
+        )));
+    };
+    let fields_dict = composite_postgres_to_py(py, fields, buf, custom_decoders)?;
+    match registered_class(py, type_) {
+        Some(cls) => {
+            let fields_dict = fields_dict.bind(py).downcast::<PyDict>().map_err(|err| {
+                RustPSQLDriverError::RustToPyValueConversionError(format!(
+                    "Cannot convert composite value into {cls}: {err}"
+                ))
+            })?;
+            Ok(cls.call((), Some(fields_dict))?.unbind())
+        }
+        None => Ok(fields_dict),
+    }
+}
+
+/// Convert range type from `PostgreSQL` to `Range`.
+///
+/// # Errors
+/// May return error if there is any problem with bytes.
+fn range_postgres_to_py(
+    py: Python<'_>,
+    element_type: &Type,
+    buf: &mut &[u8],
+    custom_decoders: &Option<Py<PyDict>>,
+) -> RustPSQLDriverPyResult<Py<PyAny>> {
+    let range = types::range_from_sql(*buf).map_err(|err| {
+        RustPSQLDriverError::RustToPyValueConversionError(format!(
+            "Cannot read bytes data from PostgreSQL: {err}"
+        ))
+    })?;
+    let range = match range {
+        types::Range::Empty => extra_types::Range::empty_range(py),
+        types::Range::Nonempty(lower, upper) => {
+            let (lower, lower_inc) =
+                range_bound_postgres_to_py(py, lower, element_type, custom_decoders)?;
+            let (upper, upper_inc) =
+                range_bound_postgres_to_py(py, upper, element_type, custom_decoders)?;
+            extra_types::Range::from_bounds(py, lower, upper, lower_inc, upper_inc)
+        }
+    };
+    Ok(Py::new(py, range)?.into_any())
+}
+
+/// Convert multirange type from `PostgreSQL` to list of `Range`.
+///
+/// # Errors
+/// May return error if there is any problem with bytes.
+fn multirange_postgres_to_py(
+    py: Python<'_>,
+    range_type: &Type,
+    buf: &mut &[u8],
+    custom_decoders: &Option<Py<PyDict>>,
+) -> RustPSQLDriverPyResult<Py<PyAny>> {
+    let ranges_count = read_postgres_i32(buf)?;
+    let result_list = PyList::empty(py);
+    for _ in 0..ranges_count {
+        let range_len = usize::try_from(read_postgres_i32(buf)?).map_err(|_| {
+            RustPSQLDriverError::RustToPyValueConversionError(
+                "Multirange cannot contain NULL".into(),
+            )
+        })?;
+        if range_len > buf.len() {
+            return Err(RustPSQLDriverError::RustToPyValueConversionError(
+                "Cannot read bytes data from PostgreSQL: invalid range length".into(),
+            ));
+        }
+        let (mut range_buf, tail) = buf.split_at(range_len);
+        *buf = tail;
+        result_list.append(element_postgres_to_py(
+            py,
+            range_type,
+            &mut range_buf,
+            custom_decoders,
+        )?)?;
+    }
+    Ok(result_list.unbind().into_any())
+}
+
+/// Convert bound of the range, infinite bound is None.
+///
+/// # Errors
+/// May return error if there is any problem with bytes.
+fn range_bound_postgres_to_py(
+    py: Python<'_>,
+    bound: types::RangeBound<Option<&[u8]>>,
+    element_type: &Type,
+    custom_decoders: &Option<Py<PyDict>>,
+) -> RustPSQLDriverPyResult<(Option<Py<PyAny>>, bool)> {
+    let (value, inclusive) = match bound {
+        types::RangeBound::Inclusive(value) => (value, true),
+        types::RangeBound::Exclusive(value) => (value, false),
+        types::RangeBound::Unbounded => (None, false),
+    };
+    let Some(mut value) = value else {
+        return Ok((None, false));
+    };
+    let value = element_postgres_to_py(py, element_type, &mut value, custom_decoders)?;
+    Ok((Some(value), inclusive))
+}
+
+/// Convert enum type from `PostgreSQL` to the member of the registered
+/// Python `Enum` or to `str` if there is no registered class.
+///
+/// # Errors
+/// May return error if there is any problem with bytes
+/// or the `Enum` has no member for the label.
+fn enum_type_postgres_to_py(
+    py: Python<'_>,
+    type_: &Type,
+    buf: &mut &[u8],
+    is_simple: bool,
+) -> RustPSQLDriverPyResult<Py<PyAny>> {
+    let label = postgres_bytes_to_py(py, &Type::VARCHAR, buf, is_simple)?;
+    let Some(cls) = registered_class(py, type_) else {
+        return Ok(label);
+    };
+    match label.extract::<Option<String>>(py)? {
+        Some(label) => Ok(enum_label_to_member(&cls, &label)?.unbind()),
+        None => Ok(py.None()),
+    }
+}
+
 /// Process raw bytes from `PostgreSQL`.
 ///
 /// # Errors
@@ -1829,13 +2866,22 @@ pub fn raw_bytes_data_process(
     }
 
     match column_type.kind() {
-        Kind::Simple | Kind::Array(_) => {
+        Kind::Simple | Kind::Array(_) if is_simple_type(column_type) => {
             postgres_bytes_to_py(py, column_type, raw_bytes_data, true)
         }
-        Kind::Composite(fields) => {
-            composite_postgres_to_py(py, fields, raw_bytes_data, custom_decoders)
+        Kind::Array(element_type) => {
+            element_array_postgres_to_py(py, element_type, raw_bytes_data, custom_decoders)
+        }
+        Kind::Composite(_) => {
+            composite_type_postgres_to_py(py, column_type, raw_bytes_data, custom_decoders)
+        }
+        Kind::Enum(_) => enum_type_postgres_to_py(py, column_type, raw_bytes_data, true),
+        Kind::Range(element_type) => {
+            range_postgres_to_py(py, element_type, raw_bytes_data, custom_decoders)
+        }
+        Kind::Multirange(range_type) => {
+            multirange_postgres_to_py(py, range_type, raw_bytes_data, custom_decoders)
         }
-        Kind::Enum(_) => postgres_bytes_to_py(py, &Type::VARCHAR, raw_bytes_data, true),
         _ => Err(RustPSQLDriverError::RustToPyValueConversionError(
             column_type.to_string(),
         )),